@@ -108,6 +108,8 @@ impl YarnCommands {
     /// Constructs an instance of [`YarnCommands`] with the builtin commands `wait` and `stop`.
     /// - `stop`: Stops the execution of the dialogue.
     /// - `wait`: Waits for the given amount of seconds before continuing the dialogue. Note that this does not block and that Bevy will continue updating as normal in the meantime.
+    ///   The runtime handles `<<wait>>` itself and reports it as [`DialogueEvent::Wait`](yarnspinner::runtime::DialogueEvent::Wait), which is then executed as this command.
+    ///   Replace it to customize waiting, e.g. to skip waits while fast-forwarding.
     pub fn builtin_commands() -> Self {
         let mut commands = Self::default();
 
@@ -3,7 +3,6 @@
 
 use crate::prelude::YarnSpinnerSystemSet;
 use bevy::prelude::*;
-use bevy::utils::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
@@ -14,7 +13,7 @@ pub(crate) fn wait_command_plugin(app: &mut App) {
 }
 
 #[derive(Debug, Clone, Resource, Default)]
-pub(crate) struct Wait(HashMap<Duration, WaitPeriod>);
+pub(crate) struct Wait(Vec<WaitPeriod>);
 
 #[derive(Debug, Clone)]
 pub(crate) struct WaitPeriod {
@@ -25,19 +24,16 @@ pub(crate) struct WaitPeriod {
 impl Wait {
     pub(crate) fn add(&mut self, duration: Duration) -> Arc<AtomicBool> {
         let done = Arc::new(AtomicBool::new(false));
-        self.0.insert(
+        self.0.push(WaitPeriod {
             duration,
-            WaitPeriod {
-                duration,
-                done: done.clone(),
-            },
-        );
+            done: done.clone(),
+        });
         done
     }
 }
 
 pub(crate) fn update_wait(time: Res<Time>, mut wait: ResMut<Wait>) {
-    for period in wait.0.values_mut() {
+    for period in wait.0.iter_mut() {
         if period.duration <= time.delta() {
             period.duration = Duration::from_secs(0);
             period.done.store(true, Ordering::Relaxed);
@@ -45,5 +41,5 @@ pub(crate) fn update_wait(time: Res<Time>, mut wait: ResMut<Wait>) {
             period.duration -= time.delta();
         }
     }
-    wait.0.remove(&Duration::from_secs(0));
+    wait.0.retain(|period| !period.duration.is_zero());
 }
@@ -43,6 +43,7 @@ pub(crate) fn command_wrapping_plugin(_app: &mut App) {}
 ///
 /// The return value must be of a type implementing [`TaskFinishedIndicator`], which is generally either `()` or some kind of wrapped boolean
 /// or [`Task`]. If you return something else than `()`, the command will be considered finished when the respective [`TaskFinishedIndicator`] says so.
+/// Return a [`CommandCompletion`] to finish the command by calling [`CommandCompletion::complete`] on a clone of it, e.g. from an async task.
 /// Until then, the dialogue will not be advanced when [`DialogueRunner::continue_in_next_update`] is called. This allows you to e.g. move the camera before the dialogue continues.
 /// If you return `()`, the command will be considered finished immediately.
 pub trait YarnCommand<Marker>: Send + Sync + 'static + Clone {
@@ -142,6 +143,27 @@ impl TaskFinishedIndicator for Task<()> {
     }
 }
 
+/// Finished once the command is completed. A command whose [`CommandCompletion`]s were all dropped without completing it
+/// is considered finished as well, so that the dialogue doesn't wait for it forever.
+impl TaskFinishedIndicator for CommandCompletion {
+    fn is_finished(&self) -> bool {
+        if self.is_abandoned() {
+            error!("A command was never completed because all of its `CommandCompletion`s were dropped. Continuing the dialogue anyway.");
+            return true;
+        }
+        self.is_completed()
+    }
+}
+
+impl TaskFinishedIndicator for CommandResult {
+    fn is_finished(&self) -> bool {
+        match self {
+            CommandResult::Completed => true,
+            CommandResult::InProgress(completion) => completion.is_finished(),
+        }
+    }
+}
+
 macro_rules! impl_task_finished_indicator {
     ($($param: ident),*) => {
         impl<$($param: TaskFinishedIndicator),*> TaskFinishedIndicator for ($($param,)*) where ($($param,)*): Debug {
@@ -335,6 +357,19 @@ pub mod tests {
         accepts_yarn_command(f);
     }
 
+    #[test]
+    fn accepts_returning_command_completion() {
+        fn f(_: In<()>) -> CommandCompletion {
+            let completion = CommandCompletion::new();
+            let task_completion = completion.clone();
+            AsyncComputeTaskPool::get()
+                .spawn(async move { task_completion.complete() })
+                .detach();
+            completion
+        }
+        accepts_yarn_command(f);
+    }
+
     macro_rules! assert_is_yarn_command {
         (($($param:ty),*) -> $ret:ty) => {
             static_assertions::assert_impl_all!(fn($($param),*) -> $ret: YarnCommand<fn($($param),*) -> $ret>);
@@ -16,6 +16,7 @@ pub enum DevelopmentFileGeneration {
     /// - Generates new strings files for all languages that are missing them, filling them with the lines found in the Yarn files.
     /// - Adds new lines to strings files when they have been added to a loaded Yarn file.
     /// - Marks lines in strings files that have been changed since they were translated by appending "NEEDS UPDATE" to the respective line texts.
+    /// - Recompiles the project and reloads running dialogues when a loaded Yarn file is modified.
     ///
     /// It is recommended to combine this setting with Bevy's [hot reload functionality](https://bevy-cheatbook.github.io/assets/hot-reload.html).
     /// Note that because of the extensive use of the filesystem, this setting is not available on Wasm or Android.
@@ -23,6 +24,7 @@ pub enum DevelopmentFileGeneration {
     /// The recommended setting for shipping the game:
     /// - Does not change any Yarn or strings files on disk.
     /// - Falls back to the base language when a line is missing in a strings file.
+    /// - Does not recompile the project when a loaded Yarn file is modified.
     None,
 }
 
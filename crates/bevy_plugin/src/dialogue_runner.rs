@@ -1,8 +1,11 @@
 pub use self::events::{
-    DialogueCompleteEvent, DialogueStartEvent, ExecuteCommandEvent, LineHintsEvent,
-    NodeCompleteEvent, NodeStartEvent, PresentLineEvent, PresentOptionsEvent,
+    DeferredFunctionCallEvent, DialogueCompleteEvent, DialogueReloadedEvent,
+    DialogueRuntimeErrorEvent, DialogueStartEvent, ExecuteCommandEvent,
+    LanguageChangeRequestedEvent, LanguageChangedEvent, LineHintsEvent, NodeCompleteEvent,
+    NodeStartEvent, PresentLineEvent, PresentOptionsEvent, VariableChangedEvent,
 };
 pub use self::{
+    budget::{DialogueRunnerBudget, PrioritizedDialogueRunner},
     builder::DialogueRunnerBuilder,
     dialogue_option::DialogueOption,
     inner::{InnerDialogue, InnerDialogueMut},
@@ -11,22 +14,30 @@ pub use self::{
 use crate::commands::TaskFinishedIndicator;
 use crate::line_provider::LineAssets;
 use crate::prelude::*;
+use crate::system_functions::UntypedYarnSystemFunction;
 use crate::UnderlyingYarnLine;
 use anyhow::{anyhow, bail};
 use bevy::asset::LoadedUntypedAsset;
-use bevy::utils::HashSet;
+use bevy::utils::{Duration, HashSet};
 use bevy::{prelude::*, utils::HashMap};
+pub(crate) use builder::retain_selected_nodes;
+use error_reporting::EventErrorReporter;
 pub(crate) use runtime_interaction::DialogueExecutionSystemSet;
 use std::any::TypeId;
+use std::borrow::Cow;
 use std::fmt::Debug;
+use variable_changes::VariableChangeQueue;
 use yarnspinner::core::Library;
 
+mod budget;
 mod builder;
 mod dialogue_option;
+mod error_reporting;
 mod events;
 mod inner;
 mod localized_line;
 mod runtime_interaction;
+mod variable_changes;
 
 pub(crate) fn dialogue_plugin(app: &mut App) {
     app.add_plugins(runtime_interaction::runtime_interaction_plugin)
@@ -34,7 +45,9 @@ pub(crate) fn dialogue_plugin(app: &mut App) {
         .add_plugins(events::dialogue_runner_events_plugin)
         .add_plugins(dialogue_option::dialogue_option_plugin)
         .add_plugins(builder::dialogue_runner_builder_plugin)
-        .add_plugins(inner::inner_dialogue_runner_plugin);
+        .add_plugins(budget::dialogue_runner_budget_plugin)
+        .add_plugins(inner::inner_dialogue_runner_plugin)
+        .add_plugins(variable_changes::variable_changes_plugin);
 }
 
 /// The main type to interact with the dialogue system.
@@ -52,8 +65,16 @@ pub struct DialogueRunner {
     pub(crate) is_running: bool,
     run_selected_options_as_lines: bool,
     pub(crate) just_started: bool,
+    pub(crate) changed_language: Option<Language>,
     pub(crate) popped_line_hints: Option<Vec<LineId>>,
     pub(crate) unsent_events: Vec<DialogueEvent>,
+    pub(crate) error_reporter: EventErrorReporter,
+    pub(crate) variable_changes: VariableChangeQueue,
+    pub(crate) system_functions: HashMap<Cow<'static, str>, Box<dyn UntypedYarnSystemFunction>>,
+    pub(crate) system_function_calls: Vec<DeferredFunctionCall>,
+    pub(crate) selected_nodes: Option<HashSet<String>>,
+    /// How long the dialogue runner was kept from continuing by the [`DialogueRunnerBudget`], to be subtracted from its next wait.
+    pub(crate) overdue: Duration,
 }
 
 impl DialogueRunner {
@@ -80,19 +101,40 @@ impl DialogueRunner {
     }
 
     /// If the dialogue is currently waiting for the user to select an option, this method will select the option with the given id.
+    /// Passing a [`DialogueOption`] instead of an [`OptionId`] additionally fails if the option is stale, i.e. the options it was presented with
+    /// have since been discarded or presented again.
     /// Implies [`DialogueRunner::continue_in_next_update`].
-    pub fn select_option(&mut self, option: OptionId) -> Result<&mut Self> {
+    pub fn select_option(&mut self, option: impl Into<OptionSelection>) -> Result<&mut Self> {
+        let selection = option.into();
+        let option = selection.id;
         if !self.is_running {
             bail!("Can't select option {option}: the dialogue is currently not running. Please call `DialogueRunner::continue_in_next_update()` only after receiving a `PresentOptionsEvent`.")
         }
         self.dialogue
-            .set_selected_option(option)
+            .set_selected_option(selection)
             .map_err(Error::from)?;
         self.last_selected_option.replace(option);
         self.continue_in_next_update();
         Ok(self)
     }
 
+    /// Passes the return value of the function call announced by a [`DeferredFunctionCallEvent`] to the dialogue.
+    /// Implies [`DialogueRunner::continue_in_next_update`].
+    pub fn complete_function(
+        &mut self,
+        token: FunctionCallToken,
+        value: impl Into<YarnValue>,
+    ) -> Result<&mut Self> {
+        if !self.is_running {
+            bail!("Can't complete function call {token}: the dialogue is currently not running.")
+        }
+        self.dialogue
+            .complete_function(token, value)
+            .map_err(Error::from)?;
+        self.continue_in_next_update();
+        Ok(self)
+    }
+
     /// Returns whether the dialogue runner is currently running. Returns `false` if:
     /// - The dialogue has not yet been started via [`DialogueRunner::start_node`]
     /// - The dialogue has been stopped via [`DialogueRunner::stop`]
@@ -128,14 +170,22 @@ impl DialogueRunner {
     /// Stops the execution of the dialogue. Any pending dialogue events will still be sent in the next update, including a [`DialogueCompleteEvent`].
     /// After this, [`DialogueRunner::start_node`] must be called before the dialogue can be advanced again.
     pub fn stop(&mut self) -> &mut Self {
+        let stop_events = self.stop_silently();
+        self.unsent_events.extend(stop_events);
+        self
+    }
+
+    /// Stops the execution of the dialogue like [`DialogueRunner::stop`], but returns the pending dialogue events instead of sending them,
+    /// so that e.g. restarting a hot reloaded dialogue does not send a [`DialogueCompleteEvent`].
+    pub(crate) fn stop_silently(&mut self) -> Vec<DialogueEvent> {
         self.is_running = false;
         self.last_selected_option = None;
         self.popped_line_hints = None;
         self.will_continue_in_next_update = false;
         self.just_started = false;
-        let stop_events = self.dialogue.stop();
-        self.unsent_events.extend(stop_events);
-        self
+        self.overdue = Duration::ZERO;
+        self.release_line_hints();
+        self.dialogue.stop()
     }
 
     /// Starts the dialogue at the given node.
@@ -154,11 +204,11 @@ impl DialogueRunner {
         if self.is_running {
             bail!("Can't start dialogue from node {node_name}: the dialogue is currently in the middle of running. Stop the dialogue first.");
         }
-        self.is_running = true;
-        self.just_started = true;
         self.dialogue
             .set_node(node_name)
             .map_err(|e| anyhow!("Can't start dialogue from node {node_name}: {e}"))?;
+        self.is_running = true;
+        self.just_started = true;
         self.popped_line_hints = self.dialogue.pop_line_hints();
         self.continue_in_next_update();
         Ok(self)
@@ -175,6 +225,18 @@ impl DialogueRunner {
         self.dialogue.get_tags_for_node(node_name)
     }
 
+    /// Returns the headers for the node `node_name`, with the values of each key in order of appearance.
+    /// Keys that appear more than once in the node keep all their values.
+    ///
+    /// Returns [`None`] if the node is not present in the program.
+    #[must_use]
+    pub fn node_headers(
+        &self,
+        node_name: &str,
+    ) -> Option<std::collections::HashMap<&str, Vec<&str>>> {
+        self.dialogue.node_headers(node_name)
+    }
+
     /// Gets a value indicating whether a specified node exists in the Yarn files.
     #[must_use]
     pub fn node_exists(&self, node_name: &str) -> bool {
@@ -188,13 +250,24 @@ impl DialogueRunner {
         self.dialogue.current_node()
     }
 
+    /// Returns where in the Yarn source the line, options or command that was delivered last was written, e.g. to report a command that failed.
+    /// This is [`None`] if [`DialogueRunner::is_running`] is `false`.
+    #[must_use]
+    pub fn current_line_info(&self) -> Option<LineInfo> {
+        self.dialogue.current_line_info()
+    }
+
     /// Returns a shallow clone of the registered [`VariableStorage`]. The storage used can be overridden by calling [`DialogueRunnerBuilder::with_variable_storage`].
+    /// Unless overridden, it is not shared with other [`DialogueRunner`]s.
+    /// Changes made through it are sent as [`VariableChangedEvent`]s.
     #[must_use]
     pub fn variable_storage(&self) -> &dyn VariableStorage {
         self.dialogue.variable_storage()
     }
 
     /// Returns a shallow mutable clone of the registered [`VariableStorage`]. The storage used can be overridden by calling [`DialogueRunnerBuilder::with_variable_storage`].
+    /// Unless overridden, it is not shared with other [`DialogueRunner`]s.
+    /// Changes made through it are sent as [`VariableChangedEvent`]s.
     #[must_use]
     pub fn variable_storage_mut(&mut self) -> &mut dyn VariableStorage {
         self.dialogue.variable_storage_mut()
@@ -215,6 +288,14 @@ impl DialogueRunner {
         self.text_provider.are_lines_available()
     }
 
+    /// Returns whether the lines of the given language are ready to be delivered, i.e. whether it is the current text language
+    /// and the text provider has finished loading its lines. The [`DialogueRunner`] waits for this before continuing
+    /// after its language was changed via [`DialogueRunner::set_text_language`] or a [`LanguageChangeRequestedEvent`].
+    #[must_use]
+    pub fn are_lines_available_for(&self, language: &Language) -> bool {
+        self.text_language().as_ref() == Some(language) && self.are_texts_available()
+    }
+
     /// Returns whether all asset providers have loaded all their assets.
     /// If no asset providers where added via [`DialogueRunnerBuilder::add_asset_provider`], this will always return `true`.
     #[must_use]
@@ -238,7 +319,8 @@ impl DialogueRunner {
     pub fn set_text_language(&mut self, language: impl Into<Language>) -> &mut Self {
         let language = language.into();
         self.assert_localizations_available_for_language(&language);
-        self.dialogue.set_language_code(language);
+        self.dialogue.set_language_code(language.clone());
+        self.changed_language = Some(language);
         self
     }
 
@@ -293,6 +375,13 @@ impl DialogueRunner {
         self.dialogue.language_code().cloned()
     }
 
+    /// Returns the [`LanguagePresentation`] of the language used by the [`TextProvider`], if its [`Localization`] has one.
+    #[must_use]
+    pub fn language_presentation(&self) -> Option<&LanguagePresentation> {
+        let language = self.dialogue.language_code()?;
+        self.localizations.as_ref()?.presentation(language)
+    }
+
     /// Returns the language used by the [`AssetProvider`]s. If there are no [`Localizations`] available, this will return [`None`].
     /// Panics if the asset providers have different languages.
     #[must_use]
@@ -341,6 +430,13 @@ impl DialogueRunner {
         self.asset_providers.values().map(|p| p.as_ref())
     }
 
+    /// Tells all asset providers that no more lines are upcoming, so that they can drop the handles of hinted lines that were never reached.
+    pub(crate) fn release_line_hints(&mut self) {
+        for asset_provider in self.asset_providers.values_mut() {
+            asset_provider.accept_line_hints(&[]);
+        }
+    }
+
     #[must_use]
     pub(crate) fn get_assets(&self, line: &UnderlyingYarnLine) -> LineAssets {
         self.asset_providers
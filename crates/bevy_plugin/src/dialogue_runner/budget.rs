@@ -0,0 +1,145 @@
+use bevy::prelude::*;
+use bevy::utils::{Duration, HashSet};
+use std::collections::VecDeque;
+
+pub(crate) fn dialogue_runner_budget_plugin(app: &mut App) {
+    app.init_resource::<DialogueRunnerBudget>()
+        .register_type::<PrioritizedDialogueRunner>();
+}
+
+/// Limits how much work is spent on advancing [`DialogueRunner`](crate::prelude::DialogueRunner)s in a single update.
+/// By default, there are no limits and every dialogue runner that is ready to continue is advanced in the same update.
+///
+/// When many dialogue runners become ready at the same time, e.g. because their `<<wait>>`s were started by the same event,
+/// the ones that exceed the budget are queued and advanced in the following updates in the order they became ready.
+/// The time a dialogue runner spent in the queue is subtracted from its next `<<wait>>`, so that its waits don't drift.
+/// Dialogue runners with a [`PrioritizedDialogueRunner`] component are always advanced immediately and do not count towards the budget.
+///
+/// ## Example
+///
+/// ```rust
+/// use bevy::{prelude::*, utils::Duration};
+/// use bevy_yarnspinner::prelude::*;
+///
+/// let budget = DialogueRunnerBudget::default()
+///     .with_max_runners_per_update(10)
+///     .with_time_budget(Duration::from_millis(2));
+/// # let mut app = App::new();
+/// app.insert_resource(budget);
+/// ```
+#[derive(Debug, Clone, Default, Resource)]
+pub struct DialogueRunnerBudget {
+    /// The maximum number of dialogue runners that are advanced in a single update. `None` means no limit.
+    pub max_runners_per_update: Option<usize>,
+    /// The maximum time spent advancing dialogue runners in a single update. `None` means no limit.
+    /// The time is only checked between dialogue runners, so at least one dialogue runner is advanced per update.
+    pub time_budget: Option<Duration>,
+    queue: VecDeque<QueuedDialogueRunner>,
+    runners_advanced: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct QueuedDialogueRunner {
+    entity: Entity,
+    overdue: Duration,
+}
+
+/// Marks a [`DialogueRunner`](crate::prelude::DialogueRunner) as important enough to ignore the [`DialogueRunnerBudget`].
+#[derive(Debug, Clone, Copy, Default, Component, Reflect)]
+#[reflect(Debug, Component, Default)]
+pub struct PrioritizedDialogueRunner;
+
+impl DialogueRunnerBudget {
+    /// Sets [`DialogueRunnerBudget::max_runners_per_update`].
+    #[must_use]
+    pub fn with_max_runners_per_update(mut self, max_runners_per_update: usize) -> Self {
+        self.max_runners_per_update = Some(max_runners_per_update);
+        self
+    }
+
+    /// Sets [`DialogueRunnerBudget::time_budget`].
+    #[must_use]
+    pub fn with_time_budget(mut self, time_budget: Duration) -> Self {
+        self.time_budget = Some(time_budget);
+        self
+    }
+
+    /// The number of dialogue runners that were advanced in the last update, including prioritized ones.
+    #[must_use]
+    pub fn runners_advanced(&self) -> usize {
+        self.runners_advanced
+    }
+
+    /// The number of dialogue runners that are ready to continue, but had to wait for a later update because the budget was exhausted.
+    #[must_use]
+    pub fn queue_depth(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// How long the dialogue runner that has been waiting the longest has been ready to continue without being advanced.
+    #[must_use]
+    pub fn max_overdue(&self) -> Duration {
+        self.queue
+            .iter()
+            .map(|queued| queued.overdue)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Queues the dialogue runners that are ready to continue and returns the order in which they should be advanced,
+    /// together with whether they are exempt from the budget.
+    /// Exempt dialogue runners come first, followed by the queued ones in the order they became ready.
+    pub(crate) fn schedule(
+        &mut self,
+        ready_runners: impl IntoIterator<Item = (Entity, bool)>,
+        delta: Duration,
+    ) -> Vec<(Entity, bool)> {
+        self.runners_advanced = 0;
+        let (exempt, budgeted): (Vec<_>, Vec<_>) =
+            ready_runners.into_iter().partition(|(_, exempt)| *exempt);
+        let ready: HashSet<_> = budgeted.iter().map(|(entity, _)| *entity).collect();
+
+        // Runners that stopped being ready, e.g. because they were despawned or stopped, leave the queue
+        self.queue.retain(|queued| ready.contains(&queued.entity));
+        for queued in &mut self.queue {
+            queued.overdue += delta;
+        }
+        let queued: HashSet<_> = self.queue.iter().map(|queued| queued.entity).collect();
+        self.queue.extend(
+            budgeted
+                .into_iter()
+                .filter(|(entity, _)| !queued.contains(entity))
+                .map(|(entity, _)| QueuedDialogueRunner {
+                    entity,
+                    overdue: Duration::ZERO,
+                }),
+        );
+
+        exempt
+            .into_iter()
+            .chain(self.queue.iter().map(|queued| (queued.entity, false)))
+            .collect()
+    }
+
+    /// Returns whether another budgeted dialogue runner may be advanced in this update.
+    pub(crate) fn allows(&self, budgeted_runners_advanced: usize, elapsed: Duration) -> bool {
+        let count_exhausted = self
+            .max_runners_per_update
+            .is_some_and(|max| budgeted_runners_advanced >= max);
+        let time_exhausted = self
+            .time_budget
+            .is_some_and(|budget| budgeted_runners_advanced > 0 && elapsed >= budget);
+        !count_exhausted && !time_exhausted
+    }
+
+    /// Returns how long the dialogue runner was ready to continue before it was advanced.
+    pub(crate) fn mark_advanced(&mut self, entity: Entity) -> Duration {
+        self.runners_advanced += 1;
+        self.queue
+            .iter()
+            .position(|queued| queued.entity == entity)
+            .and_then(|index| self.queue.remove(index))
+            .map(|queued| queued.overdue)
+            .unwrap_or_default()
+    }
+}
@@ -1,22 +1,43 @@
 use crate::default_impl::{MemoryVariableStorage, StringsFileTextProvider};
+use crate::dialogue_runner::error_reporting::EventErrorReporter;
+use crate::dialogue_runner::variable_changes::VariableChangeQueue;
 use crate::fmt_utils::SkipDebug;
-use crate::line_provider::SharedTextProvider;
+use crate::line_provider::{SharedTextProvider, TextSourceAdapter};
 use crate::prelude::*;
+use crate::system_functions::{UntypedYarnSystemFunction, YarnSystemFunctionWrapper};
+use anyhow::bail;
 use bevy::prelude::*;
-use bevy::utils::HashMap;
-use rand::{rngs::SmallRng, Rng, SeedableRng};
+use bevy::utils::{HashMap, HashSet};
 use std::any::{Any, TypeId};
+use std::borrow::Cow;
 use std::fmt::Debug;
+use yarnspinner::core::{Node, Program};
 
 pub(crate) fn dialogue_runner_builder_plugin(_app: &mut App) {}
 
 /// A builder for [`DialogueRunner`]. This is instantiated for you by calling [`YarnProject::build_dialogue_runner`].
+///
+/// ## Multiple dialogue runners
+///
+/// Every [`DialogueRunner`] built from a [`YarnProject`] gets its own [`MemoryVariableStorage`] unless told otherwise,
+/// so multiple runners, e.g. for split-screen conversations, do not influence each other's variables.
+/// They only share state if they are given the same storage, e.g. by passing [`VariableStorage::clone_shallow`] to
+/// [`DialogueRunnerBuilder::with_variable_storage`]. Use [`DialogueRunnerBuilder::with_isolated_variable_storage`] to make
+/// the isolation explicit. All runners read from the same compiled [`Program`], which is never mutated while running.
+///
+/// ## Functions of a single runner
+///
+/// Functions added with [`DialogueRunnerBuilder::with_function`] and [`DialogueRunnerBuilder::with_library`] can only be called by the built [`DialogueRunner`].
+/// Since the Yarn files are compiled before any runner is built, the compiler does not know about these functions and infers their types from how they are called.
+/// To have calls to them type checked instead, pass the union of all runners' functions to [`YarnSpinnerPlugin::add_library`].
 #[derive(Debug)]
 pub struct DialogueRunnerBuilder {
     variable_storage: Box<dyn VariableStorage>,
     text_provider: SharedTextProvider,
     asset_providers: HashMap<TypeId, Box<dyn AssetProvider>>,
     library: YarnLibrary,
+    system_functions: HashMap<Cow<'static, str>, Box<dyn UntypedYarnSystemFunction>>,
+    selected_nodes: Option<HashSet<String>>,
     commands: YarnCommands,
     compilation: Compilation,
     localizations: Option<Localizations>,
@@ -33,6 +54,8 @@ impl DialogueRunnerBuilder {
             )),
             asset_providers: HashMap::new(),
             library: create_extended_standard_library(),
+            system_functions: HashMap::new(),
+            selected_nodes: None,
             commands: YarnCommands::builtin_commands(),
             compilation: yarn_project.compilation().clone(),
             localizations: yarn_project.localizations().cloned(),
@@ -47,6 +70,25 @@ impl DialogueRunnerBuilder {
         self
     }
 
+    /// Gives the [`DialogueRunner`] a fresh [`MemoryVariableStorage`] that is seeded with the initial values of all declared variables
+    /// and not shared with any other runner. This undoes a previous call to [`DialogueRunnerBuilder::with_variable_storage`].
+    #[must_use]
+    pub fn with_isolated_variable_storage(mut self) -> Self {
+        let mut storage = MemoryVariableStorage::new();
+        if let Some(program) = self.compilation.program.as_ref() {
+            let initial_values = program
+                .initial_values
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone().into()))
+                .collect();
+            storage
+                .extend(initial_values)
+                .unwrap_or_else(|error| panic!("Failed to seed isolated VariableStorage: {error}"));
+        }
+        self.variable_storage = Box::new(storage);
+        self
+    }
+
     /// Replaces the [`TextProvider`] used by the [`DialogueRunner`]. By default, this is a [`StringsFileTextProvider`].
     #[must_use]
     pub fn with_text_provider(mut self, provider: impl TextProvider + 'static) -> Self {
@@ -54,6 +96,17 @@ impl DialogueRunnerBuilder {
         self
     }
 
+    /// Replaces the [`TextProvider`] used by the [`DialogueRunner`] with one that gets its text from the given [`TextSource`].
+    /// This is the way to use text providers from other crates, as [`TextSource`] does not depend on the internals of this plugin.
+    /// The [`TextSource`] receives the base language strings of the [`YarnProject`] via [`TextSource::set_base_language`].
+    #[must_use]
+    pub fn with_text_source(mut self, source: impl TextSource) -> Self {
+        let mut adapter = TextSourceAdapter::new(source);
+        adapter.set_base_string_table(self.compilation.string_table.clone());
+        self.text_provider.replace(adapter);
+        self
+    }
+
     /// Adds an [`AssetProvider`] to the [`DialogueRunner`]. By default, none are registered.
     #[must_use]
     pub fn add_asset_provider(mut self, provider: impl AssetProvider + 'static) -> Self {
@@ -62,6 +115,71 @@ impl DialogueRunnerBuilder {
         self
     }
 
+    /// Adds a [`YarnSystemFunction`] that can be called from Yarn by this [`DialogueRunner`] only, e.g. one that reads the position of the player from the world.
+    /// Replaces any function of the same name, including those of the standard library.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use bevy::prelude::*;
+    /// # use bevy_yarnspinner::prelude::*;
+    /// #[derive(Component)]
+    /// struct Player;
+    ///
+    /// fn spawn_dialogue_runner(mut commands: Commands, project: Res<YarnProject>) {
+    ///     let dialogue_runner = project
+    ///         .build_dialogue_runner()
+    ///         .with_function("player_x", player_x)
+    ///         .build();
+    ///     commands.spawn(dialogue_runner);
+    /// }
+    ///
+    /// fn player_x(_: In<()>, players: Query<&Transform, With<Player>>) -> f32 {
+    ///     players.single().translation.x
+    /// }
+    /// ```
+    #[must_use]
+    pub fn with_function<Marker, F>(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        function: F,
+    ) -> Self
+    where
+        Marker: 'static,
+        F: YarnSystemFunction<Marker>,
+    {
+        let name = name.into();
+        self.library
+            .add_deferred_function(name.clone(), YarnSystemFunctionSignature::of(&function));
+        let wrapped = YarnSystemFunctionWrapper::from(function);
+        self.system_functions.insert(name, Box::new(wrapped));
+        self
+    }
+
+    /// Adds the functions of the given [`YarnLibrary`] to the ones that can be called from Yarn by this [`DialogueRunner`].
+    /// Replaces any functions of the same name, including those of the standard library.
+    /// Use this for plain functions that don't need access to the world, otherwise see [`DialogueRunnerBuilder::with_function`].
+    #[must_use]
+    pub fn with_library(mut self, library: YarnLibrary) -> Self {
+        for name in library.names() {
+            self.system_functions.remove(name);
+        }
+        self.library.import(library);
+        self
+    }
+
+    /// Restricts the [`DialogueRunner`] to the nodes with the given names, e.g. to give every runner its own shard of a big project.
+    /// The other nodes cannot be started or jumped to by the runner. Nodes that belong to a selected node group are kept as well.
+    /// This selection also applies when the project is recompiled, e.g. because of hot reloading.
+    /// By default, all nodes are available.
+    ///
+    /// [`DialogueRunnerBuilder::try_build`] fails if one of the nodes does not exist.
+    #[must_use]
+    pub fn with_nodes(mut self, node_names: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.selected_nodes = Some(node_names.into_iter().map(Into::into).collect());
+        self
+    }
+
     /// Builds the [`DialogueRunner`]. See [`DialogueRunnerBuilder::try_build`] for the fallible version.
     pub fn build(self) -> DialogueRunner {
         self.try_build().unwrap_or_else(|error| {
@@ -73,12 +191,36 @@ impl DialogueRunnerBuilder {
     pub fn try_build(mut self) -> Result<DialogueRunner> {
         let text_provider = Box::new(self.text_provider);
 
-        let mut dialogue = Dialogue::new(self.variable_storage, text_provider.clone());
+        let variable_changes = VariableChangeQueue::default();
+        let variable_storage = variable_changes.observe(self.variable_storage);
+        let mut program = self.compilation.program.unwrap();
+        if let Some(selected_nodes) = self.selected_nodes.as_ref() {
+            if let Some(missing_node) = selected_nodes
+                .iter()
+                .find(|node| !program.nodes.contains_key(*node))
+            {
+                bail!("Cannot select node \"{missing_node}\" because it does not exist in the Yarn project.");
+            }
+            retain_selected_nodes(&mut program, selected_nodes);
+        }
+
+        let mut dialogue = Dialogue::new(Box::new(variable_storage), text_provider.clone());
         dialogue
             .set_line_hints_enabled(true)
             .library_mut()
-            .extend(self.library);
-        dialogue.add_program(self.compilation.program.unwrap());
+            .import(self.library);
+        let line_metadata = self
+            .compilation
+            .string_table
+            .iter()
+            .map(|(line_id, string_info)| (line_id.clone(), string_info.metadata.clone()))
+            .collect();
+        dialogue
+            .add_program(program)?
+            .add_debug_info(self.compilation.debug_info)
+            .add_line_metadata(line_metadata);
+        let error_reporter = EventErrorReporter::default();
+        dialogue.set_error_reporter(Box::new(error_reporter.clone()));
 
         for asset_provider in self.asset_providers.values_mut() {
             if let Some(ref localizations) = self.localizations {
@@ -108,8 +250,15 @@ impl DialogueRunnerBuilder {
             will_continue_in_next_update: default(),
             last_selected_option: default(),
             just_started: default(),
+            changed_language: default(),
             unsent_events: default(),
             localizations: self.localizations,
+            error_reporter,
+            variable_changes,
+            system_functions: self.system_functions,
+            system_function_calls: default(),
+            selected_nodes: self.selected_nodes,
+            overdue: default(),
         };
 
         if let Some(base_language) = base_language {
@@ -120,24 +269,24 @@ impl DialogueRunnerBuilder {
     }
 }
 
+/// Removes all nodes from the program that are not selected, except for the ones needed by selected node groups and smart variables.
+pub(crate) fn retain_selected_nodes(program: &mut Program, selected_nodes: &HashSet<String>) {
+    let node_group_variants = program.nodes.keys().filter(|name| {
+        Node::node_group_of_variant(name).is_some_and(|group| selected_nodes.contains(group))
+    });
+    let kept_nodes: HashSet<_> = selected_nodes
+        .iter()
+        .chain(node_group_variants)
+        .cloned()
+        .collect();
+    program
+        .nodes
+        .retain(|name, node| node.is_smart_variable() || kept_nodes.contains(name));
+}
+
 fn create_extended_standard_library() -> YarnLibrary {
     let mut library = YarnLibrary::standard_library();
     library
-        .add_function("random", || SmallRng::from_entropy().gen_range(0.0..1.0))
-        .add_function("random_range", |min: f32, max: f32| {
-            if let Some(min) = min.as_int() {
-                if let Some(max_inclusive) = max.as_int() {
-                    return SmallRng::from_entropy().gen_range(min..=max_inclusive) as f32;
-                }
-            }
-            SmallRng::from_entropy().gen_range(min..max)
-        })
-        .add_function("dice", |sides: u32| {
-            if sides == 0 {
-                return 1;
-            }
-            SmallRng::from_entropy().gen_range(1..=sides)
-        })
         .add_function("round", |num: f32| num.round() as i32)
         .add_function("round_places", |num: f32, places: u32| {
             num.round_places(places)
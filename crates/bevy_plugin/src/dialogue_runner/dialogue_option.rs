@@ -19,20 +19,36 @@ pub struct DialogueOption {
     /// The name of the node that will be run if this option is selected.
     ///
     /// The value of this property not be valid if this is a shortcut option.
+    /// See [`DialogueOption::destination`] for the node that the option's content jumps to.
     pub destination_node: String,
 
+    /// The node that the content of this option jumps to when it is selected.
+    /// [`None`] if the dialogue continues after the options instead, or the jump's destination is only known at runtime.
+    pub destination: Option<String>,
+
     /// Gets a value indicating whether the player should be permitted to select this option.
     ///
     /// If this value is `false`, this option had a line condition on it that failed.
     /// The option will still be delivered to the game, but, depending on the needs of the game,
     /// the game may decide to not allow the player to select it, or not offer it to the player at all.
+    /// Selecting it via [`DialogueRunner::select_option`] fails.
     ///
     /// This is intended for situations where games wish to show options that the player _could_ have taken,
     /// if some other condition had been met (e.g. having enough "charisma" points).
     pub is_available: bool,
+
+    /// The generation of the option set this option was presented in.
+    ///
+    /// Selecting this option via [`DialogueRunner::select_option`] fails if the options have been discarded or presented again since.
+    pub generation: usize,
 }
 
 impl DialogueOption {
+    /// The list of metadata associated with this option, see [`LocalizedLine::metadata`].
+    pub fn metadata(&self) -> &[String] {
+        &self.line.metadata
+    }
+
     pub(crate) fn from_yarn_dialogue_option(
         yarn_dialogue_option: yarnspinner::prelude::DialogueOption,
         assets: LineAssets,
@@ -42,7 +58,18 @@ impl DialogueOption {
             line: LocalizedLine::from_yarn_line(yarn_dialogue_option.line, assets, metadata),
             id: yarn_dialogue_option.id,
             destination_node: yarn_dialogue_option.destination_node,
+            destination: yarn_dialogue_option.destination,
             is_available: yarn_dialogue_option.is_available,
+            generation: yarn_dialogue_option.generation,
+        }
+    }
+}
+
+impl From<&DialogueOption> for OptionSelection {
+    fn from(option: &DialogueOption) -> Self {
+        Self {
+            id: option.id,
+            generation: Some(option.generation),
         }
     }
 }
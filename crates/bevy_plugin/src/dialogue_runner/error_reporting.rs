@@ -0,0 +1,44 @@
+use crate::events::DialogueRuntimeErrorEvent;
+use crate::prelude::*;
+use bevy::prelude::*;
+use std::sync::{Arc, Mutex};
+
+/// The default [`ErrorReporter`] of a [`DialogueRunner`].
+/// Logs errors with their `path:line:column` and queues them up to be sent as [`DialogueRuntimeErrorEvent`]s.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EventErrorReporter(Arc<Mutex<Vec<DialogueRuntimeErrorEvent>>>);
+
+impl EventErrorReporter {
+    /// Drains the queued events, attributing them to the given [`DialogueRunner`].
+    pub(crate) fn take_events(&self, source: Entity) -> Vec<DialogueRuntimeErrorEvent> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+            .into_iter()
+            .map(|event| DialogueRuntimeErrorEvent { source, ..event })
+            .collect()
+    }
+}
+
+impl ErrorReporter for EventErrorReporter {
+    fn clone_shallow(&self) -> Box<dyn ErrorReporter> {
+        Box::new(self.clone())
+    }
+
+    fn report(&mut self, report: &RuntimeErrorReport) {
+        let message = report.error.to_string();
+        match report.source_location() {
+            Some(source_location) => error!("{source_location}: {message}"),
+            None => error!("{message}"),
+        }
+        self.0.lock().unwrap().push(DialogueRuntimeErrorEvent {
+            message,
+            node: report.node.clone(),
+            instruction_index: report.instruction_index,
+            file_name: report.file_name.clone(),
+            line: report.line,
+            column: report.column,
+            recent_line_ids: report.recent_line_ids.clone(),
+            // Not known to the dialogue, filled in when the event is sent
+            source: Entity::PLACEHOLDER,
+        });
+    }
+}
@@ -6,11 +6,17 @@ pub(crate) fn dialogue_runner_events_plugin(app: &mut App) {
     app.add_event::<PresentLineEvent>()
         .add_event::<PresentOptionsEvent>()
         .add_event::<ExecuteCommandEvent>()
+        .add_event::<DeferredFunctionCallEvent>()
         .add_event::<NodeCompleteEvent>()
         .add_event::<NodeStartEvent>()
         .add_event::<LineHintsEvent>()
         .add_event::<DialogueCompleteEvent>()
-        .add_event::<DialogueStartEvent>();
+        .add_event::<DialogueStartEvent>()
+        .add_event::<DialogueReloadedEvent>()
+        .add_event::<DialogueRuntimeErrorEvent>()
+        .add_event::<LanguageChangeRequestedEvent>()
+        .add_event::<LanguageChangedEvent>()
+        .add_event::<VariableChangedEvent>();
 }
 
 /// An event that is fired after a dialogue advances and wishes to present a line to the user.
@@ -50,6 +56,17 @@ pub struct ExecuteCommandEvent {
     pub source: Entity,
 }
 
+/// An event that is fired when a function registered with [`YarnLibrary::add_deferred_function`] was called.
+/// The dialogue will not continue until the return value is passed to [`DialogueRunner::complete_function`].
+/// Handling this event is only necessary if you registered deferred functions.
+#[derive(Debug, Clone, PartialEq, Event)]
+pub struct DeferredFunctionCallEvent {
+    /// The function call to complete.
+    pub call: DeferredFunctionCall,
+    /// The [`DialogueRunner`] that is waiting for the return value.
+    pub source: Entity,
+}
+
 /// An event that is fired after a node has been completed, i.e. all of its lines, commands, options, etc. have been exhausted.
 /// Handling this event is **optional** for dialogue views.
 #[derive(Debug, Clone, PartialEq, Event)]
@@ -95,3 +112,82 @@ pub struct DialogueCompleteEvent {
     /// The [`DialogueRunner`] that has completed this dialogue.
     pub source: Entity,
 }
+
+/// An event that is fired when a running dialogue had to restart its current node because the Yarn files were hot reloaded
+/// and the node was changed. Any lines or options shown for this dialogue are stale and should be cleared.
+/// If the node was removed, the dialogue is stopped instead and the failure to restart it is sent as a [`DialogueRuntimeErrorEvent`].
+/// Handling this event is **optional** for dialogue views.
+#[derive(Debug, Clone, PartialEq, Event)]
+pub struct DialogueReloadedEvent {
+    /// The name of the node that the dialogue restarted.
+    pub node_name: String,
+    /// The [`DialogueRunner`] that has been reloaded.
+    pub source: Entity,
+}
+
+/// An event that is fired when a [`DialogueRunner`] ran into an error, e.g. a call to a function that is not registered.
+/// Refers back to the Yarn source that caused it, so tools like an editor integration can point the writer to it.
+/// The source coordinates are [`None`] if the error did not occur while running a particular instruction.
+/// Handling this event is **optional** for dialogue views.
+#[derive(Debug, Clone, PartialEq, Event)]
+pub struct DialogueRuntimeErrorEvent {
+    /// The error message.
+    pub message: String,
+    /// The node that was running when the error occurred, if any.
+    pub node: Option<String>,
+    /// The index of the instruction in the node that was running when the error occurred.
+    pub instruction_index: usize,
+    /// The Yarn file that the failing instruction was compiled from.
+    pub file_name: Option<String>,
+    /// The one-based line in [`DialogueRuntimeErrorEvent::file_name`] that the failing instruction was compiled from.
+    pub line: Option<usize>,
+    /// The one-based column in [`DialogueRuntimeErrorEvent::file_name`] that the failing instruction was compiled from.
+    pub column: Option<usize>,
+    /// The IDs of the last lines that were delivered before the error occurred, oldest first.
+    pub recent_line_ids: Vec<LineId>,
+    /// The [`DialogueRunner`] that ran into the error.
+    pub source: Entity,
+}
+
+/// An event that can be sent to change the languages of [`DialogueRunner`]s, e.g. from a language selection in the game's settings.
+/// It is applied like calling [`DialogueRunner::set_text_language`] and [`DialogueRunner::set_asset_language`] before the runners continue,
+/// so the new languages take effect on the next delivered line, which waits until the lines of the new languages are loaded.
+/// The text and asset languages can differ, e.g. to show German subtitles for English voice lines.
+#[derive(Debug, Clone, PartialEq, Event)]
+pub struct LanguageChangeRequestedEvent {
+    /// The new text language, or [`None`] to keep the current one.
+    pub text_language: Option<Language>,
+    /// The new asset language, or [`None`] to keep the current one.
+    pub asset_language: Option<Language>,
+    /// The [`DialogueRunner`] whose languages should be changed, or [`None`] to change the languages of all of them.
+    pub target: Option<Entity>,
+}
+
+/// An event that is fired after the text language of a [`DialogueRunner`] was changed via [`DialogueRunner::set_text_language`] or [`DialogueRunner::set_language`].
+/// Dialogue views can use this to e.g. swap fonts in one place.
+/// Handling this event is **optional** for dialogue views.
+#[derive(Debug, Clone, PartialEq, Event)]
+pub struct LanguageChangedEvent {
+    /// The new text language.
+    pub language: Language,
+    /// The presentation hints of the new language, if its [`Localization`] has any.
+    pub presentation: Option<LanguagePresentation>,
+    /// The [`DialogueRunner`] whose language was changed.
+    pub source: Entity,
+}
+
+/// An event that is fired after a variable of a [`DialogueRunner`] was written to, e.g. by a `<<set>>` statement
+/// or through [`DialogueRunner::variable_storage_mut`]. Games can use this to e.g. update a quest log.
+/// Every write is reported in order, even if it did not change the value.
+/// Handling this event is **optional** for dialogue views.
+#[derive(Debug, Clone, PartialEq, Event)]
+pub struct VariableChangedEvent {
+    /// The name of the variable, including the leading `$`.
+    pub name: String,
+    /// The new value of the variable.
+    pub value: YarnValue,
+    /// The value of the variable before it was written to, or [`None`] if it had none.
+    pub previous_value: Option<YarnValue>,
+    /// The [`DialogueRunner`] whose variable was written to.
+    pub source: Entity,
+}
@@ -15,6 +15,13 @@ pub struct LocalizedLine {
     pub text: String,
     /// The [`MarkupAttribute`]s in this line. An example of markup is `Hello, [b]world[/b]!`.
     pub attributes: Vec<MarkupAttribute>,
+    /// The text as returned by the [`TextProvider`], before markup was parsed.
+    /// Contains placeholders such as `{0}` where the values in [`LocalizedLine::substitutions`] are inserted.
+    pub raw_text: String,
+    /// The values of the expressions in the line, e.g. the value of `$gold` in `You have {$gold} gold`,
+    /// in the order of their placeholders in [`LocalizedLine::raw_text`].
+    /// Useful when presenting the values differently, e.g. by animating a number counting up.
+    pub substitutions: Vec<YarnValue>,
     /// The list of metadata associated with this line, excluding the line ID.
     /// Metadata is defined by the hashtags at the end of the line, e.g. `Hello, world! #greeting #friendly`.
     /// This data is also provided in the `comment` field of a generated strings file.
@@ -46,6 +53,8 @@ impl LocalizedLine {
     /// #        properties: HashMap::from([("name".to_owned(), "Alice".into())]),
     /// #        source_position: 0,
     /// #    }],
+    /// #    raw_text: String::new(),
+    /// #    substitutions: vec![],
     /// #    metadata: vec![],
     /// #    assets: Default::default(),
     /// # };
@@ -61,6 +70,8 @@ impl LocalizedLine {
     /// #    id: "line".into(),
     /// #    text: "Great, thanks".to_owned(),
     /// #    attributes: vec![],
+    /// #    raw_text: String::new(),
+    /// #    substitutions: vec![],
     /// #    metadata: vec![],
     /// #    assets: Default::default(),
     /// # };
@@ -99,6 +110,8 @@ impl LocalizedLine {
     /// #        properties: HashMap::from([("name".to_owned(), "Alice".into())]),
     /// #        source_position: 0,
     /// #    }],
+    /// #    raw_text: String::new(),
+    /// #    substitutions: vec![],
     /// #    metadata: vec![],
     /// #    assets: Default::default(),
     /// # };
@@ -114,6 +127,8 @@ impl LocalizedLine {
     /// #    id: "line".into(),
     /// #    text: "Great, thanks".to_owned(),
     /// #    attributes: vec![],
+    /// #    raw_text: String::new(),
+    /// #    substitutions: vec![],
     /// #    metadata: vec![],
     /// #    assets: Default::default(),
     /// # };
@@ -181,6 +196,9 @@ impl From<LocalizedLine> for YarnLine {
             id: line.id,
             text: line.text,
             attributes: line.attributes,
+            raw_text: line.raw_text,
+            substitutions: line.substitutions,
+            metadata: line.metadata,
         }
     }
 }
@@ -195,6 +213,8 @@ impl LocalizedLine {
             id: line.id,
             text: line.text,
             attributes: line.attributes,
+            raw_text: line.raw_text,
+            substitutions: line.substitutions,
             metadata,
             assets,
         }
@@ -6,12 +6,15 @@ use crate::prelude::*;
 use anyhow::bail;
 use bevy::asset::LoadedUntypedAsset;
 use bevy::prelude::*;
-use bevy::utils::HashMap;
+use bevy::utils::{HashMap, Instant};
+use yarnspinner::runtime::Command;
 
 pub(crate) fn runtime_interaction_plugin(app: &mut App) {
     app.add_systems(
         Update,
         (
+            apply_language_change_requests,
+            send_language_changed_events,
             continue_runtime
                 .pipe(panic_on_err)
                 .run_if(resource_exists::<YarnProject>),
@@ -28,42 +31,108 @@ pub(crate) fn runtime_interaction_plugin(app: &mut App) {
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, SystemSet)]
 pub(crate) struct DialogueExecutionSystemSet;
 
-fn continue_runtime(
+fn apply_language_change_requests(
+    mut events: EventReader<LanguageChangeRequestedEvent>,
+    mut dialogue_runners: Query<(Entity, &mut DialogueRunner)>,
+) {
+    for event in events.read() {
+        for (source, mut dialogue_runner) in dialogue_runners.iter_mut() {
+            if event.target.is_some_and(|target| target != source) {
+                continue;
+            }
+            if let Some(language) = event.text_language.clone() {
+                dialogue_runner.set_text_language(language);
+            }
+            if let Some(language) = event.asset_language.clone() {
+                dialogue_runner.set_asset_language(language);
+            }
+        }
+    }
+}
+
+fn send_language_changed_events(
     mut dialogue_runners: Query<(Entity, &mut DialogueRunner)>,
+    mut language_changed_events: EventWriter<LanguageChangedEvent>,
+) {
+    for (source, mut dialogue_runner) in dialogue_runners.iter_mut() {
+        if let Some(language) = dialogue_runner.changed_language.take() {
+            let presentation = dialogue_runner.language_presentation().cloned();
+            language_changed_events.send(LanguageChangedEvent {
+                language,
+                presentation,
+                source,
+            });
+        }
+    }
+}
+
+fn continue_runtime(
+    mut dialogue_runners: Query<(Entity, &mut DialogueRunner, Has<PrioritizedDialogueRunner>)>,
+    mut budget: ResMut<DialogueRunnerBudget>,
+    time: Res<Time>,
     mut present_line_events: EventWriter<PresentLineEvent>,
     mut present_options_events: EventWriter<PresentOptionsEvent>,
     mut execute_command_events: EventWriter<ExecuteCommandEvent>,
+    mut deferred_function_call_events: EventWriter<DeferredFunctionCallEvent>,
     mut node_complete_events: EventWriter<NodeCompleteEvent>,
     mut node_start_events: EventWriter<NodeStartEvent>,
     mut line_hints_events: EventWriter<LineHintsEvent>,
     mut dialogue_complete_events: EventWriter<DialogueCompleteEvent>,
     mut dialogue_start_events: EventWriter<DialogueStartEvent>,
+    mut runtime_error_events: EventWriter<DialogueRuntimeErrorEvent>,
     mut last_options: Local<HashMap<Entity, Vec<DialogueOption>>>,
     loaded_untyped_assets: Res<Assets<LoadedUntypedAsset>>,
     project: Res<YarnProject>,
 ) -> SystemResult {
-    for (source, mut dialogue_runner) in dialogue_runners.iter_mut() {
-        let is_sending_missed_events = !dialogue_runner.unsent_events.is_empty();
-        if !is_sending_missed_events {
-            if dialogue_runner.just_started {
-                dialogue_start_events.send(DialogueStartEvent { source });
-                dialogue_runner.just_started = false;
-            }
-            if !dialogue_runner.is_running {
-                dialogue_runner.will_continue_in_next_update = false;
-                continue;
-            }
+    let mut ready_runners = Vec::new();
+    for (source, mut dialogue_runner, is_prioritized) in dialogue_runners.iter_mut() {
+        // Errors from e.g. selecting an invalid option were already returned to the caller
+        runtime_error_events.send_batch(dialogue_runner.error_reporter.take_events(source));
+        if !dialogue_runner.unsent_events.is_empty() {
+            // Sending missed events is cheap, so it is not subject to the budget
+            ready_runners.push((source, true));
+            continue;
+        }
+        if dialogue_runner.just_started {
+            dialogue_start_events.send(DialogueStartEvent { source });
+            dialogue_runner.just_started = false;
+        }
+        if !dialogue_runner.is_running {
+            dialogue_runner.will_continue_in_next_update = false;
+            continue;
+        }
 
-            if let Some(line_ids) = std::mem::take(&mut dialogue_runner.popped_line_hints) {
-                line_hints_events.send(LineHintsEvent { line_ids, source });
-            }
+        if let Some(line_ids) = std::mem::take(&mut dialogue_runner.popped_line_hints) {
+            line_hints_events.send(LineHintsEvent { line_ids, source });
+        }
 
-            if !(dialogue_runner.will_continue_in_next_update
-                && dialogue_runner.poll_tasks_and_check_if_done()
-                && dialogue_runner.update_line_availability(&loaded_untyped_assets))
-            {
-                continue;
+        if dialogue_runner.will_continue_in_next_update
+            // Continues once `DialogueRunner::complete_function` was called
+            && !dialogue_runner.dialogue.is_waiting_for_deferred_function()
+            && dialogue_runner.poll_tasks_and_check_if_done()
+            && dialogue_runner.update_line_availability(&loaded_untyped_assets)
+        {
+            ready_runners.push((source, is_prioritized));
+        }
+    }
+
+    let start = Instant::now();
+    let mut budgeted_runners_advanced = 0;
+    for (source, is_exempt) in budget.schedule(ready_runners, time.delta()) {
+        if !is_exempt {
+            if !budget.allows(budgeted_runners_advanced, start.elapsed()) {
+                break;
             }
+            budgeted_runners_advanced += 1;
+        }
+        let overdue = budget.mark_advanced(source);
+        let (_, mut dialogue_runner, _) = dialogue_runners
+            .get_mut(source)
+            .expect("Scheduled a dialogue runner that does not exist anymore. This is a bug.");
+        dialogue_runner.overdue += overdue;
+
+        let is_sending_missed_events = !dialogue_runner.unsent_events.is_empty();
+        if !is_sending_missed_events {
             dialogue_runner.will_continue_in_next_update = false;
 
             if dialogue_runner.run_selected_options_as_lines {
@@ -91,7 +160,18 @@ fn continue_runtime(
         let events = if is_sending_missed_events {
             std::mem::take(&mut dialogue_runner.unsent_events)
         } else {
-            dialogue_runner.dialogue.continue_()?
+            match dialogue_runner.dialogue.continue_() {
+                Ok(events) => events,
+                Err(error) => {
+                    runtime_error_events
+                        .send_batch(dialogue_runner.error_reporter.take_events(source));
+                    if project.recoverable_errors {
+                        dialogue_runner.stop();
+                        continue;
+                    }
+                    return Err(error.into());
+                }
+            }
         };
 
         for event in events {
@@ -123,6 +203,33 @@ fn continue_runtime(
                     execute_command_events.send(ExecuteCommandEvent { command, source });
                     dialogue_runner.continue_in_next_update();
                 }
+                DialogueEvent::Wait(duration) => {
+                    // Delivered as the `wait` command, whose built-in implementation counts down with the frame clock,
+                    // so that games can still replace it, e.g. to skip waits while fast-forwarding.
+                    // Shortened by the time the runner spent waiting for the budget, so that the waits don't add up to a drift
+                    let overdue = dialogue_runner.overdue.min(duration);
+                    dialogue_runner.overdue -= overdue;
+                    let seconds = (duration - overdue).as_secs_f32();
+                    let command = Command {
+                        name: "wait".to_owned(),
+                        parameters: vec![seconds.into()],
+                        raw: format!("wait {seconds}"),
+                    };
+                    execute_command_events.send(ExecuteCommandEvent { command, source });
+                    dialogue_runner.continue_in_next_update();
+                }
+                DialogueEvent::DeferredFunctionCall(call) => {
+                    if dialogue_runner
+                        .system_functions
+                        .contains_key(call.function_name.as_str())
+                    {
+                        // Called with access to the world by `call_system_functions`
+                        dialogue_runner.system_function_calls.push(call);
+                    } else {
+                        deferred_function_call_events
+                            .send(DeferredFunctionCallEvent { call, source });
+                    }
+                }
                 DialogueEvent::NodeComplete(node_name) => {
                     node_complete_events.send(NodeCompleteEvent { node_name, source });
                 }
@@ -135,6 +242,7 @@ fn continue_runtime(
                 DialogueEvent::DialogueComplete => {
                     if !is_sending_missed_events {
                         dialogue_runner.is_running = false;
+                        dialogue_runner.release_line_hints();
                     }
                     dialogue_complete_events.send(DialogueCompleteEvent { source });
                 }
@@ -0,0 +1,54 @@
+use crate::dialogue_runner::DialogueExecutionSystemSet;
+use crate::events::VariableChangedEvent;
+use crate::prelude::*;
+use bevy::prelude::*;
+use std::sync::{Arc, Mutex};
+use yarnspinner::runtime::NotifyingVariableStorage;
+
+pub(crate) fn variable_changes_plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        send_variable_changed_events
+            .after(DialogueExecutionSystemSet)
+            .in_set(YarnSpinnerSystemSet),
+    );
+}
+
+/// Queues up the variable changes of a [`DialogueRunner`] to be sent as [`VariableChangedEvent`]s.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct VariableChangeQueue(Arc<Mutex<Vec<VariableChangedEvent>>>);
+
+impl VariableChangeQueue {
+    /// Wraps the given storage so that its changes end up in this queue.
+    pub(crate) fn observe(&self, storage: Box<dyn VariableStorage>) -> NotifyingVariableStorage {
+        let storage = NotifyingVariableStorage::new(storage);
+        let queue = self.0.clone();
+        storage.on_change(move |name, value, previous_value| {
+            queue.lock().unwrap().push(VariableChangedEvent {
+                name: name.to_owned(),
+                value: value.clone(),
+                previous_value: previous_value.cloned(),
+                // Not known to the storage, filled in when the event is sent
+                source: Entity::PLACEHOLDER,
+            });
+        });
+        storage
+    }
+
+    /// Drains the queued events, attributing them to the given [`DialogueRunner`].
+    pub(crate) fn take_events(&self, source: Entity) -> Vec<VariableChangedEvent> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+            .into_iter()
+            .map(|event| VariableChangedEvent { source, ..event })
+            .collect()
+    }
+}
+
+fn send_variable_changed_events(
+    dialogue_runners: Query<(Entity, &DialogueRunner)>,
+    mut variable_changed_events: EventWriter<VariableChangedEvent>,
+) {
+    for (source, dialogue_runner) in dialogue_runners.iter() {
+        variable_changed_events.send_batch(dialogue_runner.variable_changes.take_events(source));
+    }
+}
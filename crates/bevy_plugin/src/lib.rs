@@ -105,7 +105,9 @@ mod line_provider;
 mod localization;
 mod plugin;
 mod project;
+mod system_functions;
 mod utils;
+mod variable_watch;
 mod yarn_file_asset;
 pub use anyhow::{Error, Result};
 
@@ -120,10 +122,12 @@ pub mod default_impl {
 }
 
 pub mod events {
-    //! Events that are sent by the [`DialogueRunner`](crate::prelude::DialogueRunner). A dialogue view is expected to at least handle [`PresentLineEvent`] and [`PresentOptionsEvent`].
+    //! Events that are sent by the [`DialogueRunner`](crate::prelude::DialogueRunner), except for [`LanguageChangeRequestedEvent`], which is sent to it. A dialogue view is expected to at least handle [`PresentLineEvent`] and [`PresentOptionsEvent`].
     pub use crate::dialogue_runner::{
-        DialogueCompleteEvent, DialogueStartEvent, ExecuteCommandEvent, LineHintsEvent,
-        NodeCompleteEvent, NodeStartEvent, PresentLineEvent, PresentOptionsEvent,
+        DeferredFunctionCallEvent, DialogueCompleteEvent, DialogueReloadedEvent,
+        DialogueRuntimeErrorEvent, DialogueStartEvent, ExecuteCommandEvent,
+        LanguageChangeRequestedEvent, LanguageChangedEvent, LineHintsEvent, NodeCompleteEvent,
+        NodeStartEvent, PresentLineEvent, PresentOptionsEvent, VariableChangedEvent,
     };
 }
 
@@ -136,11 +140,16 @@ pub mod prelude {
         commands::{YarnCommand, YarnCommands},
         default_impl::FileExtensionAssetProvider,
         development_file_generation::DevelopmentFileGeneration,
-        dialogue_runner::{DialogueOption, DialogueRunner, DialogueRunnerBuilder, LocalizedLine},
+        dialogue_runner::{
+            DialogueOption, DialogueRunner, DialogueRunnerBudget, DialogueRunnerBuilder,
+            LocalizedLine, PrioritizedDialogueRunner,
+        },
         line_provider::{AssetProvider, LineAssets, TextProvider},
-        localization::{Localization, Localizations},
+        localization::{LanguagePresentation, Localization, Localizations, RevealMode},
         plugin::{YarnFileSource, YarnSpinnerPlugin, YarnSpinnerSystemSet},
         project::YarnProject,
+        system_functions::{YarnSystemFunction, YarnSystemFunctionSignature},
+        variable_watch::WatchedVariables,
         yarn_file_asset::YarnFile,
     };
     pub(crate) use crate::{localization::StringsFile, utils::*};
@@ -148,14 +157,18 @@ pub mod prelude {
     pub(crate) use serde::{Deserialize, Serialize};
     pub(crate) use yarnspinner::prelude::*;
     pub use yarnspinner::prelude::{
-        IntoYarnValueFromNonYarnValue, Language, LineId, MarkupAttribute, MarkupValue, OptionId,
+        CommandCompletion, CommandResult, DeferredFunctionCall, DiagnosticVocabulary,
+        FunctionCallToken, IntoYarnValueFromNonYarnValue, Language, LineId, LineInfo,
+        MarkupAttribute, MarkupValue, OptionId, OptionSelection, StringTable, TextSource,
         VariableStorage, YarnFn, YarnLibrary, YarnValue,
     };
+    pub use yarnspinner::runtime::{NotifyingVariableStorage, VariableWatcher, WatchHandle};
     pub(crate) type SystemResult = Result<()>;
 }
 
 pub use crate::commands::{TaskFinishedIndicator, UntypedYarnCommand};
 pub use crate::dialogue_runner::{InnerDialogue, InnerDialogueMut};
+pub use crate::system_functions::YarnSystemFunctionParameters;
 pub use yarnspinner::core::{yarn_fn_type, UntypedYarnFn};
 pub use yarnspinner::prelude::{
     Compilation, StringInfo, TextProvider as UnderlyingTextProvider, YarnAnalysisContext,
@@ -2,7 +2,7 @@
 pub use asset_provider::AudioAssetProvider;
 pub use asset_provider::{file_extensions, AssetProvider, FileExtensionAssetProvider, LineAssets};
 use bevy::prelude::*;
-pub(crate) use text_provider::SharedTextProvider;
+pub(crate) use text_provider::{SharedTextProvider, TextSourceAdapter};
 pub use text_provider::{StringsFileTextProvider, TextProvider};
 
 mod asset_provider;
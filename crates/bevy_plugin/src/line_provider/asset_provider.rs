@@ -144,6 +144,8 @@ pub trait AssetProvider: Debug + Send + Sync {
     ) -> bool;
 
     /// Passes the [`LineId`]s that this [`AssetProvider`] should soon provide assets for. These are the [`LineId`]s that are contained in the current node and are not required to be actually reached.
+    /// Assets for lines that are no longer hinted should be released, which lets Bevy unload them once no one else holds a handle to them.
+    /// When the dialogue stops or completes, this is called with no [`LineId`]s.
     fn accept_line_hints(&mut self, line_ids: &[LineId]);
 
     /// Returns the [`LineAssets`] for the given [`UnderlyingYarnLine`]. Will only be called if [`AssetProvider::update_asset_availability`] returns `true`,
@@ -31,6 +31,12 @@ impl AudioAssetProvider {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Looks for audio files in `"<assets_folder>/<language>"` inside the `assets` folder, e.g. `"voice/en-US/123.ogg"` for the line ID "123".
+    /// See [`FileExtensionAssetProvider::with_assets_folder`].
+    pub fn with_assets_folder(self, assets_folder: impl Into<std::path::PathBuf>) -> Self {
+        Self(self.0.with_assets_folder(assets_folder))
+    }
 }
 
 impl AssetProvider for AudioAssetProvider {
@@ -20,6 +20,10 @@ pub(crate) fn file_extension_asset_provider_plugin(_app: &mut App) {}
 /// Because this requires knowledge of the current language, this provider will only fetch assets if you set up Yarn Spinner with [`Localizations`] using
 /// [`YarnSpinnerPlugin::with_localizations`] or [`LoadYarnProjectEvent::with_localizations`](crate::deferred_loading::LoadYarnProjectEvent::with_localizations).
 ///
+/// If your assets are organized independently of your strings files, [`FileExtensionAssetProvider::with_assets_folder`] makes the provider look for them in
+/// `"<folder>/<language>"` instead.
+/// Lines without an asset on disk simply get no asset.
+///
 /// You can use this provider in a [`DialogueRunner`] by calling [`DialogueRunnerBuilder::add_asset_provider`] with an instance of this type.
 ///
 /// If you want to load audio assets, the feature `audio_assets` will provide you with an [`AudioAssetProvider`] that is a wrapper around this type
@@ -33,6 +37,7 @@ pub struct FileExtensionAssetProvider {
     loaded_handles: HashMap<PathBuf, UntypedHandle>,
     line_ids: HashSet<LineId>,
     file_extensions: HashMap<&'static str, Vec<String>>,
+    assets_folder: Option<PathBuf>,
 }
 
 /// A convenience macro for specifying file extensions used by [`FileExtensionAssetProvider::with_file_extensions`].
@@ -100,6 +105,27 @@ impl FileExtensionAssetProvider {
             }));
         self
     }
+
+    /// Looks for assets in `"<assets_folder>/<language>"` inside the `assets` folder instead of the [`Localization::assets_sub_folder`] of the current language.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_yarnspinner::file_extensions;
+    /// use bevy_yarnspinner::prelude::*;
+    ///
+    /// // For the language "en-US" and the line ID "123", this looks for "assets/voice/en-US/123.ogg"
+    /// let voiceover_provider = FileExtensionAssetProvider::new()
+    ///     .with_file_extensions(file_extensions! {
+    ///        AudioSource: ["ogg"],
+    ///     })
+    ///     .with_assets_folder("voice");
+    /// ```
+    pub fn with_assets_folder(mut self, assets_folder: impl Into<PathBuf>) -> Self {
+        self.assets_folder = Some(assets_folder.into());
+        self
+    }
 }
 
 impl AssetProvider for FileExtensionAssetProvider {
@@ -180,59 +206,74 @@ impl AssetProvider for FileExtensionAssetProvider {
     }
 
     fn get_assets(&self, line: &UnderlyingYarnLine) -> LineAssets {
-        if let Some(language) = self.language.as_ref() {
-            if let Some(localizations) = self.localizations.as_ref() {
-                if let Some(localization) = localizations.supported_localization(language) {
-                    let dir = localization.assets_sub_folder.as_path();
-                    let file_name_without_extension = line.id.0.trim_start_matches("line:");
-                    let assets = self
-                        .file_extensions
-                        .iter()
-                        .filter_map(|(type_id, exts)| {
-                            exts.iter().find_map(|ext| {
-                                let file_name = format!("{}.{}", file_name_without_extension, ext);
-                                let path = dir.join(file_name);
-                                self.loaded_handles
-                                    .get(&path)
-                                    .map(|handle| (*type_id, handle.clone()))
-                            })
-                        })
-                        .collect::<HashSet<_>>();
-                    return LineAssets::with_assets(assets);
-                } else {
-                    panic!("Tried to find an asset for \"{language}\", which is a language that is not supported by localizations");
-                }
-            }
-        }
-        default()
+        let Some(dir) = self.language_assets_folder() else {
+            return default();
+        };
+        let file_name_without_extension = line.id.0.trim_start_matches("line:");
+        let assets = self
+            .file_extensions
+            .iter()
+            .filter_map(|(type_id, exts)| {
+                exts.iter().find_map(|ext| {
+                    let file_name = format!("{}.{}", file_name_without_extension, ext);
+                    let path = dir.join(file_name);
+                    self.loaded_handles
+                        .get(&path)
+                        .map(|handle| (*type_id, handle.clone()))
+                })
+            })
+            .collect::<HashSet<_>>();
+        LineAssets::with_assets(assets)
     }
 }
 
 impl FileExtensionAssetProvider {
+    /// Returns the folder containing the assets for the current language, or [`None`] if the language is not known yet.
+    fn language_assets_folder(&self) -> Option<PathBuf> {
+        let language = self.language.as_ref()?;
+        let localizations = self.localizations.as_ref()?;
+        let Some(localization) = localizations.supported_localization(language) else {
+            panic!("Tried to find an asset for \"{language}\", which is a language that is not supported by localizations");
+        };
+        let dir = match self.assets_folder.as_ref() {
+            Some(assets_folder) => assets_folder.join(language.to_string()),
+            None => localization.assets_sub_folder.clone(),
+        };
+        Some(dir)
+    }
+
     fn reload_assets(&mut self) {
-        if let Some(language) = self.language.as_ref() {
-            if let Some(localizations) = self.localizations.as_ref() {
-                if let Some(localization) = localizations.supported_localization(language) {
-                    let dir = localization.assets_sub_folder.as_path();
-                    self.loading_handles.clear();
-                    self.loaded_handles.clear();
-                    let Some(asset_server) = self.asset_server.as_ref() else {
-                        return;
-                    };
-                    for line_id in self.line_ids.iter() {
-                        for extension in self.file_extensions.values().flatten() {
-                            let file_name =
-                                format!("{}.{extension}", line_id.0.trim_start_matches("line:"));
-                            let path = dir.join(file_name);
-                            let asset_path = path.to_string_lossy().replace('\\', "/");
-                            let handle = asset_server.load_untyped(asset_path);
-                            self.loading_handles.insert(path, handle);
-                        }
-                    }
-                } else {
-                    panic!("Tried to find an asset for \"{language}\", which is a language that is not supported by localizations");
-                }
+        let Some(dir) = self.language_assets_folder() else {
+            return;
+        };
+        let Some(asset_server) = self.asset_server.as_ref() else {
+            self.loading_handles.clear();
+            self.loaded_handles.clear();
+            return;
+        };
+        let dir = dir.as_path();
+        let paths: HashSet<_> = self
+            .line_ids
+            .iter()
+            .flat_map(|line_id| {
+                let file_name = line_id.0.trim_start_matches("line:");
+                self.file_extensions
+                    .values()
+                    .flatten()
+                    .map(move |extension| dir.join(format!("{file_name}.{extension}")))
+            })
+            .collect();
+        // Dropping the handles of lines that are no longer hinted lets Bevy unload their assets,
+        // while assets of lines that are still hinted are kept instead of being loaded again.
+        self.loading_handles.retain(|path, _| paths.contains(path));
+        self.loaded_handles.retain(|path, _| paths.contains(path));
+        for path in paths {
+            if self.loading_handles.contains_key(&path) || self.loaded_handles.contains_key(&path) {
+                continue;
             }
+            let asset_path = path.to_string_lossy().replace('\\', "/");
+            let handle = asset_server.load_untyped(asset_path);
+            self.loading_handles.insert(path, handle);
         }
     }
 }
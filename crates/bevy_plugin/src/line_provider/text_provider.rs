@@ -6,13 +6,16 @@ pub(crate) use shared_text_provider::SharedTextProvider;
 use std::any::Any;
 use std::collections::HashMap;
 pub use strings_file_text_provider::StringsFileTextProvider;
+pub(crate) use text_source_adapter::TextSourceAdapter;
 
 mod shared_text_provider;
 mod strings_file_text_provider;
+mod text_source_adapter;
 
 pub(crate) fn text_provider_plugin(app: &mut App) {
     app.add_plugins(shared_text_provider::shared_text_provider_plugin)
         .add_plugins(strings_file_text_provider::strings_file_text_provider_plugin)
+        .add_plugins(text_source_adapter::text_source_adapter_plugin)
         .add_systems(
             Update,
             fetch_resources
@@ -24,6 +27,9 @@ pub(crate) fn text_provider_plugin(app: &mut App) {
 /// Trait for the provider the [`DialogueRunner`]s text. By default, this is a [`StringsFileTextProvider`].
 /// You can override this with [`DialogueRunnerBuilder::with_text_provider`] if you want a custom localization strategy.
 /// For most users however, the default is fine.
+///
+/// This trait is tied to the internals of the plugin. If you want to ship a text provider in a crate of its own,
+/// implement the minimal [`TextSource`] instead and pass it to [`DialogueRunnerBuilder::with_text_source`].
 pub trait TextProvider: UnderlyingTextProvider {
     /// Stores a string table containing the base language strings, i.e. the strings found in the Yarn files themselves.
     fn set_base_string_table(&mut self, string_table: HashMap<LineId, StringInfo>);
@@ -58,6 +58,17 @@ impl UnderlyingTextProvider for SharedTextProvider {
         self.0.read().unwrap().get_text(id)
     }
 
+    fn get_text_with_substitutions(
+        &self,
+        id: &LineId,
+        substitutions: &[YarnValue],
+    ) -> Option<String> {
+        self.0
+            .read()
+            .unwrap()
+            .get_text_with_substitutions(id, substitutions)
+    }
+
     fn set_language(&mut self, language: Option<Language>) {
         self.0.write().unwrap().set_language(language)
     }
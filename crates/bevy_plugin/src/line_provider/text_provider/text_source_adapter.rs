@@ -0,0 +1,112 @@
+use crate::prelude::*;
+use crate::UnderlyingTextProvider;
+use bevy::prelude::*;
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, RwLock};
+
+pub(crate) fn text_source_adapter_plugin(_app: &mut App) {}
+
+/// A [`TextProvider`] that drives any [`TextSource`] through the Bevy-specific lifecycle of the plugin,
+/// so that implementors of [`TextSource`] never need to know about it. Shallow clones share the same [`TextSource`].
+#[derive(Debug)]
+pub(crate) struct TextSourceAdapter<T: TextSource>(Arc<RwLock<AdaptedTextSource<T>>>);
+
+#[derive(Debug)]
+struct AdaptedTextSource<T> {
+    source: T,
+    base_string_table: StringTable,
+}
+
+impl<T: TextSource> TextSourceAdapter<T> {
+    pub(crate) fn new(source: T) -> Self {
+        Self(Arc::new(RwLock::new(AdaptedTextSource {
+            source,
+            base_string_table: default(),
+        })))
+    }
+}
+
+impl<T: TextSource> Clone for TextSourceAdapter<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T: TextSource> TextProvider for TextSourceAdapter<T> {
+    fn set_base_string_table(&mut self, string_table: HashMap<LineId, StringInfo>) {
+        let mut adapted = self.0.write().unwrap();
+        adapted.base_string_table = to_text_table(string_table);
+        let base_string_table = adapted.base_string_table.clone();
+        adapted.source.set_base_language(base_string_table);
+    }
+
+    fn extend_base_string_table(&mut self, string_table: HashMap<LineId, StringInfo>) {
+        let mut adapted = self.0.write().unwrap();
+        adapted
+            .base_string_table
+            .extend(to_text_table(string_table));
+        let base_string_table = adapted.base_string_table.clone();
+        adapted.source.set_base_language(base_string_table);
+    }
+
+    fn take_fetched_assets(&mut self, _asset: Box<dyn Any>) {}
+
+    fn fetch_assets(&self, _world: &World) -> Option<Box<dyn Any + 'static>> {
+        None
+    }
+}
+
+impl<T: TextSource> UnderlyingTextProvider for TextSourceAdapter<T> {
+    fn clone_shallow(&self) -> Box<dyn UnderlyingTextProvider> {
+        Box::new(self.clone())
+    }
+
+    fn accept_line_hints(&mut self, line_ids: &[LineId]) {
+        self.0.write().unwrap().source.accept_line_hints(line_ids)
+    }
+
+    fn get_text(&self, id: &LineId) -> Option<String> {
+        self.0.read().unwrap().source.get_text(id)
+    }
+
+    fn get_text_with_substitutions(
+        &self,
+        id: &LineId,
+        substitutions: &[YarnValue],
+    ) -> Option<String> {
+        self.0
+            .read()
+            .unwrap()
+            .source
+            .get_text_with_substitutions(id, substitutions)
+    }
+
+    fn set_language(&mut self, language: Option<Language>) {
+        self.0.write().unwrap().source.set_language(language)
+    }
+
+    fn get_language(&self) -> Option<Language> {
+        self.0.read().unwrap().source.get_language()
+    }
+
+    fn are_lines_available(&self) -> bool {
+        self.0.read().unwrap().source.are_lines_available()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+fn to_text_table(string_table: HashMap<LineId, StringInfo>) -> StringTable {
+    string_table
+        .into_iter()
+        .map(|(id, string_info)| (id, string_info.text))
+        .collect()
+}
@@ -1,10 +1,11 @@
-pub use self::localizations::*;
+pub use self::{language_presentation::*, localizations::*};
 pub(crate) use self::{
     line_id_generation::LineIdUpdateSystemSet,
     strings_file::UpdateAllStringsFilesForStringTableEvent, strings_file::*,
 };
 use bevy::prelude::*;
 
+mod language_presentation;
 mod line_id_generation;
 mod localizations;
 mod strings_file;
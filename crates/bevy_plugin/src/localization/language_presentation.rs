@@ -0,0 +1,157 @@
+use crate::prelude::*;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Presentation hints for a [`Localization`], such as which fonts to use or how to reveal text.
+/// Dialogue views can read these for the current language via [`DialogueRunner::language_presentation`]
+/// or react to changes via the [`LanguageChangedEvent`](crate::events::LanguageChangedEvent).
+///
+/// ## Example
+///
+/// ```rust
+/// # use bevy_yarnspinner::prelude::*;
+/// let localization = Localization::with_language("ja-JP").with_presentation(
+///     LanguagePresentation::default()
+///         .with_font_path("fonts/NotoSansJP-Regular.otf")
+///         .with_line_height_multiplier(1.4)
+///         .with_reveal_mode(RevealMode::PerGrapheme),
+/// );
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LanguagePresentation {
+    /// The paths to the fonts for this language inside the `assets` folder, in order of preference.
+    pub font_paths: Vec<PathBuf>,
+    /// The factor by which the line height of the dialogue view should be scaled. Defaults to `1.0`.
+    pub line_height_multiplier: f32,
+    /// How text should be revealed when typing it out. Defaults to [`RevealMode::PerCharacter`].
+    pub reveal_mode: RevealMode,
+    /// Game-specific extras that are not interpreted by Yarn Spinner.
+    pub extras: BTreeMap<String, String>,
+}
+
+impl Default for LanguagePresentation {
+    fn default() -> Self {
+        Self {
+            font_paths: Vec::new(),
+            line_height_multiplier: 1.0,
+            reveal_mode: RevealMode::default(),
+            extras: BTreeMap::new(),
+        }
+    }
+}
+
+// Needed so that `Localization` stays hashable. The line height multiplier is compared by its bits, so that equality is reflexive and agrees with `Hash`.
+impl PartialEq for LanguagePresentation {
+    fn eq(&self, other: &Self) -> bool {
+        self.font_paths == other.font_paths
+            && self.line_height_multiplier.to_bits() == other.line_height_multiplier.to_bits()
+            && self.reveal_mode == other.reveal_mode
+            && self.extras == other.extras
+    }
+}
+
+impl Eq for LanguagePresentation {}
+
+impl Hash for LanguagePresentation {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.font_paths.hash(state);
+        self.line_height_multiplier.to_bits().hash(state);
+        self.reveal_mode.hash(state);
+        self.extras.hash(state);
+    }
+}
+
+impl LanguagePresentation {
+    /// Adds a path to a font for this language inside the `assets` folder.
+    pub fn with_font_path(mut self, font_path: impl Into<PathBuf>) -> Self {
+        self.font_paths.push(font_path.into());
+        self
+    }
+
+    /// Sets the factor by which the line height of the dialogue view should be scaled.
+    pub fn with_line_height_multiplier(mut self, line_height_multiplier: f32) -> Self {
+        self.line_height_multiplier = line_height_multiplier;
+        self
+    }
+
+    /// Sets how text should be revealed when typing it out.
+    pub fn with_reveal_mode(mut self, reveal_mode: RevealMode) -> Self {
+        self.reveal_mode = reveal_mode;
+        self
+    }
+
+    /// Adds a game-specific extra that is not interpreted by Yarn Spinner.
+    pub fn with_extra(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extras.insert(key.into(), value.into());
+        self
+    }
+
+    /// Returns the game-specific extra for the given key, if any.
+    #[must_use]
+    pub fn extra(&self, key: &str) -> Option<&str> {
+        self.extras.get(key).map(String::as_str)
+    }
+}
+
+/// How a dialogue view should reveal text when typing it out. See [`RevealMode::reveal_steps`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RevealMode {
+    /// Reveals one `char` at a time.
+    #[default]
+    PerCharacter,
+    /// Reveals one grapheme cluster at a time, so that e.g. combining characters are never shown without their base character.
+    PerGrapheme,
+    /// Reveals one word at a time.
+    PerWord,
+    /// Reveals the whole text at once.
+    Instant,
+}
+
+impl RevealMode {
+    /// Returns the visible part of the text after each step of typing it out.
+    /// The last step is always the whole text, unless the text is empty.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use bevy_yarnspinner::prelude::*;
+    /// // "e" followed by a combining acute accent
+    /// let text = "Cafe\u{301}";
+    /// assert_eq!(5, RevealMode::PerCharacter.reveal_steps(text).len());
+    /// assert_eq!(
+    ///     vec!["C", "Ca", "Caf", "Cafe\u{301}"],
+    ///     RevealMode::PerGrapheme.reveal_steps(text)
+    /// );
+    /// ```
+    #[must_use]
+    pub fn reveal_steps(self, text: &str) -> Vec<&str> {
+        let mut ends: Vec<usize> = match self {
+            Self::PerCharacter => text
+                .char_indices()
+                .map(|(index, char)| index + char.len_utf8())
+                .collect(),
+            Self::PerGrapheme => text
+                .grapheme_indices(true)
+                .map(|(index, grapheme)| index + grapheme.len())
+                .collect(),
+            Self::PerWord => text
+                .split_word_bound_indices()
+                .filter(|(_, word)| !word.trim().is_empty())
+                .map(|(index, word)| index + word.len())
+                .collect(),
+            Self::Instant => vec![text.len()],
+        };
+        // Trailing whitespace is not a word of its own
+        match ends.last_mut() {
+            Some(last) => *last = text.len(),
+            None => ends.push(text.len()),
+        }
+        ends.into_iter()
+            .filter(|&end| end > 0)
+            .map(|end| &text[..end])
+            .collect()
+    }
+}
@@ -16,9 +16,10 @@ pub(crate) fn line_id_generation_plugin(app: &mut App) {
             handle_yarn_file_events
                 .pipe(panic_on_err)
                 .run_if(in_development.and_then(has_localizations)),
-            handle_yarn_file_events_outside_development.run_if(
+            handle_yarn_file_events_without_localizations.run_if(
                 resource_exists::<YarnProject>
-                    .and_then(not(in_development.and_then(has_localizations))),
+                    .and_then(in_development)
+                    .and_then(not(has_localizations)),
             ),
         )
             .chain()
@@ -27,7 +28,7 @@ pub(crate) fn line_id_generation_plugin(app: &mut App) {
     );
 }
 
-fn handle_yarn_file_events_outside_development(
+fn handle_yarn_file_events_without_localizations(
     mut events: EventReader<AssetEvent<YarnFile>>,
     yarn_files_being_loaded: Res<YarnFilesBeingLoaded>,
     project: Res<YarnProject>,
@@ -74,7 +75,9 @@ fn handle_yarn_file_events(
         if !yarn_files_being_loaded.0.contains(&handle)
             && !project
                 .as_ref()
-                .map(|p| p.yarn_files.contains(&handle))
+                .map(|p| {
+                    p.yarn_files.contains(&handle) || p.yarn_files_being_added.contains(&handle)
+                })
                 .unwrap_or_default()
         {
             continue;
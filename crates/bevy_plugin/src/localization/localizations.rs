@@ -49,6 +49,11 @@ impl Localizations {
             .find(|localization| localization.language == *language)
     }
 
+    /// Returns the [`LanguagePresentation`] of the given language, if it is supported and has one.
+    pub fn presentation(&self, language: &Language) -> Option<&LanguagePresentation> {
+        self.supported_localization(language)?.presentation.as_ref()
+    }
+
     /// Iterates over all supported languages, including the base language.
     pub fn supported_languages(&self) -> impl Iterator<Item = &Language> {
         iter::once(&self.base_localization.language).chain(
@@ -84,6 +89,9 @@ pub struct Localization {
     /// The path to the subdirectory containing the assets for this localization inside the `assets` folder.
     /// Defaults to `dialogue/{language}/`.  So, for the language "de-CH", you'd end up with "assets/dialogue/de-CH/".
     pub assets_sub_folder: PathBuf,
+    /// Hints for how dialogue views should present text in this language, such as fonts or how to reveal text.
+    #[serde(default)]
+    pub presentation: Option<LanguagePresentation>,
 }
 
 impl<T> From<T> for Localization
@@ -111,6 +119,7 @@ impl Localization {
             language,
             strings_file,
             assets_sub_folder,
+            presentation: None,
         }
     }
 
@@ -125,4 +134,10 @@ impl Localization {
         self.assets_sub_folder = assets_sub_folder.into();
         self
     }
+
+    /// Sets the hints for how dialogue views should present text in this localization.
+    pub fn with_presentation(mut self, presentation: LanguagePresentation) -> Self {
+        self.presentation = Some(presentation);
+        self
+    }
 }
@@ -6,10 +6,10 @@ use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
 use bevy::prelude::*;
 use bevy::reflect::TypePath;
 use bevy::utils::HashMap;
-use sha2::{Digest, Sha256};
 use std::fs;
 use std::fs::File;
 use std::path::Path;
+use yarnspinner::compiler::{compute_lock, NEEDS_UPDATE_PREFIX};
 
 pub(crate) fn strings_file_asset_plugin(app: &mut App) {
     app.init_asset::<StringsFile>()
@@ -31,10 +31,7 @@ impl AssetLoader for StringsFileAssetLoader {
     ) -> Result<Self::Asset, Self::Error> {
         let mut bytes = Vec::new();
         reader.read_to_end(&mut bytes).await?;
-        let mut csv_reader = csv::Reader::from_reader(bytes.as_slice());
-        let records: csv::Result<Vec<_>> = csv_reader.deserialize().collect();
-        let strings_file = StringsFile::new_with_single_language(records?)?;
-        Ok(strings_file)
+        StringsFile::from_csv(&bytes)
     }
 
     fn extensions(&self) -> &[&str] {
@@ -47,6 +44,14 @@ impl AssetLoader for StringsFileAssetLoader {
 pub(crate) struct StringsFile(HashMap<LineId, StringsFileRecord>);
 
 impl StringsFile {
+    /// Parses a strings file as written by [`StringsFile::write_asset`] or edited by translators.
+    /// A leading BOM, CRLF line endings and additional columns are accepted.
+    pub(crate) fn from_csv(bytes: &[u8]) -> Result<Self> {
+        let mut csv_reader = csv::Reader::from_reader(bytes);
+        let records: csv::Result<Vec<_>> = csv_reader.deserialize().collect();
+        Self::new_with_single_language(records?)
+    }
+
     pub(crate) fn new_with_single_language(records: Vec<StringsFileRecord>) -> Result<Self> {
         if let Some(language) = records.first().map(|record| &record.language) {
             for record in records.iter().skip(1) {
@@ -101,10 +106,10 @@ impl StringsFile {
                 let text_is_copied_from_base_language =
                     Lock::compute_from(&record.text) == record.lock;
                 let text = if record.lock != other_record.lock
-                    && !record.text.starts_with(UPDATE_PREFIX)
+                    && !record.text.starts_with(NEEDS_UPDATE_PREFIX)
                     && !text_is_copied_from_base_language
                 {
-                    format!("{UPDATE_PREFIX}{}", &record.text)
+                    format!("{NEEDS_UPDATE_PREFIX}{}", &record.text)
                 } else if !text_is_copied_from_base_language {
                     // not `other_record` because that one might not contain (NEEDS UPDATE)
                     record.text.clone()
@@ -220,7 +225,6 @@ fn records_equal_except_for_text(lhs: &StringsFileRecord, rhs: &StringsFileRecor
         && lhs.lock == rhs.lock
         && lhs.comment == rhs.comment
 }
-const UPDATE_PREFIX: &str = "(NEEDS UPDATE) ";
 
 fn combine_comments(full_old_comment: &str, new_metadata: &str) -> String {
     let translator_comment = extract_translator_comment(full_old_comment);
@@ -288,11 +292,7 @@ pub(crate) struct Lock(String);
 impl Lock {
     /// Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner-Unity/blob/462c735766a4c4881cd1ef1f15de28c83b2ba0a8/Editor/Importers/YarnImporter.cs#L149>
     pub(crate) fn compute_from(text: &str) -> Self {
-        const MAX_CHARS: usize = 8;
-        let hash = Sha256::digest(text);
-        let hex = format!("{hash:x}");
-        let lock = hex.chars().take(MAX_CHARS).collect();
-        Self(lock)
+        Self(compute_lock(text))
     }
 }
 
@@ -323,6 +323,18 @@ fn read_comments(metadata: impl IntoIterator<Item = String>) -> String {
 mod test {
     use super::*;
 
+    #[test]
+    fn parses_csv_with_bom_crlf_and_extra_columns() {
+        let csv = "\u{feff}language,id,text,file,node,line_number,lock,comment,translator\r\n\
+                   de-CH,line:1,\"Hallo, Welt\",a.yarn,Start,3,abcdef12,,Anna\r\n";
+        let strings_file = StringsFile::from_csv(csv.as_bytes()).unwrap();
+        let record = &strings_file.0[&LineId("line:1".to_owned())];
+        assert_eq!(Language::from("de-CH"), record.language);
+        assert_eq!("Hallo, Welt", record.text);
+        assert_eq!(3, record.line_number);
+        assert_eq!("", record.comment);
+    }
+
     #[test]
     fn combines_comments_without_change() {
         let old = "Foo, Line metadata: Bar";
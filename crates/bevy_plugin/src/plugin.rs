@@ -155,6 +155,42 @@ impl YarnSpinnerPlugin {
             .with_development_file_generation(development_file_generation);
         self
     }
+
+    /// Sets the [`DiagnosticVocabulary`] used to reword the errors reported when compiling the Yarn files,
+    /// e.g. [`DiagnosticVocabulary::plain_english`] for writers who are not programmers.
+    /// By default, the built-in messages are used.
+    #[must_use]
+    pub fn with_diagnostic_vocabulary(
+        mut self,
+        diagnostic_vocabulary: impl Into<Option<DiagnosticVocabulary>>,
+    ) -> Self {
+        self.project = self
+            .project
+            .with_diagnostic_vocabulary(diagnostic_vocabulary);
+        self
+    }
+
+    /// Sets whether errors returned by the dialogue while it runs, e.g. calling a function that is not registered in the [`YarnLibrary`],
+    /// are recoverable. If they are, the affected [`DialogueRunner`] is stopped and the error is only sent as a [`DialogueRuntimeErrorEvent`],
+    /// after which the dialogue can be started again. Otherwise, the error causes a panic.
+    /// Defaults to `false`.
+    #[must_use]
+    pub fn with_recoverable_errors(mut self, recoverable_errors: bool) -> Self {
+        self.project = self.project.with_recoverable_errors(recoverable_errors);
+        self
+    }
+
+    /// Adds the functions of the given [`YarnLibrary`] to the ones the Yarn files are compiled against, so that calls to them are type checked.
+    /// Call this multiple times to pass the union of the functions of all [`DialogueRunner`]s,
+    /// e.g. the ones added with [`DialogueRunnerBuilder::with_function`], whose signatures are given by [`YarnSystemFunctionSignature`].
+    /// Functions that are not passed here are still allowed, but their types are inferred from how they are called.
+    ///
+    /// This only affects compilation. A [`DialogueRunner`] still needs to be given the functions it calls, e.g. with [`DialogueRunnerBuilder::with_library`].
+    #[must_use]
+    pub fn add_library(mut self, library: YarnLibrary) -> Self {
+        self.project = self.project.add_library(library);
+        self
+    }
 }
 
 impl Plugin for YarnSpinnerPlugin {
@@ -232,6 +268,7 @@ impl YarnApp for App {
             .add_plugins(crate::line_provider::line_provider_plugin)
             .add_plugins(crate::project::project_plugin)
             .add_plugins(crate::commands::commands_plugin)
+            .add_plugins(crate::system_functions::system_functions_plugin)
             .add_plugins(crate::development_file_generation::development_file_generation_plugin)
     }
 
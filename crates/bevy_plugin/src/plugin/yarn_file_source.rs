@@ -6,6 +6,7 @@ use bevy::{prelude::*, reflect::TypePath};
 #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
 use glob::glob;
 use std::path::PathBuf;
+use yarnspinner::core::fnv1a_hash;
 
 /// Possible sources to load a [`YarnFile`] from.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, TypePath)]
@@ -42,6 +43,15 @@ impl YarnFileSource {
         Self::File(path.into())
     }
 
+    /// Convenience function to create a [`YarnFileSource::InMemory`] from Yarn source code, e.g. generated dialogue.
+    /// The file is named after a hash of its content, so the name used in diagnostics and implicit line IDs stays the same across runs.
+    /// Use [`YarnFile::new`] instead to give the file a name of your own.
+    pub fn in_memory(content: impl Into<String>) -> Self {
+        let content = content.into();
+        let file_name = format!("in_memory_{:016x}.yarn", fnv1a_hash(content.bytes()));
+        Self::InMemory(YarnFile::new(file_name, content))
+    }
+
     /// Convenience function to create a [`YarnFileSource::folder`] from a path.
     /// Panics on Wasm and Android because Bevy cannot load folders on these platforms.
     pub fn folder(path: impl Into<PathBuf>) -> Self {
@@ -33,12 +33,19 @@ pub(crate) struct CompilationSystemSet;
 #[derive(Resource, Debug)]
 pub struct YarnProject {
     pub(crate) yarn_files: HashSet<Handle<YarnFile>>,
+    /// Sources passed to [`YarnProject::add_yarn_source`] that have not been loaded yet.
+    pub(crate) yarn_sources_to_add: Vec<YarnFileSource>,
+    /// Files of [`YarnProject::yarn_sources_to_add`] that are still loading. They join the [`YarnProject::yarn_files`] once all of them are loaded.
+    pub(crate) yarn_files_being_added: HashSet<Handle<YarnFile>>,
     pub(crate) compilation: Compilation,
     pub(crate) localizations: Option<Localizations>,
     pub(crate) asset_server: SkipDebug<AssetServer>,
     pub(crate) metadata: HashMap<LineId, Vec<String>>,
     pub(crate) watching_for_changes: bool,
     pub(crate) development_file_generation: DevelopmentFileGeneration,
+    pub(crate) diagnostic_vocabulary: Option<DiagnosticVocabulary>,
+    pub(crate) recoverable_errors: bool,
+    pub(crate) library: YarnLibrary,
 }
 
 impl YarnProject {
@@ -47,6 +54,23 @@ impl YarnProject {
         self.yarn_files.iter()
     }
 
+    /// Adds a Yarn file source to the project after it has been compiled, e.g. the dialogue of a DLC.
+    /// Once all of its files are loaded, the project is recompiled and all [`DialogueRunner`]s switch to the new program.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use bevy::prelude::*;
+    /// # use bevy_yarnspinner::prelude::*;
+    /// fn add_generated_dialogue(mut project: ResMut<YarnProject>) {
+    ///     project.add_yarn_source(YarnFileSource::in_memory("title: Generated\n---\nHi!\n===\n"));
+    /// }
+    /// ```
+    pub fn add_yarn_source(&mut self, yarn_file_source: impl Into<YarnFileSource>) -> &mut Self {
+        self.yarn_sources_to_add.push(yarn_file_source.into());
+        self
+    }
+
     /// Returns the underlying [`Compilation`] of this project. This is advanced functionality.
     pub fn compilation(&self) -> &Compilation {
         &self.compilation
@@ -68,6 +92,17 @@ impl YarnProject {
         DialogueRunnerBuilder::from_yarn_project(self)
     }
 
+    /// Returns whether errors returned by the dialogue while it runs stop the affected [`DialogueRunner`] instead of panicking.
+    /// See [`YarnSpinnerPlugin::with_recoverable_errors`].
+    pub fn recoverable_errors(&self) -> bool {
+        self.recoverable_errors
+    }
+
+    /// Returns the functions the Yarn files were compiled against. See [`YarnSpinnerPlugin::add_library`].
+    pub fn library(&self) -> &YarnLibrary {
+        &self.library
+    }
+
     /// Returns the metadata associated with the given [`LineId`], if any. This can also be accessed on a given [`LocalizedLine`] via its `metadata` field.
     pub fn line_metadata(&self, line_id: &LineId) -> Option<&[String]> {
         self.metadata.get(line_id).map(|v| v.as_slice())
@@ -100,6 +135,9 @@ pub struct LoadYarnProjectEvent {
     pub(crate) localizations: Option<Localizations>,
     pub(crate) yarn_files: HashSet<YarnFileSource>,
     pub(crate) development_file_generation: DevelopmentFileGeneration,
+    pub(crate) diagnostic_vocabulary: Option<DiagnosticVocabulary>,
+    pub(crate) recoverable_errors: bool,
+    pub(crate) library: YarnLibrary,
 }
 
 impl Default for LoadYarnProjectEvent {
@@ -108,6 +146,9 @@ impl Default for LoadYarnProjectEvent {
             localizations: None,
             yarn_files: HashSet::from([YarnFileSource::Folder(DEFAULT_ASSET_DIR.into())]),
             development_file_generation: default(),
+            diagnostic_vocabulary: None,
+            recoverable_errors: false,
+            library: YarnLibrary::new(),
         }
     }
 }
@@ -144,6 +185,9 @@ impl LoadYarnProjectEvent {
             localizations: None,
             yarn_files,
             development_file_generation: default(),
+            diagnostic_vocabulary: None,
+            recoverable_errors: false,
+            library: YarnLibrary::new(),
         }
     }
 
@@ -192,6 +236,30 @@ impl LoadYarnProjectEvent {
         }
         self
     }
+
+    /// See [`YarnSpinnerPlugin::with_diagnostic_vocabulary`].
+    #[must_use]
+    pub fn with_diagnostic_vocabulary(
+        mut self,
+        diagnostic_vocabulary: impl Into<Option<DiagnosticVocabulary>>,
+    ) -> Self {
+        self.diagnostic_vocabulary = diagnostic_vocabulary.into();
+        self
+    }
+
+    /// See [`YarnSpinnerPlugin::with_recoverable_errors`].
+    #[must_use]
+    pub fn with_recoverable_errors(mut self, recoverable_errors: bool) -> Self {
+        self.recoverable_errors = recoverable_errors;
+        self
+    }
+
+    /// See [`YarnSpinnerPlugin::add_library`].
+    #[must_use]
+    pub fn add_library(mut self, library: YarnLibrary) -> Self {
+        self.library.import(library);
+        self
+    }
 }
 
 impl<T, U> From<T> for LoadYarnProjectEvent
@@ -1,3 +1,5 @@
+use crate::dialogue_runner::retain_selected_nodes;
+use crate::events::DialogueReloadedEvent;
 use crate::fmt_utils::SkipDebug;
 use crate::localization::{LineIdUpdateSystemSet, UpdateAllStringsFilesForStringTableEvent};
 use crate::plugin::AssetRoot;
@@ -23,6 +25,9 @@ pub(crate) fn project_compilation_plugin(app: &mut App) {
                 compile_loaded_yarn_files
                     .pipe(panic_on_err)
                     .run_if(resource_exists::<YarnFilesToLoad>),
+                add_yarn_sources_to_project
+                    .pipe(panic_on_err)
+                    .run_if(resource_exists::<YarnProject>),
                 recompile_loaded_yarn_files
                     .map(error)
                     .run_if(events_in_queue::<RecompileLoadedYarnFilesEvent>()),
@@ -40,6 +45,9 @@ pub(crate) struct YarnProjectConfigToLoad {
     pub(crate) localizations: Option<Option<Localizations>>,
     pub(crate) watching_for_changes: bool,
     pub(crate) development_file_generation: DevelopmentFileGeneration,
+    pub(crate) diagnostic_vocabulary: Option<DiagnosticVocabulary>,
+    pub(crate) recoverable_errors: bool,
+    pub(crate) library: YarnLibrary,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default, Resource, Reflect)]
@@ -75,6 +83,9 @@ fn load_project(
             localizations: Some(event.localizations),
             watching_for_changes: is_watching_for_changes.0,
             development_file_generation: event.development_file_generation,
+            diagnostic_vocabulary: event.diagnostic_vocabulary,
+            recoverable_errors: event.recoverable_errors,
+            library: event.library,
         });
         commands.insert_resource(YarnFilesToLoad(event.yarn_files));
         *already_loaded = true;
@@ -108,11 +119,41 @@ fn add_yarn_files_to_load_queue(
 #[reflect(Debug, Default, PartialEq)]
 pub(crate) struct RecompileLoadedYarnFilesEvent;
 
+fn add_yarn_sources_to_project(
+    mut yarn_project: ResMut<YarnProject>,
+    mut assets: ResMut<Assets<YarnFile>>,
+    asset_server: Res<AssetServer>,
+    asset_root: Res<AssetRoot>,
+    mut recompile_events: EventWriter<RecompileLoadedYarnFilesEvent>,
+) -> SystemResult {
+    // Checked through a shared reference first so that the project is not marked as changed every frame
+    if yarn_project.yarn_sources_to_add.is_empty() && yarn_project.yarn_files_being_added.is_empty()
+    {
+        return Ok(());
+    }
+    for source in std::mem::take(&mut yarn_project.yarn_sources_to_add) {
+        let handles = source.load(&asset_server, &mut assets, &asset_root)?;
+        yarn_project.yarn_files_being_added.extend(handles);
+    }
+    let all_files_finished_loading = yarn_project
+        .yarn_files_being_added
+        .iter()
+        .all(|handle| assets.contains(handle));
+    if !all_files_finished_loading {
+        return Ok(());
+    }
+    let yarn_files = std::mem::take(&mut yarn_project.yarn_files_being_added);
+    yarn_project.yarn_files.extend(yarn_files);
+    recompile_events.send(RecompileLoadedYarnFilesEvent);
+    Ok(())
+}
+
 fn recompile_loaded_yarn_files(
     yarn_files: Res<Assets<YarnFile>>,
     yarn_project: Option<ResMut<YarnProject>>,
-    mut dialogue_runners: Query<&mut DialogueRunner>,
+    mut dialogue_runners: Query<(Entity, &mut DialogueRunner)>,
     mut events: ResMut<Events<RecompileLoadedYarnFilesEvent>>,
+    mut reloaded_events: EventWriter<DialogueReloadedEvent>,
 ) -> SystemResult {
     let Some(mut yarn_project) = yarn_project else {
         return Ok(());
@@ -122,6 +163,8 @@ fn recompile_loaded_yarn_files(
         &yarn_files,
         yarn_project.localizations.as_ref(),
         yarn_project.development_file_generation,
+        yarn_project.diagnostic_vocabulary.as_ref(),
+        &yarn_project.library,
     )?
     else {
         return Ok(());
@@ -134,21 +177,31 @@ fn recompile_loaded_yarn_files(
     yarn_project.compilation = compilation;
     yarn_project.metadata = metadata;
     let program = yarn_project.compilation.program.clone().unwrap();
-    for mut dialogue_runner in dialogue_runners.iter_mut() {
+    for (source, mut dialogue_runner) in dialogue_runners.iter_mut() {
         let current_node = dialogue_runner.current_node();
-        dialogue_runner.dialogue.replace_program(program.clone());
         dialogue_runner
             .text_provider
             .set_base_string_table(yarn_project.compilation.string_table.clone());
-        if let Some(current_node) = current_node {
-            dialogue_runner
-                .stop()
-                .try_start_node(current_node)
-                .map(|_| ())
-                .ok()
-                .unwrap_or_else(|| {
-                    dialogue_runner.start_node("Start");
-                });
+        let mut program = program.clone();
+        if let Some(selected_nodes) = dialogue_runner.selected_nodes.as_ref() {
+            retain_selected_nodes(&mut program, selected_nodes);
+        }
+        let position_kept = dialogue_runner.dialogue.reload_program(program);
+        let Some(current_node) = current_node else {
+            continue;
+        };
+        if position_kept {
+            continue;
+        }
+        // The events of the old program are stale, including the completion of its dialogue
+        dialogue_runner.stop_silently();
+        // If the node was removed, the runner stays stopped. The error was passed to its error reporter,
+        // which sends it as a `DialogueRuntimeErrorEvent`.
+        if dialogue_runner.try_start_node(&current_node).is_ok() {
+            reloaded_events.send(DialogueReloadedEvent {
+                node_name: current_node,
+                source,
+            });
         }
     }
     events.clear();
@@ -195,6 +248,8 @@ fn compile_loaded_yarn_files(
         &yarn_files,
         localizations,
         development_file_generation,
+        yarn_project_config_to_load.diagnostic_vocabulary.as_ref(),
+        &yarn_project_config_to_load.library,
     )?
     else {
         return Ok(());
@@ -236,11 +291,16 @@ fn compile_loaded_yarn_files(
         .collect();
     commands.insert_resource(YarnProject {
         yarn_files: std::mem::take(&mut yarn_files_being_loaded.0),
+        yarn_sources_to_add: default(),
+        yarn_files_being_added: default(),
         compilation,
         localizations: yarn_project_config_to_load.localizations.clone().unwrap(),
         asset_server: SkipDebug(asset_server.clone()),
         watching_for_changes: yarn_project_config_to_load.watching_for_changes,
         development_file_generation,
+        diagnostic_vocabulary: yarn_project_config_to_load.diagnostic_vocabulary.clone(),
+        recoverable_errors: yarn_project_config_to_load.recoverable_errors,
+        library: yarn_project_config_to_load.library.clone(),
         metadata,
     });
 
@@ -261,6 +321,8 @@ fn compile_yarn_files(
     yarn_files: &Res<Assets<YarnFile>>,
     localizations: Option<&Localizations>,
     development_file_generation: DevelopmentFileGeneration,
+    diagnostic_vocabulary: Option<&DiagnosticVocabulary>,
+    library: &YarnLibrary,
 ) -> Result<Option<Compilation>> {
     let yarn_files = yarn_file_handles
         .iter()
@@ -284,6 +346,17 @@ fn compile_yarn_files(
         }
     }
     let inner_yarn_files = yarn_files.map(|file| file.file.clone());
-    let compilation = YarnCompiler::new().add_files(inner_yarn_files).compile()?;
+    let compilation = YarnCompiler::new()
+        .add_files(inner_yarn_files)
+        .extend_library(library.clone())
+        .compile();
+    let Some(vocabulary) = diagnostic_vocabulary else {
+        return Ok(Some(compilation?));
+    };
+    let mut compilation = compilation.map_err(|error| error.reworded(vocabulary))?;
+    compilation.warnings = std::mem::take(&mut compilation.warnings)
+        .into_iter()
+        .map(|warning| vocabulary.reword(warning))
+        .collect();
     Ok(Some(compilation))
 }
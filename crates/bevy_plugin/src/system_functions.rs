@@ -0,0 +1,311 @@
+use crate::dialogue_runner::DialogueExecutionSystemSet;
+use crate::prelude::*;
+use bevy::ecs::system::{ReadOnlySystemParam, SystemParamItem, SystemState};
+use bevy::prelude::*;
+use bevy::utils::all_tuples;
+use std::any::TypeId;
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+use yarnspinner::core::{YarnFnParam, YarnFnParamItem, YarnValueWrapper};
+
+pub(crate) fn system_functions_plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        call_system_functions
+            .after(DialogueExecutionSystemSet)
+            .in_set(YarnSpinnerSystemSet),
+    );
+}
+
+/// A method that can be registered as a Yarn function of a single [`DialogueRunner`] via [`DialogueRunnerBuilder::with_function`].
+/// In contrast to a [`YarnFn`], it is a Bevy system that can read from the world when it is called.
+///
+/// The signature of the method must adhere to the following rules:
+/// The first parameter must be of the type `In<T>`, where `T` holds the parameters passed to the function from Yarn.
+/// Multiple parameters are supported as values wrapped in a tuple, e.g. `In<(String, f32)>`. Use `In<()>` for a function without parameters.
+/// Parameters must be owned, i.e. [`String`] instead of `&str`.
+/// The parameters following the `In` parameter are taken from the Bevy ECS as any other system would, but only read-only ones such as [`Res`] and [`Query`] with immutable references are allowed.
+/// The return type must be a valid return type for a [`YarnFn`].
+///
+/// ## Example
+///
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_yarnspinner::prelude::*;
+/// #[derive(Component)]
+/// struct Player;
+///
+/// fn distance_to_player(
+///     In(x): In<f32>,
+///     players: Query<&Transform, With<Player>>,
+/// ) -> f32 {
+///     players
+///         .get_single()
+///         .map_or(f32::INFINITY, |transform| (transform.translation.x - x).abs())
+/// }
+/// ```
+/// This function can be called from Yarn with `{distance_to_player(10)}`.
+///
+/// Under the hood, the function is registered as a deferred function (see [`YarnLibrary::add_deferred_function`]),
+/// so the dialogue pauses until it has been called by the [`YarnSpinnerPlugin`] with access to the world, which resumes the dialogue in the next update.
+/// As with all deferred functions, it cannot be called from the expression of a smart variable.
+pub trait YarnSystemFunction<Marker>: Send + Sync + 'static + Clone {
+    /// The input type used to determine the parameters passed to the function from Yarn. A tuple of values will be interpreted as multiple parameters.
+    type In: YarnFnParam + YarnSystemFunctionParameters;
+    /// The return type of the function.
+    type Out: IntoYarnValueFromNonYarnValue + Clone + 'static;
+    /// The parameters passed to the function from the Bevy ECS.
+    type Param: ReadOnlySystemParam;
+
+    #[doc(hidden)]
+    fn run(
+        &mut self,
+        input: YarnFnParamItem<Self::In>,
+        param_value: SystemParamItem<Self::Param>,
+    ) -> Self::Out;
+}
+
+macro_rules! impl_system_function {
+    ($($param: ident),*) => {
+        #[allow(non_snake_case)]
+        impl<Input, Func: Send + Sync + 'static, Output, $($param: ReadOnlySystemParam),*> YarnSystemFunction<fn(In<Input>, $($param,)*) -> Output> for Func
+        where
+            Input: YarnFnParam + YarnSystemFunctionParameters,
+            Output: IntoYarnValueFromNonYarnValue + Clone + 'static,
+            Func: Clone,
+        for <'a> &'a mut Func:
+            FnMut(In<Input>, $($param), *) -> Output +
+            FnMut(In<Input>, $(SystemParamItem<$param>),*) -> Output +
+            FnMut(In<YarnFnParamItem<Input>>, $($param), *) -> Output +
+            FnMut(In<YarnFnParamItem<Input>>, $(SystemParamItem<$param>),*) -> Output
+        {
+            type In = Input;
+            type Out = Output;
+            type Param = ($($param,)*);
+            #[inline]
+            fn run(&mut self, input: YarnFnParamItem<Input>, param_value: SystemParamItem< ($($param,)*)>) -> Self::Out {
+                #[allow(clippy::too_many_arguments)]
+                fn call_inner<Input: YarnFnParam, Output, $($param,)*>(
+                    mut f: impl FnMut(In<YarnFnParamItem<Input>>, $($param,)*) -> Output,
+                    input: In<YarnFnParamItem<Input>>,
+                    $($param: $param,)*
+                ) -> Output {
+                    f(input, $($param,)*)
+                }
+                let ($($param,)*) = param_value;
+                call_inner(self, In(input), $($param),*)
+            }
+        }
+    };
+}
+
+all_tuples!(impl_system_function, 0, 16, F);
+
+/// Implemented by the types that can be used as the `In` parameter of a [`YarnSystemFunction`].
+/// Provides the types of the parameters so that calls to the function can be type checked.
+pub trait YarnSystemFunctionParameters {
+    /// The [`TypeId`]s of the parameters, with tuples flattened.
+    fn parameter_types() -> Vec<TypeId>;
+}
+
+macro_rules! impl_system_function_parameters {
+    ($($param: ty),*) => {
+        $(
+            impl YarnSystemFunctionParameters for $param {
+                fn parameter_types() -> Vec<TypeId> {
+                    vec![TypeId::of::<$param>()]
+                }
+            }
+        )*
+    };
+}
+
+impl_system_function_parameters!(
+    String, YarnValue, bool, f32, f64, i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, usize,
+    isize
+);
+
+macro_rules! impl_system_function_parameters_tuple {
+    ($($param: ident),*) => {
+        impl<$($param: YarnSystemFunctionParameters),*> YarnSystemFunctionParameters for ($($param,)*) {
+            fn parameter_types() -> Vec<TypeId> {
+                let parameter_types: Vec<Vec<TypeId>> = vec![$($param::parameter_types()),*];
+                parameter_types.concat()
+            }
+        }
+    };
+}
+
+all_tuples!(impl_system_function_parameters_tuple, 0, 16, P);
+
+/// The signature of a [`YarnSystemFunction`], which can be added to a [`YarnLibrary`] with [`YarnLibrary::add_deferred_function`].
+/// This is done for you by [`DialogueRunnerBuilder::with_function`]. Do it yourself to declare the function to the compiler
+/// with [`YarnSpinnerPlugin::add_library`] so that calls to it are type checked.
+///
+/// ## Example
+///
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_yarnspinner::prelude::*;
+/// fn gold(_: In<()>, time: Res<Time>) -> f32 {
+///     time.elapsed_seconds().floor()
+/// }
+///
+/// let mut library = YarnLibrary::new();
+/// library.add_deferred_function("gold", YarnSystemFunctionSignature::of(&gold));
+/// let plugin = YarnSpinnerPlugin::new().add_library(library);
+/// ```
+pub struct YarnSystemFunctionSignature<In, Out> {
+    // NOTE: PhantomData<fn()-> T> gives this safe Send/Sync impls
+    _marker: PhantomData<fn(In) -> Out>,
+}
+
+impl<In, Out> YarnSystemFunctionSignature<In, Out> {
+    /// Returns the signature of the given function.
+    pub fn of<Marker, F>(_function: &F) -> Self
+    where
+        F: YarnSystemFunction<Marker, In = In, Out = Out>,
+    {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<In, Out> Clone for YarnSystemFunctionSignature<In, Out> {
+    fn clone(&self) -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<In, Out> Debug for YarnSystemFunctionSignature<In, Out> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let signature = std::any::type_name::<fn(In) -> Out>();
+        f.debug_struct(&format!("YarnSystemFunctionSignature<{signature}>"))
+            .finish()
+    }
+}
+
+impl<In, Out> YarnFn<Self> for YarnSystemFunctionSignature<In, Out>
+where
+    In: YarnSystemFunctionParameters + 'static,
+    Out: IntoYarnValueFromNonYarnValue + 'static,
+{
+    type Out = Out;
+
+    fn call(&self, _input: Vec<YarnValue>) -> Self::Out {
+        unreachable!("System functions are deferred and called by the YarnSpinnerPlugin")
+    }
+
+    fn parameter_types(&self) -> Vec<TypeId> {
+        In::parameter_types()
+    }
+}
+
+/// A type-erased [`YarnSystemFunction`] as it is stored in a [`DialogueRunner`].
+pub(crate) trait UntypedYarnSystemFunction: Debug + Send + Sync + 'static {
+    fn call(&mut self, input: Vec<YarnValue>, world: &mut World) -> YarnValue;
+    fn clone_box(&self) -> Box<dyn UntypedYarnSystemFunction>;
+}
+
+impl<Marker, F> UntypedYarnSystemFunction for YarnSystemFunctionWrapper<Marker, F>
+where
+    Marker: 'static,
+    F: YarnSystemFunction<Marker>,
+{
+    fn call(&mut self, input: Vec<YarnValue>, world: &mut World) -> YarnValue {
+        let mut system_state: SystemState<F::Param> = SystemState::new(world);
+        let param = system_state.get(world);
+        let mut input: Vec<_> = input.into_iter().map(YarnValueWrapper::from).collect();
+        let mut iter = input.iter_mut().peekable();
+        let input = F::In::retrieve(&mut iter);
+        assert!(
+            iter.next().is_none(),
+            "Passed too many arguments to system function"
+        );
+        YarnSystemFunction::run(&mut self.function, input, param).into_yarn_value()
+    }
+
+    fn clone_box(&self) -> Box<dyn UntypedYarnSystemFunction> {
+        Box::new(self.clone())
+    }
+}
+
+pub(crate) struct YarnSystemFunctionWrapper<Marker, F>
+where
+    F: YarnSystemFunction<Marker>,
+{
+    function: F,
+
+    // NOTE: PhantomData<fn()-> T> gives this safe Send/Sync impls
+    _marker: PhantomData<fn() -> Marker>,
+}
+
+impl<Marker, F> Clone for YarnSystemFunctionWrapper<Marker, F>
+where
+    F: YarnSystemFunction<Marker>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            function: self.function.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<Marker, F> From<F> for YarnSystemFunctionWrapper<Marker, F>
+where
+    F: YarnSystemFunction<Marker>,
+{
+    fn from(function: F) -> Self {
+        Self {
+            function,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<Marker, F> Debug for YarnSystemFunctionWrapper<Marker, F>
+where
+    F: YarnSystemFunction<Marker>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let signature = std::any::type_name::<Marker>();
+        let function_path = std::any::type_name::<F>();
+        let debug_message = format!("{signature} {{{function_path}}}");
+        f.debug_struct(&debug_message).finish()
+    }
+}
+
+fn call_system_functions(world: &mut World) {
+    let mut dialogue_runners = world.query::<(Entity, &mut DialogueRunner)>();
+    let mut calls = Vec::new();
+    for (source, mut dialogue_runner) in dialogue_runners.iter_mut(world) {
+        if dialogue_runner.system_function_calls.is_empty() {
+            continue;
+        }
+        for call in std::mem::take(&mut dialogue_runner.system_function_calls) {
+            let function =
+                dialogue_runner.system_functions[call.function_name.as_str()].clone_box();
+            calls.push((source, function, call));
+        }
+    }
+    for (source, mut function, call) in calls {
+        let value = function.call(call.parameters, world);
+        let (_, mut dialogue_runner) = dialogue_runners.get_mut(world, source).unwrap();
+        // The dialogue might have been stopped by a command in the meantime
+        if !dialogue_runner.is_running() {
+            continue;
+        }
+        dialogue_runner
+            .complete_function(call.token, value)
+            .unwrap_or_else(|error| {
+                panic!(
+                    "Failed to complete call to system function \"{}\": {error}",
+                    call.function_name
+                )
+            });
+    }
+}
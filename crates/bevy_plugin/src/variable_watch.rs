@@ -0,0 +1,37 @@
+use bevy::prelude::*;
+use yarnspinner::runtime::{VariableWatcher, WatchHandle};
+
+/// A [`Resource`] for watching Yarn variables from a system, e.g. to display them in a debug overlay.
+/// Dereferences to a [`WatchHandle`], so the changes can be drained once per update with [`WatchHandle::take_changes`].
+///
+/// ## Example
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy_yarnspinner::{default_impl::MemoryVariableStorage, prelude::*};
+///
+/// fn spawn_dialogue_runner(mut commands: Commands, project: Res<YarnProject>) {
+///     let watcher = VariableWatcher::new(Box::new(MemoryVariableStorage::new()));
+///     commands.insert_resource(WatchedVariables::new(&watcher, &["$quest_*"]));
+///     let dialogue_runner = project
+///         .build_dialogue_runner()
+///         .with_variable_storage(Box::new(watcher))
+///         .build();
+///     commands.spawn(dialogue_runner);
+/// }
+///
+/// fn update_overlay(watched_variables: Res<WatchedVariables>) {
+///     for (name, value) in watched_variables.take_changes() {
+///         info!("{name} = {value}");
+///     }
+/// }
+/// ```
+#[derive(Debug, Resource, Deref)]
+pub struct WatchedVariables(WatchHandle);
+
+impl WatchedVariables {
+    /// Subscribes to the variables of `watcher` matching any of the `patterns`. See [`VariableWatcher::subscribe`].
+    pub fn new(watcher: &VariableWatcher, patterns: &[&str]) -> Self {
+        Self(watcher.subscribe(patterns))
+    }
+}
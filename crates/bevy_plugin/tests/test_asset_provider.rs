@@ -197,3 +197,80 @@ fn does_not_load_asset_with_invalid_type() -> Result<()> {
     assert!(asset.is_none());
     Ok(())
 }
+
+#[test]
+fn preloads_assets_of_hinted_lines_and_releases_them_on_stop() -> Result<()> {
+    let mut app = App::new();
+
+    app.setup_default_plugins().add_plugins(
+        YarnSpinnerPlugin::with_yarn_source(YarnFileSource::file("lines_with_ids.yarn"))
+            .with_localizations(Localizations {
+                base_localization: "en-US".into(),
+                translations: vec![],
+            })
+            .with_development_file_generation(DevelopmentFileGeneration::None),
+    );
+
+    let project = app.load_project();
+    let mut dialogue_runner = project
+        .build_dialogue_runner()
+        .add_asset_provider(AudioAssetProvider::new())
+        .build();
+    dialogue_runner
+        .set_asset_language("en-US")
+        .start_node("Start");
+    app.world_mut().spawn(dialogue_runner);
+    app.load_lines();
+
+    // Only the first line was presented, but line 9 was hinted as part of the node
+    let assets = app.dialogue_runner().get_assets_for_id("line:9");
+    let asset: Handle<AudioSource> = assets.get_handle().unwrap();
+    let asset_server = app.world().resource::<AssetServer>();
+    assert!(asset_server.is_loaded_with_dependencies(asset.id()));
+    drop(asset);
+
+    app.dialogue_runner_mut().stop();
+    app.update();
+
+    let assets = app.dialogue_runner().get_assets_for_id("line:9");
+    assert!(assets.is_empty());
+    Ok(())
+}
+
+#[test]
+fn loads_assets_from_custom_folder_by_line_id() -> Result<()> {
+    let mut app = App::new();
+
+    app.setup_default_plugins().add_plugins(
+        YarnSpinnerPlugin::with_yarn_source(YarnFileSource::file("lines_with_ids.yarn"))
+            .with_localizations(Localizations {
+                base_localization: Localization::with_language("en-US")
+                    .with_assets_sub_folder("does-not-exist"),
+                translations: vec![
+                    Localization::with_language("de-CH").with_assets_sub_folder("does-not-exist")
+                ],
+            })
+            .with_development_file_generation(DevelopmentFileGeneration::None),
+    );
+
+    let project = app.load_project();
+    let mut dialogue_runner = project
+        .build_dialogue_runner()
+        .add_asset_provider(AudioAssetProvider::new().with_assets_folder("dialogue"))
+        .build();
+    dialogue_runner
+        .set_asset_language("de-CH")
+        .start_node("Start");
+    app.world_mut().spawn(dialogue_runner);
+    app.load_lines();
+
+    let assets = app.dialogue_runner().get_assets_for_id("line:10");
+    let asset: Handle<AudioSource> = assets.get_handle().unwrap();
+    let asset_server = app.world().resource::<AssetServer>();
+    let path = asset_server.get_path(asset.id()).unwrap();
+    assert_eq!("dialogue/de-CH/10.ogg", path.path().to_str().unwrap());
+
+    // There is no "dialogue/de-CH/9.ogg", so the line just has no asset
+    assert!(app.dialogue_runner().get_assets_for_id("line:9").is_empty());
+    Ok(())
+}
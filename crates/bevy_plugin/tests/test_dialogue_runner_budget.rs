@@ -0,0 +1,129 @@
+use anyhow::Result;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy::utils::Duration;
+use bevy_yarnspinner::{events::*, prelude::*};
+use utils::prelude::*;
+
+mod utils;
+
+#[test]
+fn drains_ready_runners_within_budget() -> Result<()> {
+    let mut app = App::new();
+    app.setup_default_plugins()
+        .add_plugins(YarnSpinnerPlugin::with_yarn_source(YarnFileSource::file(
+            "lines.yarn",
+        )))
+        .insert_resource(DialogueRunnerBudget::default().with_max_runners_per_update(10));
+    let project = app.load_project();
+    let runners: Vec<_> = (0..100)
+        .map(|_| {
+            let mut dialogue_runner = project.create_dialogue_runner();
+            dialogue_runner.start_node("Start");
+            dialogue_runner
+        })
+        .collect();
+    app.world_mut().spawn_batch(runners);
+    app.update();
+    assert_eq!(
+        10,
+        app.world()
+            .resource::<DialogueRunnerBudget>()
+            .runners_advanced()
+    );
+    assert_eq!(
+        90,
+        app.world().resource::<DialogueRunnerBudget>().queue_depth()
+    );
+
+    let mut prioritized_runner = app.load_project().create_dialogue_runner();
+    prioritized_runner.start_node("Start");
+    let prioritized_runner = app
+        .world_mut()
+        .spawn((prioritized_runner, PrioritizedDialogueRunner))
+        .id();
+    app.update();
+    let presented_lines = app.world().resource::<Events<PresentLineEvent>>();
+    assert!(presented_lines
+        .iter_current_update_events()
+        .any(|event| event.source == prioritized_runner));
+    let budget = app.world().resource::<DialogueRunnerBudget>();
+    assert_eq!(11, budget.runners_advanced());
+    assert_eq!(80, budget.queue_depth());
+    assert_eq!(app.world().resource::<Time>().delta(), budget.max_overdue());
+
+    for expected_queue_depth in (0..80).step_by(10).rev() {
+        app.update();
+        let budget = app.world().resource::<DialogueRunnerBudget>();
+        assert_eq!(10, budget.runners_advanced());
+        assert_eq!(expected_queue_depth, budget.queue_depth());
+    }
+    app.update();
+    assert_eq!(
+        0,
+        app.world()
+            .resource::<DialogueRunnerBudget>()
+            .runners_advanced()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn subtracts_time_spent_in_queue_from_next_wait() -> Result<()> {
+    const BUSY_RUNNER_COUNT: usize = 2;
+    let on_time = updates_until_waits_are_done(0);
+    let delayed = updates_until_waits_are_done(BUSY_RUNNER_COUNT);
+
+    // Only the delay before the last line remains. Without subtracting the time spent in the queue from the next wait,
+    // the delays before each of the three waits would add up.
+    assert!(
+        delayed - on_time <= BUSY_RUNNER_COUNT,
+        "{delayed} updates instead of {on_time}"
+    );
+
+    Ok(())
+}
+
+/// Runs three waits of one second in a dialogue runner that competes with the given number of other runners for a budget of one runner per update.
+fn updates_until_waits_are_done(busy_runner_count: usize) -> usize {
+    let mut app = App::new();
+    app.setup_default_plugins()
+        .add_plugins(YarnSpinnerPlugin::with_yarn_source(
+            YarnFileSource::in_memory(
+                "title: Waiting\n---\n<<wait 1>>\n<<wait 1>>\n<<wait 1>>\nDone\n===\n\
+                 title: Busy\n---\n<<work>>\n<<jump Busy>>\n===\n",
+            ),
+        ))
+        .insert_resource(DialogueRunnerBudget::default().with_max_runners_per_update(1))
+        // Exactly representable as the `f32` seconds of a wait, so that waits end on an update
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            125,
+        )));
+    let project = app.load_project();
+    // Runners that continue after every command are ready in every update, so the waiting runner is queued behind them whenever its wait ends
+    let mut runners: Vec<_> = (0..busy_runner_count)
+        .map(|_| {
+            let mut dialogue_runner = project.create_dialogue_runner();
+            dialogue_runner.start_node("Busy");
+            dialogue_runner
+        })
+        .collect();
+    let mut waiting_runner = project.create_dialogue_runner();
+    waiting_runner.start_node("Waiting");
+    runners.push(waiting_runner);
+    app.world_mut().spawn_batch(runners);
+
+    for updates in 1..100 {
+        app.update();
+        let presented_lines = app.world().resource::<Events<PresentLineEvent>>();
+        if presented_lines
+            .iter_current_update_events()
+            .next()
+            .is_some()
+        {
+            return updates;
+        }
+    }
+    panic!("The waits did not end");
+}
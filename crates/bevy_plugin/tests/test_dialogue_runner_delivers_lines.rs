@@ -215,6 +215,96 @@ fn serves_translations() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn switches_text_language_mid_dialogue() -> Result<()> {
+    let mut app = App::new();
+    let mut asserter = EventAsserter::new();
+    setup_dialogue_runner_with_localizations(&mut app).start_node("Start");
+    app.load_lines().update();
+    assert_events!(asserter, app contains
+        PresentLineEvent with |event| event.line.text == english_lines()[0]
+    );
+
+    app.dialogue_runner_mut()
+        .set_text_language("de-CH")
+        .continue_in_next_update();
+    app.load_lines().update();
+    assert_events!(asserter, app contains
+        PresentLineEvent with |event| event.line.text == german_lines()[1]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn switches_language_on_request_between_lines() -> Result<()> {
+    let mut app = App::new();
+    let mut asserter = EventAsserter::new();
+    setup_dialogue_runner_with_localizations(&mut app).start_node("Start");
+    app.load_lines().update();
+    assert_events!(asserter, app contains
+        PresentLineEvent with |event| event.line.text == english_lines()[0]
+    );
+
+    app.world_mut().send_event(LanguageChangeRequestedEvent {
+        text_language: Some("de-CH".into()),
+        asset_language: None,
+        target: None,
+    });
+    app.dialogue_runner_mut().continue_in_next_update();
+    app.update();
+    assert!(!app
+        .dialogue_runner()
+        .are_lines_available_for(&"de-CH".into()));
+    assert_eq!(
+        Some(Language::from("de-CH")),
+        app.dialogue_runner().text_language()
+    );
+    assert_events!(asserter, app contains PresentLineEvent (n = 0));
+
+    app.load_lines().update();
+    assert!(app
+        .dialogue_runner()
+        .are_lines_available_for(&"de-CH".into()));
+    assert_events!(asserter, app contains
+        PresentLineEvent with |event| event.line.text == german_lines()[1]
+    );
+
+    let source = app.dialogue_runner_entity();
+    app.world_mut().send_event(LanguageChangeRequestedEvent {
+        text_language: Some("en-US".into()),
+        asset_language: None,
+        target: Some(source),
+    });
+    app.continue_dialogue_and_update();
+    assert_events!(asserter, app contains
+        PresentLineEvent with |event| event.line.text == english_lines()[2]
+    );
+    assert!(!app
+        .dialogue_runner()
+        .are_lines_available_for(&"de-CH".into()));
+
+    Ok(())
+}
+
+#[test]
+fn ignores_language_change_requests_for_other_runners() {
+    let mut app = App::new();
+    setup_dialogue_runner_with_localizations(&mut app);
+    let other_runner = app.world_mut().spawn_empty().id();
+    app.world_mut().send_event(LanguageChangeRequestedEvent {
+        text_language: Some("de-CH".into()),
+        asset_language: Some("de-CH".into()),
+        target: Some(other_runner),
+    });
+    app.update();
+
+    assert_eq!(
+        Some(Language::from("en-US")),
+        app.dialogue_runner().text_language()
+    );
+}
+
 #[test]
 fn default_language_is_none_without_localizations() {
     let mut app = App::new();
@@ -306,7 +396,6 @@ fn english_lines() -> Vec<String> {
     lines
 }
 
-#[cfg(feature = "audio_assets")]
 fn german_lines() -> Vec<String> {
     let file = include_str!("../assets/dialogue/de-CH.strings.csv");
     let mut reader = csv::Reader::from_reader(file.as_bytes());
@@ -57,6 +57,48 @@ fn errs_on_unexpected_selection_value() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn sends_runtime_error_event_on_unexpected_selection_value() -> Result<()> {
+    let mut app = App::new();
+    let mut asserter = EventAsserter::new();
+    app.setup_dialogue_runner().start_node("Start");
+    app.continue_dialogue_and_update_n_times(4);
+    app.dialogue_runner_mut()
+        .select_option(OptionId(2))
+        .unwrap_err();
+    app.update();
+
+    assert_events!(asserter, app contains [
+        DialogueRuntimeErrorEvent with |event| event.node.as_deref() == Some("Hub0")
+            && event.message.contains("OptionId(2)")
+            && event.recent_line_ids.len() == 3,
+    ]);
+
+    Ok(())
+}
+
+#[test]
+fn errs_on_stale_option_selection() -> Result<()> {
+    let mut app = App::new();
+    app.setup_dialogue_runner().start_node("Start");
+    app.continue_dialogue_and_update_n_times(4);
+    let stale_option = last_presented_options(&app)[0].clone();
+
+    app.dialogue_runner_mut().stop().start_node("Start");
+    while !app.dialogue_runner().is_waiting_for_option_selection() {
+        app.continue_dialogue_and_update();
+    }
+    app.dialogue_runner_mut()
+        .select_option(&stale_option)
+        .unwrap_err();
+
+    let option = last_presented_options(&app)[0].clone();
+    assert_ne!(stale_option.generation, option.generation);
+    app.dialogue_runner_mut().select_option(&option)?;
+
+    Ok(())
+}
+
 #[test]
 fn option_selection_implies_continue() -> Result<()> {
     let mut app = App::new();
@@ -131,7 +173,7 @@ fn can_jump_around_nodes() -> Result<()> {
 }
 
 #[test]
-fn can_select_unavailable_choice() -> Result<()> {
+fn cannot_select_unavailable_choice() -> Result<()> {
     let mut app = App::new();
     let mut asserter = EventAsserter::new();
     app.setup_dialogue_runner().start_node("Start");
@@ -148,10 +190,14 @@ fn can_select_unavailable_choice() -> Result<()> {
             && event.options.iter().filter(|o| o.is_available).count() == 1
             && event.options.iter().filter(|o| !o.is_available).all(|o| o.id == OptionId(0)),
     ]);
-    app.dialogue_runner_mut().select_option(OptionId(0))?;
+    assert!(app
+        .dialogue_runner_mut()
+        .select_option(OptionId(0))
+        .is_err());
+    app.dialogue_runner_mut().select_option(OptionId(1))?;
     app.update();
     assert_events!(asserter, app contains [
-        PresentLineEvent with |event| event.line.text == lines()[6],
+        PresentLineEvent with |event| event.line.text == lines()[10],
         PresentOptionsEvent (n = 0),
     ]);
 
@@ -205,6 +251,17 @@ impl OptionTestAppExt for App {
     }
 }
 
+fn last_presented_options(app: &App) -> Vec<DialogueOption> {
+    let events = app.world().resource::<Events<PresentOptionsEvent>>();
+    events
+        .get_reader()
+        .read(events)
+        .last()
+        .unwrap()
+        .options
+        .clone()
+}
+
 fn lines() -> Vec<String> {
     let mut lines: Vec<_> = include_str!("../assets/options.yarn")
         .lines()
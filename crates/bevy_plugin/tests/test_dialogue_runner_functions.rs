@@ -0,0 +1,184 @@
+use anyhow::Result;
+use bevy::prelude::*;
+use bevy_yarnspinner::{events::*, prelude::*};
+use utils::prelude::*;
+
+mod utils;
+
+#[test]
+fn calls_system_functions_with_world_access() -> Result<()> {
+    let mut app = App::new();
+    let mut asserter = EventAsserter::new();
+    app.setup_default_plugins()
+        .add_plugins(YarnSpinnerPlugin::with_yarn_source(
+            YarnFileSource::in_memory(
+                "title: Start\n---\nYou have {gold()} gold.\nTwice that is {times_gold(2)}.\n===\n",
+            ),
+        ))
+        .insert_resource(Gold(21.0));
+    let dialogue_runner = app
+        .load_project()
+        .build_dialogue_runner()
+        .with_function("gold", |_: In<()>, gold: Res<Gold>| gold.0)
+        .with_function("times_gold", times_gold)
+        .build();
+    app.world_mut().spawn(dialogue_runner);
+    app.dialogue_runner_mut().start_node("Start");
+
+    // One update for the call and one for resuming the dialogue
+    app.update();
+    assert_events!(asserter, app contains [
+        PresentLineEvent (n = 0),
+    ]);
+    assert!(app
+        .world()
+        .resource::<Events<DeferredFunctionCallEvent>>()
+        .is_empty());
+    app.update();
+    assert_events!(asserter, app contains [
+        PresentLineEvent with |event| event.line.text == "You have 21 gold.",
+    ]);
+
+    app.world_mut().resource_mut::<Gold>().0 = 50.0;
+    app.continue_dialogue_and_update_n_times(2);
+    assert_events!(asserter, app contains [
+        PresentLineEvent with |event| event.line.text == "Twice that is 100.",
+    ]);
+    Ok(())
+}
+
+#[test]
+fn functions_are_scoped_to_their_runner() -> Result<()> {
+    let mut app = App::new();
+    app.setup_default_plugins()
+        .add_plugins(YarnSpinnerPlugin::with_yarn_source(
+            YarnFileSource::in_memory("title: Start\n---\nHello, {name()}!\n===\n"),
+        ));
+    let project = app.load_project();
+    let mut library = YarnLibrary::new();
+    library.add_function("name", || "Ada".to_owned());
+    let first_runner = project
+        .build_dialogue_runner()
+        .with_library(library)
+        .build();
+    let second_runner = project
+        .build_dialogue_runner()
+        .with_function("name", |_: In<()>| "Grace".to_owned())
+        .build();
+    let default_runner = project.create_dialogue_runner();
+
+    assert!(first_runner.library().contains_function("name"));
+    assert!(!first_runner.library().is_deferred("name"));
+    assert!(second_runner.library().is_deferred("name"));
+    assert!(!default_runner.library().contains_function("name"));
+    Ok(())
+}
+
+#[test]
+fn later_library_replaces_system_function() -> Result<()> {
+    let mut app = App::new();
+    let mut asserter = EventAsserter::new();
+    app.setup_default_plugins()
+        .add_plugins(YarnSpinnerPlugin::with_yarn_source(
+            YarnFileSource::in_memory("title: Start\n---\nHello, {name()}!\n===\n"),
+        ));
+    let mut library = YarnLibrary::new();
+    library.add_function("name", || "Ada".to_owned());
+    let dialogue_runner = app
+        .load_project()
+        .build_dialogue_runner()
+        .with_function("name", |_: In<()>| "Grace".to_owned())
+        .with_library(library)
+        .build();
+    app.world_mut().spawn(dialogue_runner);
+    app.dialogue_runner_mut().start_node("Start");
+    app.update();
+    assert_events!(asserter, app contains [
+        PresentLineEvent with |event| event.line.text == "Hello, Ada!",
+    ]);
+    Ok(())
+}
+
+#[test]
+fn selects_nodes() -> Result<()> {
+    let mut app = App::new();
+    app.setup_default_plugins()
+        .add_plugins(YarnSpinnerPlugin::with_yarn_source(
+            YarnFileSource::in_memory(
+                "title: Start\n---\n<<declare $rich = $gold > 100>>\n<<declare $gold = 0>>\n{$rich}\n===\n\
+                 title: Greeting\nwhen: $rich\n---\nHello, rich person!\n===\n\
+                 title: Greeting\nwhen: always\n---\nHello!\n===\n\
+                 title: Other\n---\nOther\n===\n",
+            ),
+        ));
+    let project = app.load_project();
+    let mut dialogue_runner = project
+        .build_dialogue_runner()
+        .with_nodes(["Start", "Greeting"])
+        .build();
+
+    let node_names: Vec<_> = dialogue_runner
+        .inner()
+        .node_names()
+        .map(str::to_owned)
+        .collect();
+    assert!(node_names.contains(&"Start".to_owned()));
+    assert!(node_names.contains(&"Greeting".to_owned()));
+    assert!(!node_names.contains(&"Other".to_owned()));
+    assert!(dialogue_runner.try_start_node("Other").is_err());
+    assert!(dialogue_runner.try_start_node("Greeting").is_ok());
+
+    let error = project
+        .build_dialogue_runner()
+        .with_nodes(["Missing"])
+        .try_build()
+        .unwrap_err();
+    assert!(error.to_string().contains("\"Missing\""));
+    Ok(())
+}
+
+#[test]
+fn compiles_against_added_libraries() -> Result<()> {
+    let mut app = App::new();
+    let mut first_library = YarnLibrary::new();
+    first_library.add_deferred_function("gold", YarnSystemFunctionSignature::of(&gold));
+    let mut second_library = YarnLibrary::new();
+    second_library.add_function("name", || "Ada".to_owned());
+    app.setup_default_plugins().add_plugins(
+        YarnSpinnerPlugin::with_yarn_source(YarnFileSource::in_memory(
+            "title: Start\n---\n{name()} has {gold()} gold.\n===\n",
+        ))
+        .add_library(first_library)
+        .add_library(second_library),
+    );
+    let project = app.load_project();
+    assert!(project.library().is_deferred("gold"));
+    assert!(project.library().contains_function("name"));
+    Ok(())
+}
+
+#[test]
+#[should_panic]
+fn type_checks_calls_to_added_libraries() {
+    let mut app = App::new();
+    let mut library = YarnLibrary::new();
+    library.add_deferred_function("gold", YarnSystemFunctionSignature::of(&gold));
+    app.setup_default_plugins().add_plugins(
+        YarnSpinnerPlugin::with_yarn_source(YarnFileSource::in_memory(
+            "title: Start\n---\n<<if gold() == \"lots\">>\nRich!\n<<endif>>\n===\n",
+        ))
+        .add_library(library),
+    );
+    app.load_project();
+}
+
+#[derive(Debug, Resource)]
+struct Gold(f32);
+
+fn gold(_: In<()>, gold: Res<Gold>) -> f32 {
+    gold.0
+}
+
+fn times_gold(In(factor): In<f32>, gold: Res<Gold>) -> f32 {
+    factor * gold.0
+}
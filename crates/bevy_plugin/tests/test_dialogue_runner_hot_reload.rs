@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+use bevy_yarnspinner::{events::*, prelude::*};
+use utils::prelude::*;
+
+mod utils;
+
+const ORIGINAL: &str =
+    "title: Start\n---\nHello from Start!\n===\ntitle: Second\n---\nHello from Second!\n===\n";
+const WITHOUT_SECOND: &str = "title: Start\n---\nHello again from Start!\n===\n";
+const CHANGED_SECOND: &str = "title: Start\n---\nHello from Start!\n===\n\
+    title: Second\n---\nHello from the new Second!\nHow are you?\n===\n";
+
+#[test]
+fn restarts_current_node_when_it_was_changed() {
+    let mut app = App::new();
+    let mut asserter = EventAsserter::new();
+    setup_app(&mut app, DevelopmentFileGeneration::Full);
+
+    app.dialogue_runner_mut().start_node("Second");
+    app.update();
+    assert_events!(asserter, app contains PresentLineEvent with |event| event.line.text == "Hello from Second!");
+
+    modify_yarn_file(&mut app, CHANGED_SECOND);
+    while !contains_line(&mut app, "Hello from the new Second!") {
+        app.update();
+    }
+    app.update();
+
+    assert_events!(asserter, app contains [
+        DialogueReloadedEvent with |event| event.node_name == "Second",
+        PresentLineEvent with |event| event.line.text == "Hello from the new Second!",
+        DialogueCompleteEvent (n = 0),
+    ]);
+}
+
+#[test]
+fn stops_when_current_node_was_removed() {
+    let mut app = App::new();
+    let mut asserter = EventAsserter::new();
+    setup_app(&mut app, DevelopmentFileGeneration::Full);
+
+    app.dialogue_runner_mut().start_node("Second");
+    app.update();
+    asserter.clear_events(&mut app);
+
+    modify_yarn_file(&mut app, WITHOUT_SECOND);
+    while contains_node(&mut app, "Second") {
+        app.update();
+    }
+    app.update();
+
+    assert!(!app.dialogue_runner().is_running());
+    assert_events!(asserter, app contains [
+        DialogueRuntimeErrorEvent with |event| event.message.contains("Second"),
+        DialogueReloadedEvent (n = 0),
+        DialogueCompleteEvent (n = 0),
+        PresentLineEvent (n = 0),
+    ]);
+}
+
+#[test]
+fn does_not_reload_outside_development() {
+    let mut app = App::new();
+    let mut asserter = EventAsserter::new();
+    setup_app(&mut app, DevelopmentFileGeneration::None);
+
+    app.dialogue_runner_mut().start_node("Second");
+    app.update();
+    asserter.clear_events(&mut app);
+
+    modify_yarn_file(&mut app, WITHOUT_SECOND);
+    for _ in 0..3 {
+        app.update();
+    }
+
+    assert!(contains_node(&mut app, "Second"));
+    assert_events!(asserter, app contains DialogueReloadedEvent (n = 0));
+}
+
+fn setup_app(app: &mut App, development_file_generation: DevelopmentFileGeneration) {
+    app.setup_default_plugins().add_plugins(
+        YarnSpinnerPlugin::with_yarn_source(YarnFileSource::in_memory(ORIGINAL))
+            .with_development_file_generation(development_file_generation),
+    );
+    let _ = app.dialogue_runner_entity();
+}
+
+fn modify_yarn_file(app: &mut App, content: &str) {
+    let handle = app.load_project().yarn_files().next().unwrap().clone();
+    app.world_mut()
+        .resource_mut::<Assets<YarnFile>>()
+        .get_mut(&handle)
+        .unwrap()
+        .set_content(content.to_owned())
+        .unwrap();
+}
+
+fn contains_node(app: &mut App, node_name: &str) -> bool {
+    let program = app.load_project().compilation().program.as_ref().unwrap();
+    program.nodes.contains_key(node_name)
+}
+
+fn contains_line(app: &mut App, text: &str) -> bool {
+    let string_table = &app.load_project().compilation().string_table;
+    string_table
+        .values()
+        .any(|string_info| string_info.text == text)
+}
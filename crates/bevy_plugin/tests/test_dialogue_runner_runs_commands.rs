@@ -2,7 +2,8 @@ use anyhow::Result;
 use bevy::prelude::*;
 use bevy::utils::Instant;
 use bevy_yarnspinner::{events::*, prelude::*};
-use std::thread::sleep;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, sleep};
 use utils::prelude::*;
 
 mod utils;
@@ -43,6 +44,109 @@ fn waits_on_command() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn waits_on_command_completed_from_another_thread() -> Result<()> {
+    let mut app = App::new();
+    let mut asserter = EventAsserter::new();
+    let pending = Arc::new(Mutex::new(None));
+    let mut dialogue_runner = app.setup_dialogue_runner_for_wait();
+    dialogue_runner.commands_mut().add_command("wait", {
+        let pending = pending.clone();
+        move |_: In<f32>| {
+            let completion = CommandCompletion::new();
+            *pending.lock().unwrap() = Some(completion.clone());
+            completion
+        }
+    });
+    dialogue_runner.start_node("Start");
+    app.update();
+    assert_events!(asserter, app contains [
+        PresentLineEvent with |event| event.line.text == "Starting wait",
+    ]);
+    app.continue_dialogue_and_update();
+    assert_events!(asserter, app contains [
+        PresentLineEvent (n = 0),
+        ExecuteCommandEvent (n = 1),
+    ]);
+    for _ in 0..3 {
+        app.continue_dialogue_and_update();
+        assert_events!(asserter, app contains [PresentLineEvent (n = 0)]);
+    }
+
+    let completion = pending.lock().unwrap().take().unwrap();
+    thread::spawn(move || completion.complete()).join().unwrap();
+    app.continue_dialogue_and_update();
+    assert_events!(asserter, app contains [
+        PresentLineEvent with |event| event.line.text == "Ended wait",
+    ]);
+
+    Ok(())
+}
+
+#[test]
+fn continues_after_command_completion_is_dropped() -> Result<()> {
+    let mut app = App::new();
+    let mut asserter = EventAsserter::new();
+    let mut dialogue_runner = app.setup_dialogue_runner_for_wait();
+    dialogue_runner
+        .commands_mut()
+        .add_command("wait", |_: In<f32>| CommandCompletion::new());
+    dialogue_runner.start_node("Start");
+    app.update();
+    assert_events!(asserter, app contains [
+        PresentLineEvent with |event| event.line.text == "Starting wait",
+    ]);
+    app.continue_dialogue_and_update();
+    assert_events!(asserter, app contains [ExecuteCommandEvent (n = 1)]);
+
+    app.continue_dialogue_and_update();
+    assert_events!(asserter, app contains [
+        PresentLineEvent with |event| event.line.text == "Ended wait",
+    ]);
+
+    Ok(())
+}
+
+#[test]
+fn resumes_after_computed_wait_elapses() -> Result<()> {
+    let mut app = App::new();
+    let mut asserter = EventAsserter::new();
+    app.setup_default_plugins()
+        .add_plugins(YarnSpinnerPlugin::with_yarn_source(
+            YarnFileSource::in_memory(
+                "title: Start\n---\n<<declare $delay = 0.25>>\nStarting wait\n<<wait {$delay * 2}>>\nEnded wait\n===\n",
+            ),
+        ))
+        .dialogue_runner_mut()
+        .start_node("Start");
+    app.update();
+    assert_events!(asserter, app contains [
+        PresentLineEvent with |event| event.line.text == "Starting wait",
+    ]);
+    app.continue_dialogue_and_update();
+    assert_events!(asserter, app contains [
+        PresentLineEvent (n = 0),
+        ExecuteCommandEvent with |event|
+            event.command.name == "wait" &&
+            f32::try_from(&event.command.parameters[0]).unwrap() == 0.5,
+    ]);
+    // No need to continue the dialogue, it resumes on its own
+    let now = Instant::now();
+    while now.elapsed().as_millis() <= 450 {
+        app.update();
+        assert_events!(asserter, app contains [
+            PresentLineEvent (n = 0),
+        ]);
+    }
+    sleep(std::time::Duration::from_millis(100));
+    app.update();
+    assert_events!(asserter, app contains [
+        PresentLineEvent with |event| event.line.text == "Ended wait",
+    ]);
+
+    Ok(())
+}
+
 #[test]
 fn executes_commands_and_fns() -> Result<()> {
     let mut app = App::new();
@@ -110,6 +214,52 @@ fn executes_commands_and_fns() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn stops_on_missing_function_with_recoverable_errors() -> Result<()> {
+    let mut app = App::new();
+    let mut asserter = EventAsserter::new();
+    app.setup_default_plugins().add_plugins(
+        YarnSpinnerPlugin::with_yarn_source(YarnFileSource::file("commands.yarn"))
+            .with_recoverable_errors(true),
+    );
+    app.dialogue_runner_mut()
+        .library_mut()
+        .add_function("triplicate_date", |data: &str| data.to_owned());
+    app.dialogue_runner_mut().start_node("Start");
+    app.update();
+    app.continue_dialogue_and_update_n_times(3);
+    assert_events!(asserter, app contains [
+        PresentLineEvent (n = 3),
+        DialogueRuntimeErrorEvent (n = 0),
+    ]);
+
+    app.continue_dialogue_and_update();
+    assert_events!(asserter, app contains [
+        PresentLineEvent (n = 0),
+        DialogueRuntimeErrorEvent with |event| event.node.as_deref() == Some("Start")
+            && event.message.contains("\"triplicate_data\" not found")
+            && event.message.contains("Did you mean \"triplicate_date\"?"),
+    ]);
+    assert!(!app.dialogue_runner().is_running());
+
+    app.update();
+    assert_events!(asserter, app contains [
+        DialogueCompleteEvent,
+    ]);
+
+    app.dialogue_runner_mut()
+        .library_mut()
+        .add_function("triplicate_data", |data: &str| data.repeat(3));
+    app.dialogue_runner_mut().start_node("Start");
+    app.update();
+    assert_events!(asserter, app contains [
+        PresentLineEvent with |event| event.line.text == "Setting variable",
+        DialogueRuntimeErrorEvent (n = 0),
+    ]);
+
+    Ok(())
+}
+
 #[derive(Debug, Resource)]
 struct Data(String);
 
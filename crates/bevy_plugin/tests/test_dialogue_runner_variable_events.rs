@@ -0,0 +1,74 @@
+use anyhow::Result;
+use bevy::prelude::*;
+use bevy_yarnspinner::{events::*, prelude::*};
+use utils::prelude::*;
+
+mod utils;
+
+#[test]
+fn sends_event_when_yarn_script_sets_variable() -> Result<()> {
+    let mut app = App::new();
+    app.setup_default_plugins()
+        .add_plugins(YarnSpinnerPlugin::with_yarn_source(YarnFileSource::file(
+            "variables.yarn",
+        )));
+    let project = app.load_project();
+    let dialogue_runner = project.create_dialogue_runner();
+    let source = app.world_mut().spawn(dialogue_runner).id();
+    let mut reader = app
+        .world()
+        .resource::<Events<VariableChangedEvent>>()
+        .get_reader();
+    app.update();
+    // The declared initial value is written when the program is loaded
+    assert_eq!(
+        vec![VariableChangedEvent {
+            name: "$gold".to_owned(),
+            value: 10.0.into(),
+            previous_value: None,
+            source,
+        }],
+        reader
+            .read(app.world().resource::<Events<VariableChangedEvent>>())
+            .cloned()
+            .collect::<Vec<_>>()
+    );
+
+    app.world_mut()
+        .get_mut::<DialogueRunner>(source)
+        .unwrap()
+        .start_node("Start");
+    app.update();
+    assert_eq!(
+        vec![VariableChangedEvent {
+            name: "$gold".to_owned(),
+            value: 15.0.into(),
+            previous_value: Some(10.0.into()),
+            source,
+        }],
+        reader
+            .read(app.world().resource::<Events<VariableChangedEvent>>())
+            .cloned()
+            .collect::<Vec<_>>()
+    );
+
+    app.world_mut()
+        .get_mut::<DialogueRunner>(source)
+        .unwrap()
+        .variable_storage_mut()
+        .set("$gold".to_owned(), 0.0.into())?;
+    app.update();
+    assert_eq!(
+        vec![VariableChangedEvent {
+            name: "$gold".to_owned(),
+            value: 0.0.into(),
+            previous_value: Some(15.0.into()),
+            source,
+        }],
+        reader
+            .read(app.world().resource::<Events<VariableChangedEvent>>())
+            .cloned()
+            .collect::<Vec<_>>()
+    );
+    Ok(())
+}
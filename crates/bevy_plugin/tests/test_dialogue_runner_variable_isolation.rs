@@ -0,0 +1,75 @@
+use anyhow::Result;
+use bevy::prelude::*;
+use bevy_yarnspinner::{default_impl::MemoryVariableStorage, prelude::*};
+use utils::prelude::*;
+
+mod utils;
+
+#[test]
+fn runners_do_not_share_variables() -> Result<()> {
+    let mut app = App::new();
+    app.setup_default_plugins()
+        .add_plugins(YarnSpinnerPlugin::with_yarn_source(YarnFileSource::file(
+            "variables.yarn",
+        )));
+    let project = app.load_project();
+    let first_runner = project.create_dialogue_runner();
+    let second_runner = project.create_dialogue_runner();
+    let first = app.world_mut().spawn(first_runner).id();
+    let second = app.world_mut().spawn(second_runner).id();
+
+    app.world_mut()
+        .get_mut::<DialogueRunner>(first)
+        .unwrap()
+        .start_node("Start");
+    app.update();
+
+    assert_eq!(15.0, gold(&app, first));
+    assert_eq!(10.0, gold(&app, second));
+    Ok(())
+}
+
+#[test]
+fn isolated_variable_storage_replaces_shared_storage() -> Result<()> {
+    let mut app = App::new();
+    app.setup_default_plugins()
+        .add_plugins(YarnSpinnerPlugin::with_yarn_source(YarnFileSource::file(
+            "variables.yarn",
+        )));
+    let project = app.load_project();
+    let shared_storage = MemoryVariableStorage::new();
+    let first_runner = project
+        .build_dialogue_runner()
+        .with_variable_storage(shared_storage.clone_shallow())
+        .build();
+    let second_runner = project
+        .build_dialogue_runner()
+        .with_variable_storage(shared_storage.clone_shallow())
+        .with_isolated_variable_storage()
+        .build();
+    let first = app.world_mut().spawn(first_runner).id();
+    let second = app.world_mut().spawn(second_runner).id();
+    assert_eq!(10.0, gold(&app, second));
+
+    app.world_mut()
+        .get_mut::<DialogueRunner>(second)
+        .unwrap()
+        .variable_storage_mut()
+        .set("$gold".to_owned(), 100.0_f32.into())?;
+
+    assert_eq!(10.0, gold(&app, first));
+    assert_eq!(YarnValue::from(10.0_f32), shared_storage.get("$gold")?);
+    assert_eq!(100.0, gold(&app, second));
+    Ok(())
+}
+
+fn gold(app: &App, entity: Entity) -> f32 {
+    app.world()
+        .get::<DialogueRunner>(entity)
+        .unwrap()
+        .variable_storage()
+        .get("$gold")
+        .unwrap()
+        .try_into()
+        .unwrap()
+}
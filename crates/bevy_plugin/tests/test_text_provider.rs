@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use bevy_yarnspinner::events::LanguageChangedEvent;
 use bevy_yarnspinner::prelude::*;
 use utils::prelude::*;
 
@@ -153,3 +154,82 @@ fn loads_line_from_translated_language() {
         .unwrap();
     assert_eq!("Mann: Also gut. Ich glaub das zwar nicht, aber es kann ja nicht schaden, wenn ich mir was wünsche. Ich möchte wissen, wer ich bin.", line);
 }
+
+#[test]
+fn announces_presentation_of_changed_language() {
+    let mut app = App::new();
+
+    app.setup_default_plugins().add_plugins(
+        YarnSpinnerPlugin::with_yarn_source(YarnFileSource::file("lines_with_ids.yarn"))
+            .with_localizations(Localizations {
+                base_localization: "en-US".into(),
+                translations: vec![Localization::with_language("de-CH").with_presentation(
+                    LanguagePresentation::default().with_reveal_mode(RevealMode::PerGrapheme),
+                )],
+            })
+            .with_development_file_generation(DevelopmentFileGeneration::None),
+    );
+
+    assert!(app.dialogue_runner().language_presentation().is_none());
+    app.dialogue_runner_mut().set_text_language("de-CH");
+    app.update();
+
+    let events = app.world().resource::<Events<LanguageChangedEvent>>();
+    let event = events.get_reader().read(events).last().unwrap().clone();
+    assert_eq!(Language::from("de-CH"), event.language);
+    let presentation = event.presentation.unwrap();
+    assert_eq!(
+        Some(&presentation),
+        app.dialogue_runner().language_presentation()
+    );
+
+    // "ü" and "é" written as base characters followed by combining diacritics
+    let text = "Gru\u{308}ezi, Cafe\u{301}!";
+    assert_eq!(15, RevealMode::PerCharacter.reveal_steps(text).len());
+    let steps = presentation.reveal_mode.reveal_steps(text);
+    assert_eq!(13, steps.len());
+    assert_eq!("Gru\u{308}", steps[2]);
+    assert_eq!(text, *steps.last().unwrap());
+}
+
+#[test]
+fn localizations_round_trip_through_serde() {
+    let localizations = Localizations {
+        base_localization: "en-US".into(),
+        translations: vec![
+            "de-CH".into(),
+            Localization::with_language("ja-JP").with_presentation(
+                LanguagePresentation::default()
+                    .with_font_path("fonts/NotoSansJP-Regular.otf")
+                    .with_line_height_multiplier(1.4)
+                    .with_reveal_mode(RevealMode::PerGrapheme)
+                    .with_extra("ruby", "enabled"),
+            ),
+        ],
+    };
+
+    let json = serde_json::to_string(&localizations).unwrap();
+    let deserialized: Localizations = serde_json::from_str(&json).unwrap();
+    assert_eq!(localizations, deserialized);
+    assert_eq!(
+        Some("enabled"),
+        deserialized
+            .presentation(&"ja-JP".into())
+            .and_then(|presentation| presentation.extra("ruby"))
+    );
+}
+
+#[test]
+fn presentations_compare_consistently_with_their_hash() {
+    use std::collections::HashSet;
+
+    let presentation = LanguagePresentation::default().with_line_height_multiplier(f32::NAN);
+    assert_eq!(presentation, presentation.clone());
+
+    let zero = LanguagePresentation::default().with_line_height_multiplier(0.0);
+    let negative_zero = LanguagePresentation::default().with_line_height_multiplier(-0.0);
+    let presentations: HashSet<_> = [presentation.clone(), presentation, zero, negative_zero]
+        .into_iter()
+        .collect();
+    assert_eq!(3, presentations.len());
+}
@@ -0,0 +1,86 @@
+use anyhow::Result;
+use bevy::prelude::*;
+use bevy_yarnspinner::{events::*, prelude::*};
+use std::collections::HashMap;
+use utils::prelude::*;
+
+mod utils;
+
+#[test]
+fn runs_conversation_through_external_text_source() -> Result<()> {
+    let mut app = App::new();
+    let mut asserter = EventAsserter::new();
+    setup_dialogue_runner_with_json_text_source(&mut app);
+    let mut line_reader = app
+        .world()
+        .resource::<Events<PresentLineEvent>>()
+        .get_reader();
+    app.dialogue_runner_mut()
+        .set_text_language("de-CH")
+        .start_node("Start");
+    app.load_lines().update();
+
+    let german_lines = german_lines();
+    for line_number in 1..=12 {
+        let events = app.world().resource::<Events<PresentLineEvent>>();
+        let texts: Vec<_> = line_reader
+            .read(events)
+            .map(|event| event.line.text.clone())
+            .collect();
+        // line:10 is not translated, so it falls back to the base language
+        let expected = german_lines
+            .get(&format!("line:{line_number}"))
+            .map_or("Hag: Funny,", String::as_str);
+        assert_eq!(vec![expected.to_owned()], texts);
+        app.continue_dialogue_and_update();
+    }
+    assert_events!(asserter, app contains DialogueCompleteEvent);
+
+    Ok(())
+}
+
+#[test]
+fn external_text_source_receives_base_language() -> Result<()> {
+    let mut app = App::new();
+    let mut asserter = EventAsserter::new();
+    setup_dialogue_runner_with_json_text_source(&mut app).start_node("Start");
+    app.load_lines().update();
+
+    assert_events!(asserter, app contains
+        PresentLineEvent with |event| event.line.text.starts_with("An elderly man was sitting alone on a dark path.")
+    );
+    assert_eq!(
+        "Man: Third wish?",
+        app.dialogue_runner()
+            .text_provider()
+            .get_text(&LineId("line:3".to_owned()))
+            .unwrap()
+    );
+
+    Ok(())
+}
+
+fn setup_dialogue_runner_with_json_text_source(app: &mut App) -> Mut<'_, DialogueRunner> {
+    let dialogue_runner = app
+        .setup_default_plugins()
+        .add_plugins(
+            YarnSpinnerPlugin::with_yarn_source(YarnFileSource::file("lines_with_ids.yarn"))
+                .with_localizations(Localizations {
+                    base_localization: "en-US".into(),
+                    translations: vec!["de-CH".into()],
+                })
+                .with_development_file_generation(DevelopmentFileGeneration::None),
+        )
+        .load_project()
+        .build_dialogue_runner()
+        .with_text_source(JsonTextSource::new(
+            project_root_path().join("assets").join("dialogue"),
+        ))
+        .build();
+    app.world_mut().spawn(dialogue_runner);
+    app.dialogue_runner_mut()
+}
+
+fn german_lines() -> HashMap<String, String> {
+    serde_json::from_str(include_str!("../assets/dialogue/de-CH.json")).unwrap()
+}
@@ -0,0 +1,102 @@
+use anyhow::Result;
+use bevy::prelude::*;
+use bevy_yarnspinner::{events::*, prelude::*};
+use tempfile::tempdir;
+use utils::prelude::*;
+
+mod utils;
+
+const GENERATED: &str = "title: Start\n---\nHello from memory!\n===\n";
+const DLC: &str = "title: Dlc\n---\nHello from the DLC!\n===\n";
+
+#[test]
+fn compiles_purely_from_in_memory_sources_without_asset_folder() -> Result<()> {
+    let dir = tempdir()?;
+    let mut app = App::new();
+    let mut asserter = EventAsserter::new();
+
+    app.setup_default_plugins_for_path(dir.path().join("does_not_exist"))
+        .add_plugins(
+            YarnSpinnerPlugin::with_yarn_sources([
+                YarnFileSource::in_memory(GENERATED),
+                YarnFileSource::in_memory(DLC),
+            ])
+            .with_development_file_generation(DevelopmentFileGeneration::None),
+        );
+
+    let program = app.load_project().compilation().program.as_ref().unwrap();
+    assert!(program.nodes.contains_key("Start"));
+    assert!(program.nodes.contains_key("Dlc"));
+
+    app.dialogue_runner_mut().start_node("Start");
+    app.update();
+    assert_events!(asserter, app contains [
+        PresentLineEvent with |event| event.line.text == "Hello from memory!",
+    ]);
+    Ok(())
+}
+
+#[test]
+fn mixes_asset_files_and_in_memory_sources() {
+    let mut app = App::new();
+
+    app.setup_default_plugins()
+        .add_plugins(YarnSpinnerPlugin::with_yarn_sources([
+            YarnFileSource::file("lines.yarn"),
+            YarnFileSource::in_memory(DLC),
+        ]));
+
+    let project = app.load_project();
+    assert_eq!(2, project.yarn_files().count());
+    let program = project.compilation().program.as_ref().unwrap();
+    assert!(program.nodes.contains_key("Start"));
+    assert!(program.nodes.contains_key("Dlc"));
+}
+
+#[test]
+fn in_memory_sources_have_stable_file_names() {
+    let first = YarnFileSource::in_memory(GENERATED);
+    let second = YarnFileSource::in_memory(GENERATED);
+    assert_eq!(first, second);
+
+    let YarnFileSource::InMemory(yarn_file) = first else {
+        unreachable!();
+    };
+    assert_eq!("in_memory_4c74fed3f10fc96d.yarn", yarn_file.file_name());
+    assert_ne!(
+        YarnFileSource::in_memory(DLC),
+        YarnFileSource::InMemory(yarn_file)
+    );
+}
+
+#[test]
+fn adding_source_at_runtime_recompiles_project() -> Result<()> {
+    let mut app = App::new();
+    let mut asserter = EventAsserter::new();
+
+    app.setup_default_plugins().add_plugins(
+        YarnSpinnerPlugin::with_yarn_source(YarnFileSource::in_memory(GENERATED))
+            .with_development_file_generation(DevelopmentFileGeneration::None),
+    );
+    // The runner must pick up the recompiled program
+    let _ = app.dialogue_runner_entity();
+    let program = app.load_project().compilation().program.as_ref().unwrap();
+    assert!(!program.nodes.contains_key("Dlc"));
+
+    app.load_project_mut()
+        .add_yarn_source(YarnFileSource::in_memory(DLC));
+    while app.load_project().yarn_files().count() < 2 {
+        app.update();
+    }
+    app.update();
+
+    let program = app.load_project().compilation().program.as_ref().unwrap();
+    assert!(program.nodes.contains_key("Dlc"));
+
+    app.dialogue_runner_mut().start_node("Dlc");
+    app.update();
+    assert_events!(asserter, app contains [
+        PresentLineEvent with |event| event.line.text == "Hello from the DLC!",
+    ]);
+    Ok(())
+}
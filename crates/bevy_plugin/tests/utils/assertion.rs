@@ -12,6 +12,8 @@ pub struct EventAsserter {
     pub node_complete_reader: ManualEventReader<NodeCompleteEvent>,
     pub line_hints_reader: ManualEventReader<LineHintsEvent>,
     pub execute_command_reader: ManualEventReader<ExecuteCommandEvent>,
+    pub runtime_error_reader: ManualEventReader<DialogueRuntimeErrorEvent>,
+    pub dialogue_reloaded_reader: ManualEventReader<DialogueReloadedEvent>,
 }
 
 impl EventAsserter {
@@ -36,6 +38,10 @@ impl EventAsserter {
             .clear(app.world().resource::<Events<LineHintsEvent>>());
         self.execute_command_reader
             .clear(app.world().resource::<Events<ExecuteCommandEvent>>());
+        self.runtime_error_reader
+            .clear(app.world().resource::<Events<DialogueRuntimeErrorEvent>>());
+        self.dialogue_reloaded_reader
+            .clear(app.world().resource::<Events<DialogueReloadedEvent>>());
     }
 }
 
@@ -65,6 +71,12 @@ macro_rules! get_reader {
     ($asserter:ident, ExecuteCommandEvent) => {
         &mut $asserter.execute_command_reader
     };
+    ($asserter:ident, DialogueRuntimeErrorEvent) => {
+        &mut $asserter.runtime_error_reader
+    };
+    ($asserter:ident, DialogueReloadedEvent) => {
+        &mut $asserter.dialogue_reloaded_reader
+    };
 }
 
 #[macro_export]
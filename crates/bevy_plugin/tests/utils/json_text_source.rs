@@ -0,0 +1,89 @@
+//! A reference implementation of a text provider as a third-party crate would ship it.
+//! It only depends on the public [`TextSource`] trait of the runtime and knows nothing about the Bevy plugin,
+//! so the tests using it guard the contract between the two.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use yarnspinner::core::LineId;
+use yarnspinner::runtime::{Language, StringTable, TextSource};
+
+/// Reads translations from `<directory>/<language>.json` files, which map line IDs to their text.
+/// The file of a language is only read once a line of it is hinted to be needed.
+#[derive(Debug)]
+pub struct JsonTextSource {
+    directory: PathBuf,
+    base_language: StringTable,
+    language: Option<Language>,
+    translations: HashMap<Language, StringTable>,
+}
+
+impl JsonTextSource {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            base_language: StringTable::new(),
+            language: None,
+            translations: HashMap::new(),
+        }
+    }
+
+    fn load_translation(&mut self, language: &Language) {
+        if self.translations.contains_key(language) {
+            return;
+        }
+        let path = self.directory.join(format!("{language}.json"));
+        // Languages without a file, such as the base language, fall back to the base language
+        let Ok(json) = fs::read_to_string(&path) else {
+            self.translations
+                .insert(language.clone(), StringTable::new());
+            return;
+        };
+        let texts: HashMap<String, String> = serde_json::from_str(&json)
+            .unwrap_or_else(|error| panic!("Failed to parse {}: {error}", path.display()));
+        let table = texts
+            .into_iter()
+            .map(|(id, text)| (LineId(id), text))
+            .collect();
+        self.translations.insert(language.clone(), table);
+    }
+}
+
+impl TextSource for JsonTextSource {
+    fn get_text(&self, id: &LineId) -> Option<String> {
+        self.language
+            .as_ref()
+            .and_then(|language| self.translations.get(language))
+            .and_then(|translation| translation.get(id))
+            .or_else(|| self.base_language.get(id))
+            .cloned()
+    }
+
+    fn set_language(&mut self, language: Option<Language>) {
+        self.language = language;
+    }
+
+    fn get_language(&self) -> Option<Language> {
+        self.language.clone()
+    }
+
+    fn set_base_language(&mut self, string_table: StringTable) {
+        self.base_language = string_table;
+    }
+
+    fn accept_line_hints(&mut self, line_ids: &[LineId]) {
+        if line_ids.is_empty() {
+            return;
+        }
+        if let Some(language) = self.language.clone() {
+            self.load_translation(&language);
+        }
+    }
+
+    fn are_lines_available(&self) -> bool {
+        match self.language.as_ref() {
+            Some(language) => self.translations.contains_key(language),
+            None => !self.base_language.is_empty(),
+        }
+    }
+}
@@ -9,10 +9,11 @@ use bevy_yarnspinner::UnderlyingYarnLine;
 use std::path::{Path, PathBuf};
 
 pub mod assertion;
+pub mod json_text_source;
 
 pub mod prelude {
     #[allow(unused_imports)] // False positive
-    pub use super::{assertion::*, *};
+    pub use super::{assertion::*, json_text_source::*, *};
 }
 
 pub trait AppExt {
@@ -149,6 +150,9 @@ impl DialogueRunnerExt for DialogueRunner {
             id: LineId(line_id.to_string()),
             text: String::new(),
             attributes: vec![],
+            raw_text: String::new(),
+            substitutions: vec![],
+            metadata: vec![],
         };
         self.asset_providers()
             .map(|p| p.get_assets(&line_id))
@@ -0,0 +1,67 @@
+//! Merges a translation returned by a translator into the strings file of a language, as used by `bevy_yarnspinner`.
+//!
+//! ```text
+//! merge_translation [--policy keep-existing|keep-incoming|mark-for-review] <language> <strings file> <incoming file> <yarn file>...
+//! ```
+//!
+//! The strings file is created if it doesn't exist yet and overwritten with the merged translation.
+//! The report of the merge is printed to stdout, see [`MergeResult::report`].
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::{env, fs, process};
+use yarnspinner_compiler::prelude::*;
+
+const USAGE: &str = "Usage: merge_translation [--policy keep-existing|keep-incoming|mark-for-review] <language> <strings file> <incoming file> <yarn file>...";
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args: Vec<_> = env::args().skip(1).collect();
+    let policy = match args.iter().position(|arg| arg == "--policy") {
+        Some(index) => {
+            let policy = args.get(index + 1).map(String::as_str);
+            let policy = match policy {
+                Some("keep-existing") => ConflictPolicy::KeepExisting,
+                Some("keep-incoming") => ConflictPolicy::KeepIncoming,
+                Some("mark-for-review") => ConflictPolicy::MarkForReview,
+                _ => exit_with_usage(),
+            };
+            args.drain(index..=index + 1);
+            policy
+        }
+        None => ConflictPolicy::default(),
+    };
+    let [language, strings_file, incoming_file, yarn_files @ ..] = args.as_slice() else {
+        exit_with_usage();
+    };
+    if yarn_files.is_empty() {
+        exit_with_usage();
+    }
+
+    let mut compiler = Compiler::new();
+    for yarn_file in yarn_files {
+        compiler.try_read_file(yarn_file)?;
+    }
+    let base = compiler
+        .with_compilation_type(CompilationType::StringsOnly)
+        .compile()?;
+
+    let existing = match fs::read_to_string(strings_file) {
+        Ok(existing) => read_translation_csv(&existing)?,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+        Err(error) => return Err(error.into()),
+    };
+    let incoming = fs::read_to_string(incoming_file)?;
+    let result = merge_translation(&base, existing, &incoming, policy)?;
+
+    fs::write(
+        strings_file,
+        export_translation_csv(&base, language, &result.merged),
+    )?;
+    print!("{}", result.report());
+    Ok(())
+}
+
+fn exit_with_usage() -> ! {
+    eprintln!("{USAGE}");
+    process::exit(2)
+}
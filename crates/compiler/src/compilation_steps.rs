@@ -1,9 +1,14 @@
 mod add_initial_value_registrations;
 mod add_tracking_declarations;
+mod build_node_graph;
 mod check_types;
 mod clean_up_diagnostics;
+mod collect_node_tags;
+mod compute_wrap_hints;
 mod create_declarations_for_tracking_nodes;
+mod declare_enums;
 mod early_breaks;
+mod expand_node_groups;
 mod find_tracking_nodes;
 mod generate_code;
 mod get_declarations;
@@ -11,12 +16,24 @@ mod parse_files;
 mod register_initial_variables;
 mod register_strings;
 mod resolve_deferred_type_diagnostic;
+mod resolve_interpolation_types;
+mod sort_output;
+mod validate_commands;
+mod validate_jump_loops;
+mod validate_jump_targets;
+mod validate_node_contracts;
+mod validate_smart_variables;
 mod validate_unique_node_names;
+mod validate_unreachable_content;
 
 pub(crate) use self::{
-    add_initial_value_registrations::*, add_tracking_declarations::*, check_types::*,
-    clean_up_diagnostics::*, create_declarations_for_tracking_nodes::*, early_breaks::*,
-    find_tracking_nodes::*, generate_code::*, get_declarations::*, parse_files::*,
-    register_initial_variables::*, register_strings::*, resolve_deferred_type_diagnostic::*,
-    validate_unique_node_names::*,
+    add_initial_value_registrations::*, add_tracking_declarations::*, build_node_graph::*,
+    check_types::*, clean_up_diagnostics::*, collect_node_tags::*, compute_wrap_hints::*,
+    create_declarations_for_tracking_nodes::*, declare_enums::*, early_breaks::*,
+    expand_node_groups::*, find_tracking_nodes::*, generate_code::*, get_declarations::*,
+    parse_files::*, register_initial_variables::*, register_strings::*,
+    resolve_deferred_type_diagnostic::*, resolve_interpolation_types::*, sort_output::*,
+    validate_commands::*, validate_jump_loops::*, validate_jump_targets::*,
+    validate_node_contracts::*, validate_smart_variables::*, validate_unique_node_names::*,
+    validate_unreachable_content::*,
 };
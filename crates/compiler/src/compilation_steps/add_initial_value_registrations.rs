@@ -15,22 +15,36 @@ pub(crate) fn add_initial_value_registrations(
     let declarations = state
         .known_variable_declarations
         .iter()
-        .filter(|decl| !matches!(decl.r#type, Type::Function(_)));
+        .filter(|decl| !matches!(decl.r#type, Type::Function(_)))
+        // Enum cases are constants
+        .filter(|decl| !is_enum_case(decl))
+        // Smart variables are computed whenever they are read instead of being stored
+        .filter(|decl| !decl.is_smart);
 
     for declaration in declarations {
         let Some(default_value) = declaration.default_value.clone() else {
-            state.diagnostics.push(Diagnostic::from_message(format!(
+            state.diagnostics.push(
+                Diagnostic::from_message(format!(
                 "Variable declaration {} (type {}) has a null default value. This is not allowed.",
                 declaration.name,
                 declaration.r#type.format()
-            )));
+            ))
+                .with_code(DiagnosticCode::NullDefaultValue)
+                .with_argument("variable", &declaration.name)
+                .with_argument("type", declaration.r#type.format()),
+            );
             continue;
         };
         if let Some(ref mut program) = compilation.program {
             let value = match &declaration.r#type {
                     Type::String => Operand::from(String::from(default_value)),
-                    Type::Number => Operand::from(f32::try_from(default_value).unwrap()),
+                    Type::Number | Type::Integer => Operand::from(f32::try_from(default_value).unwrap()),
                     Type::Boolean => Operand::from(bool::try_from(default_value).unwrap()),
+                    // Enum values are stored as the raw values of their cases
+                    Type::Enum(_) => match default_value {
+                        YarnValue::String(raw_value) => Operand::from(raw_value),
+                        raw_value => Operand::from(f32::try_from(raw_value).unwrap()),
+                    },
                     _ => panic!("Cannot create initial value registration for type {}. This is a bug. Please report it at https://github.com/YarnSpinnerTool/YarnSpinner-Rust/issues/new", declaration.r#type.format()),
                 };
             program
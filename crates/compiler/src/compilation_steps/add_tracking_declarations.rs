@@ -16,6 +16,13 @@ pub(crate) fn add_tracking_declarations(
                     "The generated variable for tracking visits of node {node}"
                 ))
         })
+        .chain(state.once_variables.iter().map(|name| {
+            Declaration::new(name, Type::Boolean)
+                .with_default_value(false)
+                .with_description(
+                    "The generated variable for tracking whether a <<once>> block has run",
+                )
+        }))
         .collect();
 
     // adding the generated tracking variables into the declaration list
@@ -0,0 +1,12 @@
+use crate::prelude::*;
+use crate::visitors::NodeGraphVisitor;
+use antlr_rust::tree::ParseTreeVisitorCompat;
+
+pub(crate) fn build_node_graph(mut state: CompilationIntermediate) -> CompilationIntermediate {
+    for (file, _) in &state.parsed_files {
+        let mut visitor = NodeGraphVisitor::new(&state.node_names, file.clone());
+        visitor.visit(file.tree.as_ref());
+        state.node_graph.extend(visitor.node_graph);
+    }
+    state
+}
@@ -1,11 +1,24 @@
+use crate::prelude::generated::yarnspinnerlexer;
 use crate::prelude::*;
 use crate::visitors::TypeCheckVisitor;
+use antlr_rust::token::Token;
 use antlr_rust::tree::ParseTreeVisitorCompat;
 
 pub(crate) fn check_types(mut state: CompilationIntermediate) -> CompilationIntermediate {
+    let smart_variable_declarations = find_smart_variable_declarations(&state);
+    state
+        .known_variable_declarations
+        .extend(smart_variable_declarations.clone());
+    state
+        .derived_variable_declarations
+        .extend(smart_variable_declarations);
+
     for (file, known_types) in &mut state.parsed_files {
-        let mut visitor =
-            TypeCheckVisitor::new(state.known_variable_declarations.clone(), file.clone());
+        let mut visitor = TypeCheckVisitor::new(
+            state.known_variable_declarations.clone(),
+            file.clone(),
+            state.job.library.clone(),
+        );
         visitor.visit(file.tree.as_ref());
         state
             .known_variable_declarations
@@ -19,3 +32,48 @@ pub(crate) fn check_types(mut state: CompilationIntermediate) -> CompilationInte
     }
     state
 }
+
+/// The type of a smart variable is the type of its expression, which may refer to other smart variables declared further down or in other files.
+/// So we type check all files repeatedly, keeping only the smart variables found, until no more of them can be typed.
+/// Everything else, including diagnostics, is left to the actual type check.
+fn find_smart_variable_declarations(state: &CompilationIntermediate) -> Vec<Declaration> {
+    let has_smart_variables = state.parsed_files.iter().any(|(file, _)| {
+        file.tokens()
+            .get_tokens()
+            .iter()
+            .any(|token| token.get_token_type() == yarnspinnerlexer::COMMAND_DECLARE)
+    });
+    let mut smart_variable_declarations = Vec::new();
+    if !has_smart_variables {
+        return smart_variable_declarations;
+    }
+    loop {
+        let mut known_declarations: Vec<_> = state
+            .known_variable_declarations
+            .iter()
+            .chain(smart_variable_declarations.iter())
+            .cloned()
+            .collect();
+        let mut new_declarations = Vec::new();
+        for (file, _) in &state.parsed_files {
+            let mut visitor = TypeCheckVisitor::new(
+                known_declarations.clone(),
+                file.clone(),
+                state.job.library.clone(),
+            );
+            visitor.visit(file.tree.as_ref());
+            let found = visitor
+                .new_declarations
+                .into_iter()
+                .filter(|declaration| declaration.is_smart);
+            for declaration in found {
+                known_declarations.push(declaration.clone());
+                new_declarations.push(declaration);
+            }
+        }
+        if new_declarations.is_empty() {
+            return smart_variable_declarations;
+        }
+        smart_variable_declarations.extend(new_declarations);
+    }
+}
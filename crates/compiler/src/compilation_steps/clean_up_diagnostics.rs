@@ -24,7 +24,8 @@ pub(crate) fn clean_up_diagnostics(mut state: CompilationIntermediate) -> Compil
         }
     }
     state.diagnostics = ordered_unique_diagnostics;
-    if state.diagnostics.has_errors() {
+    let is_analysis = state.job.compilation_type == CompilationType::Analyze;
+    if state.diagnostics.has_errors() && !is_analysis {
         state.result = Some(Err(CompilerError(state.diagnostics.clone())));
     } else if let Some(Ok(compilation)) = state.result.as_mut() {
         compilation.warnings.clone_from(&state.diagnostics);
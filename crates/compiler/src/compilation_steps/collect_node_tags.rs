@@ -0,0 +1,39 @@
+use crate::prelude::generated::yarnspinnerparser::{DialogueContextAttrs, NodeContextAttrs};
+use crate::prelude::*;
+use antlr_rust::token::Token;
+
+pub(crate) fn collect_node_tags(mut state: CompilationIntermediate) -> CompilationIntermediate {
+    for node in state
+        .parsed_files
+        .iter()
+        .flat_map(|(file, _)| file.tree.node_all())
+    {
+        let header_value = |key: &str| {
+            node.header_all()
+                .iter()
+                .find(|header| header.header_key.as_ref().unwrap().get_text() == key)
+                .and_then(|header| {
+                    header
+                        .header_value
+                        .as_ref()
+                        .map(|value| value.get_text().to_owned())
+                })
+        };
+        let Some(title) = header_value("title") else {
+            continue;
+        };
+        let tags: Vec<_> = header_value("tags")
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(ToOwned::to_owned)
+            .collect();
+        if tags
+            .iter()
+            .any(|tag| state.job.excluded_node_tags.contains(tag))
+        {
+            state.excluded_nodes.insert(title.clone());
+        }
+        state.node_tags.insert(title, tags);
+    }
+    state
+}
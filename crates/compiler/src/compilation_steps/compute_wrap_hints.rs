@@ -0,0 +1,50 @@
+use crate::prelude::*;
+
+pub(crate) fn compute_wrap_hints(mut state: CompilationIntermediate) -> CompilationIntermediate {
+    let Some(config) = state.job.wrap_config.as_ref() else {
+        return state;
+    };
+    for string_info in state.string_table.values_mut() {
+        let hints = wrap_hints(&string_info.text, config);
+        let mut problems = Vec::new();
+        if let Some(max_lines) = config.max_lines.filter(|max| hints.line_count > *max) {
+            problems.push(format!(
+                "wraps into {} lines, but the dialogue box only fits {max_lines}",
+                hints.line_count
+            ));
+        }
+        if hints.has_overlong_token {
+            problems.push(format!(
+                "contains a word that is wider than the dialogue box's {} cells",
+                config.width
+            ));
+        }
+        let line = string_info.line_number.saturating_sub(1);
+        let context = state
+            .job
+            .files
+            .iter()
+            .find(|file| file.file_name == string_info.file_name)
+            .and_then(|file| file.source.lines().nth(line))
+            .unwrap_or_default();
+        for problem in problems {
+            state.diagnostics.push(
+                Diagnostic::from_message(format!("The line \"{}\" {problem}", string_info.text))
+                    .with_code(DiagnosticCode::LineOverflow)
+                    .with_argument("text", &string_info.text)
+                    .with_range(
+                        Position { line, character: 0 }..Position {
+                            line,
+                            character: context.chars().count(),
+                        },
+                    )
+                    .with_context(context)
+                    .with_start_line(line)
+                    .with_file_name(&string_info.file_name)
+                    .with_severity(DiagnosticSeverity::Warning),
+            );
+        }
+        string_info.wrap_hints = Some(hints);
+    }
+    state
+}
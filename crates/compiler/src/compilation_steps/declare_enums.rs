@@ -0,0 +1,18 @@
+use crate::prelude::*;
+use crate::visitors::EnumDeclarationVisitor;
+use antlr_rust::tree::ParseTreeVisitorCompat;
+
+/// Declares the cases of all enums, so that they can be used in the declarations of variables.
+/// Like functions, they are not part of the [`Compilation::declarations`], but the declarations of variables of an enum type hold all of its cases.
+pub(crate) fn declare_enums(mut state: CompilationIntermediate) -> CompilationIntermediate {
+    for (file, _) in &state.parsed_files {
+        let mut visitor =
+            EnumDeclarationVisitor::new(state.known_variable_declarations.clone(), file.clone());
+        visitor.visit(file.tree.as_ref());
+        state
+            .known_variable_declarations
+            .extend(visitor.new_declarations);
+        state.diagnostics.extend(visitor.diagnostics);
+    }
+    state
+}
@@ -1,3 +1,4 @@
+use crate::compilation_steps::included_node_tags;
 use crate::prelude::*;
 
 pub(crate) fn break_on_job_with_only_strings(
@@ -15,6 +16,26 @@ pub(crate) fn break_on_job_with_only_strings(
     state
 }
 
+/// This was not in the original, but the checks working on the generated code, like [`validate_node_contracts`](crate::compilation_steps::validate_node_contracts),
+/// find mistakes that an analysis should report as well, so this runs after the code generation.
+/// The generated program itself is not part of the result.
+pub(crate) fn break_on_analysis_job(mut state: CompilationIntermediate) -> CompilationIntermediate {
+    if state.job.compilation_type == CompilationType::Analyze {
+        state.result = Some(Ok(Compilation {
+            string_table: state.string_table.clone().into(),
+            contains_implicit_string_tags: state.string_table.contains_implicit_string_tags(),
+            declarations: state.derived_variable_declarations.clone(),
+            warnings: state.diagnostics.clone(),
+            file_tags: state.file_tags.clone(),
+            node_tags: included_node_tags(&state),
+            node_graph: state.node_graph.clone(),
+            ..Default::default()
+        }));
+        state.early_break = true;
+    }
+    state
+}
+
 pub(crate) fn break_on_job_with_only_declarations(
     mut state: CompilationIntermediate,
 ) -> CompilationIntermediate {
@@ -23,6 +44,8 @@ pub(crate) fn break_on_job_with_only_declarations(
             declarations: state.derived_variable_declarations.clone(),
             warnings: state.diagnostics.clone(),
             file_tags: state.file_tags.clone(),
+            node_tags: included_node_tags(&state),
+            node_graph: state.node_graph.clone(),
             ..Default::default()
         }));
         state.early_break = true;
@@ -0,0 +1,464 @@
+//! Not part of the original implementation.
+//!
+//! Node groups are nodes that share a title and each have at least one `when:` header, e.g.
+//! ```yarn
+//! title: Greeting
+//! when: $met_before
+//! ---
+//! Hello again!
+//! ===
+//! ```
+//! The nodes of a group are renamed with [`Node::node_group_variant_name`] and a node named after the group,
+//! tagged with [`Node::NODE_GROUP_TAG`], is added to the file of the first one.
+//! Since the grammar reads header values as plain text, the generated node is parsed from Yarn source of its own,
+//! whose tokens are attributed to the `when:` headers they were copied from. Its conditions are thus type checked
+//! and compiled like any other Yarn code, and diagnostics about them point to the headers.
+
+use crate::error_strategy::ErrorStrategy;
+use crate::listeners::*;
+use crate::prelude::generated::yarnspinnerparser::*;
+use crate::prelude::*;
+use antlr_rust::common_token_stream::CommonTokenStream;
+use antlr_rust::int_stream::IntStream;
+use antlr_rust::parser_rule_context::ParserRuleContext;
+use antlr_rust::rule_context::RuleContext;
+use antlr_rust::token::{CommonToken, Token};
+use antlr_rust::token_factory::TokenFactory;
+use antlr_rust::tree::{TerminalNode, Tree};
+use antlr_rust::{InputStream, Parser, TidExt, TokenSource};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::rc::Rc;
+
+/// The header whose value is a condition that must pass for a node of a node group to be run.
+pub(crate) const WHEN_HEADER: &str = "when";
+
+/// The value of a `when:` header that always passes and doesn't add to the complexity of the node's conditions.
+const ALWAYS_CONDITION: &str = "always";
+
+/// Renames the nodes of all node groups and adds a node named after each group that runs its most salient node.
+///
+/// A title shared by a node without a `when:` header is not treated as a node group, so that it is reported as a duplicate node name.
+pub(crate) fn expand_node_groups(mut state: CompilationIntermediate) -> CompilationIntermediate {
+    let mut nodes_by_title: HashMap<String, Vec<NodeInGroup>> = HashMap::new();
+    let mut titles = Vec::new();
+    for (file_index, (file, _)) in state.parsed_files.iter().enumerate() {
+        for node in file.tree.node_all() {
+            let Some(node) = read_node(node, file_index) else {
+                continue;
+            };
+            if !nodes_by_title.contains_key(&node.title) {
+                titles.push(node.title.clone());
+            }
+            nodes_by_title
+                .entry(node.title.clone())
+                .or_default()
+                .push(node);
+        }
+    }
+
+    for title in titles {
+        let nodes = nodes_by_title.remove(&title).unwrap();
+        if !nodes.iter().all(|node| node.has_when_header) {
+            continue;
+        }
+        let mut occurrences: HashMap<(usize, &[String]), usize> = HashMap::new();
+        let variant_names: Vec<_> = nodes
+            .iter()
+            .map(|node| {
+                let occurrence = occurrences
+                    .entry((node.file_index, node.when_headers.as_slice()))
+                    .or_default();
+                *occurrence += 1;
+                let when_headers: Vec<_> = node.when_headers.iter().map(String::as_str).collect();
+                let file_name = &state.parsed_files[node.file_index].0.name;
+                Node::node_group_variant_name(&title, file_name, &when_headers, *occurrence)
+            })
+            .collect();
+        for (node, variant_name) in nodes.iter().zip(&variant_names) {
+            rename_node(node, variant_name);
+        }
+
+        let host = &state.parsed_files[nodes[0].file_index].0;
+        let Some(file) = state
+            .job
+            .files
+            .iter()
+            .find(|file| file.file_name == host.name)
+        else {
+            continue;
+        };
+        let source = generate_node_group(&title, &nodes, &variant_names);
+        if let Some(node_group) = parse_node_group(file, source, &mut state.diagnostics) {
+            node_group.set_parent(&Some(host.tree.clone()));
+            host.tree.add_child(node_group);
+        }
+    }
+    state
+}
+
+#[derive(Debug)]
+struct NodeInGroup<'input> {
+    file_index: usize,
+    node: Rc<NodeContext<'input>>,
+    title_header: Rc<HeaderContext<'input>>,
+    title: String,
+    /// Where the value of the `title:` header is.
+    title_text: SourceText,
+    /// The trimmed values of the `when:` headers, which identify the node within its group.
+    when_headers: Vec<String>,
+    /// The values of the `when:` headers, except for `always`.
+    conditions: Vec<(String, SourceText)>,
+    has_when_header: bool,
+}
+
+fn read_node(node: Rc<NodeContext>, file_index: usize) -> Option<NodeInGroup> {
+    let mut title = None;
+    let mut when_headers = Vec::new();
+    let mut conditions = Vec::new();
+    for header in node.header_all() {
+        let key = header.header_key.as_ref()?.get_text();
+        let value = header.header_value.as_ref();
+        match key {
+            "title" if title.is_none() => {
+                let value = value?;
+                title = Some((
+                    header.clone(),
+                    value.get_text().trim().to_owned(),
+                    SourceText::from_token(value.as_ref()),
+                ));
+            }
+            WHEN_HEADER => {
+                let text = value
+                    .map(|value| value.get_text().trim())
+                    .unwrap_or_default();
+                when_headers.push(text.to_owned());
+                if text != ALWAYS_CONDITION {
+                    let source_text = match value {
+                        Some(value) => SourceText::from_token(value.as_ref()),
+                        None => SourceText::from_token(header.header_key.as_deref()?),
+                    };
+                    conditions.push((text.to_owned(), source_text));
+                }
+            }
+            _ => {}
+        }
+    }
+    let (title_header, title, title_text) = title?;
+    Some(NodeInGroup {
+        file_index,
+        node,
+        title_header,
+        title,
+        title_text,
+        has_when_header: !when_headers.is_empty(),
+        when_headers,
+        conditions,
+    })
+}
+
+/// Replaces the `title:` header of a node with one holding the new name.
+/// Since the parsed headers can't be changed, the header is copied, and so is the token holding its value.
+fn rename_node(node: &NodeInGroup, name: &str) {
+    let header = &node.title_header;
+    let Some(value) = header.header_value.as_ref() else {
+        return;
+    };
+    let mut renamed_value = value.clone();
+    renamed_value.text = name.to_owned().into();
+    let mut ext = HeaderContextExt::clone(header);
+    ext.header_value = Some(renamed_value.clone());
+    let renamed_header = Rc::new(HeaderContext::copy_from(header.as_ref(), ext));
+    renamed_header.set_stop(Some(renamed_value.clone()));
+    let value_index = renamed_header.get_children().position(|child| {
+        child
+            .downcast_rc::<TerminalNode<YarnSpinnerParserContextType>>()
+            .is_ok_and(|terminal| terminal.symbol.get_token_index() == value.get_token_index())
+    });
+    if let Some(value_index) = value_index {
+        let terminal = Rc::new(TerminalNode::new(renamed_value));
+        let parent: Rc<ActualParserContext> = renamed_header.clone();
+        terminal.set_parent(&Some(parent));
+        replace_child(renamed_header.as_ref(), value_index, Some(terminal));
+    }
+
+    let header_index = node.node.get_children().position(|child| {
+        child
+            .downcast_rc::<HeaderContext>()
+            .is_ok_and(|child| Rc::ptr_eq(&child, header))
+    });
+    if let Some(header_index) = header_index {
+        replace_child(node.node.as_ref(), header_index, Some(renamed_header));
+    }
+}
+
+/// The Yarn source of a node named after the group that jumps to its most salient node, e.g.
+/// ```yarn
+/// title: Greeting
+/// tags: Yarn.NodeGroup
+/// ---
+/// <<if ($met_before) && ($gold > 10)>>
+/// <<jump Greeting__variant_0123456789abcdef>>
+/// <<endif>>
+/// <<jump Greeting__variant_fedcba9876543210>>
+/// ===
+/// ```
+/// Each line is attributed to the title of the first node of the group, except for the conditions and the statements using them,
+/// which are attributed to the end of the last condition, so that the whole `<<if>>` statement covers all of them.
+fn generate_node_group(
+    title: &str,
+    nodes: &[NodeInGroup],
+    variant_names: &[String],
+) -> NodeGroupSource {
+    let title_text = nodes[0].title_text.clone();
+    let mut source = NodeGroupSource::default();
+    source.push_line(&format!("title: {title}"), &title_text, Alignment::Start);
+    source.push_line(
+        &format!("tags: {}", Node::NODE_GROUP_TAG),
+        &title_text,
+        Alignment::Start,
+    );
+    source.push_line("---", &title_text, Alignment::Start);
+    for (node, variant_name) in nodes.iter().zip(variant_names) {
+        let jump = format!("<<jump {variant_name}>>");
+        let Some((_, last_condition)) = node.conditions.last() else {
+            source.push_line(&jump, &title_text, Alignment::Start);
+            continue;
+        };
+        // Joining the conditions with `&&` adds one boolean operator less than there are conditions,
+        // so the complexity of the joined condition is the sum of the complexities of the single ones
+        let line = source.lines.len();
+        let mut if_statement = String::from("<<if ");
+        for (condition_index, (condition, original)) in node.conditions.iter().enumerate() {
+            if condition_index > 0 {
+                if_statement.push_str(" && ");
+            }
+            if_statement.push('(');
+            let start = if_statement.chars().count();
+            if_statement.push_str(condition);
+            let end = if_statement.chars().count();
+            if_statement.push(')');
+            source.conditions.push(CopiedCondition {
+                line,
+                characters: start..end,
+                original: original.clone(),
+            });
+        }
+        if_statement.push_str(">>");
+        source.push_line(&if_statement, &node.conditions[0].1, Alignment::Start);
+        source.push_line(&jump, last_condition, Alignment::End);
+        source.push_line("<<endif>>", last_condition, Alignment::End);
+    }
+    source.push_line("===", &title_text, Alignment::Start);
+    source.title = title_text;
+    source
+}
+
+/// Parses the node generated for a node group, adding all problems found to `diagnostics`.
+/// If the node has syntax errors, e.g. because a condition is not a valid expression, its body is removed,
+/// so that the group can still be jumped to.
+fn parse_node_group<'input>(
+    file: &File,
+    source: NodeGroupSource,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<Rc<NodeContext<'input>>> {
+    let input = InputStream::new_owned(source.text.clone().into_boxed_str());
+    let mut lexer = YarnSpinnerLexer::new(input, file.file_name.clone());
+    lexer.remove_error_listeners();
+    let lexer_diagnostics = lexer.diagnostics.clone();
+    let tokens = NodeGroupTokenSource {
+        lexer,
+        source: source.clone(),
+    };
+    let mut parser =
+        YarnSpinnerParser::with_strategy(CommonTokenStream::new(tokens), ErrorStrategy::new());
+    let parser_error_listener = ParserErrorListener::new(file.clone());
+    let parser_diagnostics = parser_error_listener.diagnostics.clone();
+    parser.remove_error_listeners();
+    parser.add_error_listener(Box::new(parser_error_listener));
+    let tree = parser.dialogue();
+
+    let lexer_diagnostics = lexer_diagnostics
+        .borrow()
+        .iter()
+        .cloned()
+        .map(|diagnostic| source.restore_diagnostic_position(diagnostic, file))
+        .collect::<Vec<_>>();
+    let has_errors = lexer_diagnostics.has_errors() || parser_diagnostics.borrow().has_errors();
+    diagnostics.extend(lexer_diagnostics);
+    diagnostics.extend(parser_diagnostics.borrow().iter().cloned());
+
+    let node = tree.ok()?.node(0)?;
+    if has_errors {
+        remove_body(&node);
+    }
+    Some(node)
+}
+
+/// The source of the node generated for a node group, along with where its text came from.
+#[derive(Debug, Clone, Default)]
+struct NodeGroupSource {
+    text: String,
+    /// The source text that each line is attributed to, except for the conditions on it.
+    lines: Vec<(SourceText, Alignment)>,
+    /// Where the `when:` conditions were copied to.
+    conditions: Vec<CopiedCondition>,
+    /// The title of the first node of the group, which any other text is attributed to.
+    title: SourceText,
+}
+
+#[derive(Debug, Clone)]
+struct CopiedCondition {
+    /// The zero-based line of the generated source.
+    line: usize,
+    characters: Range<usize>,
+    original: SourceText,
+}
+
+/// Which end of a source text the generated text attributed to it is placed at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Alignment {
+    Start,
+    End,
+}
+
+/// A trimmed piece of text in a source file.
+#[derive(Debug, Clone, Default)]
+struct SourceText {
+    /// The zero-based line, like the ones of diagnostics.
+    line: usize,
+    column: usize,
+    /// The index of the first character in the file.
+    start: isize,
+    length: usize,
+}
+
+impl SourceText {
+    fn from_token(token: &(impl Token<Data = str> + ?Sized)) -> Self {
+        let text = token.get_text();
+        let leading_whitespace = text.chars().take_while(|c| c.is_whitespace()).count();
+        Self {
+            line: token.get_line_as_usize().saturating_sub(1),
+            column: token.get_column_as_usize() + leading_whitespace,
+            start: token.get_start() + leading_whitespace as isize,
+            length: text.trim().chars().count(),
+        }
+    }
+}
+
+impl NodeGroupSource {
+    fn push_line(&mut self, line: &str, original: &SourceText, alignment: Alignment) {
+        self.text.push_str(line);
+        self.text.push('\n');
+        self.lines.push((original.clone(), alignment));
+    }
+
+    /// The source text, the offset into it and the length that the generated text at the given zero-based position is attributed to.
+    /// The text between the conditions on a line is attributed to the end of the condition before it, or to the start of the first one,
+    /// so that an expression joining several conditions covers all of them.
+    fn original_position(
+        &self,
+        line: usize,
+        column: usize,
+        length: usize,
+    ) -> (&SourceText, usize, usize) {
+        let conditions: Vec<_> = self
+            .conditions
+            .iter()
+            .filter(|condition| condition.line == line)
+            .collect();
+        if let Some(condition) = conditions
+            .iter()
+            .find(|condition| condition.characters.contains(&column))
+        {
+            let offset = column - condition.characters.start;
+            let length = length.min(condition.original.length - offset);
+            return (&condition.original, offset, length);
+        }
+        let preceding_condition = conditions
+            .iter()
+            .rev()
+            .find(|condition| condition.characters.end <= column);
+        let (original, alignment) = match (preceding_condition, conditions.first()) {
+            (Some(condition), _) => (&condition.original, Alignment::End),
+            (None, Some(condition)) => (&condition.original, Alignment::Start),
+            (None, None) => self
+                .lines
+                .get(line)
+                .map_or((&self.title, Alignment::Start), |(original, alignment)| {
+                    (original, *alignment)
+                }),
+        };
+        let length = length.min(original.length);
+        let offset = match alignment {
+            Alignment::Start => 0,
+            Alignment::End => original.length - length,
+        };
+        (original, offset, length)
+    }
+
+    /// Attributes a token of the generated source to the source text it came from.
+    fn restore_token_position(&self, token: &mut CommonToken) {
+        let line = usize::try_from(token.line - 1).unwrap_or(usize::MAX);
+        let column = usize::try_from(token.column).unwrap_or_default();
+        let (original, offset, length) =
+            self.original_position(line, column, token.text.chars().count());
+        token.line = original.line as isize + 1;
+        token.column = (original.column + offset) as isize;
+        token.start = original.start + offset as isize;
+        token.stop = token.start + length as isize - 1;
+    }
+
+    /// Attributes a diagnostic reported by the lexer for the generated source to the source text it came from.
+    fn restore_diagnostic_position(&self, diagnostic: Diagnostic, file: &File) -> Diagnostic {
+        let Some(range) = diagnostic.range.clone() else {
+            return diagnostic;
+        };
+        let length = range.end.character.saturating_sub(range.start.character);
+        let (original, offset, length) =
+            self.original_position(range.start.line, range.start.character, length);
+        let start = Position {
+            line: original.line,
+            character: original.column + offset,
+        };
+        let end = Position {
+            character: start.character + length,
+            ..start
+        };
+        let context = file.source.lines().nth(original.line).unwrap_or_default();
+        diagnostic
+            .with_range(start..end)
+            .with_context(context)
+            .with_start_line(original.line)
+    }
+}
+
+/// Lexes the node generated for a node group, attributing its tokens to the source text they came from.
+struct NodeGroupTokenSource<'input> {
+    lexer: YarnSpinnerLexer<'input, InputStream<Box<str>>>,
+    source: NodeGroupSource,
+}
+
+antlr_rust::tid! { impl<'input> TidAble<'input> for NodeGroupTokenSource<'input> }
+
+impl<'input> TokenSource<'input> for NodeGroupTokenSource<'input> {
+    type TF = LocalTokenFactory<'input>;
+
+    fn next_token(&mut self) -> <Self::TF as TokenFactory<'input>>::Tok {
+        let mut token = self.lexer.next_token();
+        self.source.restore_token_position(&mut token);
+        token
+    }
+
+    fn get_input_stream(&mut self) -> Option<&mut dyn IntStream> {
+        self.lexer.get_input_stream()
+    }
+
+    fn get_source_name(&self) -> String {
+        self.lexer.get_source_name()
+    }
+
+    fn get_token_factory(&self) -> &'input Self::TF {
+        self.lexer.get_token_factory()
+    }
+}
@@ -14,6 +14,7 @@ pub(crate) fn find_tracking_nodes(mut state: CompilationIntermediate) -> Compila
         visitor.visit(file.tree.as_ref());
         tracking_nodes.extend(visitor.tracking_nodes);
         ignore_nodes.extend(visitor.ignoring_nodes);
+        state.once_variables.extend(visitor.once_variables);
     }
     state.tracking_nodes = tracking_nodes.difference(&ignore_nodes).cloned().collect();
     state
@@ -24,7 +24,9 @@ pub(crate) fn generate_code(mut state: CompilationIntermediate) -> CompilationIn
             .map(|(file, known_types)| {
                 generate_code_for_file(
                     &mut state.tracking_nodes,
+                    &state.node_names,
                     known_types.clone(),
+                    &state.job.library,
                     template.clone(),
                     file,
                 )
@@ -43,10 +45,22 @@ pub(crate) fn generate_code(mut state: CompilationIntermediate) -> CompilationIn
         Err(CompilerError(total_diagnostics))
     } else {
         let compilations = results.into_iter().map(|r| r.unwrap());
-        Ok(Compilation::combine(
-            compilations,
-            state.string_table.clone(),
-        ))
+        Compilation::combine(compilations, state.string_table.clone()).map(|mut compilation| {
+            // The excluded nodes were generated like any other to find their errors
+            if let Some(program) = compilation.program.as_mut() {
+                program
+                    .nodes
+                    .retain(|name, _| !state.excluded_nodes.contains(name));
+            }
+            compilation
+                .debug_info
+                .retain(|name, _| !state.excluded_nodes.contains(name));
+            Compilation {
+                node_graph: state.node_graph.clone(),
+                node_tags: included_node_tags(&state),
+                ..compilation
+            }
+        })
     };
 
     state.result = Some(result);
@@ -55,14 +69,18 @@ pub(crate) fn generate_code(mut state: CompilationIntermediate) -> CompilationIn
 
 fn generate_code_for_file<'a, 'b: 'a, 'input: 'a + 'b>(
     tracking_nodes: &mut HashSet<String>,
+    node_names: &HashSet<String>,
     known_types: KnownTypes,
+    library: &Library,
     result_template: Compilation,
     file: &'a FileParseResult<'input>,
 ) -> Result<Compilation> {
     let compiler_listener = Box::new(CompilerListener::new(
         tracking_nodes.clone(),
+        node_names.clone(),
         known_types,
         file.clone(),
+        library.clone(),
     ));
     let compiler_tracking_nodes = compiler_listener.tracking_nodes.clone();
     let compiler_diagnostics = compiler_listener.diagnostics.clone();
@@ -91,3 +109,13 @@ fn generate_code_for_file<'a, 'b: 'a, 'input: 'a + 'b>(
         })
     }
 }
+
+/// The [`CompilationIntermediate::node_tags`] without the excluded nodes.
+pub(crate) fn included_node_tags(state: &CompilationIntermediate) -> HashMap<String, Vec<String>> {
+    state
+        .node_tags
+        .iter()
+        .filter(|(name, _)| !state.excluded_nodes.contains(*name))
+        .map(|(name, tags)| (name.clone(), tags.clone()))
+        .collect()
+}
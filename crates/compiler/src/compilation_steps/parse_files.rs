@@ -2,8 +2,12 @@ use crate::prelude::*;
 
 pub(crate) fn parse_files(mut state: CompilationIntermediate) -> CompilationIntermediate {
     for (file, chars) in state.job.files.iter().zip(state.file_chars.iter()) {
-        let parse_result = parse_syntax_tree(file, chars, &mut state.diagnostics);
-        state.parsed_files.push((parse_result, Default::default()));
+        let mut diagnostics = Vec::new();
+        if let Some(parse_result) = parse_syntax_tree(file, chars, &mut diagnostics) {
+            remove_bodies_of_broken_nodes(&parse_result.tree, &diagnostics);
+            state.parsed_files.push((parse_result, Default::default()));
+        }
+        state.diagnostics.extend(diagnostics);
     }
     state
 }
@@ -11,6 +11,9 @@ pub(crate) fn register_initial_variables(
     variables.extend(standard_library_declarations);
     let job_library_declarations = get_declarations_from_library(&state.job.library);
     variables.extend(job_library_declarations);
+    // Comes last so that functions of the same name in the job's library take precedence
+    let dialogue_function_declarations = get_dialogue_function_declarations();
+    variables.extend(dialogue_function_declarations);
 
     state
 }
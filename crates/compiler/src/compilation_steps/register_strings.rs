@@ -13,11 +13,22 @@ pub(crate) fn register_strings(mut state: CompilationIntermediate) -> Compilatio
         last_line_tagger.visit(file.tree.as_ref());
 
         let mut visitor =
-            StringTableGeneratorVisitor::new(state.string_table.clone(), file.clone());
+            StringTableGeneratorVisitor::new(state.string_table.clone(), file.clone())
+                .with_line_id_namespace(
+                    state.job.line_id_namespace.clone(),
+                    state.job.line_id_namespace_violation_severity,
+                )
+                .with_explicit_line_ids_required(state.job.require_explicit_line_ids);
         visitor.visit(file.tree.as_ref());
         state.diagnostics.extend(visitor.diagnostics);
         state.string_table.extend(visitor.string_table_manager);
     }
+    // The lines of excluded nodes are still checked above, but must not end up in the output
+    let excluded_nodes = &state.excluded_nodes;
+    state
+        .string_table
+        .0
+        .retain(|_, string_info| !excluded_nodes.contains(&string_info.node_name));
 
     state
 }
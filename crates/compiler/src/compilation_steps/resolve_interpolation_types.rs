@@ -0,0 +1,28 @@
+use crate::prelude::*;
+use crate::visitors::InterpolationTypeVisitor;
+use antlr_rust::tree::ParseTreeVisitorCompat;
+
+pub(crate) fn resolve_interpolation_types(
+    mut state: CompilationIntermediate,
+) -> CompilationIntermediate {
+    for (file, known_types) in &state.parsed_files {
+        // Error recovery can produce function calls that are not in the source,
+        // which we don't want to warn about.
+        let has_syntax_errors = state.diagnostics.iter().any(|diagnostic| {
+            diagnostic.code == Some(DiagnosticCode::SyntaxError)
+                && diagnostic.file_name.as_ref() == Some(&file.name)
+        });
+        if has_syntax_errors {
+            continue;
+        }
+        let mut visitor = InterpolationTypeVisitor::new(
+            known_types,
+            &state.known_variable_declarations,
+            &mut state.string_table,
+            file.clone(),
+        );
+        visitor.visit(file.tree.as_ref());
+        state.diagnostics.extend(visitor.diagnostics);
+    }
+    state
+}
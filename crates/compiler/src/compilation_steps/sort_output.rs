@@ -0,0 +1,16 @@
+use crate::prelude::*;
+
+/// Not part of the original implementation. See [`Compiler::with_deterministic_output`].
+pub(crate) fn sort_output(mut state: CompilationIntermediate) -> CompilationIntermediate {
+    if !state.job.deterministic_output {
+        return state;
+    }
+    if let Some(Ok(compilation)) = state.result.as_mut() {
+        // Generated declarations, e.g. for tracking visits, come from hash sets.
+        // The sort is stable, so declarations sharing a name stay in source order.
+        compilation
+            .declarations
+            .sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+    }
+    state
+}
@@ -0,0 +1,28 @@
+use crate::prelude::*;
+use crate::visitors::CommandSignatureVisitor;
+use antlr_rust::tree::ParseTreeVisitorCompat;
+
+pub(crate) fn validate_commands(mut state: CompilationIntermediate) -> CompilationIntermediate {
+    if state.job.command_declarations.is_empty() {
+        return state;
+    }
+    for (file, known_types) in &state.parsed_files {
+        // Error recovery can produce commands that are not in the source,
+        // which we don't want to report.
+        let has_syntax_errors = state.diagnostics.iter().any(|diagnostic| {
+            diagnostic.code == Some(DiagnosticCode::SyntaxError)
+                && diagnostic.file_name.as_ref() == Some(&file.name)
+        });
+        if has_syntax_errors {
+            continue;
+        }
+        let mut visitor = CommandSignatureVisitor::new(
+            known_types,
+            &state.job.command_declarations,
+            file.clone(),
+        );
+        visitor.visit(file.tree.as_ref());
+        state.diagnostics.extend(visitor.diagnostics);
+    }
+    state
+}
@@ -0,0 +1,118 @@
+//! Not part of the original implementation. Warns about nodes that jump to each other in a loop
+//! without ever delivering anything to the game, since running them would never finish.
+
+use crate::parser::generated::yarnspinnerparser::*;
+use crate::prelude::*;
+use antlr_rust::token::Token;
+use std::collections::{HashMap, HashSet};
+
+pub(crate) fn validate_jump_loops(mut state: CompilationIntermediate) -> CompilationIntermediate {
+    // Every node is the source of at most one such jump, so the loops are exactly the cycles of the graph
+    // and can be found by following the jumps from each node in turn.
+    let jumps: Vec<UnconditionalJump> = state
+        .parsed_files
+        .iter()
+        .flat_map(|(file, _)| {
+            file.tree
+                .node_all()
+                .into_iter()
+                .filter_map(|node| unconditional_jump(&node, file, &state.node_names))
+        })
+        .collect();
+    let jumps_by_source: HashMap<_, _> = jumps
+        .iter()
+        .enumerate()
+        .map(|(index, jump)| (jump.source.as_str(), index))
+        .collect();
+
+    let mut visited = vec![false; jumps.len()];
+    for start in 0..jumps.len() {
+        let mut path = Vec::new();
+        let mut current = Some(start);
+        while let Some(index) = current.filter(|&index| !visited[index]) {
+            visited[index] = true;
+            path.push(index);
+            current = jumps_by_source.get(jumps[index].target.as_str()).copied();
+        }
+        let Some(loop_start) = current.and_then(|index| path.iter().position(|&i| i == index))
+        else {
+            continue;
+        };
+        let cycle = &path[loop_start..];
+        // Report the loop at the jump that comes first in the source
+        let first = cycle.iter().copied().min().unwrap();
+        let nodes: Vec<_> = cycle
+            .iter()
+            .chain(cycle.first())
+            .map(|&index| jumps[index].source.as_str())
+            .collect();
+        let nodes = nodes.join(" -> ");
+        let jump = &jumps[first];
+        let message = format!(
+            "The jumps {nodes} form a loop that never runs a line, option or command, so the dialogue would never continue"
+        );
+        state.diagnostics.push(
+            Diagnostic {
+                message,
+                ..jump.location.clone()
+            }
+            .with_code(DiagnosticCode::InfiniteJumpLoop)
+            .with_argument("node", &jump.source)
+            .with_argument("nodes", nodes)
+            .with_severity(DiagnosticSeverity::Warning),
+        );
+    }
+    state
+}
+
+/// A `<<jump>>` that a node always runs before delivering anything to the game.
+struct UnconditionalJump {
+    source: String,
+    target: String,
+    /// A diagnostic without a message that points to the jump.
+    location: Diagnostic,
+}
+
+/// Returns the jump that `node` runs first if nothing can come before it but `<<set>>`, `<<declare>>` and `<<call>>` statements.
+/// Any other statement, e.g. an `<<if>>`, a line or a command, might stop the loop, so the jump is not considered unconditional.
+fn unconditional_jump<'input>(
+    node: &NodeContextAll<'input>,
+    file: &FileParseResult<'input>,
+    node_names: &HashSet<String>,
+) -> Option<UnconditionalJump> {
+    let headers = node.header_all();
+    let header_value = |key: &str| {
+        headers
+            .iter()
+            .find(|header| header.header_key.as_ref().unwrap().get_text() == key)
+            .and_then(|header| header.header_value.as_ref())
+            .map(|value| value.get_text().to_owned())
+    };
+    let source = header_value("title")?;
+    // Node groups pick one of their jumps at runtime
+    if header_value("tags").is_some_and(|tags| {
+        tags.split_whitespace()
+            .any(|tag| tag == Node::NODE_GROUP_TAG)
+    }) {
+        return None;
+    }
+    let jump = node.body()?.statement_all().into_iter().find(|statement| {
+        statement.set_statement().is_none()
+            && statement.declare_statement().is_none()
+            && statement.call_statement().is_none()
+    })?;
+    let jump = jump.jump_statement()?;
+    let Jump_statementContextAll::JumpToNodeNameContext(jump) = jump.as_ref() else {
+        // The destination is only known at runtime
+        return None;
+    };
+    let destination = jump.destination.as_ref()?.get_text();
+    let target = resolve_node_name(node_names, destination, Some(&source));
+    Some(UnconditionalJump {
+        source,
+        target,
+        location: Diagnostic::from_message("")
+            .with_file_name(file.name.clone())
+            .with_parser_context(jump, file.tokens()),
+    })
+}
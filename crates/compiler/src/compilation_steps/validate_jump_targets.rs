@@ -0,0 +1,35 @@
+use crate::prelude::generated::yarnspinnerparser::{DialogueContextAttrs, NodeContextAttrs};
+use crate::prelude::*;
+use crate::visitors::JumpTargetVisitor;
+use antlr_rust::token::Token;
+use antlr_rust::tree::ParseTreeVisitorCompat;
+
+pub(crate) fn validate_jump_targets(mut state: CompilationIntermediate) -> CompilationIntermediate {
+    // Jumps can target nodes in any file of the compilation,
+    // so we need to know all node names before checking any of them.
+    // Later steps resolve jumps against them as well.
+    state.node_names = state
+        .parsed_files
+        .iter()
+        .flat_map(|(file, _)| file.tree.node_all())
+        .filter_map(|node| {
+            node.header_all()
+                .iter()
+                .find(|header| header.header_key.as_ref().unwrap().get_text() == "title")
+                .and_then(|title_header| {
+                    title_header
+                        .header_value
+                        .as_ref()
+                        .map(|value| value.get_text().to_owned())
+                })
+        })
+        .collect();
+
+    for (file, _) in &state.parsed_files {
+        let mut visitor = JumpTargetVisitor::new(&state.node_names, file.clone())
+            .with_excluded_nodes(&state.excluded_nodes);
+        visitor.visit(file.tree.as_ref());
+        state.diagnostics.extend(visitor.diagnostics);
+    }
+    state
+}
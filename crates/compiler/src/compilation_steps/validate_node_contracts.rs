@@ -0,0 +1,298 @@
+//! Not part of the original implementation. Checks the `ensures` and `requires` headers of nodes,
+//! see [`Compiler::with_strict_contracts`].
+
+use crate::prelude::*;
+use std::collections::HashSet;
+use yarnspinner_core::prelude::OpCode;
+
+pub(crate) fn validate_node_contracts(
+    mut state: CompilationIntermediate,
+) -> CompilationIntermediate {
+    let Some(Ok(compilation)) = state.result.as_ref() else {
+        return state;
+    };
+    let Some(program) = compilation.program.as_ref() else {
+        return state;
+    };
+    let violation_severity = if state.job.strict_contracts {
+        DiagnosticSeverity::Error
+    } else {
+        DiagnosticSeverity::Warning
+    };
+    let mut diagnostics = Vec::new();
+    for graph_node in &state.node_graph.nodes {
+        let Some(node) = program.nodes.get(&graph_node.name) else {
+            continue;
+        };
+        let source = state
+            .job
+            .files
+            .iter()
+            .find(|file| file.file_name == graph_node.file_name)
+            .map(|file| file.source.as_str())
+            .unwrap_or_default();
+        let instruction_line = |pc: usize| {
+            compilation
+                .debug_info
+                .get(&node.name)
+                .and_then(|debug_info| debug_info.line_positions.get(&pc).copied().flatten())
+                .map(|position| position.line)
+        };
+        let header_line = |key: &str, variable: &str| {
+            find_header_line(source, graph_node.range.start.line, key, variable)
+                .unwrap_or(graph_node.range.start.line)
+        };
+        let diagnostic = |message: String, code: DiagnosticCode, variable: &str, line: usize| {
+            let context = source.lines().nth(line).unwrap_or_default();
+            Diagnostic::from_message(message)
+                .with_code(code)
+                .with_argument("node", &node.name)
+                .with_argument("variable", variable)
+                .with_range(
+                    Position { line, character: 0 }..Position {
+                        line,
+                        character: context.chars().count(),
+                    },
+                )
+                .with_context(context)
+                .with_start_line(line)
+                .with_file_name(&graph_node.file_name)
+        };
+
+        for variable in header_variables(node, "requires") {
+            let is_read = node.instructions.iter().any(|instruction| {
+                opcode(instruction) == OpCode::PushVariable
+                    && instruction.read_operand::<String>(0) == variable
+            });
+            if !is_read {
+                diagnostics.push(
+                    diagnostic(
+                        format!(
+                            "Node \"{}\" requires {variable}, but never reads it",
+                            node.name
+                        ),
+                        DiagnosticCode::StaleContract,
+                        variable,
+                        header_line("requires", variable),
+                    )
+                    .with_severity(violation_severity),
+                );
+            }
+        }
+
+        for variable in header_variables(node, "ensures") {
+            match verify_ensures(program, node, variable) {
+                Verdict::Fulfilled => {}
+                Verdict::Unfulfilled { branch } => {
+                    let line = branch
+                        .and_then(instruction_line)
+                        .unwrap_or_else(|| header_line("ensures", variable));
+                    let path = if branch.is_some() {
+                        "when taking this branch"
+                    } else {
+                        "at all"
+                    };
+                    diagnostics.push(
+                        diagnostic(
+                            format!(
+                                "Node \"{}\" ensures that {variable} is set, but does not set it {path}",
+                                node.name
+                            ),
+                            DiagnosticCode::UnfulfilledContract,
+                            variable,
+                            line,
+                        )
+                        .with_severity(violation_severity),
+                    );
+                }
+                Verdict::Unverifiable { pc, reason } => {
+                    let line =
+                        instruction_line(pc).unwrap_or_else(|| header_line("ensures", variable));
+                    diagnostics.push(
+                        diagnostic(
+                            format!(
+                                "Can't verify that node \"{}\" always sets {variable} because it {reason}",
+                                node.name
+                            ),
+                            DiagnosticCode::UnverifiableContract,
+                            variable,
+                            line,
+                        )
+                        .with_severity(DiagnosticSeverity::Info),
+                    );
+                }
+            }
+        }
+    }
+    state.diagnostics.extend(diagnostics);
+    state
+}
+
+enum Verdict {
+    Fulfilled,
+    /// `branch` is the instruction that chose the path on which the variable is not set,
+    /// or `None` if the node doesn't branch before completing.
+    Unfulfilled {
+        branch: Option<usize>,
+    },
+    Unverifiable {
+        pc: usize,
+        reason: &'static str,
+    },
+}
+
+/// Follows every path through the node's instructions until it either sets the variable or completes the node.
+/// Paths that stop the dialogue don't complete the node, so they don't need to set the variable.
+fn verify_ensures(program: &Program, node: &Node, variable: &str) -> Verdict {
+    let mut unfulfilled = None;
+    // Only paths that have not set the variable yet are followed,
+    // so two of them meeting at the same instruction can share the rest of the walk.
+    let mut visited = HashSet::new();
+    let mut paths: Vec<(usize, Option<usize>)> = vec![(0, None)];
+    while let Some((pc, branch)) = paths.pop() {
+        if !visited.insert(pc) {
+            continue;
+        }
+        let Some(instruction) = node.instructions.get(pc) else {
+            unfulfilled.get_or_insert(branch);
+            continue;
+        };
+        match opcode(instruction) {
+            OpCode::StoreVariable if instruction.read_operand::<String>(0) == variable => {}
+            OpCode::Stop => {}
+            OpCode::Return => {
+                unfulfilled.get_or_insert(branch);
+            }
+            OpCode::RunNode => match pushed_node_name(node, pc) {
+                Some(target) if target != node.name => {
+                    unfulfilled.get_or_insert(branch);
+                }
+                _ => {
+                    return Verdict::Unverifiable {
+                        pc,
+                        reason: "may jump back into itself",
+                    }
+                }
+            },
+            OpCode::DetourToNode => {
+                let target_ensures = pushed_node_name(node, pc)
+                    .and_then(|target| program.nodes.get(&target))
+                    .is_some_and(|target| {
+                        header_variables(target, "ensures").any(|v| v == variable)
+                    });
+                if !target_ensures {
+                    paths.push((pc + 1, branch));
+                }
+            }
+            OpCode::JumpTo => {
+                let target = label_target(node, &instruction.read_operand::<String>(0));
+                if target <= pc {
+                    return Verdict::Unverifiable {
+                        pc,
+                        reason: "loops",
+                    };
+                }
+                paths.push((target, branch));
+            }
+            OpCode::JumpIfFalse => {
+                let target = label_target(node, &instruction.read_operand::<String>(0));
+                if target <= pc {
+                    return Verdict::Unverifiable {
+                        pc,
+                        reason: "loops",
+                    };
+                }
+                paths.push((target, Some(pc)));
+                paths.push((pc + 1, Some(pc)));
+            }
+            OpCode::Jump => {
+                // The destination is computed from the chosen option or saliency candidate,
+                // so every one of them is a possible branch.
+                let destinations: Vec<_> = node
+                    .instructions
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(candidate_pc, candidate)| match opcode(candidate) {
+                        OpCode::AddOption => {
+                            Some((candidate_pc, candidate.read_operand::<String>(1)))
+                        }
+                        OpCode::AddSaliencyCandidate => {
+                            Some((candidate_pc, candidate.read_operand::<String>(2)))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                if destinations.is_empty() {
+                    return Verdict::Unverifiable {
+                        pc,
+                        reason: "jumps to a destination that is only known at runtime",
+                    };
+                }
+                for (candidate_pc, label) in destinations.into_iter().rev() {
+                    let target = label_target(node, &label);
+                    if target <= pc {
+                        return Verdict::Unverifiable {
+                            pc,
+                            reason: "loops",
+                        };
+                    }
+                    paths.push((target, Some(candidate_pc)));
+                }
+            }
+            _ => paths.push((pc + 1, branch)),
+        }
+    }
+    match unfulfilled {
+        Some(branch) => Verdict::Unfulfilled { branch },
+        None => Verdict::Fulfilled,
+    }
+}
+
+fn opcode(instruction: &Instruction) -> OpCode {
+    instruction
+        .opcode
+        .try_into()
+        .expect("Compiled instructions have valid opcodes")
+}
+
+fn label_target(node: &Node, label: &str) -> usize {
+    node.labels[label] as usize
+}
+
+/// The name of the node that a [`OpCode::RunNode`] or [`OpCode::DetourToNode`] at `pc` goes to,
+/// if it is a constant instead of an expression.
+fn pushed_node_name(node: &Node, pc: usize) -> Option<String> {
+    let previous = node.instructions.get(pc.checked_sub(1)?)?;
+    (opcode(previous) == OpCode::PushString).then(|| previous.read_operand(0))
+}
+
+/// The variables listed by all headers with the given key, separated by whitespace or commas.
+fn header_variables<'a>(node: &'a Node, key: &'a str) -> impl Iterator<Item = &'a str> {
+    node.headers
+        .iter()
+        .filter(move |header| header.key == key)
+        .flat_map(|header| header.value.split(|c: char| c.is_whitespace() || c == ','))
+        .filter(|variable| !variable.is_empty())
+}
+
+/// Finds the line of the header with the given key that lists the variable,
+/// searching the node's headers from `node_start_line` on.
+fn find_header_line(
+    source: &str,
+    node_start_line: usize,
+    key: &str,
+    variable: &str,
+) -> Option<usize> {
+    source
+        .lines()
+        .enumerate()
+        .skip(node_start_line)
+        .take_while(|(_, line)| line.trim() != "---")
+        .find(|(_, line)| {
+            line.trim_start()
+                .strip_prefix(key)
+                .and_then(|rest| rest.trim_start().strip_prefix(':'))
+                .is_some_and(|values| values.contains(variable))
+        })
+        .map(|(line, _)| line)
+}
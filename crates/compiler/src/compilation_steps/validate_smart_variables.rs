@@ -0,0 +1,86 @@
+//! Not part of the original implementation. Checks that no smart variable is computed from itself,
+//! since reading it would never finish.
+//! The check only needs the parse trees, so it also reports cycles when the compilation stops after the analysis.
+
+use crate::parser::generated::yarnspinnerparser::*;
+use crate::prelude::*;
+use antlr_rust::tree::ParseTree;
+use antlr_rust::TidExt;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+pub(crate) fn validate_smart_variables(
+    mut state: CompilationIntermediate,
+) -> CompilationIntermediate {
+    let declarations: Vec<_> = state
+        .parsed_files
+        .iter()
+        .flat_map(|(file, _)| {
+            let mut declare_statements = Vec::new();
+            find_declare_statements(file.tree.clone(), &mut declare_statements);
+            declare_statements
+                .into_iter()
+                .filter(|declare_statement| is_smart_variable_declaration(declare_statement))
+                .filter_map(move |declare_statement| {
+                    let variable = declare_statement.variable()?;
+                    let expression = declare_statement.expression()?;
+                    Some((file, variable, get_read_variables(expression)))
+                })
+        })
+        .collect();
+    let dependencies: HashMap<_, _> = declarations
+        .iter()
+        .map(|(_, variable, read_variables)| (variable.get_text(), read_variables))
+        .collect();
+
+    for (file, variable, _) in &declarations {
+        let variable_name = variable.get_text();
+        if !depends_on(
+            &dependencies,
+            &variable_name,
+            &variable_name,
+            &mut HashSet::new(),
+        ) {
+            continue;
+        }
+        state.diagnostics.push(
+            Diagnostic::from_message(format!(
+                "Smart variable {variable_name} is computed from itself"
+            ))
+            .with_code(DiagnosticCode::SmartVariableCycle)
+            .with_argument("variable", &variable_name)
+            .with_file_name(&file.name)
+            .with_parser_context(variable.as_ref(), file.tokens()),
+        );
+    }
+    state
+}
+
+fn find_declare_statements<'input>(
+    node: Rc<ActualParserContext<'input>>,
+    declare_statements: &mut Vec<Rc<Declare_statementContext<'input>>>,
+) {
+    for child in node.get_children() {
+        match child.clone().downcast_rc::<Declare_statementContext>() {
+            Ok(declare_statement) => declare_statements.push(declare_statement),
+            Err(_) => find_declare_statements(child, declare_statements),
+        }
+    }
+}
+
+/// Whether the expression of the smart variable `current` reads the smart variable `variable_name`, directly or through other smart variables.
+fn depends_on<'a>(
+    dependencies: &'a HashMap<String, &Vec<String>>,
+    current: &str,
+    variable_name: &str,
+    visited: &mut HashSet<&'a str>,
+) -> bool {
+    let Some(read_variables) = dependencies.get(current) else {
+        return false;
+    };
+    read_variables.iter().any(|dependency| {
+        dependency == variable_name
+            || visited.insert(dependency)
+                && depends_on(dependencies, dependency, variable_name, visited)
+    })
+}
@@ -23,14 +23,9 @@ pub(crate) fn validate_unique_node_names(
         node.header_all()
             .iter()
             .find(|header| header.header_key.as_ref().unwrap().get_text() == "title")
-            .map(|title_header| {
-                let title = title_header
-                    .header_value
-                    .as_ref()
-                    .unwrap()
-                    .get_text()
-                    .to_owned();
-                (title, title_header.clone(), file)
+            .and_then(|title_header| {
+                let title = title_header.header_value.as_ref()?.get_text().to_owned();
+                Some((title, title_header.clone(), file))
             })
     });
 
@@ -52,6 +47,8 @@ pub(crate) fn validate_unique_node_names(
         for (header_context, file) in nodes {
             state.diagnostics.push(
                 Diagnostic::from_message(format!("More than one node is named {name}",))
+                    .with_code(DiagnosticCode::DuplicateNodeName)
+                    .with_argument("node", &name)
                     .with_file_name(file.name.clone())
                     .with_parser_context(header_context.as_ref(), file.tokens()),
             );
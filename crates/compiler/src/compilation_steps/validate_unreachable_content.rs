@@ -0,0 +1,127 @@
+//! Not part of the original implementation. Warns about statements that can never run
+//! because they follow a `<<jump>>`, `<<stop>>` or `<<return>>` in the same block.
+
+use crate::parser::generated::yarnspinnerparser::*;
+use crate::prelude::*;
+use antlr_rust::parser_rule_context::ParserRuleContext;
+use antlr_rust::token::Token;
+use antlr_rust::tree::ParseTree;
+use std::rc::Rc;
+
+pub(crate) fn validate_unreachable_content(
+    mut state: CompilationIntermediate,
+) -> CompilationIntermediate {
+    for (file, _) in &state.parsed_files {
+        for node in file.tree.node_all() {
+            // Node groups consist of nothing but jumps, of which only one is run
+            let is_node_group = node.header_all().iter().any(|header| {
+                header.header_key.as_ref().unwrap().get_text() == "tags"
+                    && header.header_value.as_ref().is_some_and(|tags| {
+                        tags.get_text()
+                            .split_whitespace()
+                            .any(|tag| tag == Node::NODE_GROUP_TAG)
+                    })
+            });
+            if is_node_group {
+                continue;
+            }
+            if let Some(body) = node.body() {
+                check_block(&body.statement_all(), file, &mut state.diagnostics);
+            }
+        }
+    }
+    state
+}
+
+/// Reports the first statement of `statements` that follows an exit from the block, and then checks the nested blocks.
+/// Only the blocks that are run in their entirety count: a jump in one branch of an `<<if>>` or in an option
+/// does not make the statements after the `<<if>>` or the option group unreachable.
+fn check_block<'input>(
+    statements: &[Rc<StatementContextAll<'input>>],
+    file: &FileParseResult<'input>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    // The exit, and whether the statement after it has been reported
+    let mut exit: Option<(&Rc<StatementContextAll<'input>>, bool)> = None;
+    for statement in statements {
+        match &mut exit {
+            Some((exit_statement, reported)) => {
+                if !*reported {
+                    diagnostics.push(unreachable_content(statement, exit_statement, file));
+                    *reported = true;
+                }
+            }
+            None => {
+                check_nested_blocks(statement, file, diagnostics);
+                if is_exit(statement) {
+                    exit = Some((statement, false));
+                }
+            }
+        }
+    }
+}
+
+fn check_nested_blocks<'input>(
+    statement: &StatementContextAll<'input>,
+    file: &FileParseResult<'input>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if let Some(if_statement) = statement.if_statement() {
+        if let Some(if_clause) = if_statement.if_clause() {
+            check_block(&if_clause.statement_all(), file, diagnostics);
+        }
+        for else_if_clause in if_statement.else_if_clause_all() {
+            check_block(&else_if_clause.statement_all(), file, diagnostics);
+        }
+        if let Some(else_clause) = if_statement.else_clause() {
+            check_block(&else_clause.statement_all(), file, diagnostics);
+        }
+    } else if let Some(once_statement) = statement.once_statement() {
+        if let Some(primary_clause) = once_statement.once_primary_clause() {
+            check_block(&primary_clause.statement_all(), file, diagnostics);
+        }
+        if let Some(alternate_clause) = once_statement.once_alternate_clause() {
+            check_block(&alternate_clause.statement_all(), file, diagnostics);
+        }
+    } else if let Some(shortcut_option_statement) = statement.shortcut_option_statement() {
+        for shortcut_option in shortcut_option_statement.shortcut_option_all() {
+            check_block(&shortcut_option.statement_all(), file, diagnostics);
+        }
+    } else {
+        // An indented block of statements
+        check_block(&statement.statement_all(), file, diagnostics);
+    }
+}
+
+/// Whether running the statement always leaves the block it is in.
+fn is_exit(statement: &StatementContextAll) -> bool {
+    statement.jump_statement().is_some()
+        || matches!(command_text(statement).as_deref(), Some("stop" | "return"))
+}
+
+/// Returns the trimmed text of a command, e.g. `stop` for `<<stop>>`, or `None` if the statement is not a command.
+fn command_text(statement: &StatementContextAll) -> Option<String> {
+    statement
+        .command_statement()
+        .and_then(|command| command.command_formatted_text())
+        .map(|text| text.get_text().trim().to_owned())
+}
+
+fn unreachable_content<'input>(
+    statement: &StatementContextAll<'input>,
+    exit: &StatementContextAll<'input>,
+    file: &FileParseResult<'input>,
+) -> Diagnostic {
+    let exit_text = exit.get_text_with_whitespace(file.tokens());
+    let exit_text = exit_text.trim();
+    let line = exit.start().get_line_as_usize();
+    Diagnostic::from_message(format!(
+        "This content can never run, because {exit_text} on line {line} always leaves the block before it"
+    ))
+    .with_code(DiagnosticCode::UnreachableContent)
+    .with_argument("statement", exit_text)
+    .with_argument("line", line)
+    .with_file_name(file.name.clone())
+    .with_parser_context(statement, file.tokens())
+    .with_severity(DiagnosticSeverity::Warning)
+}
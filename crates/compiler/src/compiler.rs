@@ -7,9 +7,13 @@ use yarnspinner_core::prelude::*;
 
 mod add_tags_to_lines;
 pub(crate) mod antlr_rust_ext;
+mod compilation_job_builder;
 pub(crate) mod run_compilation;
 pub(crate) mod utils;
 
+pub use add_tags_to_lines::add_line_tags;
+pub use compilation_job_builder::{CompilationJob, CompilationJobBuilder};
+
 #[allow(missing_docs)]
 pub type Result<T> = std::result::Result<T, CompilerError>;
 
@@ -43,6 +47,32 @@ pub struct Compiler {
 
     /// The declarations for variables.
     pub variable_declarations: Vec<Declaration>,
+
+    /// The declarations for commands, whose types must be [`Type::Function`] describing the command's parameters.
+    /// If this is empty, commands are not validated.
+    pub command_declarations: Vec<Declaration>,
+
+    /// The namespace that all line IDs of this compilation must belong to. See [`Compiler::with_line_id_namespace`].
+    pub line_id_namespace: Option<String>,
+
+    /// The severity of the diagnostic that is produced for explicit line IDs outside of the [`Compiler::line_id_namespace`].
+    /// Defaults to [`DiagnosticSeverity::Error`].
+    pub line_id_namespace_violation_severity: DiagnosticSeverity,
+
+    /// Whether lines without a `#line:` tag produce an error instead of an implicitly generated line ID. See [`Compiler::with_explicit_line_ids_required`].
+    pub require_explicit_line_ids: bool,
+
+    /// The fixed-width dialogue box that lines are wrapped into. See [`Compiler::with_wrap_config`].
+    pub wrap_config: Option<WrapConfig>,
+
+    /// Whether violated node contracts are reported as errors instead of warnings. See [`Compiler::with_strict_contracts`].
+    pub strict_contracts: bool,
+
+    /// Whether the output is ordered deterministically. See [`Compiler::with_deterministic_output`].
+    pub deterministic_output: bool,
+
+    /// The node tags whose nodes are left out of the compiled program. See [`Compiler::with_excluded_node_tags`].
+    pub excluded_node_tags: Vec<String>,
 }
 
 impl Compiler {
@@ -63,6 +93,30 @@ impl Compiler {
         self
     }
 
+    /// Adds Yarn source code that doesn't live on disk to the compilation, e.g. the input of a REPL.
+    /// The `file_name` does not have to exist anywhere; it is only used to attribute diagnostics and lines to the source.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use yarnspinner_compiler::prelude::*;
+    /// let compilation = Compiler::new()
+    ///     .add_source("<repl>", "title: Start\n---\nHello there!\n===\n")
+    ///     .compile()
+    ///     .unwrap();
+    /// assert!(compilation.program.unwrap().nodes.contains_key("Start"));
+    /// ```
+    pub fn add_source(
+        &mut self,
+        file_name: impl Into<String>,
+        source: impl Into<String>,
+    ) -> &mut Self {
+        self.add_file(File {
+            file_name: file_name.into(),
+            source: source.into(),
+        })
+    }
+
     /// Adds a file to the compilation by reading it from disk. Fallible version of [`Compiler::read_file`].
     pub fn try_read_file(&mut self, file_path: impl AsRef<Path>) -> std::io::Result<&mut Self> {
         let file_name = file_path.as_ref().to_string_lossy().to_string();
@@ -97,6 +151,210 @@ impl Compiler {
         self
     }
 
+    /// Adds multiple variable declarations to the compilation, e.g. the [`Compilation::declarations`] of a previous compilation.
+    pub fn declare_variables(
+        &mut self,
+        declarations: impl IntoIterator<Item = Declaration>,
+    ) -> &mut Self {
+        self.variable_declarations.extend(declarations);
+        self
+    }
+
+    /// Adds a command declaration to the compilation. Its type must be a [`Type::Function`] describing the command's parameters.
+    ///
+    /// As soon as at least one command is declared, every command in the compiled files is validated:
+    /// using an undeclared command produces a warning,
+    /// while passing the wrong number or types of arguments to a declared command produces an error.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use yarnspinner_compiler::prelude::*;
+    /// # use yarnspinner_core::prelude::*;
+    /// let mut signature = FunctionType::default();
+    /// signature.add_parameter(Type::String).add_parameter(Type::Number);
+    /// let mut compiler = Compiler::new();
+    /// compiler.declare_command(Declaration::new("walk", signature));
+    /// ```
+    pub fn declare_command(&mut self, declaration: Declaration) -> &mut Self {
+        self.command_declarations.push(declaration);
+        self
+    }
+
+    /// Sets the namespace that all line IDs of this compilation belong to, so that the compilation can be merged
+    /// with compilations of other namespaces without any risk of colliding line IDs. See [`Compilation::merge`].
+    ///
+    /// Implicitly generated line IDs are prefixed with `line:<namespace>.`,
+    /// while explicit `#line:` tags that lack this prefix produce a [`DiagnosticCode::LineIdNamespaceViolation`]
+    /// with the severity set by [`Compiler::with_line_id_namespace_violation_severity`].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use yarnspinner_compiler::prelude::*;
+    /// let compilation = Compiler::new()
+    ///     .add_file(File {
+    ///         file_name: "event.yarn".to_owned(),
+    ///         source: "title: Start\n---\nWelcome to the event! #line:liveops.welcome\n===\n".to_owned(),
+    ///     })
+    ///     .with_line_id_namespace("liveops")
+    ///     .compile()
+    ///     .unwrap();
+    /// assert_eq!(Some("liveops"), compilation.string_table[&"line:liveops.welcome".into()].line_id_namespace.as_deref());
+    /// ```
+    pub fn with_line_id_namespace(&mut self, namespace: impl Into<String>) -> &mut Self {
+        self.line_id_namespace = Some(namespace.into());
+        self
+    }
+
+    /// Sets whether explicit line IDs outside of the [`Compiler::line_id_namespace`] are reported as errors or as warnings.
+    /// By default, they are errors.
+    pub fn with_line_id_namespace_violation_severity(
+        &mut self,
+        severity: DiagnosticSeverity,
+    ) -> &mut Self {
+        self.line_id_namespace_violation_severity = severity;
+        self
+    }
+
+    /// Sets whether every line must be tagged with an explicit `#line:` ID, e.g. for projects that are localized.
+    /// By default, untagged lines are given an implicitly generated line ID, see [`Compilation::contains_implicit_string_tags`].
+    ///
+    /// If set, untagged lines produce a [`DiagnosticCode::MissingLineId`] error instead.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use yarnspinner_compiler::prelude::*;
+    /// let error = Compiler::new()
+    ///     .add_file(File {
+    ///         file_name: "shop.yarn".to_owned(),
+    ///         source: "title: Start\n---\nWelcome to my shop! #line:welcome\nHave a look around.\n===\n".to_owned(),
+    ///     })
+    ///     .with_explicit_line_ids_required(true)
+    ///     .compile()
+    ///     .unwrap_err();
+    /// assert_eq!(Some(DiagnosticCode::MissingLineId), error.0[0].code);
+    /// ```
+    pub fn with_explicit_line_ids_required(&mut self, required: bool) -> &mut Self {
+        self.require_explicit_line_ids = required;
+        self
+    }
+
+    /// Sets the fixed-width dialogue box that lines are wrapped into, e.g. for a retro-style game with a monospace font.
+    ///
+    /// The [`WrapHints`] of every line's base text are then stored in [`StringInfo::wrap_hints`],
+    /// and lines that don't fit into the dialogue box produce a [`DiagnosticCode::LineOverflow`] warning.
+    /// Translated text can be checked against the same configuration with [`wrap_hints`] and [`WrapConfig::for_language`].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use yarnspinner_compiler::prelude::*;
+    /// # use yarnspinner_core::prelude::*;
+    /// let compilation = Compiler::new()
+    ///     .add_file(File {
+    ///         file_name: "shop.yarn".to_owned(),
+    ///         source: "title: Start\n---\nWelcome to my humble shop! #line:welcome\n===\n".to_owned(),
+    ///     })
+    ///     .with_wrap_config(WrapConfig::with_width(16).with_max_lines(2))
+    ///     .compile()
+    ///     .unwrap();
+    /// let hints = compilation.string_table[&"line:welcome".into()].wrap_hints.as_ref().unwrap();
+    /// assert_eq!(2, hints.line_count);
+    /// ```
+    pub fn with_wrap_config(&mut self, wrap_config: WrapConfig) -> &mut Self {
+        self.wrap_config = Some(wrap_config);
+        self
+    }
+
+    /// Sets whether violated node contracts are reported as errors instead of warnings.
+    /// By default, they are warnings.
+    ///
+    /// A node declares its contract with the `ensures` and `requires` headers, each listing variables separated by spaces:
+    /// - `ensures: $met_guard` promises that every path through the node that reaches its end sets `$met_guard`.
+    ///   A path that doesn't produces a [`DiagnosticCode::UnfulfilledContract`] pointing at the branch it takes.
+    ///   Detours into nodes that themselves ensure the variable count as setting it.
+    /// - `requires: $met_guard` documents that the node reads `$met_guard`.
+    ///   If the node never does, the header is stale and produces a [`DiagnosticCode::StaleContract`].
+    ///
+    /// Nodes that loop or jump to computed destinations can't be checked and produce a [`DiagnosticCode::UnverifiableContract`]
+    /// with [`DiagnosticSeverity::Info`] instead, regardless of this setting.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use yarnspinner_compiler::prelude::*;
+    /// let source = "title: Gate\nensures: $met_guard\n---\n<<if $knows_password>>\n    <<set $met_guard to true>>\n<<endif>>\n===\n";
+    /// let error = Compiler::new()
+    ///     .add_file(File {
+    ///         file_name: "gate.yarn".to_owned(),
+    ///         source: source.to_owned(),
+    ///     })
+    ///     .with_strict_contracts(true)
+    ///     .compile()
+    ///     .unwrap_err();
+    /// assert_eq!(Some(DiagnosticCode::UnfulfilledContract), error.0[0].code);
+    /// ```
+    pub fn with_strict_contracts(&mut self, strict: bool) -> &mut Self {
+        self.strict_contracts = strict;
+        self
+    }
+
+    /// Sets whether the output of the compilation is ordered deterministically,
+    /// so that compiling the same files twice produces the same [`Compilation`], down to the order of its lists.
+    /// By default, the order of e.g. the generated [`Compilation::declarations`] may differ between compilations.
+    ///
+    /// Encode the [`Program`] with [`Program::to_bytes`] to get byte-identical files, e.g. for build caches or version control.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use yarnspinner_compiler::prelude::*;
+    /// let compile = || {
+    ///     Compiler::new()
+    ///         .add_file(File {
+    ///             file_name: "story.yarn".to_owned(),
+    ///             source: "title: Start\n---\n<<if visited(\"End\")>>\nAgain?\n<<endif>>\n<<jump End>>\n===\ntitle: End\n---\nBye!\n===\n".to_owned(),
+    ///         })
+    ///         .with_deterministic_output(true)
+    ///         .compile()
+    ///         .unwrap()
+    /// };
+    /// let (first, second) = (compile(), compile());
+    /// assert_eq!(first.declarations, second.declarations);
+    /// assert_eq!(first.program.unwrap().to_bytes(), second.program.unwrap().to_bytes());
+    /// ```
+    pub fn with_deterministic_output(&mut self, deterministic: bool) -> &mut Self {
+        self.deterministic_output = deterministic;
+        self
+    }
+
+    /// Leaves the nodes that carry any of the given tags in their `tags:` header out of the [`Compilation::program`]
+    /// and the [`Compilation::string_table`], e.g. to strip nodes only meant for developers from a shipping build.
+    /// The excluded nodes are still checked for errors, and jumps to them produce a [`DiagnosticCode::JumpToExcludedNode`] warning.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use yarnspinner_compiler::prelude::*;
+    /// let compilation = Compiler::new()
+    ///     .add_source("story.yarn", "title: Start\n---\nHello!\n===\ntitle: Cheats\ntags: debug\n---\nAll items unlocked.\n===\n")
+    ///     .with_excluded_node_tags(["debug"])
+    ///     .compile()
+    ///     .unwrap();
+    /// let program = compilation.program.unwrap();
+    /// assert!(program.nodes.contains_key("Start"));
+    /// assert!(!program.nodes.contains_key("Cheats"));
+    /// ```
+    pub fn with_excluded_node_tags(
+        &mut self,
+        tags: impl IntoIterator<Item = impl Into<String>>,
+    ) -> &mut Self {
+        self.excluded_node_tags = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
     /// Compiles the Yarn files previously added into a [`Compilation`].
     pub fn compile(&self) -> Result<Compilation> {
         run_compilation::compile(self)
@@ -145,6 +403,11 @@ pub enum CompilationType {
 
     /// The compiler will generate a string table only.
     StringsOnly,
+
+    /// The compiler will run every check it can without generating a [`Program`], e.g. for linters and IDEs.
+    /// It derives the declarations, file tags and string table,
+    /// and never fails: all diagnostics, including errors, are returned in [`Compilation::warnings`].
+    Analyze,
 }
 
 #[cfg(test)]
@@ -1,9 +1,13 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner.Compiler/Utils.cs>
 
 use crate::listeners::{DiagnosticVec, UntaggedLineListener};
+use crate::prelude::generated::yarnspinnerlexer;
 use crate::prelude::generated::yarnspinnerparser::YarnSpinnerParserTreeWalker;
 use crate::prelude::*;
-use std::sync::atomic::Ordering;
+use antlr_rust::int_stream::IntStream;
+use antlr_rust::token::Token;
+use antlr_rust::token_stream::TokenStream;
+use std::collections::HashSet;
 
 impl Compiler {
     /// Given Yarn source code, adds line tags to the ends of all lines
@@ -12,9 +16,12 @@ impl Compiler {
     /// This method ensures that it does not generate line
     /// tags that are already present in the file, or present in the
     /// `existing_line_tags` collection.
+    /// The generated tags are deterministic, see [`add_line_tags`].
     ///
     /// Line tags are added to any line of source code that contains
     /// user-visible text: lines, options, and shortcut options.
+    /// Everything else in the source, including comments, whitespace and line endings, is left untouched,
+    /// so running this again on its own output returns `None`.
     ///
     /// ## Parameters
     ///
@@ -26,7 +33,7 @@ impl Compiler {
     /// collection.
     ///
     /// ## Return value
-    /// Returns he modified source code, with line tags added.
+    /// Returns the modified source code, with line tags added.
     /// If all nodes already have line tags, returns `None`.
     pub fn add_tags_to_lines(
         contents: impl Into<String>,
@@ -40,40 +47,98 @@ impl Compiler {
             source: contents,
         };
         let (parse_source, diagnostics) = parse_source(&file, &chars);
-        let tree = parse_source.tree.clone();
         // Were there any error-level diagnostics?
-        if diagnostics.has_errors() {
+        let Some(parse_source) = parse_source.filter(|_| !diagnostics.has_errors()) else {
             // We encountered a parse error. Bail here; we aren't confident in our ability to correctly insert a line tag.
             return Err(CompilerError(diagnostics));
-        }
+        };
+        let tree = parse_source.tree.clone();
+
+        // The line tags already in this file must not be generated again either.
+        let tokens = parse_source.tokens();
+        let existing_line_tags = (0..tokens.size())
+            .map(|index| tokens.get(index))
+            .filter(|token| {
+                token.get_token_type() == yarnspinnerlexer::HASHTAG_TEXT
+                    && token.get_text().starts_with("line:")
+            })
+            .map(|token| LineId(token.get_text().to_owned()))
+            .chain(existing_line_tags)
+            .collect();
 
         // Create the line listener, which will produce TextReplacements for each new line tag.
         let untagged_line_listener =
             Box::new(UntaggedLineListener::new(existing_line_tags, parse_source));
-        let rewritten_nodes = untagged_line_listener.rewritten_lines.clone();
-        let rewrote_anything = untagged_line_listener.rewrote_anything.clone();
+        let insertions = untagged_line_listener.insertions.clone();
 
         // Walk the tree with this listener, and generate text replacements containing line tags.
         YarnSpinnerParserTreeWalker::walk(untagged_line_listener, tree.as_ref());
         // Apply these text replacements to the original source and return it.
-
-        if rewrote_anything.load(Ordering::Relaxed) {
-            let result = rewritten_nodes.take();
-            let mut string = result.join("\n");
-            string.push('\n');
-            Ok(Some(string))
-        } else {
-            Ok(None)
+        let insertions = insertions.take();
+        if insertions.is_empty() {
+            return Ok(None);
+        }
+        let mut insertions = insertions.into_iter().peekable();
+        let mut string = String::with_capacity(file.source.len());
+        let mut source = file.source.chars().peekable();
+        let mut index = 0;
+        loop {
+            while let Some((_, line_id)) = insertions.next_if(|(position, _)| *position == index) {
+                // Separate the tag from the surrounding text, but don't add trailing whitespace
+                if !string.ends_with(char::is_whitespace) {
+                    string.push(' ');
+                }
+                string.push_str(&format!("#{line_id}"));
+                if source
+                    .peek()
+                    .is_some_and(|next| !matches!(next, '\r' | '\n'))
+                {
+                    string.push(' ');
+                }
+            }
+            let Some(character) = source.next() else {
+                break;
+            };
+            string.push(character);
+            index += 1;
         }
+        Ok(Some(string))
     }
 }
 
+/// Adds `#line:` tags to all lines of the given Yarn source code that need one and do not already have one,
+/// so that their IDs are frozen before the lines are sent off for translation.
+/// Returns the source unchanged if all lines are already tagged.
+///
+/// The new tags are derived from the node name and the text of the line, so tagging the same source always produces the same tags.
+/// They never collide with the tags already present in the source or with `existing_ids`,
+/// which should contain the line IDs of all other files in the project.
+/// See [`Compiler::add_tags_to_lines`] for details.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_compiler::prelude::*;
+/// # use std::collections::HashSet;
+/// let source = "title: Start\n---\nHello!\nGoodbye! #line:goodbye\n===\n";
+/// let tagged = add_line_tags(source, &HashSet::new()).unwrap();
+/// assert!(tagged.starts_with("title: Start\n---\nHello! #line:"));
+/// assert!(tagged.ends_with("\nGoodbye! #line:goodbye\n===\n"));
+/// assert_eq!(tagged, add_line_tags(source, &HashSet::new()).unwrap());
+/// assert_eq!(tagged, add_line_tags(&tagged, &HashSet::new()).unwrap());
+/// ```
+pub fn add_line_tags(source: &str, existing_ids: &HashSet<LineId>) -> crate::Result<String> {
+    let existing_ids = existing_ids.iter().cloned().collect();
+    let tagged = Compiler::add_tags_to_lines(source, existing_ids)?;
+    Ok(tagged.unwrap_or_else(|| source.to_owned()))
+}
+
 /// Parses a string of Yarn source code, and produces a [`FileParseResult`]
 /// and (if there were any problems) a collection of [`Diagnostic`]s.
 fn parse_source<'a, 'b: 'a>(
     file: &'b File,
     chars: &'a [u32],
-) -> (FileParseResult<'a>, Vec<Diagnostic>) {
+) -> (Option<FileParseResult<'a>>, Vec<Diagnostic>) {
     let mut diagnostics = Vec::new();
 
     let result = parse_syntax_tree(file, chars, &mut diagnostics);
@@ -0,0 +1,124 @@
+use crate::prelude::*;
+use crate::Result;
+use yarnspinner_core::prelude::*;
+
+/// The name of [`Compiler`] in the original, under which it is created by a [`CompilationJobBuilder`].
+pub type CompilationJob = Compiler;
+
+impl Compiler {
+    /// Creates a [`CompilationJobBuilder`], which assembles a [`CompilationJob`] and checks that it has something to compile.
+    pub fn builder() -> CompilationJobBuilder {
+        CompilationJobBuilder::default()
+    }
+}
+
+/// Builds a [`CompilationJob`] in a single chain of calls. Created by [`Compiler::builder`].
+///
+/// Unlike a [`Compiler`] created by [`Compiler::new`], which compiles to an empty program when no files were added,
+/// [`CompilationJobBuilder::build`] fails in that case, since it is almost certainly a mistake.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_compiler::prelude::*;
+/// # use yarnspinner_core::prelude::*;
+/// let compilation = CompilationJob::builder()
+///     .add_file("story.yarn", "title: Start\n---\nHello there!\n===\n")
+///     .with_library(Library::standard_library())
+///     .compilation_type(CompilationType::FullCompilation)
+///     .build()
+///     .unwrap()
+///     .compile()
+///     .unwrap();
+/// assert!(compilation.program.unwrap().nodes.contains_key("Start"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CompilationJobBuilder {
+    job: CompilationJob,
+}
+
+impl CompilationJobBuilder {
+    /// Adds Yarn source code to the job. See [`Compiler::add_source`].
+    pub fn add_file(mut self, file_name: impl Into<String>, source: impl Into<String>) -> Self {
+        self.job.add_source(file_name, source);
+        self
+    }
+
+    /// Adds variable declarations to the job, e.g. the [`Compilation::declarations`] of a previous compilation.
+    pub fn with_declarations(
+        mut self,
+        declarations: impl IntoIterator<Item = Declaration>,
+    ) -> Self {
+        self.job.declare_variables(declarations);
+        self
+    }
+
+    /// Extends the Yarn function library of the job. See [`Compiler::extend_library`].
+    pub fn with_library(mut self, library: Library) -> Self {
+        self.job.extend_library(library);
+        self
+    }
+
+    /// Sets the compilation type of the job. By default, this is [`CompilationType::FullCompilation`].
+    pub fn compilation_type(mut self, compilation_type: CompilationType) -> Self {
+        self.job.with_compilation_type(compilation_type);
+        self
+    }
+
+    /// Returns the assembled [`CompilationJob`], ready to be compiled with [`Compiler::compile`].
+    ///
+    /// Fails with a [`DiagnosticCode::NoFilesToCompile`] if no files were added.
+    pub fn build(self) -> Result<CompilationJob> {
+        if self.job.files.is_empty() {
+            let diagnostic = Diagnostic::from_message(
+                "No files to compile. Add at least one with CompilationJobBuilder::add_file before building the job",
+            )
+            .with_code(DiagnosticCode::NoFilesToCompile);
+            return Err(CompilerError(vec![diagnostic]));
+        }
+        Ok(self.job)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn building_without_files_fails() {
+        let diagnostics = CompilationJob::builder()
+            .with_library(Library::standard_library())
+            .build()
+            .unwrap_err()
+            .0;
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(Some(DiagnosticCode::NoFilesToCompile), diagnostics[0].code);
+    }
+
+    #[test]
+    fn can_compile_built_job() {
+        let previous = CompilationJob::builder()
+            .add_file("first.yarn", "title: First\n---\n<<set $gold to 5>>\n===\n")
+            .compilation_type(CompilationType::DeclarationsOnly)
+            .build()
+            .unwrap()
+            .compile()
+            .unwrap();
+        let mut library = Library::new();
+        library.add_function("double", |x: f32| x * 2.0);
+        let compilation = CompilationJob::builder()
+            .add_file(
+                "second.yarn",
+                "title: Second\n---\nYou have {double($gold)} gold.\n===\n",
+            )
+            .with_declarations(previous.declarations)
+            .with_library(library)
+            .compilation_type(CompilationType::FullCompilation)
+            .build()
+            .unwrap()
+            .compile()
+            .unwrap();
+        assert!(compilation.program.unwrap().nodes.contains_key("Second"));
+        assert!(compilation.warnings.is_empty());
+    }
+}
@@ -11,17 +11,30 @@ pub(crate) fn compile(compiler: &Compiler) -> Result<Compilation> {
     let compiler_steps: Vec<&CompilationStep> = vec![
         &register_initial_variables,
         &parse_files,
+        &expand_node_groups,
+        &collect_node_tags,
         &register_strings,
+        &compute_wrap_hints,
         &validate_unique_node_names,
+        &validate_jump_targets,
+        &build_node_graph,
+        &validate_jump_loops,
+        &validate_unreachable_content,
         &break_on_job_with_only_strings,
+        &declare_enums,
         &get_declarations,
         &check_types,
+        &resolve_interpolation_types,
+        &validate_commands,
         &find_tracking_nodes,
         &create_declarations_for_tracking_nodes,
         &add_tracking_declarations,
         &resolve_deferred_type_diagnostic,
         &break_on_job_with_only_declarations,
+        &validate_smart_variables,
         &generate_code,
+        &validate_node_contracts,
+        &break_on_analysis_job,
         &add_initial_value_registrations,
     ];
 
@@ -51,7 +64,10 @@ pub(crate) fn compile(compiler: &Compiler) -> Result<Compilation> {
     // Cleaning up diagnostics doesn't change the state but makes sure
     // that diagnostics are unique, there are no errors in the warnings, etc.
     // So we execute it even if we've had early breaks.
-    let result = clean_up_diagnostics(intermediate).result.unwrap();
+    // Sorting the output likewise applies to every compilation type.
+    let result = sort_output(clean_up_diagnostics(intermediate))
+        .result
+        .unwrap();
     result
 }
 
@@ -68,9 +84,18 @@ pub(crate) struct CompilationIntermediate<'input> {
     pub(crate) potential_issues: Vec<DeferredTypeDiagnostic>,
     pub(crate) parsed_files: Vec<(FileParseResult<'input>, KnownTypes)>,
     pub(crate) tracking_nodes: HashSet<String>,
+    /// The generated variables of all `<<once>>` blocks.
+    pub(crate) once_variables: HashSet<String>,
     pub(crate) string_table: StringTableManager,
     pub(crate) diagnostics: Vec<Diagnostic>,
     pub(crate) file_tags: HashMap<String, Vec<String>>,
+    pub(crate) node_graph: NodeGraph,
+    /// The titles of all nodes in the compilation, which jumps and detours are resolved against.
+    pub(crate) node_names: HashSet<String>,
+    /// The tags of every node, as given by its `tags:` header.
+    pub(crate) node_tags: HashMap<String, Vec<String>>,
+    /// The nodes that carry one of the [`Compiler::excluded_node_tags`].
+    pub(crate) excluded_nodes: HashSet<String>,
     pub(crate) early_break: bool,
 }
 
@@ -85,9 +110,14 @@ impl<'input> CompilationIntermediate<'input> {
             potential_issues: Default::default(),
             parsed_files: Default::default(),
             tracking_nodes: Default::default(),
+            once_variables: Default::default(),
             string_table: Default::default(),
             diagnostics: Default::default(),
             file_tags: Default::default(),
+            node_graph: Default::default(),
+            node_names: Default::default(),
+            node_tags: Default::default(),
+            excluded_nodes: Default::default(),
             early_break: Default::default(),
         }
     }
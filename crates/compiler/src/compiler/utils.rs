@@ -8,8 +8,13 @@ use crate::prelude::generated::{yarnspinnerlexer, yarnspinnerparser};
 use crate::prelude::*;
 use antlr_rust::common_token_stream::CommonTokenStream;
 use antlr_rust::input_stream::CodePoint32BitCharStream;
+use antlr_rust::int_stream::{IntStream, EOF as TOKEN_EOF};
+use antlr_rust::parser_rule_context::ParserRuleContext;
 use antlr_rust::token::{Token, TOKEN_DEFAULT_CHANNEL};
+use antlr_rust::token_stream::TokenStream;
+use antlr_rust::tree::{ErrorNode, ParseTree, Tree};
 use antlr_rust::Parser;
+use antlr_rust::TidExt;
 use std::collections::HashSet;
 use std::rc::Rc;
 use yarnspinner_core::prelude::*;
@@ -31,11 +36,128 @@ pub(crate) fn get_line_id_tag<'a>(
         .cloned()
 }
 
+/// Resolves a reference to a node from within the node named `from`, which may be relative to the namespace of `from`.
+/// See [`Node::candidate_names`]. A reference that matches none of the `node_names` is returned unchanged.
+pub(crate) fn resolve_node_name(
+    node_names: &HashSet<String>,
+    reference: &str,
+    from: Option<&str>,
+) -> String {
+    from.and_then(|from| {
+        Node::candidate_names(reference, from).find(|name| node_names.contains(name))
+    })
+    .unwrap_or_else(|| reference.to_owned())
+}
+
+/// The prefix that marks a line as a candidate of a line group, e.g.
+/// ```yarn
+/// => Hello there! <<if $met_before == false>>
+/// => Hello again!
+/// ```
+/// Consecutive candidates form a group of which only the most salient one is run.
+pub(crate) const LINE_GROUP_MARKER: &str = "=>";
+
+pub(crate) fn is_line_group_candidate(line_statement: &Line_statementContext) -> bool {
+    line_statement
+        .line_formatted_text()
+        .is_some_and(|text| text.get_text().trim_start().starts_with(LINE_GROUP_MARKER))
+}
+
+/// Whether the condition of an option is a `<<once>>`, e.g.
+/// ```yarn
+/// -> Buy the map <<once if $gold >= 10>>
+/// ```
+/// Such an option disappears after it has been selected once.
+pub(crate) fn is_once_option(line_statement: &Line_statementContext) -> bool {
+    line_statement
+        .line_condition()
+        .is_some_and(|condition| condition.COMMAND_ONCE().is_some())
+}
+
+/// The position of the `<<once>>` block or option starting at the given context among those of its node, counted in source order.
+/// It identifies the block's or option's variable, see [`Library::generate_unique_once_variable`].
+/// Unlike the line it starts at, it doesn't change when lines are added to or removed from the node around it,
+/// so saved dialogue keeps knowing whether it already ran.
+pub(crate) fn get_once_index(ctx: &ActualParserContext) -> usize {
+    fn find_once_starts(node: Rc<ActualParserContext>, starts: &mut Vec<isize>) {
+        for child in node.get_children() {
+            let is_once_block = child.clone().downcast_rc::<Once_statementContext>().is_ok();
+            let is_once_option = child
+                .clone()
+                .downcast_rc::<Shortcut_optionContext>()
+                .is_ok_and(|option| {
+                    option
+                        .line_statement()
+                        .is_some_and(|line_statement| is_once_option(&line_statement))
+                });
+            if is_once_block || is_once_option {
+                starts.push(child.start().get_token_index());
+            }
+            find_once_starts(child, starts);
+        }
+    }
+
+    let mut node = ctx.get_parent_ctx();
+    while let Some(parent) = node.clone() {
+        if parent.clone().downcast_rc::<NodeContext>().is_ok() {
+            break;
+        }
+        node = parent.get_parent_ctx();
+    }
+    let mut starts = Vec::new();
+    if let Some(node) = node {
+        find_once_starts(node, &mut starts);
+    }
+    let start = ctx.start().get_token_index();
+    starts.iter().filter(|&&other| other < start).count()
+}
+
+/// Whether a `<<declare>>` statement declares a smart variable, i.e. whether its value reads another variable, e.g.
+/// ```yarn
+/// <<declare $is_rich = $gold > 100>>
+/// ```
+/// A smart variable has no stored value. Instead, its expression is evaluated whenever it is read.
+pub(crate) fn is_smart_variable_declaration(declare_statement: &Declare_statementContext) -> bool {
+    declare_statement
+        .expression()
+        .is_some_and(|expression| !get_read_variables(expression).is_empty())
+}
+
+/// The names of the variables read in an expression.
+pub(crate) fn get_read_variables(expression: Rc<ExpressionContextAll>) -> Vec<String> {
+    fn find_variables(node: Rc<ActualParserContext>, variables: &mut Vec<String>) {
+        for child in node.get_children() {
+            match child.clone().downcast_rc::<VariableContext>() {
+                Ok(variable) => variables.push(variable.get_text()),
+                Err(_) => find_variables(child, variables),
+            }
+        }
+    }
+
+    let mut variables = Vec::new();
+    find_variables(expression, &mut variables);
+    variables
+}
+
+/// Whether the declaration is that of an enum case, e.g. `Food.Apple`, which holds the case's raw value as its default value.
+/// Enum cases are constants and not stored in variables.
+pub(crate) fn is_enum_case(declaration: &Declaration) -> bool {
+    let Type::Enum(enum_type) = &declaration.r#type else {
+        return false;
+    };
+    enum_type
+        .cases
+        .iter()
+        .any(|case| enum_type.case_reference(case) == declaration.name)
+}
+
+/// Parses the given file, adding all problems found to `diagnostics`.
+/// Returns [`None`] if the parser gave up entirely, which is reported as an error diagnostic as well.
 pub(crate) fn parse_syntax_tree<'a, 'b: 'a>(
     file: &'b File,
     file_chars: &'a [u32],
     diagnostics: &mut Vec<Diagnostic>,
-) -> FileParseResult<'a> {
+) -> Option<FileParseResult<'a>> {
     // Using 32 bit codepoints because that's how big a Rust `char` is: 4 bytes.
     let input = CodePoint32BitCharStream::new(file_chars);
     let mut lexer = YarnSpinnerLexer::new(input, file.file_name.clone());
@@ -48,7 +170,14 @@ pub(crate) fn parse_syntax_tree<'a, 'b: 'a>(
     lexer.remove_error_listeners();
     lexer.add_error_listener(Box::new(lexer_error_listener));
 
-    let tokens = CommonTokenStream::new(lexer);
+    let mut tokens = CommonTokenStream::new(lexer);
+    // The ANTLR runtime panics when looking back from a token if all tokens before it are off the default channel.
+    // This happens when a file has no tokens on the default channel at all,
+    // e.g. one consisting only of blank lines or `===`s, so such a file is rejected before parsing.
+    let has_no_content = tokens
+        .lt(1)
+        .is_some_and(|token| token.get_channel() != TOKEN_DEFAULT_CHANNEL)
+        && tokens.la(2) == TOKEN_EOF;
     let mut parser = YarnSpinnerParser::with_strategy(tokens, ErrorStrategy::new());
     let parser_error_listener = ParserErrorListener::new(file.clone());
     let parser_error_listener_diagnostics = parser_error_listener.diagnostics.clone();
@@ -58,25 +187,152 @@ pub(crate) fn parse_syntax_tree<'a, 'b: 'a>(
 
     // Must be read exactly here, because the error listeners running during the parse borrow the diagnostics mutably,
     // and we want to read them after.
-    let tree = parser.dialogue().unwrap();
+    let tree = if has_no_content {
+        None
+    } else {
+        Some(parser.dialogue())
+    };
 
     let lexer_diagnostics_borrowed = lexer_diagnostics.borrow();
     let lexer_error_listener_diagnostics_borrowed = lexer_error_listener_diagnostics.borrow();
-    let parser_error_listener_diagnostics_borrowed = parser_error_listener_diagnostics.borrow();
+    let mut parser_diagnostics = parser_error_listener_diagnostics.borrow().clone();
+    if let Some(Ok(tree)) = &tree {
+        parser_diagnostics = limit_syntax_errors_per_node(parser_diagnostics, tree);
+    }
     let new_diagnostics = lexer_error_listener_diagnostics_borrowed
         .iter()
         .chain(lexer_diagnostics_borrowed.iter())
-        .chain(parser_error_listener_diagnostics_borrowed.iter())
-        .cloned();
+        .cloned()
+        .chain(parser_diagnostics);
     diagnostics.extend(new_diagnostics);
 
-    FileParseResult::new(file_name, tree, Rc::new(parser))
+    let error = match tree {
+        Some(Ok(tree)) => return Some(FileParseResult::new(file_name, tree, Rc::new(parser))),
+        Some(Err(error)) => format!("{error:?}"),
+        None => "the file contains no nodes".to_owned(),
+    };
+    diagnostics.push(
+        Diagnostic::from_message(format!("Failed to parse {file_name}: {error}"))
+            .with_code(DiagnosticCode::SyntaxError)
+            .with_file_name(file_name),
+    );
+    None
+}
+
+/// The number of syntax errors reported per node before the rest are suppressed,
+/// since they are most likely caused by the first ones anyways.
+const MAX_SYNTAX_ERRORS_PER_NODE: usize = 10;
+
+fn limit_syntax_errors_per_node(
+    diagnostics: Vec<Diagnostic>,
+    tree: &DialogueContext,
+) -> Vec<Diagnostic> {
+    // Zero-based line ranges, like the ones of diagnostics
+    let node_lines: Vec<_> = tree
+        .node_all()
+        .iter()
+        .map(|node| {
+            node.start().get_line_as_usize().saturating_sub(1)
+                ..=node.stop().get_line_as_usize().saturating_sub(1)
+        })
+        .collect();
+    let mut errors_per_node = vec![0; node_lines.len()];
+    let mut limited_diagnostics = Vec::with_capacity(diagnostics.len());
+    for diagnostic in diagnostics {
+        let node_index = diagnostic.range.as_ref().and_then(|range| {
+            node_lines
+                .iter()
+                .position(|lines| lines.contains(&range.start.line))
+        });
+        let Some(node_index) = node_index else {
+            limited_diagnostics.push(diagnostic);
+            continue;
+        };
+        errors_per_node[node_index] += 1;
+        let reported_errors = errors_per_node[node_index];
+        if reported_errors <= MAX_SYNTAX_ERRORS_PER_NODE {
+            limited_diagnostics.push(diagnostic);
+        } else if reported_errors == MAX_SYNTAX_ERRORS_PER_NODE + 1 {
+            limited_diagnostics.push(Diagnostic {
+                message: "Too many syntax errors in this node, further errors are not reported"
+                    .to_owned(),
+                context: None,
+                arguments: Default::default(),
+                ..diagnostic
+            });
+        }
+    }
+    limited_diagnostics
+}
+
+/// Removes the bodies of all nodes containing syntax errors from the tree,
+/// so that the following compilation steps only analyze the nodes that were parsed successfully.
+/// The headers are kept, so that the broken nodes can still be referred to, e.g. by jumps.
+///
+/// Besides the error nodes left in the tree by the error recovery, a node counts as broken
+/// if a syntax error was reported on one of its lines, since some recoveries don't consume any tokens,
+/// e.g. the one for a missing `<<endif>>`.
+pub(crate) fn remove_bodies_of_broken_nodes(tree: &DialogueContext, diagnostics: &[Diagnostic]) {
+    for node in tree.node_all() {
+        // Zero-based lines, like the ones of diagnostics
+        let lines = node.start().get_line_as_usize().saturating_sub(1)
+            ..=node.stop().get_line_as_usize().saturating_sub(1);
+        let has_syntax_errors = diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+            .filter_map(|diagnostic| diagnostic.range.as_ref())
+            .any(|range| lines.contains(&range.start.line));
+        if has_syntax_errors || node.get_children().any(contains_error_node) {
+            remove_body(&node);
+        }
+    }
+}
+
+fn contains_error_node(ctx: Rc<ActualParserContext>) -> bool {
+    ctx.clone()
+        .downcast_rc::<ErrorNode<YarnSpinnerParserContextType>>()
+        .is_ok()
+        || ctx.get_children().any(contains_error_node)
+}
+
+/// Removes the body of a node, keeping its headers.
+pub(crate) fn remove_body(node: &NodeContext) {
+    let body_index = node
+        .get_children()
+        .position(|child| child.downcast_rc::<BodyContext>().is_ok());
+    if let Some(body_index) = body_index {
+        replace_child(node, body_index, None);
+    }
+}
+
+/// Replaces the child at `index` with `replacement`, or removes it if there is none.
+/// Parse trees can only be modified at their end, so the children following it are removed and added back.
+pub(crate) fn replace_child<'input>(
+    parent: &ActualParserContext<'input>,
+    index: usize,
+    replacement: Option<Rc<ActualParserContext<'input>>>,
+) {
+    let children: Vec<_> = parent.get_children().collect();
+    for _ in index..children.len() {
+        parent.remove_last_child();
+    }
+    for child in replacement
+        .into_iter()
+        .chain(children.into_iter().skip(index + 1))
+    {
+        parent.add_child(child);
+    }
 }
 
 pub(crate) fn get_line_id_for_node_name(name: &str) -> LineId {
     format!("line:{name}").into()
 }
 
+/// Gets the prefix that all line IDs in the given line ID namespace start with, e.g. `line:liveops.`
+pub(crate) fn get_line_id_namespace_prefix(namespace: &str) -> String {
+    format!("line:{namespace}.")
+}
+
 /// Gets the text of the documentation comments that either immediately
 /// precede `context`, or are on the same line as `context`.
 ///
@@ -198,20 +454,17 @@ where
 /// because Rust's type system already guarantees at compile-time that all registered
 /// functions are valid and compatible with Yarn.
 pub(crate) fn get_declarations_from_library(library: &Library) -> Vec<Declaration> {
-    let operators: HashSet<_> = Type::EXPLICITLY_CONSTRUCTABLE
-        .iter()
-        .flat_map(|r#type| {
-            r#type
-                .methods()
-                .names()
-                .map(|name| r#type.get_canonical_name_for_method(name))
-                .collect::<Vec<_>>()
+    // Both the built-in operators and the ones added with `Library::add_operator` use the canonical method names, e.g. "Number.Add"
+    let is_operator = |name: &str| {
+        Type::EXPLICITLY_CONSTRUCTABLE.iter().any(|r#type| {
+            name.strip_prefix(r#type.name())
+                .is_some_and(|method| method.starts_with('.'))
         })
-        .collect();
+    };
     library
         .iter()
         // Operators are type checked by visitors instead
-        .filter(|(name, _function)| !operators.contains(*name))
+        .filter(|(name, _function)| !is_operator(name))
         .map(|(name, function)| {
             let mut function_type = FunctionType::default();
             let parameters = function
@@ -228,6 +481,21 @@ pub(crate) fn get_declarations_from_library(library: &Library) -> Vec<Declaratio
         .collect()
 }
 
+/// Returns the [`Declaration`]s of the functions that the runtime's `Dialogue` adds to its library, see [`Library::add_dialogue_functions`],
+/// so that calls to them are type checked even if the [`Compiler`] was not given that library.
+/// The implementations are never called, only their signatures are used.
+pub(crate) fn get_dialogue_function_declarations() -> Vec<Declaration> {
+    let mut library = Library::new();
+    library.add_dialogue_functions(
+        |_node: String| false,
+        |_node: String| 0.0_f32,
+        || 0.0_f32,
+        |_min: f32, _max: f32| 0.0_f32,
+        |_sides: u32| 1_u32,
+    );
+    get_declarations_from_library(&library)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -253,6 +521,7 @@ mod tests {
         assert_eq!(1, diagnostics.len());
         assert_eq!(
             Diagnostic::from_message("Indentation contains tabs and spaces")
+                .with_code(DiagnosticCode::MixedIndentation)
                 .with_context("\t   ")
                 .with_start_line(3)
                 .with_file_name("test.yarn")
@@ -3,6 +3,7 @@
 use crate::prelude::generated::yarnspinnerparser;
 use crate::prelude::*;
 use antlr_rust::errors::{ANTLRError, InputMisMatchError, NoViableAltError};
+use antlr_rust::int_stream::EOF as TOKEN_EOF;
 use antlr_rust::parser::ParserNodeType;
 use antlr_rust::parser_rule_context::ParserRuleContext;
 use antlr_rust::rule_context::CustomRuleContext;
@@ -14,12 +15,15 @@ use std::rc::Rc;
 
 pub(crate) struct ErrorStrategy<'input, Ctx: ParserNodeType<'input>> {
     default_error_strategy: DefaultErrorStrategy<'input, Ctx>,
+    /// The index of the token at which the last recovery ended.
+    last_recovery_index: Option<isize>,
 }
 
 impl<'input, Ctx: ParserNodeType<'input>> ErrorStrategy<'input, Ctx> {
     pub(crate) fn new() -> Self {
         Self {
             default_error_strategy: DefaultErrorStrategy::new(),
+            last_recovery_index: None,
         }
     }
 }
@@ -30,6 +34,7 @@ better_any::tid! { impl<'i,Ctx> TidAble<'i> for ErrorStrategy<'i,Ctx> where Ctx:
 /// Instead, we have to copy some parts of antlr4rust implementation and relay some calls to the proxied `default_error_strategy`.
 impl<'input, T: Parser<'input>> AntlrErrorStrategy<'input, T> for ErrorStrategy<'input, T::Node> {
     fn reset(&mut self, recognizer: &mut T) {
+        self.last_recovery_index = None;
         self.default_error_strategy.reset(recognizer);
     }
 
@@ -40,11 +45,43 @@ impl<'input, T: Parser<'input>> AntlrErrorStrategy<'input, T> for ErrorStrategy<
         self.default_error_strategy.recover_inline(recognizer)
     }
 
+    /// ## Implementation notes
+    /// This was not in the original, but without it, a failed recovery makes the parser report an error for every following token.
+    /// So when the parser fails again right where the last recovery left off, resynchronizing on the next statement
+    /// did not work out and the rest of the node body is skipped, while the next node is a clean slate.
     fn recover(&mut self, recognizer: &mut T, e: &ANTLRError) -> Result<(), ANTLRError> {
-        self.default_error_strategy.recover(recognizer, e)
+        let index = recognizer.get_input_stream_mut().index();
+        if self.last_recovery_index == Some(index)
+            && is_inside_rule(recognizer, yarnspinnerparser::RULE_body)
+        {
+            let mut token_type = recognizer.get_input_stream_mut().la(1);
+            while token_type != TOKEN_EOF && token_type != yarnspinnerparser::BODY_END {
+                recognizer.consume(self);
+                token_type = recognizer.get_input_stream_mut().la(1);
+            }
+        }
+        let result = self.default_error_strategy.recover(recognizer, e);
+        self.last_recovery_index = Some(recognizer.get_input_stream_mut().index());
+        result
     }
 
+    /// ## Implementation notes
+    /// Not in the original, which predates enums.
+    /// The loop over the cases of an enum is left by an unexpected token instead of a mismatched one,
+    /// so an enum without an `<<endenum>>` would only be reported as extraneous input otherwise.
     fn sync(&mut self, recognizer: &mut T) -> Result<(), ANTLRError> {
+        if !self.in_error_recovery_mode(recognizer)
+            && recognizer.get_parser_rule_context().get_rule_index()
+                == yarnspinnerparser::RULE_enum_statement
+            && recognizer.get_input_stream_mut().la(1) == yarnspinnerparser::BODY_END
+        {
+            let offending_token_index = Some(recognizer.get_input_stream_mut().index());
+            recognizer.notify_error_listeners(
+                "Found <<enum>> without a matching <<endenum>>".to_owned(),
+                offending_token_index,
+                None,
+            );
+        }
         self.default_error_strategy.sync(recognizer)
     }
 
@@ -96,6 +133,18 @@ impl<'input, Ctx: ParserNodeType<'input>> ErrorStrategy<'input, Ctx> {
             // statement, and we got an '<<', 'else', and we weren't able
             // to match that. The programmer included an extra '<<else>>'.
             "More than one <<else>> statement in an <<if>> statement isn't allowed".to_owned()
+        } else if e.start_token.token_type == yarnspinnerparser::COMMAND_START
+            && e.base.offending_token.token_type == yarnspinnerparser::COMMAND_CASE
+        {
+            // We saw a '<<', 'case' where a statement was expected. The
+            // programmer wrote a case outside of an <<enum>>.
+            "Found <<case>> outside of an <<enum>>".to_owned()
+        } else if e.start_token.token_type == yarnspinnerparser::COMMAND_START
+            && e.base.offending_token.token_type == yarnspinnerparser::COMMAND_ENDENUM
+        {
+            // We saw a '<<', 'endenum' where a statement was expected. The
+            // programmer closed an enum that was never opened.
+            "Found <<endenum>> without a matching <<enum>>".to_owned()
         } else if e.start_token.token_type == yarnspinnerparser::COMMAND_START
             && e.base.offending_token.token_type == yarnspinnerparser::COMMAND_END
         {
@@ -145,6 +194,35 @@ impl<'input, Ctx: ParserNodeType<'input>> ErrorStrategy<'input, Ctx> {
                     _ => None,
                 }
             }
+            yarnspinnerparser::RULE_once_statement => match e.base.offending_token.token_type {
+                yarnspinnerparser::BODY_END => {
+                    // We have exited a body in the middle of a once statement.
+                    // The programmer forgot to include an <<endonce>>.
+                    Some("Found <<once>> without a matching <<endonce>>".to_owned())
+                }
+                yarnspinnerparser::COMMAND_ELSE => {
+                    // We saw an else, but we expected to see an endonce. The
+                    // programmer wrote an additional <<else>>.
+                    Some(
+                        "More than one <<else>> statement in a <<once>> statement isn't allowed"
+                            .to_owned(),
+                    )
+                }
+                _ => None,
+            },
+            yarnspinnerparser::RULE_enum_statement
+                if e.base.offending_token.token_type == yarnspinnerparser::BODY_END =>
+            {
+                // We have exited a body in the middle of an enum statement.
+                // The programmer forgot to include an <<endenum>>.
+                Some("Found <<enum>> without a matching <<endenum>>".to_owned())
+            }
+            yarnspinnerparser::RULE_enum_case_statement
+                if e.base.offending_token.token_type == yarnspinnerparser::COMMAND_ENDENUM =>
+            {
+                // We saw an endenum before the first case.
+                Some("An <<enum>> needs at least one <<case>>".to_owned())
+            }
             yarnspinnerparser::RULE_variable
                 if e.base.offending_token.token_type == yarnspinnerparser::FUNC_ID =>
             {
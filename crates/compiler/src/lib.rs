@@ -15,10 +15,13 @@ pub(crate) mod error_strategy;
 mod file_parse_result;
 pub(crate) mod listeners;
 mod output;
+mod parsed_file;
 mod parser;
 pub(crate) mod parser_rule_context_ext;
+mod source_position;
 mod string_table_manager;
 pub(crate) mod token_ext;
+mod translation_merge;
 pub(crate) mod visitors;
 
 pub use crate::compiler::Result;
@@ -31,9 +34,16 @@ pub mod prelude {
         token_ext::*,
     };
     pub use crate::{
-        compiler::{CompilationType, Compiler, File},
-        listeners::{Diagnostic, DiagnosticSeverity, DiagnosticVec},
+        compiler::{
+            add_line_tags, CompilationJob, CompilationJobBuilder, CompilationType, Compiler, File,
+        },
+        listeners::{
+            Diagnostic, DiagnosticCode, DiagnosticSeverity, DiagnosticVec, DiagnosticVocabulary,
+        },
         output::*,
+        parsed_file::*,
+        source_position::*,
+        translation_merge::*,
     };
     pub(crate) use yarnspinner_core::prelude::*;
 }
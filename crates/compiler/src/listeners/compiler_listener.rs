@@ -3,7 +3,7 @@
 use crate::prelude::*;
 use antlr_rust::parser_rule_context::ParserRuleContext;
 use antlr_rust::token::Token;
-use antlr_rust::tree::{ParseTreeListener, ParseTreeVisitorCompat};
+use antlr_rust::tree::ParseTreeListener;
 use std::cell::RefCell;
 use std::collections::HashSet;
 use std::rc::Rc;
@@ -34,18 +34,26 @@ pub(crate) struct CompilerListener<'input> {
     /// Whether we are currently parsing the
     /// current node as a 'raw text' node, or as a fully syntactic node.
     is_current_node_raw_text: bool,
-    file: FileParseResult<'input>,
+    pub(crate) file: FileParseResult<'input>,
+    /// The library of the compilation job. Consulted for operators that were added to types with [`Library::add_operator`].
+    pub(crate) library: Library,
+    /// The titles of all nodes in the compilation. Used to resolve jumps relative to the namespace of the current node.
+    pub(crate) node_names: HashSet<String>,
     label_count: usize,
 }
 
 impl<'input> CompilerListener<'input> {
     pub(crate) fn new(
         tracking_nodes: HashSet<String>,
+        node_names: HashSet<String>,
         types: KnownTypes,
         file: FileParseResult<'input>,
+        library: Library,
     ) -> Self {
         Self {
             file,
+            library,
+            node_names,
             types,
             tracking_nodes: Rc::new(RefCell::new(tracking_nodes)),
             current_node: Default::default(),
@@ -68,6 +76,20 @@ impl<'input> CompilerListener<'input> {
         self.label_count += 1;
         label
     }
+
+    /// Makes `node` the node to which instructions are added, together with `debug_info` describing it.
+    /// Returns the previous node and its debug information, so that they can be restored afterwards.
+    /// Used to generate the expression of a smart variable into a node of its own.
+    pub(crate) fn replace_current_node(
+        &mut self,
+        node: Option<Node>,
+        debug_info: DebugInfo,
+    ) -> (Option<Node>, DebugInfo) {
+        (
+            std::mem::replace(&mut self.current_node, node),
+            std::mem::replace(&mut self.current_debug_info, debug_info),
+        )
+    }
 }
 
 impl<'input> ParseTreeListener<'input, YarnSpinnerParserContextType> for CompilerListener<'input> {}
@@ -86,6 +108,7 @@ impl<'input> YarnSpinnerParserListener<'input> for CompilerListener<'input> {
             // We don't have a name for this node. We can't emit code for it.
             self.diagnostics.borrow_mut().push(
                 Diagnostic::from_message("Missing title header for node")
+                    .with_code(DiagnosticCode::MissingNodeTitle)
                     .with_file_name(self.file.name.clone())
                     .with_parser_context(ctx, self.file.tokens()),
             );
@@ -136,7 +159,7 @@ impl<'input> YarnSpinnerParserListener<'input> for CompilerListener<'input> {
             }
             "tags" => {
                 // Split the list of tags by spaces, and use that
-                let tags = header_value.split(' ').map(|s| s.to_owned());
+                let tags = header_value.split_whitespace().map(|s| s.to_owned());
                 current_node.tags.extend(tags);
                 if current_node.tags.contains(&"rawText".to_owned()) {
                     // This is a raw text node. Flag it as such for future compilation.
@@ -169,9 +192,12 @@ impl<'input> YarnSpinnerParserListener<'input> for CompilerListener<'input> {
             let track = (self.tracking_nodes.borrow().contains(&current_node.name))
                 .then(|| Library::generate_unique_visited_variable_for_node(&current_node.name));
 
+            let is_node_group = current_node.is_node_group();
             let mut visitor = CodeGenerationVisitor::new(self, track);
-            for statement in ctx.statement_all() {
-                visitor.visit(statement.as_ref());
+            if is_node_group {
+                visitor.generate_code_for_node_group(&ctx.statement_all());
+            } else {
+                visitor.generate_code_for_statements(&ctx.statement_all());
             }
         } else {
             // We are a rawText node. Don't compile it; instead, note the string
@@ -196,8 +222,9 @@ impl<'input> YarnSpinnerParserListener<'input> for CompilerListener<'input> {
         if let Some(track) = track {
             CodeGenerationVisitor::generate_tracking_code(self, track);
         }
-        // We have exited the body; emit a 'stop' opcode here.
-        self.emit(Emit::from_op_code(OpCode::Stop).with_source(Position {
+        // We have exited the body; emit a 'return' opcode here.
+        // If nobody detoured into this node, this stops the dialogue.
+        self.emit(Emit::from_op_code(OpCode::Return).with_source(Position {
             line: (ctx.stop().line as usize).saturating_sub(1),
             character: 0,
         }));
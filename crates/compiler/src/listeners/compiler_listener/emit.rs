@@ -18,6 +18,29 @@ impl<'input> CompilerListener<'input> {
             .insert(current_node.instructions.len(), emit.source);
         current_node.instructions.push(instruction);
     }
+
+    /// Starts describing the instructions generated for a statement in the [`DebugInfo`], beginning with the next instruction.
+    /// Returns the index of the description, which is passed to [`CompilerListener::end_statement`] once the statement is generated.
+    pub(crate) fn begin_statement(&mut self, kind: StatementKind, source_text: String) -> usize {
+        let start = self.current_node.as_ref().unwrap().instructions.len();
+        self.current_debug_info
+            .instructions
+            .push(InstructionDebugInfo {
+                kind,
+                instructions: start..start,
+                source_text,
+                option_instructions: Vec::new(),
+            });
+        self.current_debug_info.instructions.len() - 1
+    }
+
+    /// Ends the description started by [`CompilerListener::begin_statement`] after the last emitted instruction.
+    pub(crate) fn end_statement(&mut self, index: usize) -> &mut InstructionDebugInfo {
+        let end = self.current_node.as_ref().unwrap().instructions.len();
+        let statement = &mut self.current_debug_info.instructions[index];
+        statement.instructions.end = end;
+        statement
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -1,5 +1,6 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner.Compiler/ErrorListener.cs>
 
+use crate::prelude::generated::yarnspinnerlexer;
 use crate::prelude::generated::yarnspinnerparser::YarnSpinnerParserContextType;
 use crate::prelude::generated::yarnspinnerparserlistener::YarnSpinnerParserListener;
 use crate::prelude::*;
@@ -11,11 +12,13 @@ use antlr_rust::token::Token;
 use antlr_rust::token_factory::TokenFactory;
 use antlr_rust::tree::ParseTreeListener;
 pub use diagnostic::*;
+pub use diagnostic_vocabulary::*;
 use std::cell::RefCell;
 use std::rc::Rc;
 use yarnspinner_core::prelude::*;
 
 mod diagnostic;
+mod diagnostic_vocabulary;
 pub(crate) struct LexerErrorListener {
     pub(crate) diagnostics: RefCell<Vec<Diagnostic>>,
     file_name: String,
@@ -51,6 +54,7 @@ impl<'input, T: Recognizer<'input>> ErrorListener<'input, T> for LexerErrorListe
         };
         self.diagnostics.borrow_mut().push(
             Diagnostic::from_message(msg)
+                .with_code(DiagnosticCode::SyntaxError)
                 .with_range(range)
                 .with_file_name(&self.file_name),
         );
@@ -94,9 +98,16 @@ impl<'input, T: Recognizer<'input>> ErrorListener<'input, T> for ParserErrorList
             character: (column + 1) as usize,
         };
         let mut diagnostic = Diagnostic::from_message(msg)
+            .with_code(DiagnosticCode::SyntaxError)
             .with_file_name(&self.file.file_name)
             .with_range(range);
         if let Some(offending_symbol) = offending_symbol {
+            if offending_symbol.get_token_type() == yarnspinnerlexer::UNESCAPABLE_CHARACTER {
+                self.diagnostics
+                    .borrow_mut()
+                    .push(self.invalid_escape_sequence(offending_symbol));
+                return;
+            }
             let mut string = String::new();
 
             // the line with the error on it
@@ -143,5 +154,36 @@ impl<'input, T: Recognizer<'input>> ErrorListener<'input, T> for ParserErrorList
     }
 }
 
+impl ParserErrorListener {
+    /// This was not in the original, but the lexer hides the backslash of an escape sequence and emits
+    /// the escaped character as [`yarnspinnerlexer::UNESCAPABLE_CHARACTER`] if it can't be escaped,
+    /// which would otherwise be reported as an unexpected token right after the backslash.
+    /// The parser reports this token more than once, but since the diagnostics are identical, they are deduplicated later.
+    fn invalid_escape_sequence(&self, offending_symbol: &(impl Token + ?Sized)) -> Diagnostic {
+        let character = offending_symbol.get_text().to_display();
+        let line = offending_symbol.get_line_as_usize().saturating_sub(1);
+        let column = offending_symbol.get_column_as_usize();
+        let context = self.file.source.lines().nth(line).unwrap_or_default();
+        Diagnostic::from_message(format!(
+            "\"\\{character}\" is not a valid escape sequence. \
+            Only {{, }}, [, ], <, #, / and \\ can be escaped with a backslash"
+        ))
+        .with_code(DiagnosticCode::InvalidEscapeSequence)
+        .with_argument("character", &character)
+        .with_file_name(&self.file.file_name)
+        .with_context(context)
+        .with_start_line(line)
+        .with_range(
+            Position {
+                line,
+                character: column.saturating_sub(1),
+            }..Position {
+                line,
+                character: column + character.chars().count(),
+            },
+        )
+    }
+}
+
 impl<'input> ParseTreeListener<'input, YarnSpinnerParserContextType> for ParserErrorListener {}
 impl<'input> YarnSpinnerParserListener<'input> for ParserErrorListener {}
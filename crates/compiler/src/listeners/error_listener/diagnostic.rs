@@ -5,6 +5,7 @@ use antlr_rust::rule_context::CustomRuleContext;
 use antlr_rust::token::Token;
 use antlr_rust::token_factory::TokenFactory;
 use core::fmt;
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 use std::ops::Range;
 use yarnspinner_core::prelude::*;
@@ -33,6 +34,7 @@ pub struct Diagnostic {
     pub file_name: Option<String>,
 
     /// The range of the file indicated by the [`Diagnostic::file_name`] that the issue occurred in.
+    /// Use [`SourcePosition`] to convert it to byte offsets.
     pub range: Option<Range<Position>>,
 
     /// The description of the issue.
@@ -46,6 +48,15 @@ pub struct Diagnostic {
 
     /// The line the context starts on.
     pub start_line: usize,
+
+    /// The stable code identifying the kind of issue, if it has one.
+    pub code: Option<DiagnosticCode>,
+
+    /// The values the message was built from, e.g. the name of a function, keyed by placeholder name.
+    /// These can be referenced by the templates of a [`DiagnosticVocabulary`].
+    /// See [`DiagnosticCode::placeholders`] for the ones each kind of issue provides.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub arguments: BTreeMap<String, String>,
 }
 
 impl Diagnostic {
@@ -57,6 +68,8 @@ impl Diagnostic {
             context: Default::default(),
             severity: Default::default(),
             start_line: Default::default(),
+            code: Default::default(),
+            arguments: Default::default(),
         }
     }
 
@@ -103,6 +116,16 @@ impl Diagnostic {
         self.severity = severity;
         self
     }
+
+    pub(crate) fn with_code(mut self, code: DiagnosticCode) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    pub(crate) fn with_argument(mut self, name: &str, value: impl ToString) -> Self {
+        self.arguments.insert(name.to_owned(), value.to_string());
+        self
+    }
 }
 
 impl Display for Diagnostic {
@@ -111,11 +134,12 @@ impl Display for Diagnostic {
         let annotation_type = match self.severity {
             DiagnosticSeverity::Error => AnnotationType::Error,
             DiagnosticSeverity::Warning => AnnotationType::Warning,
+            DiagnosticSeverity::Info => AnnotationType::Info,
         };
         let snippet = Snippet {
             title: Some(Annotation {
                 label: Some(label),
-                id: None,
+                id: self.code.map(DiagnosticCode::as_str),
                 annotation_type,
             }),
             footer: vec![],
@@ -181,10 +205,6 @@ impl DiagnosticVec for Vec<Diagnostic> {
 }
 
 /// The severity of the issue.
-///
-/// ## Implementation notes
-///
-/// The `Info` variant was not implemented because it was unused.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
 #[cfg_attr(feature = "bevy", derive(Reflect))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -206,6 +226,12 @@ pub enum DiagnosticSeverity {
     /// Warnings represent possible problems that the user should fix,
     /// but do not cause the compilation process to fail.
     Warning,
+
+    /// An informational note.
+    ///
+    /// Notes point out things the compiler could not check, e.g. an unverifiable node contract,
+    /// and never cause the compilation process to fail.
+    Info,
 }
 
 impl fmt::Display for DiagnosticSeverity {
@@ -213,6 +239,179 @@ impl fmt::Display for DiagnosticSeverity {
         match self {
             DiagnosticSeverity::Error => f.write_str("Error"),
             DiagnosticSeverity::Warning => f.write_str("Warning"),
+            DiagnosticSeverity::Info => f.write_str("Info"),
         }
     }
 }
+
+/// A stable code identifying the kind of issue a [`Diagnostic`] describes.
+/// Unlike the message, the code does not change between versions, so tools can rely on it.
+///
+/// ## Implementation notes
+///
+/// The original only has human-readable messages. The codes were added for tooling integrations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub enum DiagnosticCode {
+    /// `YS0001`: The source could not be lexed or parsed.
+    SyntaxError,
+    /// `YS0002`: A line's indentation mixes tabs and spaces.
+    MixedIndentation,
+    /// `YS0003`: More than one node has the same title.
+    DuplicateNodeName,
+    /// `YS0004`: A node has no `title` header.
+    MissingNodeTitle,
+    /// `YS0005`: A node's title contains characters that are not allowed.
+    InvalidNodeName,
+    /// `YS0006`: A variable was declared more than once.
+    DuplicateDeclaration,
+    /// `YS0007`: A type is unknown or not permitted.
+    UnknownType,
+    /// `YS0008`: A variable declaration's default value is not a constant.
+    NonConstantDeclaration,
+    /// `YS0009`: A number literal could not be parsed.
+    InvalidNumber,
+    /// `YS0010`: A value's type does not match the expected type.
+    TypeMismatch,
+    /// `YS0011`: The type of a variable or expression could not be determined.
+    UndeterminedType,
+    /// `YS0012`: A function was called with the wrong number of parameters.
+    WrongParameterCount,
+    /// `YS0013`: More than one line has the same line ID.
+    DuplicateLineId,
+    /// `YS0014`: A variable declaration has no default value.
+    NullDefaultValue,
+    /// `YS0015`: A `<<jump>>` targets a node that does not exist.
+    MissingJumpTarget,
+    /// `YS0016`: A constant expression divides by zero.
+    DivisionByZero,
+    /// `YS0017`: A function is called without having been declared.
+    UndeclaredFunction,
+    /// `YS0018`: A command is used without having been declared, while other commands were declared.
+    UndeclaredCommand,
+    /// `YS0019`: A line ID lies outside of the line ID namespace of its compilation,
+    /// or multiple compilations that are merged share a line ID namespace.
+    LineIdNamespaceViolation,
+    /// `YS0020`: A line does not fit into the dialogue box configured by [`Compiler::with_wrap_config`],
+    /// either because it wraps into too many lines or because it contains a word that is wider than the box.
+    LineOverflow,
+    /// `YS0021`: A node's `ensures` header names a variable that is not set on every path through the node.
+    UnfulfilledContract,
+    /// `YS0022`: A node's `requires` header names a variable that the node never reads.
+    StaleContract,
+    /// `YS0023`: A node's `ensures` header can't be verified because the node loops or jumps to a computed destination.
+    UnverifiableContract,
+    /// `YS0024`: A backslash in a line escapes a character that can't be escaped.
+    InvalidEscapeSequence,
+    /// `YS0025`: A read-only variable is assigned a value, e.g. a smart variable, whose value is computed from its declaration. See [`Declaration::is_read_only`].
+    ReadOnlyVariable,
+    /// `YS0026`: The declaration of a smart variable refers back to the variable itself, possibly through other smart variables.
+    SmartVariableCycle,
+    /// `YS0027`: A line has no `#line:` tag, while [`Compiler::with_explicit_line_ids_required`] forbids generating one.
+    MissingLineId,
+    /// `YS0028`: Nodes jump to each other in a loop without running any line, option or command in between, so the dialogue would never continue.
+    InfiniteJumpLoop,
+    /// `YS0029`: An enum is declared incorrectly, e.g. a `<<case>>` outside of an `<<enum>>`, or a case that no enum declares is used.
+    InvalidEnum,
+    /// `YS0030`: A jump leads to a node that is left out of the program because of its tags. See [`Compiler::with_excluded_node_tags`].
+    JumpToExcludedNode,
+    /// `YS0031`: No files were added to a job built with [`CompilationJobBuilder::build`].
+    NoFilesToCompile,
+    /// `YS0032`: A translated line interpolates a different number of values than its base text. See [`check_translation_interpolations`].
+    InterpolationCountMismatch,
+    /// `YS0033`: A statement follows a `<<jump>>`, `<<stop>>` or `<<return>>` in the same block, so it can never run.
+    UnreachableContent,
+}
+
+impl DiagnosticCode {
+    /// Returns the code as it is shown to users, e.g. `YS0001`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DiagnosticCode::SyntaxError => "YS0001",
+            DiagnosticCode::MixedIndentation => "YS0002",
+            DiagnosticCode::DuplicateNodeName => "YS0003",
+            DiagnosticCode::MissingNodeTitle => "YS0004",
+            DiagnosticCode::InvalidNodeName => "YS0005",
+            DiagnosticCode::DuplicateDeclaration => "YS0006",
+            DiagnosticCode::UnknownType => "YS0007",
+            DiagnosticCode::NonConstantDeclaration => "YS0008",
+            DiagnosticCode::InvalidNumber => "YS0009",
+            DiagnosticCode::TypeMismatch => "YS0010",
+            DiagnosticCode::UndeterminedType => "YS0011",
+            DiagnosticCode::WrongParameterCount => "YS0012",
+            DiagnosticCode::DuplicateLineId => "YS0013",
+            DiagnosticCode::NullDefaultValue => "YS0014",
+            DiagnosticCode::MissingJumpTarget => "YS0015",
+            DiagnosticCode::DivisionByZero => "YS0016",
+            DiagnosticCode::UndeclaredFunction => "YS0017",
+            DiagnosticCode::UndeclaredCommand => "YS0018",
+            DiagnosticCode::LineIdNamespaceViolation => "YS0019",
+            DiagnosticCode::LineOverflow => "YS0020",
+            DiagnosticCode::UnfulfilledContract => "YS0021",
+            DiagnosticCode::StaleContract => "YS0022",
+            DiagnosticCode::UnverifiableContract => "YS0023",
+            DiagnosticCode::InvalidEscapeSequence => "YS0024",
+            DiagnosticCode::ReadOnlyVariable => "YS0025",
+            DiagnosticCode::SmartVariableCycle => "YS0026",
+            DiagnosticCode::MissingLineId => "YS0027",
+            DiagnosticCode::InfiniteJumpLoop => "YS0028",
+            DiagnosticCode::InvalidEnum => "YS0029",
+            DiagnosticCode::JumpToExcludedNode => "YS0030",
+            DiagnosticCode::NoFilesToCompile => "YS0031",
+            DiagnosticCode::InterpolationCountMismatch => "YS0032",
+            DiagnosticCode::UnreachableContent => "YS0033",
+        }
+    }
+
+    /// Returns the names of the [`Diagnostic::arguments`] that diagnostics with this code may provide.
+    /// Not every diagnostic provides all of them, e.g. a [`DiagnosticCode::TypeMismatch`] between the terms of an operation has no single expected type.
+    ///
+    /// In addition to these, every diagnostic provides `message`, the built-in message, and `code`, e.g. `YS0001`.
+    pub fn placeholders(self) -> &'static [&'static str] {
+        match self {
+            DiagnosticCode::SyntaxError
+            | DiagnosticCode::MixedIndentation
+            | DiagnosticCode::MissingNodeTitle
+            | DiagnosticCode::DivisionByZero
+            | DiagnosticCode::NoFilesToCompile => &[],
+            DiagnosticCode::DuplicateNodeName
+            | DiagnosticCode::InvalidNodeName
+            | DiagnosticCode::MissingJumpTarget => &["node"],
+            DiagnosticCode::DuplicateDeclaration => &["variable", "file"],
+            DiagnosticCode::UnknownType => &["type"],
+            DiagnosticCode::NonConstantDeclaration => &["value"],
+            DiagnosticCode::InvalidNumber => &["number"],
+            DiagnosticCode::TypeMismatch => &["expected", "actual"],
+            DiagnosticCode::UndeterminedType => &["variable", "expression"],
+            DiagnosticCode::WrongParameterCount => &["name", "expected", "actual"],
+            DiagnosticCode::DuplicateLineId => &["line_id"],
+            DiagnosticCode::NullDefaultValue => &["variable", "type"],
+            DiagnosticCode::UndeclaredFunction => &["function"],
+            DiagnosticCode::UndeclaredCommand => &["command"],
+            DiagnosticCode::LineIdNamespaceViolation => &["namespace", "line_id"],
+            DiagnosticCode::LineOverflow | DiagnosticCode::MissingLineId => &["text"],
+            DiagnosticCode::UnfulfilledContract
+            | DiagnosticCode::StaleContract
+            | DiagnosticCode::UnverifiableContract => &["node", "variable"],
+            DiagnosticCode::InvalidEscapeSequence => &["character"],
+            DiagnosticCode::ReadOnlyVariable | DiagnosticCode::SmartVariableCycle => &["variable"],
+            DiagnosticCode::InfiniteJumpLoop => &["node", "nodes"],
+            DiagnosticCode::InvalidEnum => &["name"],
+            DiagnosticCode::JumpToExcludedNode => &["node"],
+            DiagnosticCode::InterpolationCountMismatch => &["line_id", "expected", "actual"],
+            DiagnosticCode::UnreachableContent => &["statement", "line"],
+        }
+    }
+}
+
+impl Display for DiagnosticCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
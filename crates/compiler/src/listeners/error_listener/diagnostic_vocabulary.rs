@@ -0,0 +1,126 @@
+use crate::prelude::*;
+use std::collections::HashMap;
+
+/// Rewords the messages of [`Diagnostic`]s using templates, e.g. to use terms that writers without a programming background understand,
+/// or to translate the messages into another language.
+///
+/// A template references the [`Diagnostic::arguments`] by name in curly braces, e.g. `"{function} has not been declared."`.
+/// See [`DiagnosticCode::placeholders`] for the names available per code.
+/// A diagnostic keeps its built-in message if there is no template for its code
+/// or if its template references an argument the diagnostic does not provide.
+///
+/// With the `serde` feature, a vocabulary can be loaded from a RON or JSON file mapping codes to templates:
+///
+/// ```ron
+/// {
+///     UndeclaredFunction: "The function \"{function}\" is used, but the game doesn't know about it.",
+///     WrongParameterCount: "\"{name}\" needs {expected} value(s), but got {actual}.",
+/// }
+/// ```
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_compiler::prelude::*;
+/// let vocabulary = DiagnosticVocabulary::new().with_template(
+///     DiagnosticCode::MissingJumpTarget,
+///     "There is no node called \"{node}\" to jump to.",
+/// );
+/// let compilation = Compiler::new()
+///     .add_file(File {
+///         file_name: "intro.yarn".to_owned(),
+///         source: "title: Start\n---\n<<jump Nowhere>>\n===\n".to_owned(),
+///     })
+///     .compile()
+///     .unwrap();
+/// let warning = vocabulary.reword(compilation.warnings[0].clone());
+/// assert_eq!("There is no node called \"Nowhere\" to jump to.", warning.message);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Default))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct DiagnosticVocabulary {
+    templates: HashMap<DiagnosticCode, String>,
+}
+
+impl DiagnosticVocabulary {
+    /// Creates a new vocabulary without any templates, i.e. one that keeps all built-in messages.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the template used for diagnostics with the given code.
+    pub fn with_template(mut self, code: DiagnosticCode, template: impl Into<String>) -> Self {
+        self.templates.insert(code, template.into());
+        self
+    }
+
+    /// Returns the template used for diagnostics with the given code, if there is one.
+    pub fn template(&self, code: DiagnosticCode) -> Option<&str> {
+        self.templates.get(&code).map(String::as_str)
+    }
+
+    /// Returns the message of the diagnostic as worded by this vocabulary,
+    /// falling back to its built-in message.
+    pub fn message(&self, diagnostic: &Diagnostic) -> String {
+        diagnostic
+            .code
+            .and_then(|code| self.template(code))
+            .and_then(|template| fill_template(template, diagnostic))
+            .unwrap_or_else(|| diagnostic.message.clone())
+    }
+
+    /// Returns the diagnostic with its message replaced by [`DiagnosticVocabulary::message`].
+    pub fn reword(&self, diagnostic: Diagnostic) -> Diagnostic {
+        Diagnostic {
+            message: self.message(&diagnostic),
+            ..diagnostic
+        }
+    }
+
+    /// Loads a vocabulary from a RON file's content.
+    #[cfg(feature = "serde")]
+    pub fn from_ron(ron: &str) -> std::result::Result<Self, ron::error::SpannedError> {
+        ron::from_str(ron)
+    }
+
+    /// Loads a vocabulary from a JSON file's content.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// A vocabulary that rewords the built-in messages in plain English for writers who are not programmers.
+    #[cfg(feature = "serde")]
+    pub fn plain_english() -> Self {
+        Self::from_ron(include_str!("../../../vocabularies/plain_english.ron"))
+            .expect("The bundled plain English vocabulary is valid RON")
+    }
+}
+
+/// Replaces every `{name}` in the template with the argument of the same name,
+/// or returns `None` if the diagnostic does not provide one of them.
+fn fill_template(template: &str, diagnostic: &Diagnostic) -> Option<String> {
+    let mut message = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        message.push_str(&rest[..start]);
+        let placeholder = &rest[start + 1..];
+        let end = placeholder.find('}')?;
+        let value = match &placeholder[..end] {
+            "message" => diagnostic.message.as_str(),
+            "code" => diagnostic.code?.as_str(),
+            name => diagnostic.arguments.get(name)?.as_str(),
+        };
+        message.push_str(value);
+        rest = &placeholder[end + 1..];
+    }
+    message.push_str(rest);
+    Some(message)
+}
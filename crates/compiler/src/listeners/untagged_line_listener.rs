@@ -1,6 +1,8 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner.Compiler/Utils.cs>
 
-use crate::parser::generated::yarnspinnerparser::Line_statementContext;
+use crate::parser::generated::yarnspinnerparser::{
+    Line_statementContext, NodeContext, NodeContextAttrs,
+};
 use crate::prelude::generated::yarnspinnerparser::{
     Line_statementContextAttrs, YarnSpinnerParserContextType,
 };
@@ -11,45 +13,46 @@ use antlr_rust::int_stream::IntStream;
 use antlr_rust::parser_rule_context::ParserRuleContext;
 use antlr_rust::token::Token;
 use antlr_rust::token_stream::TokenStream;
-use antlr_rust::tree::ParseTreeListener;
-use rand::{rngs::SmallRng, Rng, SeedableRng};
+use antlr_rust::tree::{ParseTree, ParseTreeListener};
 use std::cell::RefCell;
 use std::rc::Rc;
-use std::sync::atomic::AtomicBool;
 
 pub(crate) struct UntaggedLineListener<'input> {
     existing_line_tags: Vec<LineId>,
     file: FileParseResult<'input>,
-    pub(crate) rewritten_lines: Rc<RefCell<Vec<String>>>,
-    pub(crate) rewrote_anything: Rc<AtomicBool>,
+    current_node_name: String,
+    /// The new line tags and the index of the character in the source they are inserted before.
+    pub(crate) insertions: Rc<RefCell<Vec<(usize, LineId)>>>,
 }
 
 impl<'input> UntaggedLineListener<'input> {
     pub fn new(existing_line_tags: Vec<LineId>, file: FileParseResult<'input>) -> Self {
-        let original_source = file
-            .tokens()
-            .get_all_text()
-            .lines()
-            .map(|s| s.to_owned())
-            .collect();
         Self {
             existing_line_tags,
             file,
-            rewritten_lines: Rc::new(RefCell::new(original_source)),
-            rewrote_anything: Default::default(),
+            current_node_name: Default::default(),
+            insertions: Default::default(),
         }
     }
 
     /// Generates a new unique line tag that is not present in `existing_line_tags`.
-    fn generate_string(&self) -> LineId {
-        let mut rng = SmallRng::from_entropy();
-        loop {
-            let line: usize = rng.gen_range(0..0x1000000);
-            let tag = LineId(format!("line:{}", line));
-            if !self.existing_line_tags.contains(&tag) {
-                return tag;
-            }
-        }
+    ///
+    /// This was not in the original, but a random number would tag the same source differently on every run.
+    /// Instead, the tag is a hash of the node name, the text of the line and an occurrence index,
+    /// so tagging the same source with the same existing tags always produces the same result.
+    /// Should the hash collide with an existing tag, the occurrence index is increased until the tag is free.
+    fn generate_string(&self, text: &str) -> LineId {
+        (0_u64..)
+            .map(|occurrence| {
+                let hash = hash_parts(&[
+                    self.current_node_name.as_bytes(),
+                    text.as_bytes(),
+                    &occurrence.to_le_bytes(),
+                ]);
+                LineId(format!("line:{:08x}", hash as u32 ^ (hash >> 32) as u32))
+            })
+            .find(|tag| !self.existing_line_tags.contains(tag))
+            .unwrap()
     }
 }
 
@@ -59,6 +62,19 @@ impl<'input> ParseTreeListener<'input, YarnSpinnerParserContextType>
 }
 
 impl<'input> YarnSpinnerParserListener<'input> for UntaggedLineListener<'input> {
+    fn enter_node(&mut self, ctx: &NodeContext<'input>) {
+        self.current_node_name.clear();
+        for header in ctx.header_all() {
+            if header.header_key.as_ref().unwrap().get_text() == "title" {
+                self.current_node_name = header
+                    .header_value
+                    .as_ref()
+                    .map(|value| value.get_text().to_owned())
+                    .unwrap_or_default();
+            }
+        }
+    }
+
     fn exit_line_statement(&mut self, ctx: &Line_statementContext<'input>) {
         // We're looking at a complete line statement.
 
@@ -94,25 +110,21 @@ impl<'input> YarnSpinnerParserListener<'input> for UntaggedLineListener<'input>
         let previous_token = tokens.get(previous_token_index);
 
         // Generate a new, unique line ID.
-        let new_line_id = self.generate_string();
+        let text = ctx
+            .line_formatted_text()
+            .map(|text| text.get_text())
+            .unwrap_or_default();
+        let new_line_id = self.generate_string(&text);
         // Record that we've used this new line ID, so that we don't
         // accidentally use it twice.
         self.existing_line_tags.push(new_line_id.clone());
 
-        let mut lines = self.rewritten_lines.borrow_mut();
-        let line = lines.get_mut(line_index).unwrap();
-
-        let insertion_index = line
-            .char_indices()
-            .map(|(byte_pos, _char)| byte_pos)
-            .nth(previous_token.get_column_as_usize())
-            .unwrap_or_else(||
-                panic!("Internal error: failed to convert char pos to byte pos for insertion index on line {line_index}. \
-                        This is a bug. Please report it at https://github.com/YarnSpinnerTool/YarnSpinner-Rust/issues/new"))
-            + previous_token.get_text().len();
-        line.insert_str(insertion_index, &format!(" #{new_line_id} "));
-        self.rewrote_anything
-            .store(true, std::sync::atomic::Ordering::Relaxed);
+        // Instead of rewriting the lines of the source, we remember where to insert the tag,
+        // so that the rest of the source, including its line endings, is preserved exactly.
+        let insertion_index = usize::try_from(previous_token.get_stop() + 1).unwrap();
+        self.insertions
+            .borrow_mut()
+            .push((insertion_index, new_line_id));
     }
 }
 
@@ -1,15 +1,18 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner.Compiler/CompilationResult.cs>
 
 use crate::listeners::*;
-pub use crate::output::{debug_info::*, declaration::*, string_info::*};
+pub use crate::output::{declaration::*, node_graph::*, string_info::*};
 use crate::prelude::*;
+use crate::Result;
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{Debug, Display};
 use yarnspinner_core::prelude::*;
+pub use yarnspinner_core::prelude::{DebugInfo, InstructionDebugInfo, LineInfo, StatementKind};
+use yarnspinner_core::types::Type;
 
-mod debug_info;
 mod declaration;
+mod node_graph;
 mod string_info;
 
 /// The result of a compilation.
@@ -75,49 +78,205 @@ pub struct Compilation {
     /// file tags associated with that file.
     pub file_tags: HashMap<String, Vec<String>>,
 
+    /// The tags of every node, as given by its `tags:` header, keyed by the node's name.
+    ///
+    /// Nodes left out because of [`Compiler::with_excluded_node_tags`] are not included.
+    pub node_tags: HashMap<String, Vec<String>>,
+
     /// The collection of [`Diagnostic`] objects that
     /// describe possible problems that the user should fix,
     /// but do not cause the compilation process to fail.
     ///
-    /// All diagnostics in this collection have a severity of [`DiagnosticSeverity::Warning`].
+    /// All diagnostics in this collection have a severity of [`DiagnosticSeverity::Warning`] or [`DiagnosticSeverity::Info`].
     /// If there was an error during compilation, the compilation returns an [`Err`] variant containing
     /// error diagnostics instead of this [`Compilation`].
+    /// The only exception is [`CompilationType::Analyze`], for which this collection holds all diagnostics, including errors.
     pub warnings: Vec<Diagnostic>,
 
     /// The collection of [`DebugInfo`] objects for each node in [`Program`].
     pub debug_info: HashMap<String, DebugInfo>,
+
+    /// The graph of the nodes and the ways in which they lead into each other, e.g. for visualizing the story.
+    ///
+    /// This value will be empty if the [`Compiler`] object's
+    /// [`CompilationType`] value was [`CompilationType::StringsOnly`].
+    pub node_graph: NodeGraph,
 }
 
 impl Compilation {
+    /// Merges compilations that were compiled separately, e.g. from the repositories of different teams, into one.
+    ///
+    /// Fails with a diagnostic listing the offenders if a line ID is defined by more than one compilation
+    /// or if a line ID namespace (see [`Compiler::with_line_id_namespace`]) is used by more than one compilation,
+    /// as well as under the same conditions as [`Program::combine`].
+    pub fn merge(compilations: impl IntoIterator<Item = Compilation>) -> Result<Self> {
+        let compilations: Vec<_> = compilations.into_iter().collect();
+        let mut compilations_per_namespace: HashMap<&str, Vec<usize>> = HashMap::new();
+        let mut strings_per_line_id: HashMap<&LineId, Vec<(usize, &StringInfo)>> = HashMap::new();
+        for (index, compilation) in compilations.iter().enumerate() {
+            for (line_id, string_info) in &compilation.string_table {
+                strings_per_line_id
+                    .entry(line_id)
+                    .or_default()
+                    .push((index, string_info));
+                if let Some(namespace) = &string_info.line_id_namespace {
+                    let indices = compilations_per_namespace.entry(namespace).or_default();
+                    if !indices.contains(&index) {
+                        indices.push(index);
+                    }
+                }
+            }
+        }
+
+        let mut shared_namespaces: Vec<_> = compilations_per_namespace
+            .into_iter()
+            .filter(|(_, indices)| indices.len() > 1)
+            .collect();
+        shared_namespaces.sort();
+        let mut duplicate_line_ids: Vec<_> = strings_per_line_id
+            .into_iter()
+            .filter(|(_, strings)| strings.len() > 1)
+            .collect();
+        duplicate_line_ids.sort_by(|(lhs, _), (rhs, _)| lhs.0.cmp(&rhs.0));
+
+        let namespace_diagnostics = shared_namespaces.into_iter().map(|(namespace, indices)| {
+            let indices: Vec<_> = indices.iter().map(ToString::to_string).collect();
+            Diagnostic::from_message(format!(
+                "Line ID namespace \"{namespace}\" is used by multiple compilations: {}",
+                indices.join(", ")
+            ))
+            .with_code(DiagnosticCode::LineIdNamespaceViolation)
+            .with_argument("namespace", namespace)
+        });
+        let duplicate_diagnostics = duplicate_line_ids
+            .into_iter()
+            .map(|(line_id, mut strings)| {
+                strings.sort_by_key(|(index, _)| *index);
+                let locations: Vec<_> = strings
+                    .iter()
+                    .map(|(index, string_info)| {
+                        format!(
+                            "\"{}\" line {} (compilation {index})",
+                            string_info.file_name, string_info.line_number
+                        )
+                    })
+                    .collect();
+                Diagnostic::from_message(format!(
+                    "Duplicate line ID {line_id} in {}",
+                    locations.join(", ")
+                ))
+                .with_code(DiagnosticCode::DuplicateLineId)
+                .with_argument("line_id", line_id)
+            });
+        let diagnostics: Vec<_> = namespace_diagnostics.chain(duplicate_diagnostics).collect();
+        if !diagnostics.is_empty() {
+            return Err(CompilerError(diagnostics));
+        }
+
+        let mut string_table_manager = StringTableManager::default();
+        for compilation in &compilations {
+            string_table_manager.extend(compilation.string_table.clone().into());
+        }
+        Self::combine(compilations.into_iter(), string_table_manager)
+    }
+
+    /// Returns the [`Compilation::declarations`] of variables that were inferred from their usage instead of being declared with `<<declare>>`.
+    /// See [`Declaration::to_declare_statement`] for declaring them explicitly.
+    pub fn implicit_variable_declarations(&self) -> Vec<&Declaration> {
+        self.declarations
+            .iter()
+            .filter(|declaration| {
+                declaration.is_implicit && !matches!(declaration.r#type, Type::Function(_))
+            })
+            .collect()
+    }
+
+    /// Returns the lines of the node with the given name from the [`Compilation::string_table`],
+    /// including its options, in the order in which they appear in the source code.
+    /// Returns an empty list if there is no such node or if it has no lines.
+    pub fn lines_for_node(&self, node: &str) -> Vec<(&LineId, &StringInfo)> {
+        let mut lines: Vec<_> = self
+            .string_table
+            .iter()
+            .filter(|(_, string_info)| string_info.node_name == node)
+            .collect();
+        lines.sort_by(|(lhs_id, lhs), (rhs_id, rhs)| {
+            lhs.line_number
+                .cmp(&rhs.line_number)
+                .then_with(|| lhs_id.0.cmp(&rhs_id.0))
+        });
+        lines
+    }
+
+    /// Returns the names of the nodes that have lines in the [`Compilation::string_table`],
+    /// ordered by file name and by the position of their first line within the file.
+    /// Pass them to [`Compilation::lines_for_node`] to go through all lines of the compilation node by node.
+    pub fn nodes_with_lines(&self) -> Vec<&str> {
+        let mut first_lines: HashMap<&str, (&str, usize)> = HashMap::new();
+        for string_info in self.string_table.values() {
+            let first_line = first_lines
+                .entry(&string_info.node_name)
+                .or_insert((&string_info.file_name, string_info.line_number));
+            first_line.1 = first_line.1.min(string_info.line_number);
+        }
+        let mut nodes: Vec<_> = first_lines.into_iter().collect();
+        nodes.sort_by_key(|&(node, (file_name, line_number))| (file_name, line_number, node));
+        nodes.into_iter().map(|(node, _)| node).collect()
+    }
+
     /// Combines multiple [`CompilationResult`] objects together into one object.
+    ///
+    /// Returns an error if any of the compilations holds no [`Program`],
+    /// i.e. was not a [`CompilationType::FullCompilation`], or if the programs cannot be merged.
     pub(crate) fn combine(
         compilations: impl Iterator<Item = Compilation>,
         string_table_manager: StringTableManager,
-    ) -> Self {
+    ) -> Result<Self> {
         let mut programs = Vec::new();
         let mut declarations = Vec::new();
         let mut tags = HashMap::new();
+        let mut node_tags = HashMap::new();
         let mut diagnostics = Vec::new();
         let mut node_debug_infos = HashMap::new();
+        let mut node_graph = NodeGraph::default();
 
-        for compilation in compilations {
-            programs.push(compilation.program.unwrap());
+        for (index, compilation) in compilations.enumerate() {
+            let program = compilation.program.ok_or_else(|| {
+                CompilerError(vec![Diagnostic::from_message(format!(
+                    "Cannot combine compilations because compilation {index} contains no program. \
+                    Only full compilations produce a program."
+                ))])
+            })?;
+            programs.push(program);
             declarations.extend(compilation.declarations);
             tags.extend(compilation.file_tags);
+            node_tags.extend(compilation.node_tags);
             diagnostics.extend(compilation.warnings);
             node_debug_infos.extend(compilation.debug_info);
+            node_graph.extend(compilation.node_graph);
         }
-        let combined_program = Program::combine(programs);
+        let combined_program = match Program::combine(programs) {
+            Ok(program) => Some(program),
+            Err(ProgramCombineError::NoPrograms) => None,
+            Err(ProgramCombineError::DuplicateNode(node)) => {
+                let message = ProgramCombineError::DuplicateNode(node.clone()).to_string();
+                return Err(CompilerError(vec![Diagnostic::from_message(message)
+                    .with_code(DiagnosticCode::DuplicateNodeName)
+                    .with_argument("node", node)]));
+            }
+        };
         let contains_implicit_string_tags = string_table_manager.contains_implicit_string_tags();
-        Compilation {
+        Ok(Compilation {
             program: combined_program,
             string_table: string_table_manager.0,
             declarations,
             debug_info: node_debug_infos,
             contains_implicit_string_tags,
             file_tags: tags,
+            node_tags,
             warnings: diagnostics,
-        }
+            node_graph,
+        })
     }
 }
 
@@ -149,6 +308,11 @@ impl CompilerError {
     pub fn debug_fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_set().entries(&self.0).finish()
     }
+
+    /// Rewords the messages of all diagnostics with the given [`DiagnosticVocabulary`].
+    pub fn reworded(self, vocabulary: &DiagnosticVocabulary) -> Self {
+        Self(self.0.into_iter().map(|d| vocabulary.reword(d)).collect())
+    }
 }
 
 impl Display for CompilerError {
@@ -159,3 +323,28 @@ impl Display for CompilerError {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combining_compilation_without_program_fails() {
+        let full_compilation = Compilation {
+            program: Some(Program::default()),
+            ..Default::default()
+        };
+        let strings_only_compilation = Compilation::default();
+
+        let result = Compilation::combine(
+            [full_compilation, strings_only_compilation].into_iter(),
+            StringTableManager::default(),
+        );
+
+        let error = result.unwrap_err();
+        assert_eq!(1, error.0.len());
+        assert!(error.0[0]
+            .message
+            .contains("compilation 1 contains no program"));
+    }
+}
@@ -56,6 +56,21 @@ pub struct Declaration {
     /// If `false`, this declaration appears in the source code.
     pub is_implicit: bool,
 
+    /// A value indicating whether this declaration is a smart variable,
+    /// e.g. `<<declare $is_rich = $gold > 100>>`.
+    ///
+    /// A smart variable is read-only and has no default value. Its
+    /// expression is evaluated every time the variable is read.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub is_smart: bool,
+
+    /// A value indicating whether scripts may not assign a value to this variable,
+    /// e.g. because it is provided by the game.
+    ///
+    /// Smart variables are always read-only, whether this is set or not. See [`Declaration::is_smart`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub is_read_only: bool,
+
     /// The type of the variable, as represented by an object found
     /// in a variant of [`Type`].
     pub r#type: Type,
@@ -65,12 +80,12 @@ pub struct Declaration {
     /// This range refers to the declaration of the symbol itself, and
     /// not any syntax surrounding it. For example, the declaration
     /// `<<declare $x = 1>>` would have a range referring to the `$x`
-    /// symbol.
+    /// symbol. Use [`SourcePosition`] to convert it to byte offsets.
     pub range: Option<Range<Position>>,
 }
 
 impl Declaration {
-    /// Gets the line number at which this Declaration was found in the
+    /// Gets the zero-based line number at which this Declaration was found in the
     /// source file.
     ///
     /// If this [`Declaration`] was not found in a Yarn source file,
@@ -79,6 +94,59 @@ impl Declaration {
         self.range.as_ref()?.start.line.into()
     }
 
+    /// Renders the `<<declare>>` statement that explicitly declares this variable with its type and default value,
+    /// e.g. to offer a quick fix for an [implicit](Declaration::is_implicit) declaration.
+    ///
+    /// Returns [`None`] for declarations that can't be written as a `<<declare>>` statement,
+    /// i.e. functions, enum cases, smart variables, variables without a default value
+    /// and variables whose default value has no Yarn literal: negative or non-finite numbers
+    /// and strings containing quotes, backslashes or line breaks, since the compiler keeps escape sequences verbatim.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use yarnspinner_compiler::prelude::*;
+    /// # use yarnspinner_core::prelude::*;
+    /// let declaration = Declaration::new("$name", Type::String).with_default_value("Ada");
+    /// assert_eq!(
+    ///     Some(r#"<<declare $name = "Ada" as String>>"#.to_owned()),
+    ///     declaration.to_declare_statement()
+    /// );
+    ///
+    /// let declaration = Declaration::new("$gold", Type::Number).with_default_value(5);
+    /// assert_eq!(
+    ///     Some("<<declare $gold = 5 as Number>>".to_owned()),
+    ///     declaration.to_declare_statement()
+    /// );
+    /// ```
+    pub fn to_declare_statement(&self) -> Option<String> {
+        if self.is_smart || !self.name.starts_with('$') {
+            return None;
+        }
+        let default_value = self.default_value.as_ref()?;
+        let literal = match (&self.r#type, default_value) {
+            (Type::Function(_), _) => return None,
+            (Type::Enum(enum_type), _) => {
+                let case = enum_type.case_for_raw_value(default_value)?;
+                enum_type.case_reference(case)
+            }
+            (_, YarnValue::String(string)) if string.contains(['"', '\\', '\r', '\n']) => {
+                return None
+            }
+            (_, YarnValue::String(string)) => format!("\"{string}\""),
+            (_, YarnValue::Number(number)) if !number.is_finite() || number.is_sign_negative() => {
+                return None
+            }
+            // Integral numbers are displayed without a trailing `.0`
+            (_, YarnValue::Number(number)) => number.to_string(),
+            (_, YarnValue::Boolean(boolean)) => boolean.to_string(),
+        };
+        Some(format!(
+            "<<declare {} = {literal} as {}>>",
+            self.name, self.r#type
+        ))
+    }
+
     #[doc(hidden)]
     pub fn new(name: impl Into<String>, r#type: impl Into<Type>) -> Self {
         Self {
@@ -89,6 +157,8 @@ impl Declaration {
             source_file_name: Default::default(),
             source_node_name: Default::default(),
             is_implicit: Default::default(),
+            is_smart: Default::default(),
+            is_read_only: Default::default(),
             range: Default::default(),
         }
     }
@@ -150,6 +220,34 @@ impl Declaration {
         self
     }
 
+    #[doc(hidden)]
+    pub fn with_smart(mut self) -> Self {
+        self.is_smart = true;
+        self
+    }
+
+    /// Marks the variable as read-only, so that assigning a value to it with `<<set>>` is a compile error.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use yarnspinner_compiler::prelude::*;
+    /// # use yarnspinner_core::prelude::*;
+    /// let result = Compiler::new()
+    ///     .add_source("shop.yarn", "title: Start\n---\n<<set $player_name to \"Nobody\">>\n===\n")
+    ///     .declare_variable(
+    ///         Declaration::new("$player_name", Type::String)
+    ///             .with_default_value("Ada")
+    ///             .with_read_only(),
+    ///     )
+    ///     .compile();
+    /// assert!(result.is_err());
+    /// ```
+    pub fn with_read_only(mut self) -> Self {
+        self.is_read_only = true;
+        self
+    }
+
     #[doc(hidden)]
     pub fn with_range(mut self, range: impl Into<Range<Position>>) -> Self {
         self.range = Some(range.into());
@@ -163,6 +261,8 @@ impl Declaration {
             && self.source_file_name == other.source_file_name
             && self.source_node_name == other.source_node_name
             && self.is_implicit == other.is_implicit
+            && self.is_smart == other.is_smart
+            && self.is_read_only == other.is_read_only
             && self.r#type == other.r#type
             && self.range == other.range
             && match (&self.default_value, &other.default_value) {
@@ -214,12 +314,12 @@ where
             line: self.start().get_line_as_usize().saturating_sub(1),
             character: self.start().get_column_as_usize(),
         };
-        let text: String = self.stop().get_text().to_owned().into();
-        let stop = Position {
+        let stop_start = Position {
             line: self.stop().get_line_as_usize().saturating_sub(1),
-            character: self.stop().get_column_as_usize() + text.len(),
+            character: self.stop().get_column_as_usize(),
         };
-        start..stop
+        let stop_text: String = self.stop().get_text().to_owned().into();
+        start..text_range(stop_start, &stop_text).end
     }
 }
 
@@ -0,0 +1,179 @@
+//! Not part of the original implementation. Describes how the nodes of a [`Compilation`] lead into each other,
+//! which is useful for tools that visualize the structure of a story.
+
+use crate::prelude::*;
+use std::fmt::Write;
+use std::ops::Range;
+
+/// A graph of the nodes of a [`Compilation`] and the ways in which they lead into each other.
+///
+/// It is built from the parse trees during compilation, because the compiled [`Program`] no longer knows
+/// e.g. whether a jump was conditional or the consequence of an option.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_compiler::prelude::*;
+/// let compilation = Compiler::new()
+///     .add_file(File {
+///         file_name: "story.yarn".to_owned(),
+///         source: "title: Start\n---\n<<jump End>>\n===\ntitle: End\n---\nBye!\n===\n".to_owned(),
+///     })
+///     .compile()
+///     .unwrap();
+/// let graph = &compilation.node_graph;
+/// assert_eq!(2, graph.nodes.len());
+/// assert_eq!(NodeGraphTarget::Node("End".to_owned()), graph.edges[0].target);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash, Default))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct NodeGraph {
+    /// All nodes of the compilation, in the order in which they appear in their files.
+    pub nodes: Vec<NodeGraphNode>,
+    /// All edges between the nodes, in the order in which they appear in their files.
+    pub edges: Vec<NodeGraphEdge>,
+}
+
+/// A node of a [`NodeGraph`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct NodeGraphNode {
+    /// The title of the node.
+    pub name: String,
+    /// The name of the file the node is defined in.
+    pub file_name: String,
+    /// The range of the node in its file.
+    pub range: Range<Position>,
+}
+
+/// A way in which one node of a [`NodeGraph`] leads into another.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct NodeGraphEdge {
+    /// The title of the node the edge starts in.
+    pub source: String,
+    /// The node the edge leads to.
+    pub target: NodeGraphTarget,
+    /// The statement that produced this edge.
+    pub kind: NodeGraphEdgeKind,
+    /// Whether the edge is only taken under some condition,
+    /// i.e. it is inside an `<<if>>` statement or an option that has a condition.
+    pub is_conditional: bool,
+    /// The name of the file that contains the statement that produced this edge.
+    pub file_name: String,
+    /// The range of the statement that produced this edge in its file.
+    pub range: Range<Position>,
+}
+
+/// The node a [`NodeGraphEdge`] leads to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub enum NodeGraphTarget {
+    /// The node with the given title.
+    Node(String),
+    /// A node whose title is only known at runtime, e.g. `<<jump {$destination}>>`.
+    Dynamic,
+}
+
+/// The kind of statement that produced a [`NodeGraphEdge`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub enum NodeGraphEdgeKind {
+    /// A `<<jump>>` statement.
+    Jump,
+    /// A `<<detour>>` command, which comes back to the source node once the target node returns.
+    Detour,
+    /// A `<<jump>>` statement inside of an option, i.e. the target is reached by choosing the option.
+    Option,
+}
+
+impl NodeGraphEdgeKind {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Jump => "jump",
+            Self::Detour => "detour",
+            Self::Option => "option",
+        }
+    }
+}
+
+impl NodeGraph {
+    /// Renders the graph in the DOT language of [Graphviz](https://graphviz.org/).
+    ///
+    /// Edges are labelled with their kind, conditional edges are dashed,
+    /// and all dynamic targets are drawn as a single diamond-shaped node.
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        const DYNAMIC_NODE: &str = "\"<dynamic>\"";
+        let mut dot = String::from("digraph {\n");
+        // Writing to a `String` cannot fail
+        for node in &self.nodes {
+            let _ = writeln!(dot, "    {};", quote(&node.name));
+        }
+        if self
+            .edges
+            .iter()
+            .any(|edge| edge.target == NodeGraphTarget::Dynamic)
+        {
+            let _ = writeln!(dot, "    {DYNAMIC_NODE} [shape=diamond];");
+        }
+        for edge in &self.edges {
+            let target = match &edge.target {
+                NodeGraphTarget::Node(name) => quote(name),
+                NodeGraphTarget::Dynamic => DYNAMIC_NODE.to_owned(),
+            };
+            let style = if edge.is_conditional {
+                ", style=dashed"
+            } else {
+                ""
+            };
+            let _ = writeln!(
+                dot,
+                "    {} -> {target} [label=\"{}\"{style}];",
+                quote(&edge.source),
+                edge.kind.label()
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub(crate) fn extend(&mut self, other: Self) {
+        self.nodes.extend(other.nodes);
+        self.edges.extend(other.edges);
+    }
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
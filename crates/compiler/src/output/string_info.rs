@@ -1,6 +1,5 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner.Compiler/StringInfo.cs>
 
-#[cfg(any(feature = "bevy", feature = "serde"))]
 use crate::prelude::*;
 
 /// Information about a string. Stored inside a string table, which is
@@ -32,9 +31,9 @@ pub struct StringInfo {
     /// Indicates whether this string's line ID was implicitly
     /// generated.
     ///
-    /// Implicitly generated line IDs are not guaranteed to remain the
-    /// same across multiple compilations. To ensure that a line ID
-    /// remains the same, you must define it by adding a line tag to the
+    /// Implicitly generated line IDs are derived from the file name, node name and text of the line,
+    /// so they stay the same when other lines are added, removed or moved, but change when the line itself is edited.
+    /// To ensure that a line ID remains the same, you must define it by adding a line tag to the
     /// line.
     pub is_implicit_tag: bool,
 
@@ -43,4 +42,18 @@ pub struct StringInfo {
     /// This array will contain any hashtags associated with this
     /// string besides the `#line:` hashtag.
     pub metadata: Vec<String>,
+
+    /// The types of the expressions interpolated into this string, e.g. `{$gold}`, in order of appearance.
+    ///
+    /// An expression whose type could not be determined is recorded as [`Type::Any`].
+    pub expression_types: Vec<Type>,
+
+    /// The line ID namespace of the compilation this string was found in, if it had one.
+    /// See [`Compiler::with_line_id_namespace`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub line_id_namespace: Option<String>,
+
+    /// How the text wraps into the dialogue box configured by [`Compiler::with_wrap_config`], if one was configured.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub wrap_hints: Option<WrapHints>,
 }
@@ -0,0 +1,395 @@
+//! A stable, syntax-only view of a Yarn file for tooling such as syntax highlighters and outline views.
+
+use crate::prelude::generated::yarnspinnerlexer;
+use crate::prelude::generated::yarnspinnerparser::*;
+use crate::prelude::*;
+use antlr_rust::int_stream::IntStream;
+use antlr_rust::parser_rule_context::ParserRuleContext;
+use antlr_rust::token::{Token, TOKEN_EOF};
+use antlr_rust::token_stream::TokenStream;
+use std::ops::{Deref, Range};
+
+/// Parses a single Yarn file without compiling it, e.g. for syntax highlighting or an outline view.
+///
+/// Returns all diagnostics if the file contains syntax errors.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_compiler::prelude::*;
+/// let parsed = parse_yarn_file("intro.yarn", "title: Intro\nmood: happy\n---\nHello!\n===\n").unwrap();
+/// let node = &parsed.nodes[0];
+/// assert_eq!(Some("Intro"), node.title.as_deref());
+/// assert_eq!("mood", node.headers[1].key);
+/// assert_eq!(2, node.body_range.start.line);
+///
+/// let text: Vec<_> = parsed
+///     .tokens
+///     .iter()
+///     .filter(|token| token.kind == TokenKind::Text)
+///     .map(|token| token.text.as_str())
+///     .collect();
+/// assert_eq!("Hello!", text.concat());
+/// ```
+pub fn parse_yarn_file(
+    name: impl Into<String>,
+    source: impl Into<String>,
+) -> std::result::Result<ParsedFile, Vec<Diagnostic>> {
+    let file = File {
+        file_name: name.into(),
+        source: source.into(),
+    };
+    let source = file.source.strip_prefix('\u{feff}').unwrap_or(&file.source);
+    let chars: Vec<u32> = source.chars().map(|c| c as u32).collect();
+    let mut diagnostics = Vec::new();
+    let parse_result = parse_syntax_tree(&file, &chars, &mut diagnostics);
+    let Some(parse_result) = parse_result.filter(|_| !diagnostics.has_errors()) else {
+        return Err(diagnostics);
+    };
+
+    let nodes = parse_result
+        .tree
+        .node_all()
+        .iter()
+        .map(|node| parse_node(node))
+        .collect();
+
+    let token_stream = parse_result.tokens();
+    let mut tokens: Vec<ParsedToken> = Vec::new();
+    // The index of the first character that is not covered by a token yet
+    let mut next_char = 0;
+    for token in (0..token_stream.size())
+        .map(|index| token_stream.get(index))
+        .filter(|token| token.get_token_type() != TOKEN_EOF)
+    {
+        // The lexer skips the backslash of escape sequences, so we add it back to keep the tokens lossless
+        let start = usize::try_from(token.get_start()).unwrap_or_default();
+        if start > next_char {
+            let text: String = chars[next_char..start.min(chars.len())]
+                .iter()
+                .filter_map(|&c| char::from_u32(c))
+                .collect();
+            let range_start = tokens.last().map_or(
+                Position {
+                    line: 0,
+                    character: 0,
+                },
+                |previous| previous.range.end,
+            );
+            tokens.push(ParsedToken {
+                kind: TokenKind::Escape,
+                range: text_range(range_start, &text),
+                text,
+            });
+        }
+        next_char = next_char.max(usize::try_from(token.get_stop() + 1).unwrap_or_default());
+
+        let token = ParsedToken {
+            kind: TokenKind::from_token_type(token.get_token_type()),
+            text: token.get_text().to_owned(),
+            range: token_range(token.as_ref()),
+        };
+        // The lexer emits a run of text as several tokens, which is an implementation detail
+        match tokens.last_mut() {
+            Some(previous)
+                if previous.kind == token.kind
+                    && matches!(token.kind, TokenKind::Text | TokenKind::CommandText)
+                    && previous.range.end == token.range.start =>
+            {
+                previous.text.push_str(&token.text);
+                previous.range.end = token.range.end;
+            }
+            _ => tokens.push(token),
+        }
+    }
+
+    Ok(ParsedFile {
+        name: file.file_name.clone(),
+        nodes,
+        tokens,
+        warnings: diagnostics,
+    })
+}
+
+/// The result of [`parse_yarn_file`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct ParsedFile {
+    /// The name of the file.
+    pub name: String,
+
+    /// The nodes of the file, in order of appearance.
+    pub nodes: Vec<ParsedNode>,
+
+    /// All tokens produced by the lexer, in order of appearance, including whitespace and comments.
+    pub tokens: Vec<ParsedToken>,
+
+    /// The warnings produced while parsing the file.
+    pub warnings: Vec<Diagnostic>,
+}
+
+impl ParsedFile {
+    /// Reconstructs the source of the file from its [`ParsedFile::tokens`], including all whitespace and comments.
+    /// This is the source that was parsed, except for a leading byte order mark, which is dropped.
+    ///
+    /// Tools that rewrite Yarn files can edit the text of the tokens and then use this to emit the modified source.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use yarnspinner_compiler::prelude::*;
+    /// let source = "title: Start\r\n---\r\n// A comment\r\nHello \\{there\\}!\r\n===\r\n";
+    /// let parsed = parse_yarn_file("intro.yarn", source).unwrap();
+    /// assert_eq!(source, parsed.source());
+    /// ```
+    pub fn source(&self) -> String {
+        self.tokens
+            .iter()
+            .map(|token| token.text.as_str())
+            .collect()
+    }
+}
+
+/// A node of a [`ParsedFile`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct ParsedNode {
+    /// The value of the node's `title` header, if it has one.
+    pub title: Option<String>,
+
+    /// All headers of the node, including the `title`, in order of appearance.
+    pub headers: Vec<ParsedHeader>,
+
+    /// The range of the whole node, from its first header to its `===`.
+    pub range: Range<Position>,
+
+    /// The range of the node's body, i.e. everything between its `---` and its `===`.
+    pub body_range: Range<Position>,
+}
+
+/// A header of a [`ParsedNode`], e.g. `title: Start`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct ParsedHeader {
+    /// The key of the header, e.g. `title`.
+    pub key: String,
+
+    /// The value of the header, e.g. `Start`. Empty if the header has no value.
+    pub value: String,
+
+    /// The range of the whole header.
+    pub range: Range<Position>,
+}
+
+/// A token of a [`ParsedFile`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct ParsedToken {
+    /// What kind of token this is.
+    pub kind: TokenKind,
+
+    /// The source text of the token. Empty for tokens that are inferred from the indentation,
+    /// such as [`TokenKind::Indent`] and [`TokenKind::Dedent`].
+    pub text: String,
+
+    /// The range of the token in the source.
+    pub range: Range<Position>,
+}
+
+/// The kind of a [`ParsedToken`].
+///
+/// Unlike the token types of the underlying lexer, these are stable across versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub enum TokenKind {
+    /// The key of a header, e.g. `title` in `title: Start`.
+    HeaderKey,
+    /// The `:` between a header's key and value.
+    HeaderDelimiter,
+    /// The value of a header, e.g. `Start` in `title: Start`.
+    HeaderValue,
+    /// The `---` that starts a node's body.
+    BodyStart,
+    /// The `===` that ends a node's body.
+    BodyEnd,
+    /// Text of a line or option.
+    Text,
+    /// An escaped character in text, e.g. `\{`.
+    Escape,
+    /// The `->` of an option.
+    ShortcutArrow,
+    /// The `#` of a hashtag.
+    Hashtag,
+    /// The text of a hashtag, e.g. `line:intro` in `#line:intro`.
+    HashtagText,
+    /// The `<<` that starts a command.
+    CommandStart,
+    /// The `>>` that ends a command.
+    CommandEnd,
+    /// The text of a command that is not built into Yarn, e.g. `walk Alice` in `<<walk Alice>>`.
+    CommandText,
+    /// A keyword, e.g. `if` in `<<if $gold > 0>>` or `true`.
+    Keyword,
+    /// The `{` that starts an inline expression.
+    ExpressionStart,
+    /// The `}` that ends an inline expression.
+    ExpressionEnd,
+    /// A variable, e.g. `$gold`.
+    Variable,
+    /// The name of a called function, e.g. `visited` in `visited("Start")`.
+    Function,
+    /// A number literal, e.g. `42`.
+    Number,
+    /// A string literal, e.g. `"Alice"`.
+    String,
+    /// An operator, e.g. `+` or `and`.
+    Operator,
+    /// A parenthesis, comma or dot.
+    Punctuation,
+    /// A comment, e.g. `// TODO`.
+    Comment,
+    /// Whitespace within a line.
+    Whitespace,
+    /// A line break, including the indentation of the following line.
+    Newline,
+    /// An increase of indentation that starts the content of an option.
+    Indent,
+    /// A decrease of indentation that ends the content of an option.
+    Dedent,
+    /// A blank line that ends a group of options.
+    OptionGroupEnd,
+    /// A character that is not allowed at its position.
+    Invalid,
+}
+
+impl TokenKind {
+    fn from_token_type(token_type: isize) -> Self {
+        use yarnspinnerlexer::*;
+        match token_type {
+            ID => Self::HeaderKey,
+            HEADER_DELIMITER => Self::HeaderDelimiter,
+            REST_OF_LINE => Self::HeaderValue,
+            BODY_START => Self::BodyStart,
+            BODY_END => Self::BodyEnd,
+            TEXT => Self::Text,
+            ESCAPED_ANY | TEXT_ESCAPE => Self::Escape,
+            SHORTCUT_ARROW => Self::ShortcutArrow,
+            HASHTAG => Self::Hashtag,
+            HASHTAG_TEXT => Self::HashtagText,
+            COMMAND_START => Self::CommandStart,
+            COMMAND_END | COMMAND_TEXT_END => Self::CommandEnd,
+            COMMAND_TEXT => Self::CommandText,
+            KEYWORD_TRUE | KEYWORD_FALSE | KEYWORD_NULL | EXPRESSION_AS | COMMAND_IF
+            | COMMAND_ELSEIF | COMMAND_ELSE | COMMAND_SET | COMMAND_ENDIF | COMMAND_CALL
+            | COMMAND_DECLARE | COMMAND_JUMP | COMMAND_ENUM | COMMAND_CASE | COMMAND_ENDENUM
+            | COMMAND_LOCAL | COMMAND_ONCE | COMMAND_ENDONCE | TYPE_STRING | TYPE_NUMBER
+            | TYPE_BOOL => Self::Keyword,
+            EXPRESSION_START | COMMAND_EXPRESSION_START => Self::ExpressionStart,
+            EXPRESSION_END => Self::ExpressionEnd,
+            VAR_ID => Self::Variable,
+            FUNC_ID => Self::Function,
+            NUMBER => Self::Number,
+            STRING => Self::String,
+            OPERATOR_ASSIGNMENT
+            | OPERATOR_LOGICAL_LESS_THAN_EQUALS
+            | OPERATOR_LOGICAL_GREATER_THAN_EQUALS
+            | OPERATOR_LOGICAL_EQUALS
+            | OPERATOR_LOGICAL_LESS
+            | OPERATOR_LOGICAL_GREATER
+            | OPERATOR_LOGICAL_NOT_EQUALS
+            | OPERATOR_LOGICAL_AND
+            | OPERATOR_LOGICAL_OR
+            | OPERATOR_LOGICAL_XOR
+            | OPERATOR_LOGICAL_NOT
+            | OPERATOR_MATHS_ADDITION_EQUALS
+            | OPERATOR_MATHS_SUBTRACTION_EQUALS
+            | OPERATOR_MATHS_MULTIPLICATION_EQUALS
+            | OPERATOR_MATHS_MODULUS_EQUALS
+            | OPERATOR_MATHS_DIVISION_EQUALS
+            | OPERATOR_MATHS_ADDITION
+            | OPERATOR_MATHS_SUBTRACTION
+            | OPERATOR_MATHS_MULTIPLICATION
+            | OPERATOR_MATHS_DIVISION
+            | OPERATOR_MATHS_MODULUS => Self::Operator,
+            LPAREN | RPAREN | COMMA | DOT => Self::Punctuation,
+            COMMENT | TEXT_COMMENT | TEXT_COMMANDHASHTAG_COMMENT => Self::Comment,
+            WS | BODY_WS | TEXT_COMMANDHASHTAG_WS | HASHTAG_WS | EXPR_WS | COMMAND_WS => {
+                Self::Whitespace
+            }
+            NEWLINE => Self::Newline,
+            INDENT => Self::Indent,
+            DEDENT => Self::Dedent,
+            BLANK_LINE_FOLLOWING_OPTION => Self::OptionGroupEnd,
+            _ => Self::Invalid,
+        }
+    }
+}
+
+fn parse_node(node: &NodeContext) -> ParsedNode {
+    let headers: Vec<_> = node
+        .header_all()
+        .iter()
+        .map(|header| ParsedHeader {
+            key: header
+                .header_key
+                .as_ref()
+                .map(|key| key.get_text().to_owned())
+                .unwrap_or_default(),
+            value: header
+                .header_value
+                .as_ref()
+                .map(|value| value.get_text().to_owned())
+                .unwrap_or_default(),
+            range: token_range(header.start().deref()).start
+                ..token_range(header.stop().deref()).end,
+        })
+        .collect();
+    let title = headers
+        .iter()
+        .find(|header| header.key == "title")
+        .map(|header| header.value.clone());
+    let body_start = node
+        .BODY_START()
+        .map(|token| token_range(token.symbol.as_ref()).end)
+        .unwrap_or_else(|| token_range(node.start().deref()).start);
+    let body_end = node
+        .BODY_END()
+        .map(|token| token_range(token.symbol.as_ref()).start)
+        .unwrap_or_else(|| token_range(node.stop().deref()).end);
+    ParsedNode {
+        title,
+        headers,
+        range: token_range(node.start().deref()).start..token_range(node.stop().deref()).end,
+        body_range: body_start..body_end,
+    }
+}
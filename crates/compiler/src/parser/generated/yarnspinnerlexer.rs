@@ -107,6 +107,8 @@ pub const COMMAND_TEXT: isize = 78;
 pub const TYPE_STRING: isize = 79;
 pub const TYPE_NUMBER: isize = 80;
 pub const TYPE_BOOL: isize = 81;
+pub const COMMAND_ONCE: isize = 82;
+pub const COMMAND_ENDONCE: isize = 83;
 pub const WHITESPACE: usize = 2;
 pub const COMMENTS: usize = 3;
 pub const HeaderMode: usize = 1;
@@ -138,7 +140,7 @@ pub const modeNames: [&'static str; 12] = [
     "CommandIDOrExpressionMode",
 ];
 
-pub const ruleNames: [&'static str; 106] = [
+pub const ruleNames: [&'static str; 108] = [
     "WS",
     "COMMENT",
     "NEWLINE",
@@ -235,6 +237,8 @@ pub const ruleNames: [&'static str; 106] = [
     "COMMAND_CASE",
     "COMMAND_ENDENUM",
     "COMMAND_LOCAL",
+    "COMMAND_ONCE",
+    "COMMAND_ENDONCE",
     "COMMAND_END",
     "COMMAND_ARBITRARY",
     "COMMAND_TEXT_END",
@@ -247,7 +251,7 @@ pub const ruleNames: [&'static str; 106] = [
     "COMMAND_ID_OR_EXPRESSION_END",
 ];
 
-pub const _LITERAL_NAMES: [Option<&'static str>; 82] = [
+pub const _LITERAL_NAMES: [Option<&'static str>; 84] = [
     None,
     None,
     None,
@@ -330,8 +334,10 @@ pub const _LITERAL_NAMES: [Option<&'static str>; 82] = [
     Some("'string'"),
     Some("'number'"),
     Some("'bool'"),
+    Some("'once'"),
+    Some("'endonce'"),
 ];
-pub const _SYMBOLIC_NAMES: [Option<&'static str>; 82] = [
+pub const _SYMBOLIC_NAMES: [Option<&'static str>; 84] = [
     None,
     Some("INDENT"),
     Some("DEDENT"),
@@ -414,6 +420,8 @@ pub const _SYMBOLIC_NAMES: [Option<&'static str>; 82] = [
     Some("TYPE_STRING"),
     Some("TYPE_NUMBER"),
     Some("TYPE_BOOL"),
+    Some("COMMAND_ONCE"),
+    Some("COMMAND_ENDONCE"),
 ];
 lazy_static! {
     static ref _shared_context_cache: Arc<PredictionContextCache> =
@@ -562,7 +570,7 @@ lazy_static! {
 
 const _serializedATN: &'static str =
     "\x03\u{608b}\u{a72a}\u{8133}\u{b9ed}\u{417c}\u{3be7}\u{7786}\u{5964}\x02\
-		\x53\u{328}\x08\x01\x08\x01\x08\x01\x08\x01\x08\x01\x08\x01\x08\x01\x08\
+		\x55\u{340}\x08\x01\x08\x01\x08\x01\x08\x01\x08\x01\x08\x01\x08\x01\x08\
 		\x01\x08\x01\x08\x01\x08\x01\x08\x01\x04\x02\x09\x02\x04\x03\x09\x03\x04\
 		\x04\x09\x04\x04\x05\x09\x05\x04\x06\x09\x06\x04\x07\x09\x07\x04\x08\x09\
 		\x08\x04\x09\x09\x09\x04\x0a\x09\x0a\x04\x0b\x09\x0b\x04\x0c\x09\x0c\x04\
@@ -584,10 +592,10 @@ const _serializedATN: &'static str =
 		\x50\x04\x51\x09\x51\x04\x52\x09\x52\x04\x53\x09\x53\x04\x54\x09\x54\x04\
 		\x55\x09\x55\x04\x56\x09\x56\x04\x57\x09\x57\x04\x58\x09\x58\x04\x59\x09\
 		\x59\x04\x5a\x09\x5a\x04\x5b\x09\x5b\x04\x5c\x09\x5c\x04\x5d\x09\x5d\x04\
-		\x5e\x09\x5e\x04\x5f\x09\x5f\x04\x60\x09\x60\x04\x61\x09\x61\x04\x62\x09\
-		\x62\x04\x63\x09\x63\x04\x64\x09\x64\x04\x65\x09\x65\x04\x66\x09\x66\x04\
-		\x67\x09\x67\x04\x68\x09\x68\x04\x69\x09\x69\x04\x6a\x09\x6a\x04\x6b\x09\
-		\x6b\x03\x02\x06\x02\u{e4}\x0a\x02\x0d\x02\x0e\x02\u{e5}\x03\x02\x03\x02\
+		\x5e\x09\x5e\x04\x5f\x09\x5f\x04\x60\x09\x60\x04\x61\x09\x61\x04\x64\x09\
+		\x64\x04\x65\x09\x65\x04\x66\x09\x66\x04\x67\x09\x67\x04\x68\x09\x68\x04\
+		\x69\x09\x69\x04\x6a\x09\x6a\x04\x6b\x09\x6b\x04\x6c\x09\x6c\x04\x6d\x09\
+		\x6d\x03\x02\x06\x02\u{e4}\x0a\x02\x0d\x02\x0e\x02\u{e5}\x03\x02\x03\x02\
 		\x03\x03\x03\x03\x03\x03\x03\x03\x07\x03\u{ee}\x0a\x03\x0c\x03\x0e\x03\
 		\u{f1}\x0b\x03\x03\x03\x03\x03\x03\x04\x05\x04\u{f6}\x0a\x04\x03\x04\x03\
 		\x04\x05\x04\u{fa}\x0a\x04\x03\x04\x07\x04\u{fd}\x0a\x04\x0c\x04\x0e\x04\
@@ -650,133 +658,137 @@ const _serializedATN: &'static str =
 		\x03\x5e\x03\x5e\x03\x5f\x03\x5f\x03\x5f\x03\x5f\x03\x5f\x03\x5f\x03\x5f\
 		\x03\x5f\x03\x5f\x03\x60\x03\x60\x03\x60\x03\x60\x03\x60\x03\x60\x03\x60\
 		\x03\x60\x03\x60\x05\x60\u{2ec}\x0a\x60\x03\x61\x03\x61\x03\x61\x03\x61\
-		\x03\x61\x03\x61\x03\x61\x03\x61\x03\x62\x03\x62\x03\x62\x03\x62\x03\x62\
-		\x03\x63\x03\x63\x03\x63\x03\x63\x03\x63\x03\x64\x03\x64\x03\x64\x03\x64\
-		\x03\x64\x03\x65\x03\x65\x03\x65\x03\x65\x03\x66\x06\x66\u{30a}\x0a\x66\
-		\x0d\x66\x0e\x66\u{30b}\x03\x67\x03\x67\x03\x67\x03\x67\x03\x67\x03\x68\
-		\x03\x68\x03\x68\x03\x68\x03\x68\x03\x68\x03\x69\x03\x69\x03\x69\x03\x69\
-		\x03\x69\x03\x6a\x03\x6a\x03\x6a\x03\x6a\x03\x6a\x03\x6b\x03\x6b\x03\x6b\
-		\x03\x6b\x03\x6b\x03\x6b\x02\x02\x6c\x0e\x06\x10\x07\x12\x08\x14\x09\x16\
-		\x02\x18\x02\x1a\x02\x1c\x0a\x1e\x0b\x20\x0c\x22\x0d\x24\x02\x26\x0e\x28\
-		\x02\x2a\x02\x2c\x0f\x2e\x10\x30\x11\x32\x02\x34\x12\x36\x13\x38\x02\x3a\
-		\x02\x3c\x02\x3e\x14\x40\x02\x42\x02\x44\x02\x46\x15\x48\x16\x4a\x02\x4c\
-		\x02\x4e\x17\x50\x18\x52\x19\x54\x02\x56\x02\x58\x02\x5a\x1a\x5c\x1b\x5e\
-		\x02\x60\x1c\x62\x1d\x64\x1e\x66\x1f\x68\x20\x6a\x21\x6c\x22\x6e\x23\x70\
-		\x24\x72\x25\x74\x26\x76\x27\x78\x28\x7a\x29\x7c\x2a\x7e\x2b\u{80}\x2c\
-		\u{82}\x2d\u{84}\x2e\u{86}\x2f\u{88}\x30\u{8a}\x31\u{8c}\x32\u{8e}\x33\
-		\u{90}\x34\u{92}\x35\u{94}\x36\u{96}\x37\u{98}\x38\u{9a}\x39\u{9c}\x51\
-		\u{9e}\x52\u{a0}\x53\u{a2}\x3a\u{a4}\x3b\u{a6}\x3c\u{a8}\x02\u{aa}\x3d\
-		\u{ac}\x3e\u{ae}\x3f\u{b0}\x02\u{b2}\x02\u{b4}\x40\u{b6}\x41\u{b8}\x42\
-		\u{ba}\x43\u{bc}\x44\u{be}\x45\u{c0}\x46\u{c2}\x47\u{c4}\x48\u{c6}\x49\
-		\u{c8}\x4a\u{ca}\x4b\u{cc}\x4c\u{ce}\x4d\u{d0}\x02\u{d2}\x4e\u{d4}\x4f\
-		\u{d6}\x50\u{d8}\x02\u{da}\x02\u{dc}\x02\u{de}\x02\u{e0}\x02\x0e\x02\x03\
-		\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0f\x04\x02\x0b\x0b\x22\x22\x04\
-		\x02\x0c\x0c\x0f\x0f\x07\x02\x32\x3b\u{302}\u{371}\u{1dc2}\u{1e01}\u{20d2}\
-		\u{2101}\u{fe22}\u{fe31}\x03\x02\x22\x22\x04\x02\x31\x31\x3e\x3e\x09\x02\
-		\x0c\x0c\x0f\x0f\x25\x25\x31\x31\x3e\x3e\x5e\x5e\x7d\x7d\x09\x02\x25\x25\
-		\x31\x31\x3e\x3e\x40\x40\x5e\x5e\x7d\x7d\x7f\x7f\x07\x02\x0b\x0c\x0f\x0f\
-		\x22\x22\x25\x26\x3e\x3e\x06\x02\x0c\x0c\x0f\x0f\x24\x24\x5e\x5e\x04\x02\
-		\x24\x24\x5e\x5e\x03\x02\x32\x3b\x0c\x02\x0b\x0f\x22\x22\u{87}\u{87}\u{a2}\
-		\u{a2}\u{1682}\u{1682}\u{2002}\u{200c}\u{202a}\u{202b}\u{2031}\u{2031}\
-		\u{2061}\u{2061}\u{3002}\u{3002}\x04\x02\x40\x40\x7d\x7d\x03\x33\x02\x43\
-		\x02\x5c\x02\x61\x02\x61\x02\x63\x02\x7c\x02\u{aa}\x02\u{aa}\x02\u{ac}\
-		\x02\u{ac}\x02\u{af}\x02\u{af}\x02\u{b1}\x02\u{b1}\x02\u{b4}\x02\u{b7}\
-		\x02\u{b9}\x02\u{bc}\x02\u{be}\x02\u{c0}\x02\u{c2}\x02\u{d8}\x02\u{da}\
-		\x02\u{f8}\x02\u{fa}\x02\u{301}\x02\u{372}\x02\u{1681}\x02\u{1683}\x02\
-		\u{180f}\x02\u{1811}\x02\u{1dc1}\x02\u{1e02}\x02\u{2001}\x02\u{200d}\x02\
-		\u{200f}\x02\u{202c}\x02\u{2030}\x02\u{2041}\x02\u{2042}\x02\u{2056}\x02\
-		\u{2056}\x02\u{2062}\x02\u{20d1}\x02\u{2102}\x02\u{2191}\x02\u{2462}\x02\
-		\u{2501}\x02\u{2778}\x02\u{2795}\x02\u{2c02}\x02\u{2e01}\x02\u{2e82}\x02\
-		\u{3001}\x02\u{3006}\x02\u{3009}\x02\u{3023}\x02\u{3031}\x02\u{3033}\x02\
-		\u{10801}\x02\u{f902}\x02\u{fd3f}\x02\u{fd42}\x02\u{fdd1}\x02\u{fdf2}\x02\
-		\u{fe21}\x02\u{fe32}\x02\u{fe46}\x02\u{fe49}\x02\u{ffff}\x02\x02\x03\u{ffff}\
-		\x03\x02\x04\u{ffff}\x04\x02\x05\u{ffff}\x05\x02\x06\u{ffff}\x06\x02\x07\
-		\u{ffff}\x07\x02\x08\u{ffff}\x08\x02\x09\u{ffff}\x09\x02\x0a\u{ffff}\x0a\
-		\x02\x0b\u{ffff}\x0b\x02\x0c\u{ffff}\x0c\x02\x0d\u{ffff}\x0d\x02\x0e\u{ffff}\
-		\x0e\x02\x0f\u{ffff}\x0f\x02\x10\u{ffff}\x10\u{337}\x02\x0e\x03\x02\x02\
-		\x02\x02\x10\x03\x02\x02\x02\x02\x12\x03\x02\x02\x02\x02\x14\x03\x02\x02\
-		\x02\x02\x1c\x03\x02\x02\x02\x02\x1e\x03\x02\x02\x02\x02\x20\x03\x02\x02\
-		\x02\x03\x22\x03\x02\x02\x02\x03\x24\x03\x02\x02\x02\x04\x26\x03\x02\x02\
-		\x02\x04\x28\x03\x02\x02\x02\x04\x2a\x03\x02\x02\x02\x04\x2c\x03\x02\x02\
-		\x02\x04\x2e\x03\x02\x02\x02\x04\x30\x03\x02\x02\x02\x04\x32\x03\x02\x02\
-		\x02\x04\x34\x03\x02\x02\x02\x04\x36\x03\x02\x02\x02\x04\x38\x03\x02\x02\
-		\x02\x05\x3a\x03\x02\x02\x02\x05\x3c\x03\x02\x02\x02\x05\x3e\x03\x02\x02\
-		\x02\x05\x40\x03\x02\x02\x02\x05\x42\x03\x02\x02\x02\x05\x44\x03\x02\x02\
-		\x02\x05\x46\x03\x02\x02\x02\x05\x48\x03\x02\x02\x02\x06\x4c\x03\x02\x02\
-		\x02\x06\x4e\x03\x02\x02\x02\x07\x50\x03\x02\x02\x02\x07\x52\x03\x02\x02\
-		\x02\x07\x54\x03\x02\x02\x02\x07\x56\x03\x02\x02\x02\x07\x58\x03\x02\x02\
-		\x02\x07\x5a\x03\x02\x02\x02\x08\x5c\x03\x02\x02\x02\x08\x5e\x03\x02\x02\
-		\x02\x08\x60\x03\x02\x02\x02\x09\x62\x03\x02\x02\x02\x09\x64\x03\x02\x02\
-		\x02\x09\x66\x03\x02\x02\x02\x09\x68\x03\x02\x02\x02\x09\x6a\x03\x02\x02\
-		\x02\x09\x6c\x03\x02\x02\x02\x09\x6e\x03\x02\x02\x02\x09\x70\x03\x02\x02\
-		\x02\x09\x72\x03\x02\x02\x02\x09\x74\x03\x02\x02\x02\x09\x76\x03\x02\x02\
-		\x02\x09\x78\x03\x02\x02\x02\x09\x7a\x03\x02\x02\x02\x09\x7c\x03\x02\x02\
-		\x02\x09\x7e\x03\x02\x02\x02\x09\u{80}\x03\x02\x02\x02\x09\u{82}\x03\x02\
-		\x02\x02\x09\u{84}\x03\x02\x02\x02\x09\u{86}\x03\x02\x02\x02\x09\u{88}\
-		\x03\x02\x02\x02\x09\u{8a}\x03\x02\x02\x02\x09\u{8c}\x03\x02\x02\x02\x09\
-		\u{8e}\x03\x02\x02\x02\x09\u{90}\x03\x02\x02\x02\x09\u{92}\x03\x02\x02\
-		\x02\x09\u{94}\x03\x02\x02\x02\x09\u{96}\x03\x02\x02\x02\x09\u{98}\x03\
-		\x02\x02\x02\x09\u{9a}\x03\x02\x02\x02\x09\u{9c}\x03\x02\x02\x02\x09\u{9e}\
-		\x03\x02\x02\x02\x09\u{a0}\x03\x02\x02\x02\x09\u{a2}\x03\x02\x02\x02\x09\
-		\u{a4}\x03\x02\x02\x02\x09\u{a6}\x03\x02\x02\x02\x09\u{a8}\x03\x02\x02\
-		\x02\x09\u{aa}\x03\x02\x02\x02\x09\u{ac}\x03\x02\x02\x02\x09\u{ae}\x03\
-		\x02\x02\x02\x0a\u{b4}\x03\x02\x02\x02\x0a\u{b6}\x03\x02\x02\x02\x0a\u{b8}\
-		\x03\x02\x02\x02\x0a\u{ba}\x03\x02\x02\x02\x0a\u{bc}\x03\x02\x02\x02\x0a\
-		\u{be}\x03\x02\x02\x02\x0a\u{c0}\x03\x02\x02\x02\x0a\u{c2}\x03\x02\x02\
-		\x02\x0a\u{c4}\x03\x02\x02\x02\x0a\u{c6}\x03\x02\x02\x02\x0a\u{c8}\x03\
-		\x02\x02\x02\x0a\u{ca}\x03\x02\x02\x02\x0a\u{cc}\x03\x02\x02\x02\x0a\u{ce}\
-		\x03\x02\x02\x02\x0a\u{d0}\x03\x02\x02\x02\x0b\u{d2}\x03\x02\x02\x02\x0b\
-		\u{d4}\x03\x02\x02\x02\x0b\u{d6}\x03\x02\x02\x02\x0c\u{d8}\x03\x02\x02\
-		\x02\x0c\u{da}\x03\x02\x02\x02\x0d\u{dc}\x03\x02\x02\x02\x0d\u{de}\x03\
-		\x02\x02\x02\x0d\u{e0}\x03\x02\x02\x02\x0e\u{e3}\x03\x02\x02\x02\x10\u{e9}\
-		\x03\x02\x02\x02\x12\u{f9}\x03\x02\x02\x02\x14\u{103}\x03\x02\x02\x02\x16\
-		\u{108}\x03\x02\x02\x02\x18\u{10c}\x03\x02\x02\x02\x1a\u{10f}\x03\x02\x02\
-		\x02\x1c\u{113}\x03\x02\x02\x02\x1e\u{119}\x03\x02\x02\x02\x20\u{122}\x03\
-		\x02\x02\x02\x22\u{127}\x03\x02\x02\x02\x24\u{12b}\x03\x02\x02\x02\x26\
-		\u{131}\x03\x02\x02\x02\x28\u{135}\x03\x02\x02\x02\x2a\u{13a}\x03\x02\x02\
-		\x02\x2c\u{13f}\x03\x02\x02\x02\x2e\u{145}\x03\x02\x02\x02\x30\u{148}\x03\
-		\x02\x02\x02\x32\u{14d}\x03\x02\x02\x02\x34\u{153}\x03\x02\x02\x02\x36\
-		\u{158}\x03\x02\x02\x02\x38\u{15e}\x03\x02\x02\x02\x3a\u{163}\x03\x02\x02\
-		\x02\x3c\u{16c}\x03\x02\x02\x02\x3e\u{170}\x03\x02\x02\x02\x40\u{175}\x03\
-		\x02\x02\x02\x42\u{17b}\x03\x02\x02\x02\x44\u{180}\x03\x02\x02\x02\x46\
-		\u{187}\x03\x02\x02\x02\x48\u{191}\x03\x02\x02\x02\x4a\u{193}\x03\x02\x02\
-		\x02\x4c\u{195}\x03\x02\x02\x02\x4e\u{19a}\x03\x02\x02\x02\x50\u{19e}\x03\
-		\x02\x02\x02\x52\u{1a2}\x03\x02\x02\x02\x54\u{1a6}\x03\x02\x02\x02\x56\
-		\u{1ac}\x03\x02\x02\x02\x58\u{1b1}\x03\x02\x02\x02\x5a\u{1b6}\x03\x02\x02\
-		\x02\x5c\u{1b8}\x03\x02\x02\x02\x5e\u{1bc}\x03\x02\x02\x02\x60\u{1c1}\x03\
-		\x02\x02\x02\x62\u{1c7}\x03\x02\x02\x02\x64\u{1cb}\x03\x02\x02\x02\x66\
-		\u{1d0}\x03\x02\x02\x02\x68\u{1d6}\x03\x02\x02\x02\x6a\u{1de}\x03\x02\x02\
-		\x02\x6c\u{1e5}\x03\x02\x02\x02\x6e\u{1ec}\x03\x02\x02\x02\x70\u{1f4}\x03\
-		\x02\x02\x02\x72\u{1f9}\x03\x02\x02\x02\x74\u{1fe}\x03\x02\x02\x02\x76\
-		\u{205}\x03\x02\x02\x02\x78\u{20c}\x03\x02\x02\x02\x7a\u{212}\x03\x02\x02\
-		\x02\x7c\u{218}\x03\x02\x02\x02\x7e\u{21e}\x03\x02\x02\x02\u{80}\u{220}\
-		\x03\x02\x02\x02\u{82}\u{223}\x03\x02\x02\x02\u{84}\u{226}\x03\x02\x02\
-		\x02\u{86}\u{229}\x03\x02\x02\x02\u{88}\u{22c}\x03\x02\x02\x02\u{8a}\u{22f}\
-		\x03\x02\x02\x02\u{8c}\u{231}\x03\x02\x02\x02\u{8e}\u{233}\x03\x02\x02\
-		\x02\u{90}\u{235}\x03\x02\x02\x02\u{92}\u{237}\x03\x02\x02\x02\u{94}\u{239}\
-		\x03\x02\x02\x02\u{96}\u{23b}\x03\x02\x02\x02\u{98}\u{23d}\x03\x02\x02\
-		\x02\u{9a}\u{23f}\x03\x02\x02\x02\u{9c}\u{242}\x03\x02\x02\x02\u{9e}\u{24b}\
-		\x03\x02\x02\x02\u{a0}\u{254}\x03\x02\x02\x02\u{a2}\u{25b}\x03\x02\x02\
-		\x02\u{a4}\u{266}\x03\x02\x02\x02\u{a6}\u{268}\x03\x02\x02\x02\u{a8}\u{26c}\
-		\x03\x02\x02\x02\u{aa}\u{273}\x03\x02\x02\x02\u{ac}\u{276}\x03\x02\x02\
-		\x02\u{ae}\u{27d}\x03\x02\x02\x02\u{b0}\u{280}\x03\x02\x02\x02\u{b2}\u{284}\
-		\x03\x02\x02\x02\u{b4}\u{286}\x03\x02\x02\x02\u{b6}\u{28a}\x03\x02\x02\
-		\x02\u{b8}\u{291}\x03\x02\x02\x02\u{ba}\u{29c}\x03\x02\x02\x02\u{bc}\u{2a4}\
-		\x03\x02\x02\x02\u{be}\u{2ac}\x03\x02\x02\x02\u{c0}\u{2b2}\x03\x02\x02\
-		\x02\u{c2}\u{2bb}\x03\x02\x02\x02\u{c4}\u{2c7}\x03\x02\x02\x02\u{c6}\u{2d0}\
-		\x03\x02\x02\x02\u{c8}\u{2d9}\x03\x02\x02\x02\u{ca}\u{2e2}\x03\x02\x02\
-		\x02\u{cc}\u{2ed}\x03\x02\x02\x02\u{ce}\u{2f5}\x03\x02\x02\x02\u{d0}\u{2fa}\
-		\x03\x02\x02\x02\u{d2}\u{2ff}\x03\x02\x02\x02\u{d4}\u{304}\x03\x02\x02\
-		\x02\u{d6}\u{309}\x03\x02\x02\x02\u{d8}\u{30d}\x03\x02\x02\x02\u{da}\u{312}\
-		\x03\x02\x02\x02\u{dc}\u{318}\x03\x02\x02\x02\u{de}\u{31d}\x03\x02\x02\
-		\x02\u{e0}\u{322}\x03\x02\x02\x02\u{e2}\u{e4}\x09\x02\x02\x02\u{e3}\u{e2}\
-		\x03\x02\x02\x02\u{e4}\u{e5}\x03\x02\x02\x02\u{e5}\u{e3}\x03\x02\x02\x02\
-		\u{e5}\u{e6}\x03\x02\x02\x02\u{e6}\u{e7}\x03\x02\x02\x02\u{e7}\u{e8}\x08\
-		\x02\x02\x02\u{e8}\x0f\x03\x02\x02\x02\u{e9}\u{ea}\x07\x31\x02\x02\u{ea}\
-		\u{eb}\x07\x31\x02\x02\u{eb}\u{ef}\x03\x02\x02\x02\u{ec}\u{ee}\x0a\x03\
-		\x02\x02\u{ed}\u{ec}\x03\x02\x02\x02\u{ee}\u{f1}\x03\x02\x02\x02\u{ef}\
-		\u{ed}\x03\x02\x02\x02\u{ef}\u{f0}\x03\x02\x02\x02\u{f0}\u{f2}\x03\x02\
-		\x02\x02\u{f1}\u{ef}\x03\x02\x02\x02\u{f2}\u{f3}\x08\x03\x03\x02\u{f3}\
+		\x03\x61\x03\x61\x03\x61\x03\x61\x03\x64\x03\x64\x03\x64\x03\x64\x03\x64\
+		\x03\x65\x03\x65\x03\x65\x03\x65\x03\x65\x03\x66\x03\x66\x03\x66\x03\x66\
+		\x03\x66\x03\x67\x03\x67\x03\x67\x03\x67\x03\x68\x06\x68\u{30a}\x0a\x68\
+		\x0d\x68\x0e\x68\u{30b}\x03\x69\x03\x69\x03\x69\x03\x69\x03\x69\x03\x6a\
+		\x03\x6a\x03\x6a\x03\x6a\x03\x6a\x03\x6a\x03\x6b\x03\x6b\x03\x6b\x03\x6b\
+		\x03\x6b\x03\x6c\x03\x6c\x03\x6c\x03\x6c\x03\x6c\x03\x6d\x03\x6d\x03\x6d\
+		\x03\x6d\x03\x6d\x03\x6d\x04\x62\x09\x62\x03\x62\x03\x62\x03\x62\x03\x62\
+		\x03\x62\x04\x63\x09\x63\x03\x63\x03\x63\x03\x63\x03\x63\x03\x63\x03\x63\
+		\x03\x63\x03\x63\x0c\x6b\x0a\x6b\x07\x6b\u{33a}\x03\x6b\x03\x6b\x0b\x6b\
+		\x0e\x6b\u{33e}\x02\x02\x6e\x0e\x06\x10\x07\x12\x08\x14\x09\x16\x02\x18\
+		\x02\x1a\x02\x1c\x0a\x1e\x0b\x20\x0c\x22\x0d\x24\x02\x26\x0e\x28\x02\x2a\
+		\x02\x2c\x0f\x2e\x10\x30\x11\x32\x02\x34\x12\x36\x13\x38\x02\x3a\x02\x3c\
+		\x02\x3e\x14\x40\x02\x42\x02\x44\x02\x46\x15\x48\x16\x4a\x02\x4c\x02\x4e\
+		\x17\x50\x18\x52\x19\x54\x02\x56\x02\x58\x02\x5a\x1a\x5c\x1b\x5e\x02\x60\
+		\x1c\x62\x1d\x64\x1e\x66\x1f\x68\x20\x6a\x21\x6c\x22\x6e\x23\x70\x24\x72\
+		\x25\x74\x26\x76\x27\x78\x28\x7a\x29\x7c\x2a\x7e\x2b\u{80}\x2c\u{82}\x2d\
+		\u{84}\x2e\u{86}\x2f\u{88}\x30\u{8a}\x31\u{8c}\x32\u{8e}\x33\u{90}\x34\
+		\u{92}\x35\u{94}\x36\u{96}\x37\u{98}\x38\u{9a}\x39\u{9c}\x51\u{9e}\x52\
+		\u{a0}\x53\u{a2}\x3a\u{a4}\x3b\u{a6}\x3c\u{a8}\x02\u{aa}\x3d\u{ac}\x3e\
+		\u{ae}\x3f\u{b0}\x02\u{b2}\x02\u{b4}\x40\u{b6}\x41\u{b8}\x42\u{ba}\x43\
+		\u{bc}\x44\u{be}\x45\u{c0}\x46\u{c2}\x47\u{c4}\x48\u{c6}\x49\u{c8}\x4a\
+		\u{ca}\x4b\u{cc}\x4c\u{328}\x54\u{32f}\x55\u{ce}\x4d\u{d0}\x02\u{d2}\x4e\
+		\u{d4}\x4f\u{d6}\x50\u{d8}\x02\u{da}\x02\u{dc}\x02\u{de}\x02\u{e0}\x02\
+		\x0e\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0f\x04\x02\x0b\x0b\
+		\x22\x22\x04\x02\x0c\x0c\x0f\x0f\x07\x02\x32\x3b\u{302}\u{371}\u{1dc2}\
+		\u{1e01}\u{20d2}\u{2101}\u{fe22}\u{fe31}\x03\x02\x22\x22\x04\x02\x31\x31\
+		\x3e\x3e\x09\x02\x0c\x0c\x0f\x0f\x25\x25\x31\x31\x3e\x3e\x5e\x5e\x7d\x7d\
+		\x09\x02\x25\x25\x31\x31\x3e\x3e\x40\x40\x5e\x5e\x7d\x7d\x7f\x7f\x07\x02\
+		\x0b\x0c\x0f\x0f\x22\x22\x25\x26\x3e\x3e\x06\x02\x0c\x0c\x0f\x0f\x24\x24\
+		\x5e\x5e\x04\x02\x24\x24\x5e\x5e\x03\x02\x32\x3b\x0c\x02\x0b\x0f\x22\x22\
+		\u{87}\u{87}\u{a2}\u{a2}\u{1682}\u{1682}\u{2002}\u{200c}\u{202a}\u{202b}\
+		\u{2031}\u{2031}\u{2061}\u{2061}\u{3002}\u{3002}\x04\x02\x40\x40\x7d\x7d\
+		\x03\x33\x02\x43\x02\x5c\x02\x61\x02\x61\x02\x63\x02\x7c\x02\u{aa}\x02\
+		\u{aa}\x02\u{ac}\x02\u{ac}\x02\u{af}\x02\u{af}\x02\u{b1}\x02\u{b1}\x02\
+		\u{b4}\x02\u{b7}\x02\u{b9}\x02\u{bc}\x02\u{be}\x02\u{c0}\x02\u{c2}\x02\
+		\u{d8}\x02\u{da}\x02\u{f8}\x02\u{fa}\x02\u{301}\x02\u{372}\x02\u{1681}\
+		\x02\u{1683}\x02\u{180f}\x02\u{1811}\x02\u{1dc1}\x02\u{1e02}\x02\u{2001}\
+		\x02\u{200d}\x02\u{200f}\x02\u{202c}\x02\u{2030}\x02\u{2041}\x02\u{2042}\
+		\x02\u{2056}\x02\u{2056}\x02\u{2062}\x02\u{20d1}\x02\u{2102}\x02\u{2191}\
+		\x02\u{2462}\x02\u{2501}\x02\u{2778}\x02\u{2795}\x02\u{2c02}\x02\u{2e01}\
+		\x02\u{2e82}\x02\u{3001}\x02\u{3006}\x02\u{3009}\x02\u{3023}\x02\u{3031}\
+		\x02\u{3033}\x02\u{10801}\x02\u{f902}\x02\u{fd3f}\x02\u{fd42}\x02\u{fdd1}\
+		\x02\u{fdf2}\x02\u{fe21}\x02\u{fe32}\x02\u{fe46}\x02\u{fe49}\x02\u{ffff}\
+		\x02\x02\x03\u{ffff}\x03\x02\x04\u{ffff}\x04\x02\x05\u{ffff}\x05\x02\x06\
+		\u{ffff}\x06\x02\x07\u{ffff}\x07\x02\x08\u{ffff}\x08\x02\x09\u{ffff}\x09\
+		\x02\x0a\u{ffff}\x0a\x02\x0b\u{ffff}\x0b\x02\x0c\u{ffff}\x0c\x02\x0d\u{ffff}\
+		\x0d\x02\x0e\u{ffff}\x0e\x02\x0f\u{ffff}\x0f\x02\x10\u{ffff}\x10\u{350}\
+		\x02\x0e\x03\x02\x02\x02\x02\x10\x03\x02\x02\x02\x02\x12\x03\x02\x02\x02\
+		\x02\x14\x03\x02\x02\x02\x02\x1c\x03\x02\x02\x02\x02\x1e\x03\x02\x02\x02\
+		\x02\x20\x03\x02\x02\x02\x03\x22\x03\x02\x02\x02\x03\x24\x03\x02\x02\x02\
+		\x04\x26\x03\x02\x02\x02\x04\x28\x03\x02\x02\x02\x04\x2a\x03\x02\x02\x02\
+		\x04\x2c\x03\x02\x02\x02\x04\x2e\x03\x02\x02\x02\x04\x30\x03\x02\x02\x02\
+		\x04\x32\x03\x02\x02\x02\x04\x34\x03\x02\x02\x02\x04\x36\x03\x02\x02\x02\
+		\x04\x38\x03\x02\x02\x02\x05\x3a\x03\x02\x02\x02\x05\x3c\x03\x02\x02\x02\
+		\x05\x3e\x03\x02\x02\x02\x05\x40\x03\x02\x02\x02\x05\x42\x03\x02\x02\x02\
+		\x05\x44\x03\x02\x02\x02\x05\x46\x03\x02\x02\x02\x05\x48\x03\x02\x02\x02\
+		\x06\x4c\x03\x02\x02\x02\x06\x4e\x03\x02\x02\x02\x07\x50\x03\x02\x02\x02\
+		\x07\x52\x03\x02\x02\x02\x07\x54\x03\x02\x02\x02\x07\x56\x03\x02\x02\x02\
+		\x07\x58\x03\x02\x02\x02\x07\x5a\x03\x02\x02\x02\x08\x5c\x03\x02\x02\x02\
+		\x08\x5e\x03\x02\x02\x02\x08\x60\x03\x02\x02\x02\x09\x62\x03\x02\x02\x02\
+		\x09\x64\x03\x02\x02\x02\x09\x66\x03\x02\x02\x02\x09\x68\x03\x02\x02\x02\
+		\x09\x6a\x03\x02\x02\x02\x09\x6c\x03\x02\x02\x02\x09\x6e\x03\x02\x02\x02\
+		\x09\x70\x03\x02\x02\x02\x09\x72\x03\x02\x02\x02\x09\x74\x03\x02\x02\x02\
+		\x09\x76\x03\x02\x02\x02\x09\x78\x03\x02\x02\x02\x09\x7a\x03\x02\x02\x02\
+		\x09\x7c\x03\x02\x02\x02\x09\x7e\x03\x02\x02\x02\x09\u{80}\x03\x02\x02\
+		\x02\x09\u{82}\x03\x02\x02\x02\x09\u{84}\x03\x02\x02\x02\x09\u{86}\x03\
+		\x02\x02\x02\x09\u{88}\x03\x02\x02\x02\x09\u{8a}\x03\x02\x02\x02\x09\u{8c}\
+		\x03\x02\x02\x02\x09\u{8e}\x03\x02\x02\x02\x09\u{90}\x03\x02\x02\x02\x09\
+		\u{92}\x03\x02\x02\x02\x09\u{94}\x03\x02\x02\x02\x09\u{96}\x03\x02\x02\
+		\x02\x09\u{98}\x03\x02\x02\x02\x09\u{9a}\x03\x02\x02\x02\x09\u{9c}\x03\
+		\x02\x02\x02\x09\u{9e}\x03\x02\x02\x02\x09\u{a0}\x03\x02\x02\x02\x09\u{a2}\
+		\x03\x02\x02\x02\x09\u{a4}\x03\x02\x02\x02\x09\u{a6}\x03\x02\x02\x02\x09\
+		\u{a8}\x03\x02\x02\x02\x09\u{aa}\x03\x02\x02\x02\x09\u{ac}\x03\x02\x02\
+		\x02\x09\u{ae}\x03\x02\x02\x02\x0a\u{b4}\x03\x02\x02\x02\x0a\u{b6}\x03\
+		\x02\x02\x02\x0a\u{b8}\x03\x02\x02\x02\x0a\u{ba}\x03\x02\x02\x02\x0a\u{bc}\
+		\x03\x02\x02\x02\x0a\u{be}\x03\x02\x02\x02\x0a\u{c0}\x03\x02\x02\x02\x0a\
+		\u{c2}\x03\x02\x02\x02\x0a\u{c4}\x03\x02\x02\x02\x0a\u{c6}\x03\x02\x02\
+		\x02\x0a\u{c8}\x03\x02\x02\x02\x0a\u{ca}\x03\x02\x02\x02\x0a\u{cc}\x03\
+		\x02\x02\x02\x0a\u{328}\x03\x02\x02\x02\x0a\u{32f}\x03\x02\x02\x02\x0a\
+		\u{ce}\x03\x02\x02\x02\x0a\u{d0}\x03\x02\x02\x02\x0b\u{d2}\x03\x02\x02\
+		\x02\x0b\u{d4}\x03\x02\x02\x02\x0b\u{d6}\x03\x02\x02\x02\x0c\u{d8}\x03\
+		\x02\x02\x02\x0c\u{da}\x03\x02\x02\x02\x0d\u{dc}\x03\x02\x02\x02\x0d\u{de}\
+		\x03\x02\x02\x02\x0d\u{e0}\x03\x02\x02\x02\x0e\u{e3}\x03\x02\x02\x02\x10\
+		\u{e9}\x03\x02\x02\x02\x12\u{f9}\x03\x02\x02\x02\x14\u{103}\x03\x02\x02\
+		\x02\x16\u{108}\x03\x02\x02\x02\x18\u{10c}\x03\x02\x02\x02\x1a\u{10f}\x03\
+		\x02\x02\x02\x1c\u{113}\x03\x02\x02\x02\x1e\u{119}\x03\x02\x02\x02\x20\
+		\u{122}\x03\x02\x02\x02\x22\u{127}\x03\x02\x02\x02\x24\u{12b}\x03\x02\x02\
+		\x02\x26\u{131}\x03\x02\x02\x02\x28\u{135}\x03\x02\x02\x02\x2a\u{13a}\x03\
+		\x02\x02\x02\x2c\u{13f}\x03\x02\x02\x02\x2e\u{145}\x03\x02\x02\x02\x30\
+		\u{148}\x03\x02\x02\x02\x32\u{14d}\x03\x02\x02\x02\x34\u{153}\x03\x02\x02\
+		\x02\x36\u{158}\x03\x02\x02\x02\x38\u{15e}\x03\x02\x02\x02\x3a\u{163}\x03\
+		\x02\x02\x02\x3c\u{16c}\x03\x02\x02\x02\x3e\u{170}\x03\x02\x02\x02\x40\
+		\u{175}\x03\x02\x02\x02\x42\u{17b}\x03\x02\x02\x02\x44\u{180}\x03\x02\x02\
+		\x02\x46\u{187}\x03\x02\x02\x02\x48\u{191}\x03\x02\x02\x02\x4a\u{193}\x03\
+		\x02\x02\x02\x4c\u{195}\x03\x02\x02\x02\x4e\u{19a}\x03\x02\x02\x02\x50\
+		\u{19e}\x03\x02\x02\x02\x52\u{1a2}\x03\x02\x02\x02\x54\u{1a6}\x03\x02\x02\
+		\x02\x56\u{1ac}\x03\x02\x02\x02\x58\u{1b1}\x03\x02\x02\x02\x5a\u{1b6}\x03\
+		\x02\x02\x02\x5c\u{1b8}\x03\x02\x02\x02\x5e\u{1bc}\x03\x02\x02\x02\x60\
+		\u{1c1}\x03\x02\x02\x02\x62\u{1c7}\x03\x02\x02\x02\x64\u{1cb}\x03\x02\x02\
+		\x02\x66\u{1d0}\x03\x02\x02\x02\x68\u{1d6}\x03\x02\x02\x02\x6a\u{1de}\x03\
+		\x02\x02\x02\x6c\u{1e5}\x03\x02\x02\x02\x6e\u{1ec}\x03\x02\x02\x02\x70\
+		\u{1f4}\x03\x02\x02\x02\x72\u{1f9}\x03\x02\x02\x02\x74\u{1fe}\x03\x02\x02\
+		\x02\x76\u{205}\x03\x02\x02\x02\x78\u{20c}\x03\x02\x02\x02\x7a\u{212}\x03\
+		\x02\x02\x02\x7c\u{218}\x03\x02\x02\x02\x7e\u{21e}\x03\x02\x02\x02\u{80}\
+		\u{220}\x03\x02\x02\x02\u{82}\u{223}\x03\x02\x02\x02\u{84}\u{226}\x03\x02\
+		\x02\x02\u{86}\u{229}\x03\x02\x02\x02\u{88}\u{22c}\x03\x02\x02\x02\u{8a}\
+		\u{22f}\x03\x02\x02\x02\u{8c}\u{231}\x03\x02\x02\x02\u{8e}\u{233}\x03\x02\
+		\x02\x02\u{90}\u{235}\x03\x02\x02\x02\u{92}\u{237}\x03\x02\x02\x02\u{94}\
+		\u{239}\x03\x02\x02\x02\u{96}\u{23b}\x03\x02\x02\x02\u{98}\u{23d}\x03\x02\
+		\x02\x02\u{9a}\u{23f}\x03\x02\x02\x02\u{9c}\u{242}\x03\x02\x02\x02\u{9e}\
+		\u{24b}\x03\x02\x02\x02\u{a0}\u{254}\x03\x02\x02\x02\u{a2}\u{25b}\x03\x02\
+		\x02\x02\u{a4}\u{266}\x03\x02\x02\x02\u{a6}\u{268}\x03\x02\x02\x02\u{a8}\
+		\u{26c}\x03\x02\x02\x02\u{aa}\u{273}\x03\x02\x02\x02\u{ac}\u{276}\x03\x02\
+		\x02\x02\u{ae}\u{27d}\x03\x02\x02\x02\u{b0}\u{280}\x03\x02\x02\x02\u{b2}\
+		\u{284}\x03\x02\x02\x02\u{b4}\u{286}\x03\x02\x02\x02\u{b6}\u{28a}\x03\x02\
+		\x02\x02\u{b8}\u{291}\x03\x02\x02\x02\u{ba}\u{29c}\x03\x02\x02\x02\u{bc}\
+		\u{2a4}\x03\x02\x02\x02\u{be}\u{2ac}\x03\x02\x02\x02\u{c0}\u{2b2}\x03\x02\
+		\x02\x02\u{c2}\u{2bb}\x03\x02\x02\x02\u{c4}\u{2c7}\x03\x02\x02\x02\u{c6}\
+		\u{2d0}\x03\x02\x02\x02\u{c8}\u{2d9}\x03\x02\x02\x02\u{ca}\u{2e2}\x03\x02\
+		\x02\x02\u{cc}\u{2ed}\x03\x02\x02\x02\u{ce}\u{2f5}\x03\x02\x02\x02\u{d0}\
+		\u{2fa}\x03\x02\x02\x02\u{d2}\u{2ff}\x03\x02\x02\x02\u{d4}\u{304}\x03\x02\
+		\x02\x02\u{d6}\u{309}\x03\x02\x02\x02\u{d8}\u{30d}\x03\x02\x02\x02\u{da}\
+		\u{312}\x03\x02\x02\x02\u{dc}\u{318}\x03\x02\x02\x02\u{de}\u{31d}\x03\x02\
+		\x02\x02\u{e0}\u{322}\x03\x02\x02\x02\u{e2}\u{e4}\x09\x02\x02\x02\u{e3}\
+		\u{e2}\x03\x02\x02\x02\u{e4}\u{e5}\x03\x02\x02\x02\u{e5}\u{e3}\x03\x02\
+		\x02\x02\u{e5}\u{e6}\x03\x02\x02\x02\u{e6}\u{e7}\x03\x02\x02\x02\u{e7}\
+		\u{e8}\x08\x02\x02\x02\u{e8}\x0f\x03\x02\x02\x02\u{e9}\u{ea}\x07\x31\x02\
+		\x02\u{ea}\u{eb}\x07\x31\x02\x02\u{eb}\u{ef}\x03\x02\x02\x02\u{ec}\u{ee}\
+		\x0a\x03\x02\x02\u{ed}\u{ec}\x03\x02\x02\x02\u{ee}\u{f1}\x03\x02\x02\x02\
+		\u{ef}\u{ed}\x03\x02\x02\x02\u{ef}\u{f0}\x03\x02\x02\x02\u{f0}\u{f2}\x03\
+		\x02\x02\x02\u{f1}\u{ef}\x03\x02\x02\x02\u{f2}\u{f3}\x08\x03\x03\x02\u{f3}\
 		\x11\x03\x02\x02\x02\u{f4}\u{f6}\x07\x0f\x02\x02\u{f5}\u{f4}\x03\x02\x02\
 		\x02\u{f5}\u{f6}\x03\x02\x02\x02\u{f6}\u{f7}\x03\x02\x02\x02\u{f7}\u{fa}\
 		\x07\x0c\x02\x02\u{f8}\u{fa}\x07\x0f\x02\x02\u{f9}\u{f5}\x03\x02\x02\x02\
@@ -1000,33 +1012,43 @@ const _serializedATN: &'static str =
 		\x02\u{2f0}\u{2f1}\x07\x63\x02\x02\u{2f1}\u{2f2}\x07\x6e\x02\x02\u{2f2}\
 		\u{2f3}\x03\x02\x02\x02\u{2f3}\u{2f4}\x09\x0d\x02\x02\u{2f4}\u{cd}\x03\
 		\x02\x02\x02\u{2f5}\u{2f6}\x07\x40\x02\x02\u{2f6}\u{2f7}\x07\x40\x02\x02\
-		\u{2f7}\u{2f8}\x03\x02\x02\x02\u{2f8}\u{2f9}\x08\x62\x09\x02\u{2f9}\u{cf}\
+		\u{2f7}\u{2f8}\x03\x02\x02\x02\u{2f8}\u{2f9}\x08\x64\x09\x02\u{2f9}\u{cf}\
 		\x03\x02\x02\x02\u{2fa}\u{2fb}\x0b\x02\x02\x02\u{2fb}\u{2fc}\x03\x02\x02\
-		\x02\u{2fc}\u{2fd}\x08\x63\x1a\x02\u{2fd}\u{2fe}\x08\x63\x1b\x02\u{2fe}\
+		\x02\u{2fc}\u{2fd}\x08\x65\x1a\x02\u{2fd}\u{2fe}\x08\x65\x1b\x02\u{2fe}\
 		\u{d1}\x03\x02\x02\x02\u{2ff}\u{300}\x07\x40\x02\x02\u{300}\u{301}\x07\
-		\x40\x02\x02\u{301}\u{302}\x03\x02\x02\x02\u{302}\u{303}\x08\x64\x09\x02\
+		\x40\x02\x02\u{301}\u{302}\x03\x02\x02\x02\u{302}\u{303}\x08\x66\x09\x02\
 		\u{303}\u{d3}\x03\x02\x02\x02\u{304}\u{305}\x07\x7d\x02\x02\u{305}\u{306}\
-		\x03\x02\x02\x02\u{306}\u{307}\x08\x65\x0f\x02\u{307}\u{d5}\x03\x02\x02\
+		\x03\x02\x02\x02\u{306}\u{307}\x08\x67\x0f\x02\u{307}\u{d5}\x03\x02\x02\
 		\x02\u{308}\u{30a}\x0a\x0e\x02\x02\u{309}\u{308}\x03\x02\x02\x02\u{30a}\
 		\u{30b}\x03\x02\x02\x02\u{30b}\u{309}\x03\x02\x02\x02\u{30b}\u{30c}\x03\
 		\x02\x02\x02\u{30c}\u{d7}\x03\x02\x02\x02\u{30d}\u{30e}\x05\x14\x05\x02\
-		\u{30e}\u{30f}\x03\x02\x02\x02\u{30f}\u{310}\x08\x67\x1c\x02\u{310}\u{311}\
-		\x08\x67\x09\x02\u{311}\u{d9}\x03\x02\x02\x02\u{312}\u{313}\x07\x40\x02\
+		\u{30e}\u{30f}\x03\x02\x02\x02\u{30f}\u{310}\x08\x69\x1c\x02\u{310}\u{311}\
+		\x08\x69\x1d\x02\u{311}\u{d9}\x03\x02\x02\x02\u{312}\u{313}\x07\x40\x02\
 		\x02\u{313}\u{314}\x07\x40\x02\x02\u{314}\u{315}\x03\x02\x02\x02\u{315}\
-		\u{316}\x08\x68\x17\x02\u{316}\u{317}\x08\x68\x09\x02\u{317}\u{db}\x03\
-		\x02\x02\x02\u{318}\u{319}\x05\x14\x05\x02\u{319}\u{31a}\x03\x02\x02\x02\
-		\u{31a}\u{31b}\x08\x69\x1c\x02\u{31b}\u{31c}\x08\x69\x09\x02\u{31c}\u{dd}\
+		\u{316}\x08\x6a\x17\x02\u{316}\u{317}\x08\x6a\x09\x02\u{317}\u{db}\x03\
+		\x02\x02\x02\u{318}\u{339}\x05\x14\x05\x02\u{319}\u{31a}\x03\x02\x02\x02\
+		\u{31a}\u{31b}\x08\x6b\x1c\x02\u{31b}\u{31c}\x08\x6b\x09\x02\u{31c}\u{dd}\
 		\x03\x02\x02\x02\u{31d}\u{31e}\x05\x34\x15\x02\u{31e}\u{31f}\x03\x02\x02\
-		\x02\u{31f}\u{320}\x08\x6a\x14\x02\u{320}\u{321}\x08\x6a\x1d\x02\u{321}\
+		\x02\u{31f}\u{320}\x08\x6c\x14\x02\u{320}\u{321}\x08\x6c\x1d\x02\u{321}\
 		\u{df}\x03\x02\x02\x02\u{322}\u{323}\x07\x40\x02\x02\u{323}\u{324}\x07\
-		\x40\x02\x02\u{324}\u{325}\x03\x02\x02\x02\u{325}\u{326}\x08\x6b\x17\x02\
-		\u{326}\u{327}\x08\x6b\x09\x02\u{327}\u{e1}\x03\x02\x02\x02\x2f\x02\x03\
-		\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\u{e5}\u{ef}\u{f5}\u{f9}\u{fe}\
-		\u{105}\u{108}\u{10c}\u{111}\u{11d}\u{129}\u{16c}\u{18e}\u{191}\u{1c3}\
-		\u{1de}\u{1e5}\u{1ec}\u{1f4}\u{1f9}\u{1fe}\u{205}\u{20c}\u{212}\u{218}\
-		\u{21e}\u{25f}\u{261}\u{27d}\u{282}\u{2a2}\u{2eb}\u{30b}\x1e\x02\x03\x02\
-		\x02\x05\x02\x02\x04\x02\x07\x04\x02\x07\x03\x02\x07\x08\x02\x09\x08\x02\
-		\x06\x02\x02\x09\x07\x02\x07\x0a\x02\x09\x0c\x02\x07\x07\x02\x07\x05\x02\
-		\x07\x09\x02\x08\x02\x02\x07\x06\x02\x09\x16\x02\x04\x07\x02\x09\x12\x02\
-		\x09\x11\x02\x09\x3b\x02\x09\x4d\x02\x07\x0d\x02\x07\x0c\x02\x09\x50\x02\
-		\x04\x0b\x02\x09\x09\x02\x04\x09\x02";
+		\x40\x02\x02\u{324}\u{325}\x03\x02\x02\x02\u{325}\u{326}\x08\x6d\x17\x02\
+		\u{326}\u{327}\x08\x6d\x09\x02\u{327}\u{e1}\x03\x02\x02\x02\u{328}\u{32a}\
+		\x03\x02\x02\x02\u{32a}\u{32b}\x07\x71\x02\x02\u{32b}\u{32c}\x07\x70\x02\
+		\x02\u{32c}\u{32d}\x07\x65\x02\x02\u{32d}\u{32e}\x07\x67\x02\x02\u{32e}\
+		\u{329}\x03\x02\x02\x02\u{32f}\u{331}\x03\x02\x02\x02\u{331}\u{332}\x07\
+		\x67\x02\x02\u{332}\u{333}\x07\x70\x02\x02\u{333}\u{334}\x07\x66\x02\x02\
+		\u{334}\u{335}\x07\x71\x02\x02\u{335}\u{336}\x07\x70\x02\x02\u{336}\u{337}\
+		\x07\x65\x02\x02\u{337}\u{338}\x07\x67\x02\x02\u{338}\u{330}\x03\x02\x02\
+		\x02\u{339}\u{33b}\x03\x02\x02\x02\u{339}\u{33f}\x03\x02\x02\x02\u{33a}\
+		\u{33e}\x03\x02\x02\x02\u{33b}\u{33c}\x03\x02\x02\x02\u{33c}\u{33d}\x07\
+		\x30\x02\x02\u{33d}\u{33a}\x05\x14\x05\x02\u{33e}\u{339}\x03\x02\x02\x02\
+		\u{33f}\u{319}\x03\x02\x02\x02\x30\x02\x03\x04\x05\x06\x07\x08\x09\x0a\
+		\x0b\x0c\x0d\u{e5}\u{ef}\u{f5}\u{f9}\u{fe}\u{105}\u{108}\u{10c}\u{111}\
+		\u{11d}\u{129}\u{16c}\u{18e}\u{191}\u{1c3}\u{1de}\u{1e5}\u{1ec}\u{1f4}\
+		\u{1f9}\u{1fe}\u{205}\u{20c}\u{212}\u{218}\u{21e}\u{25f}\u{261}\u{27d}\
+		\u{282}\u{2a2}\u{2eb}\u{30b}\u{339}\x1e\x02\x03\x02\x02\x05\x02\x02\x04\
+		\x02\x07\x04\x02\x07\x03\x02\x07\x08\x02\x09\x08\x02\x06\x02\x02\x09\x07\
+		\x02\x07\x0a\x02\x09\x0c\x02\x07\x07\x02\x07\x05\x02\x07\x09\x02\x08\x02\
+		\x02\x07\x06\x02\x09\x16\x02\x04\x07\x02\x09\x12\x02\x09\x11\x02\x09\x3b\
+		\x02\x09\x4d\x02\x07\x0d\x02\x07\x0c\x02\x09\x50\x02\x04\x0b\x02\x09\x09\
+		\x02\x04\x09\x02";
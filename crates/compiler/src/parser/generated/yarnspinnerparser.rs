@@ -118,6 +118,8 @@ pub const COMMAND_TEXT: isize = 78;
 pub const TYPE_STRING: isize = 79;
 pub const TYPE_NUMBER: isize = 80;
 pub const TYPE_BOOL: isize = 81;
+pub const COMMAND_ONCE: isize = 82;
+pub const COMMAND_ENDONCE: isize = 83;
 pub const RULE_dialogue: usize = 0;
 pub const RULE_file_hashtag: usize = 1;
 pub const RULE_node: usize = 2;
@@ -144,7 +146,13 @@ pub const RULE_shortcut_option_statement: usize = 22;
 pub const RULE_shortcut_option: usize = 23;
 pub const RULE_declare_statement: usize = 24;
 pub const RULE_jump_statement: usize = 25;
-pub const ruleNames: [&'static str; 26] = [
+pub const RULE_once_statement: usize = 26;
+pub const RULE_once_primary_clause: usize = 27;
+pub const RULE_once_alternate_clause: usize = 28;
+pub const RULE_enum_statement: usize = 29;
+pub const RULE_enum_case_statement: usize = 30;
+pub const RULE_typeMemberReference: usize = 31;
+pub const ruleNames: [&'static str; 32] = [
     "dialogue",
     "file_hashtag",
     "node",
@@ -171,9 +179,15 @@ pub const ruleNames: [&'static str; 26] = [
     "shortcut_option",
     "declare_statement",
     "jump_statement",
+    "once_statement",
+    "once_primary_clause",
+    "once_alternate_clause",
+    "enum_statement",
+    "enum_case_statement",
+    "typeMemberReference",
 ];
 
-pub const _LITERAL_NAMES: [Option<&'static str>; 82] = [
+pub const _LITERAL_NAMES: [Option<&'static str>; 84] = [
     None,
     None,
     None,
@@ -256,8 +270,10 @@ pub const _LITERAL_NAMES: [Option<&'static str>; 82] = [
     Some("'string'"),
     Some("'number'"),
     Some("'bool'"),
+    Some("'once'"),
+    Some("'endonce'"),
 ];
-pub const _SYMBOLIC_NAMES: [Option<&'static str>; 82] = [
+pub const _SYMBOLIC_NAMES: [Option<&'static str>; 84] = [
     None,
     Some("INDENT"),
     Some("DEDENT"),
@@ -340,6 +356,8 @@ pub const _SYMBOLIC_NAMES: [Option<&'static str>; 82] = [
     Some("TYPE_STRING"),
     Some("TYPE_NUMBER"),
     Some("TYPE_BOOL"),
+    Some("COMMAND_ONCE"),
+    Some("COMMAND_ENDONCE"),
 ];
 lazy_static! {
     static ref _shared_context_cache: Arc<PredictionContextCache> =
@@ -1365,12 +1383,24 @@ pub trait StatementContextAttrs<'input>:
     {
         self.child_of_type(0)
     }
+    fn enum_statement(&self) -> Option<Rc<Enum_statementContextAll<'input>>>
+    where
+        Self: Sized,
+    {
+        self.child_of_type(0)
+    }
     fn jump_statement(&self) -> Option<Rc<Jump_statementContextAll<'input>>>
     where
         Self: Sized,
     {
         self.child_of_type(0)
     }
+    fn once_statement(&self) -> Option<Rc<Once_statementContextAll<'input>>>
+    where
+        Self: Sized,
+    {
+        self.child_of_type(0)
+    }
     /// Retrieves first TerminalNode corresponding to token INDENT
     /// Returns `None` if there is no child corresponding to token INDENT
     fn INDENT(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
@@ -1486,14 +1516,32 @@ where
                     //recog.base.enter_outer_alt(_localctx.clone(), 8);
                     recog.base.enter_outer_alt(None, 8);
                     {
-                        /*InvokeRule jump_statement*/
-                        recog.base.set_state(93);
-                        recog.jump_statement()?;
+                        /*InvokeRule enum_statement*/
+                        recog.base.set_state(365);
+                        recog.enum_statement()?;
                     }
                 }
                 9 => {
                     //recog.base.enter_outer_alt(_localctx.clone(), 9);
                     recog.base.enter_outer_alt(None, 9);
+                    {
+                        /*InvokeRule jump_statement*/
+                        recog.base.set_state(93);
+                        recog.jump_statement()?;
+                    }
+                }
+                10 => {
+                    //recog.base.enter_outer_alt(_localctx.clone(), 10);
+                    recog.base.enter_outer_alt(None, 10);
+                    {
+                        /*InvokeRule once_statement*/
+                        recog.base.set_state(321);
+                        recog.once_statement()?;
+                    }
+                }
+                11 => {
+                    //recog.base.enter_outer_alt(_localctx.clone(), 11);
+                    recog.base.enter_outer_alt(None, 11);
                     {
                         recog.base.set_state(94);
                         recog.base.match_token(INDENT, &mut recog.err_handler)?;
@@ -2152,6 +2200,14 @@ pub trait Line_conditionContextAttrs<'input>:
     {
         self.child_of_type(0)
     }
+    /// Retrieves first TerminalNode corresponding to token COMMAND_ONCE
+    /// Returns `None` if there is no child corresponding to token COMMAND_ONCE
+    fn COMMAND_ONCE(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(COMMAND_ONCE, 0)
+    }
     /// Retrieves first TerminalNode corresponding to token COMMAND_END
     /// Returns `None` if there is no child corresponding to token COMMAND_END
     fn COMMAND_END(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
@@ -2178,6 +2234,7 @@ where
             .base
             .enter_rule(_localctx.clone(), 18, RULE_line_condition);
         let mut _localctx: Rc<Line_conditionContextAll> = _localctx;
+        let mut _la: isize = -1;
         let result: Result<(), ANTLRError> = (|| {
             //recog.base.enter_outer_alt(_localctx.clone(), 1);
             recog.base.enter_outer_alt(None, 1);
@@ -2187,13 +2244,47 @@ where
                     .base
                     .match_token(COMMAND_START, &mut recog.err_handler)?;
 
-                recog.base.set_state(133);
-                recog.base.match_token(COMMAND_IF, &mut recog.err_handler)?;
+                recog.base.set_state(323);
+                recog.err_handler.sync(&mut recog.base)?;
+                match recog.base.input.la(1) {
+                    COMMAND_IF => {
+                        {
+                            recog.base.set_state(133);
+                            recog.base.match_token(COMMAND_IF, &mut recog.err_handler)?;
 
-                /*InvokeRule expression*/
-                recog.base.set_state(134);
-                recog.expression_rec(0)?;
+                            /*InvokeRule expression*/
+                            recog.base.set_state(134);
+                            recog.expression_rec(0)?;
+                        }
+                    }
+
+                    COMMAND_ONCE => {
+                        {
+                            recog.base.set_state(324);
+                            recog
+                                .base
+                                .match_token(COMMAND_ONCE, &mut recog.err_handler)?;
+
+                            recog.base.set_state(326);
+                            recog.err_handler.sync(&mut recog.base)?;
+                            _la = recog.base.input.la(1);
+                            if _la == COMMAND_IF {
+                                {
+                                    recog.base.set_state(327);
+                                    recog.base.match_token(COMMAND_IF, &mut recog.err_handler)?;
+
+                                    /*InvokeRule expression*/
+                                    recog.base.set_state(328);
+                                    recog.expression_rec(0)?;
+                                }
+                            }
+                        }
+                    }
 
+                    _ => Err(ANTLRError::NoAltError(NoViableAltError::new(
+                        &mut recog.base,
+                    )))?,
+                }
                 recog.base.set_state(135);
                 recog
                     .base
@@ -3714,6 +3805,7 @@ pub enum ValueContextAll<'input> {
     ValueFuncContext(ValueFuncContext<'input>),
     ValueVarContext(ValueVarContext<'input>),
     ValueStringContext(ValueStringContext<'input>),
+    ValueTypeMemberReferenceContext(ValueTypeMemberReferenceContext<'input>),
     Error(ValueContext<'input>),
 }
 antlr_rust::tid! {ValueContextAll<'a>}
@@ -3734,6 +3826,7 @@ impl<'input> Deref for ValueContextAll<'input> {
             ValueFuncContext(inner) => inner,
             ValueVarContext(inner) => inner,
             ValueStringContext(inner) => inner,
+            ValueTypeMemberReferenceContext(inner) => inner,
             Error(inner) => inner,
         }
     }
@@ -4354,6 +4447,89 @@ impl<'input> ValueStringContextExt<'input> {
     }
 }
 
+pub type ValueTypeMemberReferenceContext<'input> =
+    BaseParserRuleContext<'input, ValueTypeMemberReferenceContextExt<'input>>;
+
+pub trait ValueTypeMemberReferenceContextAttrs<'input>: YarnSpinnerParserContext<'input> {
+    fn typeMemberReference(&self) -> Option<Rc<TypeMemberReferenceContextAll<'input>>>
+    where
+        Self: Sized,
+    {
+        self.child_of_type(0)
+    }
+}
+
+impl<'input> ValueTypeMemberReferenceContextAttrs<'input>
+    for ValueTypeMemberReferenceContext<'input>
+{
+}
+
+pub struct ValueTypeMemberReferenceContextExt<'input> {
+    base: ValueContextExt<'input>,
+    ph: PhantomData<&'input str>,
+}
+
+antlr_rust::tid! {ValueTypeMemberReferenceContextExt<'a>}
+
+impl<'input> YarnSpinnerParserContext<'input> for ValueTypeMemberReferenceContext<'input> {}
+
+impl<'input, 'a> Listenable<dyn YarnSpinnerParserListener<'input> + 'a>
+    for ValueTypeMemberReferenceContext<'input>
+{
+    fn enter(&self, listener: &mut (dyn YarnSpinnerParserListener<'input> + 'a)) {
+        listener.enter_every_rule(self);
+        listener.enter_valueTypeMemberReference(self);
+    }
+    fn exit(&self, listener: &mut (dyn YarnSpinnerParserListener<'input> + 'a)) {
+        listener.exit_valueTypeMemberReference(self);
+        listener.exit_every_rule(self);
+    }
+}
+
+impl<'input, 'a> Visitable<dyn YarnSpinnerParserVisitor<'input> + 'a>
+    for ValueTypeMemberReferenceContext<'input>
+{
+    fn accept(&self, visitor: &mut (dyn YarnSpinnerParserVisitor<'input> + 'a)) {
+        visitor.visit_valueTypeMemberReference(self);
+    }
+}
+
+impl<'input> CustomRuleContext<'input> for ValueTypeMemberReferenceContextExt<'input> {
+    type TF = LocalTokenFactory<'input>;
+    type Ctx = YarnSpinnerParserContextType;
+    fn get_rule_index(&self) -> usize {
+        RULE_value
+    }
+    //fn type_rule_index() -> usize where Self: Sized { RULE_value }
+}
+
+impl<'input> Borrow<ValueContextExt<'input>> for ValueTypeMemberReferenceContext<'input> {
+    fn borrow(&self) -> &ValueContextExt<'input> {
+        &self.base
+    }
+}
+impl<'input> BorrowMut<ValueContextExt<'input>> for ValueTypeMemberReferenceContext<'input> {
+    fn borrow_mut(&mut self) -> &mut ValueContextExt<'input> {
+        &mut self.base
+    }
+}
+
+impl<'input> ValueContextAttrs<'input> for ValueTypeMemberReferenceContext<'input> {}
+
+impl<'input> ValueTypeMemberReferenceContextExt<'input> {
+    fn new(ctx: &dyn ValueContextAttrs<'input>) -> Rc<ValueContextAll<'input>> {
+        Rc::new(ValueContextAll::ValueTypeMemberReferenceContext(
+            BaseParserRuleContext::copy_from(
+                ctx,
+                ValueTypeMemberReferenceContextExt {
+                    base: ctx.borrow().clone(),
+                    ph: PhantomData,
+                },
+            ),
+        ))
+    }
+}
+
 impl<'input, I, H> YarnSpinnerParser<'input, I, H>
 where
     I: TokenStream<'input, TF = LocalTokenFactory<'input>> + TidAble<'input>,
@@ -4368,8 +4544,8 @@ where
         let result: Result<(), ANTLRError> = (|| {
             recog.base.set_state(176);
             recog.err_handler.sync(&mut recog.base)?;
-            match recog.base.input.la(1) {
-                NUMBER => {
+            match recog.interpreter.adaptive_predict(15, &mut recog.base)? {
+                1 => {
                     let tmp = ValueNumberContextExt::new(&**_localctx);
                     recog.base.enter_outer_alt(Some(tmp.clone()), 1);
                     _localctx = tmp;
@@ -4379,7 +4555,7 @@ where
                     }
                 }
 
-                KEYWORD_TRUE => {
+                2 => {
                     let tmp = ValueTrueContextExt::new(&**_localctx);
                     recog.base.enter_outer_alt(Some(tmp.clone()), 2);
                     _localctx = tmp;
@@ -4391,7 +4567,7 @@ where
                     }
                 }
 
-                KEYWORD_FALSE => {
+                3 => {
                     let tmp = ValueFalseContextExt::new(&**_localctx);
                     recog.base.enter_outer_alt(Some(tmp.clone()), 3);
                     _localctx = tmp;
@@ -4403,7 +4579,7 @@ where
                     }
                 }
 
-                VAR_ID => {
+                4 => {
                     let tmp = ValueVarContextExt::new(&**_localctx);
                     recog.base.enter_outer_alt(Some(tmp.clone()), 4);
                     _localctx = tmp;
@@ -4414,7 +4590,7 @@ where
                     }
                 }
 
-                STRING => {
+                5 => {
                     let tmp = ValueStringContextExt::new(&**_localctx);
                     recog.base.enter_outer_alt(Some(tmp.clone()), 5);
                     _localctx = tmp;
@@ -4424,7 +4600,7 @@ where
                     }
                 }
 
-                KEYWORD_NULL => {
+                6 => {
                     let tmp = ValueNullContextExt::new(&**_localctx);
                     recog.base.enter_outer_alt(Some(tmp.clone()), 6);
                     _localctx = tmp;
@@ -4436,7 +4612,7 @@ where
                     }
                 }
 
-                FUNC_ID => {
+                7 => {
                     let tmp = ValueFuncContextExt::new(&**_localctx);
                     recog.base.enter_outer_alt(Some(tmp.clone()), 7);
                     _localctx = tmp;
@@ -4447,9 +4623,18 @@ where
                     }
                 }
 
-                _ => Err(ANTLRError::NoAltError(NoViableAltError::new(
-                    &mut recog.base,
-                )))?,
+                8 => {
+                    let tmp = ValueTypeMemberReferenceContextExt::new(&**_localctx);
+                    recog.base.enter_outer_alt(Some(tmp.clone()), 8);
+                    _localctx = tmp;
+                    {
+                        /*InvokeRule typeMemberReference*/
+                        recog.base.set_state(366);
+                        recog.typeMemberReference()?;
+                    }
+                }
+
+                _ => {}
             }
             Ok(())
         })();
@@ -6700,7 +6885,7 @@ pub trait Declare_statementContextAttrs<'input>:
     {
         self.get_token(OPERATOR_ASSIGNMENT, 0)
     }
-    fn value(&self) -> Option<Rc<ValueContextAll<'input>>>
+    fn expression(&self) -> Option<Rc<ExpressionContextAll<'input>>>
     where
         Self: Sized,
     {
@@ -6774,9 +6959,9 @@ where
                     .base
                     .match_token(OPERATOR_ASSIGNMENT, &mut recog.err_handler)?;
 
-                /*InvokeRule value*/
+                /*InvokeRule expression*/
                 recog.base.set_state(294);
-                recog.value()?;
+                recog.expression_rec(0)?;
 
                 recog.base.set_state(297);
                 recog.err_handler.sync(&mut recog.base)?;
@@ -7241,6 +7426,1100 @@ where
         Ok(_localctx)
     }
 }
+//------------------- once_statement ----------------
+pub type Once_statementContextAll<'input> = Once_statementContext<'input>;
+
+pub type Once_statementContext<'input> =
+    BaseParserRuleContext<'input, Once_statementContextExt<'input>>;
+
+#[derive(Clone)]
+pub struct Once_statementContextExt<'input> {
+    ph: PhantomData<&'input str>,
+}
+
+impl<'input> YarnSpinnerParserContext<'input> for Once_statementContext<'input> {}
+
+impl<'input, 'a> Listenable<dyn YarnSpinnerParserListener<'input> + 'a>
+    for Once_statementContext<'input>
+{
+    fn enter(&self, listener: &mut (dyn YarnSpinnerParserListener<'input> + 'a)) {
+        listener.enter_every_rule(self);
+        listener.enter_once_statement(self);
+    }
+    fn exit(&self, listener: &mut (dyn YarnSpinnerParserListener<'input> + 'a)) {
+        listener.exit_once_statement(self);
+        listener.exit_every_rule(self);
+    }
+}
+
+impl<'input, 'a> Visitable<dyn YarnSpinnerParserVisitor<'input> + 'a>
+    for Once_statementContext<'input>
+{
+    fn accept(&self, visitor: &mut (dyn YarnSpinnerParserVisitor<'input> + 'a)) {
+        visitor.visit_once_statement(self);
+    }
+}
+
+impl<'input> CustomRuleContext<'input> for Once_statementContextExt<'input> {
+    type TF = LocalTokenFactory<'input>;
+    type Ctx = YarnSpinnerParserContextType;
+    fn get_rule_index(&self) -> usize {
+        RULE_once_statement
+    }
+    //fn type_rule_index() -> usize where Self: Sized { RULE_once_statement }
+}
+antlr_rust::tid! {Once_statementContextExt<'a>}
+
+impl<'input> Once_statementContextExt<'input> {
+    fn new(
+        parent: Option<Rc<dyn YarnSpinnerParserContext<'input> + 'input>>,
+        invoking_state: isize,
+    ) -> Rc<Once_statementContextAll<'input>> {
+        Rc::new(BaseParserRuleContext::new_parser_ctx(
+            parent,
+            invoking_state,
+            Once_statementContextExt { ph: PhantomData },
+        ))
+    }
+}
+
+pub trait Once_statementContextAttrs<'input>:
+    YarnSpinnerParserContext<'input> + BorrowMut<Once_statementContextExt<'input>>
+{
+    fn once_primary_clause(&self) -> Option<Rc<Once_primary_clauseContextAll<'input>>>
+    where
+        Self: Sized,
+    {
+        self.child_of_type(0)
+    }
+    /// Retrieves first TerminalNode corresponding to token COMMAND_START
+    /// Returns `None` if there is no child corresponding to token COMMAND_START
+    fn COMMAND_START(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(COMMAND_START, 0)
+    }
+    /// Retrieves first TerminalNode corresponding to token COMMAND_ENDONCE
+    /// Returns `None` if there is no child corresponding to token COMMAND_ENDONCE
+    fn COMMAND_ENDONCE(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(COMMAND_ENDONCE, 0)
+    }
+    /// Retrieves first TerminalNode corresponding to token COMMAND_END
+    /// Returns `None` if there is no child corresponding to token COMMAND_END
+    fn COMMAND_END(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(COMMAND_END, 0)
+    }
+    fn once_alternate_clause(&self) -> Option<Rc<Once_alternate_clauseContextAll<'input>>>
+    where
+        Self: Sized,
+    {
+        self.child_of_type(0)
+    }
+}
+
+impl<'input> Once_statementContextAttrs<'input> for Once_statementContext<'input> {}
+
+impl<'input, I, H> YarnSpinnerParser<'input, I, H>
+where
+    I: TokenStream<'input, TF = LocalTokenFactory<'input>> + TidAble<'input>,
+    H: ErrorStrategy<'input, BaseParserType<'input, I>>,
+{
+    pub fn once_statement(&mut self) -> Result<Rc<Once_statementContextAll<'input>>, ANTLRError> {
+        let mut recog = self;
+        let _parentctx = recog.ctx.take();
+        let mut _localctx =
+            Once_statementContextExt::new(_parentctx.clone(), recog.base.get_state());
+        recog
+            .base
+            .enter_rule(_localctx.clone(), 315, RULE_once_statement);
+        let mut _localctx: Rc<Once_statementContextAll> = _localctx;
+        let result: Result<(), ANTLRError> = (|| {
+            //recog.base.enter_outer_alt(_localctx.clone(), 1);
+            recog.base.enter_outer_alt(None, 1);
+            {
+                /*InvokeRule once_primary_clause*/
+                recog.base.set_state(329);
+                recog.once_primary_clause()?;
+
+                recog.base.set_state(331);
+                recog.err_handler.sync(&mut recog.base)?;
+                match recog.interpreter.adaptive_predict(34, &mut recog.base)? {
+                    x if x == 1 => {
+                        {
+                            /*InvokeRule once_alternate_clause*/
+                            recog.base.set_state(332);
+                            recog.once_alternate_clause()?;
+                        }
+                    }
+
+                    _ => {}
+                }
+                recog.base.set_state(333);
+                recog
+                    .base
+                    .match_token(COMMAND_START, &mut recog.err_handler)?;
+
+                recog.base.set_state(334);
+                recog
+                    .base
+                    .match_token(COMMAND_ENDONCE, &mut recog.err_handler)?;
+
+                recog.base.set_state(335);
+                recog
+                    .base
+                    .match_token(COMMAND_END, &mut recog.err_handler)?;
+            }
+            Ok(())
+        })();
+        match result {
+            Ok(_) => {}
+            Err(e @ ANTLRError::FallThrough(_)) => return Err(e),
+            Err(ref re) => {
+                //_localctx.exception = re;
+                recog.err_handler.report_error(&mut recog.base, re);
+                recog.err_handler.recover(&mut recog.base, re)?;
+            }
+        }
+        recog.base.exit_rule();
+
+        Ok(_localctx)
+    }
+}
+//------------------- once_primary_clause ----------------
+pub type Once_primary_clauseContextAll<'input> = Once_primary_clauseContext<'input>;
+
+pub type Once_primary_clauseContext<'input> =
+    BaseParserRuleContext<'input, Once_primary_clauseContextExt<'input>>;
+
+#[derive(Clone)]
+pub struct Once_primary_clauseContextExt<'input> {
+    ph: PhantomData<&'input str>,
+}
+
+impl<'input> YarnSpinnerParserContext<'input> for Once_primary_clauseContext<'input> {}
+
+impl<'input, 'a> Listenable<dyn YarnSpinnerParserListener<'input> + 'a>
+    for Once_primary_clauseContext<'input>
+{
+    fn enter(&self, listener: &mut (dyn YarnSpinnerParserListener<'input> + 'a)) {
+        listener.enter_every_rule(self);
+        listener.enter_once_primary_clause(self);
+    }
+    fn exit(&self, listener: &mut (dyn YarnSpinnerParserListener<'input> + 'a)) {
+        listener.exit_once_primary_clause(self);
+        listener.exit_every_rule(self);
+    }
+}
+
+impl<'input, 'a> Visitable<dyn YarnSpinnerParserVisitor<'input> + 'a>
+    for Once_primary_clauseContext<'input>
+{
+    fn accept(&self, visitor: &mut (dyn YarnSpinnerParserVisitor<'input> + 'a)) {
+        visitor.visit_once_primary_clause(self);
+    }
+}
+
+impl<'input> CustomRuleContext<'input> for Once_primary_clauseContextExt<'input> {
+    type TF = LocalTokenFactory<'input>;
+    type Ctx = YarnSpinnerParserContextType;
+    fn get_rule_index(&self) -> usize {
+        RULE_once_primary_clause
+    }
+    //fn type_rule_index() -> usize where Self: Sized { RULE_once_primary_clause }
+}
+antlr_rust::tid! {Once_primary_clauseContextExt<'a>}
+
+impl<'input> Once_primary_clauseContextExt<'input> {
+    fn new(
+        parent: Option<Rc<dyn YarnSpinnerParserContext<'input> + 'input>>,
+        invoking_state: isize,
+    ) -> Rc<Once_primary_clauseContextAll<'input>> {
+        Rc::new(BaseParserRuleContext::new_parser_ctx(
+            parent,
+            invoking_state,
+            Once_primary_clauseContextExt { ph: PhantomData },
+        ))
+    }
+}
+
+pub trait Once_primary_clauseContextAttrs<'input>:
+    YarnSpinnerParserContext<'input> + BorrowMut<Once_primary_clauseContextExt<'input>>
+{
+    /// Retrieves first TerminalNode corresponding to token COMMAND_START
+    /// Returns `None` if there is no child corresponding to token COMMAND_START
+    fn COMMAND_START(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(COMMAND_START, 0)
+    }
+    /// Retrieves first TerminalNode corresponding to token COMMAND_ONCE
+    /// Returns `None` if there is no child corresponding to token COMMAND_ONCE
+    fn COMMAND_ONCE(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(COMMAND_ONCE, 0)
+    }
+    /// Retrieves first TerminalNode corresponding to token COMMAND_END
+    /// Returns `None` if there is no child corresponding to token COMMAND_END
+    fn COMMAND_END(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(COMMAND_END, 0)
+    }
+    /// Retrieves first TerminalNode corresponding to token COMMAND_IF
+    /// Returns `None` if there is no child corresponding to token COMMAND_IF
+    fn COMMAND_IF(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(COMMAND_IF, 0)
+    }
+    fn expression(&self) -> Option<Rc<ExpressionContextAll<'input>>>
+    where
+        Self: Sized,
+    {
+        self.child_of_type(0)
+    }
+    fn statement_all(&self) -> Vec<Rc<StatementContextAll<'input>>>
+    where
+        Self: Sized,
+    {
+        self.children_of_type()
+    }
+    fn statement(&self, i: usize) -> Option<Rc<StatementContextAll<'input>>>
+    where
+        Self: Sized,
+    {
+        self.child_of_type(i)
+    }
+}
+
+impl<'input> Once_primary_clauseContextAttrs<'input> for Once_primary_clauseContext<'input> {}
+
+impl<'input, I, H> YarnSpinnerParser<'input, I, H>
+where
+    I: TokenStream<'input, TF = LocalTokenFactory<'input>> + TidAble<'input>,
+    H: ErrorStrategy<'input, BaseParserType<'input, I>>,
+{
+    pub fn once_primary_clause(
+        &mut self,
+    ) -> Result<Rc<Once_primary_clauseContextAll<'input>>, ANTLRError> {
+        let mut recog = self;
+        let _parentctx = recog.ctx.take();
+        let mut _localctx =
+            Once_primary_clauseContextExt::new(_parentctx.clone(), recog.base.get_state());
+        recog
+            .base
+            .enter_rule(_localctx.clone(), 317, RULE_once_primary_clause);
+        let mut _localctx: Rc<Once_primary_clauseContextAll> = _localctx;
+        let mut _la: isize = -1;
+        let result: Result<(), ANTLRError> = (|| {
+            let mut _alt: isize;
+            //recog.base.enter_outer_alt(_localctx.clone(), 1);
+            recog.base.enter_outer_alt(None, 1);
+            {
+                recog.base.set_state(337);
+                recog
+                    .base
+                    .match_token(COMMAND_START, &mut recog.err_handler)?;
+
+                recog.base.set_state(338);
+                recog
+                    .base
+                    .match_token(COMMAND_ONCE, &mut recog.err_handler)?;
+
+                recog.base.set_state(340);
+                recog.err_handler.sync(&mut recog.base)?;
+                _la = recog.base.input.la(1);
+                if _la == COMMAND_IF {
+                    {
+                        recog.base.set_state(341);
+                        recog.base.match_token(COMMAND_IF, &mut recog.err_handler)?;
+
+                        /*InvokeRule expression*/
+                        recog.base.set_state(342);
+                        recog.expression_rec(0)?;
+                    }
+                }
+
+                recog.base.set_state(343);
+                recog
+                    .base
+                    .match_token(COMMAND_END, &mut recog.err_handler)?;
+
+                recog.base.set_state(344);
+                recog.err_handler.sync(&mut recog.base)?;
+                _alt = recog.interpreter.adaptive_predict(36, &mut recog.base)?;
+                while { _alt != 2 && _alt != INVALID_ALT } {
+                    if _alt == 1 {
+                        {
+                            {
+                                /*InvokeRule statement*/
+                                recog.base.set_state(345);
+                                recog.statement()?;
+                            }
+                        }
+                    }
+                    recog.base.set_state(348);
+                    recog.err_handler.sync(&mut recog.base)?;
+                    _alt = recog.interpreter.adaptive_predict(36, &mut recog.base)?;
+                }
+            }
+            Ok(())
+        })();
+        match result {
+            Ok(_) => {}
+            Err(e @ ANTLRError::FallThrough(_)) => return Err(e),
+            Err(ref re) => {
+                //_localctx.exception = re;
+                recog.err_handler.report_error(&mut recog.base, re);
+                recog.err_handler.recover(&mut recog.base, re)?;
+            }
+        }
+        recog.base.exit_rule();
+
+        Ok(_localctx)
+    }
+}
+//------------------- once_alternate_clause ----------------
+pub type Once_alternate_clauseContextAll<'input> = Once_alternate_clauseContext<'input>;
+
+pub type Once_alternate_clauseContext<'input> =
+    BaseParserRuleContext<'input, Once_alternate_clauseContextExt<'input>>;
+
+#[derive(Clone)]
+pub struct Once_alternate_clauseContextExt<'input> {
+    ph: PhantomData<&'input str>,
+}
+
+impl<'input> YarnSpinnerParserContext<'input> for Once_alternate_clauseContext<'input> {}
+
+impl<'input, 'a> Listenable<dyn YarnSpinnerParserListener<'input> + 'a>
+    for Once_alternate_clauseContext<'input>
+{
+    fn enter(&self, listener: &mut (dyn YarnSpinnerParserListener<'input> + 'a)) {
+        listener.enter_every_rule(self);
+        listener.enter_once_alternate_clause(self);
+    }
+    fn exit(&self, listener: &mut (dyn YarnSpinnerParserListener<'input> + 'a)) {
+        listener.exit_once_alternate_clause(self);
+        listener.exit_every_rule(self);
+    }
+}
+
+impl<'input, 'a> Visitable<dyn YarnSpinnerParserVisitor<'input> + 'a>
+    for Once_alternate_clauseContext<'input>
+{
+    fn accept(&self, visitor: &mut (dyn YarnSpinnerParserVisitor<'input> + 'a)) {
+        visitor.visit_once_alternate_clause(self);
+    }
+}
+
+impl<'input> CustomRuleContext<'input> for Once_alternate_clauseContextExt<'input> {
+    type TF = LocalTokenFactory<'input>;
+    type Ctx = YarnSpinnerParserContextType;
+    fn get_rule_index(&self) -> usize {
+        RULE_once_alternate_clause
+    }
+    //fn type_rule_index() -> usize where Self: Sized { RULE_once_alternate_clause }
+}
+antlr_rust::tid! {Once_alternate_clauseContextExt<'a>}
+
+impl<'input> Once_alternate_clauseContextExt<'input> {
+    fn new(
+        parent: Option<Rc<dyn YarnSpinnerParserContext<'input> + 'input>>,
+        invoking_state: isize,
+    ) -> Rc<Once_alternate_clauseContextAll<'input>> {
+        Rc::new(BaseParserRuleContext::new_parser_ctx(
+            parent,
+            invoking_state,
+            Once_alternate_clauseContextExt { ph: PhantomData },
+        ))
+    }
+}
+
+pub trait Once_alternate_clauseContextAttrs<'input>:
+    YarnSpinnerParserContext<'input> + BorrowMut<Once_alternate_clauseContextExt<'input>>
+{
+    /// Retrieves first TerminalNode corresponding to token COMMAND_START
+    /// Returns `None` if there is no child corresponding to token COMMAND_START
+    fn COMMAND_START(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(COMMAND_START, 0)
+    }
+    /// Retrieves first TerminalNode corresponding to token COMMAND_ELSE
+    /// Returns `None` if there is no child corresponding to token COMMAND_ELSE
+    fn COMMAND_ELSE(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(COMMAND_ELSE, 0)
+    }
+    /// Retrieves first TerminalNode corresponding to token COMMAND_END
+    /// Returns `None` if there is no child corresponding to token COMMAND_END
+    fn COMMAND_END(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(COMMAND_END, 0)
+    }
+    fn statement_all(&self) -> Vec<Rc<StatementContextAll<'input>>>
+    where
+        Self: Sized,
+    {
+        self.children_of_type()
+    }
+    fn statement(&self, i: usize) -> Option<Rc<StatementContextAll<'input>>>
+    where
+        Self: Sized,
+    {
+        self.child_of_type(i)
+    }
+}
+
+impl<'input> Once_alternate_clauseContextAttrs<'input> for Once_alternate_clauseContext<'input> {}
+
+impl<'input, I, H> YarnSpinnerParser<'input, I, H>
+where
+    I: TokenStream<'input, TF = LocalTokenFactory<'input>> + TidAble<'input>,
+    H: ErrorStrategy<'input, BaseParserType<'input, I>>,
+{
+    pub fn once_alternate_clause(
+        &mut self,
+    ) -> Result<Rc<Once_alternate_clauseContextAll<'input>>, ANTLRError> {
+        let mut recog = self;
+        let _parentctx = recog.ctx.take();
+        let mut _localctx =
+            Once_alternate_clauseContextExt::new(_parentctx.clone(), recog.base.get_state());
+        recog
+            .base
+            .enter_rule(_localctx.clone(), 319, RULE_once_alternate_clause);
+        let mut _localctx: Rc<Once_alternate_clauseContextAll> = _localctx;
+        let result: Result<(), ANTLRError> = (|| {
+            let mut _alt: isize;
+            //recog.base.enter_outer_alt(_localctx.clone(), 1);
+            recog.base.enter_outer_alt(None, 1);
+            {
+                recog.base.set_state(350);
+                recog
+                    .base
+                    .match_token(COMMAND_START, &mut recog.err_handler)?;
+
+                recog.base.set_state(351);
+                recog
+                    .base
+                    .match_token(COMMAND_ELSE, &mut recog.err_handler)?;
+
+                recog.base.set_state(352);
+                recog
+                    .base
+                    .match_token(COMMAND_END, &mut recog.err_handler)?;
+
+                recog.base.set_state(353);
+                recog.err_handler.sync(&mut recog.base)?;
+                _alt = recog.interpreter.adaptive_predict(37, &mut recog.base)?;
+                while { _alt != 2 && _alt != INVALID_ALT } {
+                    if _alt == 1 {
+                        {
+                            {
+                                /*InvokeRule statement*/
+                                recog.base.set_state(354);
+                                recog.statement()?;
+                            }
+                        }
+                    }
+                    recog.base.set_state(357);
+                    recog.err_handler.sync(&mut recog.base)?;
+                    _alt = recog.interpreter.adaptive_predict(37, &mut recog.base)?;
+                }
+            }
+            Ok(())
+        })();
+        match result {
+            Ok(_) => {}
+            Err(e @ ANTLRError::FallThrough(_)) => return Err(e),
+            Err(ref re) => {
+                //_localctx.exception = re;
+                recog.err_handler.report_error(&mut recog.base, re);
+                recog.err_handler.recover(&mut recog.base, re)?;
+            }
+        }
+        recog.base.exit_rule();
+
+        Ok(_localctx)
+    }
+}
+//------------------- enum_statement ----------------
+pub type Enum_statementContextAll<'input> = Enum_statementContext<'input>;
+
+pub type Enum_statementContext<'input> =
+    BaseParserRuleContext<'input, Enum_statementContextExt<'input>>;
+
+#[derive(Clone)]
+pub struct Enum_statementContextExt<'input> {
+    pub name: Option<TokenType<'input>>,
+    ph: PhantomData<&'input str>,
+}
+
+impl<'input> YarnSpinnerParserContext<'input> for Enum_statementContext<'input> {}
+
+impl<'input, 'a> Listenable<dyn YarnSpinnerParserListener<'input> + 'a>
+    for Enum_statementContext<'input>
+{
+    fn enter(&self, listener: &mut (dyn YarnSpinnerParserListener<'input> + 'a)) {
+        listener.enter_every_rule(self);
+        listener.enter_enum_statement(self);
+    }
+    fn exit(&self, listener: &mut (dyn YarnSpinnerParserListener<'input> + 'a)) {
+        listener.exit_enum_statement(self);
+        listener.exit_every_rule(self);
+    }
+}
+
+impl<'input, 'a> Visitable<dyn YarnSpinnerParserVisitor<'input> + 'a>
+    for Enum_statementContext<'input>
+{
+    fn accept(&self, visitor: &mut (dyn YarnSpinnerParserVisitor<'input> + 'a)) {
+        visitor.visit_enum_statement(self);
+    }
+}
+
+impl<'input> CustomRuleContext<'input> for Enum_statementContextExt<'input> {
+    type TF = LocalTokenFactory<'input>;
+    type Ctx = YarnSpinnerParserContextType;
+    fn get_rule_index(&self) -> usize {
+        RULE_enum_statement
+    }
+    //fn type_rule_index() -> usize where Self: Sized { RULE_enum_statement }
+}
+antlr_rust::tid! {Enum_statementContextExt<'a>}
+
+impl<'input> Enum_statementContextExt<'input> {
+    fn new(
+        parent: Option<Rc<dyn YarnSpinnerParserContext<'input> + 'input>>,
+        invoking_state: isize,
+    ) -> Rc<Enum_statementContextAll<'input>> {
+        Rc::new(BaseParserRuleContext::new_parser_ctx(
+            parent,
+            invoking_state,
+            Enum_statementContextExt {
+                name: None,
+                ph: PhantomData,
+            },
+        ))
+    }
+}
+
+pub trait Enum_statementContextAttrs<'input>:
+    YarnSpinnerParserContext<'input> + BorrowMut<Enum_statementContextExt<'input>>
+{
+    /// Retrieves all `TerminalNode`s corresponding to token COMMAND_START in current rule
+    fn COMMAND_START_all(&self) -> Vec<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.children_of_type()
+    }
+    /// Retrieves 'i's TerminalNode corresponding to token COMMAND_START, starting from 0.
+    /// Returns `None` if number of children corresponding to token COMMAND_START is less or equal than `i`.
+    fn COMMAND_START(&self, i: usize) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(COMMAND_START, i)
+    }
+    /// Retrieves first TerminalNode corresponding to token COMMAND_ENUM
+    /// Returns `None` if there is no child corresponding to token COMMAND_ENUM
+    fn COMMAND_ENUM(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(COMMAND_ENUM, 0)
+    }
+    /// Retrieves all `TerminalNode`s corresponding to token COMMAND_END in current rule
+    fn COMMAND_END_all(&self) -> Vec<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.children_of_type()
+    }
+    /// Retrieves 'i's TerminalNode corresponding to token COMMAND_END, starting from 0.
+    /// Returns `None` if number of children corresponding to token COMMAND_END is less or equal than `i`.
+    fn COMMAND_END(&self, i: usize) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(COMMAND_END, i)
+    }
+    /// Retrieves first TerminalNode corresponding to token COMMAND_ENDENUM
+    /// Returns `None` if there is no child corresponding to token COMMAND_ENDENUM
+    fn COMMAND_ENDENUM(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(COMMAND_ENDENUM, 0)
+    }
+    /// Retrieves first TerminalNode corresponding to token ID
+    /// Returns `None` if there is no child corresponding to token ID
+    fn ID(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(ID, 0)
+    }
+    fn enum_case_statement_all(&self) -> Vec<Rc<Enum_case_statementContextAll<'input>>>
+    where
+        Self: Sized,
+    {
+        self.children_of_type()
+    }
+    fn enum_case_statement(&self, i: usize) -> Option<Rc<Enum_case_statementContextAll<'input>>>
+    where
+        Self: Sized,
+    {
+        self.child_of_type(i)
+    }
+}
+
+impl<'input> Enum_statementContextAttrs<'input> for Enum_statementContext<'input> {}
+
+impl<'input, I, H> YarnSpinnerParser<'input, I, H>
+where
+    I: TokenStream<'input, TF = LocalTokenFactory<'input>> + TidAble<'input>,
+    H: ErrorStrategy<'input, BaseParserType<'input, I>>,
+{
+    pub fn enum_statement(&mut self) -> Result<Rc<Enum_statementContextAll<'input>>, ANTLRError> {
+        let mut recog = self;
+        let _parentctx = recog.ctx.take();
+        let mut _localctx =
+            Enum_statementContextExt::new(_parentctx.clone(), recog.base.get_state());
+        recog
+            .base
+            .enter_rule(_localctx.clone(), 359, RULE_enum_statement);
+        let mut _localctx: Rc<Enum_statementContextAll> = _localctx;
+        let result: Result<(), ANTLRError> = (|| {
+            let mut _alt: isize;
+            //recog.base.enter_outer_alt(_localctx.clone(), 1);
+            recog.base.enter_outer_alt(None, 1);
+            {
+                recog.base.set_state(367);
+                recog
+                    .base
+                    .match_token(COMMAND_START, &mut recog.err_handler)?;
+
+                recog.base.set_state(368);
+                recog
+                    .base
+                    .match_token(COMMAND_ENUM, &mut recog.err_handler)?;
+
+                recog.base.set_state(369);
+                let tmp = recog.base.match_token(ID, &mut recog.err_handler)?;
+                cast_mut::<_, Enum_statementContext>(&mut _localctx).name = Some(tmp.clone());
+
+                recog.base.set_state(370);
+                recog
+                    .base
+                    .match_token(COMMAND_END, &mut recog.err_handler)?;
+
+                recog.base.set_state(372);
+                recog.err_handler.sync(&mut recog.base)?;
+                _alt = 1;
+                loop {
+                    match _alt {
+                        x if x == 1 => {
+                            {
+                                /*InvokeRule enum_case_statement*/
+                                recog.base.set_state(373);
+                                recog.enum_case_statement()?;
+                            }
+                        }
+
+                        _ => Err(ANTLRError::NoAltError(NoViableAltError::new(
+                            &mut recog.base,
+                        )))?,
+                    }
+                    recog.base.set_state(374);
+                    recog.err_handler.sync(&mut recog.base)?;
+                    _alt = recog.interpreter.adaptive_predict(38, &mut recog.base)?;
+                    if _alt == 2 || _alt == INVALID_ALT {
+                        break;
+                    }
+                }
+                recog.base.set_state(376);
+                recog
+                    .base
+                    .match_token(COMMAND_START, &mut recog.err_handler)?;
+
+                recog.base.set_state(377);
+                recog
+                    .base
+                    .match_token(COMMAND_ENDENUM, &mut recog.err_handler)?;
+
+                recog.base.set_state(378);
+                recog
+                    .base
+                    .match_token(COMMAND_END, &mut recog.err_handler)?;
+            }
+            Ok(())
+        })();
+        match result {
+            Ok(_) => {}
+            Err(e @ ANTLRError::FallThrough(_)) => return Err(e),
+            Err(ref re) => {
+                //_localctx.exception = re;
+                recog.err_handler.report_error(&mut recog.base, re);
+                recog.err_handler.recover(&mut recog.base, re)?;
+            }
+        }
+        recog.base.exit_rule();
+
+        Ok(_localctx)
+    }
+}
+//------------------- enum_case_statement ----------------
+pub type Enum_case_statementContextAll<'input> = Enum_case_statementContext<'input>;
+
+pub type Enum_case_statementContext<'input> =
+    BaseParserRuleContext<'input, Enum_case_statementContextExt<'input>>;
+
+#[derive(Clone)]
+pub struct Enum_case_statementContextExt<'input> {
+    pub name: Option<TokenType<'input>>,
+    pub raw_value: Option<Rc<ValueContextAll<'input>>>,
+    ph: PhantomData<&'input str>,
+}
+
+impl<'input> YarnSpinnerParserContext<'input> for Enum_case_statementContext<'input> {}
+
+impl<'input, 'a> Listenable<dyn YarnSpinnerParserListener<'input> + 'a>
+    for Enum_case_statementContext<'input>
+{
+    fn enter(&self, listener: &mut (dyn YarnSpinnerParserListener<'input> + 'a)) {
+        listener.enter_every_rule(self);
+        listener.enter_enum_case_statement(self);
+    }
+    fn exit(&self, listener: &mut (dyn YarnSpinnerParserListener<'input> + 'a)) {
+        listener.exit_enum_case_statement(self);
+        listener.exit_every_rule(self);
+    }
+}
+
+impl<'input, 'a> Visitable<dyn YarnSpinnerParserVisitor<'input> + 'a>
+    for Enum_case_statementContext<'input>
+{
+    fn accept(&self, visitor: &mut (dyn YarnSpinnerParserVisitor<'input> + 'a)) {
+        visitor.visit_enum_case_statement(self);
+    }
+}
+
+impl<'input> CustomRuleContext<'input> for Enum_case_statementContextExt<'input> {
+    type TF = LocalTokenFactory<'input>;
+    type Ctx = YarnSpinnerParserContextType;
+    fn get_rule_index(&self) -> usize {
+        RULE_enum_case_statement
+    }
+    //fn type_rule_index() -> usize where Self: Sized { RULE_enum_case_statement }
+}
+antlr_rust::tid! {Enum_case_statementContextExt<'a>}
+
+impl<'input> Enum_case_statementContextExt<'input> {
+    fn new(
+        parent: Option<Rc<dyn YarnSpinnerParserContext<'input> + 'input>>,
+        invoking_state: isize,
+    ) -> Rc<Enum_case_statementContextAll<'input>> {
+        Rc::new(BaseParserRuleContext::new_parser_ctx(
+            parent,
+            invoking_state,
+            Enum_case_statementContextExt {
+                name: None,
+                raw_value: None,
+                ph: PhantomData,
+            },
+        ))
+    }
+}
+
+pub trait Enum_case_statementContextAttrs<'input>:
+    YarnSpinnerParserContext<'input> + BorrowMut<Enum_case_statementContextExt<'input>>
+{
+    /// Retrieves first TerminalNode corresponding to token COMMAND_START
+    /// Returns `None` if there is no child corresponding to token COMMAND_START
+    fn COMMAND_START(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(COMMAND_START, 0)
+    }
+    /// Retrieves first TerminalNode corresponding to token COMMAND_CASE
+    /// Returns `None` if there is no child corresponding to token COMMAND_CASE
+    fn COMMAND_CASE(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(COMMAND_CASE, 0)
+    }
+    /// Retrieves first TerminalNode corresponding to token COMMAND_END
+    /// Returns `None` if there is no child corresponding to token COMMAND_END
+    fn COMMAND_END(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(COMMAND_END, 0)
+    }
+    /// Retrieves first TerminalNode corresponding to token ID
+    /// Returns `None` if there is no child corresponding to token ID
+    fn ID(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(ID, 0)
+    }
+    /// Retrieves first TerminalNode corresponding to token OPERATOR_ASSIGNMENT
+    /// Returns `None` if there is no child corresponding to token OPERATOR_ASSIGNMENT
+    fn OPERATOR_ASSIGNMENT(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(OPERATOR_ASSIGNMENT, 0)
+    }
+    fn value(&self) -> Option<Rc<ValueContextAll<'input>>>
+    where
+        Self: Sized,
+    {
+        self.child_of_type(0)
+    }
+}
+
+impl<'input> Enum_case_statementContextAttrs<'input> for Enum_case_statementContext<'input> {}
+
+impl<'input, I, H> YarnSpinnerParser<'input, I, H>
+where
+    I: TokenStream<'input, TF = LocalTokenFactory<'input>> + TidAble<'input>,
+    H: ErrorStrategy<'input, BaseParserType<'input, I>>,
+{
+    pub fn enum_case_statement(
+        &mut self,
+    ) -> Result<Rc<Enum_case_statementContextAll<'input>>, ANTLRError> {
+        let mut recog = self;
+        let _parentctx = recog.ctx.take();
+        let mut _localctx =
+            Enum_case_statementContextExt::new(_parentctx.clone(), recog.base.get_state());
+        recog
+            .base
+            .enter_rule(_localctx.clone(), 361, RULE_enum_case_statement);
+        let mut _localctx: Rc<Enum_case_statementContextAll> = _localctx;
+        let mut _la: isize = -1;
+        let result: Result<(), ANTLRError> = (|| {
+            //recog.base.enter_outer_alt(_localctx.clone(), 1);
+            recog.base.enter_outer_alt(None, 1);
+            {
+                recog.base.set_state(380);
+                recog
+                    .base
+                    .match_token(COMMAND_START, &mut recog.err_handler)?;
+
+                recog.base.set_state(381);
+                recog
+                    .base
+                    .match_token(COMMAND_CASE, &mut recog.err_handler)?;
+
+                recog.base.set_state(382);
+                let tmp = recog.base.match_token(ID, &mut recog.err_handler)?;
+                cast_mut::<_, Enum_case_statementContext>(&mut _localctx).name = Some(tmp.clone());
+
+                recog.base.set_state(384);
+                recog.err_handler.sync(&mut recog.base)?;
+                _la = recog.base.input.la(1);
+                if _la == OPERATOR_ASSIGNMENT {
+                    {
+                        recog.base.set_state(385);
+                        recog
+                            .base
+                            .match_token(OPERATOR_ASSIGNMENT, &mut recog.err_handler)?;
+
+                        /*InvokeRule value*/
+                        recog.base.set_state(386);
+                        let tmp = recog.value()?;
+                        cast_mut::<_, Enum_case_statementContext>(&mut _localctx).raw_value =
+                            Some(tmp.clone());
+                    }
+                }
+
+                recog.base.set_state(387);
+                recog
+                    .base
+                    .match_token(COMMAND_END, &mut recog.err_handler)?;
+            }
+            Ok(())
+        })();
+        match result {
+            Ok(_) => {}
+            Err(e @ ANTLRError::FallThrough(_)) => return Err(e),
+            Err(ref re) => {
+                //_localctx.exception = re;
+                recog.err_handler.report_error(&mut recog.base, re);
+                recog.err_handler.recover(&mut recog.base, re)?;
+            }
+        }
+        recog.base.exit_rule();
+
+        Ok(_localctx)
+    }
+}
+//------------------- typeMemberReference ----------------
+pub type TypeMemberReferenceContextAll<'input> = TypeMemberReferenceContext<'input>;
+
+pub type TypeMemberReferenceContext<'input> =
+    BaseParserRuleContext<'input, TypeMemberReferenceContextExt<'input>>;
+
+#[derive(Clone)]
+pub struct TypeMemberReferenceContextExt<'input> {
+    pub typeName: Option<TokenType<'input>>,
+    pub memberName: Option<TokenType<'input>>,
+    ph: PhantomData<&'input str>,
+}
+
+impl<'input> YarnSpinnerParserContext<'input> for TypeMemberReferenceContext<'input> {}
+
+impl<'input, 'a> Listenable<dyn YarnSpinnerParserListener<'input> + 'a>
+    for TypeMemberReferenceContext<'input>
+{
+    fn enter(&self, listener: &mut (dyn YarnSpinnerParserListener<'input> + 'a)) {
+        listener.enter_every_rule(self);
+        listener.enter_typeMemberReference(self);
+    }
+    fn exit(&self, listener: &mut (dyn YarnSpinnerParserListener<'input> + 'a)) {
+        listener.exit_typeMemberReference(self);
+        listener.exit_every_rule(self);
+    }
+}
+
+impl<'input, 'a> Visitable<dyn YarnSpinnerParserVisitor<'input> + 'a>
+    for TypeMemberReferenceContext<'input>
+{
+    fn accept(&self, visitor: &mut (dyn YarnSpinnerParserVisitor<'input> + 'a)) {
+        visitor.visit_typeMemberReference(self);
+    }
+}
+
+impl<'input> CustomRuleContext<'input> for TypeMemberReferenceContextExt<'input> {
+    type TF = LocalTokenFactory<'input>;
+    type Ctx = YarnSpinnerParserContextType;
+    fn get_rule_index(&self) -> usize {
+        RULE_typeMemberReference
+    }
+    //fn type_rule_index() -> usize where Self: Sized { RULE_typeMemberReference }
+}
+antlr_rust::tid! {TypeMemberReferenceContextExt<'a>}
+
+impl<'input> TypeMemberReferenceContextExt<'input> {
+    fn new(
+        parent: Option<Rc<dyn YarnSpinnerParserContext<'input> + 'input>>,
+        invoking_state: isize,
+    ) -> Rc<TypeMemberReferenceContextAll<'input>> {
+        Rc::new(BaseParserRuleContext::new_parser_ctx(
+            parent,
+            invoking_state,
+            TypeMemberReferenceContextExt {
+                typeName: None,
+                memberName: None,
+                ph: PhantomData,
+            },
+        ))
+    }
+}
+
+pub trait TypeMemberReferenceContextAttrs<'input>:
+    YarnSpinnerParserContext<'input> + BorrowMut<TypeMemberReferenceContextExt<'input>>
+{
+    /// Retrieves first TerminalNode corresponding to token DOT
+    /// Returns `None` if there is no child corresponding to token DOT
+    fn DOT(&self) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(DOT, 0)
+    }
+    /// Retrieves all `TerminalNode`s corresponding to token FUNC_ID in current rule
+    fn FUNC_ID_all(&self) -> Vec<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.children_of_type()
+    }
+    /// Retrieves 'i's TerminalNode corresponding to token FUNC_ID, starting from 0.
+    /// Returns `None` if number of children corresponding to token FUNC_ID is less or equal than `i`.
+    fn FUNC_ID(&self, i: usize) -> Option<Rc<TerminalNode<'input, YarnSpinnerParserContextType>>>
+    where
+        Self: Sized,
+    {
+        self.get_token(FUNC_ID, i)
+    }
+}
+
+impl<'input> TypeMemberReferenceContextAttrs<'input> for TypeMemberReferenceContext<'input> {}
+
+impl<'input, I, H> YarnSpinnerParser<'input, I, H>
+where
+    I: TokenStream<'input, TF = LocalTokenFactory<'input>> + TidAble<'input>,
+    H: ErrorStrategy<'input, BaseParserType<'input, I>>,
+{
+    pub fn typeMemberReference(
+        &mut self,
+    ) -> Result<Rc<TypeMemberReferenceContextAll<'input>>, ANTLRError> {
+        let mut recog = self;
+        let _parentctx = recog.ctx.take();
+        let mut _localctx =
+            TypeMemberReferenceContextExt::new(_parentctx.clone(), recog.base.get_state());
+        recog
+            .base
+            .enter_rule(_localctx.clone(), 363, RULE_typeMemberReference);
+        let mut _localctx: Rc<TypeMemberReferenceContextAll> = _localctx;
+        let result: Result<(), ANTLRError> = (|| {
+            //recog.base.enter_outer_alt(_localctx.clone(), 1);
+            recog.base.enter_outer_alt(None, 1);
+            {
+                recog.base.set_state(389);
+                let tmp = recog.base.match_token(FUNC_ID, &mut recog.err_handler)?;
+                cast_mut::<_, TypeMemberReferenceContext>(&mut _localctx).typeName =
+                    Some(tmp.clone());
+
+                recog.base.set_state(390);
+                recog.base.match_token(DOT, &mut recog.err_handler)?;
+
+                recog.base.set_state(391);
+                let tmp = recog.base.match_token(FUNC_ID, &mut recog.err_handler)?;
+                cast_mut::<_, TypeMemberReferenceContext>(&mut _localctx).memberName =
+                    Some(tmp.clone());
+            }
+            Ok(())
+        })();
+        match result {
+            Ok(_) => {}
+            Err(e @ ANTLRError::FallThrough(_)) => return Err(e),
+            Err(ref re) => {
+                //_localctx.exception = re;
+                recog.err_handler.report_error(&mut recog.base, re);
+                recog.err_handler.recover(&mut recog.base, re)?;
+            }
+        }
+        recog.base.exit_rule();
+
+        Ok(_localctx)
+    }
+}
 
 lazy_static! {
     static ref _ATN: Arc<ATN> =
@@ -7257,7 +8536,7 @@ lazy_static! {
 
 const _serializedATN: &'static str =
     "\x03\u{608b}\u{a72a}\u{8133}\u{b9ed}\u{417c}\u{3be7}\u{7786}\u{5964}\x03\
-	\x53\u{13d}\x04\x02\x09\x02\x04\x03\x09\x03\x04\x04\x09\x04\x04\x05\x09\
+	\x55\u{18b}\x04\x02\x09\x02\x04\x03\x09\x03\x04\x04\x09\x04\x04\x05\x09\
 	\x05\x04\x06\x09\x06\x04\x07\x09\x07\x04\x08\x09\x08\x04\x09\x09\x09\x04\
 	\x0a\x09\x0a\x04\x0b\x09\x0b\x04\x0c\x09\x0c\x04\x0d\x09\x0d\x04\x0e\x09\
 	\x0e\x04\x0f\x09\x0f\x04\x10\x09\x10\x04\x11\x09\x11\x04\x12\x09\x12\x04\
@@ -7295,92 +8574,104 @@ const _serializedATN: &'static str =
 	\x03\x19\x05\x19\u{123}\x0a\x19\x03\x1a\x03\x1a\x03\x1a\x03\x1a\x03\x1a\
 	\x03\x1a\x03\x1a\x05\x1a\u{12c}\x0a\x1a\x03\x1a\x03\x1a\x03\x1b\x03\x1b\
 	\x03\x1b\x03\x1b\x03\x1b\x03\x1b\x03\x1b\x03\x1b\x03\x1b\x03\x1b\x03\x1b\
-	\x05\x1b\u{13b}\x0a\x1b\x03\x1b\x02\x03\x16\x1c\x02\x04\x06\x08\x0a\x0c\
-	\x0e\x10\x12\x14\x16\x18\x1a\x1c\x1e\x20\x22\x24\x26\x28\x2a\x2c\x2e\x30\
-	\x32\x34\x02\x08\x03\x02\x33\x35\x03\x02\x31\x32\x04\x02\x22\x23\x25\x26\
-	\x04\x02\x24\x24\x27\x27\x03\x02\x28\x2a\x04\x02\x21\x21\x2c\x30\x02\u{153}\
-	\x02\x39\x03\x02\x02\x02\x04\x41\x03\x02\x02\x02\x06\x45\x03\x02\x02\x02\
-	\x08\x4d\x03\x02\x02\x02\x0a\x55\x03\x02\x02\x02\x0c\x68\x03\x02\x02\x02\
-	\x0e\x6a\x03\x02\x02\x02\x10\x7f\x03\x02\x02\x02\x12\u{83}\x03\x02\x02\x02\
-	\x14\u{86}\x03\x02\x02\x02\x16\u{95}\x03\x02\x02\x02\x18\u{b2}\x03\x02\x02\
-	\x02\x1a\u{b4}\x03\x02\x02\x02\x1c\u{b6}\x03\x02\x02\x02\x1e\u{c4}\x03\x02\
-	\x02\x02\x20\u{d2}\x03\x02\x02\x02\x22\u{dc}\x03\x02\x02\x02\x24\u{e6}\x03\
-	\x02\x02\x02\x26\u{ef}\x03\x02\x02\x02\x28\u{f6}\x03\x02\x02\x02\x2a\u{fb}\
-	\x03\x02\x02\x02\x2c\u{10b}\x03\x02\x02\x02\x2e\u{111}\x03\x02\x02\x02\x30\
-	\u{118}\x03\x02\x02\x02\x32\u{124}\x03\x02\x02\x02\x34\u{13a}\x03\x02\x02\
-	\x02\x36\x38\x05\x04\x03\x02\x37\x36\x03\x02\x02\x02\x38\x3b\x03\x02\x02\
-	\x02\x39\x37\x03\x02\x02\x02\x39\x3a\x03\x02\x02\x02\x3a\x3d\x03\x02\x02\
-	\x02\x3b\x39\x03\x02\x02\x02\x3c\x3e\x05\x06\x04\x02\x3d\x3c\x03\x02\x02\
-	\x02\x3e\x3f\x03\x02\x02\x02\x3f\x3d\x03\x02\x02\x02\x3f\x40\x03\x02\x02\
-	\x02\x40\x03\x03\x02\x02\x02\x41\x42\x07\x0c\x02\x02\x42\x43\x07\x1c\x02\
-	\x02\x43\x05\x03\x02\x02\x02\x44\x46\x05\x08\x05\x02\x45\x44\x03\x02\x02\
-	\x02\x46\x47\x03\x02\x02\x02\x47\x45\x03\x02\x02\x02\x47\x48\x03\x02\x02\
-	\x02\x48\x49\x03\x02\x02\x02\x49\x4a\x07\x0a\x02\x02\x4a\x4b\x05\x0a\x06\
-	\x02\x4b\x4c\x07\x0f\x02\x02\x4c\x07\x03\x02\x02\x02\x4d\x4e\x07\x09\x02\
-	\x02\x4e\x50\x07\x0b\x02\x02\x4f\x51\x07\x0d\x02\x02\x50\x4f\x03\x02\x02\
-	\x02\x50\x51\x03\x02\x02\x02\x51\x09\x03\x02\x02\x02\x52\x54\x05\x0c\x07\
-	\x02\x53\x52\x03\x02\x02\x02\x54\x57\x03\x02\x02\x02\x55\x53\x03\x02\x02\
-	\x02\x55\x56\x03\x02\x02\x02\x56\x0b\x03\x02\x02\x02\x57\x55\x03\x02\x02\
-	\x02\x58\x69\x05\x0e\x08\x02\x59\x69\x05\x1e\x10\x02\x5a\x69\x05\x26\x14\
-	\x02\x5b\x69\x05\x2e\x18\x02\x5c\x69\x05\x28\x15\x02\x5d\x69\x05\x2a\x16\
-	\x02\x5e\x69\x05\x32\x1a\x02\x5f\x69\x05\x34\x1b\x02\x60\x64\x07\x03\x02\
-	\x02\x61\x63\x05\x0c\x07\x02\x62\x61\x03\x02\x02\x02\x63\x66\x03\x02\x02\
-	\x02\x64\x62\x03\x02\x02\x02\x64\x65\x03\x02\x02\x02\x65\x67\x03\x02\x02\
-	\x02\x66\x64\x03\x02\x02\x02\x67\x69\x07\x04\x02\x02\x68\x58\x03\x02\x02\
-	\x02\x68\x59\x03\x02\x02\x02\x68\x5a\x03\x02\x02\x02\x68\x5b\x03\x02\x02\
-	\x02\x68\x5c\x03\x02\x02\x02\x68\x5d\x03\x02\x02\x02\x68\x5e\x03\x02\x02\
-	\x02\x68\x5f\x03\x02\x02\x02\x68\x60\x03\x02\x02\x02\x69\x0d\x03\x02\x02\
-	\x02\x6a\x6c\x05\x10\x09\x02\x6b\x6d\x05\x14\x0b\x02\x6c\x6b\x03\x02\x02\
-	\x02\x6c\x6d\x03\x02\x02\x02\x6d\x71\x03\x02\x02\x02\x6e\x70\x05\x12\x0a\
-	\x02\x6f\x6e\x03\x02\x02\x02\x70\x73\x03\x02\x02\x02\x71\x6f\x03\x02\x02\
-	\x02\x71\x72\x03\x02\x02\x02\x72\x74\x03\x02\x02\x02\x73\x71\x03\x02\x02\
-	\x02\x74\x75\x07\x08\x02\x02\x75\x0f\x03\x02\x02\x02\x76\x78\x07\x16\x02\
-	\x02\x77\x76\x03\x02\x02\x02\x78\x79\x03\x02\x02\x02\x79\x77\x03\x02\x02\
-	\x02\x79\x7a\x03\x02\x02\x02\x7a\u{80}\x03\x02\x02\x02\x7b\x7c\x07\x12\x02\
-	\x02\x7c\x7d\x05\x16\x0c\x02\x7d\x7e\x07\x3c\x02\x02\x7e\u{80}\x03\x02\x02\
-	\x02\x7f\x77\x03\x02\x02\x02\x7f\x7b\x03\x02\x02\x02\u{80}\u{81}\x03\x02\
-	\x02\x02\u{81}\x7f\x03\x02\x02\x02\u{81}\u{82}\x03\x02\x02\x02\u{82}\x11\
-	\x03\x02\x02\x02\u{83}\u{84}\x07\x0c\x02\x02\u{84}\u{85}\x07\x1c\x02\x02\
-	\u{85}\x13\x03\x02\x02\x02\u{86}\u{87}\x07\x11\x02\x02\u{87}\u{88}\x07\x41\
-	\x02\x02\u{88}\u{89}\x05\x16\x0c\x02\u{89}\u{8a}\x07\x4d\x02\x02\u{8a}\x15\
-	\x03\x02\x02\x02\u{8b}\u{8c}\x08\x0c\x01\x02\u{8c}\u{8d}\x07\x36\x02\x02\
-	\u{8d}\u{8e}\x05\x16\x0c\x02\u{8e}\u{8f}\x07\x37\x02\x02\u{8f}\u{96}\x03\
-	\x02\x02\x02\u{90}\u{91}\x07\x32\x02\x02\u{91}\u{96}\x05\x16\x0c\x0a\u{92}\
-	\u{93}\x07\x2b\x02\x02\u{93}\u{96}\x05\x16\x0c\x09\u{94}\u{96}\x05\x18\x0d\
-	\x02\u{95}\u{8b}\x03\x02\x02\x02\u{95}\u{90}\x03\x02\x02\x02\u{95}\u{92}\
-	\x03\x02\x02\x02\u{95}\u{94}\x03\x02\x02\x02\u{96}\u{a8}\x03\x02\x02\x02\
-	\u{97}\u{98}\x0c\x08\x02\x02\u{98}\u{99}\x09\x02\x02\x02\u{99}\u{a7}\x05\
-	\x16\x0c\x09\u{9a}\u{9b}\x0c\x07\x02\x02\u{9b}\u{9c}\x09\x03\x02\x02\u{9c}\
-	\u{a7}\x05\x16\x0c\x08\u{9d}\u{9e}\x0c\x06\x02\x02\u{9e}\u{9f}\x09\x04\x02\
-	\x02\u{9f}\u{a7}\x05\x16\x0c\x07\u{a0}\u{a1}\x0c\x05\x02\x02\u{a1}\u{a2}\
-	\x09\x05\x02\x02\u{a2}\u{a7}\x05\x16\x0c\x06\u{a3}\u{a4}\x0c\x04\x02\x02\
-	\u{a4}\u{a5}\x09\x06\x02\x02\u{a5}\u{a7}\x05\x16\x0c\x05\u{a6}\u{97}\x03\
-	\x02\x02\x02\u{a6}\u{9a}\x03\x02\x02\x02\u{a6}\u{9d}\x03\x02\x02\x02\u{a6}\
-	\u{a0}\x03\x02\x02\x02\u{a6}\u{a3}\x03\x02\x02\x02\u{a7}\u{aa}\x03\x02\x02\
-	\x02\u{a8}\u{a6}\x03\x02\x02\x02\u{a8}\u{a9}\x03\x02\x02\x02\u{a9}\x17\x03\
-	\x02\x02\x02\u{aa}\u{a8}\x03\x02\x02\x02\u{ab}\u{b3}\x07\x3f\x02\x02\u{ac}\
-	\u{b3}\x07\x1e\x02\x02\u{ad}\u{b3}\x07\x1f\x02\x02\u{ae}\u{b3}\x05\x1a\x0e\
-	\x02\u{af}\u{b3}\x07\x3a\x02\x02\u{b0}\u{b3}\x07\x20\x02\x02\u{b1}\u{b3}\
-	\x05\x1c\x0f\x02\u{b2}\u{ab}\x03\x02\x02\x02\u{b2}\u{ac}\x03\x02\x02\x02\
-	\u{b2}\u{ad}\x03\x02\x02\x02\u{b2}\u{ae}\x03\x02\x02\x02\u{b2}\u{af}\x03\
-	\x02\x02\x02\u{b2}\u{b0}\x03\x02\x02\x02\u{b2}\u{b1}\x03\x02\x02\x02\u{b3}\
-	\x19\x03\x02\x02\x02\u{b4}\u{b5}\x07\x3d\x02\x02\u{b5}\x1b\x03\x02\x02\x02\
-	\u{b6}\u{b7}\x07\x3b\x02\x02\u{b7}\u{b9}\x07\x36\x02\x02\u{b8}\u{ba}\x05\
-	\x16\x0c\x02\u{b9}\u{b8}\x03\x02\x02\x02\u{b9}\u{ba}\x03\x02\x02\x02\u{ba}\
-	\u{bf}\x03\x02\x02\x02\u{bb}\u{bc}\x07\x38\x02\x02\u{bc}\u{be}\x05\x16\x0c\
-	\x02\u{bd}\u{bb}\x03\x02\x02\x02\u{be}\u{c1}\x03\x02\x02\x02\u{bf}\u{bd}\
-	\x03\x02\x02\x02\u{bf}\u{c0}\x03\x02\x02\x02\u{c0}\u{c2}\x03\x02\x02\x02\
-	\u{c1}\u{bf}\x03\x02\x02\x02\u{c2}\u{c3}\x07\x37\x02\x02\u{c3}\x1d\x03\x02\
-	\x02\x02\u{c4}\u{c8}\x05\x20\x11\x02\u{c5}\u{c7}\x05\x22\x12\x02\u{c6}\u{c5}\
-	\x03\x02\x02\x02\u{c7}\u{ca}\x03\x02\x02\x02\u{c8}\u{c6}\x03\x02\x02\x02\
-	\u{c8}\u{c9}\x03\x02\x02\x02\u{c9}\u{cc}\x03\x02\x02\x02\u{ca}\u{c8}\x03\
-	\x02\x02\x02\u{cb}\u{cd}\x05\x24\x13\x02\u{cc}\u{cb}\x03\x02\x02\x02\u{cc}\
-	\u{cd}\x03\x02\x02\x02\u{cd}\u{ce}\x03\x02\x02\x02\u{ce}\u{cf}\x07\x11\x02\
-	\x02\u{cf}\u{d0}\x07\x45\x02\x02\u{d0}\u{d1}\x07\x4d\x02\x02\u{d1}\x1f\x03\
-	\x02\x02\x02\u{d2}\u{d3}\x07\x11\x02\x02\u{d3}\u{d4}\x07\x41\x02\x02\u{d4}\
-	\u{d5}\x05\x16\x0c\x02\u{d5}\u{d9}\x07\x4d\x02\x02\u{d6}\u{d8}\x05\x0c\x07\
-	\x02\u{d7}\u{d6}\x03\x02\x02\x02\u{d8}\u{db}\x03\x02\x02\x02\u{d9}\u{d7}\
-	\x03\x02\x02\x02\u{d9}\u{da}\x03\x02\x02\x02\u{da}\x21\x03\x02\x02\x02\u{db}\
+	\x05\x1b\u{13b}\x0a\x1b\x03\x1b\x04\x1c\x09\x1c\x04\x1d\x09\x1d\x04\x1e\
+	\x09\x1e\x03\x07\x0a\x0b\x05\x0b\u{144}\x03\x0b\x0a\x0b\x05\x0b\u{147}\x03\
+	\x0b\x03\x0b\x03\x1c\x0a\x1c\x05\x1c\u{14c}\x03\x1c\x03\x1c\x03\x1c\x03\
+	\x1c\x03\x1c\x03\x1d\x03\x1d\x0a\x1d\x05\x1d\u{155}\x03\x1d\x03\x1d\x03\
+	\x1d\x0c\x1d\x03\x1d\x0a\x1d\x07\x1d\u{15c}\x0b\x1d\x0e\x1d\u{15e}\x03\x1e\
+	\x03\x1e\x03\x1e\x0c\x1e\x03\x1e\x0a\x1e\x07\x1e\u{165}\x0b\x1e\x0e\x1e\
+	\u{167}\x04\x1f\x09\x1f\x04\x20\x09\x20\x04\x21\x09\x21\x03\x07\x03\x0d\
+	\x03\x1f\x03\x1f\x03\x1f\x03\x1f\x0a\x1f\x06\x1f\u{175}\x03\x1f\x0d\x1f\
+	\x0e\x1f\u{178}\x03\x1f\x03\x1f\x03\x1f\x03\x1f\x03\x20\x03\x20\x03\x20\
+	\x0a\x20\x05\x20\u{181}\x03\x20\x03\x20\x03\x20\x03\x20\x03\x21\x03\x21\
+	\x03\x21\x03\x21\x02\x03\x16\x22\x02\x04\x06\x08\x0a\x0c\x0e\x10\x12\x14\
+	\x16\x18\x1a\x1c\x1e\x20\x22\x24\x26\x28\x2a\x2c\x2e\x30\x32\x34\u{13d}\
+	\u{13f}\u{141}\u{169}\u{16b}\u{16d}\x02\x08\x03\x02\x33\x35\x03\x02\x31\
+	\x32\x04\x02\x22\x23\x25\x26\x04\x02\x24\x24\x27\x27\x03\x02\x28\x2a\x04\
+	\x02\x21\x21\x2c\x30\x02\u{1a6}\x02\x39\x03\x02\x02\x02\x04\x41\x03\x02\
+	\x02\x02\x06\x45\x03\x02\x02\x02\x08\x4d\x03\x02\x02\x02\x0a\x55\x03\x02\
+	\x02\x02\x0c\x68\x03\x02\x02\x02\x0e\x6a\x03\x02\x02\x02\x10\x7f\x03\x02\
+	\x02\x02\x12\u{83}\x03\x02\x02\x02\x14\u{86}\x03\x02\x02\x02\x16\u{95}\x03\
+	\x02\x02\x02\x18\u{b2}\x03\x02\x02\x02\x1a\u{b4}\x03\x02\x02\x02\x1c\u{b6}\
+	\x03\x02\x02\x02\x1e\u{c4}\x03\x02\x02\x02\x20\u{d2}\x03\x02\x02\x02\x22\
+	\u{dc}\x03\x02\x02\x02\x24\u{e6}\x03\x02\x02\x02\x26\u{ef}\x03\x02\x02\x02\
+	\x28\u{f6}\x03\x02\x02\x02\x2a\u{fb}\x03\x02\x02\x02\x2c\u{10b}\x03\x02\
+	\x02\x02\x2e\u{111}\x03\x02\x02\x02\x30\u{118}\x03\x02\x02\x02\x32\u{124}\
+	\x03\x02\x02\x02\x34\u{13a}\x03\x02\x02\x02\x36\x38\x05\x04\x03\x02\x37\
+	\x36\x03\x02\x02\x02\x38\x3b\x03\x02\x02\x02\x39\x37\x03\x02\x02\x02\x39\
+	\x3a\x03\x02\x02\x02\x3a\x3d\x03\x02\x02\x02\x3b\x39\x03\x02\x02\x02\x3c\
+	\x3e\x05\x06\x04\x02\x3d\x3c\x03\x02\x02\x02\x3e\x3f\x03\x02\x02\x02\x3f\
+	\x3d\x03\x02\x02\x02\x3f\x40\x03\x02\x02\x02\x40\x03\x03\x02\x02\x02\x41\
+	\x42\x07\x0c\x02\x02\x42\x43\x07\x1c\x02\x02\x43\x05\x03\x02\x02\x02\x44\
+	\x46\x05\x08\x05\x02\x45\x44\x03\x02\x02\x02\x46\x47\x03\x02\x02\x02\x47\
+	\x45\x03\x02\x02\x02\x47\x48\x03\x02\x02\x02\x48\x49\x03\x02\x02\x02\x49\
+	\x4a\x07\x0a\x02\x02\x4a\x4b\x05\x0a\x06\x02\x4b\x4c\x07\x0f\x02\x02\x4c\
+	\x07\x03\x02\x02\x02\x4d\x4e\x07\x09\x02\x02\x4e\x50\x07\x0b\x02\x02\x4f\
+	\x51\x07\x0d\x02\x02\x50\x4f\x03\x02\x02\x02\x50\x51\x03\x02\x02\x02\x51\
+	\x09\x03\x02\x02\x02\x52\x54\x05\x0c\x07\x02\x53\x52\x03\x02\x02\x02\x54\
+	\x57\x03\x02\x02\x02\x55\x53\x03\x02\x02\x02\x55\x56\x03\x02\x02\x02\x56\
+	\x0b\x03\x02\x02\x02\x57\x55\x03\x02\x02\x02\x58\x69\x05\x0e\x08\x02\x59\
+	\x69\x05\x1e\x10\x02\x5a\x69\x05\x26\x14\x02\x5b\x69\x05\x2e\x18\x02\x5c\
+	\x69\x05\x28\x15\x02\x5d\x69\x05\x2a\x16\x02\x5e\x69\x05\x32\x1a\x02\x5f\
+	\x69\x05\x34\x1b\x02\x60\x64\x07\x03\x02\x02\x61\x63\x05\x0c\x07\x02\x62\
+	\x61\x03\x02\x02\x02\x63\x66\x03\x02\x02\x02\x64\x62\x03\x02\x02\x02\x64\
+	\x65\x03\x02\x02\x02\x65\x67\x03\x02\x02\x02\x66\x64\x03\x02\x02\x02\x67\
+	\x69\x07\x04\x02\x02\x68\x58\x03\x02\x02\x02\x68\x59\x03\x02\x02\x02\x68\
+	\x5a\x03\x02\x02\x02\x68\x5b\x03\x02\x02\x02\x68\x5c\x03\x02\x02\x02\x68\
+	\x5d\x03\x02\x02\x02\x68\x5e\x03\x02\x02\x02\x68\u{16f}\x03\x02\x02\x02\
+	\x68\x5f\x03\x02\x02\x02\x68\u{143}\x03\x02\x02\x02\x68\x60\x03\x02\x02\
+	\x02\x69\x0d\x03\x02\x02\x02\x6a\x6c\x05\x10\x09\x02\x6b\x6d\x05\x14\x0b\
+	\x02\x6c\x6b\x03\x02\x02\x02\x6c\x6d\x03\x02\x02\x02\x6d\x71\x03\x02\x02\
+	\x02\x6e\x70\x05\x12\x0a\x02\x6f\x6e\x03\x02\x02\x02\x70\x73\x03\x02\x02\
+	\x02\x71\x6f\x03\x02\x02\x02\x71\x72\x03\x02\x02\x02\x72\x74\x03\x02\x02\
+	\x02\x73\x71\x03\x02\x02\x02\x74\x75\x07\x08\x02\x02\x75\x0f\x03\x02\x02\
+	\x02\x76\x78\x07\x16\x02\x02\x77\x76\x03\x02\x02\x02\x78\x79\x03\x02\x02\
+	\x02\x79\x77\x03\x02\x02\x02\x79\x7a\x03\x02\x02\x02\x7a\u{80}\x03\x02\x02\
+	\x02\x7b\x7c\x07\x12\x02\x02\x7c\x7d\x05\x16\x0c\x02\x7d\x7e\x07\x3c\x02\
+	\x02\x7e\u{80}\x03\x02\x02\x02\x7f\x77\x03\x02\x02\x02\x7f\x7b\x03\x02\x02\
+	\x02\u{80}\u{81}\x03\x02\x02\x02\u{81}\x7f\x03\x02\x02\x02\u{81}\u{82}\x03\
+	\x02\x02\x02\u{82}\x11\x03\x02\x02\x02\u{83}\u{84}\x07\x0c\x02\x02\u{84}\
+	\u{85}\x07\x1c\x02\x02\u{85}\x13\x03\x02\x02\x02\u{86}\u{145}\x07\x11\x02\
+	\x02\u{87}\u{88}\x07\x41\x02\x02\u{88}\u{144}\x05\x16\x0c\x02\u{89}\u{8a}\
+	\x07\x4d\x02\x02\u{8a}\x15\x03\x02\x02\x02\u{8b}\u{8c}\x08\x0c\x01\x02\u{8c}\
+	\u{8d}\x07\x36\x02\x02\u{8d}\u{8e}\x05\x16\x0c\x02\u{8e}\u{8f}\x07\x37\x02\
+	\x02\u{8f}\u{96}\x03\x02\x02\x02\u{90}\u{91}\x07\x32\x02\x02\u{91}\u{96}\
+	\x05\x16\x0c\x0a\u{92}\u{93}\x07\x2b\x02\x02\u{93}\u{96}\x05\x16\x0c\x09\
+	\u{94}\u{96}\x05\x18\x0d\x02\u{95}\u{8b}\x03\x02\x02\x02\u{95}\u{90}\x03\
+	\x02\x02\x02\u{95}\u{92}\x03\x02\x02\x02\u{95}\u{94}\x03\x02\x02\x02\u{96}\
+	\u{a8}\x03\x02\x02\x02\u{97}\u{98}\x0c\x08\x02\x02\u{98}\u{99}\x09\x02\x02\
+	\x02\u{99}\u{a7}\x05\x16\x0c\x09\u{9a}\u{9b}\x0c\x07\x02\x02\u{9b}\u{9c}\
+	\x09\x03\x02\x02\u{9c}\u{a7}\x05\x16\x0c\x08\u{9d}\u{9e}\x0c\x06\x02\x02\
+	\u{9e}\u{9f}\x09\x04\x02\x02\u{9f}\u{a7}\x05\x16\x0c\x07\u{a0}\u{a1}\x0c\
+	\x05\x02\x02\u{a1}\u{a2}\x09\x05\x02\x02\u{a2}\u{a7}\x05\x16\x0c\x06\u{a3}\
+	\u{a4}\x0c\x04\x02\x02\u{a4}\u{a5}\x09\x06\x02\x02\u{a5}\u{a7}\x05\x16\x0c\
+	\x05\u{a6}\u{97}\x03\x02\x02\x02\u{a6}\u{9a}\x03\x02\x02\x02\u{a6}\u{9d}\
+	\x03\x02\x02\x02\u{a6}\u{a0}\x03\x02\x02\x02\u{a6}\u{a3}\x03\x02\x02\x02\
+	\u{a7}\u{aa}\x03\x02\x02\x02\u{a8}\u{a6}\x03\x02\x02\x02\u{a8}\u{a9}\x03\
+	\x02\x02\x02\u{a9}\x17\x03\x02\x02\x02\u{aa}\u{a8}\x03\x02\x02\x02\u{ab}\
+	\u{b3}\x07\x3f\x02\x02\u{ac}\u{b3}\x07\x1e\x02\x02\u{ad}\u{b3}\x07\x1f\x02\
+	\x02\u{ae}\u{b3}\x05\x1a\x0e\x02\u{af}\u{b3}\x07\x3a\x02\x02\u{b0}\u{b3}\
+	\x07\x20\x02\x02\u{b1}\u{b3}\x05\x1c\x0f\x02\u{b2}\u{ab}\x03\x02\x02\x02\
+	\u{b2}\u{ac}\x03\x02\x02\x02\u{b2}\u{ad}\x03\x02\x02\x02\u{b2}\u{ae}\x03\
+	\x02\x02\x02\u{b2}\u{af}\x03\x02\x02\x02\u{b2}\u{b0}\x03\x02\x02\x02\u{b2}\
+	\u{b1}\x03\x02\x02\x02\u{b2}\u{170}\x03\x02\x02\x02\u{b3}\x19\x03\x02\x02\
+	\x02\u{b4}\u{b5}\x07\x3d\x02\x02\u{b5}\x1b\x03\x02\x02\x02\u{b6}\u{b7}\x07\
+	\x3b\x02\x02\u{b7}\u{b9}\x07\x36\x02\x02\u{b8}\u{ba}\x05\x16\x0c\x02\u{b9}\
+	\u{b8}\x03\x02\x02\x02\u{b9}\u{ba}\x03\x02\x02\x02\u{ba}\u{bf}\x03\x02\x02\
+	\x02\u{bb}\u{bc}\x07\x38\x02\x02\u{bc}\u{be}\x05\x16\x0c\x02\u{bd}\u{bb}\
+	\x03\x02\x02\x02\u{be}\u{c1}\x03\x02\x02\x02\u{bf}\u{bd}\x03\x02\x02\x02\
+	\u{bf}\u{c0}\x03\x02\x02\x02\u{c0}\u{c2}\x03\x02\x02\x02\u{c1}\u{bf}\x03\
+	\x02\x02\x02\u{c2}\u{c3}\x07\x37\x02\x02\u{c3}\x1d\x03\x02\x02\x02\u{c4}\
+	\u{c8}\x05\x20\x11\x02\u{c5}\u{c7}\x05\x22\x12\x02\u{c6}\u{c5}\x03\x02\x02\
+	\x02\u{c7}\u{ca}\x03\x02\x02\x02\u{c8}\u{c6}\x03\x02\x02\x02\u{c8}\u{c9}\
+	\x03\x02\x02\x02\u{c9}\u{cc}\x03\x02\x02\x02\u{ca}\u{c8}\x03\x02\x02\x02\
+	\u{cb}\u{cd}\x05\x24\x13\x02\u{cc}\u{cb}\x03\x02\x02\x02\u{cc}\u{cd}\x03\
+	\x02\x02\x02\u{cd}\u{ce}\x03\x02\x02\x02\u{ce}\u{cf}\x07\x11\x02\x02\u{cf}\
+	\u{d0}\x07\x45\x02\x02\u{d0}\u{d1}\x07\x4d\x02\x02\u{d1}\x1f\x03\x02\x02\
+	\x02\u{d2}\u{d3}\x07\x11\x02\x02\u{d3}\u{d4}\x07\x41\x02\x02\u{d4}\u{d5}\
+	\x05\x16\x0c\x02\u{d5}\u{d9}\x07\x4d\x02\x02\u{d6}\u{d8}\x05\x0c\x07\x02\
+	\u{d7}\u{d6}\x03\x02\x02\x02\u{d8}\u{db}\x03\x02\x02\x02\u{d9}\u{d7}\x03\
+	\x02\x02\x02\u{d9}\u{da}\x03\x02\x02\x02\u{da}\x21\x03\x02\x02\x02\u{db}\
 	\u{d9}\x03\x02\x02\x02\u{dc}\u{dd}\x07\x11\x02\x02\u{dd}\u{de}\x07\x42\x02\
 	\x02\u{de}\u{df}\x05\x16\x0c\x02\u{df}\u{e3}\x07\x4d\x02\x02\u{e0}\u{e2}\
 	\x05\x0c\x07\x02\u{e1}\u{e0}\x03\x02\x02\x02\u{e2}\u{e5}\x03\x02\x02\x02\
@@ -7415,7 +8706,7 @@ const _serializedATN: &'static str =
 	\x04\x02\x02\u{122}\u{11a}\x03\x02\x02\x02\u{122}\u{123}\x03\x02\x02\x02\
 	\u{123}\x31\x03\x02\x02\x02\u{124}\u{125}\x07\x11\x02\x02\u{125}\u{126}\
 	\x07\x47\x02\x02\u{126}\u{127}\x05\x1a\x0e\x02\u{127}\u{128}\x07\x21\x02\
-	\x02\u{128}\u{12b}\x05\x18\x0d\x02\u{129}\u{12a}\x07\x39\x02\x02\u{12a}\
+	\x02\u{128}\u{12b}\x05\x16\x0c\x02\u{129}\u{12a}\x07\x39\x02\x02\u{12a}\
 	\u{12c}\x07\x3b\x02\x02\u{12b}\u{129}\x03\x02\x02\x02\u{12b}\u{12c}\x03\
 	\x02\x02\x02\u{12c}\u{12d}\x03\x02\x02\x02\u{12d}\u{12e}\x07\x4d\x02\x02\
 	\u{12e}\x33\x03\x02\x02\x02\u{12f}\u{130}\x07\x11\x02\x02\u{130}\u{131}\
@@ -7424,6 +8715,40 @@ const _serializedATN: &'static str =
 	\u{136}\x07\x12\x02\x02\u{136}\u{137}\x05\x16\x0c\x02\u{137}\u{138}\x07\
 	\x3c\x02\x02\u{138}\u{139}\x07\x4d\x02\x02\u{139}\u{13b}\x03\x02\x02\x02\
 	\u{13a}\u{12f}\x03\x02\x02\x02\u{13a}\u{133}\x03\x02\x02\x02\u{13b}\x35\
-	\x03\x02\x02\x02\x22\x39\x3f\x47\x50\x55\x64\x68\x6c\x71\x79\x7f\u{81}\u{95}\
-	\u{a6}\u{a8}\u{b2}\u{b9}\u{bf}\u{c8}\u{cc}\u{d9}\u{e3}\u{ec}\u{101}\u{109}\
-	\u{10b}\u{111}\u{116}\u{11e}\u{122}\u{12b}\u{13a}";
+	\x03\x02\x02\x02\u{13d}\u{14b}\x03\x02\x02\x02\u{13f}\u{153}\x03\x02\x02\
+	\x02\u{141}\u{160}\x03\x02\x02\x02\u{143}\x69\x05\u{13d}\x1c\x02\u{144}\
+	\u{89}\x03\x02\x02\x02\u{145}\u{87}\x03\x02\x02\x02\u{145}\u{146}\x03\x02\
+	\x02\x02\u{146}\u{148}\x07\x54\x02\x02\u{147}\u{144}\x03\x02\x02\x02\u{148}\
+	\u{149}\x03\x02\x02\x02\u{148}\u{147}\x03\x02\x02\x02\u{149}\u{14a}\x07\
+	\x41\x02\x02\u{14a}\u{147}\x05\x16\x0c\x02\u{14b}\u{14d}\x05\u{13f}\x1d\
+	\x02\u{14c}\u{14f}\x03\x02\x02\x02\u{14d}\u{14e}\x03\x02\x02\x02\u{14d}\
+	\u{14c}\x03\x02\x02\x02\u{14e}\u{14c}\x05\u{141}\x1e\x02\u{14f}\u{150}\x07\
+	\x11\x02\x02\u{150}\u{151}\x07\x55\x02\x02\u{151}\u{152}\x07\x4d\x02\x02\
+	\u{152}\u{13e}\x03\x02\x02\x02\u{153}\u{154}\x07\x11\x02\x02\u{154}\u{156}\
+	\x07\x54\x02\x02\u{155}\u{159}\x03\x02\x02\x02\u{156}\u{157}\x03\x02\x02\
+	\x02\u{156}\u{155}\x03\x02\x02\x02\u{157}\u{158}\x07\x41\x02\x02\u{158}\
+	\u{155}\x05\x16\x0c\x02\u{159}\u{15a}\x07\x4d\x02\x02\u{15a}\u{15d}\x03\
+	\x02\x02\x02\u{15a}\u{15f}\x03\x02\x02\x02\u{15b}\u{15c}\x05\x0c\x07\x02\
+	\u{15c}\u{15e}\x03\x02\x02\x02\u{15d}\u{15b}\x03\x02\x02\x02\u{15e}\u{15a}\
+	\x03\x02\x02\x02\u{15f}\u{140}\x03\x02\x02\x02\u{160}\u{161}\x07\x11\x02\
+	\x02\u{161}\u{162}\x07\x43\x02\x02\u{162}\u{163}\x07\x4d\x02\x02\u{163}\
+	\u{166}\x03\x02\x02\x02\u{163}\u{168}\x03\x02\x02\x02\u{164}\u{165}\x05\
+	\x0c\x07\x02\u{165}\u{167}\x03\x02\x02\x02\u{166}\u{164}\x03\x02\x02\x02\
+	\u{167}\u{163}\x03\x02\x02\x02\u{168}\u{142}\x03\x02\x02\x02\u{169}\u{171}\
+	\x03\x02\x02\x02\u{16b}\u{17e}\x03\x02\x02\x02\u{16d}\u{187}\x03\x02\x02\
+	\x02\u{16f}\x69\x05\u{169}\x1f\x02\u{170}\u{b3}\x05\u{16d}\x21\x02\u{171}\
+	\u{172}\x07\x11\x02\x02\u{172}\u{173}\x07\x49\x02\x02\u{173}\u{174}\x07\
+	\x09\x02\x02\u{174}\u{176}\x07\x4d\x02\x02\u{175}\u{178}\x03\x02\x02\x02\
+	\u{176}\u{177}\x03\x02\x02\x02\u{177}\u{175}\x05\u{16b}\x20\x02\u{178}\u{176}\
+	\x03\x02\x02\x02\u{178}\u{179}\x03\x02\x02\x02\u{179}\u{17a}\x03\x02\x02\
+	\x02\u{17a}\u{17b}\x07\x11\x02\x02\u{17b}\u{17c}\x07\x4b\x02\x02\u{17c}\
+	\u{17d}\x07\x4d\x02\x02\u{17d}\u{16a}\x03\x02\x02\x02\u{17e}\u{17f}\x07\
+	\x11\x02\x02\u{17f}\u{180}\x07\x4a\x02\x02\u{180}\u{182}\x07\x09\x02\x02\
+	\u{181}\u{185}\x03\x02\x02\x02\u{182}\u{183}\x03\x02\x02\x02\u{182}\u{181}\
+	\x03\x02\x02\x02\u{183}\u{184}\x07\x21\x02\x02\u{184}\u{181}\x05\x18\x0d\
+	\x02\u{185}\u{186}\x07\x4d\x02\x02\u{186}\u{16c}\x03\x02\x02\x02\u{187}\
+	\u{188}\x07\x3b\x02\x02\u{188}\u{189}\x07\x3e\x02\x02\u{189}\u{18a}\x07\
+	\x3b\x02\x02\u{18a}\u{16e}\x03\x02\x02\x02\x2a\x39\x3f\x47\x50\x55\x64\x68\
+	\x6c\x71\x79\x7f\u{81}\u{95}\u{a6}\u{a8}\u{b2}\u{b9}\u{bf}\u{c8}\u{cc}\u{d9}\
+	\u{e3}\u{ec}\u{101}\u{109}\u{10b}\u{111}\u{116}\u{11e}\u{122}\u{12b}\u{13a}\
+	\u{145}\u{148}\u{14d}\u{156}\u{15a}\u{163}\u{178}\u{182}";
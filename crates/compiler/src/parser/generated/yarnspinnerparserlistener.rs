@@ -296,6 +296,18 @@ fn enter_valueFunc(&mut self, _ctx: &ValueFuncContext<'input>) { }
  * @param ctx the parse tree
  */
 fn exit_valueFunc(&mut self, _ctx: &ValueFuncContext<'input>) { }
+/**
+ * Enter a parse tree produced by the {@code valueTypeMemberReference}
+ * labeled alternative in {@link YarnSpinnerParser#value}.
+ * @param ctx the parse tree
+ */
+fn enter_valueTypeMemberReference(&mut self, _ctx: &ValueTypeMemberReferenceContext<'input>) { }
+/**
+ * Exit a parse tree produced by the {@code valueTypeMemberReference}
+ * labeled alternative in {@link YarnSpinnerParser#value}.
+ * @param ctx the parse tree
+ */
+fn exit_valueTypeMemberReference(&mut self, _ctx: &ValueTypeMemberReferenceContext<'input>) { }
 /**
  * Enter a parse tree produced by {@link YarnSpinnerParser#variable}.
  * @param ctx the parse tree
@@ -450,6 +462,66 @@ fn enter_jumpToExpression(&mut self, _ctx: &JumpToExpressionContext<'input>) { }
  * @param ctx the parse tree
  */
 fn exit_jumpToExpression(&mut self, _ctx: &JumpToExpressionContext<'input>) { }
+/**
+ * Enter a parse tree produced by {@link YarnSpinnerParser#once_statement}.
+ * @param ctx the parse tree
+ */
+fn enter_once_statement(&mut self, _ctx: &Once_statementContext<'input>) { }
+/**
+ * Exit a parse tree produced by {@link YarnSpinnerParser#once_statement}.
+ * @param ctx the parse tree
+ */
+fn exit_once_statement(&mut self, _ctx: &Once_statementContext<'input>) { }
+/**
+ * Enter a parse tree produced by {@link YarnSpinnerParser#once_primary_clause}.
+ * @param ctx the parse tree
+ */
+fn enter_once_primary_clause(&mut self, _ctx: &Once_primary_clauseContext<'input>) { }
+/**
+ * Exit a parse tree produced by {@link YarnSpinnerParser#once_primary_clause}.
+ * @param ctx the parse tree
+ */
+fn exit_once_primary_clause(&mut self, _ctx: &Once_primary_clauseContext<'input>) { }
+/**
+ * Enter a parse tree produced by {@link YarnSpinnerParser#once_alternate_clause}.
+ * @param ctx the parse tree
+ */
+fn enter_once_alternate_clause(&mut self, _ctx: &Once_alternate_clauseContext<'input>) { }
+/**
+ * Exit a parse tree produced by {@link YarnSpinnerParser#once_alternate_clause}.
+ * @param ctx the parse tree
+ */
+fn exit_once_alternate_clause(&mut self, _ctx: &Once_alternate_clauseContext<'input>) { }
+/**
+ * Enter a parse tree produced by {@link YarnSpinnerParser#enum_statement}.
+ * @param ctx the parse tree
+ */
+fn enter_enum_statement(&mut self, _ctx: &Enum_statementContext<'input>) { }
+/**
+ * Exit a parse tree produced by {@link YarnSpinnerParser#enum_statement}.
+ * @param ctx the parse tree
+ */
+fn exit_enum_statement(&mut self, _ctx: &Enum_statementContext<'input>) { }
+/**
+ * Enter a parse tree produced by {@link YarnSpinnerParser#enum_case_statement}.
+ * @param ctx the parse tree
+ */
+fn enter_enum_case_statement(&mut self, _ctx: &Enum_case_statementContext<'input>) { }
+/**
+ * Exit a parse tree produced by {@link YarnSpinnerParser#enum_case_statement}.
+ * @param ctx the parse tree
+ */
+fn exit_enum_case_statement(&mut self, _ctx: &Enum_case_statementContext<'input>) { }
+/**
+ * Enter a parse tree produced by {@link YarnSpinnerParser#typeMemberReference}.
+ * @param ctx the parse tree
+ */
+fn enter_typeMemberReference(&mut self, _ctx: &TypeMemberReferenceContext<'input>) { }
+/**
+ * Exit a parse tree produced by {@link YarnSpinnerParser#typeMemberReference}.
+ * @param ctx the parse tree
+ */
+fn exit_typeMemberReference(&mut self, _ctx: &TypeMemberReferenceContext<'input>) { }
 
 }
 
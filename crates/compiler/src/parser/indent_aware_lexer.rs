@@ -10,7 +10,7 @@ use super::generated::yarnspinnerlexer::{
 };
 use crate::collections::*;
 use crate::listeners::Diagnostic;
-use crate::prelude::{create_common_token, DiagnosticSeverity, TokenExt};
+use crate::prelude::{create_common_token, DiagnosticCode, DiagnosticSeverity, TokenExt};
 use antlr_rust::token::CommonToken;
 use antlr_rust::{
     char_stream::CharStream,
@@ -317,6 +317,7 @@ where
         if saw_spaces && saw_tabs {
             self.diagnostics.borrow_mut().push(
                 Diagnostic::from_message("Indentation contains tabs and spaces")
+                    .with_code(DiagnosticCode::MixedIndentation)
                     .with_range(get_newline_indentation_range(current_token))
                     .with_context(get_newline_indentation_text(current_token))
                     .with_start_line(current_token.line as usize)
@@ -357,6 +358,7 @@ where
             let last_line_len = token.get_text().lines().last().unwrap().len();
             self.diagnostics.borrow_mut().push(
                 Diagnostic::from_message("Newlines are not allowed in commands")
+                    .with_code(DiagnosticCode::SyntaxError)
                     .with_range(
                         Position {
                             line: token.get_line_as_usize() - 1,
@@ -1,6 +1,7 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner.Compiler/YarnSpinnerRuleContextExt.cs>
 
 use crate::prelude::*;
+use antlr_rust::int_stream::IntStream;
 use antlr_rust::parser_rule_context::ParserRuleContext;
 use antlr_rust::token::Token;
 use antlr_rust::token_stream::TokenStream;
@@ -25,7 +26,15 @@ pub(crate) trait ParserRuleContextExt<'input>: ParserRuleContext<'input> {
         // but as a non-crashing fallback we prevent this
         let start = self.start().get_token_index();
         let stop = self.stop().get_token_index();
-        if start > stop {
+        // The node generated for a node group is parsed from a token stream of its own,
+        // see `expand_node_groups`, so the indices of its tokens don't refer to the given one.
+        let is_from_token_stream = |index: isize, char_start: isize| {
+            index < token_stream.size() && token_stream.get(index).get_start() == char_start
+        };
+        if start > stop
+            || !is_from_token_stream(start, self.start().get_start())
+            || !is_from_token_stream(stop, self.stop().get_start())
+        {
             self.get_text()
         } else {
             // ## Implementation Notes
@@ -45,17 +54,19 @@ pub(crate) trait ParserRuleContextExt<'input>: ParserRuleContext<'input> {
         // This seems expensive, but it's only used for error reporting.
         let whole_file = token_stream.get_all_text();
         let char_start = self.start().get_start() as usize;
-        let char_stop = self.stop().get_stop() as usize + 1;
+        // The tokens of the node generated for a node group may be attributed to text in any order, see `expand_node_groups`
+        let char_stop = (self.stop().get_stop() as usize + 1).max(char_start);
         let byte_start = whole_file
             .char_indices()
             .map(|(byte_start, _)| byte_start)
             .nth(char_start)
-            .unwrap();
+            .unwrap_or(whole_file.len());
         let byte_stop = whole_file
             .char_indices()
             .map(|(byte_start, _)| byte_start)
             .nth(char_stop)
-            .unwrap();
+            // The context may end with the very last character of the file
+            .unwrap_or(whole_file.len());
         let first_line = self.start().get_line_as_usize().saturating_sub(1);
 
         let head = &whole_file[..byte_start];
@@ -0,0 +1,189 @@
+//! Not part of the original implementation. Conversion between byte offsets and [`Position`]s in a Yarn source.
+
+use crate::prelude::*;
+use antlr_rust::token::Token;
+use std::ops::Range;
+
+/// Converts between byte offsets into the source of a Yarn file and the [`Position`]s reported by the compiler,
+/// e.g. in [`Diagnostic::range`], [`Declaration::range`] and the [`ParsedFile`].
+///
+/// Like all positions of the compiler, lines and characters are zero-based and characters count unicode code points, not bytes.
+/// Lines are separated by `\n`, so a `\r` of a Windows line ending counts as the last character of its line.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_compiler::prelude::*;
+/// # use yarnspinner_core::prelude::*;
+/// let source = "title: Start\n---\nGrüße, {$name}!\n===\n";
+/// let positions = SourcePosition::new(source);
+///
+/// let byte_offset = source.find("{$name}").unwrap();
+/// let position = positions.position(byte_offset).unwrap();
+/// assert_eq!(Position { line: 2, character: 7 }, position);
+/// assert_eq!(Some(byte_offset), positions.byte_offset(position));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourcePosition<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> SourcePosition<'a> {
+    /// Indexes the lines of the given source.
+    pub fn new(source: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        Self {
+            source,
+            line_starts,
+        }
+    }
+
+    /// The source the positions refer to.
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// Returns the [`Position`] of the character starting at the given byte offset.
+    /// The offset equal to the length of the source is the position right after its last character.
+    ///
+    /// Returns [`None`] if the offset is out of bounds or not on a character boundary.
+    pub fn position(&self, byte_offset: usize) -> Option<Position> {
+        if !self.source.is_char_boundary(byte_offset) {
+            return None;
+        }
+        let line = self
+            .line_starts
+            .partition_point(|&start| start <= byte_offset)
+            - 1;
+        let character = self.source[self.line_starts[line]..byte_offset]
+            .chars()
+            .count();
+        Some(Position { line, character })
+    }
+
+    /// Returns the byte offset of the given [`Position`].
+    /// The character right after the last one of a line is allowed and refers to its line break.
+    ///
+    /// Returns [`None`] if the position lies outside of the source.
+    pub fn byte_offset(&self, position: Position) -> Option<usize> {
+        let line_start = *self.line_starts.get(position.line)?;
+        let line = &self.source[line_start..];
+        let line = line.split_once('\n').map_or(line, |(line, _)| line);
+        line.char_indices()
+            .map(|(index, _)| index)
+            .chain(std::iter::once(line.len()))
+            .nth(position.character)
+            .map(|index| line_start + index)
+    }
+
+    /// Returns the byte range of the given range of [`Position`]s, e.g. to slice the source with it.
+    ///
+    /// Returns [`None`] if either end lies outside of the source.
+    pub fn byte_range(&self, range: &Range<Position>) -> Option<Range<usize>> {
+        Some(self.byte_offset(range.start)?..self.byte_offset(range.end)?)
+    }
+
+    /// Returns the range of [`Position`]s of the given byte range.
+    ///
+    /// Returns [`None`] if either end is out of bounds or not on a character boundary.
+    pub fn range(&self, byte_range: &Range<usize>) -> Option<Range<Position>> {
+        Some(self.position(byte_range.start)?..self.position(byte_range.end)?)
+    }
+}
+
+/// The range covered by the text of a token, using the same zero-based positions as [`SourcePosition`].
+pub(crate) fn token_range(token: &(impl Token<Data = str> + ?Sized)) -> Range<Position> {
+    let start = Position {
+        line: token.get_line_as_usize().saturating_sub(1),
+        character: token.get_column_as_usize(),
+    };
+    text_range(start, token.get_text())
+}
+
+/// The range covered by a text starting at the given position, which may span multiple lines.
+pub(crate) fn text_range(start: Position, text: &str) -> Range<Position> {
+    let end = match text.rsplit_once('\n') {
+        Some((before, after)) => Position {
+            line: start.line + before.matches('\n').count() + 1,
+            character: after.chars().count(),
+        },
+        None => Position {
+            line: start.line,
+            character: start.character + text.chars().count(),
+        },
+    };
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "title: Start\r\n---\nGrüße!\n\n===";
+
+    #[test]
+    fn converts_byte_offsets_to_positions() {
+        let positions = SourcePosition::new(SOURCE);
+        assert_eq!(Some(position(0, 0)), positions.position(0));
+        assert_eq!(Some(position(0, 12)), positions.position(12));
+        assert_eq!(Some(position(1, 0)), positions.position(14));
+        assert_eq!(Some(position(2, 4)), positions.position(24));
+        assert_eq!(Some(position(3, 0)), positions.position(27));
+        assert_eq!(Some(position(4, 0)), positions.position(28));
+        assert_eq!(Some(position(4, 3)), positions.position(SOURCE.len()));
+        // Inside of the 'ü'
+        assert_eq!(None, positions.position(21));
+        assert_eq!(None, positions.position(SOURCE.len() + 1));
+    }
+
+    #[test]
+    fn converts_positions_to_byte_offsets() {
+        let positions = SourcePosition::new(SOURCE);
+        assert_eq!(Some(0), positions.byte_offset(position(0, 0)));
+        assert_eq!(Some(13), positions.byte_offset(position(0, 13)));
+        assert_eq!(Some(24), positions.byte_offset(position(2, 4)));
+        assert_eq!(Some(26), positions.byte_offset(position(2, 6)));
+        assert_eq!(Some(27), positions.byte_offset(position(3, 0)));
+        assert_eq!(Some(SOURCE.len()), positions.byte_offset(position(4, 3)));
+        assert_eq!(None, positions.byte_offset(position(2, 7)));
+        assert_eq!(None, positions.byte_offset(position(5, 0)));
+    }
+
+    #[test]
+    fn round_trips_every_character() {
+        let positions = SourcePosition::new(SOURCE);
+        for (byte_offset, _) in SOURCE.char_indices() {
+            let position = positions.position(byte_offset).unwrap();
+            assert_eq!(Some(byte_offset), positions.byte_offset(position));
+        }
+        let range = positions.range(&(18..25)).unwrap();
+        assert_eq!(position(2, 0)..position(2, 5), range);
+        assert_eq!(
+            Some("Grüße"),
+            positions.byte_range(&range).map(|r| &SOURCE[r])
+        );
+    }
+
+    #[test]
+    fn computes_multi_line_text_ranges() {
+        assert_eq!(
+            position(2, 3)..position(2, 8),
+            text_range(position(2, 3), "Grüße")
+        );
+        assert_eq!(
+            position(2, 3)..position(4, 2),
+            text_range(position(2, 3), "a\n\nbc")
+        );
+        assert_eq!(
+            position(2, 3)..position(3, 0),
+            text_range(position(2, 3), "\n")
+        );
+    }
+
+    fn position(line: usize, character: usize) -> Position {
+        Position { line, character }
+    }
+}
@@ -1,6 +1,7 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner.Compiler/StringTableManager.cs>
 
 use crate::output::StringInfo;
+use crate::prelude::get_line_id_namespace_prefix;
 use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
 use yarnspinner_core::prelude::*;
@@ -8,6 +9,14 @@ use yarnspinner_core::prelude::*;
 #[derive(Debug, Clone, Default)]
 pub(crate) struct StringTableManager(pub HashMap<LineId, StringInfo>);
 
+/// Returned by [`StringTableManager::insert`] when a string is inserted with an explicit line ID that another string already has.
+#[derive(Debug, Clone)]
+pub(crate) struct DuplicateLineId {
+    pub(crate) line_id: LineId,
+    /// The string that was inserted with the line ID first. Boxed to keep the [`Result`] of [`StringTableManager::insert`] small.
+    pub(crate) existing: Box<StringInfo>,
+}
+
 impl StringTableManager {
     pub(crate) fn contains_implicit_string_tags(&self) -> bool {
         self.values().any(|x| x.is_implicit_tag)
@@ -15,30 +24,34 @@ impl StringTableManager {
 
     /// Inserts a new string into the string table, optionally generating a new line ID.
     /// The `is_implicit_tag` field of the `string_info` is automatically set; its original value is ignored.
+    /// Generated line IDs are prefixed with the `line_id_namespace` of the `string_info`, if any.
+    /// See [`StringTableManager::generate_implicit_line_id`] for how they are generated.
     ///
     /// ## Returns
     ///
     /// The line ID used for insertion. This will be `line_id` if it is `Some`, otherwise it will be an autogenerated line ID.
+    /// If `line_id` is already in the string table, the string is not inserted and the existing one is returned as [`DuplicateLineId`] instead,
+    /// so that the caller can report where both came from.
     pub(crate) fn insert(
         &mut self,
         line_id: impl Into<Option<LineId>>,
         string_info: StringInfo,
-    ) -> LineId {
+    ) -> Result<LineId, DuplicateLineId> {
         let line_id = line_id.into();
         let (line_id, string_info) = if let Some(line_id) = line_id {
+            if let Some(existing) = self.get(&line_id) {
+                return Err(DuplicateLineId {
+                    line_id,
+                    existing: Box::new(existing.clone()),
+                });
+            }
             let string_info = StringInfo {
                 is_implicit_tag: false,
                 ..string_info
             };
             (line_id, string_info)
         } else {
-            let line_id = format!(
-                "line:{}-{}-{}",
-                string_info.file_name,
-                string_info.node_name,
-                self.len()
-            )
-            .into();
+            let line_id = self.generate_implicit_line_id(&string_info);
             let string_info = StringInfo {
                 is_implicit_tag: true,
                 ..string_info
@@ -46,7 +59,41 @@ impl StringTableManager {
             (line_id, string_info)
         };
         self.0.insert(line_id.clone(), string_info);
-        line_id
+        Ok(line_id)
+    }
+
+    /// Generates a line ID of the form `line:{file}-{node}-{hash}`, where the hash is computed from the file name,
+    /// the node name, the text and the number of lines with the same text that were inserted into the same node before.
+    /// This way, a line keeps its ID when other lines are added, removed or moved around it.
+    ///
+    /// Generated IDs used to end in the number of strings inserted before instead of a hash, so upgrading invalidates
+    /// translations and assets that refer to implicit line IDs once. Tag the lines with explicit `#line:` IDs to avoid this.
+    ///
+    /// Should the hash collide with an existing ID, the occurrence index is increased until the ID is free,
+    /// which is deterministic because strings are always inserted in the same order.
+    fn generate_implicit_line_id(&self, string_info: &StringInfo) -> LineId {
+        let prefix = string_info
+            .line_id_namespace
+            .as_deref()
+            .map(get_line_id_namespace_prefix)
+            .unwrap_or_else(|| "line:".to_owned());
+        (0_u64..)
+            .map(|occurrence| {
+                let hash = hash_parts(&[
+                    string_info.file_name.as_bytes(),
+                    string_info.node_name.as_bytes(),
+                    string_info.text.as_bytes(),
+                    &occurrence.to_le_bytes(),
+                ]);
+                LineId::from(format!(
+                    "{prefix}{}-{}-{:08x}",
+                    string_info.file_name,
+                    string_info.node_name,
+                    hash as u32 ^ (hash >> 32) as u32
+                ))
+            })
+            .find(|line_id| !self.contains_key(line_id))
+            .unwrap()
     }
 
     pub(crate) fn extend(&mut self, other: Self) {
@@ -54,6 +101,16 @@ impl StringTableManager {
     }
 }
 
+/// The [`fnv1a_hash`] of the given parts, each followed by a zero byte so that moving bytes between parts changes the hash.
+pub(crate) fn hash_parts(parts: &[&[u8]]) -> u64 {
+    fnv1a_hash(
+        parts
+            .iter()
+            .flat_map(|part| part.iter().chain(&[0]))
+            .copied(),
+    )
+}
+
 impl Deref for StringTableManager {
     type Target = HashMap<LineId, StringInfo>;
 
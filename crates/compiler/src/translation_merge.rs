@@ -0,0 +1,414 @@
+//! Exports the strings of a project for localizers, checks the translations that come back from them
+//! and merges them into the existing translations of the project.
+
+use crate::prelude::*;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{self, Display, Write};
+
+/// The prefix that marks a translated line whose base text changed since it was translated.
+pub const NEEDS_UPDATE_PREFIX: &str = "(NEEDS UPDATE) ";
+
+/// Computes the lock of a line, i.e. the first 8 characters of the SHA-256 hash of its text in the base language.
+/// A translation stores the lock of the base text it was translated from, so a differing lock means that the translation is out of date.
+///
+/// Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner-Unity/blob/462c735766a4c4881cd1ef1f15de28c83b2ba0a8/Editor/Importers/YarnImporter.cs#L149>
+pub fn compute_lock(text: &str) -> String {
+    const MAX_CHARS: usize = 8;
+    let hash = Sha256::digest(text);
+    let hex = format!("{hash:x}");
+    hex.chars().take(MAX_CHARS).collect()
+}
+
+/// A translated line together with the lock of the base text it was translated from. See [`compute_lock`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct TranslatedLine {
+    /// The translated text.
+    pub text: String,
+    /// The lock of the base text this line was translated from.
+    pub lock: String,
+}
+
+/// Decides what happens to an incoming translation whose base text changed since the translator saw it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash, Default))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub enum ConflictPolicy {
+    /// Keeps the existing translation, if any.
+    KeepExisting,
+    /// Uses the incoming translation anyways.
+    KeepIncoming,
+    /// Uses the incoming translation, but prefixes it with [`NEEDS_UPDATE_PREFIX`] and keeps its outdated lock,
+    /// so that it is flagged for review.
+    #[default]
+    MarkForReview,
+}
+
+/// An incoming translation whose base text changed since the translator saw it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    /// The ID of the conflicting line.
+    pub id: LineId,
+    /// The current text of the line in the base language.
+    pub base_text: String,
+    /// The translation before the merge, if there was one.
+    pub existing: Option<TranslatedLine>,
+    /// The translation that was sent back by the translator.
+    pub incoming: TranslatedLine,
+}
+
+/// The result of [`merge_translation`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MergeResult {
+    /// The translations after the merge.
+    pub merged: HashMap<LineId, TranslatedLine>,
+    /// The lines whose incoming translation was made against the current base text and was therefore accepted.
+    pub accepted: Vec<LineId>,
+    /// The incoming translations whose base text changed since the translator saw them.
+    /// They were resolved according to the [`ConflictPolicy`].
+    pub conflicts: Vec<MergeConflict>,
+    /// The lines of the incoming translation that are not part of the base [`Compilation`]. They were ignored.
+    pub unknown: Vec<LineId>,
+    /// The lines of the base [`Compilation`] that are missing in the incoming translation.
+    pub missing: Vec<LineId>,
+}
+
+impl MergeResult {
+    /// Returns a human-readable report of the merge, listing every line that needs attention.
+    #[must_use]
+    pub fn report(&self) -> String {
+        let mut report = format!(
+            "Accepted {} line(s), {} conflict(s), {} unknown line(s), {} missing line(s).\n",
+            self.accepted.len(),
+            self.conflicts.len(),
+            self.unknown.len(),
+            self.missing.len()
+        );
+        for conflict in &self.conflicts {
+            // Writing to a `String` cannot fail
+            let _ = writeln!(
+                report,
+                "Conflict in {}: base text is now \"{}\", translated from lock {}: \"{}\"",
+                conflict.id, conflict.base_text, conflict.incoming.lock, conflict.incoming.text
+            );
+            if let Some(existing) = &conflict.existing {
+                let _ = writeln!(
+                    report,
+                    "    existing translation with lock {}: \"{}\"",
+                    existing.lock, existing.text
+                );
+            }
+        }
+        for id in &self.unknown {
+            let _ = writeln!(report, "Unknown line {id} was ignored");
+        }
+        for id in &self.missing {
+            let _ = writeln!(report, "Missing line {id}");
+        }
+        report
+    }
+}
+
+/// Exports the string table of `base` as a strings table CSV as used by `bevy_yarnspinner`, ready to be handed off to translators.
+///
+/// If `namespace` is [`Some`], only the lines of that line ID namespace are exported (see [`Compiler::with_line_id_namespace`]),
+/// so that e.g. every team can hand off its own lines.
+pub fn export_strings_csv(base: &Compilation, language: &str, namespace: Option<&str>) -> String {
+    write_strings_csv(base, language, namespace, |_, string_info| {
+        (string_info.text.clone(), compute_lock(&string_info.text))
+    })
+}
+
+/// Exports a translation of `base`, e.g. the [`MergeResult::merged`] lines, as a strings table CSV as used by `bevy_yarnspinner`.
+///
+/// Like `bevy_yarnspinner` does for new lines, the lines of `base` that are not translated yet are written with their base text and lock.
+/// Translations of lines that are not part of `base` anymore are dropped.
+pub fn export_translation_csv(
+    base: &Compilation,
+    language: &str,
+    translation: &HashMap<LineId, TranslatedLine>,
+) -> String {
+    write_strings_csv(base, language, None, |id, string_info| {
+        translation.get(id).map_or_else(
+            || (string_info.text.clone(), compute_lock(&string_info.text)),
+            |line| (line.text.clone(), line.lock.clone()),
+        )
+    })
+}
+
+fn write_strings_csv(
+    base: &Compilation,
+    language: &str,
+    namespace: Option<&str>,
+    text_and_lock: impl Fn(&LineId, &StringInfo) -> (String, String),
+) -> String {
+    let mut lines: Vec<_> = base
+        .string_table
+        .iter()
+        .filter(|(_, string_info)| {
+            namespace.is_none() || string_info.line_id_namespace.as_deref() == namespace
+        })
+        .collect();
+    lines.sort_by(|(_, lhs), (_, rhs)| {
+        lhs.file_name
+            .cmp(&rhs.file_name)
+            .then(lhs.line_number.cmp(&rhs.line_number))
+    });
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let header = [
+        "language",
+        "id",
+        "text",
+        "file",
+        "node",
+        "line_number",
+        "lock",
+        "comment",
+    ];
+    // Writing to a `Vec` cannot fail
+    writer.write_record(header).unwrap();
+    for (id, string_info) in lines {
+        let metadata: Vec<_> = string_info
+            .metadata
+            .iter()
+            .filter(|metadata| !metadata.starts_with("line:"))
+            .map(String::as_str)
+            .collect();
+        let comment = if metadata.is_empty() {
+            String::new()
+        } else {
+            format!("Line metadata: {}", metadata.join(" "))
+        };
+        let (text, lock) = text_and_lock(id, string_info);
+        writer
+            .write_record([
+                language,
+                &id.0,
+                &text,
+                &string_info.file_name,
+                &string_info.node_name,
+                &string_info.line_number.to_string(),
+                &lock,
+                &comment,
+            ])
+            .unwrap();
+    }
+    String::from_utf8(writer.into_inner().unwrap()).unwrap()
+}
+
+/// Merges the translations returned by a translator into the existing translations of `base`.
+///
+/// `incoming` is a strings table CSV as used by `bevy_yarnspinner`, i.e. a header row followed by one row per line.
+/// Only the `id`, `text` and `lock` columns are read, all other columns are ignored.
+///
+/// - Incoming lines whose lock matches the lock of the current base text replace the existing translation.
+/// - Incoming lines whose lock doesn't match are conflicts and are resolved according to `policy`.
+/// - Incoming lines that are not in `base` are reported as unknown and ignored.
+/// - Lines of `base` that are not in `incoming` are reported as missing and keep their existing translation.
+pub fn merge_translation(
+    base: &Compilation,
+    existing: HashMap<LineId, TranslatedLine>,
+    incoming: &str,
+    policy: ConflictPolicy,
+) -> std::result::Result<MergeResult, TranslationParseError> {
+    let incoming = read_translation_csv(incoming)?;
+    let mut result = MergeResult {
+        merged: existing,
+        ..Default::default()
+    };
+
+    for (id, incoming_line) in incoming.iter() {
+        let Some(string_info) = base.string_table.get(id) else {
+            result.unknown.push(id.clone());
+            continue;
+        };
+        if incoming_line.lock == compute_lock(&string_info.text) {
+            result.merged.insert(id.clone(), incoming_line.clone());
+            result.accepted.push(id.clone());
+            continue;
+        }
+        let existing = result.merged.get(id).cloned();
+        match policy {
+            ConflictPolicy::KeepExisting => {}
+            ConflictPolicy::KeepIncoming => {
+                result.merged.insert(id.clone(), incoming_line.clone());
+            }
+            ConflictPolicy::MarkForReview => {
+                let text = if incoming_line.text.starts_with(NEEDS_UPDATE_PREFIX) {
+                    incoming_line.text.clone()
+                } else {
+                    format!("{NEEDS_UPDATE_PREFIX}{}", incoming_line.text)
+                };
+                let lock = incoming_line.lock.clone();
+                result
+                    .merged
+                    .insert(id.clone(), TranslatedLine { text, lock });
+            }
+        }
+        result.conflicts.push(MergeConflict {
+            id: id.clone(),
+            base_text: string_info.text.clone(),
+            existing,
+            incoming: incoming_line.clone(),
+        });
+    }
+    result.missing = base
+        .string_table
+        .keys()
+        .filter(|id| !incoming.contains_key(*id))
+        .cloned()
+        .collect();
+
+    result.accepted.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
+    result.conflicts.sort_by(|lhs, rhs| lhs.id.0.cmp(&rhs.id.0));
+    result.unknown.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
+    result.missing.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
+    Ok(result)
+}
+
+/// Checks a translation for lines whose number of interpolated values differs from their base text,
+/// e.g. because the translator dropped or mistyped a `{0}`, which usually means that the translation is broken.
+///
+/// `translation` is a strings table CSV as used by `bevy_yarnspinner`, see [`merge_translation`].
+/// Returns a [`DiagnosticCode::InterpolationCountMismatch`] warning for every such line, pointing at the line in the Yarn file.
+/// Only placeholders such as `{0}` count, so braces that are text, e.g. escaped with a backslash as in `\{`, are ignored.
+/// Lines that are not translated yet or are not in `base` are skipped.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_compiler::prelude::*;
+/// let base = Compiler::new()
+///     .add_source("shop.yarn", "title: Shop\n---\n<<declare $gold = 3>>\nYou have {$gold} gold. #line:gold\n===\n")
+///     .compile()
+///     .unwrap();
+/// let translation = "id,text,lock\nline:gold,Du hast Gold.,\n";
+/// let diagnostics = check_translation_interpolations(&base, translation).unwrap();
+/// assert_eq!(Some(DiagnosticCode::InterpolationCountMismatch), diagnostics[0].code);
+/// ```
+pub fn check_translation_interpolations(
+    base: &Compilation,
+    translation: &str,
+) -> std::result::Result<Vec<Diagnostic>, TranslationParseError> {
+    let translation = read_translation_csv(translation)?;
+    let mut diagnostics: Vec<_> = translation
+        .iter()
+        .filter(|(_, translated_line)| !translated_line.text.trim().is_empty())
+        .filter_map(|(id, translated_line)| {
+            let string_info = base.string_table.get(id)?;
+            let expected = count_interpolation_slots(&string_info.text);
+            let actual = count_interpolation_slots(&translated_line.text);
+            if expected == actual {
+                return None;
+            }
+            let position = Position {
+                line: string_info.line_number.saturating_sub(1),
+                character: 0,
+            };
+            let diagnostic = Diagnostic::from_message(format!(
+                "The translation of line {id} has {actual} interpolated value(s), but its base text has {expected}"
+            ))
+            .with_code(DiagnosticCode::InterpolationCountMismatch)
+            .with_argument("line_id", id)
+            .with_argument("expected", expected)
+            .with_argument("actual", actual)
+            .with_severity(DiagnosticSeverity::Warning)
+            .with_file_name(string_info.file_name.clone())
+            .with_range(position..position);
+            Some((id.0.clone(), diagnostic))
+        })
+        .collect();
+    diagnostics.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+    Ok(diagnostics
+        .into_iter()
+        .map(|(_, diagnostic)| diagnostic)
+        .collect())
+}
+
+/// Counts the placeholders such as `{0}` that the runtime interpolates values into.
+/// Other braces, e.g. the ones a `\{` escape in the Yarn file turns into, are text. A backslash escapes the character after it.
+fn count_interpolation_slots(text: &str) -> usize {
+    let mut count = 0;
+    let mut chars = text.char_indices();
+    while let Some((index, character)) = chars.next() {
+        match character {
+            '\\' => {
+                chars.next();
+            }
+            '{' => {
+                let placeholder = text[index + 1..]
+                    .split_once('}')
+                    .map(|(digits, _)| digits)
+                    .filter(|digits| {
+                        !digits.is_empty() && digits.bytes().all(|byte| byte.is_ascii_digit())
+                    });
+                count += usize::from(placeholder.is_some());
+            }
+            _ => {}
+        }
+    }
+    count
+}
+
+/// Reads the translated lines of a strings table CSV as used by `bevy_yarnspinner`, e.g. the existing translations passed to [`merge_translation`].
+/// Only the `id`, `text` and `lock` columns are read, all other columns are ignored.
+pub fn read_translation_csv(
+    csv: &str,
+) -> std::result::Result<HashMap<LineId, TranslatedLine>, TranslationParseError> {
+    let mut reader = csv::Reader::from_reader(csv.trim_start_matches('\u{feff}').as_bytes());
+    let headers = reader
+        .headers()
+        .map_err(TranslationParseError::from)?
+        .clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header == name)
+            .ok_or_else(|| TranslationParseError(format!("Missing column \"{name}\"")))
+    };
+    let (id_column, text_column, lock_column) = (column("id")?, column("text")?, column("lock")?);
+    reader
+        .records()
+        .map(|record| {
+            let record = record?;
+            let field = |index: usize| record.get(index).unwrap_or_default().to_owned();
+            let line = TranslatedLine {
+                text: field(text_column),
+                lock: field(lock_column),
+            };
+            Ok((LineId(field(id_column)), line))
+        })
+        .collect()
+}
+
+/// A translation passed to [`read_translation_csv`] or [`merge_translation`] is not a valid strings table CSV.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranslationParseError(pub String);
+
+impl Error for TranslationParseError {}
+
+impl Display for TranslationParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Failed to parse translation: {}", self.0)
+    }
+}
+
+impl From<csv::Error> for TranslationParseError {
+    fn from(error: csv::Error) -> Self {
+        Self(error.to_string())
+    }
+}
@@ -1,14 +1,20 @@
 mod code_generation_visitor;
+mod command_signature_visitor;
 mod constant_value_visitor;
 mod declaration_visitor;
+mod enum_declaration_visitor;
 mod hashable_interval;
+mod interpolation_type_visitor;
+mod jump_target_visitor;
 mod last_line_before_options_visitor;
+mod node_graph_visitor;
 mod node_tracking_visitor;
 mod string_table_generator_visitor;
 mod type_check_visitor;
 
 pub(crate) use self::{
-    code_generation_visitor::*, declaration_visitor::*, hashable_interval::*,
-    last_line_before_options_visitor::*, node_tracking_visitor::*,
-    string_table_generator_visitor::*, type_check_visitor::*,
+    code_generation_visitor::*, command_signature_visitor::*, declaration_visitor::*,
+    enum_declaration_visitor::*, hashable_interval::*, interpolation_type_visitor::*,
+    jump_target_visitor::*, last_line_before_options_visitor::*, node_graph_visitor::*,
+    node_tracking_visitor::*, string_table_generator_visitor::*, type_check_visitor::*,
 };
@@ -8,6 +8,7 @@ use crate::prelude::*;
 use antlr_rust::parser_rule_context::ParserRuleContext;
 use antlr_rust::token::Token;
 use antlr_rust::tree::{ParseTree, ParseTreeVisitorCompat, Tree};
+use better_any::TidExt;
 use std::ops::Deref;
 use std::rc::Rc;
 use yarnspinner_core::prelude::OpCode;
@@ -57,6 +58,60 @@ impl<'a, 'input: 'a> CodeGenerationVisitor<'a, 'input> {
         }
     }
 
+    /// Generates the expression of a smart variable into a node of its own, which is named after the variable.
+    /// Evaluating that node leaves the variable's value on the stack. See [`is_smart_variable_declaration`].
+    fn generate_smart_variable(&mut self, ctx: &Declare_statementContext<'input>) {
+        let variable_name = ctx.variable().unwrap().get_text();
+        let expression = ctx.expression().unwrap();
+        let smart_variable_node = Node {
+            name: variable_name.clone(),
+            tags: vec![Node::SMART_VARIABLE_TAG.to_owned()],
+            ..Default::default()
+        };
+        let (outer_node, outer_debug_info) = self
+            .compiler_listener
+            .replace_current_node(Some(smart_variable_node), Default::default());
+        self.visit(expression.as_ref());
+        let (smart_variable_node, mut debug_info) = self
+            .compiler_listener
+            .replace_current_node(outer_node, outer_debug_info);
+
+        debug_info.node_name.clone_from(&variable_name);
+        debug_info
+            .file_name
+            .clone_from(&self.compiler_listener.file.name);
+        self.compiler_listener
+            .debug_infos
+            .borrow_mut()
+            .push(debug_info);
+        self.compiler_listener
+            .program
+            .borrow_mut()
+            .nodes
+            .insert(variable_name, smart_variable_node.unwrap());
+    }
+
+    /// Resolves the destination of a jump or detour, which may be relative to the namespace of the current node.
+    fn resolve_node_name(&self, reference: &str) -> String {
+        let current_node_name = self
+            .compiler_listener
+            .current_node
+            .as_ref()
+            .map(|node| node.name.as_str());
+        resolve_node_name(
+            &self.compiler_listener.node_names,
+            reference,
+            current_node_name,
+        )
+    }
+
+    /// Returns the source text of `ctx` for the [`DebugInfo`], without surrounding whitespace.
+    fn get_source_text(&self, ctx: &(impl ParserRuleContext<'input> + ?Sized)) -> String {
+        ctx.get_text_with_whitespace(self.compiler_listener.file.tokens())
+            .trim()
+            .to_owned()
+    }
+
     // [sic] really ought to make this emit like a list of opcodes actually
     pub(crate) fn generate_tracking_code(compiler: &mut CompilerListener, variable_name: String) {
         // pushing the var and the increment onto the stack
@@ -95,6 +150,10 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
         //
         // <<if true>> Mae: here's a line <<endif>>
 
+        let statement = self
+            .compiler_listener
+            .begin_statement(StatementKind::Line, self.get_source_text(ctx));
+
         // Evaluate the inline expressions and push the results onto the
         // stack.
         let formatted_text = ctx.line_formatted_text().unwrap();
@@ -109,6 +168,7 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
                 .with_operand(line_id)
                 .with_operand(expression_count),
         );
+        self.compiler_listener.end_statement(statement);
     }
 
     /// (expression)
@@ -262,6 +322,40 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
         self.visit(ctx.function_call().unwrap().as_ref())
     }
 
+    /// enum cases are constants, so all we need do is visit the reference itself
+    fn visit_valueTypeMemberReference(
+        &mut self,
+        ctx: &ValueTypeMemberReferenceContext<'input>,
+    ) -> Self::Return {
+        self.visit(ctx.typeMemberReference().unwrap().as_ref())
+    }
+
+    fn visit_typeMemberReference(
+        &mut self,
+        ctx: &TypeMemberReferenceContext<'input>,
+    ) -> Self::Return {
+        let reference = ctx.get_text();
+        // The type checker knows the enum from the value expression around the reference, which spans the same tokens.
+        let raw_value = match self.compiler_listener.types.get(ctx) {
+            Some(Type::Enum(enum_type)) => enum_type
+                .cases
+                .iter()
+                .find(|case| enum_type.case_reference(case) == reference)
+                .map(|case| case.raw_value.clone()),
+            _ => None,
+        }
+        .expect("Enum case was not type checked. This is a bug.");
+        let emit = match raw_value {
+            YarnValue::String(raw_value) => {
+                Emit::from_op_code(OpCode::PushString).with_operand(raw_value)
+            }
+            raw_value => Emit::from_op_code(OpCode::PushFloat)
+                .with_operand(f32::try_from(&raw_value).unwrap()),
+        };
+        self.compiler_listener
+            .emit(emit.with_token(ctx.start().deref()));
+    }
+
     fn visit_variable(&mut self, ctx: &VariableContext<'input>) -> Self::Return {
         let variable_name = ctx.VAR_ID().unwrap().get_text();
         self.compiler_listener.emit(
@@ -340,8 +434,83 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
         );
     }
 
+    /// A <<once>> block, whose statements only run the first time it is reached,
+    /// as long as its condition passes if it has one.
+    /// Otherwise, the statements of its <<else>> are run, if there are any.
+    fn visit_once_statement(&mut self, ctx: &Once_statementContext<'input>) -> Self::Return {
+        let primary_clause = ctx.once_primary_clause().unwrap();
+        let token = primary_clause.start();
+        let node_name = self
+            .compiler_listener
+            .current_node
+            .as_ref()
+            .unwrap()
+            .name
+            .clone();
+        let variable_name = Library::generate_unique_once_variable(&node_name, get_once_index(ctx));
+        let alternate_label = self.compiler_listener.register_label("once_alternate");
+        let end_of_once_label = self.compiler_listener.register_label("once_end");
+
+        let expression = primary_clause.expression();
+        if let Some(expression) = expression.as_ref() {
+            // Evaluate the condition, and leave it on the stack
+            self.visit(expression.as_ref());
+        }
+        self.generate_code_for_once_condition(
+            variable_name.clone(),
+            token.deref(),
+            expression.is_some(),
+        );
+        self.compiler_listener.emit(
+            Emit::from_op_code(OpCode::JumpIfFalse)
+                .with_token(token.deref())
+                .with_operand(alternate_label.clone()),
+        );
+
+        // First time here, so remember that we were
+        self.compiler_listener
+            .emit(Emit::from_op_code(OpCode::Pop).with_token(token.deref()));
+        self.compiler_listener.emit(
+            Emit::from_op_code(OpCode::PushBool)
+                .with_token(token.deref())
+                .with_operand(true),
+        );
+        self.compiler_listener.emit(
+            Emit::from_op_code(OpCode::StoreVariable)
+                .with_token(token.deref())
+                .with_operand(variable_name),
+        );
+        self.compiler_listener
+            .emit(Emit::from_op_code(OpCode::Pop).with_token(token.deref()));
+        self.generate_code_for_statements(&primary_clause.statement_all());
+        self.compiler_listener.emit(
+            Emit::from_op_code(OpCode::JumpTo)
+                .with_token(primary_clause.stop().deref())
+                .with_operand(end_of_once_label.clone()),
+        );
+
+        // We've been here before, or the condition failed
+        let current_node = self.compiler_listener.current_node.as_mut().unwrap();
+        current_node
+            .labels
+            .insert(alternate_label, current_node.instructions.len() as i32);
+        self.compiler_listener
+            .emit(Emit::from_op_code(OpCode::Pop).with_token(token.deref()));
+        if let Some(alternate_clause) = ctx.once_alternate_clause() {
+            self.generate_code_for_statements(&alternate_clause.statement_all());
+        }
+
+        let current_node = self.compiler_listener.current_node.as_mut().unwrap();
+        current_node
+            .labels
+            .insert(end_of_once_label, current_node.instructions.len() as i32);
+    }
+
     /// A set command: explicitly setting a value to an expression <<set $foo to 1>>
     fn visit_set_statement(&mut self, ctx: &Set_statementContext<'input>) -> Self::Return {
+        let statement = self
+            .compiler_listener
+            .begin_statement(StatementKind::Set, self.get_source_text(ctx));
         // Ensure that the correct result is on the stack by evaluating the
         // expression. If this assignment includes an operation (e.g. +=),
         // do that work here too.
@@ -397,11 +566,16 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
         );
         self.compiler_listener
             .emit(Emit::from_op_code(OpCode::Pop).with_token(token.deref()));
+        self.compiler_listener.end_statement(statement);
     }
 
     fn visit_call_statement(&mut self, ctx: &Call_statementContext<'input>) -> Self::Return {
+        let statement = self
+            .compiler_listener
+            .begin_statement(StatementKind::Call, self.get_source_text(ctx));
         // Visit our function call, which will invoke the function
         self.visit(ctx.function_call().unwrap().as_ref());
+        self.compiler_listener.end_statement(statement);
         // [sic] TODO: if this function returns a value, it will be pushed onto
         // the stack, but there's no way for the compiler to know that, so
         // the stack will not be tidied up. is there a way for that to work?
@@ -410,53 +584,27 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
     /// semi-free form text that gets passed along to the game for things
     /// like <<turn fred left>> or <<unlockAchievement FacePlant>>
     fn visit_command_statement(&mut self, ctx: &Command_statementContext<'input>) -> Self::Return {
-        let formatted_text = ctx.command_formatted_text().unwrap();
-        let (composed_string, expression_count) = formatted_text.get_children().fold(
-            (String::new(), 0_usize),
-            |(composed_string, expression_count), node| {
-                if node.get_child_count() == 0 {
-                    // Terminal node
-                    (composed_string + &node.get_text(), expression_count)
-                } else {
-                    // Generate code for evaluating the expression at runtime
-                    self.visit(node.as_ref());
-                    // Don't include the '{' and '}', because it will have been
-                    // added as a terminal node already
-                    (
-                        composed_string + &expression_count.to_string(),
-                        expression_count + 1,
-                    )
-                }
-            },
-        );
-
-        // [sic] TODO: look into replacing this as it seems a bit odd
-        match composed_string.as_str() {
-            "stop" => {
-                // "stop" is a special command that immediately stops
-                // execution
-                self.compiler_listener.emit(
-                    Emit::from_op_code(OpCode::Stop).with_token(formatted_text.start().deref()),
-                );
-            }
-            _ => {
-                self.compiler_listener.emit(
-                    Emit::from_op_code(OpCode::RunCommand)
-                        .with_token(formatted_text.start().deref())
-                        .with_operand(composed_string)
-                        .with_operand(expression_count),
-                );
-            }
-        }
+        let statement = self
+            .compiler_listener
+            .begin_statement(StatementKind::Command, self.get_source_text(ctx));
+        self.generate_code_for_command(ctx);
+        self.compiler_listener.end_statement(statement);
     }
 
+    /// Enums only exist at compile time, their cases are replaced by their raw values.
+    fn visit_enum_statement(&mut self, _ctx: &Enum_statementContext<'input>) -> Self::Return {}
+
     /// for the shortcut options (-> line of text <<if expression>> indent statements dedent)+
     fn visit_shortcut_option_statement(
         &mut self,
         ctx: &Shortcut_option_statementContext<'input>,
     ) -> Self::Return {
+        let group_statement = self
+            .compiler_listener
+            .begin_statement(StatementKind::OptionGroup, self.get_source_text(ctx));
         let end_of_group_label = self.compiler_listener.register_label("group_end");
         let mut labels = Vec::new();
+        let mut once_variables = Vec::new();
 
         // For each option, create an internal destination label that, if
         // the user selects the option, control flow jumps to. Then,
@@ -483,23 +631,38 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
             // This line statement may have a condition on it. If it does,
             // emit code that evaluates the condition, and add a flag on the
             // 'Add Option' instruction that indicates that a condition exists.
-            let has_line_condition = if let Some(expression) = shortcut
-                .line_statement()
-                .and_then(|ctx| ctx.line_condition())
-                .and_then(|ctx| ctx.expression())
-            {
+            let line_statement = shortcut.line_statement().unwrap();
+            let option_statement = self.compiler_listener.begin_statement(
+                StatementKind::Option,
+                self.get_source_text(line_statement.as_ref()),
+            );
+            let expression = line_statement
+                .line_condition()
+                .and_then(|ctx| ctx.expression());
+            if let Some(expression) = expression.as_ref() {
                 // Evaluate the condition, and leave it on the stack
                 self.visit(expression.as_ref());
-                true
-            } else {
-                false
-            };
+            }
+
+            // A <<once>> option is only available as long as it has not been selected,
+            // which is combined with its condition if it has one.
+            let once_variable = is_once_option(&line_statement).then(|| {
+                Library::generate_unique_once_variable(&name, get_once_index(shortcut.as_ref()))
+            });
+            if let Some(once_variable) = once_variable.as_ref() {
+                self.generate_code_for_once_condition(
+                    once_variable.clone(),
+                    line_statement.start().deref(),
+                    expression.is_some(),
+                );
+            }
+            let has_line_condition = expression.is_some() || once_variable.is_some();
+            once_variables.push(once_variable);
 
             // We can now prepare and add the option.
 
             // Start by figuring out the text that we want to add. This will
             // involve evaluating any inline expressions.
-            let line_statement = shortcut.line_statement().unwrap();
             let expression_count = self.generate_code_for_expressions_in_formatted_text(
                 line_statement.line_formatted_text().unwrap().get_children(),
             );
@@ -509,15 +672,18 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
                 .expect("Internal error: no line ID provided. This is a bug. Please report it at https://github.com/YarnSpinnerTool/YarnSpinner-Rust/issues/new");
             let line_id = line_id_tag.text.as_ref().unwrap().get_text().to_owned();
 
-            // And add this option to the list.
-            self.compiler_listener.emit(
-                Emit::from_op_code(OpCode::AddOption)
-                    .with_token(line_statement.start().deref())
-                    .with_operand(line_id)
-                    .with_operand(option_destination_label)
-                    .with_operand(expression_count)
-                    .with_operand(has_line_condition),
-            );
+            // And add this option to the list, along with the node its content jumps to, if any.
+            let mut emit = Emit::from_op_code(OpCode::AddOption)
+                .with_token(line_statement.start().deref())
+                .with_operand(line_id)
+                .with_operand(option_destination_label)
+                .with_operand(expression_count)
+                .with_operand(has_line_condition);
+            if let Some(destination_node) = option_destination_node(&shortcut.statement_all()) {
+                emit = emit.with_operand(self.resolve_node_name(&destination_node));
+            }
+            self.compiler_listener.emit(emit);
+            self.compiler_listener.end_statement(option_statement);
         }
         // All of the options that we intend to show are now ready to go.
         let token = ctx.stop();
@@ -530,25 +696,49 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
             .emit(Emit::from_op_code(OpCode::Jump).with_token(token.deref()));
 
         // We'll now emit the labels and code associated with each option.
+        let mut option_instructions = Vec::new();
         for (option_count, shortcut) in ctx.shortcut_option_all().into_iter().enumerate() {
             // Emit the label for this option's code
             let current_node = self.compiler_listener.current_node.as_mut().unwrap();
-            current_node.labels.insert(
-                labels[option_count].clone(),
-                current_node.instructions.len() as i32,
-            );
+            let option_start = current_node.instructions.len();
+            current_node
+                .labels
+                .insert(labels[option_count].clone(), option_start as i32);
 
-            // Run through all the children statements of the shortcut option
-            for child in shortcut.statement_all() {
-                self.visit(child.as_ref());
+            // Remember that a <<once>> option was selected
+            if let Some(once_variable) = once_variables[option_count].clone() {
+                let token = shortcut.start();
+                self.compiler_listener.emit(
+                    Emit::from_op_code(OpCode::PushBool)
+                        .with_token(token.deref())
+                        .with_operand(true),
+                );
+                self.compiler_listener.emit(
+                    Emit::from_op_code(OpCode::StoreVariable)
+                        .with_token(token.deref())
+                        .with_operand(once_variable),
+                );
+                self.compiler_listener
+                    .emit(Emit::from_op_code(OpCode::Pop).with_token(token.deref()));
             }
 
+            // Run through all the children statements of the shortcut option
+            self.generate_code_for_statements(&shortcut.statement_all());
+
             // Jump to the end of this shortcut option group.
             self.compiler_listener.emit(
                 Emit::from_op_code(OpCode::JumpTo)
                     .with_token(shortcut.stop().deref())
                     .with_operand(end_of_group_label.clone()),
             );
+            let option_end = self
+                .compiler_listener
+                .current_node
+                .as_ref()
+                .unwrap()
+                .instructions
+                .len();
+            option_instructions.push(option_start..option_end);
         }
 
         // We made it to the end! Mark the end of the group, so we can jump to it
@@ -558,41 +748,343 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
             .insert(end_of_group_label, current_node.instructions.len() as i32);
         self.compiler_listener
             .emit(Emit::from_op_code(OpCode::Pop).with_token(token.deref()));
+        self.compiler_listener
+            .end_statement(group_statement)
+            .option_instructions = option_instructions;
     }
 
-    fn visit_declare_statement(&mut self, _ctx: &Declare_statementContext<'input>) -> Self::Return {
-        // Declare statements do not participate in code generation
+    fn visit_declare_statement(&mut self, ctx: &Declare_statementContext<'input>) -> Self::Return {
+        if is_smart_variable_declaration(ctx) {
+            self.generate_smart_variable(ctx);
+        }
+        // Other declare statements do not participate in code generation
     }
 
     /// A <<jump>> command, which immediately jumps to another node, given its name.
     fn visit_jumpToNodeName(&mut self, ctx: &JumpToNodeNameContext<'input>) -> Self::Return {
+        let statement = self
+            .compiler_listener
+            .begin_statement(StatementKind::Jump, self.get_source_text(ctx));
         if let Some(tracking_enabled) = self.tracking_enabled.clone() {
             Self::generate_tracking_code(self.compiler_listener, tracking_enabled);
         }
         let destination = ctx.destination.as_ref().unwrap();
+        let node_name = self.resolve_node_name(destination.get_text());
         self.compiler_listener.emit(
             Emit::from_op_code(OpCode::PushString)
                 .with_token(destination.deref())
-                .with_operand(destination.get_text().to_owned()),
+                .with_operand(node_name),
         );
         self.compiler_listener
-            .emit(Emit::from_op_code(OpCode::RunNode).with_token(ctx.start().deref()))
+            .emit(Emit::from_op_code(OpCode::RunNode).with_token(ctx.start().deref()));
+        self.compiler_listener.end_statement(statement);
     }
 
     /// A <<jump>> command, which immediately jumps to another node, given an
     /// expression that resolves to a node's name.
     fn visit_jumpToExpression(&mut self, ctx: &JumpToExpressionContext<'input>) -> Self::Return {
+        let statement = self
+            .compiler_listener
+            .begin_statement(StatementKind::Jump, self.get_source_text(ctx));
         if let Some(tracking_enabled) = self.tracking_enabled.clone() {
             Self::generate_tracking_code(self.compiler_listener, tracking_enabled);
         }
         // Evaluate the expression, and jump to the result on the stack.
         self.visit(ctx.expression().unwrap().as_ref());
         self.compiler_listener
-            .emit(Emit::from_op_code(OpCode::RunNode).with_token(ctx.start().deref()))
+            .emit(Emit::from_op_code(OpCode::RunNode).with_token(ctx.start().deref()));
+        self.compiler_listener.end_statement(statement);
     }
 }
 
 impl<'a, 'input: 'a> CodeGenerationVisitor<'a, 'input> {
+    /// Generates code for a list of statements.
+    /// Consecutive lines marked with [`LINE_GROUP_MARKER`] are turned into a line group.
+    pub(crate) fn generate_code_for_statements(
+        &mut self,
+        statements: &[Rc<StatementContext<'input>>],
+    ) {
+        let mut line_group = Vec::new();
+        for statement in statements {
+            match statement
+                .line_statement()
+                .filter(|line_statement| is_line_group_candidate(line_statement))
+            {
+                Some(line_statement) => line_group.push(line_statement),
+                None => {
+                    self.generate_code_for_line_group(std::mem::take(&mut line_group));
+                    self.visit(statement.as_ref());
+                }
+            }
+        }
+        self.generate_code_for_line_group(line_group);
+    }
+
+    /// Generates the code of a command, or of the built-in command it stands for.
+    fn generate_code_for_command(&mut self, ctx: &Command_statementContext<'input>) {
+        let formatted_text = ctx.command_formatted_text().unwrap();
+        let (composed_string, expression_count) = formatted_text.get_children().fold(
+            (String::new(), 0_usize),
+            |(composed_string, expression_count), node| {
+                if node.get_child_count() == 0 {
+                    // Terminal node
+                    (composed_string + &node.get_text(), expression_count)
+                } else {
+                    // Generate code for evaluating the expression at runtime
+                    self.visit(node.as_ref());
+                    // Don't include the '{' and '}', because it will have been
+                    // added as a terminal node already
+                    (
+                        composed_string + &expression_count.to_string(),
+                        expression_count + 1,
+                    )
+                }
+            },
+        );
+
+        if let Some(destination) = composed_string
+            .strip_prefix("detour ")
+            .map(str::trim)
+            .filter(|destination| expression_count == 0 && !destination.is_empty())
+        {
+            // "detour" is a special command that runs another node and
+            // comes back here once that node returns
+            let node_name = self.resolve_node_name(destination);
+            self.compiler_listener.emit(
+                Emit::from_op_code(OpCode::PushString)
+                    .with_token(formatted_text.start().deref())
+                    .with_operand(node_name),
+            );
+            self.compiler_listener.emit(
+                Emit::from_op_code(OpCode::DetourToNode).with_token(formatted_text.start().deref()),
+            );
+            return;
+        }
+
+        // [sic] TODO: look into replacing this as it seems a bit odd
+        match composed_string.as_str() {
+            "stop" => {
+                // "stop" is a special command that immediately stops
+                // execution
+                self.compiler_listener.emit(
+                    Emit::from_op_code(OpCode::Stop).with_token(formatted_text.start().deref()),
+                );
+            }
+            "return" => {
+                // "return" is a special command that goes back to the node
+                // that detoured into this one.
+                // Returning early still counts as a visit, just like jumping away
+                if let Some(tracking_enabled) = self.tracking_enabled.clone() {
+                    Self::generate_tracking_code(self.compiler_listener, tracking_enabled);
+                }
+                self.compiler_listener.emit(
+                    Emit::from_op_code(OpCode::Return).with_token(formatted_text.start().deref()),
+                );
+            }
+            _ => {
+                self.compiler_listener.emit(
+                    Emit::from_op_code(OpCode::RunCommand)
+                        .with_token(formatted_text.start().deref())
+                        .with_operand(composed_string)
+                        .with_operand(expression_count),
+                );
+            }
+        }
+    }
+
+    /// Emits code that leaves whether a `<<once>>` block or option has not been run or selected yet on the stack.
+    /// If it also has a condition, whose result is already on the stack, both are combined.
+    fn generate_code_for_once_condition(
+        &mut self,
+        once_variable: String,
+        token: &impl Token,
+        has_condition: bool,
+    ) {
+        self.compiler_listener.emit(
+            Emit::from_op_code(OpCode::PushVariable)
+                .with_token(token)
+                .with_operand(once_variable),
+        );
+        self.compiler_listener.emit(
+            Emit::from_op_code(OpCode::PushFloat)
+                .with_token(token)
+                .with_operand(1.),
+        );
+        self.compiler_listener.emit(
+            Emit::from_op_code(OpCode::CallFunc)
+                .with_token(token)
+                .with_operand(
+                    Type::Boolean.get_canonical_name_for_method(&Operator::Not.to_string()),
+                ),
+        );
+        if has_condition {
+            self.compiler_listener.emit(
+                Emit::from_op_code(OpCode::PushFloat)
+                    .with_token(token)
+                    .with_operand(2.),
+            );
+            self.compiler_listener.emit(
+                Emit::from_op_code(OpCode::CallFunc)
+                    .with_token(token)
+                    .with_operand(
+                        Type::Boolean.get_canonical_name_for_method(&Operator::And.to_string()),
+                    ),
+            );
+        }
+    }
+
+    /// Emits code that runs only the most salient line of a line group, i.e. the one with the most complex condition that passes.
+    /// If no line's condition passes, the whole group is skipped.
+    fn generate_code_for_line_group(&mut self, candidates: Vec<Rc<Line_statementContext<'input>>>) {
+        let (Some(first), Some(last)) = (candidates.first(), candidates.last()) else {
+            return;
+        };
+        let end_of_group_label = self.compiler_listener.register_label("linegroup_end");
+        let mut labels = Vec::new();
+
+        // Register every candidate whose condition passes, together with
+        // how specific its condition is.
+        for candidate in &candidates {
+            let candidate_label = self.compiler_listener.register_label("linegroup_candidate");
+            labels.push(candidate_label.clone());
+
+            let complexity = if let Some(expression) =
+                candidate.line_condition().and_then(|ctx| ctx.expression())
+            {
+                // Evaluate the condition, and leave it on the stack
+                self.visit(expression.as_ref());
+                1 + count_boolean_operators(&(expression as Rc<ActualParserContext<'input>>))
+            } else {
+                self.compiler_listener.emit(
+                    Emit::from_op_code(OpCode::PushBool)
+                        .with_token(candidate.start().deref())
+                        .with_operand(true),
+                );
+                0
+            };
+
+            let line_id_tag = get_line_id_tag(&candidate.hashtag_all())
+                .expect("Internal error: no line ID provided. This is a bug. Please report it at https://github.com/YarnSpinnerTool/YarnSpinner-Rust/issues/new");
+            let line_id = line_id_tag.text.as_ref().unwrap().get_text().to_owned();
+            self.compiler_listener.emit(
+                Emit::from_op_code(OpCode::AddSaliencyCandidate)
+                    .with_token(candidate.start().deref())
+                    .with_operand(line_id)
+                    .with_operand(complexity)
+                    .with_operand(candidate_label),
+            );
+        }
+
+        // The top of the stack now says whether a candidate was selected.
+        // If so, the destination of the candidate is below it.
+        self.compiler_listener.emit(
+            Emit::from_op_code(OpCode::SelectSaliencyCandidate).with_token(first.start().deref()),
+        );
+        self.compiler_listener.emit(
+            Emit::from_op_code(OpCode::JumpIfFalse)
+                .with_token(first.start().deref())
+                .with_operand(end_of_group_label.clone()),
+        );
+        self.compiler_listener
+            .emit(Emit::from_op_code(OpCode::Pop).with_token(first.start().deref()));
+        self.compiler_listener
+            .emit(Emit::from_op_code(OpCode::Jump).with_token(first.start().deref()));
+
+        for (candidate, label) in candidates.iter().zip(labels) {
+            let current_node = self.compiler_listener.current_node.as_mut().unwrap();
+            current_node
+                .labels
+                .insert(label, current_node.instructions.len() as i32);
+            self.visit(candidate.as_ref());
+            self.compiler_listener.emit(
+                Emit::from_op_code(OpCode::JumpTo)
+                    .with_token(candidate.stop().deref())
+                    .with_operand(end_of_group_label.clone()),
+            );
+        }
+
+        // Both paths arrive here with one value left on the stack:
+        // either the destination of the selected candidate or `false`.
+        let current_node = self.compiler_listener.current_node.as_mut().unwrap();
+        current_node
+            .labels
+            .insert(end_of_group_label, current_node.instructions.len() as i32);
+        self.compiler_listener
+            .emit(Emit::from_op_code(OpCode::Pop).with_token(last.stop().deref()));
+    }
+
+    /// Emits the code of a node generated for a node group, see [`Node::NODE_GROUP_TAG`].
+    /// Its statements are one `<<jump>>` per node of the group, wrapped in an `<<if>>` holding the node's `when:` conditions
+    /// unless they are all `always`. Only the most salient node whose conditions pass is jumped to.
+    /// If no node's conditions pass, the generated node ends without running any of them.
+    ///
+    /// A node that was tagged by hand and does not have this shape is compiled like any other node.
+    pub(crate) fn generate_code_for_node_group(
+        &mut self,
+        statements: &[Rc<StatementContext<'input>>],
+    ) {
+        let candidates: Option<Vec<_>> = statements.iter().map(node_group_candidate).collect();
+        let (Some(candidates), Some(first), Some(last)) =
+            (candidates, statements.first(), statements.last())
+        else {
+            self.generate_code_for_statements(statements);
+            return;
+        };
+        let end_of_group_label = self.compiler_listener.register_label("nodegroup_end");
+
+        // Register every node whose conditions pass, together with
+        // how specific its conditions are.
+        for (statement, (condition, destination)) in statements.iter().zip(candidates) {
+            let complexity = if let Some(expression) = condition {
+                // Evaluate the conditions, and leave the result on the stack
+                self.visit(expression.as_ref());
+                1 + count_boolean_operators(&(expression as Rc<ActualParserContext<'input>>))
+            } else {
+                self.compiler_listener.emit(
+                    Emit::from_op_code(OpCode::PushBool)
+                        .with_token(statement.start().deref())
+                        .with_operand(true),
+                );
+                0
+            };
+
+            let node_name = self.resolve_node_name(&destination);
+            self.compiler_listener.emit(
+                Emit::from_op_code(OpCode::AddSaliencyCandidate)
+                    .with_token(statement.start().deref())
+                    .with_operand(node_name.clone())
+                    .with_operand(complexity)
+                    .with_operand(node_name),
+            );
+        }
+
+        // The top of the stack now says whether a node was selected.
+        // If so, the name of the node is below it.
+        self.compiler_listener.emit(
+            Emit::from_op_code(OpCode::SelectSaliencyCandidate).with_token(first.start().deref()),
+        );
+        self.compiler_listener.emit(
+            Emit::from_op_code(OpCode::JumpIfFalse)
+                .with_token(first.start().deref())
+                .with_operand(end_of_group_label.clone()),
+        );
+        self.compiler_listener
+            .emit(Emit::from_op_code(OpCode::Pop).with_token(first.start().deref()));
+        if let Some(tracking_enabled) = self.tracking_enabled.clone() {
+            Self::generate_tracking_code(self.compiler_listener, tracking_enabled);
+        }
+        self.compiler_listener
+            .emit(Emit::from_op_code(OpCode::RunNode).with_token(first.start().deref()));
+
+        // Only arrived at if no node was selected, with `false` left on the stack.
+        let current_node = self.compiler_listener.current_node.as_mut().unwrap();
+        current_node
+            .labels
+            .insert(end_of_group_label, current_node.instructions.len() as i32);
+        self.compiler_listener
+            .emit(Emit::from_op_code(OpCode::Pop).with_token(last.stop().deref()));
+    }
+
     fn generate_code_for_expressions_in_formatted_text(
         &mut self,
         nodes: impl Iterator<Item = Rc<ActualParserContext<'input>>>,
@@ -637,7 +1129,10 @@ impl<'a, 'input: 'a> CodeGenerationVisitor<'a, 'input> {
         // Figure out the canonical name for the method that the VM should
         // invoke in order to perform this work
         let method_name = op.to_string();
-        let has_method = r#type.has_method(&method_name);
+        let has_method = self
+            .compiler_listener
+            .library
+            .has_method(r#type, &method_name);
         assert!(
             has_method,
             "Codegen failed to get implementation type for {} given input type {}.",
@@ -664,6 +1159,10 @@ impl<'a, 'input: 'a> CodeGenerationVisitor<'a, 'input> {
         let end_of_clause_label = self.compiler_listener.register_label("skipclause");
         // handling the expression (if it has one) will only be called on ifs and elseifs
         if let Some(expression) = expression.clone() {
+            let statement = self.compiler_listener.begin_statement(
+                StatementKind::IfCondition,
+                self.get_source_text(expression.as_ref()),
+            );
             // Code-generate the expression
             self.visit(expression.as_ref());
 
@@ -672,12 +1171,11 @@ impl<'a, 'input: 'a> CodeGenerationVisitor<'a, 'input> {
                     .with_token(expression.start().deref())
                     .with_operand(end_of_clause_label.clone()),
             );
+            self.compiler_listener.end_statement(statement);
         }
 
         // running through all of the children statements
-        for child in children {
-            self.visit(child.as_ref());
-        }
+        self.generate_code_for_statements(children);
 
         self.compiler_listener.emit(
             Emit::from_op_code(OpCode::JumpTo)
@@ -695,3 +1193,50 @@ impl<'a, 'input: 'a> CodeGenerationVisitor<'a, 'input> {
         }
     }
 }
+
+/// Returns the conditions, if any, and the destination of a statement generated for a node of a node group,
+/// i.e. either `<<jump Destination>>` or `<<if conditions>> <<jump Destination>> <<endif>>`.
+fn node_group_candidate<'input>(
+    statement: &Rc<StatementContext<'input>>,
+) -> Option<(Option<Rc<ExpressionContextAll<'input>>>, String)> {
+    let (condition, jump_statement) = match statement.if_statement() {
+        Some(if_statement) => {
+            let if_clause = if_statement.if_clause()?;
+            let [body] = &if_clause.statement_all()[..] else {
+                return None;
+            };
+            (Some(if_clause.expression()?), body.jump_statement()?)
+        }
+        None => (None, statement.jump_statement()?),
+    };
+    let Jump_statementContextAll::JumpToNodeNameContext(jump) = jump_statement.as_ref() else {
+        return None;
+    };
+    Some((condition, jump.destination.as_ref()?.get_text().to_owned()))
+}
+
+/// The node that the content of an option jumps to unconditionally, or [`None`] if the dialogue continues after the option group.
+fn option_destination_node(statements: &[Rc<StatementContext<'_>>]) -> Option<String> {
+    statements.iter().find_map(|statement| {
+        let jump_statement = statement.jump_statement()?;
+        let Jump_statementContextAll::JumpToNodeNameContext(jump) = jump_statement.as_ref() else {
+            return None;
+        };
+        Some(jump.destination.as_ref()?.get_text().to_owned())
+    })
+}
+
+fn count_boolean_operators(context: &Rc<ActualParserContext<'_>>) -> usize {
+    let is_boolean_operator = matches!(
+        context
+            .clone()
+            .downcast_rc::<ExpressionContextAll>()
+            .as_deref(),
+        Ok(ExpressionContextAll::ExpAndOrXorContext(_))
+    );
+    usize::from(is_boolean_operator)
+        + context
+            .get_children()
+            .map(|child| count_boolean_operators(&child))
+            .sum::<usize>()
+}
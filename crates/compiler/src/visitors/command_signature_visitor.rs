@@ -0,0 +1,260 @@
+use crate::prelude::generated::yarnspinnerparser::*;
+use crate::prelude::generated::yarnspinnerparservisitor::YarnSpinnerParserVisitorCompat;
+use crate::prelude::*;
+use crate::visitors::KnownTypes;
+use antlr_rust::tree::{ParseTreeVisitorCompat, Tree};
+use std::rc::Rc;
+use yarnspinner_core::prelude::*;
+use yarnspinner_core::types::*;
+
+/// The commands that are handled by the compiler or the runtime itself and therefore never need to be declared.
+const BUILT_IN_COMMANDS: &[&str] = &["stop", "return", "detour", "wait"];
+
+/// Validates the commands of a file against the command declarations of the [`Compiler`],
+/// i.e. warns about undeclared commands and errors on arguments whose count or types don't match the declared signature.
+///
+/// Must run after the [`TypeCheckVisitor`](crate::visitors::TypeCheckVisitor) determined the types of all expressions.
+pub(crate) struct CommandSignatureVisitor<'a, 'input: 'a> {
+    pub(crate) diagnostics: Vec<Diagnostic>,
+    known_types: &'a KnownTypes,
+    command_declarations: &'a [Declaration],
+    file: FileParseResult<'input>,
+    _dummy: (),
+}
+
+impl<'a, 'input: 'a> CommandSignatureVisitor<'a, 'input> {
+    pub(crate) fn new(
+        known_types: &'a KnownTypes,
+        command_declarations: &'a [Declaration],
+        file: FileParseResult<'input>,
+    ) -> Self {
+        Self {
+            diagnostics: Default::default(),
+            known_types,
+            command_declarations,
+            file,
+            _dummy: Default::default(),
+        }
+    }
+
+    fn push_diagnostic(&mut self, diagnostic: Diagnostic, ctx: &Command_statementContext<'input>) {
+        self.diagnostics.push(
+            diagnostic
+                .with_file_name(self.file.name.clone())
+                .with_parser_context(ctx, self.file.tokens()),
+        );
+    }
+
+    fn check_argument(
+        &mut self,
+        command_name: &str,
+        index: usize,
+        argument: &CommandArgument<'input>,
+        expected: &Type,
+        ctx: &Command_statementContext<'input>,
+    ) {
+        let (actual, matches) = match argument {
+            CommandArgument::Expression(expression) => {
+                // Expressions whose type could not be determined were already reported by the type checker
+                let Some(actual) = self.known_types.get(expression.as_ref()) else {
+                    return;
+                };
+                (actual.clone(), actual.is_sub_type_of(expected))
+            }
+            CommandArgument::Text { text, is_quoted } => {
+                let literal_type = match text.as_str() {
+                    _ if *is_quoted => Type::String,
+                    "true" | "false" if *expected == Type::Boolean => Type::Boolean,
+                    _ if *expected == Type::Number && text.parse::<f32>().is_ok() => Type::Number,
+                    _ => Type::String,
+                };
+                let matches = literal_type.is_sub_type_of(expected);
+                (literal_type, matches)
+            }
+        };
+        if !matches {
+            self.push_diagnostic(
+                Diagnostic::from_message(format!(
+                    "Argument {} of command \"{command_name}\" expects {}, but received {}",
+                    index + 1,
+                    expected.format(),
+                    actual.format()
+                ))
+                .with_code(DiagnosticCode::TypeMismatch)
+                .with_argument("expected", expected.format())
+                .with_argument("actual", actual.format()),
+                ctx,
+            );
+        }
+    }
+}
+
+impl<'a, 'input: 'a> ParseTreeVisitorCompat<'input> for CommandSignatureVisitor<'a, 'input> {
+    type Node = YarnSpinnerParserContextType;
+    type Return = ();
+
+    fn temp_result(&mut self) -> &mut Self::Return {
+        &mut self._dummy
+    }
+}
+
+impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input>
+    for CommandSignatureVisitor<'a, 'input>
+{
+    fn visit_command_statement(&mut self, ctx: &Command_statementContext<'input>) -> Self::Return {
+        let Some(formatted_text) = ctx.command_formatted_text() else {
+            return;
+        };
+        let mut arguments = tokenize_command(&formatted_text).into_iter();
+        let Some(CommandArgument::Text {
+            text: command_name,
+            is_quoted: false,
+        }) = arguments.next()
+        else {
+            // Commands whose name is computed at runtime cannot be validated
+            return;
+        };
+        if BUILT_IN_COMMANDS.contains(&command_name.as_str()) {
+            return;
+        }
+        let arguments: Vec<_> = arguments.collect();
+
+        let declaration = self
+            .command_declarations
+            .iter()
+            .find(|declaration| declaration.name == command_name);
+        let Some(declaration) = declaration else {
+            self.diagnostics.push(
+                Diagnostic::from_message(format!("Command \"{command_name}\" is not declared"))
+                    .with_code(DiagnosticCode::UndeclaredCommand)
+                    .with_argument("command", &command_name)
+                    .with_file_name(self.file.name.clone())
+                    .with_parser_context(ctx, self.file.tokens())
+                    .with_severity(DiagnosticSeverity::Warning),
+            );
+            return;
+        };
+        let Type::Function(signature) = &declaration.r#type else {
+            self.push_diagnostic(
+                Diagnostic::from_message(format!(
+                    "Command \"{command_name}\" is declared with the type {}, but commands must be declared with a function type",
+                    declaration.r#type.format()
+                ))
+                .with_code(DiagnosticCode::TypeMismatch)
+                .with_argument("expected", "Function")
+                .with_argument("actual", declaration.r#type.format()),
+                ctx,
+            );
+            return;
+        };
+
+        let parameter_count = signature.parameters.len();
+        let has_valid_count = if signature.is_variadic {
+            arguments.len() + 1 >= parameter_count
+        } else {
+            arguments.len() == parameter_count
+        };
+        if !has_valid_count {
+            let expected = if signature.is_variadic {
+                format!("at least {}", parameter_count.saturating_sub(1))
+            } else {
+                parameter_count.to_string()
+            };
+            self.push_diagnostic(
+                Diagnostic::from_message(format!(
+                    "Command \"{command_name}\" expects {expected} argument(s), but received {}",
+                    arguments.len()
+                ))
+                .with_code(DiagnosticCode::WrongParameterCount)
+                .with_argument("name", &command_name)
+                .with_argument("expected", &expected)
+                .with_argument("actual", arguments.len()),
+                ctx,
+            );
+            return;
+        }
+
+        for (index, argument) in arguments.iter().enumerate() {
+            // Surplus arguments of a variadic command all have the type of its last parameter
+            let expected = signature
+                .parameters
+                .get(index)
+                .or_else(|| signature.parameters.last())
+                .cloned()
+                .flatten();
+            if let Some(expected) = expected {
+                self.check_argument(&command_name, index, argument, &expected, ctx);
+            }
+        }
+    }
+}
+
+enum CommandArgument<'input> {
+    /// An argument consisting of a single expression, e.g. `{$gold}`.
+    Expression(Rc<ExpressionContextAll<'input>>),
+    /// Any other argument. Arguments that mix text and expressions, e.g. `gold_{$gold}`, are treated as text.
+    Text { text: String, is_quoted: bool },
+}
+
+/// Splits a command into its name and arguments the same way the runtime does,
+/// i.e. at whitespace that is not inside of double quotes.
+fn tokenize_command<'input>(
+    formatted_text: &Command_formatted_textContext<'input>,
+) -> Vec<CommandArgument<'input>> {
+    #[derive(Default)]
+    struct Token<'input> {
+        text: String,
+        expressions: Vec<Rc<ExpressionContextAll<'input>>>,
+        is_quoted: bool,
+    }
+
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut current: Option<Token> = None;
+    let mut is_in_quotes = false;
+    let mut is_escaped = false;
+    let mut expressions = formatted_text.expression_all().into_iter();
+    for child in formatted_text.get_children() {
+        if child.get_child_count() > 0 {
+            let token = current.get_or_insert_with(Token::default);
+            token.expressions.extend(expressions.next());
+            continue;
+        }
+        let text = child.get_text();
+        if text == "{" || text == "}" {
+            continue;
+        }
+        for char in text.chars() {
+            match char {
+                _ if is_escaped => {
+                    is_escaped = false;
+                    current.get_or_insert_with(Token::default).text.push(char);
+                }
+                '\\' if is_in_quotes => is_escaped = true,
+                '"' => {
+                    is_in_quotes = !is_in_quotes;
+                    current.get_or_insert_with(Token::default).is_quoted = true;
+                }
+                _ if char.is_whitespace() && !is_in_quotes => {
+                    tokens.extend(current.take());
+                }
+                _ => current.get_or_insert_with(Token::default).text.push(char),
+            }
+        }
+    }
+    tokens.extend(current);
+
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            if token.text.is_empty() && !token.is_quoted && token.expressions.len() == 1 {
+                CommandArgument::Expression(token.expressions.remove(0))
+            } else {
+                let is_quoted = token.is_quoted || !token.expressions.is_empty();
+                CommandArgument::Text {
+                    text: token.text,
+                    is_quoted,
+                }
+            }
+        })
+        .collect()
+}
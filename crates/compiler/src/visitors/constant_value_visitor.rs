@@ -1,17 +1,37 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner.Compiler/ConstantValueVisitor.cs>
 
+use crate::parser_rule_context_ext::ParserRuleContextExt;
 use crate::prelude::generated::yarnspinnerparser::*;
 use crate::prelude::generated::yarnspinnerparservisitor::YarnSpinnerParserVisitorCompat;
 use crate::prelude::*;
+use crate::visitors::CodeGenerationVisitor;
 use antlr_rust::parser::ParserNodeType;
+use antlr_rust::rule_context::CustomRuleContext;
+use antlr_rust::token::Token;
+use antlr_rust::token_factory::TokenFactory;
 use antlr_rust::tree::{ParseTree, ParseTreeVisitorCompat, VisitChildren};
 use std::mem;
-use std::ops::{Deref, DerefMut};
 use yarnspinner_core::prelude::*;
+use yarnspinner_core::types::TypeFormat;
 
-/// A visitor that visits any valid constant value, and returns a [`InternalValue`].
-/// Currently only supports terminals, not expressions,
-/// even if those expressions would be constant.
+/// Evaluates a constant value or expression at compile time.
+/// The value is `None` if it is not constant or cannot be evaluated, in which case the returned diagnostics say why.
+/// Its type may still be known, e.g. `1 / 0` is a number.
+pub(crate) fn evaluate_constant<'input>(
+    ctx: &<YarnSpinnerParserContextType as ParserNodeType<'input>>::Type,
+    file: FileParseResult<'input>,
+) -> (ConstantValue, Vec<Diagnostic>) {
+    let mut visitor = ConstantValueVisitor::new(Vec::new(), file);
+    let value = visitor.visit(ctx);
+    (value, visitor.diagnostics)
+}
+
+/// A visitor that visits any valid constant value or expression, and returns a [`InternalValue`].
+/// Expressions are folded, so `(1 + 2) * 3` results in `9`.
+///
+/// ## Implementation notes
+///
+/// The original only supports terminals, not expressions, even if those expressions would be constant.
 #[derive(Clone)]
 pub(crate) struct ConstantValueVisitor<'input> {
     pub(crate) diagnostics: Vec<Diagnostic>,
@@ -55,6 +75,8 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for ConstantValueVisitor<'in
             let message = format!("Failed to parse {text} as a float",);
             self.diagnostics.push(
                 Diagnostic::from_message(message)
+                    .with_code(DiagnosticCode::InvalidNumber)
+                    .with_argument("number", &text)
                     .with_file_name(&self.file.name)
                     .with_parser_context(ctx, self.file.tokens()),
             );
@@ -76,10 +98,12 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for ConstantValueVisitor<'in
     fn visit_valueVar(&mut self, ctx: &ValueVarContext<'input>) -> Self::Return {
         let text = ctx.get_text();
         let message = format!(
-            "Variable declarations must be constant values, but `{text}` is another variable",
+            "Default values must be constant expressions, but `{text}` is another variable",
         );
         self.diagnostics.push(
             Diagnostic::from_message(message)
+                .with_code(DiagnosticCode::NonConstantDeclaration)
+                .with_argument("value", &text)
                 .with_file_name(&self.file.name)
                 .with_parser_context(ctx, self.file.tokens()),
         );
@@ -95,6 +119,8 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for ConstantValueVisitor<'in
         let message = "Null is not a permitted type in Yarn Spinner 2.0 and later";
         self.diagnostics.push(
             Diagnostic::from_message(message)
+                .with_code(DiagnosticCode::UnknownType)
+                .with_argument("type", "null")
                 .with_file_name(&self.file.name)
                 .with_parser_context(ctx, self.file.tokens()),
         );
@@ -104,14 +130,249 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for ConstantValueVisitor<'in
     fn visit_valueFunc(&mut self, ctx: &ValueFuncContext<'input>) -> Self::Return {
         let text = ctx.get_text();
         let message =
-            format!("Variable declarations must be constant values, but `{text}` is a function",);
+            format!("Default values must be constant expressions, but `{text}` is a function",);
         self.diagnostics.push(
             Diagnostic::from_message(message)
+                .with_code(DiagnosticCode::NonConstantDeclaration)
+                .with_argument("value", &text)
                 .with_file_name(&self.file.name)
                 .with_parser_context(ctx, self.file.tokens()),
         );
         ConstantValue::non_panicking_default()
     }
+
+    /// Known enum cases are looked up by the [`DeclarationVisitor`](crate::visitors::DeclarationVisitor) before folding,
+    /// so this is an unknown one, which the [`TypeCheckVisitor`](crate::visitors::TypeCheckVisitor) reports.
+    fn visit_valueTypeMemberReference(
+        &mut self,
+        _ctx: &ValueTypeMemberReferenceContext<'input>,
+    ) -> Self::Return {
+        ConstantValue::non_panicking_default()
+    }
+
+    fn visit_expParens(&mut self, ctx: &ExpParensContext<'input>) -> Self::Return {
+        self.visit(ctx.expression().unwrap().as_ref())
+    }
+
+    fn visit_expValue(&mut self, ctx: &ExpValueContext<'input>) -> Self::Return {
+        self.visit(ctx.value().unwrap().as_ref())
+    }
+
+    fn visit_expNegative(&mut self, ctx: &ExpNegativeContext<'input>) -> Self::Return {
+        let operand = self.visit(ctx.expression().unwrap().as_ref());
+        // The operator token is the same as for subtraction, so it cannot be looked up
+        self.fold(ctx, Operator::UnarySubtract, "-", &[operand])
+    }
+
+    fn visit_expNot(&mut self, ctx: &ExpNotContext<'input>) -> Self::Return {
+        let operand = self.visit(ctx.expression().unwrap().as_ref());
+        let op = ctx.op.as_ref().unwrap();
+        self.fold_token(ctx, op.token_type, op.get_text(), &[operand])
+    }
+
+    fn visit_expMultDivMod(&mut self, ctx: &ExpMultDivModContext<'input>) -> Self::Return {
+        let operands: Vec<_> = ctx
+            .expression_all()
+            .iter()
+            .map(|expression| self.visit(expression.as_ref()))
+            .collect();
+        let op = ctx.op.as_ref().unwrap();
+        self.fold_token(ctx, op.token_type, op.get_text(), &operands)
+    }
+
+    fn visit_expAddSub(&mut self, ctx: &ExpAddSubContext<'input>) -> Self::Return {
+        let operands: Vec<_> = ctx
+            .expression_all()
+            .iter()
+            .map(|expression| self.visit(expression.as_ref()))
+            .collect();
+        let op = ctx.op.as_ref().unwrap();
+        self.fold_token(ctx, op.token_type, op.get_text(), &operands)
+    }
+
+    fn visit_expComparison(&mut self, ctx: &ExpComparisonContext<'input>) -> Self::Return {
+        let operands: Vec<_> = ctx
+            .expression_all()
+            .iter()
+            .map(|expression| self.visit(expression.as_ref()))
+            .collect();
+        let op = ctx.op.as_ref().unwrap();
+        self.fold_token(ctx, op.token_type, op.get_text(), &operands)
+    }
+
+    fn visit_expEquality(&mut self, ctx: &ExpEqualityContext<'input>) -> Self::Return {
+        let operands: Vec<_> = ctx
+            .expression_all()
+            .iter()
+            .map(|expression| self.visit(expression.as_ref()))
+            .collect();
+        let op = ctx.op.as_ref().unwrap();
+        self.fold_token(ctx, op.token_type, op.get_text(), &operands)
+    }
+
+    fn visit_expAndOrXor(&mut self, ctx: &ExpAndOrXorContext<'input>) -> Self::Return {
+        let operands: Vec<_> = ctx
+            .expression_all()
+            .iter()
+            .map(|expression| self.visit(expression.as_ref()))
+            .collect();
+        let op = ctx.op.as_ref().unwrap();
+        self.fold_token(ctx, op.token_type, op.get_text(), &operands)
+    }
+}
+
+impl<'input> ConstantValueVisitor<'input> {
+    /// Applies the operator behind the given token to already folded operands.
+    fn fold_token<T>(
+        &mut self,
+        ctx: &T,
+        token_type: isize,
+        description: &str,
+        operands: &[ConstantValue],
+    ) -> ConstantValue
+    where
+        T: ParserRuleContextExt<'input>,
+    <<<<T as CustomRuleContext<'input>>::TF as TokenFactory<'input>>::Inner as Token>::Data as ToOwned>::Owned: Into<String>{
+        let operator = CodeGenerationVisitor::token_to_operator(token_type)
+            .expect("Expression operator is not an operator. This is a bug.");
+        self.fold(ctx, operator, description, operands)
+    }
+
+    /// Applies an operator to already folded operands.
+    /// If any operand could not be folded, a diagnostic was already emitted for it, so this silently returns only the type of the result, if known.
+    fn fold<T>(
+        &mut self,
+        ctx: &T,
+        operator: Operator,
+        description: &str,
+        operands: &[ConstantValue],
+    ) -> ConstantValue
+    where
+        T: ParserRuleContextExt<'input>,
+    <<<<T as CustomRuleContext<'input>>::TF as TokenFactory<'input>>::Inner as Token>::Data as ToOwned>::Owned: Into<String>{
+        let result_type = operands
+            .iter()
+            .map(|operand| operand.r#type.clone())
+            .collect::<Option<Vec<_>>>()
+            .and_then(|types| fold_result_type(operator, &types));
+        let Some(operands) = operands
+            .iter()
+            .map(|operand| operand.value.as_ref().map(|value| &value.raw_value))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return ConstantValue::unfolded(result_type);
+        };
+        match fold_operation(operator, &operands) {
+            Ok(value) => value.into(),
+            Err(FoldError::DivisionByZero) => {
+                self.push_diagnostic(
+                    ctx,
+                    DiagnosticCode::DivisionByZero,
+                    "Division by zero in constant expression".to_owned(),
+                );
+                ConstantValue::unfolded(result_type)
+            }
+            Err(FoldError::TypeMismatch) => {
+                let types = operands
+                    .iter()
+                    .map(|operand| InternalValue::from((*operand).clone()).r#type.format())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let message = format!(
+                    "Operator {description} cannot be applied to constant values of type {types}"
+                );
+                self.push_diagnostic(ctx, DiagnosticCode::TypeMismatch, message);
+                ConstantValue::non_panicking_default()
+            }
+        }
+    }
+
+    fn push_diagnostic<T>(&mut self, ctx: &T, code: DiagnosticCode, message: String)
+    where
+        T: ParserRuleContextExt<'input>,
+    <<<<T as CustomRuleContext<'input>>::TF as TokenFactory<'input>>::Inner as Token>::Data as ToOwned>::Owned: Into<String>{
+        self.diagnostics.push(
+            Diagnostic::from_message(message)
+                .with_code(code)
+                .with_file_name(&self.file.name)
+                .with_parser_context(ctx, self.file.tokens()),
+        );
+    }
+}
+
+/// Why a constant expression could not be folded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FoldError {
+    DivisionByZero,
+    TypeMismatch,
+}
+
+/// The type of the result of applying an operator to operands of the given types, matching [`fold_operation`].
+/// Returns `None` if the operator cannot be applied to them.
+fn fold_result_type(operator: Operator, operand_types: &[Type]) -> Option<Type> {
+    use Type::*;
+    let result_type = match (operator, operand_types) {
+        (Operator::UnarySubtract, [Number]) => Number,
+        (Operator::Not, [Boolean]) => Boolean,
+        (Operator::Add, [Number, Number]) => Number,
+        (Operator::Add, [String, String]) => String,
+        (
+            Operator::Subtract | Operator::Multiply | Operator::Divide | Operator::Modulo,
+            [Number, Number],
+        ) => Number,
+        (
+            Operator::LessThan
+            | Operator::LessThanOrEqualTo
+            | Operator::GreaterThan
+            | Operator::GreaterThanOrEqualTo,
+            [Number, Number],
+        ) => Boolean,
+        (Operator::And | Operator::Or | Operator::Xor, [Boolean, Boolean]) => Boolean,
+        (Operator::EqualTo | Operator::NotEqualTo, [a, b]) if a == b => Boolean,
+        _ => return None,
+    };
+    Some(result_type)
+}
+
+/// Applies an operator to constant operands the same way the standard library does at runtime,
+/// i.e. through the operators of [`YarnValue`].
+fn fold_operation(
+    operator: Operator,
+    operands: &[&YarnValue],
+) -> std::result::Result<InternalValue, FoldError> {
+    use YarnValue::*;
+    let operands: Vec<YarnValue> = operands.iter().map(|&operand| operand.clone()).collect();
+    let result = match (operator, operands.as_slice()) {
+        (Operator::UnarySubtract, [a @ Number(_)]) => -a.clone(),
+        (Operator::Not, [a @ Boolean(_)]) => !a.clone(),
+        (Operator::Add, [a @ Number(_), b @ Number(_)] | [a @ String(_), b @ String(_)]) => {
+            a.clone() + b.clone()
+        }
+        (Operator::Divide | Operator::Modulo, [Number(_), Number(b)]) if *b == 0.0 => {
+            return Err(FoldError::DivisionByZero)
+        }
+        (Operator::Subtract, [a @ Number(_), b @ Number(_)]) => a.clone() - b.clone(),
+        (Operator::Multiply, [a @ Number(_), b @ Number(_)]) => a.clone() * b.clone(),
+        (Operator::Divide, [a @ Number(_), b @ Number(_)]) => a.clone() / b.clone(),
+        (Operator::Modulo, [a @ Number(_), b @ Number(_)]) => a.clone() % b.clone(),
+        (Operator::LessThan, [a @ Number(_), b @ Number(_)]) => Ok((a < b).into()),
+        (Operator::LessThanOrEqualTo, [a @ Number(_), b @ Number(_)]) => Ok((a <= b).into()),
+        (Operator::GreaterThan, [a @ Number(_), b @ Number(_)]) => Ok((a > b).into()),
+        (Operator::GreaterThanOrEqualTo, [a @ Number(_), b @ Number(_)]) => Ok((a >= b).into()),
+        (Operator::And, [a @ Boolean(_), b @ Boolean(_)]) => a.clone() & b.clone(),
+        (Operator::Or, [a @ Boolean(_), b @ Boolean(_)]) => a.clone() | b.clone(),
+        (Operator::Xor, [a @ Boolean(_), b @ Boolean(_)]) => a.clone() ^ b.clone(),
+        (Operator::EqualTo, [a, b]) if mem::discriminant(a) == mem::discriminant(b) => {
+            Ok((a == b).into())
+        }
+        (Operator::NotEqualTo, [a, b]) if mem::discriminant(a) == mem::discriminant(b) => {
+            Ok((a != b).into())
+        }
+        _ => return Err(FoldError::TypeMismatch),
+    };
+    result
+        .map(InternalValue::from)
+        .map_err(|_| FoldError::TypeMismatch)
 }
 
 /// Needed because ANTLR needs visitors' return values to have a default.
@@ -130,25 +391,18 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for ConstantValueVisitor<'in
 /// But, judging by the original wording, this case should not happen anyways and should be treated as an internal error / a bug.
 /// Thus, we panic instead with a call to action to report the bug.
 #[derive(Debug, Clone)]
-pub(crate) struct ConstantValue(pub(crate) Option<InternalValue>);
-
-impl Deref for ConstantValue {
-    type Target = Option<InternalValue>;
-
-    fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
-
-impl DerefMut for ConstantValue {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
-    }
+pub(crate) struct ConstantValue {
+    pub(crate) value: Option<InternalValue>,
+    /// The type of the value, which may be known even if the value could not be folded.
+    pub(crate) r#type: Option<Type>,
 }
 
 impl From<InternalValue> for ConstantValue {
     fn from(value: InternalValue) -> Self {
-        Self(Some(value))
+        Self {
+            r#type: Some(value.r#type.clone()),
+            value: Some(value),
+        }
     }
 }
 
@@ -164,6 +418,83 @@ impl Default for ConstantValue {
 impl ConstantValue {
     /// Only use this for dummy assignments.
     fn non_panicking_default() -> Self {
-        Self(None)
+        Self::unfolded(None)
+    }
+
+    /// A value that could not be folded, but whose type may still be known.
+    fn unfolded(r#type: Option<Type>) -> Self {
+        Self {
+            value: None,
+            r#type,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use better_any::TidExt;
+    use std::rc::Rc;
+
+    #[test]
+    fn folds_nested_parentheses() {
+        let (value, diagnostics) = evaluate_set_expression("(1 + (2 * (3 - 1))) / -(5 % 3)");
+        assert!(diagnostics.is_empty());
+        assert_eq!(Some(InternalValue::from(-2.5)), value.value);
+    }
+
+    #[test]
+    fn folds_strings_and_booleans() {
+        let (value, diagnostics) = evaluate_set_expression("\"a\" + \"b\" == \"ab\" and !false");
+        assert!(diagnostics.is_empty());
+        assert_eq!(Some(InternalValue::from(true)), value.value);
+    }
+
+    #[test]
+    fn reports_division_by_zero() {
+        let (value, diagnostics) = evaluate_set_expression("1 + 2 / (1 - 1)");
+        assert_eq!(None, value.value);
+        assert_eq!(Some(Type::Number), value.r#type);
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(Some(DiagnosticCode::DivisionByZero), diagnostics[0].code);
+    }
+
+    #[test]
+    fn reports_non_constant_values() {
+        let (value, diagnostics) = evaluate_set_expression("$x * 2");
+        assert_eq!(None, value.value);
+        assert_eq!(None, value.r#type);
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(
+            "Default values must be constant expressions, but `$x` is another variable",
+            diagnostics[0].message
+        );
+    }
+
+    fn evaluate_set_expression(expression: &str) -> (ConstantValue, Vec<Diagnostic>) {
+        let file = File {
+            file_name: "test.yarn".to_owned(),
+            source: format!("title: Start\n---\n<<set $result = {expression}>>\n===\n"),
+        };
+        let chars: Vec<_> = file.source.chars().map(|c| c as u32).collect();
+        let mut diagnostics = Vec::new();
+        let parse_result = parse_syntax_tree(&file, &chars, &mut diagnostics).unwrap();
+        assert!(diagnostics.is_empty());
+        let tree: Rc<<YarnSpinnerParserContextType as ParserNodeType>::Type> =
+            parse_result.tree.clone();
+        let expression = find_first_expression(tree).unwrap();
+        evaluate_constant(expression.as_ref(), parse_result)
+    }
+
+    fn find_first_expression<'input>(
+        node: Rc<<YarnSpinnerParserContextType as ParserNodeType<'input>>::Type>,
+    ) -> Option<Rc<ExpressionContextAll<'input>>> {
+        node.get_children().find_map(|child| {
+            child
+                .clone()
+                .downcast_rc::<ExpressionContextAll>()
+                .ok()
+                .or_else(|| find_first_expression(child))
+        })
     }
 }
@@ -1,9 +1,11 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner.Compiler/DeclarationVisitor.cs>
 
+use crate::prelude::generated::yarnspinnerlexer;
 use crate::prelude::generated::yarnspinnerparser::*;
 use crate::prelude::generated::yarnspinnerparservisitor::YarnSpinnerParserVisitorCompat;
 use crate::prelude::*;
-use crate::visitors::constant_value_visitor::ConstantValueVisitor;
+use crate::visitors::constant_value_visitor::{evaluate_constant, ConstantValue};
+use crate::visitors::{is_integer_literal, DefaultValue};
 use antlr_rust::token::Token;
 use antlr_rust::tree::{ParseTree, ParseTreeVisitorCompat};
 use regex::Regex;
@@ -71,6 +73,31 @@ impl<'input> DeclarationVisitor<'input> {
     }
 }
 
+impl<'input> DeclarationVisitor<'input> {
+    /// Returns the value of the enum case with the given name, e.g. `Food.Apple`.
+    fn find_enum_case(&self, name: &str) -> Option<InternalValue> {
+        let declaration = self
+            .existing_declarations
+            .iter()
+            .find(|decl| decl.name == name && is_enum_case(decl))?;
+        Some(InternalValue {
+            r#type: declaration.r#type.clone(),
+            raw_value: declaration.default_value.clone()?,
+        })
+    }
+
+    /// Returns the enum type with the given name, e.g. `Food`.
+    fn find_enum_type(&self, name: &str) -> Option<Type> {
+        self.existing_declarations
+            .iter()
+            .filter(|decl| is_enum_case(decl))
+            .find_map(|decl| match &decl.r#type {
+                Type::Enum(enum_type) if enum_type.name == name => Some(decl.r#type.clone()),
+                _ => None,
+            })
+    }
+}
+
 impl<'input> ParseTreeVisitorCompat<'input> for DeclarationVisitor<'input> {
     type Node = YarnSpinnerParserContextType;
     type Return = ();
@@ -93,14 +120,24 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for DeclarationVisitor<'inpu
                 continue;
             }
 
-            let header_value = header.header_value.as_ref().unwrap();
-            let current_node_name = header_value.get_text();
+            let current_node_name = header
+                .header_value
+                .as_ref()
+                .map(|header_value| header_value.get_text())
+                .unwrap_or_default();
             self.current_node_name = Some(current_node_name.to_owned());
-            if self.regex.is_match(current_node_name) {
+            // Dots separate namespaces, e.g. `Chapter1.Intro`, none of which may be empty
+            let has_empty_namespace = current_node_name.contains(Node::NAMESPACE_SEPARATOR)
+                && current_node_name
+                    .split(Node::NAMESPACE_SEPARATOR)
+                    .any(str::is_empty);
+            if self.regex.is_match(current_node_name) || has_empty_namespace {
                 let message =
                     format!("The node '{current_node_name}' contains illegal characters.");
                 self.diagnostics.push(
                     Diagnostic::from_message(message)
+                        .with_code(DiagnosticCode::InvalidNodeName)
+                        .with_argument("node", current_node_name)
                         .with_file_name(self.file.name.clone())
                         .with_parser_context(header.as_ref(), self.file.tokens()),
                 );
@@ -111,7 +148,12 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for DeclarationVisitor<'inpu
         }
     }
 
+    /// Smart variables are declared by the [`TypeCheckVisitor`](crate::visitors::TypeCheckVisitor) instead,
+    /// since their type is that of their expression. See [`is_smart_variable_declaration`].
     fn visit_declare_statement(&mut self, ctx: &Declare_statementContext<'input>) -> Self::Return {
+        if is_smart_variable_declaration(ctx) {
+            return;
+        }
         // Get the name of the variable we're declaring
         let variable_context = ctx.variable().unwrap();
         let variable_name = variable_context.get_text();
@@ -133,37 +175,50 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for DeclarationVisitor<'inpu
             );
             self.diagnostics.push(
                 Diagnostic::from_message(msg)
+                    .with_code(DiagnosticCode::DuplicateDeclaration)
+                    .with_argument("variable", &existing_explicit_declaration.name)
+                    .with_argument("file", &existing_explicit_declaration.source_file_name)
                     .with_file_name(&self.file.name)
                     .with_parser_context(ctx, self.file.tokens()),
             );
             return;
         }
 
-        // Figure out the value and its type
-        let mut constant_value_visitor =
-            ConstantValueVisitor::new(self.diagnostics.clone(), self.file.clone());
-        let value_context = ctx.value().unwrap();
-        let value = constant_value_visitor.visit(value_context.as_ref());
-        self.diagnostics
-            .extend_from_slice(&constant_value_visitor.diagnostics);
+        // Figure out the value and its type by folding the expression, e.g. `5 * 2`
+        let value_context = ctx.expression().unwrap();
+        let (mut value, is_folded) = match self.find_enum_case(&value_context.get_text()) {
+            Some(enum_case) => (Some(enum_case), true),
+            None => {
+                let (value, diagnostics) =
+                    evaluate_constant(value_context.as_ref(), self.file.clone());
+                self.diagnostics.extend(diagnostics);
+                let is_folded = value.value.is_some();
+                (value_or_placeholder(value), is_folded)
+            }
+        };
 
         // Did the source code name an explicit type?
         if let Some(declaration_type) = ctx.declaration_type.as_ref() {
-            let explicit_type = match keyword_to_type(declaration_type.get_text()) {
+            let declaration_type = declaration_type.get_text();
+            let explicit_type = match keyword_to_type(declaration_type) {
                 Some(builtin_type) => builtin_type,
 
                 // The type name provided didn't map to a built-in
                 // type. Look for the type in our type collection.
                 None => match Type::EXPLICITLY_CONSTRUCTABLE
                     .iter()
-                    .find(|t| t.to_string() == declaration_type.get_text())
+                    .find(|t| t.to_string() == declaration_type)
+                    .cloned()
+                    .or_else(|| self.find_enum_type(declaration_type))
                 {
-                    Some(explicit_type) => explicit_type.clone(),
+                    Some(explicit_type) => explicit_type,
                     None => {
                         // We didn't find a type by this name.
-                        let msg = format!("Unknown type {}", declaration_type.get_text());
+                        let msg = format!("Unknown type {}", declaration_type);
                         self.diagnostics.push(
                             Diagnostic::from_message(msg)
+                                .with_code(DiagnosticCode::UnknownType)
+                                .with_argument("type", declaration_type)
                                 .with_file_name(&self.file.name)
                                 .with_parser_context(ctx, self.file.tokens()),
                         );
@@ -172,6 +227,32 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for DeclarationVisitor<'inpu
                 },
             };
 
+            // A value that could not be folded into one of unknown type still declares the variable, see `value_or_placeholder`
+            if value.is_none() {
+                value = explicit_type
+                    .default_value()
+                    .map(|raw_value| InternalValue {
+                        raw_value,
+                        r#type: explicit_type.clone(),
+                    });
+            }
+
+            if let Some(value) = value.as_mut() {
+                // Whole numbers are integers if the variable is declared as one
+                if explicit_type == Type::Integer
+                    && is_folded
+                    && self.is_integer_expression(&value_context, value)
+                {
+                    value.r#type = Type::Integer;
+                }
+                // Raw values of enum cases are cases themselves if the variable is declared as the enum
+                if let Type::Enum(enum_type) = &explicit_type {
+                    if enum_type.case_for_raw_value(&value.raw_value).is_some() {
+                        value.r#type = explicit_type.clone();
+                    }
+                }
+            }
+
             // Check that the type we've found is compatible with the
             // type of the value that was provided - if it doesn't,
             // that's a type error
@@ -179,12 +260,15 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for DeclarationVisitor<'inpu
                 if !value.r#type.is_sub_type_of(&explicit_type) {
                     let msg = format!(
                         "Type {} does not match value {} ({})",
-                        declaration_type.get_text(),
+                        declaration_type,
                         value_context.get_text(),
                         value.r#type.format()
                     );
                     self.diagnostics.push(
                         Diagnostic::from_message(msg)
+                            .with_code(DiagnosticCode::TypeMismatch)
+                            .with_argument("expected", declaration_type)
+                            .with_argument("actual", value.r#type.format())
                             .with_file_name(&self.file.name)
                             .with_parser_context(ctx, self.file.tokens()),
                     );
@@ -207,11 +291,43 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for DeclarationVisitor<'inpu
     }
 }
 
+impl<'input> DeclarationVisitor<'input> {
+    /// Whether the folded value of an expression is a whole number and all of its number literals are written without a fraction.
+    /// Folding `7 / 2` results in a fraction, even though its operands are integer literals.
+    fn is_integer_expression(
+        &self,
+        expression: &ExpressionContextAll<'input>,
+        value: &InternalValue,
+    ) -> bool {
+        f32::try_from(&value.raw_value).is_ok_and(|number| number.fract() == 0.0)
+            && self.file.tokens().get_tokens()[expression.start().get_token_index() as usize
+                ..=expression.stop().get_token_index() as usize]
+                .iter()
+                .filter(|token| token.get_token_type() == yarnspinnerlexer::NUMBER)
+                .all(|token| is_integer_literal(token.get_text()))
+    }
+}
+
+/// Returns the folded value or, if it could not be folded but its type is known, e.g. for `1 / 0`, the default value of that type.
+/// The diagnostics of the failed fold already prevent a program from being emitted,
+/// but the variable is still declared so that its uses don't report that its type can't be figured out.
+fn value_or_placeholder(value: ConstantValue) -> Option<InternalValue> {
+    let ConstantValue { value, r#type } = value;
+    value.or_else(|| {
+        let r#type = r#type?;
+        Some(InternalValue {
+            raw_value: r#type.default_value()?,
+            r#type,
+        })
+    })
+}
+
 fn keyword_to_type(keyword: &str) -> Option<Type> {
     match keyword {
         "string" => Some(Type::String),
         "number" => Some(Type::Number),
         "bool" => Some(Type::Boolean),
+        "integer" => Some(Type::Integer),
         _ => None,
     }
 }
@@ -238,6 +354,14 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            command_declarations: vec![],
+            line_id_namespace: None,
+            line_id_namespace_violation_severity: Default::default(),
+            wrap_config: None,
+            strict_contracts: false,
+            deterministic_output: false,
+            excluded_node_tags: vec![],
+            require_explicit_line_ids: false,
         }
         .compile()
         .unwrap();
@@ -328,6 +452,14 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            command_declarations: vec![],
+            line_id_namespace: None,
+            line_id_namespace_violation_severity: Default::default(),
+            wrap_config: None,
+            strict_contracts: false,
+            deterministic_output: false,
+            excluded_node_tags: vec![],
+            require_explicit_line_ids: false,
         }
         .compile();
 
@@ -336,6 +468,9 @@ mod tests {
         assert_eq!(
             diagnostics[0],
             Diagnostic::from_message("Type string does not match value 1 (Number)".to_string())
+                .with_code(DiagnosticCode::TypeMismatch)
+                .with_argument("expected", "string")
+                .with_argument("actual", "Number")
                 .with_file_name("test.yarn".to_string())
                 .with_context(file.source.clone())
                 .with_range(
@@ -351,6 +486,8 @@ mod tests {
         assert_eq!(
             diagnostics[1],
             Diagnostic::from_message("Can't figure out the type of variable $foo given its context. Specify its type with a <<declare>> statement.".to_string())
+                .with_code(DiagnosticCode::UndeterminedType)
+                .with_argument("variable", "$foo")
                 .with_file_name("test.yarn".to_string())
                 .with_context(file.source)
                 .with_range(
@@ -0,0 +1,189 @@
+//! Not part of the original implementation at the version the rest of this crate is adapted from, which predates enums.
+
+use crate::prelude::generated::yarnspinnerparser::*;
+use crate::prelude::generated::yarnspinnerparservisitor::YarnSpinnerParserVisitorCompat;
+use crate::prelude::*;
+use antlr_rust::token::Token;
+use antlr_rust::tree::{ParseTree, ParseTreeVisitorCompat};
+use yarnspinner_core::prelude::*;
+use yarnspinner_core::types::*;
+
+/// A visitor that extracts the enums declared in a parse tree, e.g.
+/// ```yarn
+/// <<enum Food>>
+///     <<case Apple>>
+///     <<case Banana = 5>>
+/// <<endenum>>
+/// ```
+/// After visiting an entire parse tree for a file, [`EnumDeclarationVisitor::new_declarations`]
+/// contains a declaration for every case of the enums found, e.g. `Food.Apple`. See [`is_enum_case`].
+pub(crate) struct EnumDeclarationVisitor<'input> {
+    pub(crate) new_declarations: Vec<Declaration>,
+
+    pub(crate) diagnostics: Vec<Diagnostic>,
+
+    /// The collection of declarations we know about before starting our work, including the enum cases of other files.
+    existing_declarations: Vec<Declaration>,
+
+    file: FileParseResult<'input>,
+
+    /// The name of the node that we're currently visiting.
+    current_node_name: Option<String>,
+
+    _dummy: (),
+}
+
+impl<'input> EnumDeclarationVisitor<'input> {
+    pub(crate) fn new(
+        existing_declarations: Vec<Declaration>,
+        file: FileParseResult<'input>,
+    ) -> Self {
+        Self {
+            file,
+            existing_declarations,
+            new_declarations: Default::default(),
+            diagnostics: Default::default(),
+            current_node_name: None,
+            _dummy: Default::default(),
+        }
+    }
+
+    /// Adds the case to the enum type and returns its declaration, whose type is only completed once all cases are known.
+    fn declare_case(
+        &mut self,
+        enum_type: &mut EnumType,
+        ctx: &Enum_case_statementContext<'input>,
+    ) -> Option<Declaration> {
+        let name = ctx.name.as_ref()?.get_text().to_owned();
+        let enum_name = enum_type.name.clone();
+        let raw_value = match ctx.raw_value.as_deref() {
+            Some(raw_value) => parse_raw_value(raw_value).ok_or_else(|| raw_value.get_text()),
+            // Cases without an explicit raw value are numbered
+            None => Ok(YarnValue::from(enum_type.cases.len())),
+        };
+        let message = match &raw_value {
+            _ if enum_type.case(&name).is_some() => {
+                Some(format!("Enum {enum_name} already has a case named {name}"))
+            }
+            Err(raw_value) => Some(format!(
+                "The raw value {raw_value} of case {enum_name}.{name} must be a number or a string"
+            )),
+            Ok(raw_value) => {
+                let existing_case = enum_type.case_for_raw_value(raw_value);
+                let first_case = enum_type.cases.first();
+                if let Some(existing_case) = existing_case {
+                    Some(format!("Cases {enum_name}.{} and {enum_name}.{name} have the same raw value {raw_value}", existing_case.name))
+                } else if first_case
+                    .is_some_and(|case| case.raw_value.r#type() != raw_value.r#type())
+                {
+                    Some(format!("The raw value {raw_value} of case {enum_name}.{name} has a different type than those of the other cases"))
+                } else {
+                    None
+                }
+            }
+        };
+        if let Some(message) = message {
+            self.diagnostics.push(
+                Diagnostic::from_message(message)
+                    .with_code(DiagnosticCode::InvalidEnum)
+                    .with_argument("name", format!("{enum_name}.{name}"))
+                    .with_file_name(&self.file.name)
+                    .with_parser_context(ctx, self.file.tokens()),
+            );
+            return None;
+        }
+        let raw_value = raw_value.unwrap(); // Guaranteed to be Ok
+        let declaration = Declaration::new(format!("{enum_name}.{name}"), Type::Any)
+            .with_default_value(raw_value.clone())
+            .with_description(format!("Case of enum {enum_name}"))
+            .with_source_file_name(self.file.name.clone())
+            .with_source_node_name_optional(self.current_node_name.clone())
+            .with_range(ctx.range());
+        enum_type.add_case(name, raw_value);
+        Some(declaration)
+    }
+}
+
+impl<'input> ParseTreeVisitorCompat<'input> for EnumDeclarationVisitor<'input> {
+    type Node = YarnSpinnerParserContextType;
+    type Return = ();
+
+    fn temp_result(&mut self) -> &mut Self::Return {
+        &mut self._dummy
+    }
+}
+
+impl<'input> YarnSpinnerParserVisitorCompat<'input> for EnumDeclarationVisitor<'input> {
+    fn visit_node(&mut self, ctx: &NodeContext<'input>) -> Self::Return {
+        self.current_node_name = ctx
+            .header_all()
+            .iter()
+            .find(|header| header.header_key.as_ref().unwrap().get_text() == "title")
+            .and_then(|header| header.header_value.as_ref())
+            .map(|value| value.get_text().to_owned());
+        if let Some(body) = ctx.body() {
+            self.visit(body.as_ref());
+        }
+    }
+
+    fn visit_enum_statement(&mut self, ctx: &Enum_statementContext<'input>) -> Self::Return {
+        let Some(name) = ctx.name.as_ref() else {
+            return;
+        };
+        let mut enum_type = EnumType::new(name.get_text());
+        let cases: Vec<_> = ctx
+            .enum_case_statement_all()
+            .iter()
+            .filter_map(|case| self.declare_case(&mut enum_type, case))
+            .collect();
+        if enum_type.cases.is_empty() {
+            // Every case was invalid, which was already reported
+            return;
+        }
+
+        let existing_declaration = self
+            .existing_declarations
+            .iter()
+            .chain(self.new_declarations.iter())
+            .filter(|declaration| is_enum_case(declaration))
+            .find(|declaration| declaration.r#type == Type::Enum(enum_type.clone()));
+        if let Some(existing_declaration) = existing_declaration {
+            let line = existing_declaration
+                .source_file_line()
+                .map(|l| format!(", line: {l}"))
+                .unwrap_or_default();
+            let msg = format!(
+                "Enum {} has already been declared in {}{line}",
+                enum_type.name, existing_declaration.source_file_name,
+            );
+            let diagnostic = Diagnostic::from_message(msg)
+                .with_code(DiagnosticCode::DuplicateDeclaration)
+                .with_argument("variable", &enum_type.name)
+                .with_argument("file", &existing_declaration.source_file_name)
+                .with_file_name(&self.file.name)
+                .with_parser_context(ctx, self.file.tokens());
+            self.diagnostics.push(diagnostic);
+            return;
+        }
+        let cases = cases
+            .into_iter()
+            .map(|declaration| declaration.with_type(enum_type.clone()));
+        self.new_declarations.extend(cases);
+    }
+}
+
+/// Only numbers and strings are allowed as raw values, since they need to be known at compile time.
+fn parse_raw_value(ctx: &ValueContextAll) -> Option<YarnValue> {
+    match ctx {
+        ValueContextAll::ValueNumberContext(ctx) => ctx
+            .get_text()
+            .parse::<f32>()
+            .ok()
+            .filter(|number| number.is_finite())
+            .map(YarnValue::from),
+        ValueContextAll::ValueStringContext(ctx) => {
+            Some(ctx.STRING().unwrap().get_text().trim_matches('"').into())
+        }
+        _ => None,
+    }
+}
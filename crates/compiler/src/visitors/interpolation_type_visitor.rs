@@ -0,0 +1,108 @@
+use crate::prelude::generated::yarnspinnerlexer;
+use crate::prelude::generated::yarnspinnerparser::*;
+use crate::prelude::generated::yarnspinnerparservisitor::YarnSpinnerParserVisitorCompat;
+use crate::prelude::*;
+use crate::visitors::KnownTypes;
+use antlr_rust::parser_rule_context::ParserRuleContext;
+use antlr_rust::token::Token;
+use antlr_rust::tree::{ParseTree, ParseTreeVisitorCompat};
+use yarnspinner_core::prelude::*;
+
+/// Records the types of the expressions interpolated into lines, e.g. `{$gold}`, in their [`StringInfo`],
+/// so that they can be formatted according to their type.
+///
+/// Must run after the [`TypeCheckVisitor`](crate::visitors::TypeCheckVisitor) determined the types of all expressions.
+/// Since calling a function that was never declared is only caught at runtime,
+/// this also warns about such calls inside of interpolations.
+pub(crate) struct InterpolationTypeVisitor<'a, 'input: 'a> {
+    pub(crate) diagnostics: Vec<Diagnostic>,
+    known_types: &'a KnownTypes,
+    declarations: &'a [Declaration],
+    string_table: &'a mut StringTableManager,
+    file: FileParseResult<'input>,
+    is_in_interpolation: bool,
+    _dummy: (),
+}
+
+impl<'a, 'input: 'a> InterpolationTypeVisitor<'a, 'input> {
+    pub(crate) fn new(
+        known_types: &'a KnownTypes,
+        declarations: &'a [Declaration],
+        string_table: &'a mut StringTableManager,
+        file: FileParseResult<'input>,
+    ) -> Self {
+        Self {
+            diagnostics: Default::default(),
+            known_types,
+            declarations,
+            string_table,
+            file,
+            is_in_interpolation: Default::default(),
+            _dummy: Default::default(),
+        }
+    }
+}
+
+impl<'a, 'input: 'a> ParseTreeVisitorCompat<'input> for InterpolationTypeVisitor<'a, 'input> {
+    type Node = YarnSpinnerParserContextType;
+    type Return = ();
+
+    fn temp_result(&mut self) -> &mut Self::Return {
+        &mut self._dummy
+    }
+}
+
+impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input>
+    for InterpolationTypeVisitor<'a, 'input>
+{
+    fn visit_line_statement(&mut self, ctx: &Line_statementContext<'input>) -> Self::Return {
+        let formatted_text = ctx.line_formatted_text().unwrap();
+        let expressions = formatted_text.expression_all();
+        let line_id = get_line_id_tag(&ctx.hashtag_all())
+            .map(|tag| LineId::from(tag.text.as_ref().unwrap().get_text().to_owned()));
+        // Lines with duplicate IDs were not added to the string table
+        if let Some(string_info) = line_id.and_then(|id| self.string_table.get_mut(&id)) {
+            string_info.expression_types = expressions
+                .iter()
+                .map(|expression| {
+                    self.known_types
+                        .get(expression.as_ref())
+                        .cloned()
+                        .unwrap_or_default()
+                })
+                .collect();
+        }
+
+        self.is_in_interpolation = true;
+        for expression in expressions {
+            self.visit(expression.as_ref());
+        }
+        self.is_in_interpolation = false;
+    }
+
+    fn visit_function_call(&mut self, ctx: &Function_callContext<'input>) -> Self::Return {
+        if self.is_in_interpolation {
+            let function_name = ctx
+                .get_token(yarnspinnerlexer::FUNC_ID, 0)
+                .unwrap()
+                .get_text();
+            let is_declared = self
+                .declarations
+                .iter()
+                .any(|declaration| declaration.name == function_name && !declaration.is_implicit);
+            if !is_declared {
+                self.diagnostics.push(
+                    Diagnostic::from_message(format!(
+                        "Function \"{function_name}\" is not declared, so interpolating it will fail at runtime unless it is registered"
+                    ))
+                    .with_code(DiagnosticCode::UndeclaredFunction)
+                    .with_argument("function", function_name)
+                    .with_file_name(self.file.name.clone())
+                    .with_parser_context(ctx, self.file.tokens())
+                    .with_severity(DiagnosticSeverity::Warning),
+                );
+            }
+        }
+        self.visit_children(ctx);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::parser::generated::yarnspinnerparser::*;
+use crate::prelude::generated::yarnspinnerparservisitor::YarnSpinnerParserVisitorCompat;
+use crate::prelude::*;
+use antlr_rust::token::Token;
+use antlr_rust::tree::ParseTreeVisitorCompat;
+use std::collections::HashSet;
+
+/// Finds all `<<jump NodeName>>` statements whose destination is not a node in the compilation.
+/// Destinations may be relative to the namespace of the node they are in, see [`Node::candidate_names`].
+///
+/// Also warns about jumps from a node in the program to one of the nodes left out of it, see [`Compiler::with_excluded_node_tags`].
+///
+/// Jumps to an expression are skipped, since their destination is only known at runtime.
+pub(crate) struct JumpTargetVisitor<'a, 'input: 'a> {
+    pub(crate) diagnostics: Vec<Diagnostic>,
+    node_names: &'a HashSet<String>,
+    excluded_nodes: Option<&'a HashSet<String>>,
+    file: FileParseResult<'input>,
+    current_node_name: Option<String>,
+    _dummy: (),
+}
+
+impl<'a, 'input: 'a> JumpTargetVisitor<'a, 'input> {
+    pub(crate) fn new(node_names: &'a HashSet<String>, file: FileParseResult<'input>) -> Self {
+        Self {
+            diagnostics: Default::default(),
+            node_names,
+            excluded_nodes: None,
+            file,
+            current_node_name: Default::default(),
+            _dummy: Default::default(),
+        }
+    }
+
+    pub(crate) fn with_excluded_nodes(mut self, excluded_nodes: &'a HashSet<String>) -> Self {
+        self.excluded_nodes = Some(excluded_nodes);
+        self
+    }
+
+    fn is_excluded(&self, node_name: &str) -> bool {
+        self.excluded_nodes
+            .is_some_and(|excluded_nodes| excluded_nodes.contains(node_name))
+    }
+}
+
+impl<'a, 'input: 'a> ParseTreeVisitorCompat<'input> for JumpTargetVisitor<'a, 'input> {
+    type Node = YarnSpinnerParserContextType;
+    type Return = ();
+
+    fn temp_result(&mut self) -> &mut Self::Return {
+        &mut self._dummy
+    }
+}
+
+impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for JumpTargetVisitor<'a, 'input> {
+    fn visit_node(&mut self, ctx: &NodeContext<'input>) -> Self::Return {
+        self.current_node_name = ctx
+            .header_all()
+            .iter()
+            .find(|header| header.header_key.as_ref().unwrap().get_text() == "title")
+            .and_then(|header| header.header_value.as_ref())
+            .map(|value| value.get_text().to_owned());
+        if let Some(body) = ctx.body() {
+            self.visit(body.as_ref());
+        }
+    }
+
+    fn visit_jumpToNodeName(&mut self, ctx: &JumpToNodeNameContext<'input>) -> Self::Return {
+        let destination = ctx.destination.as_ref().unwrap().get_text();
+        let resolved = resolve_node_name(
+            self.node_names,
+            destination,
+            self.current_node_name.as_deref(),
+        );
+        if self.node_names.contains(&resolved) {
+            let is_excluded_jump = self.is_excluded(&resolved)
+                && !self
+                    .current_node_name
+                    .as_deref()
+                    .is_some_and(|name| self.is_excluded(name));
+            if is_excluded_jump {
+                self.diagnostics.push(
+                    Diagnostic::from_message(format!(
+                        "Jump target '{destination}' is excluded from the program because of its tags"
+                    ))
+                    .with_code(DiagnosticCode::JumpToExcludedNode)
+                    .with_argument("node", destination)
+                    .with_file_name(self.file.name.clone())
+                    .with_parser_context(ctx, self.file.tokens())
+                    .with_severity(DiagnosticSeverity::Warning),
+                );
+            }
+            return;
+        }
+        self.diagnostics.push(
+            Diagnostic::from_message(format!("Jump target '{destination}' does not exist"))
+                .with_code(DiagnosticCode::MissingJumpTarget)
+                .with_argument("node", destination)
+                .with_file_name(self.file.name.clone())
+                .with_parser_context(ctx, self.file.tokens())
+                .with_severity(DiagnosticSeverity::Warning),
+        );
+    }
+
+    fn visit_jumpToExpression(&mut self, _ctx: &JumpToExpressionContext<'input>) -> Self::Return {
+        // The destination can only be known at runtime
+    }
+}
@@ -0,0 +1,157 @@
+use crate::parser::generated::yarnspinnerparser::*;
+use crate::prelude::generated::yarnspinnerparservisitor::YarnSpinnerParserVisitorCompat;
+use crate::prelude::*;
+use antlr_rust::token::Token;
+use antlr_rust::tree::{ParseTree, ParseTreeVisitorCompat};
+use std::collections::HashSet;
+
+/// Collects the nodes of a file and the jumps, detours and options that lead from them to other nodes into a [`NodeGraph`].
+pub(crate) struct NodeGraphVisitor<'a, 'input: 'a> {
+    pub(crate) node_graph: NodeGraph,
+    /// The titles of all nodes in the compilation, against which the targets of edges are resolved.
+    node_names: &'a HashSet<String>,
+    file: FileParseResult<'input>,
+    current_node_name: Option<String>,
+    /// The number of `<<if>>` statements and conditional options the visitor is currently inside of.
+    condition_depth: usize,
+    /// The number of options the visitor is currently inside of.
+    option_depth: usize,
+    _dummy: (),
+}
+
+impl<'a, 'input: 'a> NodeGraphVisitor<'a, 'input> {
+    pub(crate) fn new(node_names: &'a HashSet<String>, file: FileParseResult<'input>) -> Self {
+        Self {
+            node_graph: Default::default(),
+            node_names,
+            file,
+            current_node_name: Default::default(),
+            condition_depth: Default::default(),
+            option_depth: Default::default(),
+            _dummy: Default::default(),
+        }
+    }
+
+    fn resolve_node_name(&self, reference: &str) -> String {
+        resolve_node_name(
+            self.node_names,
+            reference,
+            self.current_node_name.as_deref(),
+        )
+    }
+
+    fn add_edge(
+        &mut self,
+        target: NodeGraphTarget,
+        kind: NodeGraphEdgeKind,
+        range: std::ops::Range<Position>,
+    ) {
+        let Some(source) = self.current_node_name.clone() else {
+            return;
+        };
+        let kind = if kind == NodeGraphEdgeKind::Jump && self.option_depth > 0 {
+            NodeGraphEdgeKind::Option
+        } else {
+            kind
+        };
+        self.node_graph.edges.push(NodeGraphEdge {
+            source,
+            target,
+            kind,
+            is_conditional: self.condition_depth > 0,
+            file_name: self.file.name.clone(),
+            range,
+        });
+    }
+}
+
+impl<'a, 'input: 'a> ParseTreeVisitorCompat<'input> for NodeGraphVisitor<'a, 'input> {
+    type Node = YarnSpinnerParserContextType;
+    type Return = ();
+
+    fn temp_result(&mut self) -> &mut Self::Return {
+        &mut self._dummy
+    }
+}
+
+impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for NodeGraphVisitor<'a, 'input> {
+    fn visit_node(&mut self, ctx: &NodeContext<'input>) -> Self::Return {
+        self.current_node_name = ctx
+            .header_all()
+            .iter()
+            .find(|header| header.header_key.as_ref().unwrap().get_text() == "title")
+            .and_then(|header| header.header_value.as_ref())
+            .map(|value| value.get_text().to_owned());
+        if let Some(name) = self.current_node_name.clone() {
+            self.node_graph.nodes.push(NodeGraphNode {
+                name,
+                file_name: self.file.name.clone(),
+                range: ctx.range(),
+            });
+        }
+        if let Some(body) = ctx.body() {
+            self.visit(body.as_ref());
+        }
+    }
+
+    fn visit_if_statement(&mut self, ctx: &If_statementContext<'input>) -> Self::Return {
+        self.condition_depth += 1;
+        self.visit_children(ctx);
+        self.condition_depth -= 1;
+    }
+
+    fn visit_shortcut_option(&mut self, ctx: &Shortcut_optionContext<'input>) -> Self::Return {
+        let is_conditional = ctx
+            .line_statement()
+            .is_some_and(|line| line.line_condition().is_some());
+        self.option_depth += 1;
+        if is_conditional {
+            self.condition_depth += 1;
+        }
+        self.visit_children(ctx);
+        if is_conditional {
+            self.condition_depth -= 1;
+        }
+        self.option_depth -= 1;
+    }
+
+    fn visit_jumpToNodeName(&mut self, ctx: &JumpToNodeNameContext<'input>) -> Self::Return {
+        let destination = ctx.destination.as_ref().unwrap().get_text();
+        self.add_edge(
+            NodeGraphTarget::Node(self.resolve_node_name(destination)),
+            NodeGraphEdgeKind::Jump,
+            ctx.range(),
+        );
+    }
+
+    fn visit_jumpToExpression(&mut self, ctx: &JumpToExpressionContext<'input>) -> Self::Return {
+        self.add_edge(
+            NodeGraphTarget::Dynamic,
+            NodeGraphEdgeKind::Jump,
+            ctx.range(),
+        );
+    }
+
+    fn visit_command_statement(&mut self, ctx: &Command_statementContext<'input>) -> Self::Return {
+        let Some(formatted_text) = ctx.command_formatted_text() else {
+            return;
+        };
+        // Detours with interpolated destinations are run as regular commands, just like in the code generation
+        if !formatted_text.expression_all().is_empty() {
+            return;
+        }
+        let text = formatted_text.get_text();
+        let Some(destination) = text
+            .strip_prefix("detour ")
+            .map(str::trim)
+            .filter(|destination| !destination.is_empty())
+        else {
+            return;
+        };
+        self.add_edge(
+            NodeGraphTarget::Node(self.resolve_node_name(destination)),
+            NodeGraphEdgeKind::Detour,
+            ctx.range(),
+        );
+    }
+}
@@ -1,14 +1,19 @@
 use crate::parser::generated::yarnspinnerparser::{self, *};
 use crate::prelude::generated::yarnspinnerparservisitor::YarnSpinnerParserVisitorCompat;
+use crate::prelude::{get_once_index, is_once_option};
 use antlr_rust::parser_rule_context::ParserRuleContext;
 use antlr_rust::token::Token;
 use antlr_rust::tree::{ParseTree, ParseTreeVisitorCompat};
 use std::collections::HashSet;
+use yarnspinner_core::prelude::Library;
 
 #[derive(Clone, Default)]
 pub(crate) struct NodeTrackingVisitor {
     pub(crate) tracking_nodes: HashSet<String>,
     pub(crate) ignoring_nodes: HashSet<String>,
+    /// The generated variables of all `<<once>>` blocks and options.
+    pub(crate) once_variables: HashSet<String>,
+    current_node: Option<String>,
     _dummy: Option<String>,
 }
 
@@ -47,6 +52,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for NodeTrackingVisitor {
                 _ => {}
             }
         }
+        self.current_node.clone_from(&title);
         if let Some(title) = title {
             if let Some(tracking) = tracking {
                 match tracking.as_str() {
@@ -66,6 +72,31 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for NodeTrackingVisitor {
         None
     }
 
+    fn visit_once_statement(&mut self, ctx: &Once_statementContext<'input>) -> Self::Return {
+        if let Some(node_name) = self.current_node.as_ref() {
+            self.once_variables
+                .insert(Library::generate_unique_once_variable(
+                    node_name,
+                    get_once_index(ctx),
+                ));
+        }
+        self.visit_children(ctx)
+    }
+
+    fn visit_shortcut_option(&mut self, ctx: &Shortcut_optionContext<'input>) -> Self::Return {
+        let is_once = ctx
+            .line_statement()
+            .is_some_and(|line_statement| is_once_option(&line_statement));
+        if let Some(node_name) = self.current_node.as_ref().filter(|_| is_once) {
+            self.once_variables
+                .insert(Library::generate_unique_once_variable(
+                    node_name,
+                    get_once_index(ctx),
+                ));
+        }
+        self.visit_children(ctx)
+    }
+
     fn visit_valueString(&mut self, ctx: &ValueStringContext<'input>) -> Self::Return {
         ctx.get_token(yarnspinnerparser::STRING, 0)
             .unwrap()
@@ -17,6 +17,9 @@ pub(crate) struct StringTableGeneratorVisitor<'input> {
     current_node_name: String,
     pub(crate) string_table_manager: StringTableManager,
     file: FileParseResult<'input>,
+    line_id_namespace: Option<String>,
+    line_id_namespace_violation_severity: DiagnosticSeverity,
+    require_explicit_line_ids: bool,
     _dummy: (),
 }
 
@@ -30,9 +33,29 @@ impl<'input> StringTableGeneratorVisitor<'input> {
             string_table_manager,
             diagnostics: Default::default(),
             current_node_name: Default::default(),
+            line_id_namespace: Default::default(),
+            line_id_namespace_violation_severity: Default::default(),
+            require_explicit_line_ids: Default::default(),
             _dummy: (),
         }
     }
+
+    /// Requires all line IDs to be in the given namespace. See [`Compiler::with_line_id_namespace`].
+    pub(crate) fn with_line_id_namespace(
+        mut self,
+        namespace: Option<String>,
+        violation_severity: DiagnosticSeverity,
+    ) -> Self {
+        self.line_id_namespace = namespace;
+        self.line_id_namespace_violation_severity = violation_severity;
+        self
+    }
+
+    /// Reports lines without a line ID instead of generating one. See [`Compiler::with_explicit_line_ids_required`].
+    pub(crate) fn with_explicit_line_ids_required(mut self, required: bool) -> Self {
+        self.require_explicit_line_ids = required;
+        self
+    }
 }
 
 impl<'input> ParseTreeVisitorCompat<'input> for StringTableGeneratorVisitor<'input> {
@@ -54,8 +77,8 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for StringTableGeneratorVisi
                 header
                     .header_value
                     .as_ref()
-                    .unwrap()
-                    .get_text()
+                    .map(|header| header.get_text())
+                    .unwrap_or_default()
                     .clone_into(&mut self.current_node_name)
             } else if header_key == "tags" {
                 let header_value = header
@@ -74,13 +97,16 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for StringTableGeneratorVisi
             // This is a raw text node. Use its entire contents as a
             // string and don't use its contents.
             let line_id = get_line_id_for_node_name(&self.current_node_name);
-            self.string_table_manager.insert(
+            // The line ID is derived from the node's title, so it can only be a duplicate if the title is,
+            // which is reported as a duplicate node name
+            let _ = self.string_table_manager.insert(
                 line_id,
                 StringInfo {
                     text: ctx.body().unwrap().get_text(),
                     node_name: self.current_node_name.clone(),
                     line_number: ctx.body().unwrap().start().line as usize,
                     file_name: self.file.name.clone(),
+                    line_id_namespace: self.line_id_namespace.clone(),
                     ..Default::default()
                 },
             );
@@ -99,39 +125,76 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for StringTableGeneratorVisi
         let line_id = line_id_tag.as_ref().and_then(|t| t.text.as_ref());
 
         if let Some(line_id) = line_id {
-            if self
-                .string_table_manager
-                .contains_key(&line_id.get_text().into())
-            {
-                // The original has a fallback for when this is `null` / `None`,
-                // but this can logically not be the case in this scope.
-                let diagnostic_context = line_id_tag.clone().unwrap();
+            if let Some(namespace) = &self.line_id_namespace {
+                let prefix = get_line_id_namespace_prefix(namespace);
                 let line_id = line_id.get_text();
-                self.diagnostics.push(
-                    Diagnostic::from_message(format!("Duplicate line ID {line_id}"))
+                if !line_id.starts_with(&prefix) {
+                    let diagnostic_context = line_id_tag.clone().unwrap();
+                    self.diagnostics.push(
+                        Diagnostic::from_message(format!(
+                            "Line ID {line_id} is not in the line ID namespace \"{namespace}\", so it must start with \"{prefix}\""
+                        ))
+                        .with_code(DiagnosticCode::LineIdNamespaceViolation)
+                        .with_argument("namespace", namespace)
+                        .with_argument("line_id", line_id)
+                        .with_severity(self.line_id_namespace_violation_severity)
                         .with_parser_context(diagnostic_context.as_ref(), self.file.tokens())
                         .with_file_name(&self.file.name),
-                );
-                return;
+                    );
+                }
             }
         };
 
         let line_number = ctx.start().get_line_as_usize();
         let hashtag_texts = get_hashtag_texts(&hashtags);
 
-        let composed_string = generate_formatted_text(&ctx.line_formatted_text().unwrap());
+        let mut composed_string = generate_formatted_text(&ctx.line_formatted_text().unwrap());
+        if is_line_group_candidate(ctx) {
+            // The marker only tells the compiler that this line is part of a group
+            composed_string = composed_string[LINE_GROUP_MARKER.len()..]
+                .trim_start()
+                .to_owned();
+        }
 
-        let string_id = self.string_table_manager.insert(
-            line_id.map(|t| t.get_text().into()),
-            StringInfo {
-                text: composed_string,
-                node_name: self.current_node_name.clone(),
-                line_number,
-                file_name: self.file.name.clone(),
-                metadata: hashtag_texts,
-                ..Default::default()
-            },
-        );
+        if line_id.is_none() && self.require_explicit_line_ids {
+            self.diagnostics.push(
+                Diagnostic::from_message(format!(
+                    "The line \"{composed_string}\" has no line ID, but explicit line IDs are required"
+                ))
+                .with_code(DiagnosticCode::MissingLineId)
+                .with_argument("text", &composed_string)
+                .with_parser_context(ctx, self.file.tokens())
+                .with_file_name(&self.file.name),
+            );
+            return;
+        }
+
+        let string_info = StringInfo {
+            text: composed_string,
+            node_name: self.current_node_name.clone(),
+            line_number,
+            file_name: self.file.name.clone(),
+            metadata: hashtag_texts,
+            line_id_namespace: self.line_id_namespace.clone(),
+            ..Default::default()
+        };
+        let string_id = match self
+            .string_table_manager
+            .insert(line_id.map(|t| t.get_text().into()), string_info.clone())
+        {
+            Ok(string_id) => string_id,
+            Err(duplicate) => {
+                // The original has a fallback for when this is `null` / `None`,
+                // but this can logically not be the case in this scope.
+                let diagnostic_context = line_id_tag.unwrap();
+                self.diagnostics.push(
+                    duplicate_line_id_diagnostic(&duplicate, &string_info)
+                        .with_parser_context(diagnostic_context.as_ref(), self.file.tokens())
+                        .with_file_name(&self.file.name),
+                );
+                return;
+            }
+        };
 
         if line_id.is_none() {
             add_hashtag_child(ctx, string_id.0);
@@ -139,6 +202,40 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for StringTableGeneratorVisi
     }
 }
 
+/// Names where both lines that share a line ID come from, and whether they are copies of each other.
+fn duplicate_line_id_diagnostic(
+    duplicate: &DuplicateLineId,
+    string_info: &StringInfo,
+) -> Diagnostic {
+    let existing = &duplicate.existing;
+    let location = |string_info: &StringInfo| {
+        format!(
+            "\"{}\" node {} line {}",
+            string_info.file_name, string_info.node_name, string_info.line_number
+        )
+    };
+    let line_id = &duplicate.line_id;
+    let message = if existing.text == string_info.text {
+        format!(
+            "Duplicate line ID {line_id}: the line \"{}\" in {} is a copy of the one in {}. Give each copy its own line ID",
+            string_info.text,
+            location(string_info),
+            location(existing),
+        )
+    } else {
+        format!(
+            "Duplicate line ID {line_id}: it is used for \"{}\" in {} and for \"{}\" in {}",
+            existing.text,
+            location(existing),
+            string_info.text,
+            location(string_info),
+        )
+    };
+    Diagnostic::from_message(message)
+        .with_code(DiagnosticCode::DuplicateLineId)
+        .with_argument("line_id", line_id)
+}
+
 /// Takes a string like
 /// `Hi there { some_expression }, how are you { another_expression } doing?`
 /// and turns it into
@@ -260,6 +357,14 @@ a {1 + 3} cool expression
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            command_declarations: vec![],
+            line_id_namespace: None,
+            line_id_namespace_violation_severity: Default::default(),
+            wrap_config: None,
+            strict_contracts: false,
+            deterministic_output: false,
+            excluded_node_tags: vec![],
+            require_explicit_line_ids: false,
         }
         .compile()
         .unwrap();
@@ -267,7 +372,7 @@ a {1 + 3} cool expression
         let string_table = result.string_table;
         assert_eq!(string_table.len(), 3);
         assert_eq!(
-            string_table[&"line:test.yarn-test-0".into()],
+            *implicit_string(&string_table, "foo"),
             StringInfo {
                 text: "foo".to_string(),
                 node_name: "test".to_string(),
@@ -275,10 +380,13 @@ a {1 + 3} cool expression
                 file_name: "test.yarn".to_string(),
                 is_implicit_tag: true,
                 metadata: vec![],
+                expression_types: vec![],
+                line_id_namespace: None,
+                wrap_hints: None,
             }
         );
         assert_eq!(
-            string_table[&"line:test.yarn-test-1".into()],
+            *implicit_string(&string_table, "bar"),
             StringInfo {
                 text: "bar".to_string(),
                 node_name: "test".to_string(),
@@ -286,10 +394,13 @@ a {1 + 3} cool expression
                 file_name: "test.yarn".to_string(),
                 is_implicit_tag: true,
                 metadata: vec![],
+                expression_types: vec![],
+                line_id_namespace: None,
+                wrap_hints: None,
             }
         );
         assert_eq!(
-            string_table[&"line:test.yarn-test-2".into()],
+            *implicit_string(&string_table, "a {0} cool expression"),
             StringInfo {
                 text: "a {0} cool expression".to_string(),
                 node_name: "test".to_string(),
@@ -297,10 +408,25 @@ a {1 + 3} cool expression
                 file_name: "test.yarn".to_string(),
                 is_implicit_tag: true,
                 metadata: vec![],
+                expression_types: vec![Type::Number],
+                line_id_namespace: None,
+                wrap_hints: None,
             }
         );
     }
 
+    fn implicit_string<'a>(
+        string_table: &'a std::collections::HashMap<LineId, StringInfo>,
+        text: &str,
+    ) -> &'a StringInfo {
+        let (line_id, string_info) = string_table
+            .iter()
+            .find(|(_, string_info)| string_info.text == text)
+            .unwrap();
+        assert!(line_id.0.starts_with("line:test.yarn-test-"));
+        string_info
+    }
+
     #[test]
     fn catches_expression_errors() {
         let file = File {
@@ -318,6 +444,14 @@ a {very} cool expression
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            command_declarations: vec![],
+            line_id_namespace: None,
+            line_id_namespace_violation_severity: Default::default(),
+            wrap_config: None,
+            strict_contracts: false,
+            deterministic_output: false,
+            excluded_node_tags: vec![],
+            require_explicit_line_ids: false,
         }
         .compile();
 
@@ -333,7 +467,8 @@ a {very} cool expression
         };
         let context = "a {very} cool expression\n       ^".to_owned();
         let first_expected =
-            Diagnostic::from_message("Unexpected \"}\" while reading a function call".to_string())
+            Diagnostic::from_message("Unexpected \"}\" while reading a value".to_string())
+                .with_code(DiagnosticCode::SyntaxError)
                 .with_file_name("test.yarn".to_string())
                 .with_range(range.clone())
                 .with_context(context.clone())
@@ -341,7 +476,8 @@ a {very} cool expression
                 .with_severity(DiagnosticSeverity::Error);
 
         let second_expected =
-            Diagnostic::from_message("mismatched input '}' expecting '('".to_string())
+            Diagnostic::from_message("no viable alternative at input 'very}'".to_string())
+                .with_code(DiagnosticCode::SyntaxError)
                 .with_file_name("test.yarn".to_string())
                 .with_range(range)
                 .with_context(context)
@@ -69,6 +69,9 @@ pub(crate) struct TypeCheckVisitor<'input> {
     hints: KnownTypes,
 
     file: FileParseResult<'input>,
+
+    /// The library of the compilation job. Consulted for operators that were added to types with [`Library::add_operator`].
+    library: Library,
     _dummy: Option<Type>,
 }
 
@@ -76,9 +79,11 @@ impl<'input> TypeCheckVisitor<'input> {
     pub(crate) fn new(
         existing_declarations: Vec<Declaration>,
         file: FileParseResult<'input>,
+        library: Library,
     ) -> Self {
         Self {
             file,
+            library,
             existing_declarations,
             diagnostics: Default::default(),
             new_declarations: Default::default(),
@@ -98,6 +103,93 @@ impl<'input> TypeCheckVisitor<'input> {
             .chain(self.new_declarations.iter())
     }
 
+    /// Declares the smart variable of a `<<declare>>` whose value is an expression. See [`is_smart_variable_declaration`].
+    /// The variable has the type of the expression and no default value.
+    fn declare_smart_variable(&mut self, ctx: &Declare_statementContext<'input>) -> Option<Type> {
+        let variable_context = ctx.variable()?;
+        let expression_context = ctx.expression()?;
+        let expression_type = self.visit(expression_context.as_ref());
+        let variable_name = variable_context.get_text();
+        let range = variable_context.range();
+
+        // A smart variable that was used before its declaration has been declared implicitly in the meantime
+        self.existing_declarations
+            .retain(|decl| !(decl.is_implicit && decl.name == variable_name));
+        self.new_declarations
+            .retain(|decl| !(decl.is_implicit && decl.name == variable_name));
+        let existing_declaration = self
+            .declarations()
+            .find(|decl| decl.name == variable_name)
+            .cloned();
+        if let Some(existing_declaration) = existing_declaration {
+            // Smart variables are declared in a pass of their own before the other statements are checked,
+            // so that they can be used before their declaration. See `check_types`.
+            let is_this_declaration = existing_declaration.is_smart
+                && existing_declaration.range.as_ref() == Some(&range)
+                && existing_declaration.source_file_name
+                    == DeclarationSource::from(self.file.name.as_str());
+            if !is_this_declaration {
+                let line = existing_declaration
+                    .source_file_line()
+                    .map(|l| format!(", line: {l}"))
+                    .unwrap_or_default();
+                let msg = format!(
+                    "{} has already been declared in {}{line}",
+                    existing_declaration.name, existing_declaration.source_file_name,
+                );
+                self.diagnostics.push(
+                    Diagnostic::from_message(msg)
+                        .with_code(DiagnosticCode::DuplicateDeclaration)
+                        .with_argument("variable", &existing_declaration.name)
+                        .with_argument("file", &existing_declaration.source_file_name)
+                        .with_file_name(&self.file.name)
+                        .with_parser_context(ctx, self.file.tokens()),
+                );
+            }
+            return expression_type;
+        }
+
+        let Some(expression_type) = expression_type else {
+            self.diagnostics.push(
+                Diagnostic::from_message(format_cannot_determine_variable_type_error(
+                    &variable_name,
+                ))
+                .with_code(DiagnosticCode::UndeterminedType)
+                .with_argument("variable", &variable_name)
+                .with_file_name(&self.file.name)
+                .with_parser_context(ctx, self.file.tokens()),
+            );
+            return None;
+        };
+        let description = get_document_comments(self.file.tokens(), ctx);
+        let description_as_option = (!description.is_empty()).then_some(description);
+        let declaration = Declaration::new(variable_name, expression_type.clone())
+            .with_description_optional(description_as_option)
+            .with_source_file_name(self.file.name.clone())
+            .with_source_node_name_optional(self.current_node_name.clone())
+            .with_range(range)
+            .with_smart();
+        self.new_declarations.push(declaration);
+        Some(expression_type)
+    }
+
+    /// Literals assigned to a variable of an enum type are cases of the enum if they are the raw value of one,
+    /// e.g. the `1` in `<<set $snack to 1>>` if `$snack` is a `Food` and `Food.Banana` has the raw value `1`.
+    ///
+    /// Returns the enum type if the literal is such a raw value.
+    fn enum_type_with_raw_value(
+        &self,
+        ctx: &impl YarnSpinnerParserContext<'input>,
+        raw_value: Option<impl Into<YarnValue>>,
+    ) -> Option<Type> {
+        let hint @ Type::Enum(enum_type) = self.hints.get(ctx)? else {
+            return None;
+        };
+        enum_type
+            .case_for_raw_value(&raw_value?.into())
+            .map(|_| hint.clone())
+    }
+
     /// Gets the collection of all declarations mutably - both the ones we received
     /// at the start, and the new ones we've derived ourselves.
     pub(crate) fn declarations_mut(&mut self) -> impl Iterator<Item = &mut Declaration> + '_ {
@@ -122,7 +214,11 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
         for header in ctx.header_all() {
             let key = header.header_key.as_ref().unwrap().get_text();
             if key == "title" {
-                let value = header.header_value.as_ref().unwrap().get_text();
+                let value = header
+                    .header_value
+                    .as_ref()
+                    .map(|value| value.get_text())
+                    .unwrap_or_default();
                 self.current_node_name = Some(value.to_owned());
             }
         }
@@ -227,8 +323,20 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
         Some(Type::Boolean)
     }
 
-    fn visit_valueNumber(&mut self, _ctx: &ValueNumberContext<'input>) -> Self::Return {
-        Some(Type::Number)
+    fn visit_valueNumber(&mut self, ctx: &ValueNumberContext<'input>) -> Self::Return {
+        if let Some(enum_type) =
+            self.enum_type_with_raw_value(ctx, ctx.get_text().parse::<f32>().ok())
+        {
+            return Some(enum_type);
+        }
+        // Whole numbers assigned to integers are integers themselves
+        let is_integer =
+            self.hints.get(ctx) == Some(&Type::Integer) && is_integer_literal(&ctx.get_text());
+        Some(if is_integer {
+            Type::Integer
+        } else {
+            Type::Number
+        })
     }
 
     fn visit_valueTrue(&mut self, _ctx: &ValueTrueContext<'input>) -> Self::Return {
@@ -244,13 +352,49 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
         self.visit_variable(&variable)
     }
 
-    fn visit_valueString(&mut self, _ctx: &ValueStringContext<'input>) -> Self::Return {
+    fn visit_valueTypeMemberReference(
+        &mut self,
+        ctx: &ValueTypeMemberReferenceContext<'input>,
+    ) -> Self::Return {
+        let type_member_reference = ctx.typeMemberReference().unwrap();
+        self.visit_typeMemberReference(&type_member_reference)
+    }
+
+    fn visit_typeMemberReference(
+        &mut self,
+        ctx: &TypeMemberReferenceContext<'input>,
+    ) -> Self::Return {
+        // The only type members are enum cases, which are declared when the enum is
+        let name = ctx.get_text();
+        if let Some(declaration) = self
+            .declarations()
+            .find(|decl| decl.name == name && is_enum_case(decl))
+        {
+            return Some(declaration.r#type.clone());
+        }
+        self.diagnostics.push(
+            Diagnostic::from_message(format!("{name} is not a case of any enum"))
+                .with_code(DiagnosticCode::InvalidEnum)
+                .with_argument("name", &name)
+                .with_file_name(&self.file.name)
+                .with_parser_context(ctx, self.file.tokens()),
+        );
+        None
+    }
+
+    fn visit_valueString(&mut self, ctx: &ValueStringContext<'input>) -> Self::Return {
+        let text = ctx.get_text();
+        if let Some(enum_type) = self.enum_type_with_raw_value(ctx, Some(text.trim_matches('"'))) {
+            return Some(enum_type);
+        }
         Some(Type::String)
     }
 
     fn visit_valueNull(&mut self, ctx: &ValueNullContext<'input>) -> Self::Return {
         self.diagnostics.push(
             Diagnostic::from_message("Null is not a permitted type in Yarn Spinner 2.0 and later")
+                .with_code(DiagnosticCode::UnknownType)
+                .with_argument("type", "null")
                 .with_file_name(&self.file.name)
                 .with_parser_context(ctx, self.file.tokens()),
         );
@@ -308,13 +452,15 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
 
             let line = ctx.start().get_line_as_usize();
             let column = ctx.start().get_column_as_usize();
+            // Only the name of the function, not the whole call
+            let function_id = ctx.function_call().unwrap().FUNC_ID().unwrap();
             let function_declaration =
                 Declaration::new(function_name.clone(), function_type.clone())
                     .with_description(format!(
                         "Implicit declaration of function at {}:{}:{}",
                         self.file.name, line, column
                     ))
-                    .with_range(ctx.range())
+                    .with_range(token_range(function_id.symbol.as_ref()))
                     .with_implicit();
             self.new_declarations.push(function_declaration);
             function_type
@@ -337,6 +483,10 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
                 parameters,
                 supplied_parameters.len()
             ))
+            .with_code(DiagnosticCode::WrongParameterCount)
+            .with_argument("name", &function_name)
+            .with_argument("expected", expected_parameter_types.len())
+            .with_argument("actual", supplied_parameters.len())
             .with_file_name(&self.file.name)
             .with_parser_context(ctx, self.file.tokens());
             self.diagnostics.push(diagnostic);
@@ -372,6 +522,9 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
                     expected_type.format(),
                     supplied_type.format()
                 ))
+                .with_code(DiagnosticCode::TypeMismatch)
+                .with_argument("expected", expected_type.format())
+                .with_argument("actual", supplied_type.format())
                 .with_file_name(&self.file.name)
                 .with_parser_context(ctx, self.file.tokens());
                 self.diagnostics.push(diagnostic);
@@ -411,6 +564,8 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
         // so we save this as a potential diagnostic for the compiler itself to resolve
         let diagnostic =
             Diagnostic::from_message(format_cannot_determine_variable_type_error(&name))
+                .with_code(DiagnosticCode::UndeterminedType)
+                .with_argument("variable", &name)
                 .with_file_name(&self.file.name)
                 .with_parser_context(ctx, self.file.tokens());
         self.deferred_types
@@ -436,6 +591,23 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
         self.check_operation(ctx, expressions, None, "elseif statement", &[Type::Boolean])
     }
 
+    fn visit_once_primary_clause(
+        &mut self,
+        ctx: &Once_primary_clauseContext<'input>,
+    ) -> Self::Return {
+        ParseTreeVisitorCompat::visit_children(self, ctx);
+        // Once conditions are required to be boolean
+        let expressions = &[ctx.expression()?.into()];
+        self.check_operation(ctx, expressions, None, "once statement", &[Type::Boolean])
+    }
+
+    fn visit_declare_statement(&mut self, ctx: &Declare_statementContext<'input>) -> Self::Return {
+        if is_smart_variable_declaration(ctx) {
+            return self.declare_smart_variable(ctx);
+        }
+        ParseTreeVisitorCompat::visit_children(self, ctx)
+    }
+
     fn visit_set_statement(&mut self, ctx: &Set_statementContext<'input>) -> Self::Return {
         let variable_context = ctx.variable()?;
         let expression_context = ctx.expression()?;
@@ -448,6 +620,23 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
         }
         let mut expression_type = self.visit(expression_context.as_ref());
         let variable_name = variable_context.get_text();
+        let read_only_declaration = self
+            .declarations()
+            .find(|decl| (decl.is_smart || decl.is_read_only) && decl.name == variable_name);
+        if let Some(declaration) = read_only_declaration {
+            let message = if declaration.is_smart {
+                format!("{variable_name} is a smart variable, which can't be assigned a value")
+            } else {
+                format!("{variable_name} is read-only, so it can't be assigned a value")
+            };
+            self.diagnostics.push(
+                Diagnostic::from_message(message)
+                    .with_code(DiagnosticCode::ReadOnlyVariable)
+                    .with_argument("variable", &variable_name)
+                    .with_file_name(&self.file.name)
+                    .with_parser_context(ctx, self.file.tokens()),
+            );
+        }
         let terms: &[Term] = &[
             variable_context.clone().into(),
             expression_context.clone().into(),
@@ -467,6 +656,9 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
                             variable_type.format(),
                             expression_type.format(),
                         ))
+                        .with_code(DiagnosticCode::TypeMismatch)
+                        .with_argument("expected", variable_type.format())
+                        .with_argument("actual", expression_type.format())
                         .with_file_name(&self.file.name)
                         .with_parser_context(ctx, self.file.tokens());
                         self.diagnostics.push(diagnostic);
@@ -480,7 +672,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
                         // we can't get one, we can't create the definition.
                         if let Some(default_value) = expression_type.default_value() {
                             // Generate a declaration for this variable here.
-                            let decl = Declaration::new(variable_name, expression_type.clone())
+                            let decl = Declaration::new(variable_name.clone(), expression_type.clone())
                                 .with_description(format!(
                                     "Implicitly declared in {}, node {}",
                                     get_filename(&self.file.name),
@@ -497,6 +689,8 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
                                 Diagnostic::from_message(
                                     format_cannot_determine_variable_type_error(&variable_name),
                                 )
+                                .with_code(DiagnosticCode::UndeterminedType)
+                                .with_argument("variable", &variable_name)
                                 .with_file_name(&self.file.name)
                                 .with_parser_context(ctx, self.file.tokens()),
                             )
@@ -535,10 +729,32 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
             }
             _ => panic!("Internal error: `visit_set_statement` got unexpected operand {}. This is a bug. Please report it at https://github.com/YarnSpinnerTool/YarnSpinner-Rust/issues/new", op.get_text())
         }
+        // Compound assignments can promote integers to numbers, e.g. `$gold *= 1.5`
+        if let (Some(variable_type), Some(resolved_type)) =
+            (variable_type.as_ref(), expression_type.as_ref())
+        {
+            let is_compound_assignment = op.token_type != yarnspinnerlexer::OPERATOR_ASSIGNMENT;
+            if is_compound_assignment && !resolved_type.is_sub_type_of(variable_type) {
+                let diagnostic = Diagnostic::from_message(format!(
+                    "{variable_name} ({}) cannot be assigned a {}",
+                    variable_type.format(),
+                    resolved_type.format(),
+                ))
+                .with_code(DiagnosticCode::TypeMismatch)
+                .with_argument("expected", variable_type.format())
+                .with_argument("actual", resolved_type.format())
+                .with_file_name(&self.file.name)
+                .with_parser_context(ctx, self.file.tokens());
+                self.diagnostics.push(diagnostic);
+            }
+        }
         if variable_type.is_none() && expression_type.is_none() {
+            let expression = ctx.get_text_with_whitespace(self.file.tokens());
             self.diagnostics.push(
                             Diagnostic::from_message(
-                                format!("Type of expression \"{}\" can't be determined without more context. Please declare one or more terms.", ctx.get_text_with_whitespace(self.file.tokens())))
+                                format!("Type of expression \"{expression}\" can't be determined without more context. Please declare one or more terms."))
+                                .with_code(DiagnosticCode::UndeterminedType)
+                                .with_argument("expression", &expression)
                                 .with_file_name(&self.file.name)
                                 .with_parser_context(ctx, self.file.tokens()));
         }
@@ -585,15 +801,22 @@ fn get_filename(path: &str) -> &str {
     path
 }
 
-trait DefaultValue {
+/// Returns `true` if the text is a number literal without a fractional part, e.g. `5` but not `5.0`,
+/// which makes it an integer when used with other integers.
+pub(crate) fn is_integer_literal(text: &str) -> bool {
+    !text.contains('.') && text.parse::<i32>().is_ok()
+}
+
+pub(crate) trait DefaultValue {
     fn default_value(&self) -> Option<YarnValue>;
 }
 impl DefaultValue for Type {
     fn default_value(&self) -> Option<YarnValue> {
         match self {
             Type::String => Some(YarnValue::String(Default::default())),
-            Type::Number => Some(YarnValue::Number(Default::default())),
+            Type::Number | Type::Integer => Some(YarnValue::Number(Default::default())),
             Type::Boolean => Some(YarnValue::Boolean(Default::default())),
+            Type::Enum(enum_type) => enum_type.cases.first().map(|case| case.raw_value.clone()),
             _ => None,
         }
     }
@@ -629,6 +852,14 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            command_declarations: vec![],
+            line_id_namespace: None,
+            line_id_namespace_violation_severity: Default::default(),
+            wrap_config: None,
+            strict_contracts: false,
+            deterministic_output: false,
+            excluded_node_tags: vec![],
+            require_explicit_line_ids: false,
         }
         .compile()
         .unwrap();
@@ -654,6 +885,14 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            command_declarations: vec![],
+            line_id_namespace: None,
+            line_id_namespace_violation_severity: Default::default(),
+            wrap_config: None,
+            strict_contracts: false,
+            deterministic_output: false,
+            excluded_node_tags: vec![],
+            require_explicit_line_ids: false,
         }
         .compile();
 
@@ -707,6 +946,113 @@ mod tests {
         );
     }
 
+    #[test]
+    fn implicit_declarations_cover_their_identifier() {
+        let file = File {
+            file_name: "test.yarn".to_string(),
+            source: "title: test
+---
+<<if \"Grüße\" == name(1, 2)>>
+<<set $greeting to name(3, 4) + \"!\">>
+<<endif>>
+==="
+            .to_string(),
+        };
+        let result = Compiler {
+            files: vec![file],
+            library: Default::default(),
+            compilation_type: CompilationType::DeclarationsOnly,
+            variable_declarations: vec![],
+            command_declarations: vec![],
+            line_id_namespace: None,
+            line_id_namespace_violation_severity: Default::default(),
+            wrap_config: None,
+            strict_contracts: false,
+            deterministic_output: false,
+            excluded_node_tags: vec![],
+            require_explicit_line_ids: false,
+        }
+        .compile()
+        .unwrap();
+
+        let range = |name: &str| {
+            result
+                .declarations
+                .iter()
+                .find(|decl| decl.name == name)
+                .and_then(|decl| decl.range.clone())
+        };
+        assert_eq!(
+            Some(
+                Position {
+                    line: 2,
+                    character: 16,
+                }..Position {
+                    line: 2,
+                    character: 20,
+                }
+            ),
+            range("name")
+        );
+        assert_eq!(
+            Some(
+                Position {
+                    line: 3,
+                    character: 6,
+                }..Position {
+                    line: 3,
+                    character: 15,
+                }
+            ),
+            range("$greeting")
+        );
+    }
+
+    #[test]
+    fn diagnostic_ranges_count_characters() {
+        let file = File {
+            file_name: "test.yarn".to_string(),
+            source: "title: test
+---
+<<declare $grüße = 1>>
+<<set $grüße to 1 + \"Grüße\">>
+==="
+            .to_string(),
+        };
+        let result = Compiler {
+            files: vec![file],
+            library: Default::default(),
+            compilation_type: CompilationType::FullCompilation,
+            variable_declarations: vec![],
+            command_declarations: vec![],
+            line_id_namespace: None,
+            line_id_namespace_violation_severity: Default::default(),
+            wrap_config: None,
+            strict_contracts: false,
+            deterministic_output: false,
+            excluded_node_tags: vec![],
+            require_explicit_line_ids: false,
+        }
+        .compile();
+
+        let diagnostics = result.unwrap_err().0;
+
+        assert_contains(
+            &diagnostics,
+            &Diagnostic::from_message("All terms of + must be the same, not Number, String")
+                .with_file_name("test.yarn")
+                .with_range(
+                    Position {
+                        line: 3,
+                        character: 20,
+                    }..Position {
+                        line: 3,
+                        character: 27,
+                    },
+                ),
+        );
+    }
+
     fn assert_contains(diagnostics: &[Diagnostic], expected: &Diagnostic) {
         assert!(
             // Does not factor in context or start line because these are subject to frequent change
@@ -741,6 +1087,14 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            command_declarations: vec![],
+            line_id_namespace: None,
+            line_id_namespace_violation_severity: Default::default(),
+            wrap_config: None,
+            strict_contracts: false,
+            deterministic_output: false,
+            excluded_node_tags: vec![],
+            require_explicit_line_ids: false,
         }
         .compile()
         .unwrap();
@@ -764,6 +1118,14 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            command_declarations: vec![],
+            line_id_namespace: None,
+            line_id_namespace_violation_severity: Default::default(),
+            wrap_config: None,
+            strict_contracts: false,
+            deterministic_output: false,
+            excluded_node_tags: vec![],
+            require_explicit_line_ids: false,
         }
         .compile();
 
@@ -808,7 +1170,7 @@ mod tests {
                 .with_range(
                     Position {
                         line: 4,
-                        character: 14,
+                        character: 21,
                     }..Position {
                         line: 4,
                         character: 25,
@@ -823,7 +1185,7 @@ mod tests {
                 .with_range(
                     Position {
                         line: 5,
-                        character: 14,
+                        character: 21,
                     }..Position {
                         line: 5,
                         character: 30,
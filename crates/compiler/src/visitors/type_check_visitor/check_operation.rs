@@ -2,7 +2,7 @@ use crate::parser_rule_context_ext::ParserRuleContextExt;
 use crate::prelude::generated::yarnspinnerparser::*;
 use crate::prelude::*;
 use crate::visitors::type_check_visitor::{
-    format_cannot_determine_variable_type_error, get_filename, DefaultValue,
+    format_cannot_determine_variable_type_error, get_filename, is_integer_literal, DefaultValue,
 };
 use crate::visitors::*;
 use antlr_rust::rule_context::CustomRuleContext;
@@ -13,7 +13,7 @@ use better_any::TidExt;
 use std::cmp::Ordering;
 use std::ops::Deref;
 use std::rc::Rc;
-use yarnspinner_core::prelude::Operator;
+use yarnspinner_core::prelude::{Operator, YarnValue};
 use yarnspinner_core::types::{SubTypeOf, Type, TypeFormat};
 
 impl<'input> TypeCheckVisitor<'input> {
@@ -38,6 +38,7 @@ impl<'input> TypeCheckVisitor<'input> {
         let operation_type = operation_type.into();
         let mut term_types = Vec::new();
         let mut expression_type = None;
+        let mut types_per_term = Vec::new();
         for expression in terms {
             // Visit this expression, and determine its type.
             let r#type = self.visit(expression.deref());
@@ -49,6 +50,29 @@ impl<'input> TypeCheckVisitor<'input> {
                 }
                 term_types.push(r#type);
             }
+            types_per_term.push(r#type);
+        }
+        let enum_type = term_types
+            .iter()
+            .find(|r#type| matches!(r#type, Type::Enum(_)))
+            .cloned();
+        if let Some(enum_type) = enum_type {
+            self.cast_raw_values_to_enum(terms, &mut types_per_term, &enum_type);
+            term_types = types_per_term.iter().flatten().cloned().collect();
+            expression_type = term_types.first().cloned();
+        }
+        if term_types.contains(&Type::Integer) && term_types.contains(&Type::Number) {
+            let resolved_type = self.unify_integers_and_numbers(terms, &types_per_term);
+            for r#type in term_types.iter_mut() {
+                if *r#type == Type::Integer || *r#type == Type::Number {
+                    *r#type = resolved_type.clone();
+                }
+            }
+            if let Some(r#type) = expression_type.as_mut() {
+                if *r#type == Type::Integer || *r#type == Type::Number {
+                    *r#type = resolved_type;
+                }
+            }
         }
         if permitted_types.len() == 1 && expression_type.is_none() {
             // If we aren't sure of the expression type from
@@ -69,9 +93,19 @@ impl<'input> TypeCheckVisitor<'input> {
             // it's that type.
             if let Some(operation_type) = operation_type {
                 let operation_type_name = operation_type.to_string();
-                let types_implementing_method: Vec<_> = Type::EXPLICITLY_CONSTRUCTABLE
+                let candidates: Vec<_> = Type::EXPLICITLY_CONSTRUCTABLE
                     .iter()
-                    .filter(|t| t.has_method(&operation_type_name))
+                    .filter(|t| self.library.has_method(t, &operation_type_name))
+                    .collect();
+                // Prefer the more general type, e.g. a number over an integer
+                let types_implementing_method: Vec<_> = candidates
+                    .iter()
+                    .filter(|t| {
+                        !candidates
+                            .iter()
+                            .any(|other| other != *t && t.is_sub_type_of(*other))
+                    })
+                    .copied()
                     .collect();
                 match types_implementing_method.len().cmp(&1_usize) {
                     Ordering::Equal => {
@@ -89,11 +123,13 @@ impl<'input> TypeCheckVisitor<'input> {
                             .map(|t| t.format())
                             .collect::<Vec<_>>()
                             .join(", or ");
+                        let expression = context.get_text_with_whitespace(self.file.tokens());
                         let message = format!(
-                        "Type of expression \"{}\" can't be determined without more context (the compiler thinks it could be {type_names}). Use a type cast on at least one of the terms (e.g. the string(), number(), bool() functions)",
-                        context.get_text_with_whitespace(self.file.tokens()),
+                        "Type of expression \"{expression}\" can't be determined without more context (the compiler thinks it could be {type_names}). Use a type cast on at least one of the terms (e.g. the string(), number(), bool() functions)",
                     );
                         let diagnostic = Diagnostic::from_message(message)
+                            .with_code(DiagnosticCode::UndeterminedType)
+                            .with_argument("expression", &expression)
                             .with_file_name(&self.file.name)
                             .with_parser_context(context, self.file.tokens());
                         self.diagnostics.push(diagnostic);
@@ -101,11 +137,13 @@ impl<'input> TypeCheckVisitor<'input> {
                     }
                     Ordering::Less => {
                         // No types implement this operation (??) [sic]
+                        let expression = context.get_text_with_whitespace(self.file.tokens());
                         let message = format!(
-                        "Type of expression \"{}\" can't be determined without more context. Use a type cast on at least one of the terms (e.g. the string(), number(), bool() functions)",
-                        context.get_text_with_whitespace(self.file.tokens()),
+                        "Type of expression \"{expression}\" can't be determined without more context. Use a type cast on at least one of the terms (e.g. the string(), number(), bool() functions)",
                     );
                         let diagnostic = Diagnostic::from_message(message)
+                            .with_code(DiagnosticCode::UndeterminedType)
+                            .with_argument("expression", &expression)
                             .with_file_name(&self.file.name)
                             .with_parser_context(context, self.file.tokens());
                         self.diagnostics.push(diagnostic);
@@ -256,6 +294,8 @@ impl<'input> TypeCheckVisitor<'input> {
                 let diagnostic = Diagnostic::from_message(
                     format_cannot_determine_variable_type_error(&var_name),
                 )
+                .with_code(DiagnosticCode::UndeterminedType)
+                .with_argument("variable", &var_name)
                 .with_file_name(&self.file.name)
                 .with_parser_context(undefined_variable_context.as_ref(), self.file.tokens());
                 self.diagnostics.push(diagnostic);
@@ -280,12 +320,32 @@ impl<'input> TypeCheckVisitor<'input> {
             let message =
                 format!("All terms of {operation_description} must be the same, not {type_list}");
             let diagnostic = Diagnostic::from_message(message)
-                .with_file_name(&self.file.name)
-                .with_parser_context(context, self.file.tokens());
+                .with_code(DiagnosticCode::TypeMismatch)
+                .with_argument("actual", &type_list)
+                .with_file_name(&self.file.name);
+            // Point at the first term that doesn't fit the others
+            let offending_term = terms.iter().zip(&types_per_term).find(|(_, r#type)| {
+                r#type.as_ref().is_some_and(|r#type| {
+                    Some(r#type) != expression_type.as_ref()
+                        && !(is_numeric(r#type) && expression_type.as_ref().is_some_and(is_numeric))
+                })
+            });
+            let diagnostic = match offending_term {
+                Some((term, _)) => self.with_term_context(diagnostic, term),
+                None => diagnostic.with_parser_context(context, self.file.tokens()),
+            };
             self.diagnostics.push(diagnostic);
             return None;
         }
 
+        // All typed terms have the expression type by now, so the first one is as wrong as any other
+        // if the type turns out not to fit the operation.
+        let first_typed_term = terms
+            .iter()
+            .zip(&types_per_term)
+            .find(|(_, r#type)| r#type.is_some())
+            .map(|(term, _)| term);
+
         // We've now determined that this expression is of
         // expressionType. In case any of the terms had an undefined
         // type, we'll define it now.
@@ -308,15 +368,22 @@ impl<'input> TypeCheckVisitor<'input> {
 
             // By the logic of this function, this is guaranteed to be Some
             let expression_type = expression_type.as_ref().unwrap();
-            let implements_method = expression_type.has_method(&operation_type.to_string());
+            let implements_method = self
+                .library
+                .has_method(expression_type, &operation_type.to_string());
             if !implements_method {
                 let message = format!(
                     "{} has no implementation defined for {operation_description}",
                     expression_type.format(),
                 );
                 let diagnostic = Diagnostic::from_message(message)
-                    .with_file_name(&self.file.name)
-                    .with_parser_context(context, self.file.tokens());
+                    .with_code(DiagnosticCode::TypeMismatch)
+                    .with_argument("actual", expression_type.format())
+                    .with_file_name(&self.file.name);
+                let diagnostic = match first_typed_term {
+                    Some(term) => self.with_term_context(diagnostic, term),
+                    None => diagnostic.with_parser_context(context, self.file.tokens()),
+                };
                 self.diagnostics.push(diagnostic);
                 return None;
             }
@@ -349,8 +416,14 @@ impl<'input> TypeCheckVisitor<'input> {
                 "Terms of '{operation_description}' must be {permitted_types_list}, not {type_list}",
             );
             let diagnostic = Diagnostic::from_message(message)
-                .with_file_name(&self.file.name)
-                .with_parser_context(context, self.file.tokens());
+                .with_code(DiagnosticCode::TypeMismatch)
+                .with_argument("expected", &permitted_types_list)
+                .with_argument("actual", &type_list)
+                .with_file_name(&self.file.name);
+            let diagnostic = match first_typed_term {
+                Some(term) => self.with_term_context(diagnostic, term),
+                None => diagnostic.with_parser_context(context, self.file.tokens()),
+            };
             self.diagnostics.push(diagnostic);
             return None;
         }
@@ -363,7 +436,7 @@ impl<'input> TypeCheckVisitor<'input> {
 
         let has_method = expression_type
             .as_ref()
-            .and_then(|exp| operation_type.map(|op| exp.has_method(&op.to_string())))
+            .and_then(|exp| operation_type.map(|op| self.library.has_method(exp, &op.to_string())))
             .unwrap_or_default();
         if !has_method {
             // The type doesn't have a method for handling this
@@ -375,6 +448,8 @@ impl<'input> TypeCheckVisitor<'input> {
             );
             self.diagnostics.push(
                 Diagnostic::from_message(message)
+                    .with_code(DiagnosticCode::TypeMismatch)
+                    .with_argument("actual", expression_type.format())
                     .with_file_name(&self.file.name)
                     .with_parser_context(context, self.file.tokens()),
             );
@@ -384,6 +459,124 @@ impl<'input> TypeCheckVisitor<'input> {
     }
 }
 
+impl<'input> TypeCheckVisitor<'input> {
+    /// Narrows the range of a diagnostic about an operation down to one of its terms, so that editors highlight just the culprit.
+    fn with_term_context(&self, diagnostic: Diagnostic, term: &Term<'input>) -> Diagnostic {
+        match term {
+            Term::Expression(context) => {
+                diagnostic.with_parser_context(context.as_ref(), self.file.tokens())
+            }
+            Term::Variable(context) => {
+                diagnostic.with_parser_context(context.as_ref(), self.file.tokens())
+            }
+        }
+    }
+
+    /// Integers are a subtype of numbers, so mixing them promotes an operation to a number operation,
+    /// e.g. `$gold * 1.5` is a floating point multiplication.
+    /// The exception are whole number literals such as the `2` in `$gold / 2`, which become integers themselves.
+    ///
+    /// Returns the type that all integer and number terms resolve to.
+    fn unify_integers_and_numbers(
+        &mut self,
+        terms: &[Term<'input>],
+        types_per_term: &[Option<Type>],
+    ) -> Type {
+        let only_integer_literals = terms
+            .iter()
+            .zip(types_per_term)
+            .filter(|(_term, r#type)| *r#type == &Some(Type::Number))
+            .all(|(term, _type)| is_number_literal(term) && is_integer_literal(&term.get_text()));
+        let resolved_type = if only_integer_literals {
+            Type::Integer
+        } else {
+            Type::Number
+        };
+        if resolved_type == Type::Integer {
+            for (term, r#type) in terms.iter().zip(types_per_term) {
+                let Term::Expression(expression) = term else {
+                    continue;
+                };
+                if *r#type == Some(Type::Number) {
+                    self.known_types
+                        .insert(expression.as_ref(), Some(Type::Integer));
+                }
+            }
+        }
+        resolved_type
+    }
+}
+
+impl<'input> TypeCheckVisitor<'input> {
+    /// Literals compared to or combined with a value of an enum type are cases of the enum if they are the raw value of one,
+    /// e.g. the `1` in `$snack == 1` if `$snack` is a `Food` and `Food.Banana` has the raw value `1`.
+    fn cast_raw_values_to_enum(
+        &mut self,
+        terms: &[Term<'input>],
+        types_per_term: &mut [Option<Type>],
+        enum_type: &Type,
+    ) {
+        let Type::Enum(enum_type_definition) = enum_type else {
+            return;
+        };
+        for (term, r#type) in terms.iter().zip(types_per_term.iter_mut()) {
+            let Term::Expression(expression) = term else {
+                continue;
+            };
+            let text = term.get_text();
+            let raw_value = match r#type {
+                Some(Type::Number) if is_number_literal(term) => {
+                    text.parse::<f32>().ok().map(YarnValue::from)
+                }
+                Some(Type::String) if is_string_literal(term) => {
+                    Some(YarnValue::from(text.trim_matches('"')))
+                }
+                _ => None,
+            };
+            let is_case = raw_value.is_some_and(|raw_value| {
+                enum_type_definition
+                    .case_for_raw_value(&raw_value)
+                    .is_some()
+            });
+            if is_case {
+                *r#type = Some(enum_type.clone());
+                self.known_types
+                    .insert(expression.as_ref(), Some(enum_type.clone()));
+            }
+        }
+    }
+}
+
+fn is_numeric(r#type: &Type) -> bool {
+    matches!(r#type, Type::Integer | Type::Number)
+}
+
+fn is_string_literal(term: &Term) -> bool {
+    let Term::Expression(expression) = term else {
+        return false;
+    };
+    let ExpressionContextAll::ExpValueContext(value_context) = expression.as_ref() else {
+        return false;
+    };
+    matches!(
+        value_context.value().as_deref(),
+        Some(ValueContextAll::ValueStringContext(_))
+    )
+}
+
+fn is_number_literal(term: &Term) -> bool {
+    let Term::Expression(expression) = term else {
+        return false;
+    };
+    let ExpressionContextAll::ExpValueContext(value_context) = expression.as_ref() else {
+        return false;
+    };
+    matches!(
+        value_context.value().as_deref(),
+        Some(ValueContextAll::ValueNumberContext(_))
+    )
+}
+
 /// Bandaid enum to allow static type checks that work via dynamic dispatch on C#
 pub(super) enum Term<'input> {
     Expression(Rc<ExpressionContextAll<'input>>),
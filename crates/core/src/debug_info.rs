@@ -0,0 +1,160 @@
+//! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner.Compiler/DebugInfo.cs>
+//!
+//! ## Implementation notes
+//!
+//! Lives in the core crate instead of the compiler so that the runtime can use it to report source positions.
+
+use crate::prelude::*;
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::ops::Range;
+
+/// Contains debug information for a node in a Yarn file.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Default))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct DebugInfo {
+    /// The file that this DebugInfo was produced from.
+    pub file_name: String,
+
+    /// The node that this DebugInfo was produced from.
+    pub node_name: String,
+
+    /// The mapping of instruction numbers to line and character
+    /// information in the file indicated by `file_name`.
+    pub line_positions: HashMap<usize, Option<Position>>,
+
+    /// The statements that instructions were generated for,
+    /// ordered by the first instruction they cover.
+    /// Nested statements, e.g. the body of an `<<if>>`, get entries of their own.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub instructions: Vec<InstructionDebugInfo>,
+}
+
+impl DebugInfo {
+    /// Gets a [`LineInfo`] object that describes the specified instruction
+    /// at the index `instruction_number`.
+    ///
+    /// # Arguments
+    ///
+    /// * `instruction_number`: The index of the instruction to retrieve
+    ///   information for.
+    ///
+    /// # Returns
+    ///
+    /// A [`LineInfo`] object that describes the position of the instruction.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `instruction_number` is greater than the
+    /// number of instructions present in the node.
+    ///
+    /// # See also
+    ///
+    /// * [`DebugInfo::try_get_line_info`] for the fallible version.
+    pub fn get_line_info(&self, instruction_number: usize) -> LineInfo {
+        self.try_get_line_info(instruction_number)
+            .expect("instruction_number out of range")
+    }
+
+    /// Fallible version of [`DebugInfo::get_line_info`].
+    pub fn try_get_line_info(&self, instruction_number: usize) -> Option<LineInfo> {
+        self.line_positions
+            .get(&instruction_number)
+            .map(|position| LineInfo {
+                file_name: self.file_name.clone(),
+                node_name: self.node_name.clone(),
+                position: *position,
+            })
+    }
+}
+
+/// Describes which instructions of a node were generated for a statement.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct InstructionDebugInfo {
+    /// The kind of statement the instructions were generated for.
+    pub kind: StatementKind,
+
+    /// The indices of the instructions that were generated for the statement, including those of nested statements.
+    pub instructions: Range<usize>,
+
+    /// The source text of the statement, without surrounding whitespace.
+    /// For an [`StatementKind::IfCondition`], this is only the condition.
+    pub source_text: String,
+
+    /// For a [`StatementKind::OptionGroup`], the instructions that run when each option is selected, in the order of the options.
+    /// Empty for all other statements.
+    pub option_instructions: Vec<Range<usize>>,
+}
+
+/// The kind of statement described by an [`InstructionDebugInfo`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub enum StatementKind {
+    /// A line of dialogue.
+    Line,
+    /// A `<<set>>` statement.
+    Set,
+    /// The condition of an `<<if>>` or `<<elseif>>` clause.
+    IfCondition,
+    /// A group of options, covering all of its options and their bodies.
+    OptionGroup,
+    /// A single option of an option group, covering the instructions that add it to the presented options.
+    Option,
+    /// A command, including the built-in `<<stop>>`, `<<return>>` and `<<detour>>`.
+    Command,
+    /// A `<<call>>` statement.
+    Call,
+    /// A `<<jump>>` statement.
+    Jump,
+}
+
+/// Contains positional information about an instruction.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct LineInfo {
+    /// The file name of the source that this instruction was produced from.
+    pub file_name: String,
+
+    /// The node name of the source that this instruction was produced from.
+    pub node_name: String,
+
+    /// The zero-indexed position in `file_name` that contains the
+    /// statement or expression that this line was produced from.
+    pub position: Option<Position>,
+}
+
+impl Display for LineInfo {
+    /// Formats the position like compilers do, e.g. `Start.yarn:12:5`, with one-based line and column numbers.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.file_name)?;
+        if let Some(position) = self.position {
+            write!(f, ":{}:{}", position.line + 1, position.character + 1)?;
+        }
+        Ok(())
+    }
+}
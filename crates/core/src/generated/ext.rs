@@ -1,8 +1,14 @@
 //! Contains extensions to generated types that in the original implementation are sprinkled around the repo via partial classes
 
 use crate::prelude::*;
+use prost::Message;
+use std::collections::{BTreeMap, HashSet};
 use std::error::Error;
 use std::fmt::{Debug, Display};
+use std::iter;
+
+/// Separates the name of a node group from the hash identifying one of its nodes, see [`Node::node_group_variant_name`].
+const NODE_GROUP_VARIANT_INFIX: &str = "__variant_";
 
 impl From<String> for Operand {
     fn from(s: String) -> Self {
@@ -112,27 +118,635 @@ impl Display for InvalidOpCodeError {
     }
 }
 
+/// An error that occurred while merging programs with [`Program::combine`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub enum ProgramCombineError {
+    /// No programs were passed, so there was nothing to combine.
+    NoPrograms,
+    /// More than one of the programs contains a node with this name.
+    DuplicateNode(String),
+}
+
+impl Error for ProgramCombineError {}
+
+impl Display for ProgramCombineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoPrograms => f.write_str("Cannot combine an empty list of programs"),
+            Self::DuplicateNode(node_name) => write!(
+                f,
+                "Cannot combine programs because more than one of them contains a node named {node_name}"
+            ),
+        }
+    }
+}
+
+/// An error that occurred while loading a program with [`Program::from_bytes`].
+///
+/// Every variant describes where in the program the problem was found, so that it can be reported to e.g. the author of a mod.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub enum ProgramLoadError {
+    /// The bytes are not a valid protobuf encoding of a program, e.g. because they were truncated.
+    Decode(String),
+    /// A node is stored under a different name than its own.
+    NodeNameMismatch {
+        /// The name the node is stored under in [`Program::nodes`].
+        key: String,
+        /// The name of the node itself.
+        name: String,
+    },
+    /// An instruction has an opcode that is not known.
+    InvalidOpCode {
+        /// The node containing the instruction.
+        node: String,
+        /// The index of the instruction in the node.
+        instruction: usize,
+        /// The unknown opcode.
+        opcode: i32,
+    },
+    /// An instruction lacks operands its opcode needs, or they are of the wrong type.
+    InvalidOperands {
+        /// The node containing the instruction.
+        node: String,
+        /// The index of the instruction in the node.
+        instruction: usize,
+        /// The opcode of the instruction.
+        opcode: OpCode,
+    },
+    /// An instruction jumps to a label that its node does not define.
+    UnknownLabel {
+        /// The node containing the instruction.
+        node: String,
+        /// The index of the instruction in the node.
+        instruction: usize,
+        /// The name of the missing label.
+        label: String,
+    },
+    /// A label points outside of the instructions of its node.
+    LabelOutOfBounds {
+        /// The node defining the label.
+        node: String,
+        /// The name of the label.
+        label: String,
+        /// The instruction index the label points to.
+        target: i32,
+    },
+    /// An instruction runs or detours to a node that is not part of the program.
+    MissingNode {
+        /// The node containing the instruction.
+        node: String,
+        /// The index of the instruction in the node.
+        instruction: usize,
+        /// The name of the missing node.
+        target: String,
+    },
+    /// An initial value of a variable has no value.
+    MissingInitialValue {
+        /// The name of the variable.
+        variable: String,
+    },
+    /// An instruction references a line ID that is not in the string table. See [`Program::validate_line_ids`].
+    MissingLineId {
+        /// The node containing the instruction.
+        node: String,
+        /// The index of the instruction in the node.
+        instruction: usize,
+        /// The missing line ID.
+        line_id: LineId,
+    },
+}
+
+impl Error for ProgramLoadError {}
+
+impl Display for ProgramLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Decode(message) => write!(f, "Failed to decode program: {message}"),
+            Self::NodeNameMismatch { key, name } => {
+                write!(f, "Node {name} is stored under the name {key}")
+            }
+            Self::InvalidOpCode {
+                node,
+                instruction,
+                opcode,
+            } => write!(
+                f,
+                "Instruction {instruction} of node {node} has the invalid opcode {opcode}"
+            ),
+            Self::InvalidOperands {
+                node,
+                instruction,
+                opcode,
+            } => write!(
+                f,
+                "Instruction {instruction} of node {node} has invalid operands for {opcode:?}"
+            ),
+            Self::UnknownLabel {
+                node,
+                instruction,
+                label,
+            } => write!(
+                f,
+                "Instruction {instruction} of node {node} jumps to the unknown label {label}"
+            ),
+            Self::LabelOutOfBounds {
+                node,
+                label,
+                target,
+            } => write!(
+                f,
+                "Label {label} of node {node} points to instruction {target}, which does not exist"
+            ),
+            Self::MissingNode {
+                node,
+                instruction,
+                target,
+            } => write!(
+                f,
+                "Instruction {instruction} of node {node} goes to node {target}, which is not part of the program"
+            ),
+            Self::MissingInitialValue { variable } => {
+                write!(f, "The initial value of variable {variable} is missing")
+            }
+            Self::MissingLineId {
+                node,
+                instruction,
+                line_id,
+            } => write!(
+                f,
+                "Instruction {instruction} of node {node} references line {line_id}, which is not in the string table"
+            ),
+        }
+    }
+}
+
+impl Node {
+    /// The tag of the nodes that hold the expression of a smart variable, e.g. `<<declare $is_rich = $gold > 100>>`.
+    /// Such a node is named after its variable and leaves the variable's value on the stack.
+    /// It is evaluated whenever the variable is read and is never run as part of the dialogue.
+    pub const SMART_VARIABLE_TAG: &'static str = "Yarn.SmartVariable";
+
+    /// Whether this node holds the expression of a smart variable. See [`Node::SMART_VARIABLE_TAG`].
+    pub fn is_smart_variable(&self) -> bool {
+        self.tags.iter().any(|tag| tag == Self::SMART_VARIABLE_TAG)
+    }
+
+    /// The tag of the nodes that the compiler generates for node groups, i.e. nodes sharing a title that each have `when:` headers.
+    /// Such a node is named after the group and runs the most salient of the group's nodes, which are renamed by [`Node::node_group_variant_name`].
+    pub const NODE_GROUP_TAG: &'static str = "Yarn.NodeGroup";
+
+    /// Whether this node was generated to run one of the nodes of a node group. See [`Node::NODE_GROUP_TAG`].
+    pub fn is_node_group(&self) -> bool {
+        self.tags.iter().any(|tag| tag == Self::NODE_GROUP_TAG)
+    }
+
+    /// Returns the name that the compiler gives a node of a node group, derived from the name of the file the node is in
+    /// and the text of its `when:` headers. Adding, removing or reordering the other nodes of the group thus doesn't rename it,
+    /// which would change its line IDs and visit count. `occurrence` tells apart nodes of a group that share both,
+    /// counted from one in source order.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use yarnspinner_core::prelude::*;
+    /// let name = Node::node_group_variant_name("Greeting", "greetings.yarn", &["$met_before"], 1);
+    /// assert!(name.starts_with("Greeting__variant_"));
+    /// assert_ne!(name, Node::node_group_variant_name("Greeting", "greetings.yarn", &["$met_before"], 2));
+    /// assert_ne!(name, Node::node_group_variant_name("Greeting", "greetings.yarn", &["always"], 1));
+    /// ```
+    pub fn node_group_variant_name(
+        group_name: &str,
+        file_name: &str,
+        conditions: &[&str],
+        occurrence: usize,
+    ) -> String {
+        let occurrence = (occurrence > 1).then(|| occurrence.to_string());
+        let parts = iter::once(file_name)
+            .chain(conditions.iter().copied())
+            .chain(occurrence.as_deref());
+        // Each part is followed by a zero byte so that moving text between them changes the hash
+        let hash = fnv1a_hash(parts.flat_map(|part| part.bytes().chain(iter::once(0))));
+        format!("{group_name}{NODE_GROUP_VARIANT_INFIX}{hash:016x}")
+    }
+
+    /// Returns the name of the node group that a node named by [`Node::node_group_variant_name`] belongs to.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use yarnspinner_core::prelude::*;
+    /// let name = Node::node_group_variant_name("Greeting", "greetings.yarn", &["$met_before"], 1);
+    /// assert_eq!(Some("Greeting"), Node::node_group_of_variant(&name));
+    /// assert_eq!(None, Node::node_group_of_variant("Greeting"));
+    /// ```
+    pub fn node_group_of_variant(name: &str) -> Option<&str> {
+        let (group_name, hash) = name.rsplit_once(NODE_GROUP_VARIANT_INFIX)?;
+        let is_hash = hash.len() == 16 && hash.chars().all(|c| c.is_ascii_hexdigit());
+        is_hash.then_some(group_name)
+    }
+
+    /// Separates the namespaces of a node from its own name, e.g. in `Chapter1.Intro`.
+    pub const NAMESPACE_SEPARATOR: char = '.';
+
+    /// Returns the names that a reference to a node from within the node named `from` could mean, most specific first.
+    ///
+    /// A reference is looked up in the namespace of `from`, then in each namespace enclosing it, and finally as a fully-qualified name.
+    /// A node outside of any namespace can thus only reference other nodes by their fully-qualified name.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use yarnspinner_core::prelude::*;
+    /// let candidates: Vec<_> = Node::candidate_names("Outro", "Chapter1.Part2.Intro").collect();
+    /// assert_eq!(vec!["Chapter1.Part2.Outro", "Chapter1.Outro", "Outro"], candidates);
+    ///
+    /// let candidates: Vec<_> = Node::candidate_names("Outro", "Start").collect();
+    /// assert_eq!(vec!["Outro"], candidates);
+    /// ```
+    pub fn candidate_names<'a>(
+        reference: &'a str,
+        from: &'a str,
+    ) -> impl Iterator<Item = String> + 'a {
+        let parent_namespace = |name: &'a str| {
+            name.rsplit_once(Self::NAMESPACE_SEPARATOR)
+                .map(|(namespace, _)| namespace)
+        };
+        std::iter::successors(parent_namespace(from), move |&namespace| {
+            parent_namespace(namespace)
+        })
+        .map(move |namespace| format!("{namespace}{}{reference}", Self::NAMESPACE_SEPARATOR))
+        .chain(std::iter::once(reference.to_owned()))
+    }
+}
+
 impl Program {
+    /// Returns the node holding the expression of the smart variable with the given name, e.g. `$is_rich`,
+    /// or [`None`] if the variable is not a smart variable. See [`Node::SMART_VARIABLE_TAG`].
+    pub fn smart_variable(&self, variable_name: &str) -> Option<&Node> {
+        self.nodes
+            .get(variable_name)
+            .filter(|node| node.is_smart_variable())
+    }
+
+    /// Returns the names of all nodes in the program, sorted alphabetically.
+    /// The nodes holding the expressions of smart variables are not included.
+    ///
+    /// Node names are unique, since [`Program::combine`] refuses to merge programs that contain a node with the same name.
+    pub fn node_names(&self) -> Vec<String> {
+        let mut names: Vec<_> = self
+            .nodes
+            .values()
+            .filter(|node| !node.is_smart_variable())
+            .map(|node| node.name.clone())
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Returns the names of all nodes that have the given tag in their `tags` header, sorted alphabetically.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use yarnspinner_core::prelude::*;
+    /// let mut program = Program::default();
+    /// for (name, tags) in [("Shop", vec!["hub"]), ("Start", vec![]), ("Tavern", vec!["hub", "night"])] {
+    ///     let node = Node {
+    ///         name: name.to_owned(),
+    ///         tags: tags.into_iter().map(str::to_owned).collect(),
+    ///         ..Default::default()
+    ///     };
+    ///     program.nodes.insert(name.to_owned(), node);
+    /// }
+    /// assert_eq!(vec!["Shop", "Tavern"], program.nodes_with_tag("hub"));
+    /// ```
+    pub fn nodes_with_tag(&self, tag: &str) -> Vec<String> {
+        let mut names: Vec<_> = self
+            .nodes
+            .values()
+            .filter(|node| node.tags.iter().any(|node_tag| node_tag == tag))
+            .map(|node| node.name.clone())
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Returns the name of the node that a reference from within the node named `from` resolves to,
+    /// or [`None`] if no such node exists. See [`Node::candidate_names`] for how references are resolved.
+    pub fn resolve_node_name(&self, reference: &str, from: &str) -> Option<String> {
+        Node::candidate_names(reference, from).find(|name| self.nodes.contains_key(name))
+    }
+
     /// Creates a new Program by merging multiple Programs together.
     ///
     /// The new program will contain every node from every input program.
-    /// Returns [`None`] if the input is empty.
-    pub fn combine(programs: Vec<Program>) -> Option<Self> {
+    /// Returns an error if the input is empty or if a node name occurs in more than one program.
+    pub fn combine(programs: Vec<Program>) -> Result<Self, ProgramCombineError> {
         if programs.is_empty() {
-            return None;
+            return Err(ProgramCombineError::NoPrograms);
         }
         let mut output = Program::default();
         for program in programs {
             for (node_name, node) in program.nodes {
-                assert!(
-                    !output.nodes.contains_key(&node_name),
-                    "This program already contains a node named {node_name}",
-                );
+                if output.nodes.contains_key(&node_name) {
+                    return Err(ProgramCombineError::DuplicateNode(node_name));
+                }
                 output.nodes.insert(node_name, node);
             }
             output.initial_values.extend(program.initial_values);
         }
-        Some(output)
+        Ok(output)
+    }
+
+    /// Loads a program from its protobuf encoding, e.g. a compiled Yarn file shipped by a mod.
+    ///
+    /// Unlike decoding the bytes directly, this validates the program so that running it cannot panic because of malformed instructions:
+    /// every instruction must have a known opcode and the operands it needs, every label it jumps to must exist and lie within its node,
+    /// and every node it runs or detours to by name must be part of the program.
+    /// Since the string table is not part of a program, check the line IDs it references with [`Program::validate_line_ids`] once it is loaded.
+    /// Whether the instructions find the values they need on the stack depends on the path taken through a node,
+    /// so that is only checked while running them: the dialogue fails with an error instead.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use yarnspinner_core::prelude::*;
+    /// let bytes = Program::default().to_bytes();
+    /// assert!(Program::from_bytes(&bytes).is_ok());
+    /// assert!(Program::from_bytes(&[0xff, 0xff]).is_err());
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProgramLoadError> {
+        let program =
+            Self::decode(bytes).map_err(|error| ProgramLoadError::Decode(error.to_string()))?;
+        program.validate()?;
+        Ok(program)
+    }
+
+    /// Returns the protobuf encoding of the program, which can be loaded again with [`Program::from_bytes`].
+    ///
+    /// Unlike encoding the program directly, which writes the entries of its maps in the random iteration order of a [`HashMap`](std::collections::HashMap),
+    /// this sorts them by key. Encoding the same program therefore always produces the same bytes, which keeps build caches and diffs of compiled files useful.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        SortedProgram::from(self).encode_to_vec()
+    }
+
+    /// Returns the [`fnv1a_hash`] of [`Program::to_bytes`], which identifies the compiled content of the program.
+    /// It is stable across platforms and Rust versions, so it can be stored in save files to detect that a different version of the program was loaded since.
+    pub fn checksum(&self) -> u64 {
+        fnv1a_hash(self.to_bytes())
+    }
+
+    /// Checks that every line referenced by the program's instructions is one of the given line IDs,
+    /// usually the keys of the string table that was compiled along with the program.
+    pub fn validate_line_ids<'a>(
+        &self,
+        line_ids: impl IntoIterator<Item = &'a LineId>,
+    ) -> Result<(), ProgramLoadError> {
+        let line_ids: HashSet<_> = line_ids.into_iter().collect();
+        for (node_name, node) in self.sorted_nodes() {
+            for (index, instruction) in node.instructions.iter().enumerate() {
+                let Ok(OpCode::RunLine | OpCode::AddOption | OpCode::AddSaliencyCandidate) =
+                    OpCode::try_from(instruction.opcode)
+                else {
+                    continue;
+                };
+                let Some(line_id) = string_operand(instruction, 0) else {
+                    continue;
+                };
+                let line_id = LineId(line_id);
+                if !line_ids.contains(&line_id) {
+                    return Err(ProgramLoadError::MissingLineId {
+                        node: node_name.clone(),
+                        instruction: index,
+                        line_id,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), ProgramLoadError> {
+        let mut variables: Vec<_> = self.initial_values.iter().collect();
+        variables.sort_by_key(|(variable, _)| *variable);
+        if let Some((variable, _)) = variables.iter().find(|(_, value)| value.value.is_none()) {
+            return Err(ProgramLoadError::MissingInitialValue {
+                variable: variable.to_string(),
+            });
+        }
+        for (node_name, node) in self.sorted_nodes() {
+            if *node_name != node.name {
+                return Err(ProgramLoadError::NodeNameMismatch {
+                    key: node_name.clone(),
+                    name: node.name.clone(),
+                });
+            }
+            let mut labels: Vec<_> = node.labels.iter().collect();
+            labels.sort_by_key(|(label, _)| *label);
+            if let Some((label, target)) = labels.into_iter().find(|(_, target)| {
+                usize::try_from(**target).map_or(true, |target| target >= node.instructions.len())
+            }) {
+                return Err(ProgramLoadError::LabelOutOfBounds {
+                    node: node_name.clone(),
+                    label: label.clone(),
+                    target: *target,
+                });
+            }
+            for (index, instruction) in node.instructions.iter().enumerate() {
+                self.validate_instruction(node, index, instruction)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_instruction(
+        &self,
+        node: &Node,
+        index: usize,
+        instruction: &Instruction,
+    ) -> Result<(), ProgramLoadError> {
+        use OperandKind::*;
+        let opcode =
+            OpCode::try_from(instruction.opcode).map_err(|_| ProgramLoadError::InvalidOpCode {
+                node: node.name.clone(),
+                instruction: index,
+                opcode: instruction.opcode,
+            })?;
+        let expected_operands: &[OperandKind] = match opcode {
+            OpCode::Jump
+            | OpCode::ShowOptions
+            | OpCode::PushNull
+            | OpCode::Pop
+            | OpCode::Stop
+            | OpCode::RunNode
+            | OpCode::DetourToNode
+            | OpCode::Return
+            | OpCode::SelectSaliencyCandidate => &[],
+            OpCode::JumpTo
+            | OpCode::PushString
+            | OpCode::JumpIfFalse
+            | OpCode::CallFunc
+            | OpCode::PushVariable
+            | OpCode::StoreVariable => &[String],
+            OpCode::RunLine | OpCode::RunCommand => &[String, Float],
+            OpCode::AddOption => &[String, String, Float, Bool],
+            OpCode::PushFloat => &[Float],
+            OpCode::PushBool => &[Bool],
+            OpCode::AddSaliencyCandidate => &[String, Float, String],
+        };
+        let has_expected_operands = instruction.operands.len() >= expected_operands.len()
+            && expected_operands
+                .iter()
+                .zip(&instruction.operands)
+                .all(|(kind, operand)| kind.matches(operand));
+        if !has_expected_operands {
+            return Err(ProgramLoadError::InvalidOperands {
+                node: node.name.clone(),
+                instruction: index,
+                opcode,
+            });
+        }
+
+        let label = match opcode {
+            OpCode::JumpTo | OpCode::JumpIfFalse => string_operand(instruction, 0),
+            OpCode::AddOption => string_operand(instruction, 1),
+            OpCode::AddSaliencyCandidate => string_operand(instruction, 2),
+            _ => None,
+        };
+        if let Some(label) = label.filter(|label| !node.labels.contains_key(label)) {
+            return Err(ProgramLoadError::UnknownLabel {
+                node: node.name.clone(),
+                instruction: index,
+                label,
+            });
+        }
+
+        // Node names are usually pushed right before running or detouring to them,
+        // but they can also be computed at runtime, in which case they can't be checked here.
+        if matches!(opcode, OpCode::RunNode | OpCode::DetourToNode) {
+            let target = index
+                .checked_sub(1)
+                .and_then(|previous| node.instructions.get(previous))
+                .filter(|previous| previous.opcode == OpCode::PushString as i32)
+                .and_then(|previous| string_operand(previous, 0));
+            if let Some(target) = target.filter(|target| !self.nodes.contains_key(target)) {
+                return Err(ProgramLoadError::MissingNode {
+                    node: node.name.clone(),
+                    instruction: index,
+                    target,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// The nodes sorted by name, so that validation errors are reported deterministically.
+    fn sorted_nodes(&self) -> Vec<(&String, &Node)> {
+        let mut nodes: Vec<_> = self.nodes.iter().collect();
+        nodes.sort_by_key(|(name, _)| *name);
+        nodes
+    }
+}
+
+/// Has the same encoding as [`Program`], but with maps whose iteration order is deterministic.
+#[derive(Clone, PartialEq, Message)]
+struct SortedProgram {
+    #[prost(string, tag = "1")]
+    name: String,
+    #[prost(btree_map = "string, message", tag = "2")]
+    nodes: BTreeMap<String, SortedNode>,
+    #[prost(btree_map = "string, message", tag = "3")]
+    initial_values: BTreeMap<String, Operand>,
+}
+
+/// Has the same encoding as [`Node`], but with maps whose iteration order is deterministic.
+#[derive(Clone, PartialEq, Message)]
+struct SortedNode {
+    #[prost(string, tag = "1")]
+    name: String,
+    #[prost(message, repeated, tag = "2")]
+    instructions: Vec<Instruction>,
+    #[prost(btree_map = "string, int32", tag = "3")]
+    labels: BTreeMap<String, i32>,
+    #[prost(string, repeated, tag = "4")]
+    tags: Vec<String>,
+    #[prost(string, tag = "5")]
+    source_text_string_id: String,
+    #[prost(message, repeated, tag = "6")]
+    headers: Vec<Header>,
+}
+
+impl From<&Program> for SortedProgram {
+    fn from(program: &Program) -> Self {
+        Self {
+            name: program.name.clone(),
+            nodes: program
+                .nodes
+                .iter()
+                .map(|(name, node)| (name.clone(), SortedNode::from(node)))
+                .collect(),
+            initial_values: program
+                .initial_values
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl From<&Node> for SortedNode {
+    fn from(node: &Node) -> Self {
+        Self {
+            name: node.name.clone(),
+            instructions: node.instructions.clone(),
+            labels: node
+                .labels
+                .iter()
+                .map(|(label, index)| (label.clone(), *index))
+                .collect(),
+            tags: node.tags.clone(),
+            source_text_string_id: node.source_text_string_id.clone(),
+            headers: node.headers.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum OperandKind {
+    String,
+    Float,
+    Bool,
+}
+
+impl OperandKind {
+    fn matches(self, operand: &Operand) -> bool {
+        matches!(
+            (self, &operand.value),
+            (Self::String, Some(OperandValue::StringValue(_)))
+                | (Self::Float, Some(OperandValue::FloatValue(_)))
+                | (Self::Bool, Some(OperandValue::BoolValue(_)))
+        )
+    }
+}
+
+fn string_operand(instruction: &Instruction, index: usize) -> Option<String> {
+    match &instruction.operands.get(index)?.value {
+        Some(OperandValue::StringValue(value)) => Some(value.clone()),
+        _ => None,
     }
 }
 
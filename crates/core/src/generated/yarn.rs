@@ -125,6 +125,8 @@ pub mod instruction {
         /// - opD = bool: whether the option has a condition on it (in which
         ///    case a value should be popped off the stack and used to signal
         ///    the game that the option should be not available)
+        /// - opE = string (optional): the node that the option's content jumps to,
+        ///    if it jumps to one
         AddOption = 4,
         /// Presents the current list of options to the client, then clears
         /// the list. The most recently selected option will be on the top
@@ -169,6 +171,27 @@ pub mod instruction {
         /// that name.
         /// No operands.
         RunNode = 16,
+        /// Pops a string off the top of the stack, and runs the node with
+        /// that name. When that node returns, execution resumes at the
+        /// instruction after this one.
+        /// No operands.
+        DetourToNode = 17,
+        /// Returns from the current node to the node that detoured into it,
+        /// or stops execution of the program if there is no such node.
+        /// No operands.
+        Return = 18,
+        /// Pops a bool off the stack and, if it is true, adds a candidate
+        /// to the list of saliency candidates (see SelectSaliencyCandidate).
+        /// - opA = string: string ID of the candidate's line
+        /// - opB = float: the complexity of the candidate's condition
+        /// - opC = string: destination to go to if this candidate is selected
+        AddSaliencyCandidate = 19,
+        /// Selects the candidate with the most complex condition from the
+        /// list of saliency candidates, picking randomly among ties, then
+        /// clears the list. Pushes the selected candidate's destination and
+        /// true onto the stack, or only false if there were no candidates.
+        /// No operands.
+        SelectSaliencyCandidate = 20,
     }
     impl OpCode {
         /// String value of the enum field names used in the ProtoBuf definition.
@@ -194,6 +217,10 @@ pub mod instruction {
                 OpCode::StoreVariable => "STORE_VARIABLE",
                 OpCode::Stop => "STOP",
                 OpCode::RunNode => "RUN_NODE",
+                OpCode::DetourToNode => "DETOUR_TO_NODE",
+                OpCode::Return => "RETURN",
+                OpCode::AddSaliencyCandidate => "ADD_SALIENCY_CANDIDATE",
+                OpCode::SelectSaliencyCandidate => "SELECT_SALIENCY_CANDIDATE",
             }
         }
         /// Creates an enum from field names used in the ProtoBuf definition.
@@ -216,6 +243,10 @@ pub mod instruction {
                 "STORE_VARIABLE" => Some(Self::StoreVariable),
                 "STOP" => Some(Self::Stop),
                 "RUN_NODE" => Some(Self::RunNode),
+                "DETOUR_TO_NODE" => Some(Self::DetourToNode),
+                "RETURN" => Some(Self::Return),
+                "ADD_SALIENCY_CANDIDATE" => Some(Self::AddSaliencyCandidate),
+                "SELECT_SALIENCY_CANDIDATE" => Some(Self::SelectSaliencyCandidate),
                 _ => None,
             }
         }
@@ -0,0 +1,21 @@
+//! Not part of the original implementation.
+
+/// The 64-bit FNV-1a hash of the given bytes.
+///
+/// Unlike [`std::hash::DefaultHasher`], its output is guaranteed to be the same across platforms and Rust versions,
+/// so it can be used for values that are stored or shared, such as generated line IDs and [`Program::checksum`](crate::prelude::Program::checksum).
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_core::prelude::*;
+/// assert_eq!(0xcbf2_9ce4_8422_2325, fnv1a_hash([]));
+/// assert_eq!(fnv1a_hash("Yarn".bytes()), fnv1a_hash(*b"Yarn"));
+/// ```
+pub fn fnv1a_hash(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    bytes.into_iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
@@ -6,14 +6,17 @@
 //! - If you wish to write an adapter crate for an engine yourself, use the [`yarnspinner`](https://crates.io/crates/yarnspinner) crate.
 
 #![warn(missing_docs, missing_debug_implementations)]
+mod debug_info;
 mod feature_gates;
 mod generated;
+mod hash;
 mod internal_value;
 mod library;
 mod line_id;
 mod operator;
 mod position;
 pub mod types;
+mod wrap_hints;
 mod yarn_fn;
 mod yarn_value;
 
@@ -23,16 +26,19 @@ pub mod prelude {
     pub use crate::feature_gates::*;
 
     pub use crate::{
+        debug_info::*,
         generated::{
             instruction::OpCode, operand::Value as OperandValue, Header, Instruction,
-            InvalidOpCodeError, Node, Operand, Program,
+            InvalidOpCodeError, Node, Operand, Program, ProgramCombineError, ProgramLoadError,
         },
+        hash::*,
         internal_value::*,
         library::*,
         line_id::*,
         operator::*,
         position::*,
-        types::Type,
+        types::{EnumCase, EnumType, FunctionType, Type},
+        wrap_hints::*,
         yarn_fn::*,
         yarn_value::*,
     };
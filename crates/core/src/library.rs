@@ -2,21 +2,28 @@
 
 use crate::prelude::*;
 use std::borrow::Cow;
-use std::collections::hash_map;
+use std::collections::{hash_map, HashSet};
 use std::fmt::Display;
 
 /// A collection of functions that can be called from Yarn scripts.
 ///
 /// Can be conveniently created with the [`yarn_library!`] macro.
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
-pub struct Library(YarnFnRegistry);
+pub struct Library {
+    functions: YarnFnRegistry,
+    /// The functions that are not called by the runtime, but completed by the game later on.
+    deferred_functions: HashSet<Cow<'static, str>>,
+}
 
 impl Extend<<YarnFnRegistry as IntoIterator>::Item> for Library {
     fn extend<T: IntoIterator<Item = (Cow<'static, str>, Box<dyn UntypedYarnFn>)>>(
         &mut self,
         iter: T,
     ) {
-        self.0.extend(iter);
+        for (name, function) in iter {
+            self.deferred_functions.remove(&name);
+            self.functions.add_boxed(name, function);
+        }
     }
 }
 
@@ -25,7 +32,7 @@ impl IntoIterator for Library {
     type IntoIter = hash_map::IntoIter<Cow<'static, str>, Box<dyn UntypedYarnFn>>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+        self.functions.into_iter()
     }
 }
 
@@ -35,6 +42,37 @@ impl Library {
         Self::default()
     }
 
+    /// Adds the functions that depend on the state of a running dialogue:
+    /// - `visited`: Returns whether the node with the given name was visited.
+    /// - `visited_count`: Returns how often the node with the given name was visited.
+    /// - `random`: Returns a random number between `0` and `1`.
+    /// - `random_range`: Returns a random number between the two given numbers.
+    /// - `dice`: Returns a random integer between `1` and the given number of sides.
+    ///
+    /// The runtime's `Dialogue` adds its implementations, while the compiler adds placeholders so that calls to them are type checked
+    /// even if it was not given the dialogue's library. Their names and signatures are defined here for both.
+    pub fn add_dialogue_functions<Visited, VisitedCount, Random, RandomRange, Dice>(
+        &mut self,
+        visited: Visited,
+        visited_count: VisitedCount,
+        random: Random,
+        random_range: RandomRange,
+        dice: Dice,
+    ) -> &mut Self
+    where
+        Visited: YarnFn<fn(String) -> bool, Out = bool> + 'static + Clone,
+        VisitedCount: YarnFn<fn(String) -> f32, Out = f32> + 'static + Clone,
+        Random: YarnFn<fn() -> f32, Out = f32> + 'static + Clone,
+        RandomRange: YarnFn<fn(f32, f32) -> f32, Out = f32> + 'static + Clone,
+        Dice: YarnFn<fn(u32) -> u32, Out = u32> + 'static + Clone,
+    {
+        self.add_function("visited", visited)
+            .add_function("visited_count", visited_count)
+            .add_function("random", random)
+            .add_function("random_range", random_range)
+            .add_function("dice", dice)
+    }
+
     /// Loads functions from another [`Library`].
     ///
     /// Will overwrite any functions that have the same name.
@@ -43,17 +81,21 @@ impl Library {
     ///
     /// The original implementation throws an exception if a function with the same name already exists.
     pub fn import(&mut self, other: Self) {
-        self.0.extend(other.0 .0);
+        for name in other.functions.names() {
+            self.deferred_functions.remove(name);
+        }
+        self.functions.extend(other.functions.0);
+        self.deferred_functions.extend(other.deferred_functions);
     }
 
     /// Iterates over the names and functions in the library.
     pub fn iter(&self) -> impl Iterator<Item = (&str, &(dyn UntypedYarnFn))> {
-        self.0.iter()
+        self.functions.iter()
     }
 
     /// Gets a function by name.
     pub fn get(&self, name: &str) -> Option<&(dyn UntypedYarnFn)> {
-        self.0.get(name)
+        self.functions.get(name)
     }
 
     /// Generates a unique tracking variable name.
@@ -64,19 +106,68 @@ impl Library {
         format!("$Yarn.Internal.Visiting.{node_name}")
     }
 
+    /// Generates the name of the variable that tracks whether a `<<once>>` block
+    /// or option of the given node has already run. `index` is its position among those of the node, counted in source order.
+    pub fn generate_unique_once_variable(node_name: &str, index: usize) -> String {
+        format!("$Yarn.Internal.Once.{node_name}.{index}")
+    }
+
+    /// Generates the name of the variable that tracks how often the line of a line group or the node of a node group
+    /// with the given content ID was picked.
+    pub fn generate_unique_view_count_variable(content_id: &str) -> String {
+        format!("$Yarn.Internal.ViewCount.{content_id}")
+    }
+
+    /// Generates the name of the variable that stores the value of [`Library::SALIENCY_PICK_COUNT_VARIABLE`]
+    /// at the time the line or node with the given content ID was last picked.
+    pub fn generate_unique_last_viewed_variable(content_id: &str) -> String {
+        format!("$Yarn.Internal.LastViewed.{content_id}")
+    }
+
+    /// The name of the variable that counts how often any line group or node group picked one of its candidates.
+    pub const SALIENCY_PICK_COUNT_VARIABLE: &'static str = "$Yarn.Internal.SaliencyPickCount";
+
     /// Creates a [`Library`] with the standard functions that are included in Yarn Spinner.
     /// These are:
-    /// - `string`: Converts a value to a string.
-    /// - `number`: Converts a value to a number.
-    /// - `bool`: Converts a value to a boolean.
+    /// - `string`: Converts a value to a string, e.g. `"3.5"` for `3.5` and `"true"` for `true`.
+    /// - `number`: Converts a value to a number. Strings are parsed, e.g. `"3.5"` becomes `3.5`, and booleans become `1` or `0`.
+    ///   Running the dialogue fails if a string is not a number, e.g. `"abc"`.
+    /// - `bool`: Converts a value to a boolean. Numbers other than `0` are `true`, and the strings `"true"` and `"false"` are parsed.
+    ///   Running the dialogue fails for any other string.
+    /// - `round`: Rounds a number to the nearest integer, rounding half-way cases away from zero.
+    /// - `round_places`: Rounds a number to the given number of decimal places.
+    /// - `floor`: Rounds a number down to the nearest integer.
+    /// - `ceil`: Rounds a number up to the nearest integer.
+    /// - `inc`: Rounds a number up to the nearest integer, or adds one if it already is an integer.
+    /// - `dec`: Rounds a number down to the nearest integer, or subtracts one if it already is an integer.
+    /// - `decimal`: Returns the fractional part of a number, e.g. `0.5` for `1.5` and `-0.5` for `-1.5`.
+    /// - `int`: Returns the integer part of a number, i.e. truncates it toward zero.
     /// - Comparison operators for numbers, strings, and booleans. (`==`, `!=`, `<`, `<=`, `>`, `>=`)
+    /// - Arithmetic operators for numbers and integers.
+    ///
+    /// The functions that depend on the state of a running dialogue, i.e. `visited`, `visited_count`, `random`, `random_range` and `dice`,
+    /// are added by the runtime's `Dialogue` instead, see [`Library::add_dialogue_functions`].
     pub fn standard_library() -> Self {
         let mut library = yarn_library!(
             "string" => <String as From<YarnValue >>::from,
             "number" => |value: YarnValue| f32::try_from(value).expect("Failed to convert a Yarn value to a number"),
             "bool" => |value: YarnValue| bool::try_from(value).expect("Failed to convert a Yarn value to a bool"),
+            "round" => f32::round,
+            "round_places" => round_places,
+            "floor" => f32::floor,
+            "ceil" => f32::ceil,
+            "inc" => inc,
+            "dec" => dec,
+            "decimal" => f32::fract,
+            "int" => f32::trunc,
         );
-        for r#type in [Type::Number, Type::String, Type::Boolean] {
+        for r#type in [
+            Type::Number,
+            Type::Integer,
+            Type::String,
+            Type::Boolean,
+            Type::Enum(EnumType::default()),
+        ] {
             library.add_methods(r#type);
         }
         library
@@ -118,37 +209,115 @@ impl Library {
         F: YarnFn<Marker> + 'static + Clone,
         F::Out: IntoYarnValueFromNonYarnValue + 'static + Clone,
     {
-        self.0.register_function(name, function);
+        let name = name.into();
+        self.deferred_functions.remove(&name);
+        self.functions.register_function(name, function);
         self
     }
 
+    /// Adds a function that is not called by the runtime. Instead, calling it suspends the dialogue and
+    /// asks the game for the return value, which is passed back via `Dialogue::complete_function`.
+    /// Use this for functions that may take a long time, such as a lookup in a database, so that they can run in the background.
+    ///
+    /// The `signature` is never called. It only declares the parameter and return types,
+    /// so that calls to the function can be type checked.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use yarnspinner_core::prelude::*;
+    /// # let mut library = Library::default();
+    /// library.add_deferred_function("lookup_price", |_item: String| -> f32 {
+    ///     unreachable!("Deferred functions are completed by the game")
+    /// });
+    /// assert!(library.is_deferred("lookup_price"));
+    /// ```
+    pub fn add_deferred_function<Marker, F>(
+        &mut self,
+        name: impl Into<Cow<'static, str>>,
+        signature: F,
+    ) -> &mut Self
+    where
+        Marker: 'static,
+        F: YarnFn<Marker> + 'static + Clone,
+        F::Out: IntoYarnValueFromNonYarnValue + 'static + Clone,
+    {
+        let name = name.into();
+        self.functions.register_function(name.clone(), signature);
+        self.deferred_functions.insert(name);
+        self
+    }
+
+    /// Adds an implementation of an operator for a type, e.g. [`Operator::Subtract`] for [`Type::String`].
+    /// When this library is passed to the compiler, expressions using the operator on values of this type will type-check,
+    /// and when it is passed to the dialogue, the runtime will call the function to evaluate them.
+    ///
+    /// Just like the built-in operators, all operands must be of the given type.
+    /// Arithmetic operators must return that type as well, while comparisons and logical operators must return a [`bool`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use yarnspinner_core::prelude::*;
+    /// # let mut library = Library::default();
+    /// library.add_operator(Type::String, Operator::Subtract, |a: String, b: String| {
+    ///     a.replace(&b, "")
+    /// });
+    /// assert!(library.has_method(&Type::String, "Subtract"));
+    /// ```
+    pub fn add_operator<Marker, F>(
+        &mut self,
+        r#type: Type,
+        operator: Operator,
+        function: F,
+    ) -> &mut Self
+    where
+        Marker: 'static,
+        F: YarnFn<Marker> + 'static + Clone,
+        F::Out: IntoYarnValueFromNonYarnValue + 'static + Clone,
+    {
+        let name = r#type.get_canonical_name_for_method(&operator.to_string());
+        self.add_function(name, function)
+    }
+
+    /// Returns `true` if the given type has a method by that name, either built into the type itself or added with [`Library::add_operator`].
+    pub fn has_method(&self, r#type: &Type, name: &str) -> bool {
+        r#type.has_method(name)
+            || self.contains_function(&r#type.get_canonical_name_for_method(name))
+    }
+
+    /// Returns `true` if the function with the given name was added with [`Library::add_deferred_function`].
+    pub fn is_deferred(&self, name: &str) -> bool {
+        self.deferred_functions.contains(name)
+    }
+
     /// Returns `true` if the library contains a function with the given name.
     pub fn contains_function(&self, name: &str) -> bool {
-        self.0.contains_function(name)
+        self.functions.contains_function(name)
     }
 
     /// Iterates over the names of all functions in the library.
     pub fn names(&self) -> impl Iterator<Item = &str> {
-        self.0.names()
+        self.functions.names()
     }
 
     /// Iterates over all functions in the library.
     pub fn functions(&self) -> impl Iterator<Item = &(dyn UntypedYarnFn)> {
-        self.0.functions()
+        self.functions.functions()
     }
 
     /// Registers the methods found inside a type.
     fn add_methods(&mut self, r#type: Type) {
         for (name, function) in r#type.methods().into_iter() {
             let canonical_name = r#type.get_canonical_name_for_method(name.as_ref());
-            self.0.add_boxed(canonical_name, function.clone());
+            self.functions.add_boxed(canonical_name, function.clone());
         }
     }
 }
 
 impl Display for Library {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut functions: Vec<_> = self.0.iter().collect();
+        let mut functions: Vec<_> = self.functions.iter().collect();
         functions.sort_by_key(|(name, _)| name.to_string());
         writeln!(f, "{{")?;
         for (name, function) in functions {
@@ -159,6 +328,27 @@ impl Display for Library {
     }
 }
 
+fn round_places(number: f32, places: i32) -> f32 {
+    let factor = 10_f32.powi(places);
+    (number * factor).round() / factor
+}
+
+fn inc(number: f32) -> f32 {
+    if number.fract() == 0.0 {
+        number + 1.0
+    } else {
+        number.ceil()
+    }
+}
+
+fn dec(number: f32) -> f32 {
+    if number.fract() == 0.0 {
+        number - 1.0
+    } else {
+        number.floor()
+    }
+}
+
 /// Create a [`Library`] from a list of named functions.
 ///
 /// ## Example
@@ -2,11 +2,13 @@
 //! ## Implementation Notes
 //! - `IBridgeableType` is not implemented because it is not actually used anywhere.
 
-pub use {function::*, r#type::*, type_util::*};
+pub use {function::*, r#enum::*, r#type::*, type_util::*};
 
 mod any;
 mod boolean;
+mod r#enum;
 mod function;
+mod integer;
 mod number;
 mod string;
 mod r#type;
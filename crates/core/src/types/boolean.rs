@@ -2,17 +2,17 @@
 
 use crate::prelude::*;
 use crate::types::TypeProperties;
-use std::ops::*;
+use crate::yarn_value::into_operand_type;
 
 /// A type that bridges to [`bool`]
 pub(crate) fn boolean_type_properties() -> TypeProperties {
     TypeProperties::from_name("Bool").with_methods(yarn_library! {
-        Operator::EqualTo => <RustType as PartialEq>::eq,
-        Operator::NotEqualTo => <RustType as PartialEq>::ne,
-        Operator::And => <RustType as BitAnd>::bitand,
-        Operator::Or => <RustType as BitOr>::bitor,
-        Operator::Xor => <RustType as BitXor>::bitxor,
-        Operator::Not => RustType::not,
+        Operator::EqualTo => |a: RustType, b: RustType| YarnValue::from(a) == YarnValue::from(b),
+        Operator::NotEqualTo => |a: RustType, b: RustType| YarnValue::from(a) != YarnValue::from(b),
+        Operator::And => |a: RustType, b: RustType| -> RustType { into_operand_type(YarnValue::from(a) & b) },
+        Operator::Or => |a: RustType, b: RustType| -> RustType { into_operand_type(YarnValue::from(a) | b) },
+        Operator::Xor => |a: RustType, b: RustType| -> RustType { into_operand_type(YarnValue::from(a) ^ b) },
+        Operator::Not => |a: RustType| -> RustType { into_operand_type(!YarnValue::from(a)) },
     })
 }
 
@@ -0,0 +1,125 @@
+//! Not part of the original implementation at the version the rest of this crate is adapted from, which predates enums.
+
+use crate::prelude::*;
+use crate::types::{Type, TypeProperties};
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+
+/// Enums can only be compared for equality.
+pub(crate) fn enum_type_properties(enum_type: &EnumType) -> TypeProperties {
+    TypeProperties::from_name("Enum")
+        .with_description(format!("Enum {}", enum_type.name))
+        .with_methods(yarn_library! {
+            Operator::EqualTo => |a: YarnValue, b: YarnValue| a == b,
+            Operator::NotEqualTo => |a: YarnValue, b: YarnValue| a != b,
+        })
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Default, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+/// A type that represents an enum declared in a Yarn script, e.g.
+/// ```yarn
+/// <<enum Food>>
+///     <<case Apple>>
+///     <<case Banana>>
+/// <<endenum>>
+/// ```
+///
+/// Every case has a raw value, which is what is stored in variables of the enum type at runtime.
+/// Cases without an explicit raw value are numbered, starting at 0.
+///
+/// Two enum types are the same if they have the same name.
+pub struct EnumType {
+    /// The name of the enum, e.g. `Food`.
+    pub name: String,
+
+    #[cfg_attr(feature = "bevy", reflect(ignore))]
+    /// The cases of the enum, in the order they were declared.
+    pub cases: Vec<EnumCase>,
+}
+
+/// A case of an [`EnumType`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct EnumCase {
+    /// The name of the case, e.g. `Apple`.
+    pub name: String,
+    /// The value that represents the case at runtime. Either a [`YarnValue::Number`] or a [`YarnValue::String`].
+    pub raw_value: YarnValue,
+}
+
+impl From<EnumType> for Type {
+    fn from(enum_type: EnumType) -> Self {
+        Type::Enum(enum_type)
+    }
+}
+
+impl EnumType {
+    /// Creates an enum without any cases.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            cases: Vec::new(),
+        }
+    }
+
+    /// Adds a case to this enum.
+    pub fn add_case(
+        &mut self,
+        name: impl Into<String>,
+        raw_value: impl Into<YarnValue>,
+    ) -> &mut Self {
+        self.cases.push(EnumCase {
+            name: name.into(),
+            raw_value: raw_value.into(),
+        });
+        self
+    }
+
+    /// Returns the case with the given name, e.g. `Apple`.
+    pub fn case(&self, name: &str) -> Option<&EnumCase> {
+        self.cases.iter().find(|case| case.name == name)
+    }
+
+    /// Returns the case that is represented by the given raw value at runtime.
+    pub fn case_for_raw_value(&self, raw_value: &YarnValue) -> Option<&EnumCase> {
+        self.cases.iter().find(|case| case.raw_value == *raw_value)
+    }
+
+    /// The name by which a case is referred to in Yarn scripts, e.g. `Food.Apple`.
+    pub fn case_reference(&self, case: &EnumCase) -> String {
+        format!("{}.{}", self.name, case.name)
+    }
+}
+
+impl PartialEq for EnumType {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for EnumType {}
+
+impl Hash for EnumType {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+    }
+}
+
+impl Display for EnumType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
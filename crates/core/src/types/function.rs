@@ -34,6 +34,10 @@ pub struct FunctionType {
     ///The type of value that this function returns.
     // Needs to be on the heap because of type recursion
     pub return_type: Box<Option<Type>>,
+
+    /// Whether the last parameter may be repeated any number of times, including zero.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub is_variadic: bool,
 }
 
 impl From<FunctionType> for Type {
@@ -54,16 +58,27 @@ impl FunctionType {
         self.parameters.push(parameter.into());
         self
     }
+
+    /// Sets whether the last parameter of this function signature may be repeated any number of times, including zero.
+    pub fn set_variadic(&mut self, is_variadic: bool) -> &mut Self {
+        self.is_variadic = is_variadic;
+        self
+    }
 }
 
 impl Display for FunctionType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let parameters = self
+        let mut parameters = self
             .parameters
             .iter()
             .map(TypeFormat::format)
-            .collect::<Vec<_>>()
-            .join(", ");
+            .collect::<Vec<_>>();
+        if self.is_variadic {
+            if let Some(last) = parameters.last_mut() {
+                last.push_str("...");
+            }
+        }
+        let parameters = parameters.join(", ");
         let return_type = self.return_type.as_ref().format();
         write!(f, "Fn({}) -> {}", parameters, return_type)
     }
@@ -0,0 +1,26 @@
+use crate::prelude::*;
+use crate::types::TypeProperties;
+
+/// A type that bridges to [`i32`]. Arithmetic wraps around on overflow and division truncates towards zero.
+/// Dividing by zero has no result, so the dialogue fails with an error before calling [`Operator::Divide`] or [`Operator::Modulo`] with a zero divisor.
+///
+/// Integers are a subtype of [`Type::Number`] and are stored as [`YarnValue::Number`] at runtime,
+/// so they are only represented exactly within ±2^24.
+pub(crate) fn integer_type_properties() -> TypeProperties {
+    TypeProperties::from_name("Integer").with_methods(yarn_library! {
+        Operator::EqualTo => <RustType as PartialEq>::eq,
+        Operator::NotEqualTo => <RustType as PartialEq>::ne,
+        Operator::Add => RustType::wrapping_add,
+        Operator::Subtract => RustType::wrapping_sub,
+        Operator::Multiply => RustType::wrapping_mul,
+        Operator::Divide => RustType::wrapping_div,
+        Operator::Modulo => RustType::wrapping_rem,
+        Operator::UnarySubtract => RustType::wrapping_neg,
+        Operator::GreaterThan => RustType::gt,
+        Operator::GreaterThanOrEqualTo => RustType::ge,
+        Operator::LessThan => RustType::lt,
+        Operator::LessThanOrEqualTo => RustType::le,
+    })
+}
+
+type RustType = i32;
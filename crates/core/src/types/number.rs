@@ -2,23 +2,23 @@
 
 use crate::prelude::*;
 use crate::types::TypeProperties;
-use std::ops::*;
+use crate::yarn_value::into_operand_type;
 
 /// A type that bridges to [`f32`]
 pub(crate) fn number_type_properties() -> TypeProperties {
     TypeProperties::from_name("Number").with_methods(yarn_library! {
-        Operator::EqualTo => <RustType as PartialEq>::eq,
-        Operator::NotEqualTo => <RustType as PartialEq>::ne,
-        Operator::Add => <RustType as Add>::add,
-        Operator::Subtract => <RustType as Sub>::sub,
-        Operator::Multiply => <RustType as Mul>::mul,
-        Operator::Divide => <RustType as Div>::div,
-        Operator::Modulo => <RustType as Rem>::rem,
-        Operator::UnarySubtract => <RustType as Neg>::neg,
-        Operator::GreaterThan => RustType::gt,
-        Operator::GreaterThanOrEqualTo => RustType::ge,
-        Operator::LessThan => RustType::lt,
-        Operator::LessThanOrEqualTo => RustType::le,
+        Operator::EqualTo => |a: RustType, b: RustType| YarnValue::from(a) == YarnValue::from(b),
+        Operator::NotEqualTo => |a: RustType, b: RustType| YarnValue::from(a) != YarnValue::from(b),
+        Operator::Add => |a: RustType, b: RustType| -> RustType { into_operand_type(YarnValue::from(a) + b) },
+        Operator::Subtract => |a: RustType, b: RustType| -> RustType { into_operand_type(YarnValue::from(a) - b) },
+        Operator::Multiply => |a: RustType, b: RustType| -> RustType { into_operand_type(YarnValue::from(a) * b) },
+        Operator::Divide => |a: RustType, b: RustType| -> RustType { into_operand_type(YarnValue::from(a) / b) },
+        Operator::Modulo => |a: RustType, b: RustType| -> RustType { into_operand_type(YarnValue::from(a) % b) },
+        Operator::UnarySubtract => |a: RustType| -> RustType { into_operand_type(-YarnValue::from(a)) },
+        Operator::GreaterThan => |a: RustType, b: RustType| YarnValue::from(a) > YarnValue::from(b),
+        Operator::GreaterThanOrEqualTo => |a: RustType, b: RustType| YarnValue::from(a) >= YarnValue::from(b),
+        Operator::LessThan => |a: RustType, b: RustType| YarnValue::from(a) < YarnValue::from(b),
+        Operator::LessThanOrEqualTo => |a: RustType, b: RustType| YarnValue::from(a) <= YarnValue::from(b),
     })
 }
 
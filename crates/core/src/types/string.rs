@@ -2,13 +2,14 @@
 
 use crate::prelude::*;
 use crate::types::TypeProperties;
+use crate::yarn_value::into_operand_type;
 
 /// A type that bridges to [`String`]
 pub(crate) fn string_type_properties() -> TypeProperties {
     TypeProperties::from_name("String").with_methods(yarn_library! {
-        Operator::EqualTo => <RustType as PartialEq>::eq,
-        Operator::NotEqualTo => <RustType as PartialEq>::ne,
-        Operator::Add => |a: RustType, b: RustType| a + &b,
+        Operator::EqualTo => |a: RustType, b: RustType| YarnValue::from(a) == YarnValue::from(b),
+        Operator::NotEqualTo => |a: RustType, b: RustType| YarnValue::from(a) != YarnValue::from(b),
+        Operator::Add => |a: RustType, b: RustType| -> RustType { into_operand_type(YarnValue::from(a) + b) },
     })
 }
 
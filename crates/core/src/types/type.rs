@@ -1,7 +1,9 @@
 use crate::prelude::*;
 use crate::types::any::any_type_properties;
 use crate::types::boolean::boolean_type_properties;
+use crate::types::integer::integer_type_properties;
 use crate::types::number::number_type_properties;
+use crate::types::r#enum::enum_type_properties;
 use crate::types::string::string_type_properties;
 use crate::types::*;
 use std::any::TypeId;
@@ -36,8 +38,14 @@ pub enum Type {
     Any,
     /// The type representing booleans
     Boolean,
+    /// The type representing an enum declared in a Yarn script. Its values are stored as the raw values of its cases.
+    Enum(EnumType),
     /// The type representing functions
     Function(FunctionType),
+    /// The type representing whole numbers. It is a subtype of [`Type::Number`],
+    /// but its arithmetic wraps around on overflow and its division truncates.
+    /// Only used for variables that are explicitly declared `as integer`.
+    Integer,
     /// The type representing numbers
     Number,
     /// The type representing strings
@@ -49,6 +57,7 @@ impl Display for Type {
         let name = self.name();
         match self {
             Type::Function(function) => Display::fmt(function, f),
+            Type::Enum(enum_type) => Display::fmt(enum_type, f),
             _ => write!(f, "{}", name),
         }
     }
@@ -96,7 +105,9 @@ impl Type {
         match self {
             Type::Any => any_type_properties(),
             Type::Boolean => boolean_type_properties(),
+            Type::Enum(enum_type) => enum_type_properties(enum_type),
             Type::Function(function_type) => function_type_properties(function_type),
+            Type::Integer => integer_type_properties(),
             Type::Number => number_type_properties(),
             Type::String => string_type_properties(),
         }
@@ -122,7 +133,9 @@ impl Type {
         Type::Number,
         Type::String,
         Type::Boolean,
+        Type::Integer,
         // Functions are not explicitly constructable
+        // Enums are declared in Yarn scripts, so they are looked up by name instead
     ];
 }
 
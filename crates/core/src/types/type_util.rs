@@ -11,7 +11,7 @@ pub trait SubTypeOf<T: ?Sized = Self> {
     ///
     /// The original implementation features the bones of an actual hierarchical type system,
     /// but de facto it was unused. So, this implementation is way simpler, simply checking
-    /// for special cases, namely `Type::Any` and `Type::Undefined`.
+    /// for special cases, namely `Type::Any`, `Type::Undefined` and `Type::Integer`, which is a subtype of `Type::Number`.
     ///
     /// Careful, the original implementation has the param order flipped!
     fn is_sub_type_of(&self, parent: &T) -> bool;
@@ -27,6 +27,7 @@ where
         match (self, parent) {
             //  ALL types are a subtype of the Any type, including undefined
             (_, Type::Any) => true,
+            (Type::Integer, Type::Number) => true,
             (a, b) => *a == b,
         }
     }
@@ -44,6 +45,7 @@ where
             (_, Type::Any) => true,
             // The subtype is undefined. Assume that it is not a subtype of parent.
             (None, _) => false,
+            (Some(Type::Integer), Type::Number) => true,
             (Some(a), b) => *a == b,
         }
     }
@@ -60,6 +62,7 @@ where
             //  ALL types are a subtype of the Any type, including undefined
             (_, Some(Type::Any)) => true,
             (_, None) => false,
+            (Type::Integer, Some(Type::Number)) => true,
             (a, Some(b)) => *a == b,
         }
     }
@@ -78,6 +81,7 @@ where
             // The subtype is undefined. Assume that it is not a subtype of parent.
             (None, _) => false,
             (_, None) => false,
+            (Some(Type::Integer), Some(Type::Number)) => true,
             (a, b) => *a == b,
         }
     }
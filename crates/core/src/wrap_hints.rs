@@ -0,0 +1,263 @@
+#[cfg(any(feature = "bevy", feature = "serde"))]
+use crate::prelude::*;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Configures how [`wrap_hints`] wraps text into a fixed-width dialogue box, e.g. one rendered with a monospace font.
+///
+/// Widths are measured in cells: every grapheme cluster takes up one cell,
+/// except for the ones in [`WrapConfig::double_width_ranges`], which take up two.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Default))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct WrapConfig {
+    /// The number of cells that fit into a single line of the dialogue box.
+    pub width: usize,
+
+    /// Widths that replace [`WrapConfig::width`] for specific languages, keyed by language code, e.g. `"ja-JP"`.
+    /// See [`WrapConfig::for_language`].
+    pub language_widths: HashMap<String, usize>,
+
+    /// The number of lines that fit into the dialogue box. Lines of dialogue that wrap into more lines than this produce a warning when compiled.
+    /// If this is `None`, any number of lines is fine.
+    pub max_lines: Option<usize>,
+
+    /// The number of cells assumed for a placeholder of an interpolated value, e.g. the `{0}` in `"You have {0} gold."`.
+    pub placeholder_width: usize,
+
+    /// The characters that take up two cells, such as CJK ideographs.
+    /// Text may also be broken between two of these characters, as CJK text usually has no spaces.
+    pub double_width_ranges: Vec<RangeInclusive<char>>,
+}
+
+impl Default for WrapConfig {
+    fn default() -> Self {
+        Self {
+            width: 40,
+            language_widths: HashMap::new(),
+            max_lines: None,
+            placeholder_width: 8,
+            double_width_ranges: vec![
+                // Hangul Jamo
+                '\u{1100}'..='\u{115F}',
+                // CJK Radicals through Yi Radicals, including Hiragana, Katakana and CJK Unified Ideographs
+                '\u{2E80}'..='\u{A4CF}',
+                // Hangul Syllables
+                '\u{AC00}'..='\u{D7A3}',
+                // CJK Compatibility Ideographs
+                '\u{F900}'..='\u{FAFF}',
+                // CJK Compatibility Forms
+                '\u{FE30}'..='\u{FE4F}',
+                // Fullwidth Forms
+                '\u{FF00}'..='\u{FF60}',
+                '\u{FFE0}'..='\u{FFE6}',
+                // CJK Unified Ideographs Extension B and beyond
+                '\u{20000}'..='\u{3FFFD}',
+            ],
+        }
+    }
+}
+
+impl WrapConfig {
+    /// Creates a new [`WrapConfig`] for a dialogue box that is `width` cells wide.
+    pub fn with_width(width: usize) -> Self {
+        Self {
+            width,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the width used instead of [`WrapConfig::width`] for the given language.
+    pub fn with_language_width(mut self, language: impl Into<String>, width: usize) -> Self {
+        self.language_widths.insert(language.into(), width);
+        self
+    }
+
+    /// Sets [`WrapConfig::max_lines`].
+    pub fn with_max_lines(mut self, max_lines: impl Into<Option<usize>>) -> Self {
+        self.max_lines = max_lines.into();
+        self
+    }
+
+    /// Sets [`WrapConfig::placeholder_width`].
+    pub fn with_placeholder_width(mut self, placeholder_width: usize) -> Self {
+        self.placeholder_width = placeholder_width;
+        self
+    }
+
+    /// Sets [`WrapConfig::double_width_ranges`].
+    pub fn with_double_width_ranges(
+        mut self,
+        double_width_ranges: impl IntoIterator<Item = RangeInclusive<char>>,
+    ) -> Self {
+        self.double_width_ranges = double_width_ranges.into_iter().collect();
+        self
+    }
+
+    /// Returns the configuration to use for text in the given language, i.e. with the width overridden by [`WrapConfig::language_widths`], if there is an override.
+    pub fn for_language(&self, language: &str) -> Self {
+        let width = self
+            .language_widths
+            .get(language)
+            .copied()
+            .unwrap_or(self.width);
+        Self {
+            width,
+            ..self.clone()
+        }
+    }
+
+    fn grapheme_width(&self, grapheme: &str) -> usize {
+        let is_double_width = grapheme.chars().next().is_some_and(|c| {
+            self.double_width_ranges
+                .iter()
+                .any(|range| range.contains(&c))
+        });
+        if is_double_width {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+/// The result of [`wrap_hints`]: how a text wraps into a fixed-width dialogue box.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash, Default))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct WrapHints {
+    /// The number of lines the text wraps into.
+    pub line_count: usize,
+
+    /// The byte offsets into the text at which a new line starts, not including the first line.
+    pub line_starts: Vec<usize>,
+
+    /// Whether a single unbreakable token, e.g. a URL, is wider than the line and had to be broken in the middle.
+    pub has_overlong_token: bool,
+}
+
+/// A grapheme cluster or placeholder, i.e. something that cannot be broken up further.
+struct Cell {
+    start: usize,
+    width: usize,
+}
+
+/// Computes how the given text wraps into lines of [`WrapConfig::width`] cells.
+///
+/// Lines are broken at whitespace and between double-width characters.
+/// Placeholders like `{0}` count as [`WrapConfig::placeholder_width`] cells and are never broken up.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_core::prelude::*;
+/// let hints = wrap_hints("The quick brown fox", &WrapConfig::with_width(10));
+/// assert_eq!(2, hints.line_count);
+/// assert_eq!(vec![10], hints.line_starts);
+/// assert!(!hints.has_overlong_token);
+/// ```
+pub fn wrap_hints(text: &str, config: &WrapConfig) -> WrapHints {
+    let width = config.width.max(1);
+    let mut hints = WrapHints {
+        line_count: 1,
+        ..Default::default()
+    };
+    let mut line_width = 0;
+    let mut pending_space = 0;
+    for token in tokenize(text, config) {
+        let token = match token {
+            Token::Space(space) => {
+                pending_space += space;
+                continue;
+            }
+            Token::Word(cells) => cells,
+        };
+        let token_width: usize = token.iter().map(|cell| cell.width).sum();
+        if line_width > 0 && line_width + pending_space + token_width > width {
+            hints.line_starts.push(token[0].start);
+            hints.line_count += 1;
+            line_width = 0;
+        } else if line_width > 0 {
+            line_width += pending_space;
+        }
+        pending_space = 0;
+
+        if token_width <= width {
+            line_width += token_width;
+            continue;
+        }
+        hints.has_overlong_token = true;
+        for cell in token {
+            if line_width > 0 && line_width + cell.width > width {
+                hints.line_starts.push(cell.start);
+                hints.line_count += 1;
+                line_width = 0;
+            }
+            line_width += cell.width;
+        }
+    }
+    hints
+}
+
+enum Token {
+    Space(usize),
+    Word(Vec<Cell>),
+}
+
+fn tokenize(text: &str, config: &WrapConfig) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut word = Vec::new();
+    let mut graphemes = text.grapheme_indices(true).peekable();
+    while let Some((start, grapheme)) = graphemes.next() {
+        if grapheme.chars().all(char::is_whitespace) {
+            if !word.is_empty() {
+                tokens.push(Token::Word(std::mem::take(&mut word)));
+            }
+            tokens.push(Token::Space(1));
+            continue;
+        }
+        if let Some(placeholder_len) = placeholder_len(&text[start..]) {
+            word.push(Cell {
+                start,
+                width: config.placeholder_width,
+            });
+            while graphemes
+                .next_if(|(index, _)| *index < start + placeholder_len)
+                .is_some()
+            {}
+            continue;
+        }
+        let width = config.grapheme_width(grapheme);
+        if width == 2 {
+            // CJK text may be broken between any two characters
+            if !word.is_empty() {
+                tokens.push(Token::Word(std::mem::take(&mut word)));
+            }
+            tokens.push(Token::Word(vec![Cell { start, width }]));
+        } else {
+            word.push(Cell { start, width });
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(Token::Word(word));
+    }
+    tokens
+}
+
+/// Returns the length in bytes of the placeholder at the start of the text, e.g. `{0}`, if there is one.
+fn placeholder_len(text: &str) -> Option<usize> {
+    let digits = text.strip_prefix('{')?;
+    let digit_count = digits.chars().take_while(char::is_ascii_digit).count();
+    (digit_count > 0 && digits[digit_count..].starts_with('}')).then_some(digit_count + 2)
+}
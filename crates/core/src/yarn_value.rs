@@ -4,12 +4,18 @@ use crate::prelude::*;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 
+mod ops;
+pub(crate) use ops::into_operand_type;
+
 /// Represents a Yarn value. The chosen variant corresponds to the last assignment of the value,
 /// with the type being inferred from the type checker.
 ///
 /// The type implements meaningful conversions between types through [`TryFrom`] and [`From`].
 /// A failure to convert one variant to another will result in an [`YarnValueCastError`].
 ///
+/// Values can be calculated with like in Yarn scripts through the operators of [`std::ops`], e.g. `+`, `-` and `&` for `and`,
+/// which convert their operands to the type they work on and return a [`Result`], as well as compared with `<`, `>=`, etc.
+///
 /// ## Implementation Notes
 ///
 /// Corresponds to C#'s [`Convert`](https://docs.microsoft.com/en-us/dotnet/api/system.convert?view=net-5.0) class.
@@ -123,6 +129,12 @@ macro_rules! impl_whole_number {
                 type Error = YarnValueCastError;
 
                 fn try_from(value: &YarnValue) -> Result<Self, Self::Error> {
+                    // Parse whole numbers directly, so that they keep all their digits instead of being rounded to the precision of an `f32`
+                    if let YarnValue::String(string) = value {
+                        if let Ok(value) = string.parse::<$from_type>() {
+                            return Ok(value);
+                        }
+                    }
                     f32::try_from(value).map(|value| value as $from_type)
                 }
             }
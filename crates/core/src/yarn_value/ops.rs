@@ -0,0 +1,98 @@
+//! Not part of the original implementation. Lets game code calculate with [`YarnValue`]s the same way Yarn scripts do.
+//! The operators of the built-in types, which the virtual machine calls, are implemented on top of these.
+
+use crate::prelude::*;
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Neg, Not, Rem, Sub};
+
+/// Concatenates the operands if either of them is a [`YarnValue::String`], and adds them as numbers otherwise.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use yarnspinner_core::prelude::*;
+/// assert_eq!(YarnValue::from(3), (YarnValue::from(1) + 2).unwrap());
+/// assert_eq!(YarnValue::from("Gold: 10"), (YarnValue::from("Gold: ") + 10).unwrap());
+/// ```
+impl<T: Into<YarnValue>> Add<T> for YarnValue {
+    type Output = Result<YarnValue, YarnValueCastError>;
+
+    fn add(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+        if matches!(self, YarnValue::String(_)) || matches!(rhs, YarnValue::String(_)) {
+            Ok((String::from(self) + &String::from(rhs)).into())
+        } else {
+            Ok((f32::try_from(self)? + f32::try_from(rhs)?).into())
+        }
+    }
+}
+
+macro_rules! impl_binary_operator {
+    ($($trait:ident :: $method:ident => $operand_type:ty, $doc:literal,)*) => {
+        $(
+            #[doc = $doc]
+            /// Fails if an operand can't be converted, e.g. a string that doesn't contain one.
+            impl<T: Into<YarnValue>> $trait<T> for YarnValue {
+                type Output = Result<YarnValue, YarnValueCastError>;
+
+                fn $method(self, rhs: T) -> Self::Output {
+                    let lhs = <$operand_type>::try_from(self)?;
+                    let rhs = <$operand_type>::try_from(rhs.into())?;
+                    Ok(lhs.$method(rhs).into())
+                }
+            }
+        )*
+    };
+}
+
+impl_binary_operator![
+    Sub::sub => f32, "Subtracts the operands as numbers.",
+    Mul::mul => f32, "Multiplies the operands as numbers.",
+    Div::div => f32, "Divides the operands as numbers. Like in Yarn scripts, dividing by zero results in an infinite number rather than an error.",
+    Rem::rem => f32, "Calculates the remainder of dividing the operands as numbers.",
+    BitAnd::bitand => bool, "Combines the operands as booleans with `and`.",
+    BitOr::bitor => bool, "Combines the operands as booleans with `or`.",
+    BitXor::bitxor => bool, "Combines the operands as booleans with `xor`.",
+];
+
+/// Negates the value as a number.
+impl Neg for YarnValue {
+    type Output = Result<YarnValue, YarnValueCastError>;
+
+    fn neg(self) -> Self::Output {
+        Ok((-f32::try_from(self)?).into())
+    }
+}
+
+/// Negates the value as a boolean.
+impl Not for YarnValue {
+    type Output = Result<YarnValue, YarnValueCastError>;
+
+    fn not(self) -> Self::Output {
+        Ok((!bool::try_from(self)?).into())
+    }
+}
+
+/// Compares values of the same variant. Like equality, values of different variants are never compared,
+/// so e.g. `YarnValue::from("2") < YarnValue::from(3)` is `false`.
+impl PartialOrd for YarnValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Self::Number(a), Self::Number(b)) => a.partial_cmp(b),
+            (Self::String(a), Self::String(b)) => a.partial_cmp(b),
+            (Self::Boolean(a), Self::Boolean(b)) => a.partial_cmp(b),
+            _ => None,
+        }
+    }
+}
+
+/// Converts the result of an operation on operands of type `T` back to `T`, which can't fail.
+pub(crate) fn into_operand_type<T>(result: Result<YarnValue, YarnValueCastError>) -> T
+where
+    T: TryFrom<YarnValue>,
+    T::Error: Debug,
+{
+    const MESSAGE: &str = "Internal error: an operation on a built-in type resulted in a different type. This is a bug. Please report it at https://github.com/YarnSpinnerTool/YarnSpinner-Rust/issues/new";
+    T::try_from(result.expect(MESSAGE)).expect(MESSAGE)
+}
@@ -29,6 +29,7 @@
 //! ## Inputs
 //!
 //! - Advance the dialogue: press the space bar, enter key, left click or tap the screen after the text is done typing.
+//!   These inputs can be changed with [`ExampleYarnSpinnerDialogueViewPlugin::with_continue_input`].
 //! - Type out the text faster: Same as above, but hold press before the text is done typing.
 //! - Select an option: press the number key corresponding to the option you want to select or click/tap the option.
 //!
@@ -43,20 +44,22 @@
 use bevy::prelude::*;
 use bevy_yarnspinner::prelude::YarnSpinnerPlugin;
 pub use setup::UiRootNode;
-pub use updating::SpeakerChangeEvent;
+pub use updating::{ContinueInput, SpeakerChangeEvent};
 
 pub mod prelude {
     //! Everything you need to get starting using this example Yarn Spinner dialogue view.
     pub use crate::{
-        ExampleYarnSpinnerDialogueViewPlugin, ExampleYarnSpinnerDialogueViewSystemSet,
-        SpeakerChangeEvent,
+        ContinueInput, ExampleYarnSpinnerDialogueViewPlugin,
+        ExampleYarnSpinnerDialogueViewSystemSet, SpeakerChangeEvent,
     };
 }
 
 /// The plugin registering all systems of the dialogue view.
 #[derive(Debug, Default)]
 #[non_exhaustive]
-pub struct ExampleYarnSpinnerDialogueViewPlugin;
+pub struct ExampleYarnSpinnerDialogueViewPlugin {
+    continue_input: ContinueInput,
+}
 
 /// The [`SystemSet`] containing all systems added by the [`ExampleYarnSpinnerDialogueViewPlugin`].
 /// Is run after the [`YarnSpinnerSystemSet`](bevy_yarnspinner::prelude::YarnSpinnerSystemSet).
@@ -68,6 +71,13 @@ impl ExampleYarnSpinnerDialogueViewPlugin {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Sets the inputs that advance the dialogue. See [`ContinueInput`] for the defaults.
+    #[must_use]
+    pub fn with_continue_input(mut self, continue_input: ContinueInput) -> Self {
+        self.continue_input = continue_input;
+        self
+    }
 }
 
 mod assets;
@@ -86,7 +96,8 @@ impl Plugin for ExampleYarnSpinnerDialogueViewPlugin {
             .add_plugins(setup::ui_setup_plugin)
             .add_plugins(updating::ui_updating_plugin)
             .add_plugins(typewriter::typewriter_plugin)
-            .add_plugins(option_selection::option_selection_plugin);
+            .add_plugins(option_selection::option_selection_plugin)
+            .insert_resource(self.continue_input.clone());
     }
 }
 
@@ -5,7 +5,6 @@ use crate::ExampleYarnSpinnerDialogueViewSystemSet;
 use bevy::prelude::*;
 use bevy::utils::Instant;
 use bevy_yarnspinner::{events::*, prelude::*};
-use unicode_segmentation::UnicodeSegmentation;
 
 pub(crate) fn typewriter_plugin(app: &mut App) {
     app.add_systems(
@@ -32,7 +31,8 @@ pub(crate) struct TypewriterFinishedEvent;
 pub(crate) struct Typewriter {
     pub(crate) character_name: Option<String>,
     pub(crate) current_text: String,
-    pub(crate) graphemes_left: Vec<String>,
+    /// The text added by each remaining step of the [`RevealMode`].
+    pub(crate) steps_left: Vec<String>,
     pub(crate) last_before_options: bool,
     elapsed: f32,
     start: Instant,
@@ -44,7 +44,7 @@ impl Default for Typewriter {
         Self {
             character_name: default(),
             current_text: default(),
-            graphemes_left: default(),
+            steps_left: default(),
             last_before_options: default(),
             elapsed: default(),
             start: Instant::now(),
@@ -54,22 +54,29 @@ impl Default for Typewriter {
 }
 
 impl Typewriter {
-    pub(crate) fn set_line(&mut self, line: &LocalizedLine) {
+    pub(crate) fn set_line(&mut self, line: &LocalizedLine, reveal_mode: RevealMode) {
+        let text = line.text_without_character_name();
+        let mut revealed_len = 0;
+        let steps_left = reveal_mode
+            .reveal_steps(&text)
+            .into_iter()
+            .map(|step| {
+                let added = step[revealed_len..].to_string();
+                revealed_len = step.len();
+                added
+            })
+            .collect();
         *self = Self {
             character_name: line.character_name().map(|s| s.to_string()),
             current_text: String::new(),
-            graphemes_left: line
-                .text_without_character_name()
-                .graphemes(true)
-                .map(|s| s.to_string())
-                .collect(),
+            steps_left,
             last_before_options: line.is_last_line_before_options(),
             ..default()
         };
     }
 
     pub(crate) fn is_finished(&self) -> bool {
-        self.graphemes_left.is_empty() && !self.current_text.is_empty()
+        self.steps_left.is_empty() && !self.current_text.is_empty()
     }
 
     pub(crate) fn fast_forward(&mut self) {
@@ -82,15 +89,15 @@ impl Typewriter {
         }
         self.elapsed += self.start.elapsed().as_secs_f32();
         self.start = Instant::now();
-        let calculated_graphemes = (self.graphemes_per_second() * self.elapsed).floor() as usize;
-        let graphemes_left = self.graphemes_left.len();
-        let grapheme_length_to_take = (calculated_graphemes).min(graphemes_left);
-        self.elapsed -= grapheme_length_to_take as f32 / self.graphemes_per_second();
-        let graphemes_to_take = self.graphemes_left.drain(..grapheme_length_to_take);
-        self.current_text.extend(graphemes_to_take);
+        let calculated_steps = (self.steps_per_second() * self.elapsed).floor() as usize;
+        let steps_left = self.steps_left.len();
+        let step_count_to_take = (calculated_steps).min(steps_left);
+        self.elapsed -= step_count_to_take as f32 / self.steps_per_second();
+        let steps_to_take = self.steps_left.drain(..step_count_to_take);
+        self.current_text.extend(steps_to_take);
     }
 
-    fn graphemes_per_second(&self) -> f32 {
+    fn steps_per_second(&self) -> f32 {
         if self.fast_typing {
             120.0
         } else {
@@ -129,7 +136,7 @@ fn write_text(
     }
 
     let current_text = &typewriter.current_text;
-    let rest = typewriter.graphemes_left.join("");
+    let rest = typewriter.steps_left.join("");
     *text = create_dialog_text(current_text, rest);
 }
 
@@ -22,7 +22,48 @@ pub(crate) fn ui_updating_plugin(app: &mut App) {
             .in_set(ExampleYarnSpinnerDialogueViewSystemSet),
     )
     .add_event::<SpeakerChangeEvent>()
-    .register_type::<SpeakerChangeEvent>();
+    .register_type::<SpeakerChangeEvent>()
+    .init_resource::<ContinueInput>()
+    .register_type::<ContinueInput>();
+}
+
+/// The inputs that advance the dialogue, or type out the current line faster if it is still being written.
+/// Set it with [`ExampleYarnSpinnerDialogueViewPlugin::with_continue_input`](crate::ExampleYarnSpinnerDialogueViewPlugin::with_continue_input)
+/// or change the resource at runtime, e.g. for a key rebinding menu.
+///
+/// By default, the space bar, the enter key, a left click and tapping the screen continue.
+#[derive(Debug, Clone, PartialEq, Eq, Resource, Reflect)]
+#[reflect(Debug, PartialEq, Resource)]
+pub struct ContinueInput {
+    /// The keys that continue when pressed.
+    pub keys: Vec<KeyCode>,
+    /// The mouse buttons that continue when pressed.
+    pub mouse_buttons: Vec<MouseButton>,
+    /// Whether tapping the screen continues.
+    pub touch: bool,
+}
+
+impl Default for ContinueInput {
+    fn default() -> Self {
+        Self {
+            keys: vec![KeyCode::Space, KeyCode::Enter],
+            mouse_buttons: vec![MouseButton::Left],
+            touch: true,
+        }
+    }
+}
+
+impl ContinueInput {
+    fn just_pressed(
+        &self,
+        keys: &ButtonInput<KeyCode>,
+        mouse_buttons: &ButtonInput<MouseButton>,
+        touches: &Touches,
+    ) -> bool {
+        keys.any_just_pressed(self.keys.iter().copied())
+            || mouse_buttons.any_just_pressed(self.mouse_buttons.iter().copied())
+            || (self.touch && touches.any_just_pressed())
+    }
 }
 
 /// Signals that a speaker has changed.
@@ -57,6 +98,7 @@ fn present_line(
     mut speaker_change_events: EventWriter<SpeakerChangeEvent>,
     mut typewriter: ResMut<Typewriter>,
     mut name_node: Query<&mut Text, With<DialogueNameNode>>,
+    dialogue_runners: Query<&DialogueRunner>,
 ) {
     for event in line_events.read() {
         let name = if let Some(name) = event.line.character_name() {
@@ -69,7 +111,13 @@ fn present_line(
             String::new()
         };
         name_node.single_mut().sections[0].value = name;
-        typewriter.set_line(&event.line);
+        let reveal_mode = dialogue_runners
+            .get(event.source)
+            .ok()
+            .and_then(|dialogue_runner| dialogue_runner.language_presentation())
+            .map(|presentation| presentation.reveal_mode)
+            .unwrap_or_default();
+        typewriter.set_line(&event.line, reveal_mode);
     }
 }
 
@@ -81,6 +129,7 @@ fn present_options(mut commands: Commands, mut events: EventReader<PresentOption
 }
 
 fn continue_dialogue(
+    continue_input: Res<ContinueInput>,
     keys: Res<ButtonInput<KeyCode>>,
     mouse_buttons: Res<ButtonInput<MouseButton>>,
     touches: Res<Touches>,
@@ -93,10 +142,7 @@ fn continue_dialogue(
         (With<DialogueContinueNode>, Without<UiRootNode>),
     >,
 ) {
-    let explicit_continue = keys.just_pressed(KeyCode::Space)
-        || keys.just_pressed(KeyCode::Enter)
-        || mouse_buttons.just_pressed(MouseButton::Left)
-        || touches.any_just_pressed();
+    let explicit_continue = continue_input.just_pressed(&keys, &mouse_buttons, &touches);
     if explicit_continue && !typewriter.is_finished() {
         typewriter.fast_forward();
         return;
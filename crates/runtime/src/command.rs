@@ -6,6 +6,7 @@
 use crate::markup::normalize;
 #[cfg(any(feature = "bevy", feature = "serde"))]
 use crate::prelude::*;
+use std::time::Duration;
 use yarnspinner_core::prelude::YarnValue;
 
 /// A custom command found in a Yarn file within the `<<` and `>>` characters.
@@ -59,6 +60,19 @@ impl Command {
             raw: input,
         }
     }
+
+    /// Returns how long a built-in `<<wait>>` command pauses the dialogue,
+    /// i.e. whether this is a command named `wait` whose only parameter is a non-negative number of seconds.
+    pub(crate) fn wait_duration(&self) -> Option<Duration> {
+        let [seconds] = self.parameters.as_slice() else {
+            return None;
+        };
+        if self.name != "wait" {
+            return None;
+        }
+        let seconds = f32::try_from(seconds).ok()?;
+        Duration::try_from_secs_f32(seconds).ok()
+    }
 }
 
 /// Splits input into a number of non-empty sub-strings, separated
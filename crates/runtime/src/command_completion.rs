@@ -0,0 +1,82 @@
+//! Not part of the original implementation, which lets command handlers block the dialogue through the (C#) coroutines they return.
+
+use std::sync::atomic::{fence, AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// What a [`CommandHandler`](crate::prelude::CommandHandler) returns to tell whether the command is done.
+/// Handlers that don't return anything always return [`CommandResult::Completed`].
+///
+/// Pass it to [`Dialogue::wait_for_command`](crate::prelude::Dialogue::wait_for_command) to keep the dialogue from continuing
+/// while the command is still running, e.g. while a character walks to a door.
+#[derive(Debug, Clone, Default)]
+pub enum CommandResult {
+    /// The command is done and the dialogue can continue right away.
+    #[default]
+    Completed,
+    /// The command is still running. It is done when [`CommandCompletion::complete`] is called.
+    InProgress(CommandCompletion),
+}
+
+impl CommandResult {
+    /// Creates a [`CommandResult::InProgress`] together with the token to complete it with.
+    pub fn in_progress() -> (Self, CommandCompletion) {
+        let completion = CommandCompletion::new();
+        (Self::InProgress(completion.clone()), completion)
+    }
+
+    /// Returns `true` if the command is done, i.e. it was [`CommandResult::Completed`] or its [`CommandCompletion`] has been completed since.
+    pub fn is_completed(&self) -> bool {
+        match self {
+            Self::Completed => true,
+            Self::InProgress(completion) => completion.is_completed(),
+        }
+    }
+}
+
+impl From<()> for CommandResult {
+    fn from(_: ()) -> Self {
+        Self::Completed
+    }
+}
+
+impl From<CommandCompletion> for CommandResult {
+    fn from(completion: CommandCompletion) -> Self {
+        Self::InProgress(completion)
+    }
+}
+
+/// Marks a running command as done when [`CommandCompletion::complete`] is called on it or any of its clones.
+/// Can be moved to and completed from any thread.
+///
+/// If every clone is dropped without completing the command, e.g. because the task running it panicked,
+/// the command is considered [abandoned](CommandCompletion::is_abandoned) and [`Dialogue::continue_`](crate::prelude::Dialogue::continue_)
+/// fails with [`DialogueError::CommandAbandoned`](crate::prelude::DialogueError::CommandAbandoned) instead of waiting forever.
+#[derive(Debug, Clone, Default)]
+pub struct CommandCompletion(Arc<AtomicBool>);
+
+impl CommandCompletion {
+    /// Creates a token for a command that is still running.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks the command as done. Completing it more than once has no effect.
+    pub fn complete(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    /// Returns `true` if [`CommandCompletion::complete`] was called on this token or any of its clones.
+    pub fn is_completed(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+
+    /// Returns `true` if this is the last clone of the token left and the command was not completed,
+    /// which means that nothing can complete it anymore.
+    pub fn is_abandoned(&self) -> bool {
+        // No clones can be created concurrently once this is the only one left.
+        // The fence makes a completion that happened before the other clones were dropped visible.
+        let is_last = Arc::strong_count(&self.0) == 1;
+        fence(Ordering::Acquire);
+        is_last && !self.is_completed()
+    }
+}
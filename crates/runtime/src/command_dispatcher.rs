@@ -0,0 +1,215 @@
+//! Not part of the original implementation. Saves game code from matching on [`Command::name`] and converting
+//! each of the [`Command::parameters`] by hand.
+
+use crate::prelude::{Command, CommandResult};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+use yarnspinner_core::prelude::YarnValue;
+use yarnspinner_macros::all_tuples;
+
+/// A function that can handle a [`Command`] registered with [`CommandDispatcher::add_command`].
+/// It may have zero or more parameters of any type that a [`YarnValue`] can be converted to with [`TryFrom`], e.g.
+/// - [`bool`]
+/// - A numeric type, i.e. one of [`f32`], [`f64`], [`i8`], [`i16`], [`i32`], [`i64`], [`i128`], [`u8`], [`u16`], [`u32`], [`u64`], [`u128`], [`usize`], [`isize`]
+/// - [`String`]
+/// - [`YarnValue`] itself
+///
+/// It returns either nothing, in which case the command is done as soon as it returns, or a [`CommandResult`]
+/// for commands that keep running afterwards, e.g. a character walking to a door.
+pub trait CommandHandler<Marker>: Send + Sync + 'static {
+    /// Converts the parameters of the command and runs the handler with them.
+    fn handle(&mut self, command: &Command) -> Result<CommandResult, CommandError>;
+    /// The number of parameters the handler expects.
+    fn parameter_count(&self) -> usize;
+}
+
+type BoxedCommandHandler =
+    Box<dyn FnMut(&Command) -> Result<CommandResult, CommandError> + Send + Sync>;
+
+/// Runs the functions registered for [`Command`]s with their parameters converted to the types the functions expect.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_runtime::prelude::*;
+/// # use std::sync::{Arc, Mutex};
+/// let inventory = Arc::new(Mutex::new(Vec::new()));
+/// let mut dispatcher = CommandDispatcher::new();
+/// dispatcher.add_command("give", {
+///     let inventory = inventory.clone();
+///     move |amount: u32, item: String| inventory.lock().unwrap().push((amount, item))
+/// });
+///
+/// // Usually, the commands come from a `DialogueEvent::Command`
+/// let command = Command {
+///     name: "give".to_owned(),
+///     parameters: vec!["3".into(), "magic beans".into()],
+///     raw: "give 3 \"magic beans\"".to_owned(),
+/// };
+/// dispatcher.dispatch(&command).unwrap();
+/// assert_eq!(vec![(3, "magic beans".to_owned())], *inventory.lock().unwrap());
+/// ```
+#[derive(Default)]
+pub struct CommandDispatcher {
+    handlers: HashMap<String, BoxedCommandHandler>,
+}
+
+impl CommandDispatcher {
+    /// Creates a dispatcher without any commands.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a [`CommandHandler`] for the command with the given name, replacing any handler previously registered for it.
+    pub fn add_command<Marker>(
+        &mut self,
+        name: impl Into<String>,
+        mut handler: impl CommandHandler<Marker>,
+    ) -> &mut Self {
+        self.handlers.insert(
+            name.into(),
+            Box::new(move |command: &Command| handler.handle(command)),
+        );
+        self
+    }
+
+    /// Removes the handler for the command with the given name. Returns whether there was one.
+    pub fn remove_command(&mut self, name: &str) -> bool {
+        self.handlers.remove(name).is_some()
+    }
+
+    /// Returns whether a handler is registered for the command with the given name.
+    pub fn contains_command(&self, name: &str) -> bool {
+        self.handlers.contains_key(name)
+    }
+
+    /// The names of all commands that have a handler, in no particular order.
+    pub fn command_names(&self) -> impl Iterator<Item = &str> {
+        self.handlers.keys().map(String::as_str)
+    }
+
+    /// Runs the handler registered for the command. Fails without running it if the command has the wrong number of parameters
+    /// or one of them can't be converted to the type the handler expects.
+    ///
+    /// Pass the returned [`CommandResult`] to [`Dialogue::wait_for_command`](crate::prelude::Dialogue::wait_for_command)
+    /// to keep the dialogue from continuing until the command is done.
+    pub fn dispatch(&mut self, command: &Command) -> Result<CommandResult, CommandError> {
+        let handler =
+            self.handlers
+                .get_mut(&command.name)
+                .ok_or_else(|| CommandError::CommandNotFound {
+                    command: command.name.clone(),
+                })?;
+        handler(command)
+    }
+}
+
+impl Debug for CommandDispatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<_> = self.command_names().collect();
+        names.sort_unstable();
+        f.debug_struct("CommandDispatcher")
+            .field("handlers", &names)
+            .finish()
+    }
+}
+
+/// An error returned by [`CommandDispatcher::dispatch`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandError {
+    /// No handler is registered for the command.
+    CommandNotFound {
+        /// The name of the command.
+        command: String,
+    },
+    /// The command has a different number of parameters than its handler.
+    ParameterCountMismatch {
+        /// The name of the command.
+        command: String,
+        /// The number of parameters of the handler.
+        expected: usize,
+        /// The number of parameters passed to the command.
+        found: usize,
+    },
+    /// A parameter can't be converted to the type the handler expects.
+    InvalidParameter {
+        /// The name of the command.
+        command: String,
+        /// The index of the parameter, starting at 0.
+        parameter_index: usize,
+        /// The name of the type the handler expects.
+        expected: &'static str,
+        /// The parameter as it was passed to the command.
+        found: YarnValue,
+        /// Why the conversion failed.
+        reason: String,
+    },
+}
+
+impl Error for CommandError {}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use CommandError::*;
+        match self {
+            CommandNotFound { command } => write!(f, "No handler is registered for the command \"{command}\"."),
+            ParameterCountMismatch { command, expected, found } => write!(f, "Command \"{command}\" expects {expected} parameter(s), but received {found}."),
+            InvalidParameter { command, parameter_index, expected, found, reason } => write!(f, "Command \"{command}\" expected parameter {parameter_index} to be of type {expected}, but received \"{found}\", which cannot be converted to it: {reason}"),
+        }
+    }
+}
+
+fn convert_parameter<T>(
+    command: &Command,
+    parameter_index: usize,
+    parameter: &YarnValue,
+) -> Result<T, CommandError>
+where
+    T: TryFrom<YarnValue>,
+    T::Error: Display,
+{
+    T::try_from(parameter.clone()).map_err(|error| CommandError::InvalidParameter {
+        command: command.name.clone(),
+        parameter_index,
+        expected: std::any::type_name::<T>(),
+        found: parameter.clone(),
+        reason: error.to_string(),
+    })
+}
+
+macro_rules! impl_command_handler_tuple {
+    ($($param: ident),*) => {
+        #[allow(non_snake_case)]
+        impl<F, Output, $($param,)*> CommandHandler<fn($($param,)*) -> Output> for F
+        where
+            F: FnMut($($param,)*) -> Output + Send + Sync + 'static,
+            Output: Into<CommandResult> + 'static,
+            $($param: TryFrom<YarnValue> + 'static, <$param as TryFrom<YarnValue>>::Error: Display,)*
+        {
+            fn handle(&mut self, command: &Command) -> Result<CommandResult, CommandError> {
+                let expected = <Self as CommandHandler<fn($($param,)*) -> Output>>::parameter_count(self);
+                if command.parameters.len() != expected {
+                    return Err(CommandError::ParameterCountMismatch {
+                        command: command.name.clone(),
+                        expected,
+                        found: command.parameters.len(),
+                    });
+                }
+                #[allow(unused_variables, unused_mut)] // for n = 0 tuples
+                let mut parameters = command.parameters.iter().enumerate();
+                $(
+                    let (index, parameter) = parameters.next().unwrap();
+                    let $param = convert_parameter::<$param>(command, index, parameter)?;
+                )*
+                Ok(self($($param,)*).into())
+            }
+
+            fn parameter_count(&self) -> usize {
+                <[&str]>::len(&[$(stringify!($param)),*])
+            }
+        }
+    };
+}
+
+all_tuples!(impl_command_handler_tuple, 0, 16, P);
@@ -0,0 +1,44 @@
+//! Not part of the original implementation.
+
+#[cfg(any(feature = "bevy", feature = "serde"))]
+use crate::prelude::*;
+use std::fmt::Display;
+use yarnspinner_core::prelude::YarnValue;
+
+/// A call to a function that was registered with [`Library::add_deferred_function`](yarnspinner_core::prelude::Library::add_deferred_function).
+/// The [`Dialogue`](crate::prelude::Dialogue) is suspended in the middle of evaluating the expression the call is part of
+/// until the game passes the return value to [`Dialogue::complete_function`](crate::prelude::Dialogue::complete_function).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct DeferredFunctionCall {
+    /// Identifies this call when completing it.
+    pub token: FunctionCallToken,
+    /// The name of the called function.
+    pub function_name: String,
+    /// The arguments the function was called with.
+    pub parameters: Vec<YarnValue>,
+}
+
+/// Identifies a [`DeferredFunctionCall`]. Pass it to [`Dialogue::complete_function`](crate::prelude::Dialogue::complete_function)
+/// together with the return value of the call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct FunctionCallToken(pub usize);
+
+impl Display for FunctionCallToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -6,11 +6,24 @@ use log::error;
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{self, Debug, Display};
+use std::sync::Arc;
+use std::time::Duration;
 use yarnspinner_core::prelude::*;
+use yarnspinner_core::types::TypedValue as _;
 
 /// Co-ordinates the execution of Yarn programs.
 ///
 /// The main functions of interest are [`Dialogue::continue_`] and [`Dialogue::set_selected_option`].
+///
+/// ## Visit counts
+///
+/// Like in the original, a node counts as visited when it is completed, i.e. when its end is reached or it is left via
+/// `<<jump>>` or `<<return>>`, not when it is entered. Consequently:
+/// - `visited` and `visited_count` called from inside a node only see the visits completed before, not the running one.
+/// - A node that is detoured into recursively, directly or through a cycle of nodes, is counted once per invocation,
+///   each time that invocation completes. The innermost invocation is thus counted first.
+/// - Invocations that never complete are not counted, e.g. because [`Dialogue::stop`] was called or a `<<jump>>`
+///   abandoned the nodes waiting to be returned to.
 #[derive(Debug, Clone)]
 pub struct Dialogue {
     vm: VirtualMachine,
@@ -33,16 +46,119 @@ pub enum DialogueError {
         max_id: usize,
     },
     UnexpectedOptionSelectionError,
+    StaleOptionSelection {
+        presented_generation: usize,
+        current_generation: usize,
+    },
+    UnavailableOptionSelected {
+        selected_option_id: OptionId,
+    },
     ContinueOnOptionSelectionError,
+    ContinueOnDeferredFunctionError,
+    ContinueOnPendingCommandError,
+    CommandAbandoned,
+    UnexpectedFunctionCompletion {
+        token: FunctionCallToken,
+    },
+    DeferredFunctionReturnMismatch {
+        function: String,
+        expected: Type,
+        found: YarnValue,
+    },
     NoNodeSelectedOnContinue,
     NoProgramLoaded,
     InvalidNode {
         node_name: String,
     },
     VariableStorageError(VariableStorageError),
+    ProgramCombineError(ProgramCombineError),
     FunctionNotFound {
         function_name: String,
-        library: Library,
+        /// Boxed to keep [`DialogueError`] small.
+        library: Box<Library>,
+        /// The registered functions with names similar to `function_name`, closest first.
+        available: Vec<String>,
+        node: String,
+        source_file: Option<String>,
+        line: Option<usize>,
+    },
+    FunctionArgumentMismatch {
+        function: String,
+        parameter_index: usize,
+        /// Boxed to keep [`DialogueError`] small.
+        expected: Box<Type>,
+        /// Boxed to keep [`DialogueError`] small.
+        found: Box<YarnValue>,
+        node: String,
+        source_file: Option<String>,
+        line: Option<usize>,
+    },
+    FunctionArgumentCountMismatch {
+        function: String,
+        expected: usize,
+        found: usize,
+        node: String,
+        source_file: Option<String>,
+        line: Option<usize>,
+    },
+    InvalidCast {
+        function: String,
+        value: YarnValue,
+        /// Boxed to keep [`DialogueError`] small.
+        target: Box<Type>,
+        node: String,
+        source_file: Option<String>,
+        line: Option<usize>,
+    },
+    DivisionByZero {
+        function: String,
+        node: String,
+        source_file: Option<String>,
+        line: Option<usize>,
+    },
+    FunctionTimeoutSuspected {
+        function_name: String,
+        elapsed: Duration,
+        timeout: Duration,
+        node: String,
+        source_file: Option<String>,
+        line: Option<usize>,
+    },
+    RngReplayMismatch {
+        expected: RngDecision,
+        kind: RngDecisionKind,
+        node_name: String,
+        candidate_count: usize,
+    },
+    VariableTypeMismatch {
+        name: String,
+        expected: Type,
+        found: YarnValue,
+    },
+    SnapshotProgramMismatch {
+        snapshot_checksum: Option<u64>,
+        program_checksum: Option<u64>,
+    },
+    ReadOnlyVariable {
+        name: String,
+    },
+    SmartVariableCycle {
+        variable_names: Vec<String>,
+    },
+    DeferredFunctionInSmartVariable {
+        variable_name: String,
+        function_name: String,
+    },
+    UnsupportedLocale {
+        language_code: Option<Language>,
+    },
+    /// An instruction found the wrong values on the stack, e.g. because a program loaded with [`Program::from_bytes`]
+    /// was not produced by the compiler. Unlike its instructions and labels, a program's use of the stack depends on the
+    /// path taken through a node, so it can only be checked while running.
+    MalformedInstruction {
+        node: String,
+        instruction: usize,
+        reason: String,
     },
 }
 
@@ -52,6 +168,7 @@ impl Error for DialogueError {
         match self {
             MarkupParseError(e) => e.source(),
             VariableStorageError(e) => e.source(),
+            ProgramCombineError(e) => e.source(),
             _ => None,
         }
     }
@@ -65,12 +182,72 @@ impl Display for DialogueError {
             LineProviderError { id, language_code } => write!(f, "Line ID \"{id}\" not found in line provider with language code {language_code:?}"),
             InvalidOptionIdError { selected_option_id, max_id } => write!(f, "{selected_option_id:?} is not a valid option ID (expected a number between 0 and {max_id}."),
             UnexpectedOptionSelectionError => f.write_str("An option was selected, but the dialogue wasn't waiting for a selection. This method should only be called after the Dialogue is waiting for the user to select an option."),
+            StaleOptionSelection { presented_generation, current_generation } => write!(f, "An option from the option set with generation {presented_generation} was selected, but the options currently presented have generation {current_generation}. The options were discarded or presented again since."),
+            UnavailableOptionSelected { selected_option_id } => write!(f, "{selected_option_id:?} was selected, but it is not available because its condition failed."),
             ContinueOnOptionSelectionError => f.write_str("Dialogue was asked to continue running, but it is waiting for the user to select an option first."),
+            ContinueOnDeferredFunctionError => f.write_str("Dialogue was asked to continue running, but it is waiting for the return value of a deferred function first. Pass it to `Dialogue::complete_function`."),
+            ContinueOnPendingCommandError => f.write_str("Dialogue was asked to continue running, but it is waiting for a command to finish first. Call `CommandCompletion::complete` when it is done."),
+            CommandAbandoned => f.write_str("Dialogue was waiting for a command to finish, but every `CommandCompletion` of the command was dropped without completing it. The dialogue can be continued."),
+            UnexpectedFunctionCompletion { token } => write!(f, "The deferred function call {token} was completed, but the dialogue wasn't waiting for it."),
+            DeferredFunctionReturnMismatch { function, expected, found } => write!(f, "Deferred function \"{function}\" was completed with \"{found}\", which cannot be converted to its return type {expected}."),
             NoNodeSelectedOnContinue => f.write_str("Cannot continue running dialogue. No node has been selected."),
             NoProgramLoaded => f.write_str("No program has been loaded. Cannot continue running dialogue."),
             InvalidNode { node_name } => write!(f, "No node named \"{node_name}\" has been loaded."),
             VariableStorageError(e) => Display::fmt(e, f),
-            FunctionNotFound { function_name, library } => write!(f, "Function \"{function_name}\" not found in library: {library}"),
+            ProgramCombineError(e) => Display::fmt(e, f),
+            FunctionNotFound { function_name, library, available, node, source_file, line } => {
+                write!(f, "Function \"{function_name}\" not found in library{}.", SourceLocation { node, source_file, line })?;
+                if !available.is_empty() {
+                    let suggestions: Vec<_> = available.iter().map(|name| format!("\"{name}\"")).collect();
+                    write!(f, " Did you mean {}?", suggestions.join(" or "))?;
+                }
+                write!(f, " Registered functions: {library}")
+            }
+            FunctionArgumentMismatch { function, parameter_index, expected, found, node, source_file, line } => write!(f, "Function \"{function}\" expected parameter {parameter_index} to be of type {expected}, but received \"{found}\", which cannot be converted to it{}.", SourceLocation { node, source_file, line }),
+            FunctionArgumentCountMismatch { function, expected, found, node, source_file, line } => write!(f, "Function \"{function}\" expected {expected} parameter(s), but received {found}{}.", SourceLocation { node, source_file, line }),
+            InvalidCast { function, value, target, node, source_file, line } => write!(f, "Function \"{function}\" cannot convert \"{value}\" to {target}{}.", SourceLocation { node, source_file, line }),
+            DivisionByZero { function, node, source_file, line } => write!(f, "Function \"{function}\" divided an integer by zero{}, which has no result.", SourceLocation { node, source_file, line }),
+            FunctionTimeoutSuspected { function_name, elapsed, timeout, node, source_file, line } => write!(f, "Function \"{function_name}\" took {elapsed:?} to return, which exceeds the timeout of {timeout:?}{}. It could not be cancelled, so its return value was used anyway and the dialogue can be continued.", SourceLocation { node, source_file, line }),
+            RngReplayMismatch { expected, kind, node_name, candidate_count } => write!(f, "Replayed random decision {expected:?} does not match the decision the dialogue is making: a {kind:?} decision in node \"{node_name}\" between {candidate_count} candidate(s). The content changed since the decisions were recorded."),
+            VariableTypeMismatch { name, expected, found } => write!(f, "Variable \"{name}\" is declared as {expected}, but was set to \"{found}\", which is of type {}.", found.r#type()),
+            SnapshotProgramMismatch { snapshot_checksum, program_checksum } => write!(f, "Cannot restore a snapshot taken while running the program with checksum {} into a dialogue running the program with checksum {}. The program changed since the snapshot was taken.", ChecksumDisplay(snapshot_checksum), ChecksumDisplay(program_checksum)),
+            ReadOnlyVariable { name } => write!(f, "Variable \"{name}\" is a smart variable, which is computed from its declaration and can't be set."),
+            SmartVariableCycle { variable_names } => write!(f, "Smart variable \"{}\" is computed from itself: {}", variable_names[0], variable_names.join(" -> ")),
+            DeferredFunctionInSmartVariable { variable_name, function_name } => write!(f, "Smart variable \"{variable_name}\" calls the deferred function \"{function_name}\", whose return value can't be waited for while reading a variable."),
+            UnsupportedLocale { language_code } => write!(f, "The line contains a plural or ordinal marker, but there are no plural rules for language code {language_code:?} and no plural fallback language code with plural rules is set. See `supported_locales` for the language codes with plural rules."),
+            MalformedInstruction { node, instruction, reason } => write!(f, "Instruction {instruction} of node \"{node}\" is malformed: {reason}. Recompile the program from its Yarn source."),
+        }
+    }
+}
+
+/// Formats where in the Yarn source an error occurred, e.g. ` (node "Start", Start.yarn:12)`.
+pub(crate) struct SourceLocation<'a> {
+    pub(crate) node: &'a str,
+    pub(crate) source_file: &'a Option<String>,
+    pub(crate) line: &'a Option<usize>,
+}
+
+impl Display for SourceLocation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, " (node \"{}\"", self.node)?;
+        if let Some(source_file) = self.source_file {
+            write!(f, ", {source_file}")?;
+            if let Some(line) = self.line {
+                write!(f, ":{line}")?;
+            }
+        }
+        f.write_str(")")
+    }
+}
+
+/// Formats an optional [`Program::checksum`], e.g. `0x1a2b3c4d5e6f7a8b`.
+struct ChecksumDisplay<'a>(&'a Option<u64>);
+
+impl Display for ChecksumDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(checksum) => write!(f, "{checksum:#018x}"),
+            None => f.write_str("<none, no program loaded>"),
         }
     }
 }
@@ -87,6 +264,12 @@ impl From<VariableStorageError> for DialogueError {
     }
 }
 
+impl From<ProgramCombineError> for DialogueError {
+    fn from(source: ProgramCombineError) -> Self {
+        DialogueError::ProgramCombineError(source)
+    }
+}
+
 impl Dialogue {
     /// Creates a new [`Dialogue`] instance with the given [`VariableStorage`] and [`TextProvider`].
     /// - The [`TextProvider`] is used to retrieve the text of lines and options.
@@ -98,10 +281,15 @@ impl Dialogue {
         variable_storage: Box<dyn VariableStorage>,
         text_provider: Box<dyn TextProvider>,
     ) -> Self {
+        let rng = DialogueRng::default();
         let mut library = Library::standard_library();
-        library
-            .add_function("visited", visited(variable_storage.clone()))
-            .add_function("visited_count", visited_count(variable_storage.clone()));
+        library.add_dialogue_functions(
+            visited(variable_storage.clone()),
+            visited_count(variable_storage.clone()),
+            random(rng.shared()),
+            random_range(rng.shared()),
+            dice(rng.shared()),
+        );
 
         let dialogue_text_processor = Box::new(DialogueTextProcessor::new());
         let line_parser = LineParser::new()
@@ -109,11 +297,56 @@ impl Dialogue {
             .register_marker_processor("plural", dialogue_text_processor.clone())
             .register_marker_processor("ordinal", dialogue_text_processor);
 
+        let mut vm = VirtualMachine::new(library, variable_storage, line_parser, text_provider);
+        vm.rng = rng;
         Self {
-            vm: VirtualMachine::new(library, variable_storage, line_parser, text_provider),
+            vm,
             language_code: Default::default(),
         }
     }
+
+    /// Creates a new [`Dialogue`] that runs the same [`Program`] with the same [`Library`], e.g. for ambient barks running alongside the main conversation.
+    /// The program and library are shared instead of cloned, while the current node and all other execution state are independent.
+    /// The child also shares this dialogue's [`VariableStorage`] and [`TextProvider`], so variables set in one dialogue are visible in the other.
+    /// Use [`Dialogue::spawn_child_with_variable_storage`] to give the child its own variables instead.
+    /// The child draws from the same random number generator, see [`Dialogue::set_random_seed`], but does not inherit a recording or replay
+    /// started by [`Dialogue::set_rng_trace_capacity`] or [`Dialogue::set_rng_replay`].
+    ///
+    /// Settings such as [`Dialogue::set_line_hints_enabled`] are copied, but the [`ErrorReporter`] is not.
+    /// Later changes to either dialogue, including [`Dialogue::replace_program`], [`Dialogue::add_program`] and [`Dialogue::library_mut`],
+    /// are not propagated to the other one.
+    #[must_use]
+    pub fn spawn_child(&self) -> Dialogue {
+        let variable_storage = self.vm.variable_storage.clone_shallow();
+        Self {
+            vm: self
+                .vm
+                .spawn_child(self.vm.library.clone(), variable_storage),
+            language_code: self.language_code.clone(),
+        }
+    }
+
+    /// Like [`Dialogue::spawn_child`], but the child stores its variables in the given [`VariableStorage`],
+    /// which is initialized with the initial values of the program.
+    /// Since the built-in `visited` and `visited_count` functions need to read from the child's storage, the [`Library`] is cloned in this case.
+    #[must_use]
+    pub fn spawn_child_with_variable_storage(
+        &self,
+        variable_storage: Box<dyn VariableStorage>,
+    ) -> Dialogue {
+        let mut library = self.vm.library.as_ref().clone();
+        library
+            .add_function("visited", visited(variable_storage.clone()))
+            .add_function("visited_count", visited_count(variable_storage.clone()));
+        let mut child = Self {
+            vm: self.vm.spawn_child(Arc::new(library), variable_storage),
+            language_code: self.language_code.clone(),
+        };
+        if let Some(program) = self.vm.program.clone() {
+            child.extend_variable_storage_from(&program);
+        }
+        child
+    }
 }
 
 fn visited(storage: Box<dyn VariableStorage>) -> yarn_fn_type! { impl Fn(String) -> bool } {
@@ -138,6 +371,38 @@ fn visited_count(storage: Box<dyn VariableStorage>) -> yarn_fn_type! { impl Fn(S
     }
 }
 
+/// The number of equally likely results of `random`, which are all exactly representable as an [`f32`].
+const RANDOM_RESOLUTION: usize = 1 << f32::MANTISSA_DIGITS;
+
+fn random(rng: SharedRng) -> impl Fn() -> f32 + Send + Sync + Clone {
+    move || draw_fraction(&rng)
+}
+
+/// Both bounds are inclusive if they are integers.
+fn random_range(rng: SharedRng) -> yarn_fn_type! { impl Fn(f32, f32) -> f32 } {
+    move |min: f32, max: f32| {
+        if min >= max {
+            return min;
+        }
+        if min.fract() == 0.0 && max.fract() == 0.0 {
+            let candidate_count = (max as i64 - min as i64 + 1) as usize;
+            min + rng.choose_index(candidate_count) as f32
+        } else {
+            min + (max - min) * draw_fraction(&rng)
+        }
+    }
+}
+
+/// Rolls a die with the given number of sides, treating a die without sides as having one.
+fn dice(rng: SharedRng) -> yarn_fn_type! { impl Fn(u32) -> u32 } {
+    move |sides: u32| rng.choose_index(sides.max(1) as usize) as u32 + 1
+}
+
+/// Draws a number in `0.0..1.0`.
+fn draw_fraction(rng: &SharedRng) -> f32 {
+    rng.choose_index(RANDOM_RESOLUTION) as f32 / RANDOM_RESOLUTION as f32
+}
+
 impl Iterator for Dialogue {
     type Item = Vec<DialogueEvent>;
 
@@ -168,6 +433,10 @@ impl Dialogue {
 
     /// Sets the [`Dialogue`]'s language. A value of `None` means that you are using the base language, i.e. the one the Yarn files are written in.
     /// Returns the last language code.
+    ///
+    /// Numbers interpolated into lines are formatted for this language, e.g. `{$gold}` with a value of 1234.5 becomes "1,234.5" in "en-US"
+    /// and "1.234,5" in "de-DE". Values inside of markup such as `[plural value={$gold} .../]` are left as they are so that markers can parse them,
+    /// as are all values when using the base language. Use `{string($gold)}` to interpolate a number without formatting it.
     pub fn set_language_code(
         &mut self,
         language_code: impl Into<Option<Language>>,
@@ -177,6 +446,24 @@ impl Dialogue {
         std::mem::replace(&mut self.language_code, language_code)
     }
 
+    /// The language code whose plural rules the `plural` and `ordinal` markers use when there are none for [`Dialogue::language_code`],
+    /// not even for its base language. The default is `None`.
+    #[must_use]
+    pub fn plural_fallback_language_code(&self) -> Option<&Language> {
+        self.vm.plural_fallback_language_code()
+    }
+
+    /// Sets the language code to fall back to when there are no plural rules for [`Dialogue::language_code`] or its base language,
+    /// e.g. when using a custom locale for translations. Without one, running a line with a `plural` or `ordinal` marker
+    /// in such a language fails with [`DialogueError::UnsupportedLocale`]. See [`supported_locales`] for the language codes with plural rules.
+    /// Returns the last plural fallback language code.
+    pub fn set_plural_fallback_language_code(
+        &mut self,
+        language_code: impl Into<Option<Language>>,
+    ) -> Option<Language> {
+        self.vm.set_plural_fallback_language_code(language_code)
+    }
+
     /// Gets the [`Library`] that this Dialogue uses to locate functions.
     ///
     /// When the Dialogue is constructed, the Library is initialized with
@@ -187,9 +474,12 @@ impl Dialogue {
     }
 
     /// See [`Dialogue::library`].
+    ///
+    /// If the library is shared with dialogues created by [`Dialogue::spawn_child`], it is cloned first,
+    /// so changes only affect this dialogue.
     #[must_use]
     pub fn library_mut(&mut self) -> &mut Library {
-        &mut self.vm.library
+        Arc::make_mut(&mut self.vm.library)
     }
 
     /// Gets whether [`Dialogue::next`] is able able to return [`DialogueEvent::LineHints`] events.
@@ -206,6 +496,196 @@ impl Dialogue {
         self
     }
 
+    /// Gets whether the Yarn source position of every line and command is logged when it is run.
+    /// The default is `false`.
+    #[must_use]
+    pub fn log_debug_info(&self) -> bool {
+        self.vm.log_debug_info
+    }
+
+    /// Sets whether the Yarn source position of every line and command is logged with [`log::info!`] when it is run.
+    /// The positions are only known for nodes whose [`DebugInfo`] was passed to [`Dialogue::add_debug_info`].
+    /// The default is `false`.
+    pub fn set_log_debug_info(&mut self, enabled: bool) -> &mut Self {
+        self.vm.log_debug_info = enabled;
+        self
+    }
+
+    /// Gets whether options whose condition failed are delivered in [`DialogueEvent::Options`].
+    /// The default is `true`.
+    #[must_use]
+    pub fn should_send_unavailable_options(&self) -> bool {
+        self.vm.should_send_unavailable_options
+    }
+
+    /// Sets whether options whose condition failed are delivered in [`DialogueEvent::Options`], with [`DialogueOption::is_available`] set to `false`.
+    /// If disabled, they are left out, and the [`OptionId`]s of the remaining options stay the same.
+    /// Either way, selecting an unavailable option fails with [`DialogueError::UnavailableOptionSelected`].
+    /// The default is `true`.
+    pub fn set_should_send_unavailable_options(&mut self, enabled: bool) -> &mut Self {
+        self.vm.should_send_unavailable_options = enabled;
+        self
+    }
+
+    /// Gets the duration after which a call to a library function is logged as slow. See [`Dialogue::set_function_warning_threshold`].
+    #[must_use]
+    pub fn function_warning_threshold(&self) -> Option<Duration> {
+        self.vm.function_dispatcher.warning_threshold
+    }
+
+    /// Sets the duration after which a call to a library function is logged with [`log::warn!`], together with the node and Yarn source position.
+    /// The default is [`None`], i.e. calls are not timed.
+    ///
+    /// Timing calls requires [`std::time::Instant`], which is not available on all platforms, e.g. `wasm32-unknown-unknown`.
+    pub fn set_function_warning_threshold(
+        &mut self,
+        threshold: impl Into<Option<Duration>>,
+    ) -> &mut Self {
+        self.vm.function_dispatcher.warning_threshold = threshold.into();
+        self
+    }
+
+    /// Gets the duration after which a call to a library function fails. See [`Dialogue::set_function_timeout`].
+    #[must_use]
+    pub fn function_timeout(&self) -> Option<Duration> {
+        self.vm.function_dispatcher.timeout
+    }
+
+    /// Sets the duration after which a call to a library function makes [`Dialogue::continue_`] fail with [`DialogueError::FunctionTimeoutSuspected`].
+    /// The default is [`None`].
+    ///
+    /// Functions cannot be interrupted, so the error is only returned once the function returned.
+    /// Its return value is still used, so calling [`Dialogue::continue_`] again resumes the dialogue.
+    /// For functions that are known to be slow, use [`Library::add_deferred_function`] instead.
+    ///
+    /// Timing calls requires [`std::time::Instant`], which is not available on all platforms, e.g. `wasm32-unknown-unknown`.
+    pub fn set_function_timeout(&mut self, timeout: impl Into<Option<Duration>>) -> &mut Self {
+        self.vm.function_dispatcher.timeout = timeout.into();
+        self
+    }
+
+    /// Seeds the random number generator used for random decisions, such as picking one of the equally salient lines of a line group,
+    /// and by the built-in `random`, `random_range` and `dice` functions.
+    /// The same seed thus leads to the same dialogue flow, given the same choices and variables.
+    /// By default, it is seeded from the operating system's entropy, except on `wasm32-unknown-unknown`, where the default seed is always the same.
+    pub fn set_random_seed(&mut self, seed: u64) -> &mut Self {
+        self.vm.rng.set_seed(seed);
+        self
+    }
+
+    /// Returns the current state of the random number generator, see [`Dialogue::set_random_seed`].
+    /// Store it when saving the game and restore it with [`Dialogue::set_rng_state`] to get the same random outcomes after loading.
+    #[must_use]
+    pub fn rng_state(&self) -> RngState {
+        self.vm.rng.state()
+    }
+
+    /// Restores a state of the random number generator returned by [`Dialogue::rng_state`].
+    pub fn set_rng_state(&mut self, state: RngState) -> &mut Self {
+        self.vm.rng.set_state(state);
+        self
+    }
+
+    /// Captures the current state of the dialogue, i.e. the position in the running nodes, the pending options,
+    /// the variables and the state of the random number generator. Pass it to [`Dialogue::restore`] to rewind to this point.
+    ///
+    /// The [`Program`] is not copied, so this is cheap enough to call after every line. Note that the variables
+    /// are read from the [`VariableStorage`], so a storage with many variables makes for a larger snapshot.
+    #[must_use]
+    pub fn snapshot(&self) -> DialogueSnapshot {
+        self.vm.snapshot()
+    }
+
+    /// Rewinds the dialogue to a state captured by [`Dialogue::snapshot`], including the values of all variables.
+    /// Events that were not yet returned by [`Dialogue::continue_`] are discarded.
+    /// If the snapshot was taken while waiting for an option selection, present [`DialogueSnapshot::pending_options`] again before selecting one.
+    ///
+    /// Returns an error and leaves the dialogue untouched if the snapshot was taken while running a different [`Program`],
+    /// as determined by comparing their [`Program::checksum`]s.
+    pub fn restore(&mut self, snapshot: DialogueSnapshot) -> Result<&mut Self> {
+        self.vm.restore(snapshot)?;
+        Ok(self)
+    }
+
+    /// Starts recording every random decision of this dialogue, including the results of the built-in `random`, `random_range` and `dice` functions,
+    /// keeping only the last `capacity` ones,
+    /// or stops recording if `capacity` is [`None`], which is the default.
+    /// The recorded decisions can be retrieved with [`Dialogue::take_rng_trace`] and passed to [`Dialogue::set_rng_replay`]
+    /// to reproduce a run regardless of the random seed, e.g. for bug reports.
+    ///
+    /// Stops a replay started by [`Dialogue::set_rng_replay`].
+    pub fn set_rng_trace_capacity(&mut self, capacity: impl Into<Option<usize>>) -> &mut Self {
+        self.vm.rng.set_trace_capacity(capacity.into());
+        self
+    }
+
+    /// Returns the random decisions recorded since the last call, oldest first. See [`Dialogue::set_rng_trace_capacity`].
+    #[must_use]
+    pub fn take_rng_trace(&mut self) -> Vec<RngDecision> {
+        self.vm.rng.take_trace()
+    }
+
+    /// Makes the next random decisions use the recorded ones instead of drawing random values, in order.
+    /// Once they are used up, values are drawn randomly again.
+    ///
+    /// If the content changed since the decisions were recorded, so that a decision is made in a different place
+    /// or between a different number of candidates than recorded, [`Dialogue::continue_`] fails with [`DialogueError::RngReplayMismatch`].
+    /// Stops recording started by [`Dialogue::set_rng_trace_capacity`].
+    pub fn set_rng_replay(&mut self, trace: impl IntoIterator<Item = RngDecision>) -> &mut Self {
+        self.vm.rng.set_replay(trace);
+        self
+    }
+
+    /// Sets the [`SaliencyStrategy`] that decides which line of a line group or which node of a node group is run
+    /// when the conditions of several of them pass. Defaults to [`BestSaliencyStrategy`].
+    pub fn set_saliency_strategy(&mut self, strategy: Box<dyn SaliencyStrategy>) -> &mut Self {
+        self.vm.saliency_strategy = strategy;
+        self
+    }
+
+    /// Adds the [`DebugInfo`] of compiled nodes, as found in `Compilation::debug_info`.
+    /// Errors that occur while running these nodes will then mention the file and line they were caused by.
+    pub fn add_debug_info(&mut self, debug_info: HashMap<String, DebugInfo>) -> &mut Self {
+        self.vm.debug_infos.extend(debug_info);
+        self
+    }
+
+    /// Adds the hashtags of lines, as found in the `metadata` of the `StringInfo`s in `Compilation::string_table`.
+    /// Delivered [`Line`]s and the lines of [`DialogueOption`]s then carry them in [`Line::metadata`].
+    /// `line:` tags are left out, as they are the IDs of the lines.
+    pub fn add_line_metadata(&mut self, metadata: HashMap<LineId, Vec<String>>) -> &mut Self {
+        let metadata = metadata.into_iter().map(|(line_id, mut tags)| {
+            tags.retain(|tag| !tag.starts_with("line:"));
+            (line_id, tags)
+        });
+        self.vm.line_metadata.extend(metadata);
+        self
+    }
+
+    /// Returns where in the Yarn source the instruction that was run last was produced from,
+    /// e.g. the line, options or command that the last call to [`Dialogue::continue_`] delivered.
+    /// This is useful to report where a command that failed to run was written.
+    ///
+    /// Returns [`None`] if the dialogue is not running or the [`DebugInfo`] of the current node was not passed to [`Dialogue::add_debug_info`].
+    #[must_use]
+    pub fn current_line_info(&self) -> Option<LineInfo> {
+        self.vm.last_line_info()
+    }
+
+    /// Returns where in the Yarn source the instruction at the given index of the given node was produced from,
+    /// if the [`DebugInfo`] of the node was passed to [`Dialogue::add_debug_info`].
+    /// Since node names are unique across all files of a [`Program`], they determine the file as well.
+    #[must_use]
+    pub fn line_info(&self, node_name: &str, instruction_number: usize) -> Option<LineInfo> {
+        self.vm.line_info(node_name, instruction_number)
+    }
+
+    /// Sets the [`ErrorReporter`] that is notified of every error returned while running the dialogue.
+    pub fn set_error_reporter(&mut self, error_reporter: Box<dyn ErrorReporter>) -> &mut Self {
+        self.vm.error_reporter.replace(error_reporter);
+        self
+    }
+
     /// Gets the currently registered [`TextProvider`].
     pub fn text_provider(&self) -> &dyn TextProvider {
         self.vm.text_provider()
@@ -217,6 +697,7 @@ impl Dialogue {
     }
 
     /// Gets the currently registered [`VariableStorage`].
+    /// Wrap it in a [`NotifyingVariableStorage`] before passing it to [`Dialogue::new`] to be notified when the dialogue changes a variable.
     pub fn variable_storage(&self) -> &dyn VariableStorage {
         self.vm.variable_storage()
     }
@@ -225,6 +706,64 @@ impl Dialogue {
     pub fn variable_storage_mut(&mut self) -> &mut dyn VariableStorage {
         self.vm.variable_storage_mut()
     }
+
+    /// Gets the value of a variable, e.g. `"$player_name"`, from the [`VariableStorage`].
+    /// Falls back to the initial value declared in the loaded [`Program`] if the variable has not been stored yet.
+    /// The value of a smart variable, e.g. `<<declare $is_rich = $gold > 100>>`, is computed from its declaration instead.
+    /// Returns [`None`] if the variable is neither stored nor declared, or if computing a smart variable failed.
+    #[must_use]
+    pub fn get_variable(&self, name: &str) -> Option<YarnValue> {
+        if let Some(value) = self.vm.smart_variable_value(name) {
+            return value
+                .map_err(|e| error!("Failed to compute the value of {name}: {e}"))
+                .ok();
+        }
+        self.variable_storage()
+            .get(name)
+            .ok()
+            .or_else(|| self.declared_initial_value(name))
+    }
+
+    /// Sets the value of a variable, e.g. `"$player_name"`, in the [`VariableStorage`].
+    ///
+    /// If the loaded [`Program`] declares the variable, the value must be of the declared type,
+    /// otherwise a [`DialogueError::VariableTypeMismatch`] is returned and the variable is left unchanged.
+    /// Variables without a declaration are stored as-is, since the game may use them for its own bookkeeping.
+    /// Smart variables are computed from their declaration and can't be set, see [`DialogueError::ReadOnlyVariable`].
+    pub fn set_variable(&mut self, name: &str, value: impl Into<YarnValue>) -> Result<()> {
+        let value = value.into();
+        let is_smart_variable = self
+            .vm
+            .program
+            .as_ref()
+            .is_some_and(|program| program.smart_variable(name).is_some());
+        if is_smart_variable {
+            return Err(DialogueError::ReadOnlyVariable {
+                name: name.to_owned(),
+            });
+        }
+        if let Some(initial_value) = self.declared_initial_value(name) {
+            let expected = initial_value.r#type();
+            if value.r#type() != expected {
+                return Err(DialogueError::VariableTypeMismatch {
+                    name: name.to_owned(),
+                    expected,
+                    found: value,
+                });
+            }
+        }
+        self.variable_storage_mut().set(name.to_owned(), value)?;
+        Ok(())
+    }
+
+    fn declared_initial_value(&self, name: &str) -> Option<YarnValue> {
+        self.vm
+            .program
+            .as_ref()?
+            .initial_values
+            .get(name)
+            .map(|value| value.clone().into())
+    }
 }
 
 // VM proxy
@@ -268,39 +807,73 @@ impl Dialogue {
     }
 
     /// Sets or replaces the [`Dialogue`]'s current [`Program`]. The program is replaced, all current state is reset.
+    ///
+    /// Dialogues created by [`Dialogue::spawn_child`] keep running the previous program.
     pub fn replace_program(&mut self, program: Program) -> &mut Self {
-        self.vm.program.replace(program.clone());
+        self.vm.set_program(program.clone());
         self.vm.reset_state();
         self.extend_variable_storage_from(&program);
         self
     }
 
+    /// Replaces the [`Dialogue`]'s current [`Program`] without losing track of where the dialogue currently is.
+    /// This is intended for hot reloading changed Yarn files.
+    ///
+    /// Variables that already have a value in the [`VariableStorage`] keep it; only new variables receive their initial value.
+    /// If the current node still exists in the new program with the same number of instructions, execution continues where it left off
+    /// and this method returns `true`. Otherwise, or if the dialogue is waiting for an option selection,
+    /// all current state is reset like in [`Dialogue::replace_program`] and `false` is returned.
+    pub fn reload_program(&mut self, program: Program) -> bool {
+        let position_kept = self.vm.reload_program(program.clone());
+        let new_variables: HashMap<String, YarnValue> = program
+            .initial_values
+            .into_iter()
+            .filter(|(name, _)| !self.variable_storage().contains(name))
+            .map(|(name, value)| (name, value.into()))
+            .collect();
+        if let Err(e) = self.variable_storage_mut().extend(new_variables) {
+            error!(
+                "Failed to populate VariableStorage with initial values: {}",
+                e
+            );
+        }
+        position_kept
+    }
+
     /// Merges the currently set [`Program`] with the given one. If there is no program set, the given one is set.
-    pub fn add_program(&mut self, program: Program) -> &mut Self {
-        if let Some(existing_program) = self.vm.program.as_mut() {
-            *existing_program =
-                Program::combine(vec![existing_program.clone(), program.clone()]).unwrap();
+    ///
+    /// Returns an error if the programs cannot be merged, e.g. because both contain a node with the same name.
+    pub fn add_program(&mut self, program: Program) -> Result<&mut Self> {
+        if let Some(existing_program) = self.vm.program.as_ref() {
+            let combined =
+                Program::combine(vec![existing_program.as_ref().clone(), program.clone()])?;
+            self.vm.set_program(combined);
         } else {
-            self.vm.program.replace(program.clone());
+            self.vm.set_program(program.clone());
             self.vm.reset_state();
         }
         self.extend_variable_storage_from(&program);
 
-        self
+        Ok(self)
     }
 
     /// Prepares the [`Dialogue`] that the user intends to start running a node.
     ///
     /// After this method is called, you call [`Dialogue::next`] to start executing it.
     ///
+    /// This can also be called while the dialogue is running, e.g. to resume a quest at a specific node.
+    /// Execution then restarts at the beginning of `node_name`, and any pending options or function calls are discarded.
+    ///
     /// If [`Dialogue::line_hints_enabled`] has been set, the next [`Dialogue::next`] call will return a [`DialogueEvent::LineHints`],
     /// as the Dialogue determines which lines may be delivered during the `node_name` node's execution.
     ///
     /// ## Errors
     ///
-    /// Returns an error if no node with the value of `node_name` has been loaded.
+    /// Returns an error if no node with the value of `node_name` has been loaded. The dialogue then stays where it was.
     pub fn set_node(&mut self, node_name: impl Into<String>) -> Result<&mut Self> {
-        self.vm.set_node(node_name)?;
+        self.vm
+            .set_node(node_name)
+            .map_err(|error| self.vm.report_error(error))?;
         Ok(self)
     }
 
@@ -328,12 +901,16 @@ impl Dialogue {
     }
 
     /// Gets the names of the nodes in the currently loaded Program, if there is one.
+    /// The nodes holding the expressions of smart variables are not included.
     #[must_use]
     pub fn node_names(&self) -> Option<impl Iterator<Item = &str>> {
-        self.vm
-            .program
-            .as_ref()
-            .map(|program| program.nodes.keys().map(|s| s.as_str()))
+        self.vm.program.as_ref().map(|program| {
+            program
+                .nodes
+                .values()
+                .filter(|node| !node.is_smart_variable())
+                .map(|node| node.name.as_str())
+        })
     }
 
     /// Returns the line ID that contains the original, uncompiled source
@@ -380,6 +957,25 @@ impl Dialogue {
         })
     }
 
+    /// Returns the headers for the node `node_name`, with the values of each key in order of appearance.
+    ///
+    /// Unlike [`Dialogue::get_headers_for_node`], this keeps all values of keys that appear more than once,
+    /// which Yarn allows, and borrows them from the [`Program`] instead of copying them.
+    ///
+    /// Returns [`None`] if the node is not present in the program.
+    #[must_use]
+    pub fn node_headers(&self, node_name: &str) -> Option<HashMap<&str, Vec<&str>>> {
+        let node = self.vm.program.as_ref()?.nodes.get(node_name)?;
+        let mut headers: HashMap<_, Vec<_>> = HashMap::new();
+        for header in &node.headers {
+            headers
+                .entry(header.key.as_str())
+                .or_default()
+                .push(header.value.as_str());
+        }
+        Some(headers)
+    }
+
     /// Gets a value indicating whether a specified node exists in the [`Program`].
     #[must_use]
     pub fn node_exists(&self, node_name: &str) -> bool {
@@ -434,18 +1030,87 @@ impl Dialogue {
     ///
     /// The ID number that should be passed as the parameter to this method should be the [`OptionId`]
     /// field in the [`DialogueOption`] that represents the user's selection.
+    /// Passing the [`DialogueOption`] itself additionally verifies that it belongs to the currently presented options.
     ///
-    /// ## Panics
+    /// ## Errors
     /// - If the Dialogue is not expecting an option to be selected.
     /// - If the option ID is not found in the vector of [`DialogueOption`] provided by [`DialogueEvent::Options`].
+    /// - If a [`DialogueOption`] is passed whose [`DialogueOption::generation`] is not [`Dialogue::option_generation`].
+    /// - If the option is not [available](DialogueOption::is_available).
     ///
     /// ## See Also
     /// - [`Dialogue::continue_`]
-    pub fn set_selected_option(&mut self, selected_option_id: OptionId) -> Result<&mut Self> {
-        self.vm.set_selected_option(selected_option_id)?;
+    pub fn set_selected_option(
+        &mut self,
+        selection: impl Into<OptionSelection>,
+    ) -> Result<&mut Self> {
+        self.vm
+            .set_selected_option(selection.into())
+            .map_err(|error| self.vm.report_error(error))?;
+        Ok(self)
+    }
+
+    /// Passes the return value of a [`DeferredFunctionCall`] to the [`Dialogue`], which resumes evaluating the expression
+    /// the call was part of on the next [`Dialogue::continue_`].
+    ///
+    /// ## Errors
+    /// - If the Dialogue is not waiting for the call identified by `token`.
+    /// - If `value` cannot be converted to the return type of the function. The call stays pending in this case.
+    pub fn complete_function(
+        &mut self,
+        token: FunctionCallToken,
+        value: impl Into<YarnValue>,
+    ) -> Result<&mut Self> {
+        self.vm
+            .complete_function(token, value.into())
+            .map_err(|error| self.vm.report_error(error))?;
         Ok(self)
     }
 
+    /// Keeps the [`Dialogue`] from continuing until the command that produced `result` is done, i.e. until [`CommandCompletion::complete`] is called.
+    /// Does nothing if the command was [`CommandResult::Completed`] right away. Replaces the command waited for before, if any.
+    ///
+    /// Since [`Dialogue::continue_`] fails with [`DialogueError::ContinueOnPendingCommandError`] until then, poll [`Dialogue::is_waiting_for_command`] before continuing.
+    /// If every clone of the [`CommandCompletion`] is dropped without completing the command, the next [`Dialogue::continue_`] fails
+    /// with [`DialogueError::CommandAbandoned`] once, after which the dialogue can be continued.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use yarnspinner_runtime::prelude::*;
+    /// # let mut dialogue = Dialogue::new(Box::new(MemoryVariableStorage::new()), Box::new(StringTableTextProvider::new()));
+    /// let mut dispatcher = CommandDispatcher::new();
+    /// dispatcher.add_command("walk", |_character: String, _target: String| {
+    ///     let (result, completion) = CommandResult::in_progress();
+    ///     std::thread::spawn(move || {
+    ///         // Walk the character to the target...
+    ///         completion.complete();
+    ///     });
+    ///     result
+    /// });
+    ///
+    /// // Usually, the commands come from a `DialogueEvent::Command`
+    /// let command = Command {
+    ///     name: "walk".to_owned(),
+    ///     parameters: vec!["Player".into(), "Door".into()],
+    ///     raw: "walk Player Door".to_owned(),
+    /// };
+    /// dialogue.wait_for_command(dispatcher.dispatch(&command).unwrap());
+    /// while dialogue.is_waiting_for_command() {
+    ///     std::thread::yield_now();
+    /// }
+    /// ```
+    pub fn wait_for_command(&mut self, result: impl Into<CommandResult>) -> &mut Self {
+        self.vm.wait_for_command(result.into());
+        self
+    }
+
+    /// The generation of the currently presented options. See [`DialogueOption::generation`].
+    #[must_use]
+    pub fn option_generation(&self) -> usize {
+        self.vm.option_generation()
+    }
+
     /// Gets a value indicating whether the Dialogue is currently executing Yarn instructions.
     #[must_use]
     pub fn is_active(&self) -> bool {
@@ -458,6 +1123,19 @@ impl Dialogue {
     pub fn is_waiting_for_option_selection(&self) -> bool {
         self.vm.is_waiting_for_option_selection()
     }
+
+    /// Returns `true` if the last call to [`Dialogue::continue_`] returned [`DialogueEvent::DeferredFunctionCall`] and the dialogue is therefore
+    /// waiting for its return value via [`Dialogue::complete_function`]. If this is `true`, calling [`Dialogue::continue_`] will error
+    /// and [`Dialogue::next`] will panic.
+    pub fn is_waiting_for_deferred_function(&self) -> bool {
+        self.vm.is_waiting_for_deferred_function()
+    }
+
+    /// Returns `true` if a command passed to [`Dialogue::wait_for_command`] is still running. If this is `true`, calling [`Dialogue::continue_`] will error
+    /// and [`Dialogue::next`] will return [`None`].
+    pub fn is_waiting_for_command(&self) -> bool {
+        self.vm.is_waiting_for_command()
+    }
 }
 
 #[cfg(test)]
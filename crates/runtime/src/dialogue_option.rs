@@ -24,17 +24,35 @@ pub struct DialogueOption {
     /// The name of the node that will be run if this option is selected.
     ///
     /// The value of this property not be valid if this is a shortcut option.
+    /// See [`DialogueOption::destination`] for the node that the option's content jumps to.
     pub destination_node: String,
 
+    /// The node that the content of this option jumps to when it is selected, e.g. `Shop` for
+    /// ```yarn
+    /// -> Let's go shopping.
+    ///     <<jump Shop>>
+    /// ```
+    /// [`None`] if the dialogue continues after the options instead, or the jump's destination is only known at runtime.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub destination: Option<String>,
+
     /// Gets a value indicating whether the player should be permitted to select this option.
     ///
     /// If this value is `false`, this option had a line condition on it that failed.
-    /// The option will still be delivered to the game, but, depending on the needs of the game,
-    /// the game may decide to not allow the player to select it, or not offer it to the player at all.
+    /// Unless disabled with [`Dialogue::set_should_send_unavailable_options`], the option will still be delivered to the game,
+    /// which may decide to show it as unselectable or not offer it to the player at all.
+    /// Selecting it fails with [`DialogueError::UnavailableOptionSelected`].
     ///
     /// This is intended for situations where games wish to show options that the player _could_ have taken,
     /// if some other condition had been met (e.g. having enough "charisma" points).
     pub is_available: bool,
+
+    /// The generation of the option set this option was presented in.
+    ///
+    /// Every time the [`Dialogue`] presents options or discards the ones it presented, the generation is increased.
+    /// Selecting this option via [`Dialogue::set_selected_option`] fails with [`DialogueError::StaleOptionSelection`]
+    /// if its generation is no longer the current one.
+    pub generation: usize,
 }
 
 /// The identifying number for an option. You should not need to create these yourself, since you get them from [`DialogueOption`]s.
@@ -51,8 +69,52 @@ pub struct DialogueOption {
 )]
 pub struct OptionId(pub usize);
 
+impl DialogueOption {
+    /// The hashtags written after the option, see [`Line::metadata`].
+    pub fn metadata(&self) -> &[String] {
+        self.line.metadata()
+    }
+}
+
 impl Display for OptionId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0)
     }
 }
+
+/// The option to select in [`Dialogue::set_selected_option`].
+///
+/// Created from an [`OptionId`], which always selects from the currently presented options,
+/// or from a [`DialogueOption`], which additionally checks that the option was presented in the current set of options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct OptionSelection {
+    /// The ID of the selected option.
+    pub id: OptionId,
+    /// The generation the option was presented in. If [`None`], the generation is not checked.
+    pub generation: Option<usize>,
+}
+
+impl From<OptionId> for OptionSelection {
+    fn from(id: OptionId) -> Self {
+        Self {
+            id,
+            generation: None,
+        }
+    }
+}
+
+impl From<&DialogueOption> for OptionSelection {
+    fn from(option: &DialogueOption) -> Self {
+        Self {
+            id: option.id,
+            generation: Some(option.generation),
+        }
+    }
+}
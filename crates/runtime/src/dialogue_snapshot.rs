@@ -0,0 +1,66 @@
+//! Not part of the original implementation.
+
+use crate::prelude::*;
+use std::collections::HashMap;
+
+/// The complete state of a running [`Dialogue`] at one point in time, as returned by [`Dialogue::snapshot`].
+/// Pass it to [`Dialogue::restore`] to rewind the dialogue to that point, e.g. for a "rewind dialogue" feature or to store it in a save file.
+///
+/// A snapshot contains the position in the current node and in the nodes that detoured into it, the pending options,
+/// the values of all variables, including the visit counts, and the state of the random number generator.
+/// It refers to the nodes of the [`Program`] by name and is thus cheap to take, but can only be restored
+/// into a dialogue running the same program. See [`DialogueSnapshot::program_checksum`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct DialogueSnapshot {
+    pub(crate) program_checksum: Option<u64>,
+    pub(crate) current_node_name: Option<String>,
+    pub(crate) last_program_counter: usize,
+    pub(crate) state: State,
+    pub(crate) execution_state: ExecutionState,
+    /// The names of the nodes that detoured into the current one, together with the state to resume them in.
+    pub(crate) call_stack: Vec<(String, State)>,
+    pub(crate) saliency_candidates: Vec<SaliencyCandidate>,
+    pub(crate) pending_function_call: Option<PendingFunctionCall>,
+    pub(crate) option_generation: usize,
+    pub(crate) variables: HashMap<String, YarnValue>,
+    pub(crate) rng_state: RngState,
+}
+
+impl DialogueSnapshot {
+    /// The [`Program::checksum`] of the program the dialogue was running when the snapshot was taken,
+    /// or [`None`] if no program was loaded.
+    #[must_use]
+    pub fn program_checksum(&self) -> Option<u64> {
+        self.program_checksum
+    }
+
+    /// The name of the node the dialogue was running, if any.
+    #[must_use]
+    pub fn current_node(&self) -> Option<&str> {
+        self.current_node_name.as_deref()
+    }
+
+    /// The options the dialogue was waiting for a selection of, so that they can be presented again after restoring the snapshot.
+    /// Empty if the dialogue was not waiting for an option selection.
+    #[must_use]
+    pub fn pending_options(&self) -> &[DialogueOption] {
+        if self.execution_state == ExecutionState::WaitingOnOptionSelection {
+            &self.state.current_options
+        } else {
+            &[]
+        }
+    }
+
+    /// The values of all variables, including the visit counts of the nodes.
+    #[must_use]
+    pub fn variables(&self) -> &HashMap<String, YarnValue> {
+        &self.variables
+    }
+}
@@ -0,0 +1,108 @@
+//! Not part of the original implementation.
+
+use crate::prelude::*;
+use log::Level;
+use std::fmt::Debug;
+use yarnspinner_core::prelude::*;
+
+/// Gets notified of every [`DialogueError`] a [`Dialogue`] runs into, in addition to the error being returned.
+/// Set with [`Dialogue::set_error_reporter`].
+///
+/// This is intended for forwarding errors to the people who can fix them, e.g. a writer's editor during playtesting,
+/// which is why the [`RuntimeErrorReport`] refers back to the Yarn source where possible.
+pub trait ErrorReporter: Debug + Send + Sync {
+    /// Creates a shallow clone of this error reporter, i.e. a clone that
+    /// shares the same underlying reporter and will thus be perfectly in sync
+    /// with the original instance.
+    fn clone_shallow(&self) -> Box<dyn ErrorReporter>;
+    /// Called for every error before it is returned to the caller.
+    fn report(&mut self, report: &RuntimeErrorReport);
+}
+
+impl Clone for Box<dyn ErrorReporter> {
+    fn clone(&self) -> Self {
+        self.clone_shallow()
+    }
+}
+
+/// An [`ErrorReporter`] that forwards every error to the [`log`] crate at a fixed [`Level`], prefixed with its `path:line:column` where known.
+/// This way, runtime errors end up in the same logging pipeline as the rest of an application,
+/// including [`tracing`](https://docs.rs/tracing) subscribers that capture `log` records.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_runtime::prelude::*;
+/// # let mut dialogue = Dialogue::new(Box::new(MemoryVariableStorage::new()), Box::new(StringTableTextProvider::new()));
+/// dialogue.set_error_reporter(Box::new(LogErrorReporter::new(log::Level::Warn)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LogErrorReporter {
+    level: Level,
+}
+
+impl LogErrorReporter {
+    /// Creates a reporter that logs errors at the given [`Level`].
+    pub fn new(level: Level) -> Self {
+        Self { level }
+    }
+
+    /// The [`Level`] that errors are logged at.
+    pub fn level(&self) -> Level {
+        self.level
+    }
+}
+
+impl Default for LogErrorReporter {
+    /// Logs errors at [`Level::Error`].
+    fn default() -> Self {
+        Self::new(Level::Error)
+    }
+}
+
+impl ErrorReporter for LogErrorReporter {
+    fn clone_shallow(&self) -> Box<dyn ErrorReporter> {
+        Box::new(*self)
+    }
+
+    fn report(&mut self, report: &RuntimeErrorReport) {
+        match report.source_location() {
+            Some(source_location) => log::log!(self.level, "{source_location}: {}", report.error),
+            None => log::log!(self.level, "{}", report.error),
+        }
+    }
+}
+
+/// A [`DialogueError`] together with where in the dialogue it occurred. Passed to an [`ErrorReporter`].
+///
+/// The source coordinates are only known if the [`DebugInfo`] of the running node was passed to [`Dialogue::add_debug_info`].
+#[derive(Debug)]
+pub struct RuntimeErrorReport<'a> {
+    /// The error that occurred.
+    pub error: &'a DialogueError,
+    /// The node that was running when the error occurred, if any.
+    pub node: Option<String>,
+    /// The index of the instruction in the node that was running when the error occurred.
+    pub instruction_index: usize,
+    /// The Yarn file that the failing instruction was compiled from.
+    pub file_name: Option<String>,
+    /// The one-based line in [`RuntimeErrorReport::file_name`] that the failing instruction was compiled from.
+    pub line: Option<usize>,
+    /// The one-based column in [`RuntimeErrorReport::file_name`] that the failing instruction was compiled from.
+    pub column: Option<usize>,
+    /// The IDs of the last lines that were delivered before the error occurred, oldest first.
+    pub recent_line_ids: Vec<LineId>,
+}
+
+impl RuntimeErrorReport<'_> {
+    /// Formats the source coordinates as `file:line:column`, which most editors and terminals recognize as a link.
+    /// Returns [`None`] if the source file is not known.
+    pub fn source_location(&self) -> Option<String> {
+        let file_name = self.file_name.as_ref()?;
+        Some(match (self.line, self.column) {
+            (Some(line), Some(column)) => format!("{file_name}:{line}:{column}"),
+            (Some(line), None) => format!("{file_name}:{line}"),
+            _ => file_name.clone(),
+        })
+    }
+}
@@ -32,6 +32,18 @@ pub enum DialogueEvent {
     /// It is not specified whether the command should be finished executing before calling [`Dialogue::continue_`] again or it is run in parallel.
     /// A library wrapping Yarn Spinner for a game engine should specify this.
     Command(Command),
+    /// The dialogue pauses for the given duration because of a `<<wait>>` command, e.g. `<<wait 2>>` or `<<wait {$delay}>>`.
+    /// Call [`Dialogue::continue_`] again once the duration has elapsed.
+    ///
+    /// Commands named `wait` whose only parameter is not a non-negative number of seconds are delivered as [`DialogueEvent::Command`] instead.
+    ///
+    /// ## Implementation note
+    ///
+    /// Not part of the original implementation, where `wait` is a command of the Unity plugin.
+    Wait(std::time::Duration),
+    /// A function registered with [`Library::add_deferred_function`] was called.
+    /// Its return value must be passed to [`Dialogue::complete_function`] before calling [`Dialogue::continue_`] again.
+    DeferredFunctionCall(DeferredFunctionCall),
     /// The node with the given name was completed.
     NodeComplete(String),
     /// The node with the given name was entered.
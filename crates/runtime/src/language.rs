@@ -1,10 +1,13 @@
 #[cfg(any(feature = "bevy", feature = "serde"))]
 use crate::prelude::*;
 use core::fmt::Display;
-use icu_locid::LanguageIdentifier;
+use icu_locid::{LanguageIdentifier, Locale};
 
 /// IETF BCP 47 code.
 /// The default is "en-US".
+///
+/// Extensions such as the calendar in "th-TH-u-ca-buddhist" or private use subtags like "en-US-x-custom" are dropped,
+/// since translations and plural rules are looked up by language, script, region and variants only.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[non_exhaustive]
@@ -13,7 +16,8 @@ impl Language {
     /// Creates a new `Language` from a string. Panics if the string is not a valid IETF BCP 47 code.
     pub fn new(language: impl Into<String>) -> Self {
         let language = language.into();
-        Self(language.parse().unwrap())
+        let locale: Locale = language.parse().unwrap();
+        Self(locale.id)
     }
 }
 
@@ -4,19 +4,40 @@
 //! - If you're a game developer, you'll want to use a crate that is already designed for your game engine of choice,
 //!     such as [`bevy_yarnspinner`](https://crates.io/crates/bevy_yarnspinner) for the [Bevy engine](https://bevyengine.org/).
 //! - If you wish to write an adapter crate for an engine yourself, use the [`yarnspinner`](https://crates.io/crates/yarnspinner) crate.
+//!
+//! ## Running without a filesystem
+//!
+//! The runtime never touches the filesystem, so it also runs on targets without one, such as `wasm32-unknown-unknown`.
+//! Load the compiled program from its bytes with [`Program::from_bytes`](yarnspinner_core::prelude::Program::from_bytes),
+//! pass the strings of the string table to [`StringTableTextProvider::extend_base_language`](prelude::StringTableTextProvider::extend_base_language)
+//! and store the variables in a [`MemoryVariableStorage`](prelude::MemoryVariableStorage).
+//! Both can be embedded into the binary with [`include_bytes!`] or fetched by the host.
+//!
+//! `wasm32-unknown-unknown` offers no entropy without a JavaScript host, so the random number generator always starts from the same seed there.
+//! Seed it with [`Dialogue::set_random_seed`](prelude::Dialogue::set_random_seed) if the outcome of `random`, `dice` and line groups should vary.
 
 #![warn(missing_docs, missing_debug_implementations)]
 mod analyser;
 mod command;
+mod command_completion;
+mod command_dispatcher;
+mod deferred_function;
 mod dialogue;
 mod dialogue_option;
+mod dialogue_snapshot;
+mod error_reporter;
 mod events;
 mod language;
 mod line;
 pub mod markup;
+mod notifying_variable_storage;
 mod pluralization;
+mod rng_trace;
+mod saliency;
 mod text_provider;
+mod value_formatting;
 mod variable_storage;
+mod variable_watcher;
 mod virtual_machine;
 
 pub use dialogue::Result;
@@ -26,15 +47,30 @@ pub mod prelude {
     pub use crate::{
         analyser::*,
         command::*,
+        command_completion::*,
+        command_dispatcher::*,
+        deferred_function::*,
         dialogue::{Dialogue, DialogueError},
         dialogue_option::*,
+        dialogue_snapshot::*,
+        error_reporter::*,
         events::*,
         language::*,
         line::*,
         markup::MarkupParseError,
+        notifying_variable_storage::*,
+        pluralization::supported_locales,
+        rng_trace::{RngDecision, RngDecisionKind, RngState},
+        saliency::*,
         text_provider::*,
         variable_storage::*,
+        variable_watcher::*,
+    };
+    pub(crate) use crate::{
+        pluralization::*,
+        rng_trace::{DialogueRng, SharedRng},
+        value_formatting::*,
+        virtual_machine::*,
     };
-    pub(crate) use crate::{pluralization::*, virtual_machine::*};
     pub(crate) use yarnspinner_core::prelude::*;
 }
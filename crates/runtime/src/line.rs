@@ -38,9 +38,49 @@ pub struct Line {
     pub text: String,
     /// The list of [`MarkupAttribute`] in this parse result.
     pub attributes: Vec<MarkupAttribute>,
+    /// The text as returned by the [`TextProvider`], before markup was parsed.
+    /// Contains placeholders such as `{0}` where the values in [`Line::substitutions`] are inserted.
+    pub raw_text: String,
+    /// The values of the expressions in the line, e.g. the value of `$gold` in `You have {$gold} gold`,
+    /// in the order of their placeholders in [`Line::raw_text`].
+    pub substitutions: Vec<YarnValue>,
+    /// The hashtags written after the line, e.g. `emotion:happy` and `shout` for `Hello! #emotion:happy #shout`,
+    /// without the `#` and without the `line:` tag, which is the [`Line::id`].
+    /// Only available if the metadata was passed to [`Dialogue::add_line_metadata`].
+    pub metadata: Vec<String>,
 }
 
 impl Line {
+    /// Inserts the [`Line::substitutions`] into the placeholders of [`Line::raw_text`], as is done before parsing the markup into [`Line::text`].
+    /// Useful when presenting the values differently, e.g. by animating a number counting up.
+    /// Unlike in [`Line::text`], numbers are not formatted for the dialogue's language.
+    ///
+    /// ## Examples
+    /// ```rust
+    /// # use yarnspinner_core::prelude::*;
+    /// # use yarnspinner_runtime::prelude::*;
+    /// # let line = Line {
+    /// #    id: "line".into(),
+    /// #    text: "You have 10 gold".to_owned(),
+    /// #    attributes: vec![],
+    /// #    raw_text: "You have {0} gold".to_owned(),
+    /// #    substitutions: vec![10.into()],
+    /// #    metadata: vec![],
+    /// # };
+    /// assert_eq!("You have {0} gold", line.raw_text);
+    /// assert_eq!(vec![YarnValue::from(10)], line.substitutions);
+    /// assert_eq!("You have 10 gold", line.expand_substitutions());
+    /// ```
+    #[must_use]
+    pub fn expand_substitutions(&self) -> String {
+        expand_substitutions(&self.raw_text, &self.substitutions)
+    }
+
+    /// The hashtags written after the line, see [`Line::metadata`].
+    pub fn metadata(&self) -> &[String] {
+        &self.metadata
+    }
+
     /// Gets the first attribute with the specified name, if present.
     ///
     /// ## Implementation note
@@ -68,6 +108,9 @@ impl Line {
     /// #        properties: HashMap::from([("name".to_owned(), "Alice".into())]),
     /// #        source_position: 0,
     /// #    }],
+    /// #    raw_text: String::new(),
+    /// #    substitutions: vec![],
+    /// #    metadata: vec![],
     /// # };
     /// assert_eq!("Alice: Hello! How are you today?", line.text);
     /// assert_eq!(Some("Alice"), line.character_name());
@@ -83,6 +126,9 @@ impl Line {
     /// #    id: "line".into(),
     /// #    text: "Great, thanks".to_owned(),
     /// #    attributes: vec![],
+    /// #    raw_text: "Great, thanks".to_owned(),
+    /// #    substitutions: vec![],
+    /// #    metadata: vec![],
     /// # };
     /// assert_eq!("Great, thanks", line.text);
     /// assert!(line.character_name().is_none());
@@ -120,6 +166,9 @@ impl Line {
     /// #        properties: HashMap::from([("name".to_owned(), "Alice".into())]),
     /// #        source_position: 0,
     /// #    }],
+    /// #    raw_text: String::new(),
+    /// #    substitutions: vec![],
+    /// #    metadata: vec![],
     /// # };
     /// assert_eq!("Alice: Hello! How are you today?", line.text);
     /// assert_eq!("Hello! How are you today?", &line.text_without_character_name());
@@ -135,6 +184,9 @@ impl Line {
     /// #    id: "line".into(),
     /// #    text: "Great, thanks".to_owned(),
     /// #    attributes: vec![],
+    /// #    raw_text: "Great, thanks".to_owned(),
+    /// #    substitutions: vec![],
+    /// #    metadata: vec![],
     /// # };
     /// assert_eq!("Great, thanks", line.text);
     /// assert_eq!("Great, thanks", &line.text_without_character_name());
@@ -201,6 +253,9 @@ impl Line {
                 id: self.id.clone(),
                 text: self.text.to_string(),
                 attributes,
+                raw_text: self.raw_text.clone(),
+                substitutions: self.substitutions.clone(),
+                metadata: self.metadata.clone(),
             };
         }
         let deletion_start = attribute_to_delete.position;
@@ -276,6 +331,9 @@ impl Line {
             id: self.id.clone(),
             text: edited_substring,
             attributes,
+            raw_text: self.raw_text.clone(),
+            substitutions: self.substitutions.clone(),
+            metadata: self.metadata.clone(),
         }
     }
 }
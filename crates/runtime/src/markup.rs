@@ -359,6 +359,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn invalid_replacement_markers_are_errors() {
+        for (input, expected) in [
+            (
+                "[select male=\"he\"/]",
+                MarkupParseError::MissingReplacementValue {
+                    name: "select".to_owned(),
+                    position: 0,
+                },
+            ),
+            (
+                "[select value=\"other\" male=\"he\"/]",
+                MarkupParseError::NoReplacementForCase {
+                    name: "select".to_owned(),
+                    case: "other".to_owned(),
+                },
+            ),
+            (
+                "[plural value=\"many\" one=\"a cat\" other=\"% cats\"/]",
+                MarkupParseError::ValueIsNotANumber {
+                    name: "plural".to_owned(),
+                    value: "many".to_owned(),
+                },
+            ),
+            (
+                "[plural value=1 few=\"a few cats\"/]",
+                MarkupParseError::NoReplacementForCase {
+                    name: "plural".to_owned(),
+                    case: "one".to_owned(),
+                },
+            ),
+        ] {
+            let mut line_parser = line_parser();
+            line_parser.set_language_code(Language::from("en"));
+            let error = line_parser.parse_markup(input).unwrap_err();
+
+            assert_eq!(expected, error, "input: {input}");
+        }
+    }
+
     fn line_parser() -> LineParser {
         let dialogue_text_processor = Box::new(DialogueTextProcessor::new());
 
@@ -394,6 +434,9 @@ mod tests {
                 id: "test".into(),
                 text: self.text.clone(),
                 attributes: self.attributes.clone(),
+                raw_text: self.text.clone(),
+                substitutions: vec![],
+                metadata: vec![],
             }
         }
     }
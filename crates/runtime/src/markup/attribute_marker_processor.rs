@@ -1,7 +1,7 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner/YarnSpinner.Markup/IAttributeMarkerProcessor.cs>
 
 pub(crate) use self::{dialogue_text_processor::*, no_markup_text_processor::*};
-use crate::markup::MarkupAttributeMarker;
+use crate::markup::{MarkupAttributeMarker, Result};
 use crate::prelude::Language;
 use core::fmt::Debug;
 
@@ -16,8 +16,9 @@ pub(crate) trait AttributeMarkerProcessor: Debug + Send + Sync {
     /// If the marker is an `open` marker, the text from the marker's
     /// position to its corresponding closing marker is provided as a string
     /// property called `contents`.
-    fn replacement_text_for_marker(&self, marker: &MarkupAttributeMarker) -> String;
+    fn replacement_text_for_marker(&self, marker: &MarkupAttributeMarker) -> Result<String>;
     fn set_language_code(&mut self, language_code: Option<Language>);
+    fn set_plural_fallback_language_code(&mut self, language_code: Option<Language>);
     fn clone_box(&self) -> Box<dyn AttributeMarkerProcessor>;
 }
 
@@ -1,6 +1,6 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner/YarnSpinner/Dialogue.cs>, which was split into multiple files.
 
-use crate::markup::AttributeMarkerProcessor;
+use crate::markup::{AttributeMarkerProcessor, MarkupParseError, Result};
 use crate::prelude::*;
 use icu_plurals::PluralCategory;
 use std::collections::HashSet;
@@ -8,6 +8,7 @@ use std::collections::HashSet;
 #[derive(Default, Debug, Clone)]
 pub(crate) struct DialogueTextProcessor {
     pub(crate) language_code: Option<Language>,
+    pub(crate) plural_fallback_language_code: Option<Language>,
 }
 
 impl DialogueTextProcessor {
@@ -25,63 +26,90 @@ impl AttributeMarkerProcessor for DialogueTextProcessor {
     /// Returns the text that should be used to replace the
     /// contents of `marker`.
     ///
-    /// ## Panics
-    /// Panics when the string contains a `plural` or `ordinal` marker, but the specified value cannot be parsed as a number.
-    fn replacement_text_for_marker(&self, marker: &crate::markup::MarkupAttributeMarker) -> String {
-        let value_prop = marker
-            .properties
-            .get("value")
-            .expect("Expected a property \"value\"");
+    /// ## Errors
+    /// - [`MarkupParseError::MissingReplacementValue`] when the marker has no `value` property.
+    /// - [`MarkupParseError::ValueIsNotANumber`] for `plural` and `ordinal` markers whose value cannot be parsed as a number.
+    /// - [`MarkupParseError::UnsupportedLocale`] for `plural` and `ordinal` markers when neither the language code
+    ///   nor the plural fallback language code resolve to a locale with plural rules.
+    /// - [`MarkupParseError::NoReplacementForCase`] when the marker has no replacement for the value of a `select` marker,
+    ///   or for neither the plural case of the value nor `other`.
+    fn replacement_text_for_marker(
+        &self,
+        marker: &crate::markup::MarkupAttributeMarker,
+    ) -> Result<String> {
+        let name = marker.name.clone().unwrap_or_default();
+        let value_prop = marker.properties.get("value").ok_or_else(|| {
+            MarkupParseError::MissingReplacementValue {
+                name: name.clone(),
+                position: marker.position,
+            }
+        })?;
         let value = value_prop.to_string();
 
         // Apply the "select" marker
-        if marker.name.as_ref().unwrap() == "select" {
-            let replacement_prop = marker
-                .properties
-                .get(&value)
-                .unwrap_or_else(|| panic!("error: no replacement for {value}"));
+        if name == "select" {
+            let replacement_prop = marker.properties.get(&value).ok_or_else(|| {
+                MarkupParseError::NoReplacementForCase {
+                    name: name.clone(),
+                    case: value.clone(),
+                }
+            })?;
             let replacement = replacement_prop.to_string();
 
-            return replace_value_placeholders(&replacement, &value);
+            return Ok(replace_value_placeholders(&replacement, &value));
         }
 
         // If it's not "select", then it's "plural" or "ordinal"
 
-        let language_code = self.language_code.as_ref()
-            .expect("Dialogue locale code is not set. 'plural' and 'ordinal' markers cannot be called unless one is set.");
-
         // Attempt to parse the value as a float, so we can determine its plural class
-        let value_as_float = value
-            .parse::<f32>()
-            .unwrap_or_else(|_| panic!("Error while pluralising line: '{value}' is not a number"));
-
-        // Implementation note: no need to fiddle with locales here because ICU already does fallbacks for us.
-
+        let value_as_float =
+            value
+                .parse::<f32>()
+                .map_err(|_| MarkupParseError::ValueIsNotANumber {
+                    name: name.clone(),
+                    value: value.clone(),
+                })?;
+
+        // Implementation note: ICU falls back to the root rules for locales it has no data for,
+        // which are wrong for most languages, so we resolve the locale ourselves.
         // I would love to cache this, but `icu_plural::PluralRules` is not `Send` because it contains an `Rc`, so even a mutex can't help here :(
-        let plural_case =
-            match marker.name.as_ref().unwrap().as_str() {
-                "plural" => Pluralization::new(language_code.clone())
-                    .get_cardinal_plural_case(value_as_float),
-                "ordinal" => Pluralization::new(language_code.clone())
-                    .get_ordinal_plural_case(value_as_float),
-                _ => panic!("Invalid marker name {:?}", marker.name),
-            };
+        let pluralization = Pluralization::try_new(
+            self.language_code.as_ref(),
+            self.plural_fallback_language_code.as_ref(),
+        )
+        .ok_or_else(|| MarkupParseError::UnsupportedLocale { name: name.clone() })?;
+        let plural_case = match name.as_str() {
+            "plural" => pluralization.get_cardinal_plural_case(value_as_float),
+            "ordinal" => pluralization.get_ordinal_plural_case(value_as_float),
+            _ => panic!("Invalid marker name {:?}", marker.name),
+        };
         let plural_case_name = plural_case_name(plural_case);
 
         // Now that we know the plural case, we can select the
-        // appropriate replacement text for it
-        let replacement_value = marker.properties.get(plural_case_name).unwrap_or_else(|| {
-            panic!("error: no replacement for {value}'s plural case of {plural_case_name}")
-        });
+        // appropriate replacement text for it.
+        // Every locale has an "other" case, so it serves as the fallback for
+        // e.g. the "few" and "many" cases of Polish when the writer only provided "one" and "other".
+        let replacement_value = marker
+            .properties
+            .get(plural_case_name)
+            .or_else(|| marker.properties.get("other"))
+            .ok_or_else(|| MarkupParseError::NoReplacementForCase {
+                name,
+                case: plural_case_name.to_owned(),
+            })?;
         let input = replacement_value.to_string();
 
-        replace_value_placeholders(&input, &value)
+        Ok(replace_value_placeholders(&input, &value))
     }
 
     fn set_language_code(&mut self, language_code: Option<Language>) {
         self.language_code = language_code;
     }
 
+    fn set_plural_fallback_language_code(&mut self, language_code: Option<Language>) {
+        self.plural_fallback_language_code = language_code;
+    }
+
     fn clone_box(&self) -> Box<dyn AttributeMarkerProcessor> {
         Box::new(self.clone())
     }
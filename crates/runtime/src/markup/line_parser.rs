@@ -217,6 +217,16 @@ impl LineParser {
         }
     }
 
+    pub(crate) fn set_plural_fallback_language_code(
+        &mut self,
+        language_code: impl Into<Option<Language>>,
+    ) {
+        let language_code = language_code.into();
+        for processor in self.marker_processors.values_mut() {
+            processor.set_plural_fallback_language_code(language_code.clone());
+        }
+    }
+
     /// Parses an open, close, self-closing, or close-all attribute marker.
     fn parse_attribute_marker(&mut self) -> Result<MarkupAttributeMarker> {
         // Implementation note: -1 because the original increments `source_position` at the end of the loop in `parse_markup`,
@@ -361,7 +371,7 @@ impl LineParser {
             .marker_processors
             .get(name)
             .unwrap()
-            .replacement_text_for_marker(marker);
+            .replacement_text_for_marker(marker)?;
         Ok(replacement)
     }
 
@@ -49,6 +49,22 @@ pub enum MarkupParseError {
         name: String,
         position: usize,
     },
+    /// Converted to [`DialogueError::UnsupportedLocale`](crate::prelude::DialogueError::UnsupportedLocale) when running dialogue.
+    UnsupportedLocale {
+        name: String,
+    },
+    MissingReplacementValue {
+        name: String,
+        position: usize,
+    },
+    ValueIsNotANumber {
+        name: String,
+        value: String,
+    },
+    NoReplacementForCase {
+        name: String,
+        case: String,
+    },
 }
 
 impl Error for MarkupParseError {}
@@ -79,6 +95,10 @@ impl fmt::Display for MarkupParseError {
                 name,
                 position,
             } => write!(f, "Unterminated marker {name} in line {input} at position {position}"),
+            UnsupportedLocale { name } => write!(f, "Cannot replace {name} marker: there are no plural rules for the language code"),
+            MissingReplacementValue { name, position } => write!(f, "Cannot replace {name} marker at position {position}: it has no \"value\" property"),
+            ValueIsNotANumber { name, value } => write!(f, "Cannot replace {name} marker: '{value}' is not a number"),
+            NoReplacementForCase { name, case } => write!(f, "Cannot replace {name} marker: there is no replacement for the case \"{case}\""),
         }
     }
 }
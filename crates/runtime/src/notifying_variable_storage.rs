@@ -0,0 +1,280 @@
+//! Not part of the original implementation.
+
+use crate::prelude::*;
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{self, Debug};
+use std::sync::{Arc, Mutex, TryLockError};
+
+/// A callback registered with [`NotifyingVariableStorage::on_change`].
+/// Receives the name of the changed variable, its new value and its previous value, if it had one.
+pub type VariableChangeCallback = dyn FnMut(&str, &YarnValue, Option<&YarnValue>) + Send;
+
+/// A [`VariableStorage`] that wraps another one and calls back whenever a variable is written to,
+/// e.g. to update a quest log when a Yarn script sets `$quest_stage`.
+///
+/// Unlike [`VariableWatcher`], every single write is reported, in the order it happened.
+/// Writes via [`VariableStorage::set`] and [`VariableStorage::extend`] are reported, [`VariableStorage::clear`] is not.
+///
+/// ## Locking
+///
+/// The callbacks are never called while the wrapped storage is locked, so they may read and write variables themselves,
+/// e.g. through a [`VariableStorage::clone_shallow`] of this storage.
+/// A write made from inside a callback does not call back recursively. Instead, it is queued up and reported
+/// as soon as the current callback returns. The same goes for writes from other threads while a callback is running.
+/// Note that a callback that unconditionally writes a variable will thus be called forever.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_runtime::prelude::*;
+/// # use yarnspinner_core::prelude::*;
+/// # use std::sync::{Arc, Mutex};
+/// let mut storage = NotifyingVariableStorage::new(Box::new(MemoryVariableStorage::new()));
+/// let changes = Arc::new(Mutex::new(Vec::new()));
+/// let recorded_changes = changes.clone();
+/// storage.on_change(move |name, value, previous_value| {
+///     recorded_changes
+///         .lock()
+///         .unwrap()
+///         .push((name.to_owned(), value.clone(), previous_value.cloned()));
+/// });
+///
+/// storage.set("$quest_stage".to_owned(), 1.into()).unwrap();
+/// storage.set("$quest_stage".to_owned(), 2.into()).unwrap();
+///
+/// assert_eq!(
+///     vec![
+///         ("$quest_stage".to_owned(), YarnValue::from(1), None),
+///         ("$quest_stage".to_owned(), YarnValue::from(2), Some(YarnValue::from(1))),
+///     ],
+///     *changes.lock().unwrap()
+/// );
+/// ```
+#[derive(Clone)]
+pub struct NotifyingVariableStorage {
+    storage: Box<dyn VariableStorage>,
+    callbacks: Arc<Mutex<Vec<Box<VariableChangeCallback>>>>,
+    pending_changes: Arc<Mutex<VecDeque<VariableChange>>>,
+}
+
+#[derive(Debug)]
+struct VariableChange {
+    name: String,
+    value: YarnValue,
+    previous_value: Option<YarnValue>,
+}
+
+impl Debug for NotifyingVariableStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NotifyingVariableStorage")
+            .field("storage", &self.storage)
+            .field("callbacks", &"<callbacks>")
+            .field("pending_changes", &self.pending_changes)
+            .finish()
+    }
+}
+
+impl NotifyingVariableStorage {
+    /// Wraps the given [`VariableStorage`]. Writes through this storage are reported to its callbacks,
+    /// so the wrapped storage should not be written to directly afterwards.
+    pub fn new(storage: Box<dyn VariableStorage>) -> Self {
+        Self {
+            storage,
+            callbacks: Default::default(),
+            pending_changes: Default::default(),
+        }
+    }
+
+    /// Registers a callback that is called with the name, the new value and the previous value of every written variable.
+    /// The callback is shared with all shallow clones of this storage.
+    pub fn on_change(
+        &self,
+        callback: impl FnMut(&str, &YarnValue, Option<&YarnValue>) + Send + 'static,
+    ) -> &Self {
+        self.callbacks.lock().unwrap().push(Box::new(callback));
+        self
+    }
+
+    /// Returns the wrapped [`VariableStorage`].
+    pub fn inner(&self) -> &dyn VariableStorage {
+        self.storage.as_ref()
+    }
+
+    fn notify(&self, changes: impl IntoIterator<Item = VariableChange>) {
+        self.pending_changes.lock().unwrap().extend(changes);
+        loop {
+            let mut callbacks = match self.callbacks.try_lock() {
+                Ok(callbacks) => callbacks,
+                Err(TryLockError::Poisoned(error)) => error.into_inner(),
+                // Whoever is running the callbacks right now will also report our changes
+                Err(TryLockError::WouldBlock) => return,
+            };
+            // Don't hold the queue while calling back, so that the callbacks can write variables
+            let next_change = || self.pending_changes.lock().unwrap().pop_front();
+            while let Some(change) = next_change() {
+                for callback in callbacks.iter_mut() {
+                    callback(&change.name, &change.value, change.previous_value.as_ref());
+                }
+            }
+            drop(callbacks);
+            // Another thread may have queued a change after we were done but before we released the callbacks
+            if self.pending_changes.lock().unwrap().is_empty() {
+                return;
+            }
+        }
+    }
+}
+
+impl VariableStorage for NotifyingVariableStorage {
+    fn clone_shallow(&self) -> Box<dyn VariableStorage> {
+        Box::new(self.clone())
+    }
+
+    fn set(&mut self, name: String, value: YarnValue) -> crate::variable_storage::Result<()> {
+        let previous_value = self.storage.get(&name).ok();
+        self.storage.set(name.clone(), value.clone())?;
+        self.notify([VariableChange {
+            name,
+            value,
+            previous_value,
+        }]);
+        Ok(())
+    }
+
+    fn get(&self, name: &str) -> crate::variable_storage::Result<YarnValue> {
+        self.storage.get(name)
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.storage.contains(name)
+    }
+
+    fn extend(
+        &mut self,
+        values: HashMap<String, YarnValue>,
+    ) -> crate::variable_storage::Result<()> {
+        let mut changes: Vec<_> = values
+            .iter()
+            .map(|(name, value)| VariableChange {
+                name: name.clone(),
+                value: value.clone(),
+                previous_value: self.storage.get(name).ok(),
+            })
+            .collect();
+        VariableStorage::extend(self.storage.as_mut(), values)?;
+        changes.sort_by(|a, b| a.name.cmp(&b.name));
+        self.notify(changes);
+        Ok(())
+    }
+
+    fn variables(&self) -> HashMap<String, YarnValue> {
+        self.storage.variables()
+    }
+
+    /// Clears the wrapped storage. Since cleared variables have no value anymore, this is not reported to the callbacks.
+    fn clear(&mut self) {
+        self.storage.clear();
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_from_callbacks_are_reported_after_the_callback_returns() {
+        let mut storage = NotifyingVariableStorage::new(Box::new(MemoryVariableStorage::new()));
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let recorded_changes = changes.clone();
+        let mut quest_log = storage.clone_shallow();
+        storage.on_change(move |name, value, _previous_value| {
+            recorded_changes.lock().unwrap().push(name.to_owned());
+            if name == "$quest_stage" {
+                // Reading and writing from inside the callback must not deadlock
+                let stage = quest_log.get("$quest_stage").unwrap();
+                assert_eq!(value, &stage);
+                quest_log
+                    .set("$quest_log_entries".to_owned(), stage)
+                    .unwrap();
+                quest_log
+                    .set("$quest_log_dirty".to_owned(), true.into())
+                    .unwrap();
+            }
+        });
+
+        storage.set("$quest_stage".to_owned(), 2.into()).unwrap();
+
+        assert_eq!(
+            vec!["$quest_stage", "$quest_log_entries", "$quest_log_dirty"],
+            *changes.lock().unwrap()
+        );
+        assert_eq!(
+            YarnValue::from(2),
+            storage.get("$quest_log_entries").unwrap()
+        );
+    }
+
+    #[test]
+    fn reports_previous_values_of_extended_variables() {
+        let mut storage = NotifyingVariableStorage::new(Box::new(MemoryVariableStorage::new()));
+        storage.set("$gold".to_owned(), 5.into()).unwrap();
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let recorded_changes = changes.clone();
+        storage.on_change(move |name, value, previous_value| {
+            recorded_changes.lock().unwrap().push((
+                name.to_owned(),
+                value.clone(),
+                previous_value.cloned(),
+            ));
+        });
+
+        let values = HashMap::from([
+            ("$gold".to_owned(), 10.into()),
+            ("$name".to_owned(), "Sally".into()),
+        ]);
+        storage.extend(values).unwrap();
+
+        assert_eq!(
+            vec![
+                (
+                    "$gold".to_owned(),
+                    YarnValue::from(10),
+                    Some(YarnValue::from(5))
+                ),
+                ("$name".to_owned(), YarnValue::from("Sally"), None),
+            ],
+            *changes.lock().unwrap()
+        );
+    }
+
+    #[test]
+    fn writes_from_other_threads_during_callbacks_are_reported() {
+        let mut storage = NotifyingVariableStorage::new(Box::new(MemoryVariableStorage::new()));
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let recorded_changes = changes.clone();
+        let other_storage = storage.clone_shallow();
+        storage.on_change(move |name, _value, _previous_value| {
+            recorded_changes.lock().unwrap().push(name.to_owned());
+            if name == "$first" {
+                let mut other_storage = other_storage.clone_shallow();
+                std::thread::spawn(move || other_storage.set("$second".to_owned(), 2.into()))
+                    .join()
+                    .unwrap()
+                    .unwrap();
+            }
+        });
+
+        storage.set("$first".to_owned(), 1.into()).unwrap();
+
+        assert_eq!(vec!["$first", "$second"], *changes.lock().unwrap());
+    }
+}
@@ -1,8 +1,65 @@
 use crate::prelude::Language;
 use fixed_decimal::{DoublePrecision, FixedDecimal};
+use icu_locid::LanguageIdentifier;
 use icu_plurals::{PluralCategory, PluralRuleType};
 use icu_plurals::{PluralOperands, PluralRules};
 
+/// The locales with plural rules in the CLDR data that is compiled into the runtime,
+/// followed by the languages whose only plural category is `other`, which share the root rules.
+///
+/// Not part of the original implementation, which leaves locale support to .NET's `CultureInfo`.
+const SUPPORTED_LOCALES: &[&str] = &[
+    "af", "am", "ar", "as", "ast", "az", "be", "bg", "bho", "blo", "bn", "br", "brx", "bs", "ca",
+    "ceb", "chr", "cs", "cy", "da", "de", "doi", "dsb", "el", "en", "eo", "es", "et", "eu", "fa",
+    "fi", "fil", "fo", "fr", "fy", "ga", "gd", "gl", "gu", "ha", "he", "hi", "hr", "hsb", "hu",
+    "hy", "ia", "is", "it", "ka", "kk", "kn", "ks", "ku", "ky", "lb", "lij", "lt", "lv", "mk",
+    "ml", "mn", "mr", "mt", "ne", "nl", "no", "or", "pa", "pcm", "pl", "prg", "ps", "pt", "pt-PT",
+    "rm", "ro", "ru", "sat", "sc", "sd", "si", "sk", "sl", "so", "sq", "sr", "sv", "sw", "syr",
+    "ta", "te", "ti", "tk", "tr", "ug", "uk", "ur", "uz", "vec", "xh", "zu",
+    // Only `other`
+    "bm", "bo", "dz", "hnj", "id", "ig", "ii", "ja", "jbo", "jv", "kde", "kea", "km", "ko", "lkt",
+    "lo", "ms", "my", "nqo", "osa", "sah", "ses", "sg", "su", "th", "to", "tpi", "vi", "wo", "yo",
+    "yue", "zh",
+];
+
+/// Returns the locales whose plural rules are known, i.e. the ones the `plural` and `ordinal` markers work with,
+/// e.g. to let users pick one from a list. More specific language codes such as "en-US" resolve to these,
+/// see [`Dialogue::set_plural_fallback_language_code`](crate::prelude::Dialogue::set_plural_fallback_language_code).
+pub fn supported_locales() -> Vec<Language> {
+    SUPPORTED_LOCALES
+        .iter()
+        .map(|locale| Language::new(*locale))
+        .collect()
+}
+
+/// Returns the supported locale whose plural rules apply to `language`, trying in order:
+/// - the language code as is, e.g. "pt-PT"
+/// - the language and region, dropping script and variants, e.g. "sr-Latn-XK" -> "sr-XK"
+/// - the base language, e.g. "sr"
+///
+/// and then the same for `fallback`. Extensions were already dropped when the [`Language`] was created.
+pub(crate) fn resolve_plural_locale(
+    language: Option<&Language>,
+    fallback: Option<&Language>,
+) -> Option<Language> {
+    language
+        .into_iter()
+        .chain(fallback)
+        .flat_map(|language| fallback_chain(&language.0))
+        .find(|id| SUPPORTED_LOCALES.contains(&id.to_string().as_str()))
+        .map(Language)
+}
+
+fn fallback_chain(id: &LanguageIdentifier) -> [LanguageIdentifier; 3] {
+    let mut language_and_region = LanguageIdentifier::from(id.language);
+    language_and_region.region = id.region;
+    [
+        id.clone(),
+        language_and_region,
+        LanguageIdentifier::from(id.language),
+    ]
+}
+
 #[derive(Debug)]
 pub(crate) struct Pluralization {
     cardinal_rules: PluralRules,
@@ -10,15 +67,20 @@ pub(crate) struct Pluralization {
 }
 
 impl Pluralization {
-    pub(crate) fn new(language: impl Into<Language>) -> Self {
-        let language = language.into();
+    /// Returns [`None`] if neither `language` nor `fallback` resolve to a supported locale, see [`resolve_plural_locale`],
+    /// or if the compiled data has no plural rules for the locale they resolve to.
+    pub(crate) fn try_new(
+        language: Option<&Language>,
+        fallback: Option<&Language>,
+    ) -> Option<Self> {
+        let language = resolve_plural_locale(language, fallback)?;
         let locale = language.0.into();
-        let cardinal_rules = PluralRules::try_new(&locale, PluralRuleType::Cardinal).unwrap();
-        let ordinal_rules = PluralRules::try_new(&locale, PluralRuleType::Ordinal).unwrap();
-        Self {
+        let cardinal_rules = PluralRules::try_new(&locale, PluralRuleType::Cardinal).ok()?;
+        let ordinal_rules = PluralRules::try_new(&locale, PluralRuleType::Ordinal).ok()?;
+        Some(Self {
             cardinal_rules,
             ordinal_rules,
-        }
+        })
     }
 
     pub(crate) fn get_cardinal_plural_case(&self, value: f32) -> PluralCategory {
@@ -111,7 +173,7 @@ mod tests {
         ];
 
         for (locale, value, expected_category) in cardinal_tests.into_iter() {
-            let result = Pluralization::new(locale).get_cardinal_plural_case(value);
+            let result = pluralization(locale).get_cardinal_plural_case(value);
             assert_eq!(
                 expected_category, result,
                 "locale: {locale}, value: {value}, type: Cardinal"
@@ -119,11 +181,108 @@ mod tests {
         }
 
         for (locale, value, expected_category) in ordinal_tests.into_iter() {
-            let result = Pluralization::new(locale).get_ordinal_plural_case(value);
+            let result = pluralization(locale).get_ordinal_plural_case(value);
             assert_eq!(
                 expected_category, result,
                 "locale: {locale}, value: {value}, type: Ordinal"
             );
         }
     }
+
+    #[test]
+    fn test_few_and_many_plurals() {
+        let cardinal_tests = [
+            // Arabic
+            ("ar", 103.0_f32, PluralCategory::Few),
+            ("ar", 110.0, PluralCategory::Few),
+            ("ar", 111.0, PluralCategory::Many),
+            ("ar", 199.0, PluralCategory::Many),
+            ("ar", 102.0, PluralCategory::Other),
+            // Polish
+            ("pl", 0.0, PluralCategory::Many),
+            ("pl", 12.0, PluralCategory::Many),
+            ("pl", 14.0, PluralCategory::Many),
+            ("pl", 21.0, PluralCategory::Many),
+            ("pl", 22.0, PluralCategory::Few),
+            ("pl", 104.0, PluralCategory::Few),
+            ("pl", 112.0, PluralCategory::Many),
+            // Ukrainian
+            ("uk", 1.0, PluralCategory::One),
+            ("uk", 21.0, PluralCategory::One),
+            ("uk", 11.0, PluralCategory::Many),
+            ("uk", 2.0, PluralCategory::Few),
+            ("uk", 24.0, PluralCategory::Few),
+            ("uk", 12.0, PluralCategory::Many),
+            ("uk", 5.0, PluralCategory::Many),
+            ("uk", 0.0, PluralCategory::Many),
+            ("uk", 1.5, PluralCategory::Other),
+        ];
+        let ordinal_tests = [
+            ("uk", 3.0, PluralCategory::Few),
+            ("uk", 23.0, PluralCategory::Few),
+            ("uk", 13.0, PluralCategory::Other),
+            ("pl", 3.0, PluralCategory::Other),
+        ];
+
+        for (locale, value, expected_category) in cardinal_tests.into_iter() {
+            let result = pluralization(locale).get_cardinal_plural_case(value);
+            assert_eq!(
+                expected_category, result,
+                "locale: {locale}, value: {value}, type: Cardinal"
+            );
+        }
+
+        for (locale, value, expected_category) in ordinal_tests.into_iter() {
+            let result = pluralization(locale).get_ordinal_plural_case(value);
+            assert_eq!(
+                expected_category, result,
+                "locale: {locale}, value: {value}, type: Ordinal"
+            );
+        }
+    }
+
+    #[test]
+    fn resolves_locales_to_supported_ones() {
+        let english = Language::new("en");
+        for (language, fallback, expected) in [
+            ("en-US", None, Some("en")),
+            ("en-US-x-custom", None, Some("en")),
+            ("de-CH-u-ca-buddhist", None, Some("de")),
+            ("pt-PT", None, Some("pt-PT")),
+            ("pt-BR", None, Some("pt")),
+            ("sr-Latn-XK", None, Some("sr")),
+            ("ja-JP", None, Some("ja")),
+            ("tlh", Some(&english), Some("en")),
+            ("tlh", None, None),
+        ] {
+            let resolved = resolve_plural_locale(Some(&Language::new(language)), fallback);
+            assert_eq!(
+                expected.map(Language::new),
+                resolved,
+                "language: {language}"
+            );
+        }
+        assert_eq!(
+            Some(english.clone()),
+            resolve_plural_locale(None, Some(&english))
+        );
+        assert_eq!(None, resolve_plural_locale(None, None));
+    }
+
+    #[test]
+    fn supported_locales_resolve_to_themselves() {
+        let locales = supported_locales();
+        assert!(locales.contains(&Language::new("uk")));
+        assert!(locales.contains(&Language::new("zh")));
+        for locale in locales {
+            assert_eq!(
+                Some(&locale),
+                resolve_plural_locale(Some(&locale), None).as_ref()
+            );
+        }
+    }
+
+    fn pluralization(locale: &str) -> Pluralization {
+        Pluralization::try_new(Some(&Language::new(locale)), None).unwrap()
+    }
 }
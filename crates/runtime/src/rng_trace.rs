@@ -0,0 +1,357 @@
+//! Not part of the original implementation.
+
+use crate::prelude::*;
+use crate::Result;
+use rand::{rngs::SmallRng, Rng, RngCore, SeedableRng};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// The kind of place in which a [`Dialogue`] made a random decision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub enum RngDecisionKind {
+    /// Picking one of the equally salient candidates of a line group.
+    LineGroup,
+    /// Picking one of the equally salient nodes of a node group.
+    NodeGroup,
+    /// Drawing the result of the built-in `random`, `random_range` or `dice` function.
+    Function,
+}
+
+/// A random decision made by a [`Dialogue`]. See [`Dialogue::set_rng_trace_capacity`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct RngDecision {
+    /// The kind of place in which the decision was made.
+    pub kind: RngDecisionKind,
+    /// The name of the node in which the decision was made.
+    pub node_name: String,
+    /// The number of possible outcomes.
+    pub candidate_count: usize,
+    /// The drawn outcome, i.e. an index below [`RngDecision::candidate_count`].
+    pub value: usize,
+}
+
+/// The state of the random number generator of a [`Dialogue`], as returned by [`Dialogue::rng_state`].
+/// Save it along with the variables to continue a saved game with exactly the same random outcomes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct RngState {
+    seed: u64,
+    // The generator is restored by replaying its draws. Since a 64 bit draw may advance it further than a 32 bit one,
+    // both kinds are counted. Their order does not matter.
+    u32_draws: u64,
+    u64_draws: u64,
+}
+
+/// The source of all random decisions of a [`Dialogue`], which can record them or replay previously recorded ones.
+/// The generator is shared with the random functions of the [`Library`], so they draw from the same seed.
+/// While the dialogue calls a function, see [`DialogueRng::call_function`], the functions record and replay their draws along with the dialogue's decisions.
+#[derive(Debug, Default)]
+pub(crate) struct DialogueRng {
+    rng: SharedRng,
+    mode: Arc<Mutex<RngMode>>,
+}
+
+/// A seeded random number generator that keeps track of how far it advanced, so that its state can be saved.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SharedRng {
+    rng: Arc<Mutex<CountingRng>>,
+    /// The dialogue that is currently calling a library function.
+    caller: Arc<Mutex<Option<FunctionCaller>>>,
+}
+
+/// Clones share the generator, like [`DialogueRng::spawn_child`], but get a copy of the recorded or replayed decisions.
+impl Clone for DialogueRng {
+    fn clone(&self) -> Self {
+        Self {
+            rng: self.rng.clone(),
+            mode: Arc::new(Mutex::new(self.mode.lock().unwrap().clone())),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct FunctionCaller {
+    mode: Arc<Mutex<RngMode>>,
+    node_name: String,
+    /// The first replay mismatch of a draw, which the function itself can't return.
+    error: Option<DialogueError>,
+}
+
+#[derive(Debug)]
+struct CountingRng {
+    rng: SmallRng,
+    state: RngState,
+}
+
+#[derive(Debug, Clone, Default)]
+enum RngMode {
+    #[default]
+    Random,
+    Recording {
+        decisions: VecDeque<RngDecision>,
+        capacity: usize,
+    },
+    Replaying(VecDeque<RngDecision>),
+}
+
+impl Default for CountingRng {
+    fn default() -> Self {
+        Self::from_seed(default_seed())
+    }
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn default_seed() -> u64 {
+    rand::random()
+}
+
+/// `wasm32-unknown-unknown` has no source of entropy without a JavaScript host,
+/// so the seed is fixed and should be set through [`Dialogue::set_random_seed`] instead.
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn default_seed() -> u64 {
+    0
+}
+
+impl CountingRng {
+    fn from_seed(seed: u64) -> Self {
+        Self {
+            rng: SmallRng::seed_from_u64(seed),
+            state: RngState {
+                seed,
+                u32_draws: 0,
+                u64_draws: 0,
+            },
+        }
+    }
+
+    fn from_state(state: RngState) -> Self {
+        let mut rng = Self::from_seed(state.seed);
+        for _ in 0..state.u32_draws {
+            rng.next_u32();
+        }
+        for _ in 0..state.u64_draws {
+            rng.next_u64();
+        }
+        rng
+    }
+}
+
+impl RngCore for CountingRng {
+    fn next_u32(&mut self) -> u32 {
+        self.state.u32_draws += 1;
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state.u64_draws += 1;
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> std::result::Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SharedRng {
+    /// Draws an index below `candidate_count`, which must not be zero, for a library function.
+    /// The draw is recorded or replayed as an [`RngDecisionKind::Function`] decision of the dialogue that is calling the function, if any.
+    pub(crate) fn choose_index(&self, candidate_count: usize) -> usize {
+        let mut caller = self.caller.lock().unwrap();
+        match caller.as_mut() {
+            Some(caller) if caller.error.is_none() => {
+                let mode = &mut caller.mode.lock().unwrap();
+                let decision = draw(
+                    mode,
+                    &mut self.lock(),
+                    RngDecisionKind::Function,
+                    &caller.node_name,
+                    candidate_count,
+                );
+                decision.unwrap_or_else(|error| {
+                    caller.error = Some(error);
+                    0
+                })
+            }
+            _ => self.lock().gen_range(0..candidate_count),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CountingRng> {
+        self.rng.lock().unwrap()
+    }
+
+    fn set(&self, rng: CountingRng) {
+        *self.lock() = rng;
+    }
+}
+
+impl DialogueRng {
+    pub(crate) fn set_seed(&mut self, seed: u64) {
+        self.rng.set(CountingRng::from_seed(seed));
+    }
+
+    pub(crate) fn state(&self) -> RngState {
+        self.rng.lock().state.clone()
+    }
+
+    pub(crate) fn set_state(&mut self, state: RngState) {
+        self.rng.set(CountingRng::from_state(state));
+    }
+
+    /// The generator, for use by library functions.
+    pub(crate) fn shared(&self) -> SharedRng {
+        self.rng.clone()
+    }
+
+    /// A generator for a child dialogue, which draws from the same generator but records and replays its own decisions.
+    pub(crate) fn spawn_child(&self) -> Self {
+        Self {
+            rng: self.rng.clone(),
+            mode: Default::default(),
+        }
+    }
+
+    /// Calls a library function, recording or replaying the draws of the random functions as decisions made in the node `node_name`.
+    /// Fails if a replayed draw does not match, see [`SharedRng::choose_index`].
+    pub(crate) fn call_function<T>(&self, node_name: &str, call: impl FnOnce() -> T) -> Result<T> {
+        *self.rng.caller.lock().unwrap() = Some(FunctionCaller {
+            mode: self.mode.clone(),
+            node_name: node_name.to_owned(),
+            error: None,
+        });
+        let result = call();
+        let caller = self.rng.caller.lock().unwrap().take();
+        match caller.and_then(|caller| caller.error) {
+            Some(error) => Err(error),
+            None => Ok(result),
+        }
+    }
+
+    /// Starts recording decisions, keeping at most the last `capacity` ones, or stops recording if `capacity` is [`None`].
+    /// Stops a replay.
+    pub(crate) fn set_trace_capacity(&mut self, capacity: Option<usize>) {
+        let mode = &mut *self.mode.lock().unwrap();
+        *mode = match capacity {
+            Some(capacity) => {
+                let mut decisions = match std::mem::take(mode) {
+                    RngMode::Recording { decisions, .. } => decisions,
+                    _ => VecDeque::new(),
+                };
+                while decisions.len() > capacity {
+                    decisions.pop_front();
+                }
+                RngMode::Recording {
+                    decisions,
+                    capacity,
+                }
+            }
+            None => RngMode::Random,
+        };
+    }
+
+    /// Returns the recorded decisions, oldest first, and clears them while continuing to record.
+    pub(crate) fn take_trace(&mut self) -> Vec<RngDecision> {
+        match &mut *self.mode.lock().unwrap() {
+            RngMode::Recording { decisions, .. } => decisions.drain(..).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Makes the next decisions use the given ones instead of drawing random values.
+    /// Once they are used up, values are drawn randomly again.
+    pub(crate) fn set_replay(&mut self, decisions: impl IntoIterator<Item = RngDecision>) {
+        *self.mode.lock().unwrap() = RngMode::Replaying(decisions.into_iter().collect());
+    }
+
+    /// Draws an index below `candidate_count`, which must not be zero.
+    pub(crate) fn choose_index(
+        &mut self,
+        kind: RngDecisionKind,
+        node_name: &str,
+        candidate_count: usize,
+    ) -> Result<usize> {
+        draw(
+            &mut self.mode.lock().unwrap(),
+            &mut self.rng.lock(),
+            kind,
+            node_name,
+            candidate_count,
+        )
+    }
+}
+
+fn draw(
+    mode: &mut RngMode,
+    rng: &mut CountingRng,
+    kind: RngDecisionKind,
+    node_name: &str,
+    candidate_count: usize,
+) -> Result<usize> {
+    match mode {
+        RngMode::Random => Ok(rng.gen_range(0..candidate_count)),
+        RngMode::Recording {
+            decisions,
+            capacity,
+        } => {
+            let value = rng.gen_range(0..candidate_count);
+            if *capacity > 0 {
+                if decisions.len() == *capacity {
+                    decisions.pop_front();
+                }
+                decisions.push_back(RngDecision {
+                    kind,
+                    node_name: node_name.to_owned(),
+                    candidate_count,
+                    value,
+                });
+            }
+            Ok(value)
+        }
+        RngMode::Replaying(decisions) => {
+            let Some(expected) = decisions.pop_front() else {
+                *mode = RngMode::Random;
+                return Ok(rng.gen_range(0..candidate_count));
+            };
+            if expected.kind != kind
+                || expected.node_name != node_name
+                || expected.candidate_count != candidate_count
+            {
+                return Err(DialogueError::RngReplayMismatch {
+                    expected,
+                    kind,
+                    node_name: node_name.to_owned(),
+                    candidate_count,
+                });
+            }
+            Ok(expected.value)
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Not part of the original implementation.
+
+#[cfg(any(feature = "bevy", feature = "serde"))]
+use crate::prelude::*;
+use std::cmp::Reverse;
+use std::fmt::Debug;
+
+/// Decides which content of a line group or node group is run when several candidates' conditions pass.
+/// Set with [`Dialogue::set_saliency_strategy`](crate::prelude::Dialogue::set_saliency_strategy). Defaults to [`BestSaliencyStrategy`].
+///
+/// A strategy only ranks the candidates. If it considers several of them equally salient,
+/// the [`Dialogue`](crate::prelude::Dialogue) picks one of them with its random number generator, so that the pick can be seeded and replayed.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_runtime::prelude::*;
+/// # let mut dialogue = Dialogue::new(Box::new(MemoryVariableStorage::new()), Box::new(StringTableTextProvider::new()));
+/// dialogue.set_saliency_strategy(Box::new(FirstMatchingSaliencyStrategy));
+/// ```
+pub trait SaliencyStrategy: Debug + Send + Sync {
+    /// Creates a shallow clone of this strategy, i.e. a clone that
+    /// shares the same underlying strategy and will thus be perfectly in sync
+    /// with the original instance.
+    fn clone_shallow(&self) -> Box<dyn SaliencyStrategy>;
+
+    /// Returns the indices of the most salient of the given candidates, all of whose conditions passed.
+    /// The candidates are in the order they appear in the Yarn source and there is always at least one.
+    /// Returning no index skips the group.
+    fn most_salient(&self, candidates: &[SaliencyCandidate]) -> Vec<usize>;
+}
+
+impl Clone for Box<dyn SaliencyStrategy> {
+    fn clone(&self) -> Self {
+        self.clone_shallow()
+    }
+}
+
+/// A line of a line group or a node of a node group whose conditions passed. Passed to a [`SaliencyStrategy`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct SaliencyCandidate {
+    /// The ID of the line of a line group or the name of the node of a node group.
+    pub content_id: String,
+    /// How specific the conditions of the candidate are: one per condition plus one per `and`, `or` and `xor` in it.
+    /// A candidate without conditions, e.g. a node with `when: always`, has a complexity of 0.
+    pub complexity: usize,
+    /// How often this candidate was picked before.
+    pub view_count: usize,
+    /// How many picks of any line group or node group ago this candidate was picked last,
+    /// e.g. `Some(0)` if it was the content picked most recently, or [`None`] if it was never picked.
+    pub picks_since_viewed: Option<usize>,
+    /// The label or node that running this candidate jumps to.
+    pub(crate) destination: String,
+}
+
+/// Picks the first candidate whose conditions passed, regardless of how specific the others are.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct FirstMatchingSaliencyStrategy;
+
+impl SaliencyStrategy for FirstMatchingSaliencyStrategy {
+    fn clone_shallow(&self) -> Box<dyn SaliencyStrategy> {
+        Box::new(*self)
+    }
+
+    fn most_salient(&self, candidates: &[SaliencyCandidate]) -> Vec<usize> {
+        candidates.first().map(|_| 0).into_iter().collect()
+    }
+}
+
+/// Picks the candidate with the highest complexity, i.e. the most satisfied conditions. Ties are broken randomly.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct BestSaliencyStrategy;
+
+impl SaliencyStrategy for BestSaliencyStrategy {
+    fn clone_shallow(&self) -> Box<dyn SaliencyStrategy> {
+        Box::new(*self)
+    }
+
+    fn most_salient(&self, candidates: &[SaliencyCandidate]) -> Vec<usize> {
+        indices_of_max_by_key(candidates, 0..candidates.len(), |candidate| {
+            candidate.complexity
+        })
+    }
+}
+
+/// Like [`BestSaliencyStrategy`], but breaks ties by picking the candidate that was picked the longest time ago,
+/// so that content with equally specific conditions is cycled through before any of it repeats
+/// and the same content is never shown twice in a row while equally salient content is available.
+/// Remaining ties, i.e. candidates that were never picked, are broken by picking the least viewed one and then randomly.
+///
+/// The view counts are stored in the [`VariableStorage`](crate::prelude::VariableStorage), so they persist along with the other variables.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct RandomBestLeastRecentlyViewedSaliencyStrategy;
+
+impl SaliencyStrategy for RandomBestLeastRecentlyViewedSaliencyStrategy {
+    fn clone_shallow(&self) -> Box<dyn SaliencyStrategy> {
+        Box::new(*self)
+    }
+
+    fn most_salient(&self, candidates: &[SaliencyCandidate]) -> Vec<usize> {
+        let best = BestSaliencyStrategy.most_salient(candidates);
+        let least_recent = indices_of_max_by_key(candidates, best, |candidate| {
+            candidate.picks_since_viewed.unwrap_or(usize::MAX)
+        });
+        indices_of_max_by_key(candidates, least_recent, |candidate| {
+            Reverse(candidate.view_count)
+        })
+    }
+}
+
+/// Returns those of the `indices` into `candidates` whose candidates have the maximum key.
+fn indices_of_max_by_key<K: Ord>(
+    candidates: &[SaliencyCandidate],
+    indices: impl IntoIterator<Item = usize>,
+    key: impl Fn(&SaliencyCandidate) -> K,
+) -> Vec<usize> {
+    let indices: Vec<_> = indices.into_iter().collect();
+    let max = indices.iter().map(|&index| key(&candidates[index])).max();
+    indices
+        .into_iter()
+        .filter(|&index| Some(key(&candidates[index])) == max)
+        .collect()
+}
@@ -21,6 +21,18 @@ pub trait TextProvider: Debug + Send + Sync {
     fn accept_line_hints(&mut self, line_ids: &[LineId]);
     /// Returns the text for the given [`LineId`]. Will only be called if [`TextProvider::are_lines_available`] returns `true`.
     fn get_text(&self, id: &LineId) -> Option<String>;
+    /// Returns the text for the given [`LineId`] when it is delivered with the given substitutions, i.e. the values of its expressions.
+    /// The returned text must still contain the placeholders, e.g. `{0}`, which are then replaced by the [`Dialogue`](crate::prelude::Dialogue).
+    /// Since the placeholders are numbered, a translation is free to reorder them.
+    ///
+    /// Override this if the text depends on the values, e.g. to choose between phrasings. By default, this calls [`TextProvider::get_text`].
+    fn get_text_with_substitutions(
+        &self,
+        id: &LineId,
+        _substitutions: &[YarnValue],
+    ) -> Option<String> {
+        self.get_text(id)
+    }
     /// Sets the current language. If `None` is passed, the base language will be used.
     fn set_language(&mut self, language: Option<Language>);
     /// Returns the current language. If `None` is returned, the base language is used.
@@ -35,6 +47,74 @@ pub trait TextProvider: Debug + Send + Sync {
     fn as_any_mut(&mut self) -> &mut dyn Any;
 }
 
+/// The minimal interface for providing text, intended as the stable extension point for crates that ship their own localization strategy,
+/// e.g. based on Fluent files or a CMS.
+///
+/// Unlike [`TextProvider`], this trait does not require shallow cloning or downcasting, so it can be implemented without knowing
+/// anything about the engine integration that ends up using it. Integrations wrap it in their own [`TextProvider`],
+/// e.g. `bevy_yarnspinner` does so in `DialogueRunnerBuilder::with_text_source`.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_runtime::prelude::*;
+/// # use yarnspinner_core::prelude::*;
+/// # use std::collections::HashMap;
+/// #[derive(Debug, Default)]
+/// struct ShoutingTextSource {
+///     base_language: StringTable,
+///     language: Option<Language>,
+/// }
+///
+/// impl TextSource for ShoutingTextSource {
+///     fn get_text(&self, id: &LineId) -> Option<String> {
+///         self.base_language.get(id).map(|text| text.to_uppercase())
+///     }
+///
+///     fn set_language(&mut self, language: Option<Language>) {
+///         self.language = language;
+///     }
+///
+///     fn get_language(&self) -> Option<Language> {
+///         self.language.clone()
+///     }
+///
+///     fn set_base_language(&mut self, string_table: StringTable) {
+///         self.base_language = string_table;
+///     }
+/// }
+///
+/// let mut source = ShoutingTextSource::default();
+/// source.set_base_language(HashMap::from([("line:1".into(), "Hello".to_owned())]));
+/// assert_eq!(Some("HELLO".to_owned()), source.get_text(&"line:1".into()));
+/// ```
+pub trait TextSource: Debug + Send + Sync + 'static {
+    /// Returns the text for the given [`LineId`] in the current language. Will only be called if [`TextSource::are_lines_available`] returns `true`.
+    fn get_text(&self, id: &LineId) -> Option<String>;
+    /// Same as [`TextProvider::get_text_with_substitutions`]. By default, this calls [`TextSource::get_text`].
+    fn get_text_with_substitutions(
+        &self,
+        id: &LineId,
+        _substitutions: &[YarnValue],
+    ) -> Option<String> {
+        self.get_text(id)
+    }
+    /// Sets the current language. If `None` is passed, the base language will be used.
+    fn set_language(&mut self, language: Option<Language>);
+    /// Returns the current language. If `None` is returned, the base language is used.
+    fn get_language(&self) -> Option<Language>;
+    /// Receives the strings of the base language, i.e. the text found in the Yarn files themselves. Called again with the full table whenever it changes.
+    /// By default, this does nothing.
+    fn set_base_language(&mut self, _string_table: StringTable) {}
+    /// Passes the [`LineId`]s that should soon be provided, which allows fetching them in the background. These are the [`LineId`]s that are contained in the current node and are not required to be actually reached.
+    /// By default, this does nothing.
+    fn accept_line_hints(&mut self, _line_ids: &[LineId]) {}
+    /// Returns whether the text for all lines announced by [`TextSource::accept_line_hints`] is available. By default, this returns `true`.
+    fn are_lines_available(&self) -> bool {
+        true
+    }
+}
+
 impl Clone for Box<dyn TextProvider> {
     fn clone(&self) -> Self {
         self.clone_shallow()
@@ -45,13 +125,33 @@ impl Clone for Box<dyn TextProvider> {
 pub type StringTable = HashMap<LineId, String>;
 
 /// A basic implementation of [`TextProvider`] which keeps the text for the base language,
-/// i.e. the language the Yarn files are written in, and the text for the currently selected translation in memory.
+/// i.e. the language the Yarn files are written in, and the text of its translations in memory.
+///
+/// Lines missing from the translation of the currently selected language are looked up in the
+/// [fallback languages](StringTableTextProvider::set_fallback_languages) in order, and finally in the base language.
+/// A translation that is empty or consists only of whitespace counts as missing.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_runtime::prelude::*;
+/// # use std::collections::HashMap;
+/// let mut text_provider = StringTableTextProvider::new();
+/// text_provider.extend_base_language(HashMap::from([("line:1".into(), "Color".to_owned())]));
+/// text_provider.extend_translation("en-GB", HashMap::from([("line:1".into(), "Colour".to_owned())]));
+/// text_provider.extend_translation("en-AU", HashMap::new());
+///
+/// text_provider.set_language(Some("en-AU".into()));
+/// text_provider.set_fallback_languages(["en-GB"]);
+/// assert_eq!(Some("Colour".to_owned()), text_provider.get_text(&"line:1".into()));
+/// ```
 #[derive(Debug, Clone, Default)]
 pub struct StringTableTextProvider {
     base_language_table: StringTable,
-    translation_table: Option<(Language, StringTable)>,
+    translation_tables: HashMap<Language, StringTable>,
     /// Set to `None` to select base language.
     translation_language: Option<Language>,
+    fallback_languages: Vec<Language>,
 }
 
 impl StringTableTextProvider {
@@ -65,20 +165,32 @@ impl StringTableTextProvider {
         self.base_language_table.extend(string_table);
     }
 
-    /// Adds strings for the a specific language. If this is not the language used selected by [`TextProvider::set_language`], the strings will be ignored.
+    /// Adds strings for a specific language. They are used if the language is selected by [`TextProvider::set_language`]
+    /// or is one of the [fallback languages](StringTableTextProvider::set_fallback_languages).
     pub fn extend_translation(
         &mut self,
         language: impl Into<Language>,
         string_table: HashMap<LineId, String>,
     ) {
-        let language = language.into();
-        if let Some((current_language, translation_table)) = self.translation_table.as_mut() {
-            if language == *current_language {
-                translation_table.extend(string_table);
-                return;
-            }
-        }
-        self.translation_table.replace((language, string_table));
+        self.translation_tables
+            .entry(language.into())
+            .or_default()
+            .extend(string_table);
+    }
+
+    /// Sets the languages whose translations are used, in order, for lines that the selected language has no translation for,
+    /// e.g. `["fr", "en"]` for a dialogue in `fr-CA`. Lines that none of them translate are taken from the base language.
+    /// Only applies while a translation is selected with [`TextProvider::set_language`].
+    pub fn set_fallback_languages(
+        &mut self,
+        languages: impl IntoIterator<Item = impl Into<Language>>,
+    ) {
+        self.fallback_languages = languages.into_iter().map(Into::into).collect();
+    }
+
+    /// Returns the languages set by [`StringTableTextProvider::set_fallback_languages`].
+    pub fn fallback_languages(&self) -> &[Language] {
+        &self.fallback_languages
     }
 }
 
@@ -93,16 +205,14 @@ impl TextProvider for StringTableTextProvider {
 
     fn get_text(&self, id: &LineId) -> Option<String> {
         if let Some(language) = self.translation_language.as_ref() {
-            if let Some((registered_language, translation_table)) = self.translation_table.as_ref()
-            {
-                if registered_language != language {
-                    error!("Didn't find language {language} in translations, falling back to base language.");
-                } else if let Some(line) = translation_table.get(id) {
-                    return Some(line.clone());
-                } else {
-                    error!("No translation found for line {id} in language {language}, falling back to base language.");
-                }
+            let translation = std::iter::once(language)
+                .chain(&self.fallback_languages)
+                .filter_map(|language| self.translation_tables.get(language)?.get(id))
+                .find(|line| !line.trim().is_empty());
+            if let Some(line) = translation {
+                return Some(line.clone());
             }
+            error!("No translation found for line {id} in language {language} or its fallback languages, falling back to base language.");
         }
         self.base_language_table.get(id).cloned()
     }
@@ -119,11 +229,7 @@ impl TextProvider for StringTableTextProvider {
         let Some(language) = self.translation_language.as_ref() else {
             return !self.base_language_table.is_empty();
         };
-        let translation_language = self
-            .translation_table
-            .as_ref()
-            .map(|(language, _)| language);
-        translation_language == Some(language)
+        self.translation_tables.contains_key(language)
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -134,3 +240,58 @@ impl TextProvider for StringTableTextProvider {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_lines_through_fallback_languages() {
+        let mut text_provider = StringTableTextProvider::new();
+        text_provider.extend_base_language(strings([
+            ("line:greeting", "Hello"),
+            ("line:farewell", "Goodbye"),
+            ("line:thanks", "Thank you"),
+        ]));
+        text_provider.extend_translation(
+            "fr-CA",
+            strings([("line:greeting", "Allô"), ("line:farewell", "  ")]),
+        );
+        text_provider.extend_translation(
+            "fr",
+            strings([("line:greeting", "Bonjour"), ("line:farewell", "Au revoir")]),
+        );
+        text_provider.extend_translation("en", strings([("line:greeting", "Hi")]));
+        text_provider.set_language(Some("fr-CA".into()));
+        text_provider.set_fallback_languages(["fr", "en"]);
+
+        assert!(text_provider.are_lines_available());
+        assert_eq!(
+            Some("Allô".to_owned()),
+            text_provider.get_text(&"line:greeting".into())
+        );
+        // The whitespace-only translation counts as missing
+        assert_eq!(
+            Some("Au revoir".to_owned()),
+            text_provider.get_text(&"line:farewell".into())
+        );
+        assert_eq!(
+            Some("Thank you".to_owned()),
+            text_provider.get_text(&"line:thanks".into())
+        );
+        assert_eq!(None, text_provider.get_text(&"line:missing".into()));
+
+        text_provider.set_language(None);
+        assert_eq!(
+            Some("Hello".to_owned()),
+            text_provider.get_text(&"line:greeting".into())
+        );
+    }
+
+    fn strings<const N: usize>(lines: [(&str, &str); N]) -> StringTable {
+        lines
+            .into_iter()
+            .map(|(id, text)| (id.into(), text.to_owned()))
+            .collect()
+    }
+}
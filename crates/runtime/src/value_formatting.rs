@@ -0,0 +1,115 @@
+//! Not part of the original implementation, which leaves formatting interpolated values to .NET.
+
+use crate::prelude::*;
+use fixed_decimal::FixedDecimal;
+use icu_decimal::FixedDecimalFormatter;
+
+/// Replaces the placeholders `{0}`, `{1}`, etc. in `text` with the given substitutions.
+/// Numbers are formatted for `language`, e.g. 1234.5 becomes "1,234.5" in "en-US" and "1.234,5" in "de-DE".
+///
+/// Substitutions inside of markup, e.g. `[plural value={0} one="apple" other="apples"/]`, are left unformatted
+/// so that markers can still parse them. The same goes for every substitution when `language` is [`None`].
+pub(crate) fn expand_localized_substitutions(
+    text: &str,
+    substitutions: &[YarnValue],
+    language: Option<&Language>,
+) -> String {
+    let formatter = language.and_then(|language| {
+        FixedDecimalFormatter::try_new(&language.0.clone().into(), Default::default()).ok()
+    });
+    let mut expanded = String::with_capacity(text.len());
+    let mut is_in_markup = false;
+    let mut chars = text.char_indices();
+    while let Some((index, character)) = chars.next() {
+        match character {
+            '\\' if text[index + 1..].starts_with(['[', ']']) => {
+                // Escaped brackets don't open or close markup
+                expanded.push(character);
+                if let Some((_, escaped)) = chars.next() {
+                    expanded.push(escaped);
+                }
+                continue;
+            }
+            '[' => is_in_markup = true,
+            ']' => is_in_markup = false,
+            '{' => {
+                if let Some((substitution, length)) = parse_placeholder(&text[index..])
+                    .and_then(|(i, length)| Some((substitutions.get(i)?, length)))
+                {
+                    let formatter = formatter.as_ref().filter(|_| !is_in_markup);
+                    expanded.push_str(&format_value(substitution, formatter));
+                    // Placeholders only consist of ASCII characters
+                    chars.nth(length - 2);
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        expanded.push(character);
+    }
+    expanded
+}
+
+/// Returns the index of the placeholder at the start of `text`, e.g. `{2}`, and the placeholder's length.
+fn parse_placeholder(text: &str) -> Option<(usize, usize)> {
+    let end = text.find('}')?;
+    let digits = &text[1..end];
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    Some((digits.parse().ok()?, end + 1))
+}
+
+fn format_value(value: &YarnValue, formatter: Option<&FixedDecimalFormatter>) -> String {
+    match (value, formatter) {
+        // `f32`'s `Display` yields the shortest representation without an exponent, which keeps e.g. 0.1 from becoming 0.100000001490116
+        (YarnValue::Number(number), Some(formatter)) => number
+            .to_string()
+            .parse::<FixedDecimal>()
+            .map(|decimal| formatter.format_to_string(&decimal))
+            // NaN and infinity
+            .unwrap_or_else(|_| number.to_string()),
+        _ => String::from(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_numbers_for_language() {
+        let substitutions = [YarnValue::from(1234567.5), YarnValue::from(-0.25)];
+        for (language, expected) in [
+            ("en-US", "1,234,567.5 and -0.25"),
+            ("de-DE", "1.234.567,5 and -0,25"),
+            ("fr-FR", "1\u{202f}234\u{202f}567,5 and -0,25"),
+        ] {
+            let language = Language::new(language);
+            let expanded =
+                expand_localized_substitutions("{0} and {1}", &substitutions, Some(&language));
+            assert_eq!(expected, expanded, "language: {language}");
+        }
+        let expanded = expand_localized_substitutions("{0} and {1}", &substitutions, None);
+        assert_eq!("1234567.5 and -0.25", expanded);
+    }
+
+    #[test]
+    fn leaves_markup_and_other_values_unformatted() {
+        let language = Language::new("de-DE");
+        let substitutions = [
+            YarnValue::from(1000.5),
+            YarnValue::from(true),
+            YarnValue::from("1000"),
+        ];
+        let expanded = expand_localized_substitutions(
+            r"{0} [plural value={0} other=%/] \[{0}\] {1} {2} {3} {x}",
+            &substitutions,
+            Some(&language),
+        );
+        assert_eq!(
+            r"1.000,5 [plural value=1000.5 other=%/] \[1.000,5\] true 1000 {3} {x}",
+            expanded
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! Not part of the original implementation.
+
+use crate::prelude::*;
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex, RwLock, Weak};
+
+/// A [`VariableStorage`] that wraps another one and lets you watch its variables for changes.
+///
+/// Instead of notifying you on every single write, the changes are accumulated per [`WatchHandle`]
+/// and can be drained whenever it suits you, e.g. once per frame in a debug overlay.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_runtime::prelude::*;
+/// # use yarnspinner_core::prelude::*;
+/// let mut watcher = VariableWatcher::new(Box::new(MemoryVariableStorage::new()));
+/// let handle = watcher.subscribe(&["$quest_*"]);
+///
+/// watcher.set("$quest_started".to_owned(), true.into()).unwrap();
+/// watcher.set("$gold".to_owned(), 10.into()).unwrap();
+///
+/// assert_eq!(
+///     vec![("$quest_started".to_owned(), YarnValue::from(true))],
+///     handle.take_changes()
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct VariableWatcher {
+    storage: Box<dyn VariableStorage>,
+    subscriptions: Arc<RwLock<Vec<Weak<Mutex<Subscription>>>>>,
+}
+
+/// A subscription to the variables of a [`VariableWatcher`] that match a set of patterns.
+/// Created by [`VariableWatcher::subscribe`]. Dropping the handle unsubscribes it.
+#[derive(Debug)]
+pub struct WatchHandle(Arc<Mutex<Subscription>>);
+
+#[derive(Debug)]
+struct Subscription {
+    patterns: Vec<String>,
+    changes: BTreeMap<String, YarnValue>,
+}
+
+impl VariableWatcher {
+    /// Wraps the given [`VariableStorage`]. Writes through the watcher are reported to its subscriptions,
+    /// so the storage should not be written to directly afterwards.
+    pub fn new(storage: Box<dyn VariableStorage>) -> Self {
+        Self {
+            storage,
+            subscriptions: Default::default(),
+        }
+    }
+
+    /// Watches all variables whose names match any of the given patterns.
+    /// Patterns are matched against the full variable name, including the `$`.
+    /// A `*` matches any number of characters and a `?` matches exactly one.
+    ///
+    /// The current values of all matching variables are reported by the first [`WatchHandle::take_changes`].
+    pub fn subscribe(&self, patterns: &[&str]) -> WatchHandle {
+        let mut subscription = Subscription {
+            patterns: patterns.iter().map(|pattern| pattern.to_string()).collect(),
+            changes: Default::default(),
+        };
+        for (name, value) in self.storage.variables() {
+            subscription.record(name, value);
+        }
+        let subscription = Arc::new(Mutex::new(subscription));
+        self.subscriptions
+            .write()
+            .unwrap()
+            .push(Arc::downgrade(&subscription));
+        WatchHandle(subscription)
+    }
+
+    /// Returns the wrapped [`VariableStorage`].
+    pub fn inner(&self) -> &dyn VariableStorage {
+        self.storage.as_ref()
+    }
+
+    fn notify(&self, changes: impl IntoIterator<Item = (String, YarnValue)>) {
+        let mut subscriptions = self.subscriptions.write().unwrap();
+        subscriptions.retain(|subscription| subscription.strong_count() > 0);
+        let subscriptions: Vec<_> = subscriptions.iter().filter_map(Weak::upgrade).collect();
+        for (name, value) in changes {
+            for subscription in &subscriptions {
+                subscription
+                    .lock()
+                    .unwrap()
+                    .record(name.clone(), value.clone());
+            }
+        }
+    }
+}
+
+impl WatchHandle {
+    /// Drains the changes since the last call, sorted by variable name.
+    /// If a variable was written to multiple times, only its latest value is reported.
+    pub fn take_changes(&self) -> Vec<(String, YarnValue)> {
+        std::mem::take(&mut self.0.lock().unwrap().changes)
+            .into_iter()
+            .collect()
+    }
+
+    /// Stops watching. Equivalent to dropping the handle.
+    pub fn unsubscribe(self) {}
+}
+
+impl Subscription {
+    fn record(&mut self, name: String, value: YarnValue) {
+        if self
+            .patterns
+            .iter()
+            .any(|pattern| matches_pattern(pattern, &name))
+        {
+            self.changes.insert(name, value);
+        }
+    }
+}
+
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<_> = pattern.chars().collect();
+    let name: Vec<_> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // The position of the last `*` in the pattern and the position in the name it was matched against
+    let mut backtrack = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` swallow one more character
+                Some((star, matched)) => {
+                    backtrack = Some((star, matched + 1));
+                    p = star + 1;
+                    n = matched + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+impl VariableStorage for VariableWatcher {
+    fn clone_shallow(&self) -> Box<dyn VariableStorage> {
+        Box::new(self.clone())
+    }
+
+    fn set(&mut self, name: String, value: YarnValue) -> crate::variable_storage::Result<()> {
+        self.storage.set(name.clone(), value.clone())?;
+        self.notify([(name, value)]);
+        Ok(())
+    }
+
+    fn get(&self, name: &str) -> crate::variable_storage::Result<YarnValue> {
+        self.storage.get(name)
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.storage.contains(name)
+    }
+
+    fn extend(
+        &mut self,
+        values: HashMap<String, YarnValue>,
+    ) -> crate::variable_storage::Result<()> {
+        VariableStorage::extend(self.storage.as_mut(), values.clone())?;
+        self.notify(values);
+        Ok(())
+    }
+
+    fn variables(&self) -> HashMap<String, YarnValue> {
+        self.storage.variables()
+    }
+
+    /// Clears the wrapped storage. Since cleared variables have no value anymore, this is not reported to subscriptions.
+    fn clear(&mut self) {
+        self.storage.clear();
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batches_bursts_of_writes_per_variable() {
+        let mut watcher = VariableWatcher::new(Box::new(MemoryVariableStorage::new()));
+        let handle = watcher.subscribe(&["$quest_*"]);
+        for i in 0..1000 {
+            watcher
+                .set(format!("$quest_{}", i % 10), (i as f32).into())
+                .unwrap();
+            watcher
+                .set("$unrelated".to_owned(), (i as f32).into())
+                .unwrap();
+        }
+
+        let changes = handle.take_changes();
+        let expected: Vec<_> = (0..10)
+            .map(|i| (format!("$quest_{i}"), YarnValue::from((990 + i) as f32)))
+            .collect();
+        assert_eq!(expected, changes);
+        assert!(handle.take_changes().is_empty());
+    }
+
+    #[test]
+    fn reports_initial_values_and_supports_overlapping_subscriptions() {
+        let mut watcher = VariableWatcher::new(Box::new(MemoryVariableStorage::new()));
+        watcher
+            .set("$rel_marcus_trust".to_owned(), 3.into())
+            .unwrap();
+        let marcus = watcher.subscribe(&["$rel_marcus_*"]);
+        let everything = watcher.subscribe(&["$*"]);
+        assert_eq!(
+            vec![("$rel_marcus_trust".to_owned(), YarnValue::from(3))],
+            marcus.take_changes()
+        );
+
+        marcus.unsubscribe();
+        watcher
+            .set("$rel_marcus_trust".to_owned(), 4.into())
+            .unwrap();
+        assert_eq!(
+            vec![("$rel_marcus_trust".to_owned(), YarnValue::from(4))],
+            everything.take_changes()
+        );
+        assert_eq!(1, watcher.subscriptions.read().unwrap().len());
+    }
+
+    #[test]
+    fn matches_glob_patterns() {
+        assert!(matches_pattern("$quest_*", "$quest_"));
+        assert!(matches_pattern("$quest_*", "$quest_started"));
+        assert!(matches_pattern("$*_done", "$quest_a_done"));
+        assert!(matches_pattern("$quest_?", "$quest_1"));
+        assert!(!matches_pattern("$quest_?", "$quest_12"));
+        assert!(!matches_pattern("$quest_*", "$side_quest_1"));
+        assert!(!matches_pattern("$a*b", "$acbc"));
+    }
+}
@@ -3,32 +3,67 @@
 //! ## Implementation Notes
 //! The `Operand` extensions and the `Operator` enum were moved into upstream crates to make them not depend on the runtime.
 
-pub(crate) use self::{execution_state::*, state::*};
+pub(crate) use self::{execution_state::*, function_dispatcher::*, state::*};
+use crate::dialogue::SourceLocation;
 use crate::markup::{LineParser, ParsedMarkup};
 use crate::prelude::*;
 use crate::Result;
 use log::*;
-use std::fmt::Debug;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{Debug, Display};
+use std::sync::Arc;
+use std::time::Duration;
 use yarnspinner_core::prelude::OpCode;
 use yarnspinner_core::prelude::*;
 
 mod execution_state;
+mod function_dispatcher;
 mod state;
 
 #[derive(Debug, Clone)]
 pub(crate) struct VirtualMachine {
-    pub(crate) library: Library,
-    pub(crate) program: Option<Program>,
+    /// Shared with the dialogues spawned from this one. Cloned on write.
+    pub(crate) library: Arc<Library>,
+    /// Shared with the dialogues spawned from this one.
+    pub(crate) program: Option<Arc<Program>>,
+    /// The [`Program::checksum`] of [`VirtualMachine::program`], cached so that snapshots are cheap to take.
+    program_checksum: Option<u64>,
     pub(crate) variable_storage: Box<dyn VariableStorage>,
     pub(crate) line_hints_enabled: bool,
+    pub(crate) log_debug_info: bool,
+    pub(crate) should_send_unavailable_options: bool,
+    pub(crate) function_dispatcher: FunctionDispatcher,
+    /// The deferred function call whose return value the VM is waiting for.
+    pending_function_call: Option<PendingFunctionCall>,
+    next_function_call_token: usize,
+    /// The command the VM is waiting for, see [`Dialogue::wait_for_command`](crate::prelude::Dialogue::wait_for_command).
+    pending_command: Option<CommandCompletion>,
+    /// The debug info of the loaded nodes, used to refer to Yarn source positions in errors and logs.
+    pub(crate) debug_infos: HashMap<String, DebugInfo>,
+    /// The hashtags of the lines, without their `line:` tags.
+    pub(crate) line_metadata: HashMap<LineId, Vec<String>>,
+    pub(crate) error_reporter: Option<Box<dyn ErrorReporter>>,
+    /// The IDs of the last delivered lines, oldest first. Capped at [`RECENT_LINE_ID_COUNT`].
+    recent_line_ids: VecDeque<LineId>,
     current_node_name: Option<String>,
+    /// The program counter of the instruction that was run last in the current node.
+    last_program_counter: usize,
     state: State,
     execution_state: ExecutionState,
     current_node: Option<Node>,
+    /// The nodes that detoured into the current one, together with the state to resume them in.
+    call_stack: Vec<(Node, State)>,
+    /// The line group or node group candidates whose conditions passed.
+    saliency_candidates: Vec<SaliencyCandidate>,
+    pub(crate) saliency_strategy: Box<dyn SaliencyStrategy>,
+    /// Breaks ties between line group and node group candidates.
+    pub(crate) rng: DialogueRng,
+    option_generation: usize,
     batched_events: Vec<DialogueEvent>,
     line_parser: LineParser,
     text_provider: Box<dyn TextProvider>,
     language_code: Option<Language>,
+    plural_fallback_language_code: Option<Language>,
 }
 
 impl Iterator for VirtualMachine {
@@ -49,21 +84,70 @@ impl VirtualMachine {
         text_provider: Box<dyn TextProvider>,
     ) -> Self {
         Self {
-            library,
+            library: Arc::new(library),
             variable_storage,
             line_parser,
             text_provider,
             language_code: Default::default(),
+            plural_fallback_language_code: Default::default(),
             program: Default::default(),
+            program_checksum: Default::default(),
             current_node_name: Default::default(),
+            last_program_counter: Default::default(),
             state: Default::default(),
             execution_state: Default::default(),
             current_node: Default::default(),
+            call_stack: Default::default(),
+            saliency_candidates: Default::default(),
+            saliency_strategy: Box::new(BestSaliencyStrategy),
+            rng: Default::default(),
+            option_generation: Default::default(),
             batched_events: Default::default(),
             line_hints_enabled: Default::default(),
+            log_debug_info: Default::default(),
+            should_send_unavailable_options: true,
+            function_dispatcher: Default::default(),
+            pending_function_call: Default::default(),
+            next_function_call_token: Default::default(),
+            pending_command: Default::default(),
+            debug_infos: Default::default(),
+            line_metadata: Default::default(),
+            error_reporter: Default::default(),
+            recent_line_ids: Default::default(),
         }
     }
 
+    /// Creates a virtual machine that shares the program and library with this one, but has its own state.
+    /// The settings are copied, while the error reporter is not.
+    pub(crate) fn spawn_child(
+        &self,
+        library: Arc<Library>,
+        variable_storage: Box<dyn VariableStorage>,
+    ) -> Self {
+        let mut child = Self::new(
+            Library::default(),
+            variable_storage,
+            self.line_parser.clone(),
+            self.text_provider.clone_shallow(),
+        );
+        child.library = library;
+        child.program.clone_from(&self.program);
+        child.program_checksum = self.program_checksum;
+        child.language_code.clone_from(&self.language_code);
+        child
+            .plural_fallback_language_code
+            .clone_from(&self.plural_fallback_language_code);
+        child.line_hints_enabled = self.line_hints_enabled;
+        child.log_debug_info = self.log_debug_info;
+        child.should_send_unavailable_options = self.should_send_unavailable_options;
+        child.function_dispatcher = self.function_dispatcher;
+        child.debug_infos.clone_from(&self.debug_infos);
+        child.line_metadata.clone_from(&self.line_metadata);
+        child.saliency_strategy = self.saliency_strategy.clone();
+        child.rng = self.rng.spawn_child();
+        child
+    }
+
     pub(crate) fn text_provider(&self) -> &dyn TextProvider {
         self.text_provider.as_ref()
     }
@@ -87,15 +171,63 @@ impl VirtualMachine {
         self.text_provider.set_language(language_code);
     }
 
+    pub(crate) fn plural_fallback_language_code(&self) -> Option<&Language> {
+        self.plural_fallback_language_code.as_ref()
+    }
+
+    pub(crate) fn set_plural_fallback_language_code(
+        &mut self,
+        language_code: impl Into<Option<Language>>,
+    ) -> Option<Language> {
+        let language_code = language_code.into();
+        self.line_parser
+            .set_plural_fallback_language_code(language_code.clone());
+        std::mem::replace(&mut self.plural_fallback_language_code, language_code)
+    }
+
     pub(crate) fn reset_state(&mut self) {
         self.state = State::default();
+        self.saliency_candidates.clear();
+        self.pending_function_call = None;
+        self.pending_command = None;
         self.current_node_name = None;
+        // Any options presented until now can no longer be selected
+        self.option_generation += 1;
+    }
+
+    /// Swaps the program while trying to stay at the current position.
+    /// Returns `false` and resets the state if the current node is gone or changed its instruction count,
+    /// if options were presented, as they were built by the old program,
+    /// or if the current node was detoured into, as the nodes to return to may have changed.
+    pub(crate) fn reload_program(&mut self, program: Program) -> bool {
+        let reloaded_node = self
+            .current_node_name
+            .as_ref()
+            .filter(|_| self.execution_state != ExecutionState::WaitingOnOptionSelection)
+            .filter(|_| self.call_stack.is_empty())
+            .and_then(|node_name| program.nodes.get(node_name))
+            .filter(|new_node| {
+                self.current_node.as_ref().is_some_and(|old_node| {
+                    old_node.instructions.len() == new_node.instructions.len()
+                })
+            })
+            .cloned();
+        self.set_program(program);
+        if reloaded_node.is_some() {
+            self.current_node = reloaded_node;
+            true
+        } else {
+            self.reset_state();
+            self.call_stack.clear();
+            false
+        }
     }
 
     pub(crate) fn set_execution_state(&mut self, execution_state: ExecutionState) -> &mut Self {
         self.execution_state = execution_state;
         if execution_state == ExecutionState::Stopped {
-            self.reset_state()
+            self.reset_state();
+            self.call_stack.clear();
         }
         self
     }
@@ -104,11 +236,29 @@ impl VirtualMachine {
     /// The original does not reset the state upon calling this. I suspect that's a bug.
     pub(crate) fn stop(&mut self) -> Vec<DialogueEvent> {
         self.set_execution_state(ExecutionState::Stopped);
+        self.pending_command = None;
         self.batched_events.push(DialogueEvent::DialogueComplete);
         std::mem::take(&mut self.batched_events)
     }
 
+    /// Starts running the given node. Any nodes that detoured into the current one will not be returned to.
+    /// Leaves the current state untouched if the node does not exist.
     pub(crate) fn set_node(&mut self, node_name: impl Into<String>) -> Result<()> {
+        let node_name = node_name.into();
+        self.get_node_from_name(&node_name)?;
+        self.call_stack.clear();
+        self.load_node(node_name)?;
+        // The pending options or function call were discarded along with the old state
+        if matches!(
+            self.execution_state,
+            ExecutionState::WaitingOnOptionSelection | ExecutionState::WaitingOnDeferredFunction
+        ) {
+            self.execution_state = ExecutionState::WaitingForContinue;
+        }
+        Ok(())
+    }
+
+    fn load_node(&mut self, node_name: impl Into<String>) -> Result<()> {
         let node_name = node_name.into();
         debug!("Loading node \"{node_name}\"");
         let current_node = self.get_node_from_name(&node_name)?;
@@ -171,16 +321,25 @@ impl VirtualMachine {
         }
     }
 
+    /// Resolves the name of a node to jump or detour to, which may be relative to the namespace of the current node.
+    /// The compiler already resolves fixed destinations, so this is only needed for destinations computed at runtime.
+    /// See [`Node::candidate_names`].
+    fn resolve_node_name(&self, node_name: String) -> String {
+        let (Some(program), Some(current_node_name)) =
+            (self.program.as_ref(), self.current_node_name.as_deref())
+        else {
+            return node_name;
+        };
+        program
+            .resolve_node_name(&node_name, current_node_name)
+            .unwrap_or(node_name)
+    }
+
     fn get_node_from_name(&self, node_name: &str) -> Result<&Node> {
         let program = self
             .program
             .as_ref()
             .ok_or_else(|| DialogueError::NoProgramLoaded)?;
-        assert!(
-            !program.nodes.is_empty(),
-            "Cannot load node \"{node_name}\": No nodes have been loaded.",
-        );
-
         program
             .nodes
             .get(node_name)
@@ -195,7 +354,19 @@ impl VirtualMachine {
     /// Exposed via the more idiomatic [`Iterator::next`] implementation.
     ///
     pub(crate) fn continue_(&mut self) -> crate::Result<Vec<DialogueEvent>> {
+        self.run_until_blocked()
+            .map_err(|error| self.report_error(error))
+    }
+
+    fn run_until_blocked(&mut self) -> crate::Result<Vec<DialogueEvent>> {
         self.assert_can_continue()?;
+        if self
+            .pending_command
+            .take()
+            .is_some_and(|completion| !completion.is_completed())
+        {
+            return Err(DialogueError::CommandAbandoned);
+        }
         self.set_execution_state(ExecutionState::Running);
 
         while self.execution_state == ExecutionState::Running {
@@ -206,15 +377,14 @@ impl VirtualMachine {
             // The original increments the program counter here, but that leads to intentional underflow on [`OpCode::RunNode`],
             // so we do the incrementation in [`VirtualMachine::run_instruction`] instead.
 
-            if self.state.program_counter < current_node.instructions.len() {
+            if self.execution_state != ExecutionState::Running
+                || self.state.program_counter
+                    < self.current_node.as_ref().unwrap().instructions.len()
+            {
                 continue;
             }
 
-            self.batched_events
-                .push(DialogueEvent::NodeComplete(current_node.name.clone()));
-            self.set_execution_state(ExecutionState::Stopped);
-            self.batched_events.push(DialogueEvent::DialogueComplete);
-            debug!("Run complete.");
+            self.return_from_node();
         }
         Ok(std::mem::take(&mut self.batched_events))
     }
@@ -229,6 +399,10 @@ impl VirtualMachine {
             Err(DialogueError::NoNodeSelectedOnContinue)
         } else if self.execution_state == ExecutionState::WaitingOnOptionSelection {
             Err(DialogueError::ContinueOnOptionSelectionError)
+        } else if self.execution_state == ExecutionState::WaitingOnDeferredFunction {
+            Err(DialogueError::ContinueOnDeferredFunctionError)
+        } else if self.is_waiting_for_command() {
+            Err(DialogueError::ContinueOnPendingCommandError)
         } else {
             // ## Implementation note:
             // The other checks the original did are not needed because our relevant handlers cannot be `None` per our API.
@@ -236,26 +410,56 @@ impl VirtualMachine {
         }
     }
 
+    pub(crate) fn set_program(&mut self, program: Program) {
+        self.program_checksum = Some(program.checksum());
+        self.program = Some(Arc::new(program));
+    }
+
     pub(crate) fn unload_programs(&mut self) {
-        self.program = None
+        self.program = None;
+        self.program_checksum = None;
+        self.debug_infos.clear();
+        self.line_metadata.clear();
     }
 
-    pub(crate) fn set_selected_option(&mut self, selected_option_id: OptionId) -> Result<()> {
+    pub(crate) fn set_selected_option(&mut self, selection: OptionSelection) -> Result<()> {
         if self.execution_state != ExecutionState::WaitingOnOptionSelection {
             return Err(DialogueError::UnexpectedOptionSelectionError);
         }
-        if selected_option_id.0 >= self.state.current_options.len() {
+        if let Some(presented_generation) = selection.generation {
+            if presented_generation != self.option_generation {
+                return Err(DialogueError::StaleOptionSelection {
+                    presented_generation,
+                    current_generation: self.option_generation,
+                });
+            }
+        }
+        let selected_option_id = selection.id;
+        // Unavailable options may have been left out, so the IDs are not necessarily contiguous.
+        let Some(selected_option) = self
+            .state
+            .current_options
+            .iter()
+            .find(|option| option.id == selected_option_id)
+        else {
             return Err(DialogueError::InvalidOptionIdError {
                 selected_option_id,
-                max_id: self.state.current_options.len().saturating_sub(1),
+                max_id: self
+                    .state
+                    .current_options
+                    .iter()
+                    .map(|option| option.id.0)
+                    .max()
+                    .unwrap_or_default(),
             });
+        };
+        if !selected_option.is_available {
+            return Err(DialogueError::UnavailableOptionSelected { selected_option_id });
         }
 
         // We now know what number option was selected; push the
         // corresponding node name to the stack.
-        let destination_node = self.state.current_options[selected_option_id.0]
-            .destination_node
-            .clone();
+        let destination_node = selected_option.destination_node.clone();
         self.state.push(destination_node);
 
         // We no longer need the accumulated list of options; clear it
@@ -275,15 +479,195 @@ impl VirtualMachine {
         self.execution_state == ExecutionState::WaitingOnOptionSelection
     }
 
+    pub(crate) fn is_waiting_for_deferred_function(&self) -> bool {
+        self.execution_state == ExecutionState::WaitingOnDeferredFunction
+    }
+
+    pub(crate) fn wait_for_command(&mut self, result: CommandResult) {
+        self.pending_command = match result {
+            CommandResult::Completed => None,
+            CommandResult::InProgress(completion) => Some(completion),
+        };
+    }
+
+    /// Abandoned commands are not waited for, so that [`VirtualMachine::continue_`] can report them.
+    pub(crate) fn is_waiting_for_command(&self) -> bool {
+        self.pending_command
+            .as_ref()
+            .is_some_and(|completion| !completion.is_completed() && !completion.is_abandoned())
+    }
+
+    pub(crate) fn option_generation(&self) -> usize {
+        self.option_generation
+    }
+
+    pub(crate) fn snapshot(&self) -> DialogueSnapshot {
+        DialogueSnapshot {
+            program_checksum: self.program_checksum,
+            current_node_name: self.current_node_name.clone(),
+            last_program_counter: self.last_program_counter,
+            state: self.state.clone(),
+            execution_state: self.execution_state,
+            call_stack: self
+                .call_stack
+                .iter()
+                .map(|(node, state)| (node.name.clone(), state.clone()))
+                .collect(),
+            saliency_candidates: self.saliency_candidates.clone(),
+            pending_function_call: self.pending_function_call.clone(),
+            option_generation: self.option_generation,
+            variables: self.variable_storage.variables(),
+            rng_state: self.rng.state(),
+        }
+    }
+
+    /// Leaves the current state untouched if the snapshot was taken while running a different program.
+    pub(crate) fn restore(&mut self, snapshot: DialogueSnapshot) -> Result<()> {
+        if snapshot.program_checksum != self.program_checksum {
+            return Err(DialogueError::SnapshotProgramMismatch {
+                snapshot_checksum: snapshot.program_checksum,
+                program_checksum: self.program_checksum,
+            });
+        }
+        let current_node = snapshot
+            .current_node_name
+            .as_deref()
+            .map(|node_name| self.get_node_from_name(node_name).cloned())
+            .transpose()?;
+        let call_stack = snapshot
+            .call_stack
+            .into_iter()
+            .map(|(node_name, state)| Ok((self.get_node_from_name(&node_name)?.clone(), state)))
+            .collect::<Result<Vec<_>>>()?;
+
+        self.variable_storage.clear();
+        VariableStorage::extend(self.variable_storage.as_mut(), snapshot.variables)?;
+        self.current_node_name = snapshot.current_node_name;
+        self.current_node = current_node;
+        self.last_program_counter = snapshot.last_program_counter;
+        self.state = snapshot.state;
+        self.execution_state = snapshot.execution_state;
+        self.call_stack = call_stack;
+        self.saliency_candidates = snapshot.saliency_candidates;
+        self.pending_function_call = snapshot.pending_function_call;
+        self.pending_command = None;
+        self.option_generation = snapshot.option_generation;
+        self.rng.set_state(snapshot.rng_state);
+        self.batched_events.clear();
+        Ok(())
+    }
+
     pub(crate) fn current_node(&self) -> Option<String> {
         self.current_node_name.clone()
     }
 
+    /// Computes the value of the smart variable with the given name, e.g. `$is_rich` for `<<declare $is_rich = $gold > 100>>`.
+    /// Returns [`None`] if the loaded program has no smart variable by that name.
+    pub(crate) fn smart_variable_value(&self, variable_name: &str) -> Option<Result<YarnValue>> {
+        let node = self.program.as_ref()?.smart_variable(variable_name)?;
+        Some(self.evaluate_smart_variable(node, &mut Vec::new()))
+    }
+
+    /// Runs the expression of a smart variable on a stack of its own, see [`Node::SMART_VARIABLE_TAG`].
+    /// The variables it reads are not stored, so reading a smart variable never changes the variable storage.
+    /// `evaluating` holds the smart variables whose evaluation is in progress, so that a smart variable that is computed from itself
+    /// results in an error instead of recursing forever.
+    fn evaluate_smart_variable(
+        &self,
+        node: &Node,
+        evaluating: &mut Vec<String>,
+    ) -> Result<YarnValue> {
+        if evaluating.contains(&node.name) {
+            evaluating.push(node.name.clone());
+            return Err(DialogueError::SmartVariableCycle {
+                variable_names: std::mem::take(evaluating),
+            });
+        }
+        evaluating.push(node.name.clone());
+        let mut state = State::default();
+        let stack_error =
+            |index: usize| move |error: StackError| malformed_instruction(&node.name, index, error);
+        for (index, instruction) in node.instructions.iter().enumerate() {
+            match instruction.opcode.try_into().unwrap() {
+                OpCode::PushString => state.push(instruction.read_operand::<String>(0)),
+                OpCode::PushFloat => state.push(instruction.read_operand::<f32>(0)),
+                OpCode::PushBool => state.push(instruction.read_operand::<bool>(0)),
+                OpCode::Pop => {
+                    state.pop_value().map_err(stack_error(index))?;
+                }
+                OpCode::PushVariable => {
+                    let variable_name: String = instruction.read_operand(0);
+                    let program = self.program.as_ref().unwrap();
+                    let value = if let Some(node) = program.smart_variable(&variable_name) {
+                        self.evaluate_smart_variable(node, evaluating)?
+                    } else {
+                        match self.variable_storage.get(&variable_name) {
+                            Ok(value) => value,
+                            Err(VariableStorageError::VariableNotFound { .. }) => program
+                                .initial_values
+                                .get(&variable_name)
+                                .unwrap_or_else(|| panic!("The loaded program does not contain an initial value for the variable {variable_name}"))
+                                .clone()
+                                .into(),
+                            Err(e) => return Err(e.into()),
+                        }
+                    };
+                    state.push(value);
+                }
+                OpCode::CallFunc => {
+                    let function_name: String = instruction.read_operand(0);
+                    let parameters = state
+                        .pop_function_arguments()
+                        .map_err(stack_error(index))?;
+                    let function = self.find_function(&function_name, &parameters, || {
+                        (node.name.clone(), None, None)
+                    })?;
+                    if self.library.is_deferred(&function_name) {
+                        return Err(DialogueError::DeferredFunctionInSmartVariable {
+                            variable_name: node.name.clone(),
+                            function_name,
+                        });
+                    }
+                    let return_type = function_return_type(function, &function_name);
+                    let outcome = self.rng.call_function(&node.name, || {
+                        self.function_dispatcher.call(function, parameters)
+                    })?;
+                    state.push(InternalValue {
+                        raw_value: outcome.return_value,
+                        r#type: return_type,
+                    });
+                }
+                opcode => {
+                    return Err(malformed_instruction(
+                        &node.name,
+                        index,
+                        format!("{opcode:?} is not part of an expression, which is all a smart variable consists of"),
+                    ))
+                }
+            }
+        }
+        evaluating.pop();
+        let value = state
+            .pop_value()
+            .map_err(stack_error(node.instructions.len()))?;
+        Ok(value.raw_value)
+    }
+
     /// ## Implementation note
     ///
     /// Increments the program counter here instead of in `continue_` for cleaner code
     fn run_instruction(&mut self, instruction: &Instruction) -> crate::Result<()> {
         let opcode: OpCode = instruction.opcode.try_into().unwrap();
+        self.last_program_counter = self.state.program_counter;
+        if self.log_debug_info && matches!(opcode, OpCode::RunLine | OpCode::RunCommand) {
+            let (node, source_file, line) = self.current_source_location();
+            match (source_file, line) {
+                (Some(source_file), Some(line)) => {
+                    info!("Running {opcode:?} of node \"{node}\" at {source_file}:{line}")
+                }
+                _ => info!("Running {opcode:?} of node \"{node}\" at an unknown source position"),
+            }
+        }
         match opcode {
             OpCode::JumpTo => {
                 // Jumps to a named label
@@ -292,7 +676,8 @@ impl VirtualMachine {
             }
             OpCode::Jump => {
                 // Jumps to a label whose name is on the stack.
-                let jump_destination: String = self.state.peek();
+                let jump_destination: String =
+                    self.state.peek().map_err(|e| self.stack_error(e))?;
                 self.state.program_counter =
                     self.find_instruction_point_for_label(&jump_destination);
             }
@@ -309,8 +694,12 @@ impl VirtualMachine {
                 // line handler.
                 assert_up_to_date_compiler(instruction.operands.len() >= 2);
 
-                let substitutions = self.pop_substitutions_with_count_at_operand(instruction, 1);
-                let line = self.prepare_line(string_id, &substitutions)?;
+                let substitutions = self.pop_substitutions_with_count_at_operand(instruction, 1)?;
+                let line = self.prepare_line(string_id.clone(), substitutions)?;
+                if self.recent_line_ids.len() == RECENT_LINE_ID_COUNT {
+                    self.recent_line_ids.pop_front();
+                }
+                self.recent_line_ids.push_back(string_id);
 
                 self.batched_events.push(DialogueEvent::Line(line));
 
@@ -327,15 +716,19 @@ impl VirtualMachine {
                 let command_text: String = instruction.read_operand(0);
                 assert_up_to_date_compiler(instruction.operands.len() >= 2);
                 let command_text = self
-                    .pop_substitutions_with_count_at_operand(instruction, 1)
+                    .pop_substitutions_with_count_at_operand(instruction, 1)?
                     .into_iter()
                     .enumerate()
                     .fold(command_text, |command_text, (i, substitution)| {
-                        command_text.replace(&format!("{{{i}}}"), &substitution)
+                        command_text.replace(&format!("{{{i}}}"), &String::from(substitution))
                     });
                 let command = Command::parse(command_text);
+                let event = match command.wait_duration() {
+                    Some(duration) => DialogueEvent::Wait(duration),
+                    None => DialogueEvent::Command(command),
+                };
 
-                self.batched_events.push(DialogueEvent::Command(command));
+                self.batched_events.push(event);
 
                 // Implementation note:
                 // In the original, this is only done if `execution_state` is still `DeliveringContent`,
@@ -350,8 +743,8 @@ impl VirtualMachine {
                 let string_id: String = instruction.read_operand(0);
                 let string_id: LineId = string_id.into();
                 assert_up_to_date_compiler(instruction.operands.len() >= 4);
-                let substitutions = self.pop_substitutions_with_count_at_operand(instruction, 2);
-                let line = self.prepare_line(string_id, &substitutions)?;
+                let substitutions = self.pop_substitutions_with_count_at_operand(instruction, 2)?;
+                let line = self.prepare_line(string_id, substitutions)?;
 
                 // Indicates whether the VM believes that the
                 // option should be shown to the user, based on any
@@ -363,13 +756,16 @@ impl VirtualMachine {
                     // the stack indicating whether the condition
                     // passed or not. We pass that information to
                     // the game.
-                    self.state.pop()
+                    self.state.pop().map_err(|e| self.stack_error(e))?
                 } else {
                     true
                 };
 
                 let index = self.state.current_options.len();
                 let node_name = instruction.read_operand(1);
+                // The optional fifth operand is the node the option's content jumps to
+                let destination =
+                    (instruction.operands.len() > 4).then(|| instruction.read_operand(4));
                 // ## Implementation note:
                 // The original calculates the ID in the `ShowOptions` opcode,
                 // but this way is cleaner because it allows us to store a `DialogueOption` instead of a bunch of values in a big tuple.
@@ -377,11 +773,21 @@ impl VirtualMachine {
                     line,
                     id: OptionId(index),
                     destination_node: node_name,
+                    destination,
                     is_available: line_condition_passed,
+                    // Set when the options are shown
+                    generation: Default::default(),
                 });
                 self.state.program_counter += 1;
             }
             OpCode::ShowOptions => {
+                if !self.should_send_unavailable_options {
+                    // The IDs are indices into all options, so we keep them as they are.
+                    self.state
+                        .current_options
+                        .retain(|option| option.is_available);
+                }
+
                 // If we have no options to show, immediately stop.
                 if self.state.current_options.is_empty() {
                     self.batched_events.push(DialogueEvent::DialogueComplete);
@@ -393,6 +799,11 @@ impl VirtualMachine {
                 // We can't continue until our client tell us which option to pick
                 self.set_execution_state(ExecutionState::WaitingOnOptionSelection);
 
+                self.option_generation += 1;
+                for option in &mut self.state.current_options {
+                    option.generation = self.option_generation;
+                }
+
                 // Pass the options set to the client, as well as a
                 // delegate for them to call when the user has made
                 // a selection
@@ -428,7 +839,7 @@ impl VirtualMachine {
             }
             OpCode::JumpIfFalse => {
                 // Jumps to a named label if the value on the top of the stack evaluates to the boolean value 'false'.
-                let is_top_value_true: bool = self.state.peek();
+                let is_top_value_true: bool = self.state.peek().map_err(|e| self.stack_error(e))?;
                 if !is_top_value_true {
                     let label_name: String = instruction.read_operand(0);
                     let instruction_point = self.find_instruction_point_for_label(&label_name);
@@ -439,48 +850,49 @@ impl VirtualMachine {
             }
             OpCode::Pop => {
                 // Pops a value from the stack.
-                self.state.pop_value();
+                self.state.pop_value().map_err(|e| self.stack_error(e))?;
                 self.state.program_counter += 1;
             }
             OpCode::CallFunc => {
-                let actual_parameter_count: usize = self.state.pop();
-                // Get the parameters, which were pushed in reverse
-                let parameters = {
-                    let mut parameters: Vec<_> = (0..actual_parameter_count)
-                        .rev()
-                        .map(|_| self.state.pop_value().raw_value)
-                        .collect();
-                    parameters.reverse();
-                    parameters
-                };
-
                 // Call a function, whose parameters are expected to be on the stack. Pushes the function's return value, if it returns one.
                 let function_name: String = instruction.read_operand(0);
-                let function =
-                    self.library
-                        .get(&function_name)
-                        .ok_or(DialogueError::FunctionNotFound {
-                            function_name: function_name.to_string(),
-                            library: self.library.clone(),
-                        })?;
-
-                // Expect the compiler to have placed the number of parameters
-                // actually passed at the top of the stack.
-                let expected_parameter_count = function.parameter_types().len();
-
-                assert_eq!(
-                    expected_parameter_count, actual_parameter_count,
-                    "Function {function_name} expected {expected_parameter_count} parameters, but received {actual_parameter_count}",
-                );
+                let parameters = self
+                    .state
+                    .pop_function_arguments()
+                    .map_err(|e| self.stack_error(e))?;
+                let function = self.find_function(&function_name, &parameters, || {
+                    self.current_source_location()
+                })?;
+                let return_type = function_return_type(function, &function_name);
+
+                if self.library.is_deferred(&function_name) {
+                    // Suspend in the middle of the expression. The stack is left as it is,
+                    // so the evaluation resumes once the return value is pushed by `complete_function`.
+                    let token = FunctionCallToken(self.next_function_call_token);
+                    self.next_function_call_token += 1;
+                    self.pending_function_call = Some(PendingFunctionCall {
+                        token,
+                        function_name: function_name.clone(),
+                        return_type,
+                    });
+                    self.batched_events
+                        .push(DialogueEvent::DeferredFunctionCall(DeferredFunctionCall {
+                            token,
+                            function_name,
+                            parameters,
+                        }));
+                    self.set_execution_state(ExecutionState::WaitingOnDeferredFunction);
+                    self.state.program_counter += 1;
+                    return Ok(());
+                }
 
                 // Invoke the function
-                let return_value = function.call(parameters);
-                let return_type = function
-                    .return_type()
-                    .try_into()
-                    .unwrap_or_else(|e| panic!("Failed to get Yarn type for return type id of function {function_name}: {e:?}"));
+                let node_name = self.current_node_name.as_deref().unwrap_or_default();
+                let outcome = self.rng.call_function(node_name, || {
+                    self.function_dispatcher.call(function, parameters)
+                })?;
                 let typed_return_value = InternalValue {
-                    raw_value: return_value,
+                    raw_value: outcome.return_value,
                     r#type: return_type,
                 };
                 // ## Implementation note:
@@ -488,10 +900,19 @@ impl VirtualMachine {
                 // In current Yarn, every function MUST return a valid typed value, so we skip that check.
                 self.state.push(typed_return_value);
                 self.state.program_counter += 1;
+
+                if let Some(elapsed) = outcome.elapsed {
+                    self.check_function_duration(function_name, elapsed)?;
+                }
             }
             OpCode::PushVariable => {
                 // Get the contents of a variable, push that onto the stack.
                 let variable_name: String = instruction.read_operand(0);
+                if let Some(value) = self.smart_variable_value(&variable_name) {
+                    self.state.push(value?);
+                    self.state.program_counter += 1;
+                    return Ok(());
+                }
                 let loaded_value = self
                     .variable_storage
                     .get(&variable_name)
@@ -523,7 +944,11 @@ impl VirtualMachine {
             }
             OpCode::StoreVariable => {
                 // Store the top value on the stack in a variable.
-                let top_value = self.state.peek_value().clone();
+                let top_value = self
+                    .state
+                    .peek_value()
+                    .map_err(|e| self.stack_error(e))?
+                    .clone();
                 let variable_name: String = instruction.read_operand(0);
                 self.variable_storage.set(variable_name, top_value.into())?;
                 self.state.program_counter += 1;
@@ -543,36 +968,372 @@ impl VirtualMachine {
 
                 // Pop a string from the stack, and jump to a node
                 // with that name.
-                let node_name: String = self.state.pop();
+                let node_name: String = self.state.pop().map_err(|e| self.stack_error(e))?;
+                let node_name = self.resolve_node_name(node_name);
                 self.batched_events
                     .push(DialogueEvent::NodeComplete(node_name.clone()));
+                // Jumping away ends all detours
                 self.set_node(&node_name)?;
 
                 // No need to increment the program counter, since otherwise we'd skip the first instruction
             }
+            OpCode::DetourToNode => {
+                // Pop a string from the stack, and run the node with that name,
+                // remembering where to come back to.
+                let node_name: String = self.state.pop().map_err(|e| self.stack_error(e))?;
+                let node_name = self.resolve_node_name(node_name);
+                let mut return_state = self.state.clone();
+                return_state.program_counter += 1;
+                let return_node = self.current_node.clone().unwrap();
+                self.load_node(&node_name)?;
+                self.call_stack.push((return_node, return_state));
+
+                // No need to increment the program counter, since otherwise we'd skip the first instruction
+            }
+            OpCode::Return => {
+                self.return_from_node();
+            }
+            OpCode::AddSaliencyCandidate => {
+                // The condition of the candidate is on the stack.
+                // Candidates without a condition push `true`.
+                let condition_passed: bool = self.state.pop().map_err(|e| self.stack_error(e))?;
+                if condition_passed {
+                    let content_id: String = instruction.read_operand(0);
+                    let complexity: f32 = instruction.read_operand(1);
+                    let destination: String = instruction.read_operand(2);
+                    let view_count_variable =
+                        Library::generate_unique_view_count_variable(&content_id);
+                    let last_viewed_variable =
+                        Library::generate_unique_last_viewed_variable(&content_id);
+                    let view_count = self.stored_count(&view_count_variable);
+                    let picks_since_viewed = self
+                        .variable_storage
+                        .contains(&last_viewed_variable)
+                        .then(|| {
+                            let pick_count =
+                                self.stored_count(Library::SALIENCY_PICK_COUNT_VARIABLE);
+                            pick_count.saturating_sub(self.stored_count(&last_viewed_variable) + 1)
+                        });
+                    self.saliency_candidates.push(SaliencyCandidate {
+                        view_count,
+                        picks_since_viewed,
+                        content_id,
+                        complexity: complexity as usize,
+                        destination,
+                    });
+                }
+                self.state.program_counter += 1;
+            }
+            OpCode::SelectSaliencyCandidate => {
+                // The strategy ranks the candidates, ties are broken randomly.
+                let mut candidates = std::mem::take(&mut self.saliency_candidates);
+                let most_salient = if candidates.is_empty() {
+                    Vec::new()
+                } else {
+                    self.saliency_strategy.most_salient(&candidates)
+                };
+                if most_salient.is_empty() {
+                    self.state.push(false);
+                } else {
+                    // Line group candidates jump to a label of the current node, node group candidates to another node
+                    let is_line_group = self.current_node.as_ref().is_some_and(|node| {
+                        node.labels
+                            .contains_key(&candidates[most_salient[0]].destination)
+                    });
+                    let kind = if is_line_group {
+                        RngDecisionKind::LineGroup
+                    } else {
+                        RngDecisionKind::NodeGroup
+                    };
+                    let node_name = self.current_node_name.as_deref().unwrap_or_default();
+                    let index = self.rng.choose_index(kind, node_name, most_salient.len())?;
+                    let candidate = candidates.swap_remove(most_salient[index]);
+                    self.record_saliency_pick(&candidate)?;
+                    self.state.push(candidate.destination);
+                    self.state.push(true);
+                }
+                self.state.program_counter += 1;
+            }
         }
         Ok(())
     }
 
-    fn prepare_line(&mut self, string_id: LineId, substitutions: &[String]) -> Result<Line> {
-        let line_text = self.text_provider.get_text(&string_id).ok_or_else(|| {
-            DialogueError::LineProviderError {
+    /// Tracks the view count and recency of the picked candidate in the variable storage, so that they persist.
+    fn record_saliency_pick(&mut self, candidate: &SaliencyCandidate) -> Result<()> {
+        let pick_count = self.stored_count(Library::SALIENCY_PICK_COUNT_VARIABLE);
+        self.variable_storage.set(
+            Library::generate_unique_view_count_variable(&candidate.content_id),
+            ((candidate.view_count + 1) as f32).into(),
+        )?;
+        self.variable_storage.set(
+            Library::generate_unique_last_viewed_variable(&candidate.content_id),
+            (pick_count as f32).into(),
+        )?;
+        self.variable_storage.set(
+            Library::SALIENCY_PICK_COUNT_VARIABLE.to_owned(),
+            ((pick_count + 1) as f32).into(),
+        )?;
+        Ok(())
+    }
+
+    /// Reads a count tracked by the runtime from the variable storage, treating a missing variable as zero.
+    fn stored_count(&self, variable_name: &str) -> usize {
+        match self.variable_storage.get(variable_name) {
+            Ok(YarnValue::Number(count)) => count as usize,
+            _ => 0,
+        }
+    }
+
+    /// Completes the current node and resumes the node that detoured into it.
+    /// If there is no such node, execution stops.
+    ///
+    /// The visit of the completed node has already been counted by the tracking code the compiler emits before every exit,
+    /// so the resumed invocation of a recursively detoured node sees it in `visited_count`.
+    fn return_from_node(&mut self) {
+        let current_node_name = self.current_node_name.clone().unwrap();
+        self.batched_events
+            .push(DialogueEvent::NodeComplete(current_node_name));
+        if let Some((node, state)) = self.call_stack.pop() {
+            debug!("Returning to node \"{}\"", node.name);
+            self.current_node_name = Some(node.name.clone());
+            self.current_node = Some(node);
+            self.state = state;
+        } else {
+            self.batched_events.push(DialogueEvent::DialogueComplete);
+            self.set_execution_state(ExecutionState::Stopped);
+            debug!("Run complete.");
+        }
+    }
+
+    fn prepare_line(&mut self, string_id: LineId, substitutions: Vec<YarnValue>) -> Result<Line> {
+        let raw_text = self
+            .text_provider
+            .get_text_with_substitutions(&string_id, &substitutions)
+            .ok_or_else(|| DialogueError::LineProviderError {
                 id: string_id.clone(),
                 language_code: self.language_code.clone(),
-            }
-        })?;
-        let substituted_text = expand_substitutions(&line_text, substitutions);
+            })?;
+        let substituted_text =
+            expand_localized_substitutions(&raw_text, &substitutions, self.language_code.as_ref());
         let markup = self
             .parse_markup(&substituted_text)
-            .map_err(DialogueError::MarkupParseError)?;
+            .map_err(|error| match error {
+                MarkupParseError::UnsupportedLocale { .. } => DialogueError::UnsupportedLocale {
+                    language_code: self.language_code.clone(),
+                },
+                error => DialogueError::MarkupParseError(error),
+            })?;
+        let metadata = self
+            .line_metadata
+            .get(&string_id)
+            .cloned()
+            .unwrap_or_default();
         let line = Line {
             id: string_id,
             text: markup.text,
             attributes: markup.attributes,
+            raw_text,
+            substitutions,
+            metadata,
         };
         Ok(line)
     }
 
+    pub(crate) fn complete_function(
+        &mut self,
+        token: FunctionCallToken,
+        value: YarnValue,
+    ) -> Result<()> {
+        let Some(pending_function_call) = self
+            .pending_function_call
+            .as_ref()
+            .filter(|pending_function_call| pending_function_call.token == token)
+        else {
+            return Err(DialogueError::UnexpectedFunctionCompletion { token });
+        };
+        let return_type = pending_function_call.return_type.clone();
+        let converted_value = match return_type {
+            Type::Number => f32::try_from(&value).ok().map(YarnValue::from),
+            Type::Boolean => bool::try_from(&value).ok().map(YarnValue::from),
+            Type::String => Some(YarnValue::from(String::from(value.clone()))),
+            _ => Some(value.clone()),
+        };
+        let Some(converted_value) = converted_value else {
+            // The call stays pending, so the game can try again with a fitting value
+            return Err(DialogueError::DeferredFunctionReturnMismatch {
+                function: pending_function_call.function_name.clone(),
+                expected: return_type,
+                found: value,
+            });
+        };
+        self.pending_function_call = None;
+        self.state.push(InternalValue {
+            raw_value: converted_value,
+            r#type: return_type,
+        });
+        self.set_execution_state(ExecutionState::WaitingForContinue);
+        Ok(())
+    }
+
+    /// Logs functions that took longer than the warning threshold and fails if the timeout was exceeded.
+    /// Since the function already returned, its return value is used and the dialogue can be continued afterwards.
+    fn check_function_duration(&mut self, function_name: String, elapsed: Duration) -> Result<()> {
+        if !self.function_dispatcher.exceeds_warning_threshold(elapsed)
+            && self.function_dispatcher.exceeded_timeout(elapsed).is_none()
+        {
+            return Ok(());
+        }
+        let (node, source_file, line) = self.current_source_location();
+        if self.function_dispatcher.exceeds_warning_threshold(elapsed) {
+            warn!(
+                "Function \"{function_name}\" took {elapsed:?} to return{}",
+                SourceLocation {
+                    node: &node,
+                    source_file: &source_file,
+                    line: &line,
+                }
+            );
+        }
+        let Some(timeout) = self.function_dispatcher.exceeded_timeout(elapsed) else {
+            return Ok(());
+        };
+        self.set_execution_state(ExecutionState::WaitingForContinue);
+        Err(DialogueError::FunctionTimeoutSuspected {
+            function_name,
+            elapsed,
+            timeout,
+            node,
+            source_file,
+            line,
+        })
+    }
+
+    /// Passes the error to the [`ErrorReporter`], if one is set, and hands it back.
+    pub(crate) fn report_error(&mut self, error: DialogueError) -> DialogueError {
+        let Some(mut error_reporter) = self.error_reporter.take() else {
+            return error;
+        };
+        let line_info = self.current_line_info();
+        let position = line_info.as_ref().and_then(|line_info| line_info.position);
+        error_reporter.report(&RuntimeErrorReport {
+            error: &error,
+            node: self.current_node_name.clone(),
+            instruction_index: self.state.program_counter,
+            file_name: line_info.map(|line_info| line_info.file_name),
+            line: position.map(|position| position.line + 1),
+            column: position.map(|position| position.character + 1),
+            recent_line_ids: self.recent_line_ids.iter().cloned().collect(),
+        });
+        self.error_reporter = Some(error_reporter);
+        error
+    }
+
+    /// Returns where in the Yarn source the currently running instruction was produced from,
+    /// if the [`DebugInfo`] of the current node was provided.
+    fn current_line_info(&self) -> Option<LineInfo> {
+        self.line_info(self.current_node_name.as_ref()?, self.state.program_counter)
+    }
+
+    /// Returns where in the Yarn source the instruction that was run last was produced from,
+    /// if the [`DebugInfo`] of the current node was provided.
+    pub(crate) fn last_line_info(&self) -> Option<LineInfo> {
+        self.line_info(self.current_node_name.as_ref()?, self.last_program_counter)
+    }
+
+    pub(crate) fn line_info(&self, node_name: &str, instruction_number: usize) -> Option<LineInfo> {
+        self.debug_infos
+            .get(node_name)?
+            .try_get_line_info(instruction_number)
+    }
+
+    /// Looks up the function called by an [`OpCode::CallFunc`] instruction and checks that it can be called with the given arguments.
+    /// `location` is only evaluated for the returned error and names the node, file and line of the call.
+    fn find_function(
+        &self,
+        function_name: &str,
+        parameters: &[YarnValue],
+        location: impl Fn() -> (String, Option<String>, Option<usize>),
+    ) -> Result<&dyn UntypedYarnFn> {
+        let function = self.library.get(function_name).ok_or_else(|| {
+            let (node, source_file, line) = location();
+            DialogueError::FunctionNotFound {
+                available: find_similar_function_names(&self.library, function_name),
+                function_name: function_name.to_owned(),
+                library: Box::new(self.library.as_ref().clone()),
+                node,
+                source_file,
+                line,
+            }
+        })?;
+
+        // Expect the compiler to have placed the number of parameters
+        // actually passed at the top of the stack.
+        let expected_parameter_count = function.parameter_types().len();
+        let actual_parameter_count = parameters.len();
+        if expected_parameter_count != actual_parameter_count {
+            let (node, source_file, line) = location();
+            return Err(DialogueError::FunctionArgumentCountMismatch {
+                function: function_name.to_owned(),
+                expected: expected_parameter_count,
+                found: actual_parameter_count,
+                node,
+                source_file,
+                line,
+            });
+        }
+
+        if let Some((parameter_index, expected, found)) =
+            find_function_argument_mismatch(&function.parameter_types(), parameters)
+        {
+            let (node, source_file, line) = location();
+            return Err(DialogueError::FunctionArgumentMismatch {
+                function: function_name.to_owned(),
+                parameter_index,
+                expected: Box::new(expected),
+                found: Box::new(found),
+                node,
+                source_file,
+                line,
+            });
+        }
+
+        if let Some((target, value)) =
+            find_invalid_cast(function_name, &function.parameter_types(), parameters)
+        {
+            let (node, source_file, line) = location();
+            return Err(DialogueError::InvalidCast {
+                function: function_name.to_owned(),
+                value,
+                target: Box::new(target),
+                node,
+                source_file,
+                line,
+            });
+        }
+
+        if is_integer_division_by_zero(function_name, parameters) {
+            let (node, source_file, line) = location();
+            return Err(DialogueError::DivisionByZero {
+                function: function_name.to_owned(),
+                node,
+                source_file,
+                line,
+            });
+        }
+        Ok(function)
+    }
+
+    /// Returns the name of the current node and, if its [`DebugInfo`] was provided,
+    /// the file and one-based line number that the currently running instruction was produced from.
+    fn current_source_location(&self) -> (String, Option<String>, Option<usize>) {
+        let node = self.current_node_name.clone().unwrap_or_default();
+        let Some(line_info) = self.current_line_info() else {
+            return (node, None, None);
+        };
+        let line = line_info.position.map(|position| position.line + 1);
+        (node, Some(line_info.file_name), line)
+    }
+
     /// Looks up the instruction number for a named label in the current node.
     ///
     /// # Panics
@@ -602,14 +1363,20 @@ impl VirtualMachine {
         &mut self,
         instruction: &Instruction,
         index: usize,
-    ) -> Vec<String> {
+    ) -> Result<Vec<YarnValue>> {
         let expression_count: usize = instruction.operands[index].clone().try_into().unwrap();
-        let mut values: Vec<_> = (0..expression_count)
-            .rev()
-            .map(|_| self.state.pop())
-            .collect();
-        values.reverse();
-        values
+        self.state
+            .pop_values(expression_count)
+            .map_err(|e| self.stack_error(e))
+    }
+
+    /// Describes the [`StackError`] of the instruction that is being run.
+    fn stack_error(&self, error: StackError) -> DialogueError {
+        malformed_instruction(
+            self.current_node_name.as_deref().unwrap_or_default(),
+            self.state.program_counter,
+            error,
+        )
     }
 }
 
@@ -621,6 +1388,135 @@ fn assert_up_to_date_compiler(predicate: bool) {
     )
 }
 
+/// A call to a deferred function that waits for its return value.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub(crate) struct PendingFunctionCall {
+    token: FunctionCallToken,
+    function_name: String,
+    return_type: Type,
+}
+
+/// How many of the last delivered line IDs are kept for a [`RuntimeErrorReport`].
+const RECENT_LINE_ID_COUNT: usize = 5;
+
+/// Finds the first argument that cannot be converted to the type of its parameter,
+/// so that a mismatch is reported as an error instead of panicking inside the function call.
+fn find_function_argument_mismatch(
+    parameter_types: &[std::any::TypeId],
+    arguments: &[YarnValue],
+) -> Option<(usize, Type, YarnValue)> {
+    for (parameter_index, (parameter_type, argument)) in
+        parameter_types.iter().zip(arguments).enumerate()
+    {
+        // Parameters such as `Option<T>` or tuples don't map to a single argument,
+        // so the remaining arguments cannot be matched up with their parameters anymore.
+        let Ok(expected) = Type::try_from(*parameter_type) else {
+            break;
+        };
+        if !is_convertible(argument, &expected) {
+            return Some((parameter_index, expected, argument.clone()));
+        }
+    }
+    None
+}
+
+fn malformed_instruction(node: &str, instruction: usize, reason: impl Display) -> DialogueError {
+    DialogueError::MalformedInstruction {
+        node: node.to_owned(),
+        instruction,
+        reason: reason.to_string(),
+    }
+}
+
+fn function_return_type(function: &dyn UntypedYarnFn, function_name: &str) -> Type {
+    function.return_type().try_into().unwrap_or_else(|e| {
+        panic!("Failed to get Yarn type for return type id of function {function_name}: {e:?}")
+    })
+}
+
+/// The casts of the standard library, `number` and `bool`, accept values of any type,
+/// so values that can't be converted would only be noticed inside of them. Returns the target type and the value if the cast fails.
+///
+/// Functions of the same name that take anything else than a single [`YarnValue`] were not registered by the standard library and are left alone.
+fn find_invalid_cast(
+    function_name: &str,
+    parameter_types: &[std::any::TypeId],
+    arguments: &[YarnValue],
+) -> Option<(Type, YarnValue)> {
+    let target = match function_name {
+        "number" => Type::Number,
+        "bool" => Type::Boolean,
+        _ => return None,
+    };
+    if parameter_types != [std::any::TypeId::of::<YarnValue>()] {
+        return None;
+    }
+    let [argument] = arguments else {
+        return None;
+    };
+    (!is_convertible(argument, &target)).then(|| (target, argument.clone()))
+}
+
+/// Whether the function divides integers by zero, which has no result. Numbers divided by zero are infinite instead.
+fn is_integer_division_by_zero(function_name: &str, arguments: &[YarnValue]) -> bool {
+    let is_integer_division = [Operator::Divide, Operator::Modulo].iter().any(|operator| {
+        function_name == Type::Integer.get_canonical_name_for_method(&operator.to_string())
+    });
+    is_integer_division
+        && matches!(arguments, [_, divisor] if i32::try_from(divisor).is_ok_and(|divisor| divisor == 0))
+}
+
+fn is_convertible(value: &YarnValue, r#type: &Type) -> bool {
+    match r#type {
+        Type::Number => f32::try_from(value).is_ok(),
+        Type::Boolean => bool::try_from(value).is_ok(),
+        _ => true,
+    }
+}
+
+/// Finds the registered functions whose names are close enough to `function_name` to likely be what was meant,
+/// e.g. because of a typo or a function that was renamed. The closest names come first.
+fn find_similar_function_names(library: &Library, function_name: &str) -> Vec<String> {
+    let max_distance = (function_name.chars().count() / 3).max(2);
+    let mut candidates: Vec<_> = library
+        .names()
+        .map(|name| (edit_distance(name, function_name), name))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    candidates.sort();
+    candidates
+        .into_iter()
+        .map(|(_, name)| name.to_owned())
+        .collect()
+}
+
+/// The Levenshtein distance between two strings, i.e. the number of single character insertions,
+/// deletions, or substitutions needed to turn one into the other.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<_> = b.chars().collect();
+    let mut previous_row: Vec<_> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current_row = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution_cost = usize::from(a_char != *b_char);
+            current_row.push(
+                (previous_row[j] + substitution_cost)
+                    .min(previous_row[j + 1] + 1)
+                    .min(current_row[j] + 1),
+            );
+        }
+        previous_row = current_row;
+    }
+    previous_row[b.len()]
+}
+
 /// Replaces all substitution markers in a text with the given substitution list.
 ///
 /// This method replaces substitution markers
@@ -629,11 +1525,11 @@ fn assert_up_to_date_compiler(predicate: bool) {
 /// index is not present in `substitutions`, it is
 /// ignored.
 #[must_use]
-fn expand_substitutions(text: &str, substitutions: &[String]) -> String {
+pub(crate) fn expand_substitutions(text: &str, substitutions: &[YarnValue]) -> String {
     substitutions
         .iter()
         .enumerate()
         .fold(text.to_owned(), |text, (i, substitution)| {
-            text.replace(&format!("{{{i}}}",), substitution)
+            text.replace(&format!("{{{i}}}",), &String::from(substitution))
         })
 }
@@ -24,6 +24,10 @@ pub(crate) enum ExecutionState {
     /// [`VirtualMachine::next`].
     WaitingOnOptionSelection,
 
+    /// The VirtualMachine is waiting for the return value of a deferred function.
+    /// Call [`VirtualMachine::complete_function`] before calling [`VirtualMachine::next`].
+    WaitingOnDeferredFunction,
+
     /// The VirtualMachine has finished delivering content to the
     /// client game, and is waiting for [`VirtualMachine::next`]
     /// to be called.
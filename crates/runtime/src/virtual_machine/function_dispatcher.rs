@@ -0,0 +1,56 @@
+//! Not part of the original implementation.
+
+use std::time::{Duration, Instant};
+use yarnspinner_core::prelude::*;
+
+/// Calls library functions on behalf of the [`VirtualMachine`](super::VirtualMachine), measuring how long they take.
+///
+/// Functions are arbitrary closures that cannot be preempted, so a function that takes too long
+/// can only be reported after it returned.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct FunctionDispatcher {
+    /// Calls taking longer than this are logged.
+    pub(crate) warning_threshold: Option<Duration>,
+    /// Calls taking longer than this fail with [`DialogueError::FunctionTimeoutSuspected`](crate::prelude::DialogueError::FunctionTimeoutSuspected).
+    pub(crate) timeout: Option<Duration>,
+}
+
+/// The result of a [`FunctionDispatcher::call`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FunctionCallOutcome {
+    pub(crate) return_value: YarnValue,
+    /// Only measured if a warning threshold or timeout is set.
+    pub(crate) elapsed: Option<Duration>,
+}
+
+impl FunctionDispatcher {
+    pub(crate) fn call(
+        &self,
+        function: &dyn UntypedYarnFn,
+        parameters: Vec<YarnValue>,
+    ) -> FunctionCallOutcome {
+        // Not all platforms support measuring time, so we only do it when asked to
+        if self.warning_threshold.is_none() && self.timeout.is_none() {
+            return FunctionCallOutcome {
+                return_value: function.call(parameters),
+                elapsed: None,
+            };
+        }
+        let start = Instant::now();
+        let return_value = function.call(parameters);
+        FunctionCallOutcome {
+            return_value,
+            elapsed: Some(start.elapsed()),
+        }
+    }
+
+    pub(crate) fn exceeds_warning_threshold(&self, elapsed: Duration) -> bool {
+        self.warning_threshold
+            .is_some_and(|threshold| elapsed > threshold)
+    }
+
+    /// Returns the timeout if it was exceeded.
+    pub(crate) fn exceeded_timeout(&self, elapsed: Duration) -> Option<Duration> {
+        self.timeout.filter(|timeout| elapsed > *timeout)
+    }
+}
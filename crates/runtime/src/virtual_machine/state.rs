@@ -1,7 +1,8 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner/VirtualMachine.cs>, which we split into multiple files
 
 use crate::prelude::*;
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
+use std::result::Result;
 use yarnspinner_core::prelude::*;
 
 #[derive(Debug, Clone, PartialEq, Default)]
@@ -31,46 +32,82 @@ impl State {
 
     /// Pops a value from the stack and tries to convert it to the specified type.
     ///
-    /// ## Panics
-    /// - Panics on an empty stack to mirror C# behavior.
-    /// - Panics if the value cannot be converted to the specified type.
-    pub(crate) fn pop<T>(&mut self) -> T
+    /// Unlike the original, this fails instead of throwing on an empty stack or a value of the wrong type,
+    /// since programs loaded with [`Program::from_bytes`] can use the stack incorrectly.
+    pub(crate) fn pop<T>(&mut self) -> Result<T, StackError>
     where
         T: TryFrom<InternalValue>,
         <T as TryFrom<InternalValue>>::Error: Debug,
     {
-        self.pop_value()
-            .try_into()
-            .unwrap_or_else(|e| panic!("Failed to convert popped value: {e:?}",))
+        convert(self.pop_value()?)
     }
 
-    /// Pops a value from the stack. Panics on an empty stack to mirror C# behavior.
-    pub(crate) fn pop_value(&mut self) -> InternalValue {
-        self.stack
-            .pop()
-            .unwrap_or_else(|| panic!("Tried to pop value, but the stack was empty."))
+    /// Pops a value from the stack. Fails on an empty stack.
+    pub(crate) fn pop_value(&mut self) -> Result<InternalValue, StackError> {
+        self.stack.pop().ok_or(StackError::Empty)
     }
 
-    /// Peeks the top value of the stack. Panics on an empty stack to mirror C# behavior.
-    pub(crate) fn peek<T>(&self) -> T
+    /// Pops the arguments of a function call, which are topped by their count, and returns them in the order they were pushed.
+    pub(crate) fn pop_function_arguments(&mut self) -> Result<Vec<YarnValue>, StackError> {
+        let count: usize = self.pop()?;
+        self.pop_values(count)
+    }
+
+    /// Pops `count` values from the stack and returns them in the order they were pushed.
+    pub(crate) fn pop_values(&mut self, count: usize) -> Result<Vec<YarnValue>, StackError> {
+        let mut values = (0..count)
+            .map(|_| self.pop_value().map(|value| value.raw_value))
+            .collect::<Result<Vec<_>, _>>()?;
+        values.reverse();
+        Ok(values)
+    }
+
+    /// Copies the top value of the stack and tries to convert it to the specified type.
+    /// Fails on an empty stack or a value of the wrong type.
+    pub(crate) fn peek<T>(&self) -> Result<T, StackError>
     where
         T: TryFrom<InternalValue>,
         <T as TryFrom<InternalValue>>::Error: Debug,
     {
-        self.peek_value()
-            .clone()
-            .try_into()
-            .unwrap_or_else(|e| panic!("Failed to convert popped value: {e:?}",))
+        convert(self.peek_value()?.clone())
     }
 
-    /// Copies the top value of the stack and tries to convert it to the specified type.
-    ///
-    /// ## Panics
-    /// - Panics on an empty stack to mirror C# behavior.
-    /// - Panics if the value cannot be converted to the specified type.
-    pub(crate) fn peek_value(&self) -> &InternalValue {
-        self.stack
-            .last()
-            .unwrap_or_else(|| panic!("Tried to peek value, but the stack was empty."))
+    /// Peeks the top value of the stack. Fails on an empty stack.
+    pub(crate) fn peek_value(&self) -> Result<&InternalValue, StackError> {
+        self.stack.last().ok_or(StackError::Empty)
+    }
+}
+
+fn convert<T>(value: InternalValue) -> Result<T, StackError>
+where
+    T: TryFrom<InternalValue>,
+    <T as TryFrom<InternalValue>>::Error: Debug,
+{
+    let raw_value = value.raw_value.clone();
+    value
+        .try_into()
+        .map_err(|error| StackError::UnexpectedValue {
+            value: raw_value,
+            error: format!("{error:?}"),
+        })
+}
+
+/// An instruction found the wrong values on the stack, which instructions produced by the compiler never do.
+/// See [`DialogueError::MalformedInstruction`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum StackError {
+    Empty,
+    UnexpectedValue { value: YarnValue, error: String },
+}
+
+impl Display for StackError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StackError::Empty => write!(f, "it needs a value, but the stack is empty"),
+            StackError::UnexpectedValue { value, error } => write!(
+                f,
+                "it can't use the value \"{value}\" on the stack: {error}"
+            ),
+        }
     }
 }
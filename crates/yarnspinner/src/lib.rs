@@ -10,28 +10,32 @@ pub use log;
 pub mod prelude {
     //! Everything you need to get started using Yarn Spinner.
     pub use crate::compiler::{
-        Compilation, CompilationType, Compiler as YarnCompiler, CompilerError, File as YarnFile,
-        LineInfo, Result as YarnCompilerResult, StringInfo,
+        Compilation, CompilationType, Compiler as YarnCompiler, CompilerError,
+        DiagnosticVocabulary, File as YarnFile, LineInfo, Result as YarnCompilerResult, StringInfo,
     };
     pub use crate::core::{
         yarn_library, IntoYarnValueFromNonYarnValue, Library as YarnLibrary, LineId,
         Program as YarnProgram, YarnFn, YarnValue,
     };
     pub use crate::runtime::{
-        Command as YarnCommand, CompiledProgramAnalyser as YarnAnalyser,
-        Context as YarnAnalysisContext, Dialogue, DialogueError, DialogueEvent, DialogueOption,
-        Language, Line as YarnLine, MarkupAttribute, MarkupValue, OptionId,
-        Result as YarnRuntimeResult, StringTable, TextProvider, VariableStorage,
+        Command as YarnCommand, CommandCompletion, CommandDispatcher, CommandResult,
+        CompiledProgramAnalyser as YarnAnalyser, Context as YarnAnalysisContext,
+        DeferredFunctionCall, Dialogue, DialogueError, DialogueEvent, DialogueOption,
+        ErrorReporter, FunctionCallToken, Language, Line as YarnLine, MarkupAttribute, MarkupValue,
+        OptionId, OptionSelection, Result as YarnRuntimeResult, RuntimeErrorReport, StringTable,
+        TextProvider, TextSource, VariableStorage,
     };
 }
 
 pub mod core {
     //! Core types and traits that are used by both the compiler and runtime.
     pub use yarnspinner_core::prelude::{
-        optionality, yarn_fn_type, yarn_library, Header, Instruction,
-        IntoYarnValueFromNonYarnValue, InvalidOpCodeError, Library, LineId, Node, Position,
-        Program, Type, UntypedYarnFn, YarnFn, YarnFnParam, YarnFnParamItem, YarnValue,
-        YarnValueCastError, YarnValueWrapper, YarnValueWrapperIter,
+        fnv1a_hash, optionality, wrap_hints, yarn_fn_type, yarn_library, DebugInfo, FunctionType,
+        Header, Instruction, InstructionDebugInfo, IntoYarnValueFromNonYarnValue,
+        InvalidOpCodeError, Library, LineId, LineInfo, Node, OpCode, Operator, Position, Program,
+        ProgramCombineError, ProgramLoadError, StatementKind, Type, UntypedYarnFn, WrapConfig,
+        WrapHints, YarnFn, YarnFnParam, YarnFnParamItem, YarnValue, YarnValueCastError,
+        YarnValueWrapper, YarnValueWrapperIter,
     };
 }
 pub mod compiler {
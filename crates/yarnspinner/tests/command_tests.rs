@@ -0,0 +1,310 @@
+//! Tests for validating commands against the signatures declared with [`Compiler::declare_command`]
+//! and for running them with a [`CommandDispatcher`].
+
+use std::sync::{Arc, Barrier, Mutex};
+use std::thread;
+use std::time::Duration;
+use test_base::prelude::*;
+use yarnspinner::compiler::*;
+use yarnspinner::core::*;
+use yarnspinner::runtime::{
+    Command, CommandDispatcher, CommandError, CommandResult, DialogueError, DialogueEvent,
+};
+
+mod test_base;
+
+fn signature(parameters: impl IntoIterator<Item = Type>) -> FunctionType {
+    let mut signature = FunctionType::default();
+    for parameter in parameters {
+        signature.add_parameter(parameter);
+    }
+    signature
+}
+
+fn compile_with_commands(source: &str) -> Result<Compilation> {
+    let mut variadic = signature([Type::String]);
+    variadic.set_variadic(true);
+    Compiler::from_test_source(source)
+        .declare_command(Declaration::new(
+            "walk",
+            signature([Type::String, Type::Number]),
+        ))
+        .declare_command(Declaration::new("show", signature([Type::Boolean])))
+        .declare_command(Declaration::new("say_all", variadic))
+        .compile()
+}
+
+#[test]
+fn test_matching_commands_compile_without_diagnostics() {
+    let source = "
+    <<declare $speed = 2>>
+    <<walk Sally 1.5>>
+    <<walk \"Sally Sparrow\" {$speed * 2}>>
+    <<show true>>
+    <<say_all>>
+    <<say_all hello there {\"friend\"}>>
+    <<once>>
+    <<endonce>>
+    <<stop>>
+    ";
+    let result = compile_with_commands(source).unwrap();
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+}
+
+#[test]
+fn test_undeclared_command_is_a_warning() {
+    let result = compile_with_commands("<<fly Sally>>").unwrap();
+    assert_eq!(1, result.warnings.len());
+    assert_eq!(
+        Some(DiagnosticCode::UndeclaredCommand),
+        result.warnings[0].code
+    );
+    assert!(result.warnings[0].message.contains("\"fly\""));
+}
+
+#[test]
+fn test_commands_are_not_validated_without_declarations() {
+    let result = Compiler::from_test_source("<<fly Sally>>")
+        .compile()
+        .unwrap();
+    assert!(result.warnings.is_empty());
+}
+
+#[test]
+fn test_wrong_argument_count_is_an_error() {
+    let errors = compile_with_commands("<<walk Sally>>").unwrap_err().0;
+    assert!(errors
+        .iter()
+        .any(|e| e.code == Some(DiagnosticCode::WrongParameterCount)
+            && e.message.contains("expects 2 argument(s), but received 1")));
+}
+
+#[test]
+fn test_literal_of_wrong_type_is_an_error() {
+    let errors = compile_with_commands("<<walk Sally fast>>").unwrap_err().0;
+    assert!(errors
+        .iter()
+        .any(|e| e.code == Some(DiagnosticCode::TypeMismatch)
+            && e.message.contains("Argument 2 of command \"walk\"")));
+
+    let errors = compile_with_commands("<<show \"true\">>").unwrap_err().0;
+    assert!(errors
+        .iter()
+        .any(|e| e.code == Some(DiagnosticCode::TypeMismatch)));
+}
+
+#[test]
+fn test_expression_of_wrong_type_is_an_error() {
+    let source = "
+    <<declare $name = \"Sally\">>
+    <<walk Sally {$name}>>
+    ";
+    let errors = compile_with_commands(source).unwrap_err().0;
+    assert!(errors
+        .iter()
+        .any(|e| e.code == Some(DiagnosticCode::TypeMismatch)
+            && e.message.contains("expects Number, but received String")));
+}
+
+#[test]
+fn test_dispatcher_runs_handlers_with_converted_parameters() {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let mut dispatcher = CommandDispatcher::new();
+    dispatcher
+        .add_command("give", {
+            let calls = calls.clone();
+            move |amount: i32, item: String| calls.lock().unwrap().push(format!("{amount} {item}"))
+        })
+        .add_command("rest", {
+            let calls = calls.clone();
+            move || calls.lock().unwrap().push("rest".to_owned())
+        });
+
+    let source =
+        "<<declare $gold = 2>>\n<<give {$gold + 1} coins>>\n<<rest>>\n<<give 1 \"magic sword\">>";
+    for command in run_commands(source) {
+        dispatcher.dispatch(&command).unwrap();
+    }
+
+    assert_eq!(
+        vec!["3 coins", "rest", "1 magic sword"],
+        *calls.lock().unwrap()
+    );
+}
+
+#[test]
+fn test_dispatcher_reports_mismatched_parameters() {
+    let calls = Arc::new(Mutex::new(0));
+    let mut dispatcher = CommandDispatcher::new();
+    dispatcher.add_command("give", {
+        let calls = calls.clone();
+        move |_amount: i32, _item: String| *calls.lock().unwrap() += 1
+    });
+
+    let commands = run_commands("<<give lots coins>>\n<<give 1>>\n<<take 1 coin>>");
+    let errors: Vec<_> = commands
+        .iter()
+        .map(|command| dispatcher.dispatch(command).unwrap_err())
+        .collect();
+
+    assert_eq!(0, *calls.lock().unwrap());
+    assert!(matches!(
+        &errors[0],
+        CommandError::InvalidParameter { parameter_index: 0, found, .. } if *found == YarnValue::from("lots")
+    ));
+    assert_eq!(
+        CommandError::ParameterCountMismatch {
+            command: "give".to_owned(),
+            expected: 2,
+            found: 1,
+        },
+        errors[1]
+    );
+    assert_eq!(
+        CommandError::CommandNotFound {
+            command: "take".to_owned(),
+        },
+        errors[2]
+    );
+}
+
+#[test]
+fn test_dialogue_waits_for_command_completed_from_another_thread() {
+    let arrive = Arc::new(Barrier::new(2));
+    let walker = Arc::new(Mutex::new(None));
+    let mut dispatcher = CommandDispatcher::new();
+    dispatcher.add_command("walk", {
+        let arrive = arrive.clone();
+        let walker = walker.clone();
+        move |_character: String, _target: String| {
+            let (result, completion) = CommandResult::in_progress();
+            let arrive = arrive.clone();
+            *walker.lock().unwrap() = Some(thread::spawn(move || {
+                arrive.wait();
+                completion.complete();
+            }));
+            result
+        }
+    });
+    let compilation = Compiler::from_test_source("<<walk Player Door>>\nArrived")
+        .compile()
+        .unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_node("Start").unwrap();
+
+    let events = dialogue.continue_().unwrap();
+    let Some(DialogueEvent::Command(command)) = events.last() else {
+        panic!("Expected a command, got {events:?}");
+    };
+    dialogue.wait_for_command(dispatcher.dispatch(command).unwrap());
+    assert!(dialogue.is_waiting_for_command());
+    assert!(matches!(
+        dialogue.continue_(),
+        Err(DialogueError::ContinueOnPendingCommandError)
+    ));
+
+    arrive.wait();
+    walker.lock().unwrap().take().unwrap().join().unwrap();
+    assert!(!dialogue.is_waiting_for_command());
+    let events = dialogue.continue_().unwrap();
+    assert!(matches!(&events[0], DialogueEvent::Line(line) if line.text == "Arrived"));
+}
+
+#[test]
+fn test_dropping_command_completion_fails_continue_instead_of_hanging() {
+    let (result, completion) = CommandResult::in_progress();
+    let compilation = Compiler::from_test_source("<<walk Player Door>>\nArrived")
+        .compile()
+        .unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_node("Start").unwrap();
+    dialogue.continue_().unwrap();
+    dialogue.wait_for_command(result);
+
+    thread::spawn(move || drop(completion)).join().unwrap();
+    assert!(!dialogue.is_waiting_for_command());
+    assert!(matches!(
+        dialogue.continue_(),
+        Err(DialogueError::CommandAbandoned)
+    ));
+
+    // The error is only reported once
+    let events = dialogue.continue_().unwrap();
+    assert!(matches!(&events[0], DialogueEvent::Line(line) if line.text == "Arrived"));
+}
+
+#[test]
+fn test_completed_command_results_are_not_waited_for() {
+    let mut dispatcher = CommandDispatcher::new();
+    dispatcher.add_command("rest", || {});
+    let command = run_commands("<<rest>>").remove(0);
+    let result = dispatcher.dispatch(&command).unwrap();
+    assert!(result.is_completed());
+
+    let compilation = Compiler::from_test_source("<<rest>>\nRested")
+        .compile()
+        .unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_node("Start").unwrap();
+    dialogue.continue_().unwrap();
+    dialogue.wait_for_command(result);
+    assert!(!dialogue.is_waiting_for_command());
+    dialogue.continue_().unwrap();
+}
+
+#[test]
+fn test_wait_pauses_dialogue_for_duration() {
+    let source = "
+    <<declare $delay = 1.5>>
+    <<wait 0.5>>
+    <<wait {$delay * 2}>>
+    <<wait soon>>
+    ";
+    let compilation = compile_with_commands(source).unwrap();
+    // `wait` is built in, so it doesn't need to be declared
+    assert!(
+        compilation.warnings.is_empty(),
+        "{:?}",
+        compilation.warnings
+    );
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_node("Start").unwrap();
+
+    let mut pauses = Vec::new();
+    let mut is_complete = false;
+    while !is_complete {
+        for event in dialogue.continue_().unwrap() {
+            match event {
+                DialogueEvent::Wait(duration) => pauses.push(Ok(duration)),
+                DialogueEvent::Command(command) => pauses.push(Err(command.raw)),
+                DialogueEvent::DialogueComplete => is_complete = true,
+                _ => {}
+            }
+        }
+    }
+    assert_eq!(
+        vec![
+            Ok(Duration::from_millis(500)),
+            Ok(Duration::from_secs(3)),
+            // Not a duration, so it's up to the game to handle it
+            Err("wait soon".to_owned()),
+        ],
+        pauses
+    );
+}
+
+fn run_commands(source: &str) -> Vec<Command> {
+    let compilation = Compiler::from_test_source(source).compile().unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_node("Start").unwrap();
+    let mut commands = Vec::new();
+    loop {
+        for event in dialogue.continue_().unwrap() {
+            match event {
+                DialogueEvent::Command(command) => commands.push(command),
+                DialogueEvent::DialogueComplete => return commands,
+                _ => {}
+            }
+        }
+    }
+}
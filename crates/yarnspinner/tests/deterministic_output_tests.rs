@@ -0,0 +1,75 @@
+use yarnspinner::compiler::*;
+
+const FIRST: &str = "\
+title: Start
+---
+<<declare $gold = 10>>
+<<if visited(\"Shop\")>>
+    Back again? You have {$gold} gold.
+<<endif>>
+-> Go shopping
+    <<jump Shop>>
+-> Leave
+===
+title: Tavern
+---
+<<once>>
+    First time here!
+<<endonce>>
+=> Cheers!
+=> Hello!
+===
+";
+
+const SECOND: &str = "\
+title: Shop
+---
+<<set $item to \"sword\">>
+<<if visited_count(\"Tavern\") > 1>>
+    Regulars get a discount.
+<<endif>>
+===
+title: Forge
+---
+<<set $hot to true>>
+<<detour Start>>
+===
+";
+
+#[test]
+fn test_compiling_same_files_produces_identical_output() {
+    let compile = || {
+        Compiler::new()
+            .add_file(File {
+                file_name: "first.yarn".to_owned(),
+                source: FIRST.to_owned(),
+            })
+            .add_file(File {
+                file_name: "second.yarn".to_owned(),
+                source: SECOND.to_owned(),
+            })
+            .with_deterministic_output(true)
+            .compile()
+            .unwrap()
+    };
+    let reference = compile();
+    let reference_bytes = reference.program.as_ref().unwrap().to_bytes();
+    let declaration_names: Vec<_> = reference
+        .declarations
+        .iter()
+        .map(|declaration| declaration.name.as_str())
+        .collect();
+    let mut sorted_names = declaration_names.clone();
+    sorted_names.sort();
+    assert_eq!(sorted_names, declaration_names);
+
+    for _ in 0..50 {
+        let compilation = compile();
+        assert_eq!(
+            reference_bytes,
+            compilation.program.as_ref().unwrap().to_bytes()
+        );
+        assert_eq!(reference.declarations, compilation.declarations);
+        assert_eq!(reference.warnings, compilation.warnings);
+    }
+}
@@ -0,0 +1,179 @@
+use regex::Regex;
+use std::path::PathBuf;
+use yarnspinner::compiler::*;
+
+const FIXTURES: &[(&str, &str)] = &[
+    ("syntax_error", "<<if true>>"),
+    (
+        "duplicate_declaration",
+        "<<declare $gold = 0>>\n<<declare $gold = 1>>",
+    ),
+    (
+        "type_mismatch",
+        "<<declare $gold = 0>>\n<<set $gold to \"lots\">>",
+    ),
+    (
+        "type_mismatch_between_terms",
+        "<<declare $gold = 0>>\n<<if $gold + \"lots\">>\n<<endif>>",
+    ),
+    ("undetermined_type", "<<set $gold to $silver>>"),
+    (
+        "wrong_parameter_count",
+        "<<declare $gold = 0>>\n<<set $gold to dice(6)>>\n<<set $gold to dice(1, 6)>>",
+    ),
+    ("non_constant_declaration", "<<declare $gold = dice(6)>>"),
+    ("missing_jump_target", "<<jump Nowhere>>"),
+    ("undeclared_function", "You have {gold_count()} gold."),
+    ("invalid_escape_sequence", "Keep calm \\and carry on."),
+];
+
+#[test]
+fn test_default_vocabulary_keeps_built_in_messages() {
+    assert_snapshot("default", &DiagnosticVocabulary::new());
+}
+
+#[test]
+fn test_plain_english_vocabulary() {
+    assert_snapshot("plain_english", &DiagnosticVocabulary::plain_english());
+}
+
+#[test]
+fn test_templates_missing_arguments_fall_back_to_built_in_message() {
+    let vocabulary = DiagnosticVocabulary::new()
+        .with_template(DiagnosticCode::MissingJumpTarget, "[{code}] {message}")
+        .with_template(
+            DiagnosticCode::TypeMismatch,
+            "Expected {expected}, got {actual}",
+        );
+    let diagnostics = compile_fixture("<<jump Nowhere>>");
+    assert_eq!(
+        "[YS0015] Jump target 'Nowhere' does not exist",
+        vocabulary.message(&diagnostics[0])
+    );
+
+    // An operation between terms of different types has no single expected type
+    let diagnostics = compile_fixture("<<declare $gold = 0>>\n<<if $gold + \"lots\">>\n<<endif>>");
+    let type_mismatch = diagnostics
+        .iter()
+        .find(|diagnostic| diagnostic.code == Some(DiagnosticCode::TypeMismatch))
+        .unwrap();
+    assert!(!type_mismatch.arguments.contains_key("expected"));
+    assert_eq!(type_mismatch.message, vocabulary.message(type_mismatch));
+}
+
+#[test]
+fn test_loading_vocabulary_from_json_and_ron() {
+    let json = r#"{ "MissingJumpTarget": "Le nœud « {node} » n'existe pas." }"#;
+    let ron = r#"{ MissingJumpTarget: "Le nœud « {node} » n'existe pas." }"#;
+    let from_json = DiagnosticVocabulary::from_json(json).unwrap();
+    let from_ron = DiagnosticVocabulary::from_ron(ron).unwrap();
+    assert_eq!(from_json, from_ron);
+
+    let error = CompilerError(compile_fixture("<<jump Nowhere>>")).reworded(&from_json);
+    assert_eq!("Le nœud « Nowhere » n'existe pas.", error.0[0].message);
+}
+
+#[test]
+fn test_every_placeholder_is_used_by_plain_english_vocabulary() {
+    let vocabulary = DiagnosticVocabulary::plain_english();
+    for code in all_codes() {
+        let template = vocabulary.template(code).unwrap();
+        for placeholder in code.placeholders() {
+            let is_optional = matches!(
+                (code, *placeholder),
+                (DiagnosticCode::UndeterminedType, "expression")
+            );
+            assert!(
+                is_optional || template.contains(&format!("{{{placeholder}}}")),
+                "{code} does not use {{{placeholder}}}"
+            );
+        }
+    }
+}
+
+fn all_codes() -> Vec<DiagnosticCode> {
+    use DiagnosticCode::*;
+    vec![
+        SyntaxError,
+        MixedIndentation,
+        DuplicateNodeName,
+        MissingNodeTitle,
+        InvalidNodeName,
+        DuplicateDeclaration,
+        UnknownType,
+        NonConstantDeclaration,
+        InvalidNumber,
+        TypeMismatch,
+        UndeterminedType,
+        WrongParameterCount,
+        DuplicateLineId,
+        NullDefaultValue,
+        MissingJumpTarget,
+        DivisionByZero,
+        UndeclaredFunction,
+        UndeclaredCommand,
+        LineIdNamespaceViolation,
+        LineOverflow,
+        UnfulfilledContract,
+        StaleContract,
+        UnverifiableContract,
+        InvalidEscapeSequence,
+        ReadOnlyVariable,
+        SmartVariableCycle,
+        MissingLineId,
+        InfiniteJumpLoop,
+        InvalidEnum,
+        JumpToExcludedNode,
+        NoFilesToCompile,
+        InterpolationCountMismatch,
+        UnreachableContent,
+    ]
+}
+
+fn compile_fixture(body: &str) -> Vec<Diagnostic> {
+    let source = format!("title: Start\n---\n{body}\n===\n");
+    let result = Compiler::new()
+        .add_file(File {
+            file_name: "story.yarn".to_owned(),
+            source,
+        })
+        .compile();
+    match result {
+        Ok(compilation) => compilation.warnings,
+        Err(error) => error.0,
+    }
+}
+
+fn render(vocabulary: &DiagnosticVocabulary) -> String {
+    let ansi_escape = Regex::new("\x1b\\[[0-9;]*m").unwrap();
+    let mut rendered = String::new();
+    for (name, body) in FIXTURES {
+        rendered.push_str(&format!("===== {name} =====\n"));
+        for diagnostic in compile_fixture(body) {
+            let diagnostic = vocabulary.reword(diagnostic);
+            rendered.push_str(&ansi_escape.replace_all(&diagnostic.to_string(), ""));
+        }
+    }
+    rendered
+}
+
+/// Compares the rendered fixtures to the snapshot in `tests/snapshots`.
+/// Run with `UPDATE_SNAPSHOTS=1` to overwrite the snapshot instead.
+fn assert_snapshot(name: &str, vocabulary: &DiagnosticVocabulary) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("snapshots")
+        .join(format!("diagnostic_vocabulary_{name}.txt"));
+    let rendered = render(vocabulary);
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, rendered).unwrap();
+        return;
+    }
+    let snapshot = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        snapshot,
+        rendered,
+        "snapshot {} is outdated",
+        path.display()
+    );
+}
@@ -3,9 +3,14 @@
 //! ## Implementation notes
 //! `TestDumpingCode` was not ported because `GetByteCode` is not used by a user directly and thus was not implemented at all.
 
+use std::any::Any;
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
 use test_base::prelude::*;
 use yarnspinner::compiler::*;
+use yarnspinner::core::*;
 use yarnspinner::runtime::*;
 
 mod test_base;
@@ -102,6 +107,54 @@ fn test_missing_node() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_set_node_restarts_dialogue_at_start_of_node() {
+    let mut dialogue = compile_quest_dialogue().dialogue;
+    dialogue.set_node("Intro").unwrap();
+    assert_eq!(vec!["Hello, traveller."], next_lines(&mut dialogue));
+    assert_eq!(2, next_options(&mut dialogue).len());
+
+    // Abandons the pending options
+    dialogue.set_node("Quest").unwrap();
+    assert_eq!(Some("Quest".to_owned()), dialogue.current_node());
+    assert_eq!(vec!["The quest begins."], next_lines(&mut dialogue));
+    assert_eq!(vec!["The quest continues."], next_lines(&mut dialogue));
+
+    dialogue.set_node("Quest").unwrap();
+    assert_eq!(vec!["The quest begins."], next_lines(&mut dialogue));
+}
+
+#[test]
+fn test_set_node_to_unknown_node_fails_without_changing_state() {
+    let mut dialogue = compile_quest_dialogue()
+        .with_runtime_errors_do_not_cause_failure()
+        .dialogue;
+    dialogue.set_node("Quest").unwrap();
+    assert_eq!(vec!["The quest begins."], next_lines(&mut dialogue));
+
+    let error = dialogue.set_node("Missing").unwrap_err();
+    assert!(matches!(
+        error,
+        DialogueError::InvalidNode { node_name } if node_name == "Missing"
+    ));
+    assert_eq!(Some("Quest".to_owned()), dialogue.current_node());
+    assert_eq!(vec!["The quest continues."], next_lines(&mut dialogue));
+}
+
+fn compile_quest_dialogue() -> TestBase {
+    let source =
+        "title: Intro\n---\nHello, traveller.\n-> Accept\n    <<jump Quest>>\n-> Decline\n===\n\
+                  title: Quest\n---\nThe quest begins.\nThe quest continues.\n===\n";
+    let compilation = Compiler::new()
+        .add_file(File {
+            file_name: "quest.yarn".to_string(),
+            source: source.to_string(),
+        })
+        .compile()
+        .unwrap();
+    TestBase::new().with_compilation(compilation)
+}
+
 #[test]
 fn test_getting_current_node_name() {
     let path = space_demo_scripts_path().join("Sally.yarn");
@@ -184,6 +237,36 @@ fn test_getting_headers() {
     assert_eq!(headers, expected_headers);
 }
 
+#[test]
+fn test_node_headers_keep_custom_and_repeated_keys() {
+    let source = "\
+title: Shop
+group: merchants
+style: cozy
+visited_with: Sally
+visited_with: Ship
+---
+Welcome!
+===
+";
+    let compilation = Compiler::new()
+        .add_file(File {
+            file_name: "shop.yarn".to_owned(),
+            source: source.to_owned(),
+        })
+        .compile()
+        .unwrap();
+    let dialogue = TestBase::new().with_compilation(compilation).dialogue;
+
+    let headers = dialogue.node_headers("Shop").unwrap();
+
+    assert_eq!(Some(&vec!["Shop"]), headers.get("title"));
+    assert_eq!(Some(&vec!["merchants"]), headers.get("group"));
+    assert_eq!(Some(&vec!["cozy"]), headers.get("style"));
+    assert_eq!(Some(&vec!["Sally", "Ship"]), headers.get("visited_with"));
+    assert_eq!(None, dialogue.node_headers("Start"));
+}
+
 /// ## Implementation note
 /// Corresponds to `TestPrepareForLine`
 #[test]
@@ -267,6 +350,294 @@ fn test_function_argument_type_inference() {
     assert!(!bool_value);
 }
 
+#[test]
+fn test_function_argument_mismatch_is_reported() {
+    let mut compile_time_library = Library::new();
+    compile_time_library.add_function("Double", |text: String| text.repeat(2));
+    let result =
+        Compiler::from_test_source("<<declare $result = \"\">>\n<<set $result = Double(\"two\")>>")
+            .extend_library(compile_time_library)
+            .compile()
+            .unwrap();
+
+    // The library at runtime disagrees with the one the script was compiled against
+    let mut dialogue = TestBase::new()
+        .extend_library(|library| {
+            library.add_function("Double", |number: f32| number * 2.0);
+        })
+        .with_compilation(result)
+        .dialogue;
+    dialogue.set_node("Start").unwrap();
+
+    let error = dialogue.continue_().unwrap_err();
+    assert!(matches!(
+        error,
+        DialogueError::FunctionArgumentMismatch {
+            function,
+            parameter_index: 0,
+            expected,
+            found,
+            ..
+        } if function == "Double" && *expected == Type::Number && *found == YarnValue::from("two")
+    ));
+}
+
+#[test]
+fn test_function_argument_count_mismatch_is_reported() {
+    let mut compile_time_library = Library::new();
+    compile_time_library.add_function("Add", |a: f32, b: f32| a + b);
+    let result = Compiler::from_test_source("<<declare $result = 0>>\n<<set $result = Add(1, 2)>>")
+        .extend_library(compile_time_library)
+        .compile()
+        .unwrap();
+
+    // The library at runtime takes fewer parameters than the one the script was compiled against
+    let mut dialogue = TestBase::new()
+        .extend_library(|library| {
+            library.add_function("Add", |a: f32| a);
+        })
+        .with_compilation(result)
+        .dialogue;
+    dialogue.set_node("Start").unwrap();
+
+    let error = dialogue.continue_().unwrap_err();
+    assert!(matches!(
+        error,
+        DialogueError::FunctionArgumentCountMismatch {
+            function,
+            expected: 1,
+            found: 2,
+            ..
+        } if function == "Add"
+    ));
+}
+
+#[test]
+fn test_runtime_errors_refer_to_source_position() {
+    let mut compile_time_library = Library::new();
+    compile_time_library.add_function("Missing", || true);
+    let source = "\
+Before the call
+<<if Missing()>>
+    Unreachable
+<<endif>>";
+    let result = Compiler::from_test_source(source)
+        .extend_library(compile_time_library)
+        .compile()
+        .unwrap();
+
+    let mut dialogue = TestBase::new().with_compilation(result).dialogue;
+    dialogue.set_node("Start").unwrap();
+    assert_eq!(vec!["Before the call"], next_lines(&mut dialogue));
+
+    let error = dialogue.continue_().unwrap_err();
+    // The test node is preceded by a title and a `---` line
+    assert!(matches!(
+        &error,
+        DialogueError::FunctionNotFound {
+            function_name,
+            node,
+            source_file: Some(source_file),
+            line: Some(4),
+            ..
+        } if function_name == "Missing" && node == "Start" && source_file == "<input>"
+    ));
+    assert!(error.to_string().contains("(node \"Start\", <input>:4)"));
+}
+
+#[test]
+fn test_missing_function_error_suggests_similar_functions() {
+    let mut compile_time_library = Library::new();
+    compile_time_library.add_function("add_tree", |a: f32| a + 3.0);
+    let source = "\
+Result: {add_tree(1)}";
+    let result = Compiler::from_test_source(source)
+        .extend_library(compile_time_library)
+        .compile()
+        .unwrap();
+
+    let mut dialogue = TestBase::new().with_compilation(result).dialogue;
+    dialogue
+        .library_mut()
+        .add_function("add_three", |a: f32| a + 3.0)
+        .add_function("add_one", |a: f32| a + 1.0)
+        .add_function("add_threes", |a: f32| a + 33.0)
+        .add_function("subtract_three", |a: f32| a - 3.0);
+    dialogue.set_node("Start").unwrap();
+
+    let error = dialogue.continue_().unwrap_err();
+    let DialogueError::FunctionNotFound {
+        function_name,
+        node,
+        available,
+        ..
+    } = &error
+    else {
+        panic!("Expected a missing function error, got {error:?}");
+    };
+    assert_eq!("add_tree", function_name);
+    assert_eq!("Start", node);
+    assert_eq!(
+        &vec!["add_three".to_owned(), "add_threes".to_owned()],
+        available
+    );
+    assert!(error
+        .to_string()
+        .contains("Did you mean \"add_three\" or \"add_threes\"?"));
+
+    // The dialogue is left in a state from which it can be stopped and started again
+    dialogue.stop();
+    assert!(!dialogue.is_active());
+    dialogue
+        .library_mut()
+        .add_function("add_tree", |a: f32| a + 3.0);
+    dialogue.set_node("Start").unwrap();
+    assert_eq!(vec!["Result: 4"], next_lines(&mut dialogue));
+}
+
+#[test]
+fn test_error_reporter_receives_source_coordinates() {
+    /// The file, line, column and recent line IDs of a report
+    type RecordedReport = (Option<String>, Option<usize>, Option<usize>, Vec<LineId>);
+
+    #[derive(Debug, Clone, Default)]
+    struct RecordingErrorReporter(Arc<Mutex<Vec<RecordedReport>>>);
+
+    impl ErrorReporter for RecordingErrorReporter {
+        fn clone_shallow(&self) -> Box<dyn ErrorReporter> {
+            Box::new(self.clone())
+        }
+
+        fn report(&mut self, report: &RuntimeErrorReport) {
+            assert!(matches!(
+                report.error,
+                DialogueError::FunctionNotFound { .. }
+            ));
+            assert_eq!(Some("Start"), report.node.as_deref());
+            self.0.lock().unwrap().push((
+                report.file_name.clone(),
+                report.line,
+                report.column,
+                report.recent_line_ids.clone(),
+            ));
+        }
+    }
+
+    let mut compile_time_library = Library::new();
+    compile_time_library.add_function("Missing", || 1);
+    let source = "\
+First line #line:first
+Second line #line:second
+Result: {1 + Missing()}";
+    let result = Compiler::from_test_source(source)
+        .extend_library(compile_time_library)
+        .compile()
+        .unwrap();
+
+    let error_reporter = RecordingErrorReporter::default();
+    let mut dialogue = TestBase::new().with_compilation(result).dialogue;
+    dialogue
+        .set_error_reporter(Box::new(error_reporter.clone()))
+        .set_node("Start")
+        .unwrap();
+    next_lines(&mut dialogue);
+    next_lines(&mut dialogue);
+    dialogue.continue_().unwrap_err();
+
+    // The test node is preceded by a title and a `---` line
+    let reports = error_reporter.0.lock().unwrap();
+    assert_eq!(
+        vec![(
+            Some("<input>".to_owned()),
+            Some(5),
+            Some(14),
+            vec![LineId::from("line:first"), LineId::from("line:second")]
+        )],
+        *reports
+    );
+}
+
+#[test]
+fn test_log_error_reporter_forwards_errors_to_log() {
+    let mut compile_time_library = Library::new();
+    compile_time_library.add_function("MissingLogged", || 1);
+    let result = Compiler::from_test_source("Result: {1 + MissingLogged()}")
+        .extend_library(compile_time_library)
+        .compile()
+        .unwrap();
+
+    let mut dialogue = TestBase::new().with_compilation(result).dialogue;
+    dialogue
+        .set_error_reporter(Box::new(LogErrorReporter::new(log::Level::Warn)))
+        .set_node("Start")
+        .unwrap();
+    let error = dialogue.continue_().unwrap_err();
+
+    // The test node is preceded by a title and a `---` line
+    let expected = format!("<input>:3:14: {error}");
+    assert!(logged_warnings().contains(&expected));
+}
+
+#[test]
+fn test_resolving_instructions_to_source_positions() {
+    let compilation = Compiler::new()
+        .add_file(File {
+            file_name: "first.yarn".to_owned(),
+            source: "title: First\n---\nHello from the first file\n<<detour Second>>\n===\n"
+                .to_owned(),
+        })
+        .add_file(File {
+            file_name: "second.yarn".to_owned(),
+            source: "title: Second\n---\n\n<<wave>>\n    Hello from the second file\n===\n"
+                .to_owned(),
+        })
+        .compile()
+        .unwrap();
+    let mut dialogue = TestBase::new()
+        .with_compilation(compilation.clone())
+        .dialogue;
+    assert_eq!(None, dialogue.current_line_info());
+
+    dialogue.set_node("First").unwrap();
+    assert_eq!(vec!["Hello from the first file"], next_lines(&mut dialogue));
+    let line_info = dialogue.current_line_info().unwrap();
+    assert_eq!("First", line_info.node_name);
+    assert_eq!("first.yarn:3:1", line_info.to_string());
+
+    let events = dialogue.continue_().unwrap();
+    assert!(
+        matches!(events.last(), Some(DialogueEvent::Command(command)) if command.name == "wave")
+    );
+    // Commands refer to their text, which starts after the `<<`
+    assert_eq!(
+        "second.yarn:4:3",
+        dialogue.current_line_info().unwrap().to_string()
+    );
+    assert_eq!(
+        vec!["Hello from the second file"],
+        next_lines(&mut dialogue)
+    );
+    assert_eq!(
+        "second.yarn:5:5",
+        dialogue.current_line_info().unwrap().to_string()
+    );
+
+    let second_debug_info = &compilation.debug_info["Second"];
+    for instruction_number in 0..compilation.program.unwrap().nodes["Second"]
+        .instructions
+        .len()
+    {
+        assert_eq!(
+            second_debug_info.try_get_line_info(instruction_number),
+            dialogue.line_info("Second", instruction_number)
+        );
+    }
+    assert_eq!(None, dialogue.line_info("Missing", 0));
+
+    let _ = dialogue.stop();
+    assert_eq!(None, dialogue.current_line_info());
+}
+
 #[test]
 fn test_selecting_option_from_inside_option_callback() {
     let result = Compiler::from_test_source("-> option 1\n->option 2\nfinal line\n")
@@ -320,10 +691,1640 @@ fn test_selecting_option_from_inside_option_callback() {
                     assert_eq!(ExpectedStepType::Stop, expected_step);
                 }
                 DialogueEvent::Command(_)
+                | DialogueEvent::DeferredFunctionCall(_)
                 | DialogueEvent::NodeComplete(_)
                 | DialogueEvent::NodeStart(_)
-                | DialogueEvent::LineHints(_) => {}
+                | DialogueEvent::LineHints(_)
+                | DialogueEvent::Wait(_) => {}
             }
         }
     }
 }
+
+#[test]
+fn test_reloading_program_keeps_position_and_variables() {
+    let source = "<<declare $x = 1>>\n<<set $x = 2>>\nOne\nTwo\n";
+    let compilation = Compiler::from_test_source(source).compile().unwrap();
+    let mut dialogue = TestBase::new()
+        .with_compilation(compilation.clone())
+        .dialogue;
+    dialogue.set_node("Start").unwrap();
+
+    let events = dialogue.continue_().unwrap();
+    assert!(matches!(events.last(), Some(DialogueEvent::Line(line)) if line.text == "One"));
+
+    assert!(dialogue.reload_program(compilation.program.unwrap()));
+    assert_eq!(Some("Start".to_owned()), dialogue.current_node());
+    let x: f32 = dialogue
+        .variable_storage()
+        .get("$x")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(2.0, x);
+
+    let events = dialogue.continue_().unwrap();
+    assert!(matches!(events.last(), Some(DialogueEvent::Line(line)) if line.text == "Two"));
+}
+
+#[test]
+fn test_getting_and_setting_variables_by_name() {
+    let source = "<<declare $player_name = \"Player\">>\nHello, {$player_name}!\n";
+    let compilation = Compiler::from_test_source(source).compile().unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    assert_eq!(
+        Some(YarnValue::from("Player")),
+        dialogue.get_variable("$player_name")
+    );
+
+    dialogue.set_variable("$player_name", "Alice").unwrap();
+    assert_eq!(
+        Some(YarnValue::from("Alice")),
+        dialogue.get_variable("$player_name")
+    );
+    dialogue.set_node("Start").unwrap();
+    let events = dialogue.continue_().unwrap();
+    assert!(
+        matches!(events.last(), Some(DialogueEvent::Line(line)) if line.text == "Hello, Alice!")
+    );
+
+    // Undeclared variables are up to the game
+    assert_eq!(None, dialogue.get_variable("$coins"));
+    dialogue.set_variable("$coins", 3).unwrap();
+    assert_eq!(Some(YarnValue::from(3)), dialogue.get_variable("$coins"));
+}
+
+#[test]
+fn test_setting_variable_of_wrong_type_is_rejected() {
+    let source = "<<declare $gold = 10>>\nYou have {$gold} gold.\n";
+    let compilation = Compiler::from_test_source(source).compile().unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+
+    let result = dialogue.set_variable("$gold", "lots");
+    assert!(matches!(
+        result,
+        Err(DialogueError::VariableTypeMismatch {
+            expected: Type::Number,
+            ..
+        })
+    ));
+    assert_eq!(Some(YarnValue::from(10)), dialogue.get_variable("$gold"));
+}
+
+#[test]
+fn test_reloading_changed_node_resets_position() {
+    let compilation = Compiler::from_test_source("One\nTwo\n").compile().unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_node("Start").unwrap();
+    let _ = dialogue.continue_().unwrap();
+
+    let changed = Compiler::from_test_source("One\nTwo\nThree\n")
+        .compile()
+        .unwrap();
+    assert!(!dialogue.reload_program(changed.program.unwrap()));
+    assert_eq!(None, dialogue.current_node());
+}
+
+#[test]
+fn test_selecting_stale_option_fails() {
+    let compilation = Compiler::from_test_source("-> A\n-> B\n")
+        .compile()
+        .unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_node("Start").unwrap();
+    let stale_options = next_options(&mut dialogue);
+
+    // Rewind by restarting the node
+    let _ = dialogue.stop();
+    dialogue.set_node("Start").unwrap();
+    let options = next_options(&mut dialogue);
+    assert_eq!(options[0].generation, dialogue.option_generation());
+
+    let error = dialogue.set_selected_option(&stale_options[0]).unwrap_err();
+    assert!(matches!(
+        error,
+        DialogueError::StaleOptionSelection {
+            presented_generation,
+            current_generation,
+        } if presented_generation == stale_options[0].generation && current_generation == options[0].generation
+    ));
+    dialogue.set_selected_option(&options[1]).unwrap();
+}
+
+#[test]
+fn test_replacing_program_invalidates_options() {
+    let compilation = Compiler::from_test_source("-> A\n-> B\n")
+        .compile()
+        .unwrap();
+    let mut dialogue = TestBase::new()
+        .with_compilation(compilation.clone())
+        .dialogue;
+    dialogue.set_node("Start").unwrap();
+    let options = next_options(&mut dialogue);
+
+    dialogue.replace_program(compilation.program.unwrap());
+    assert!(matches!(
+        dialogue.set_selected_option(&options[0]),
+        Err(DialogueError::StaleOptionSelection { .. })
+    ));
+}
+
+#[test]
+fn test_detour_returns_to_caller() {
+    let source = "title: A\n---\nBefore\n<<detour B>>\nAfter\n===\n\
+                  title: B\n---\nIn B\n<<return>>\nUnreachable\n===\n";
+    let compilation = Compiler::new()
+        .add_file(File {
+            file_name: "detour.yarn".to_string(),
+            source: source.to_string(),
+        })
+        .compile()
+        .unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_node("A").unwrap();
+
+    assert_eq!(vec!["Before"], next_lines(&mut dialogue));
+    assert_eq!(vec!["In B"], next_lines(&mut dialogue));
+    assert_eq!(Some("B".to_owned()), dialogue.current_node());
+    assert_eq!(vec!["After"], next_lines(&mut dialogue));
+    assert_eq!(Some("A".to_owned()), dialogue.current_node());
+    assert!(next_lines(&mut dialogue).is_empty());
+    assert!(!dialogue.is_active());
+}
+
+#[test]
+fn test_nested_detours_and_jumping_out_of_a_detour() {
+    let source = "title: A\n---\n<<detour B>>\nBack in A\n===\n\
+                  title: B\n---\n<<detour C>>\nBack in B\n<<jump D>>\n===\n\
+                  title: C\n---\nIn C\n===\n\
+                  title: D\n---\nIn D\n===\n";
+    let compilation = Compiler::new()
+        .add_file(File {
+            file_name: "detour.yarn".to_string(),
+            source: source.to_string(),
+        })
+        .compile()
+        .unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_node("A").unwrap();
+
+    let mut lines = next_lines(&mut dialogue);
+    while dialogue.is_active() {
+        lines.extend(next_lines(&mut dialogue));
+    }
+    // Jumping to D ends the detour, so A is never resumed
+    assert_eq!(vec!["In C", "Back in B", "In D"], lines);
+}
+
+#[test]
+fn test_nested_detours_return_to_each_caller() {
+    let source = "title: A\n---\nA1\n<<detour B>>\nA2\n===\n\
+                  title: B\n---\nB1\n<<detour C>>\nB2\n===\n\
+                  title: C\n---\nC1\n<<return>>\nUnreachable\n===\n";
+    let mut dialogue = detour_dialogue(source);
+    dialogue.set_node("A").unwrap();
+
+    assert_eq!(vec!["A1"], next_lines(&mut dialogue));
+    assert_eq!(vec!["B1"], next_lines(&mut dialogue));
+    assert_eq!(vec!["C1"], next_lines(&mut dialogue));
+    assert_eq!(Some("C".to_owned()), dialogue.current_node());
+    assert_eq!(vec!["B2"], next_lines(&mut dialogue));
+    assert_eq!(Some("B".to_owned()), dialogue.current_node());
+    assert_eq!(vec!["A2"], next_lines(&mut dialogue));
+    assert_eq!(Some("A".to_owned()), dialogue.current_node());
+    assert!(next_lines(&mut dialogue).is_empty());
+    assert!(!dialogue.is_active());
+}
+
+#[test]
+fn test_detours_interleaved_with_options() {
+    let source = "title: A\n---\n\
+                  -> Visit the shop\n    <<detour Shop>>\n    Left the shop\n\
+                  -> Stay\n    Stayed\n\
+                  Goodbye\n===\n\
+                  title: Shop\n---\nWelcome!\n\
+                  -> Buy\n    Bought it\n\
+                  -> Leave\n    <<return>>\n\
+                  Thanks for buying\n===\n";
+    let mut dialogue = detour_dialogue(source);
+    dialogue.set_node("A").unwrap();
+
+    let options = next_options(&mut dialogue);
+    dialogue.set_selected_option(&options[0]).unwrap();
+    assert_eq!(vec!["Welcome!"], next_lines(&mut dialogue));
+    let options = next_options(&mut dialogue);
+    assert_eq!(Some("Shop".to_owned()), dialogue.current_node());
+    dialogue.set_selected_option(&options[1]).unwrap();
+    assert_eq!(vec!["Left the shop"], next_lines(&mut dialogue));
+    assert_eq!(Some("A".to_owned()), dialogue.current_node());
+    assert_eq!(vec!["Goodbye"], next_lines(&mut dialogue));
+    assert!(next_lines(&mut dialogue).is_empty());
+    assert!(!dialogue.is_active());
+}
+
+#[test]
+fn test_detoured_nodes_count_as_visited() {
+    let source = "title: A\n---\n<<detour B>>\n<<detour B>>\n\
+                  B was visited {visited_count(\"B\")} times\n===\n\
+                  title: B\n---\nIn B\n<<if true>>\n    <<return>>\n<<endif>>\nUnreachable\n===\n";
+    let mut dialogue = detour_dialogue(source);
+    dialogue.set_node("A").unwrap();
+
+    let mut lines = next_lines(&mut dialogue);
+    while dialogue.is_active() {
+        lines.extend(next_lines(&mut dialogue));
+    }
+    assert_eq!(vec!["In B", "In B", "B was visited 2 times"], lines);
+}
+
+const SELF_DETOUR: &str = "title: Start\n---\n<<declare $depth = 0>>\n<<detour Loop>>\n\
+                           Done {visited_count(\"Loop\")}\n===\n\
+                           title: Loop\n---\nEntering {visited_count(\"Loop\")}\n\
+                           <<if $depth < 2>>\n    <<set $depth to $depth + 1>>\n    <<detour Loop>>\n<<endif>>\n\
+                           Leaving {visited_count(\"Loop\")}\n===\n";
+
+#[test]
+fn test_recursive_detours_count_each_completed_visit() {
+    let mut dialogue = detour_dialogue(SELF_DETOUR);
+
+    // A visit only counts once its invocation completes, so the inner invocations are counted before the outer ones
+    assert_eq!(
+        vec![
+            "Entering 0",
+            "Entering 0",
+            "Entering 0",
+            "Leaving 0",
+            "Leaving 1",
+            "Leaving 2",
+            "Done 3"
+        ],
+        run_to_end(&mut dialogue)
+    );
+}
+
+#[test]
+fn test_detour_cycles_count_each_completed_visit() {
+    let source = "title: Ping\n---\n<<declare $rounds = 0>>\n\
+                  <<if $rounds < 2>>\n    <<set $rounds to $rounds + 1>>\n    <<detour Pong>>\n<<endif>>\n\
+                  Ping done: {visited_count(\"Ping\")} {visited_count(\"Pong\")}\n===\n\
+                  title: Pong\n---\n<<detour Ping>>\n\
+                  Pong done: {visited_count(\"Ping\")} {visited_count(\"Pong\")}\n===\n";
+    let mut dialogue = detour_dialogue(source);
+    dialogue.set_node("Ping").unwrap();
+
+    let mut lines = next_lines(&mut dialogue);
+    while dialogue.is_active() {
+        lines.extend(next_lines(&mut dialogue));
+    }
+    assert_eq!(
+        vec![
+            "Ping done: 0 0",
+            "Pong done: 1 0",
+            "Ping done: 1 1",
+            "Pong done: 2 1",
+            "Ping done: 2 2"
+        ],
+        lines
+    );
+    assert_eq!(Some(3.0), visited_count(&dialogue, "Ping"));
+    assert_eq!(Some(2.0), visited_count(&dialogue, "Pong"));
+}
+
+#[test]
+fn test_stopping_mid_detour_does_not_count_incomplete_visits() {
+    let mut dialogue = detour_dialogue(SELF_DETOUR);
+    dialogue.set_node("Start").unwrap();
+    for _ in 0..3 {
+        let _ = next_lines(&mut dialogue);
+    }
+    // Two invocations of Loop are waiting to be returned to, one is running
+    assert_eq!(Some("Loop".to_owned()), dialogue.current_node());
+    let _ = dialogue.stop();
+    assert_eq!(Some(0.0), visited_count(&dialogue, "Loop"));
+    assert!(matches!(
+        dialogue.continue_(),
+        Err(DialogueError::NoNodeSelectedOnContinue)
+    ));
+
+    // $depth is still 2, so Loop does not detour into itself this time
+    assert_eq!(
+        vec!["Entering 0", "Leaving 0", "Done 1"],
+        run_to_end(&mut dialogue)
+    );
+}
+
+#[test]
+fn test_visited_before_and_after_entering_node() {
+    let source = "title: Start\n---\n\
+                  <<if not visited(\"Shop\")>>\n    Not in the shop yet: {visited_count(\"Shop\")}\n<<endif>>\n\
+                  <<detour Shop>>\n\
+                  <<if visited(\"Shop\")>>\n    Back from the shop: {visited_count(\"Shop\")}\n<<endif>>\n\
+                  <<if not visited(\"Cellar\")>>\n    Never in the cellar: {visited_count(\"Cellar\")}\n<<endif>>\n===\n\
+                  title: Shop\n---\nIn the shop\n===\n\
+                  title: Cellar\n---\nIt's dusty\n===\n";
+    let mut dialogue = detour_dialogue(source);
+
+    assert_eq!(
+        vec![
+            "Not in the shop yet: 0",
+            "In the shop",
+            "Back from the shop: 1",
+            "Never in the cellar: 0"
+        ],
+        run_to_end(&mut dialogue)
+    );
+    assert_eq!(Some(1.0), visited_count(&dialogue, "Shop"));
+    assert_eq!(Some(0.0), visited_count(&dialogue, "Cellar"));
+}
+
+#[test]
+fn test_visited_functions_declare_tracking_variables() {
+    let source = "title: Start\n---\n\
+                  Cellar: {visited_count(\"Cellar\")}\n===\n\
+                  title: Cellar\n---\nIt's dusty\n===\n\
+                  title: Attic\n---\nIt's dark\n===\n";
+    let compilation = Compiler::new()
+        .add_source("tracking.yarn", source)
+        .compile()
+        .unwrap();
+
+    let tracking_variable = Library::generate_unique_visited_variable_for_node("Cellar");
+    let declaration = compilation
+        .declarations
+        .iter()
+        .find(|declaration| declaration.name == tracking_variable)
+        .unwrap();
+    assert_eq!(Type::Number, declaration.r#type);
+    assert_eq!(Some(YarnValue::from(0.0)), declaration.default_value);
+    // Nodes that no visited function refers to are not tracked
+    let untracked_variable = Library::generate_unique_visited_variable_for_node("Attic");
+    assert!(compilation
+        .declarations
+        .iter()
+        .all(|declaration| declaration.name != untracked_variable));
+}
+
+fn visited_count(dialogue: &Dialogue, node_name: &str) -> Option<f32> {
+    dialogue
+        .get_variable(&Library::generate_unique_visited_variable_for_node(
+            node_name,
+        ))
+        .map(|value| value.try_into().unwrap())
+}
+
+#[test]
+fn test_return_outside_of_detour_ends_dialogue() {
+    let source = "title: A\n---\nBefore\n<<return>>\nAfter\n===\n";
+    let mut dialogue = detour_dialogue(source);
+    dialogue.set_node("A").unwrap();
+
+    assert_eq!(vec!["Before"], next_lines(&mut dialogue));
+    let events = dialogue.continue_().unwrap();
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, DialogueEvent::DialogueComplete)));
+    assert!(!dialogue.is_active());
+}
+
+#[test]
+fn test_stop_ends_dialogue_mid_node() {
+    let source = "title: Start\n---\nBefore\n<<stop>>\nAfter\n===\n";
+    let mut dialogue = detour_dialogue(source);
+    dialogue.set_node("Start").unwrap();
+
+    assert_eq!(vec!["Before"], next_lines(&mut dialogue));
+    let events = dialogue.continue_().unwrap();
+    assert!(!events
+        .iter()
+        .any(|event| matches!(event, DialogueEvent::Line(_))));
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, DialogueEvent::DialogueComplete)));
+    assert!(!dialogue.is_active());
+}
+
+#[test]
+fn test_stop_in_detour_ends_whole_dialogue() {
+    let source = "title: Start\n---\nBefore\n<<detour Detour>>\nAfter detour\n===\n\
+                  title: Detour\n---\nIn detour\n<<stop>>\nAfter stop\n===\n";
+    let mut dialogue = detour_dialogue(source);
+
+    assert_eq!(vec!["Before", "In detour"], run_to_end(&mut dialogue));
+    assert!(matches!(
+        dialogue.continue_(),
+        Err(DialogueError::NoNodeSelectedOnContinue)
+    ));
+}
+
+fn detour_dialogue(source: &str) -> Dialogue {
+    let compilation = Compiler::new()
+        .add_file(File {
+            file_name: "detour.yarn".to_string(),
+            source: source.to_string(),
+        })
+        .compile()
+        .unwrap();
+    TestBase::new().with_compilation(compilation).dialogue
+}
+
+#[test]
+fn test_line_group_selects_most_salient_candidate() {
+    let source = "<<declare $gold = 10>>\n\
+                  => You are broke. <<if $gold == 0>>\n\
+                  => You have some gold. <<if $gold > 0>>\n\
+                  => You are rich. <<if $gold > 5 && $gold < 100>>\n\
+                  => Hello.\n\
+                  Done\n";
+    let compilation = Compiler::from_test_source(source).compile().unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_node("Start").unwrap();
+
+    assert_eq!(vec!["You are rich."], next_lines(&mut dialogue));
+    assert_eq!(vec!["Done"], next_lines(&mut dialogue));
+    assert!(next_lines(&mut dialogue).is_empty());
+    assert!(!dialogue.is_active());
+}
+
+#[test]
+fn test_line_group_is_skipped_when_no_condition_passes() {
+    let source = "<<declare $gold = 10>>\n\
+                  Before\n\
+                  => You are broke. <<if $gold == 0>>\n\
+                  => You are in debt. <<if $gold < 0>>\n\
+                  After\n";
+    let compilation = Compiler::from_test_source(source).compile().unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_node("Start").unwrap();
+
+    assert_eq!(vec!["Before"], next_lines(&mut dialogue));
+    assert_eq!(vec!["After"], next_lines(&mut dialogue));
+    assert!(next_lines(&mut dialogue).is_empty());
+    assert!(!dialogue.is_active());
+}
+
+#[test]
+fn test_bark_line_group_does_not_repeat_while_others_are_eligible() {
+    let source = "title: Bark\n---\n\
+                  <<declare $wanted = false>>\n\
+                  => Halt!\n\
+                  => Move along.\n\
+                  => Stop, thief! <<if $wanted>>\n\
+                  => Nice weather today.\n\
+                  => Keep your nose clean.\n\
+                  ===\n";
+    let compilation = Compiler::new()
+        .add_file(File {
+            file_name: "bark.yarn".to_string(),
+            source: source.to_string(),
+        })
+        .compile()
+        .unwrap();
+    assert_eq!(5, compilation.string_table.len());
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_saliency_strategy(Box::new(RandomBestLeastRecentlyViewedSaliencyStrategy));
+
+    let mut barks = Vec::new();
+    for _ in 0..40 {
+        dialogue.set_node("Bark").unwrap();
+        barks.extend(next_lines(&mut dialogue));
+    }
+    assert_eq!(40, barks.len());
+    assert!(barks.windows(2).all(|pair| pair[0] != pair[1]));
+    assert!(!barks.contains(&"Stop, thief!".to_owned()));
+    assert!(barks.contains(&"Nice weather today.".to_owned()));
+
+    // The view counts are variables, so they persist along with the others
+    let view_counts: f32 = dialogue
+        .variable_storage()
+        .variables()
+        .into_iter()
+        .filter(|(name, _)| name.starts_with("$Yarn.Internal.ViewCount."))
+        .map(|(_, value)| f32::try_from(value).unwrap())
+        .sum();
+    assert_eq!(40.0, view_counts);
+}
+
+#[test]
+fn test_once_runs_on_first_visit_and_else_afterwards() {
+    let source = "<<once>>\n\
+                  First visit\n\
+                  <<else>>\n\
+                  Later visit\n\
+                  <<endonce>>\n\
+                  <<once>>\n\
+                  Welcome\n\
+                  <<endonce>>\n\
+                  Done\n\
+                  <<jump Start>>\n";
+    let compilation = Compiler::from_test_source(source).compile().unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_node("Start").unwrap();
+
+    let lines: Vec<_> = (0..6).flat_map(|_| next_lines(&mut dialogue)).collect();
+    assert_eq!(
+        vec![
+            "First visit",
+            "Welcome",
+            "Done",
+            "Later visit",
+            "Done",
+            "Later visit"
+        ],
+        lines
+    );
+}
+
+#[test]
+fn test_once_with_condition_runs_once_the_condition_passes() {
+    let source = "<<declare $ready = false>>\n\
+                  <<once if $ready>>\n\
+                  Ready\n\
+                  <<else>>\n\
+                  Not now\n\
+                  <<endonce>>\n\
+                  <<set $ready to true>>\n\
+                  <<jump Start>>\n";
+    let compilation = Compiler::from_test_source(source).compile().unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_node("Start").unwrap();
+
+    let lines: Vec<_> = (0..3).flat_map(|_| next_lines(&mut dialogue)).collect();
+    assert_eq!(vec!["Not now", "Ready", "Not now"], lines);
+}
+
+#[test]
+fn test_once_without_endonce_fails_to_compile() {
+    let result = Compiler::from_test_source("<<once>>\nNever closed\n").compile();
+    let diagnostics = result.unwrap_err().0;
+    assert!(diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.message == "Found <<once>> without a matching <<endonce>>"));
+}
+
+#[test]
+fn test_once_block_stays_suppressed_after_restoring_variables() {
+    let source = "<<once>>\n\
+                  Welcome\n\
+                  <<endonce>>\n\
+                  Done\n";
+    let compilation = Compiler::from_test_source(source).compile().unwrap();
+    let mut dialogue = TestBase::new()
+        .with_compilation(compilation.clone())
+        .dialogue;
+    dialogue.set_node("Start").unwrap();
+    assert_eq!(vec!["Welcome"], next_lines(&mut dialogue));
+    let saved_variables = dialogue.variable_storage().variables();
+
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue
+        .variable_storage_mut()
+        .extend(saved_variables)
+        .unwrap();
+    dialogue.set_node("Start").unwrap();
+    assert_eq!(vec!["Done"], next_lines(&mut dialogue));
+}
+
+#[test]
+fn test_once_state_survives_editing_lines_around_it() {
+    let original =
+        "-> Ask about map <<once>>\n    <<once>>\n        Told about map\n    <<endonce>>\n\
+                    -> Leave\n";
+    let edited = "Hello again\n\
+                  Good to see you\n\
+                  -> Ask about map <<once>>\n    Well...\n    <<once>>\n        Told about map\n    <<endonce>>\n\
+                  -> Leave\n";
+    let compilation = Compiler::from_test_source(original).compile().unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_node("Start").unwrap();
+    let options = next_options(&mut dialogue);
+    dialogue.set_selected_option(&options[0]).unwrap();
+    assert_eq!(vec!["Told about map"], next_lines(&mut dialogue));
+    let saved_variables = dialogue.variable_storage().variables();
+
+    let compilation = Compiler::from_test_source(edited).compile().unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue
+        .variable_storage_mut()
+        .extend(saved_variables)
+        .unwrap();
+    dialogue.set_node("Start").unwrap();
+    assert_eq!(vec!["Hello again"], next_lines(&mut dialogue));
+    assert_eq!(vec!["Good to see you"], next_lines(&mut dialogue));
+    let options = next_options(&mut dialogue);
+    assert!(!options[0].is_available);
+    dialogue.set_selected_option(&options[1]).unwrap();
+}
+
+#[test]
+fn test_once_options_are_unavailable_after_being_selected() {
+    let source = "<<declare $gold = 10>>\n\
+                  -> Buy map <<once if $gold >= 10>>\n    Bought map\n\
+                  -> Ask about map <<once>> #important\n    Asked\n\
+                  -> Leave\n\
+                  <<jump Start>>\n";
+    let compilation = Compiler::from_test_source(source).compile().unwrap();
+    // The once marker is not part of the metadata
+    let metadata: HashMap<_, _> = compilation
+        .string_table
+        .values()
+        .map(|info| (info.text.as_str(), info.metadata.clone()))
+        .collect();
+    assert!(metadata["Buy map"].is_empty());
+    assert_eq!(vec!["important"], metadata["Ask about map"]);
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_node("Start").unwrap();
+
+    let options = next_options(&mut dialogue);
+    assert!(options.iter().all(|option| option.is_available));
+    dialogue.set_selected_option(&options[0]).unwrap();
+    assert_eq!(vec!["Bought map"], next_lines(&mut dialogue));
+
+    let options = next_options(&mut dialogue);
+    assert!(!options[0].is_available);
+    assert!(options[1].is_available);
+    dialogue.set_selected_option(&options[1]).unwrap();
+    assert_eq!(vec!["Asked"], next_lines(&mut dialogue));
+
+    let options = next_options(&mut dialogue);
+    assert!(!options[0].is_available);
+    assert!(!options[1].is_available);
+    assert!(options[2].is_available);
+}
+
+#[test]
+fn test_unavailable_options_are_only_sent_when_enabled() {
+    let source = "<<declare $gold = 5>>\n\
+                  -> Buy sword <<if $gold >= 10>>\n\
+                  -> Leave\n";
+    let compilation = Compiler::from_test_source(source).compile().unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    assert!(dialogue.should_send_unavailable_options());
+
+    dialogue.set_node("Start").unwrap();
+    let options = next_options(&mut dialogue);
+    assert_eq!(2, options.len());
+    assert!(!options[0].is_available);
+    assert!(options[1].is_available);
+
+    dialogue.stop();
+    dialogue.set_should_send_unavailable_options(false);
+    dialogue.set_node("Start").unwrap();
+    let options = next_options(&mut dialogue);
+    assert_eq!(1, options.len());
+    assert_eq!("Leave", options[0].line.text);
+    // IDs refer to all options, including the ones that were left out
+    assert_eq!(OptionId(1), options[0].id);
+    dialogue.set_selected_option(&options[0]).unwrap();
+}
+
+#[test]
+fn test_selecting_unavailable_option_fails() {
+    let source = "<<declare $gold = 5>>\n\
+                  -> Buy sword <<if $gold >= 10>>\n\
+                  -> Leave\n";
+    let compilation = Compiler::from_test_source(source).compile().unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_node("Start").unwrap();
+    let options = next_options(&mut dialogue);
+
+    let error = dialogue.set_selected_option(&options[0]).unwrap_err();
+    assert!(matches!(
+        error,
+        DialogueError::UnavailableOptionSelected {
+            selected_option_id: OptionId(0)
+        }
+    ));
+    // The options are still waiting for a valid selection
+    dialogue.set_selected_option(&options[1]).unwrap();
+}
+
+#[test]
+fn test_slow_function_is_logged_with_source_position() {
+    let mut compile_time_library = Library::new();
+    compile_time_library.add_function("slow_lookup", || true);
+    let result = Compiler::from_test_source("Before\n<<if slow_lookup()>>\nFound\n<<endif>>")
+        .extend_library(compile_time_library)
+        .compile()
+        .unwrap();
+    let mut dialogue = TestBase::new()
+        .extend_library(|library| {
+            library.add_function("slow_lookup", || {
+                thread::sleep(Duration::from_millis(20));
+                true
+            });
+        })
+        .with_compilation(result)
+        .dialogue;
+    dialogue.set_function_warning_threshold(Duration::from_millis(1));
+    dialogue.set_node("Start").unwrap();
+
+    assert_eq!(vec!["Before"], next_lines(&mut dialogue));
+    assert_eq!(vec!["Found"], next_lines(&mut dialogue));
+    assert!(logged_warnings().iter().any(|warning| warning
+        .starts_with("Function \"slow_lookup\" took")
+        && warning.ends_with("(node \"Start\", <input>:4)")));
+}
+
+#[test]
+fn test_function_exceeding_timeout_is_reported_after_returning() {
+    let mut compile_time_library = Library::new();
+    compile_time_library.add_function("stalling_lookup", || true);
+    let result = Compiler::from_test_source("Before\n<<if stalling_lookup()>>\nFound\n<<endif>>")
+        .extend_library(compile_time_library)
+        .compile()
+        .unwrap();
+    let mut dialogue = TestBase::new()
+        .extend_library(|library| {
+            library.add_function("stalling_lookup", || {
+                thread::sleep(Duration::from_millis(20));
+                true
+            });
+        })
+        .with_compilation(result)
+        .dialogue;
+    dialogue.set_function_timeout(Duration::from_millis(1));
+    dialogue.set_node("Start").unwrap();
+
+    assert_eq!(vec!["Before"], next_lines(&mut dialogue));
+    let error = dialogue.continue_().unwrap_err();
+    assert!(matches!(
+        error,
+        DialogueError::FunctionTimeoutSuspected {
+            function_name,
+            elapsed,
+            timeout,
+            line: Some(4),
+            ..
+        } if function_name == "stalling_lookup" && timeout == Duration::from_millis(1) && elapsed > timeout
+    ));
+    // The function did return, so its value is used
+    assert_eq!(vec!["Found"], next_lines(&mut dialogue));
+}
+
+#[test]
+fn test_deferred_function_resumes_inside_condition() {
+    let mut library = Library::new();
+    library.add_deferred_function("lookup_price", |_item: String| -> f32 {
+        unreachable!("Deferred functions are completed by the game")
+    });
+    let source = "<<if lookup_price(\"sword\") + 1 >= 11>>\n\
+                  Affordable\n\
+                  <<else>>\n\
+                  Too expensive\n\
+                  <<endif>>\n";
+    let result = Compiler::from_test_source(source)
+        .extend_library(library.clone())
+        .compile()
+        .unwrap();
+    let mut dialogue = TestBase::new()
+        .extend_library(|dialogue_library| dialogue_library.import(library.clone()))
+        .with_compilation(result)
+        .dialogue;
+    dialogue.set_node("Start").unwrap();
+
+    let call = dialogue
+        .continue_()
+        .unwrap()
+        .into_iter()
+        .find_map(|event| match event {
+            DialogueEvent::DeferredFunctionCall(call) => Some(call),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!("lookup_price", call.function_name);
+    assert_eq!(vec![YarnValue::from("sword")], call.parameters);
+    assert!(dialogue.is_waiting_for_deferred_function());
+    assert!(matches!(
+        dialogue.continue_().unwrap_err(),
+        DialogueError::ContinueOnDeferredFunctionError
+    ));
+    assert!(matches!(
+        dialogue
+            .complete_function(FunctionCallToken(call.token.0 + 1), 10.0)
+            .unwrap_err(),
+        DialogueError::UnexpectedFunctionCompletion { .. }
+    ));
+    assert!(matches!(
+        dialogue.complete_function(call.token, "ten").unwrap_err(),
+        DialogueError::DeferredFunctionReturnMismatch { .. }
+    ));
+
+    dialogue.complete_function(call.token, 10.0).unwrap();
+    assert_eq!(vec!["Affordable"], next_lines(&mut dialogue));
+}
+
+#[test]
+fn test_lines_carry_raw_text_and_substitutions() {
+    let source = "<<declare $gold = 10>>\n\
+                  <<declare $name = \"Alice\">>\n\
+                  You have {$gold} gold, {$name}.\n";
+    let compilation = Compiler::from_test_source(source).compile().unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_node("Start").unwrap();
+
+    let line = next_line(&mut dialogue);
+    assert_eq!("You have {0} gold, {1}.", line.raw_text);
+    assert_eq!(
+        vec![YarnValue::from(10), YarnValue::from("Alice")],
+        line.substitutions
+    );
+    assert_eq!("You have 10 gold, Alice.", line.text);
+    assert_eq!(line.text, line.expand_substitutions());
+}
+
+#[test]
+fn test_lines_carry_metadata_without_line_id() {
+    let source = "Hello there! #emotion:happy #line:greeting #shout\n\
+                  How are you?\n\
+                  -> Fine! #shy\n";
+    let compilation = Compiler::from_test_source(source).compile().unwrap();
+    let metadata = compilation
+        .string_table
+        .iter()
+        .map(|(line_id, string_info)| (line_id.clone(), string_info.metadata.clone()))
+        .collect();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue
+        .add_line_metadata(metadata)
+        .set_node("Start")
+        .unwrap();
+
+    let line = next_line(&mut dialogue);
+    assert_eq!(LineId::from("line:greeting"), line.id);
+    assert_eq!(["emotion:happy", "shout"], line.metadata());
+    // The compiler marks lines that are followed by options
+    assert_eq!(["lastline"], next_line(&mut dialogue).metadata());
+    let options = next_options(&mut dialogue);
+    assert_eq!(["shy"], options[0].line.metadata());
+}
+
+#[test]
+fn test_options_carry_destination_and_metadata() {
+    let source = "title: Town.Square\n---\n\
+                  -> Go shopping #style:shop\n    Let's see.\n    <<jump Shop>>\n\
+                  -> Leave town #analytics:leave #line:leave\n    <<jump Road>>\n\
+                  -> Stay\n    <<if true>>\n        <<jump Shop>>\n    <<endif>>\n\
+                  Still here.\n===\n\
+                  title: Town.Shop\n---\nWelcome!\n===\n\
+                  title: Road\n---\nOn the road.\n===\n";
+    let compilation = Compiler::new()
+        .add_file(File {
+            file_name: "town.yarn".to_string(),
+            source: source.to_string(),
+        })
+        .compile()
+        .unwrap();
+    let metadata = compilation
+        .string_table
+        .iter()
+        .map(|(line_id, string_info)| (line_id.clone(), string_info.metadata.clone()))
+        .collect();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue
+        .add_line_metadata(metadata)
+        .set_node("Town.Square")
+        .unwrap();
+
+    let options = next_options(&mut dialogue);
+    // Relative destinations are resolved within the namespace of the node
+    assert_eq!(Some("Town.Shop"), options[0].destination.as_deref());
+    assert_eq!(["style:shop"], options[0].metadata());
+    assert_eq!(Some("Road"), options[1].destination.as_deref());
+    assert_eq!(["analytics:leave"], options[1].metadata());
+    // A conditional jump doesn't make the option jump anywhere for sure
+    assert_eq!(None, options[2].destination);
+    assert!(options[2].metadata().is_empty());
+}
+
+#[test]
+fn test_text_provider_receives_substitutions() {
+    let source = "<<declare $gold = 10>>\n\
+                  <<declare $name = \"Alice\">>\n\
+                  You have {$gold} gold, {$name}.\n";
+    let compilation = Compiler::from_test_source(source).compile().unwrap();
+    let mut test_base = TestBase::new().with_compilation(compilation);
+    let received_substitutions = Arc::new(Mutex::new(Vec::new()));
+    // A translation that reorders the placeholders
+    test_base.string_table.replace(RecordingTextProvider {
+        text: "{1}, du hast {0} Gold.".to_owned(),
+        received_substitutions: received_substitutions.clone(),
+    });
+    let mut dialogue = test_base.dialogue;
+    dialogue.set_node("Start").unwrap();
+
+    let line = next_line(&mut dialogue);
+    assert_eq!("Alice, du hast 10 Gold.", line.text);
+    assert_eq!(
+        vec![vec![YarnValue::from(10), YarnValue::from("Alice")]],
+        *received_substitutions.lock().unwrap()
+    );
+}
+
+#[derive(Debug, Clone)]
+struct RecordingTextProvider {
+    text: String,
+    received_substitutions: Arc<Mutex<Vec<Vec<YarnValue>>>>,
+}
+
+impl TextProvider for RecordingTextProvider {
+    fn clone_shallow(&self) -> Box<dyn TextProvider> {
+        Box::new(self.clone())
+    }
+
+    fn accept_line_hints(&mut self, _line_ids: &[LineId]) {}
+
+    fn get_text(&self, _id: &LineId) -> Option<String> {
+        Some(self.text.clone())
+    }
+
+    fn get_text_with_substitutions(
+        &self,
+        id: &LineId,
+        substitutions: &[YarnValue],
+    ) -> Option<String> {
+        self.received_substitutions
+            .lock()
+            .unwrap()
+            .push(substitutions.to_vec());
+        self.get_text(id)
+    }
+
+    fn set_language(&mut self, _language: Option<Language>) {}
+
+    fn get_language(&self) -> Option<Language> {
+        None
+    }
+
+    fn are_lines_available(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+fn next_line(dialogue: &mut Dialogue) -> Line {
+    dialogue
+        .continue_()
+        .unwrap()
+        .into_iter()
+        .find_map(|event| match event {
+            DialogueEvent::Line(line) => Some(line),
+            _ => None,
+        })
+        .unwrap()
+}
+
+fn next_lines(dialogue: &mut Dialogue) -> Vec<String> {
+    dialogue
+        .continue_()
+        .unwrap()
+        .into_iter()
+        .filter_map(|event| match event {
+            DialogueEvent::Line(line) => Some(line.text),
+            _ => None,
+        })
+        .collect()
+}
+
+fn next_options(dialogue: &mut Dialogue) -> Vec<DialogueOption> {
+    dialogue
+        .continue_()
+        .unwrap()
+        .into_iter()
+        .find_map(|event| match event {
+            DialogueEvent::Options(options) => Some(options),
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[test]
+fn test_plural_markers_with_interpolated_values_in_english() {
+    for (count, expected) in [(1.0, "You have 1 item."), (2.0, "You have 2 items.")] {
+        let text = plural_line(
+            count,
+            "en",
+            "You have {$count} [plural value={$count} one=\"item\" other=\"items\"/].",
+        );
+        assert_eq!(expected, text);
+    }
+}
+
+#[test]
+fn test_plural_markers_with_interpolated_values_in_languages_with_more_plural_cases() {
+    let polish_line = "Masz {$count} [plural value={$count} one=\"jabłko\" few=\"jabłka\" many=\"jabłek\" other=\"jabłka\"/].";
+    for (count, expected) in [
+        (1.0, "Masz 1 jabłko."),
+        (3.0, "Masz 3 jabłka."),
+        (5.0, "Masz 5 jabłek."),
+        (22.0, "Masz 22 jabłka."),
+        (1.5, "Masz 1,5 jabłka."),
+    ] {
+        assert_eq!(expected, plural_line(count, "pl", polish_line));
+    }
+
+    let arabic_line = "[plural value={$count} zero=\"zero\" one=\"one\" two=\"two\" few=\"few\" many=\"many\" other=\"other\"/]";
+    for (count, expected) in [
+        (0.0, "zero"),
+        (1.0, "one"),
+        (2.0, "two"),
+        (3.0, "few"),
+        (11.0, "many"),
+        (100.0, "other"),
+    ] {
+        assert_eq!(expected, plural_line(count, "ar", arabic_line));
+    }
+}
+
+#[test]
+fn test_interpolated_numbers_are_formatted_for_language() {
+    let line = "You have {$count} gold [plural value={$count} one=\"coin\" other=\"coins\"/].";
+    for (language, expected) in [
+        ("en-US", "You have 1,234.5 gold coins."),
+        ("de-DE", "You have 1.234,5 gold coins."),
+    ] {
+        assert_eq!(expected, plural_line(1234.5, language, line));
+    }
+}
+
+#[test]
+fn test_plural_markers_fall_back_to_other_case() {
+    let text = plural_line(
+        5.0,
+        "pl",
+        "Masz {$count} [plural value={$count} one=\"przedmiot\" other=\"przedmiotów\"/].",
+    );
+    assert_eq!("Masz 5 przedmiotów.", text);
+}
+
+#[test]
+fn test_plural_markers_fall_back_to_base_language() {
+    let line = "Masz {$count} [plural value={$count} one=\"przedmiot\" few=\"przedmioty\" other=\"przedmiotów\"/].";
+    assert_eq!(
+        "Masz 3 przedmioty.",
+        plural_line(3.0, "pl-PL-x-custom", line)
+    );
+}
+
+#[test]
+fn test_plural_markers_in_unsupported_locale_use_fallback_language() {
+    let mut dialogue = plural_dialogue(
+        2.0,
+        "tlh",
+        "[plural value={$count} one=\"wa'\" other=\"law'\"/]",
+    );
+    let error = dialogue.continue_().unwrap_err();
+    assert!(matches!(
+        error,
+        DialogueError::UnsupportedLocale { language_code: Some(language) } if language == Language::new("tlh")
+    ));
+
+    let mut dialogue = plural_dialogue(
+        1.0,
+        "tlh",
+        "[plural value={$count} one=\"wa'\" other=\"law'\"/]",
+    );
+    dialogue.set_plural_fallback_language_code(Language::new("en"));
+    assert_eq!("wa'", next_line(&mut dialogue).text);
+}
+
+fn plural_line(count: f32, language: &str, line: &str) -> String {
+    let mut dialogue = plural_dialogue(count, language, line);
+    next_line(&mut dialogue).text
+}
+
+fn plural_dialogue(count: f32, language: &str, line: &str) -> Dialogue {
+    let source = format!("<<declare $count = {count}>>\n{line}\n");
+    let compilation = Compiler::from_test_source(&source).compile().unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_language_code(Language::from(language));
+    dialogue.set_node("Start").unwrap();
+    dialogue
+}
+
+fn compile_main_and_bark() -> Compilation {
+    let source = "title: Main\n---\n<<declare $count = 0>>\nMain 1\n<<set $count to $count + 1>>\nMain 2 {$count}\n===\n\
+                  title: Bark\n---\nBark 1\n<<set $count to $count + 10>>\nBark 2 {$count}\n===\n";
+    Compiler::new()
+        .add_file(File {
+            file_name: "barks.yarn".to_string(),
+            source: source.to_string(),
+        })
+        .compile()
+        .unwrap()
+}
+
+#[test]
+fn test_child_dialogues_run_independently() {
+    let mut main = TestBase::new()
+        .with_compilation(compile_main_and_bark())
+        .dialogue;
+    let mut bark = main.spawn_child();
+    main.set_node("Main").unwrap();
+    bark.set_node("Bark").unwrap();
+
+    assert_eq!(vec!["Main 1"], next_lines(&mut main));
+    assert_eq!(vec!["Bark 1"], next_lines(&mut bark));
+    assert_eq!(Some("Main".to_owned()), main.current_node());
+    assert_eq!(Some("Bark".to_owned()), bark.current_node());
+    assert_eq!(vec!["Main 2 1"], next_lines(&mut main));
+    // The variable storage is shared, so the bark sees the increment of the main conversation
+    assert_eq!(vec!["Bark 2 11"], next_lines(&mut bark));
+    assert!(next_lines(&mut main).is_empty());
+    assert!(!main.is_active());
+    assert!(bark.is_active());
+}
+
+#[test]
+fn test_child_dialogues_can_have_their_own_variables() {
+    let mut main = TestBase::new()
+        .with_compilation(compile_main_and_bark())
+        .dialogue;
+    let mut bark = main.spawn_child_with_variable_storage(Box::new(MemoryVariableStorage::new()));
+    main.set_node("Main").unwrap();
+    bark.set_node("Bark").unwrap();
+
+    assert_eq!(vec!["Main 1"], next_lines(&mut main));
+    assert_eq!(vec!["Bark 1"], next_lines(&mut bark));
+    assert_eq!(vec!["Main 2 1"], next_lines(&mut main));
+    assert_eq!(vec!["Bark 2 10"], next_lines(&mut bark));
+}
+
+#[test]
+fn test_replacing_the_program_does_not_propagate_to_children() {
+    let mut main = TestBase::new()
+        .with_compilation(compile_main_and_bark())
+        .dialogue;
+    let mut bark = main.spawn_child();
+    let other = Compiler::from_test_source("Other").compile().unwrap();
+    main.replace_program(other.program.unwrap());
+
+    assert!(!main.node_exists("Bark"));
+    assert!(bark.node_exists("Bark"));
+    bark.set_node("Bark").unwrap();
+    assert_eq!(vec!["Bark 1"], next_lines(&mut bark));
+}
+
+#[test]
+fn test_replaying_rng_trace_reproduces_run_with_different_seed() {
+    let compilation = compile_line_groups(4);
+    let mut recorded = TestBase::new()
+        .with_compilation(compilation.clone())
+        .dialogue;
+    recorded.set_random_seed(1).set_rng_trace_capacity(64);
+    let recorded_lines = run_to_end(&mut recorded);
+    let trace = recorded.take_rng_trace();
+    assert_eq!(8, trace.len());
+    assert!(trace.iter().all(|decision| {
+        decision.kind == RngDecisionKind::LineGroup
+            && decision.node_name == "Start"
+            && decision.candidate_count == 4
+    }));
+
+    let mut unreplayed = TestBase::new()
+        .with_compilation(compilation.clone())
+        .dialogue;
+    unreplayed.set_random_seed(2);
+    assert_ne!(recorded_lines, run_to_end(&mut unreplayed));
+
+    let mut replayed = TestBase::new().with_compilation(compilation).dialogue;
+    replayed.set_random_seed(2).set_rng_replay(trace);
+    assert_eq!(recorded_lines, run_to_end(&mut replayed));
+}
+
+#[test]
+fn test_replaying_rng_trace_against_changed_content_fails() {
+    let mut recorded = TestBase::new()
+        .with_compilation(compile_line_groups(4))
+        .dialogue;
+    recorded.set_rng_trace_capacity(64);
+    run_to_end(&mut recorded);
+    let trace = recorded.take_rng_trace();
+
+    let mut replayed = TestBase::new()
+        .with_compilation(compile_line_groups(5))
+        .dialogue;
+    replayed.set_rng_replay(trace).set_node("Start").unwrap();
+    let error = replayed.continue_().unwrap_err();
+
+    assert!(matches!(
+        error,
+        DialogueError::RngReplayMismatch {
+            kind: RngDecisionKind::LineGroup,
+            candidate_count: 5,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_rng_trace_keeps_only_the_most_recent_decisions() {
+    let mut dialogue = TestBase::new()
+        .with_compilation(compile_line_groups(4))
+        .dialogue;
+    dialogue.set_rng_trace_capacity(3);
+    run_to_end(&mut dialogue);
+
+    assert_eq!(3, dialogue.take_rng_trace().len());
+    assert!(dialogue.take_rng_trace().is_empty());
+}
+
+const RANDOM_FUNCTIONS: &str = "title: Start\n---\n<<declare $round = 0>>\n\
+                                <<set $round to $round + 1>>\n\
+                                Round {$round}: rolled {dice(6)}, drew {random_range(1, 100)}.\n\
+                                => Candidate A\n=> Candidate B\n=> Candidate C\n\
+                                -> Take {dice(20)}\n-> Leave {random_range(0.5, 2.5)}\n\
+                                <<if random() < 0.5>>\n    Heads\n<<else>>\n    Tails\n<<endif>>\n\
+                                <<save>>\n\
+                                <<if $round < 8>>\n    <<jump Start>>\n<<endif>>\n===\n";
+
+fn random_functions_dialogue() -> Dialogue {
+    let test_base = TestBase::new();
+    let compilation = Compiler::new()
+        .add_file(File {
+            file_name: "random.yarn".to_string(),
+            source: RANDOM_FUNCTIONS.to_string(),
+        })
+        .extend_library(test_base.dialogue.library().clone())
+        .compile()
+        .unwrap();
+    test_base.with_compilation(compilation).dialogue
+}
+
+/// Continues until the end of the dialogue or the next `<<save>>` command, always picking the first option.
+/// Returns the texts of all lines and options.
+fn run_until_save(dialogue: &mut Dialogue) -> Vec<String> {
+    let mut texts = Vec::new();
+    loop {
+        for event in dialogue.continue_().unwrap() {
+            match event {
+                DialogueEvent::Line(line) => texts.push(line.text),
+                DialogueEvent::Options(options) => {
+                    texts.extend(options.iter().map(|option| option.line.text.clone()));
+                    dialogue.set_selected_option(options[0].id).unwrap();
+                }
+                DialogueEvent::Command(command) if command.name == "save" => return texts,
+                _ => {}
+            }
+        }
+        if !dialogue.is_active() {
+            return texts;
+        }
+    }
+}
+
+fn run_choosing_first_options(dialogue: &mut Dialogue) -> Vec<String> {
+    let mut texts = Vec::new();
+    loop {
+        texts.extend(run_until_save(dialogue));
+        if !dialogue.is_active() {
+            return texts;
+        }
+    }
+}
+
+#[test]
+fn test_same_seed_produces_same_random_results() {
+    let run = |seed| {
+        let mut dialogue = random_functions_dialogue();
+        dialogue.set_random_seed(seed).set_node("Start").unwrap();
+        run_choosing_first_options(&mut dialogue)
+    };
+    let reference = run(42);
+
+    assert_eq!(reference, run(42));
+    assert_ne!(reference, run(43));
+}
+
+#[test]
+fn test_restoring_rng_state_continues_with_same_random_results() {
+    let mut dialogue = random_functions_dialogue();
+    dialogue.set_node("Start").unwrap();
+    for _ in 0..3 {
+        run_until_save(&mut dialogue);
+    }
+    let rng_state = dialogue.rng_state();
+    let variables = dialogue.variable_storage().variables();
+    let original_rest = run_choosing_first_options(&mut dialogue);
+
+    // Loading the game saved at the end of round 3
+    let mut loaded = random_functions_dialogue();
+    loaded.variable_storage_mut().extend(variables).unwrap();
+    loaded.set_rng_state(rng_state).set_node("Start").unwrap();
+
+    assert!(original_rest[0].starts_with("Round 4"));
+    assert_eq!(original_rest, run_choosing_first_options(&mut loaded));
+}
+
+#[test]
+fn test_replaying_rng_trace_reproduces_random_functions_with_different_seed() {
+    let mut recorded = random_functions_dialogue();
+    recorded
+        .set_random_seed(1)
+        .set_rng_trace_capacity(1024)
+        .set_node("Start")
+        .unwrap();
+    let recorded_texts = run_choosing_first_options(&mut recorded);
+    let trace = recorded.take_rng_trace();
+    // dice(6), random_range(1, 100), dice(20), random_range(0.5, 2.5) and random() in each of the 8 rounds
+    let function_draws: Vec<_> = trace
+        .iter()
+        .filter(|decision| decision.kind == RngDecisionKind::Function)
+        .collect();
+    assert_eq!(40, function_draws.len());
+    assert_eq!(6, function_draws[0].candidate_count);
+    assert_eq!("Start", function_draws[0].node_name);
+
+    let mut replayed = random_functions_dialogue();
+    replayed
+        .set_random_seed(2)
+        .set_rng_replay(trace)
+        .set_node("Start")
+        .unwrap();
+    assert_eq!(recorded_texts, run_choosing_first_options(&mut replayed));
+}
+
+#[test]
+fn test_replaying_dice_trace_with_different_sides_fails() {
+    let compile = |sides| {
+        Compiler::from_test_source(&format!("Rolled {{dice({sides})}}"))
+            .compile()
+            .unwrap()
+    };
+    let mut recorded = TestBase::new().with_compilation(compile(6)).dialogue;
+    recorded
+        .set_rng_trace_capacity(8)
+        .set_node("Start")
+        .unwrap();
+    next_lines(&mut recorded);
+    let trace = recorded.take_rng_trace();
+
+    let mut replayed = TestBase::new().with_compilation(compile(20)).dialogue;
+    replayed.set_rng_replay(trace).set_node("Start").unwrap();
+    let error = replayed.continue_().unwrap_err();
+
+    assert!(matches!(
+        error,
+        DialogueError::RngReplayMismatch {
+            kind: RngDecisionKind::Function,
+            candidate_count: 20,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_restoring_snapshot_rewinds_across_delivered_line() {
+    let compilation = Compiler::from_test_source(
+        "<<declare $gold = 0>>\nFirst {$gold}\n<<set $gold to 10>>\nSecond {$gold}\nThird\n",
+    )
+    .compile()
+    .unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_node("Start").unwrap();
+    assert_eq!(vec!["First 0"], next_lines(&mut dialogue));
+    let snapshot = dialogue.snapshot();
+
+    assert_eq!(vec!["Second 10"], next_lines(&mut dialogue));
+    dialogue.restore(snapshot).unwrap();
+
+    assert_eq!(Some(YarnValue::from(0)), dialogue.get_variable("$gold"));
+    assert_eq!(vec!["Second 10"], next_lines(&mut dialogue));
+    assert_eq!(vec!["Third"], next_lines(&mut dialogue));
+}
+
+#[test]
+fn test_restoring_snapshot_rewinds_across_options() {
+    let compilation = Compiler::from_test_source(
+        "<<declare $picked = \"nothing\">>\n\
+        -> A\n    <<set $picked to \"A\">>\n    Picked A\n\
+        -> B\n    <<set $picked to \"B\">>\n    Picked B\n\
+        After picking {$picked}\n",
+    )
+    .compile()
+    .unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_node("Start").unwrap();
+    let options = next_options(&mut dialogue);
+    let snapshot = dialogue.snapshot();
+    assert_eq!(options, snapshot.pending_options());
+
+    dialogue.set_selected_option(&options[0]).unwrap();
+    assert_eq!(vec!["Picked A"], next_lines(&mut dialogue));
+    assert_eq!(vec!["After picking A"], next_lines(&mut dialogue));
+
+    dialogue.restore(snapshot.clone()).unwrap();
+    assert!(dialogue.is_waiting_for_option_selection());
+    assert!(matches!(
+        dialogue.continue_(),
+        Err(DialogueError::ContinueOnOptionSelectionError)
+    ));
+    dialogue
+        .set_selected_option(&snapshot.pending_options()[1])
+        .unwrap();
+    assert_eq!(vec!["Picked B"], next_lines(&mut dialogue));
+    assert_eq!(vec!["After picking B"], next_lines(&mut dialogue));
+}
+
+#[test]
+fn test_restoring_snapshot_mid_detour_returns_to_callers() {
+    let run_rest = |dialogue: &mut Dialogue| {
+        let mut lines = Vec::new();
+        while dialogue.is_active() {
+            lines.extend(next_lines(dialogue));
+        }
+        lines
+    };
+    let mut dialogue = detour_dialogue(SELF_DETOUR);
+    dialogue.set_node("Start").unwrap();
+    for _ in 0..3 {
+        let _ = next_lines(&mut dialogue);
+    }
+    // Two invocations of Loop are waiting to be returned to, one is running
+    let snapshot = dialogue.snapshot();
+    let expected = vec!["Leaving 0", "Leaving 1", "Leaving 2", "Done 3"];
+    assert_eq!(expected, run_rest(&mut dialogue));
+
+    dialogue.restore(snapshot).unwrap();
+    assert_eq!(Some("Loop".to_owned()), dialogue.current_node());
+    assert_eq!(Some(0.0), visited_count(&dialogue, "Loop"));
+    assert_eq!(expected, run_rest(&mut dialogue));
+    assert_eq!(Some(3.0), visited_count(&dialogue, "Loop"));
+}
+
+#[test]
+fn test_restoring_snapshot_of_different_program_fails() {
+    let old = Compiler::from_test_source("<<declare $gold = 0>>\nOne\n<<set $gold to 5>>\nTwo\n")
+        .compile()
+        .unwrap();
+    let new = Compiler::from_test_source(
+        "<<declare $gold = 0>>\nOne\n<<set $gold to 5>>\nTwo, changed\n",
+    )
+    .compile()
+    .unwrap();
+    let mut dialogue = TestBase::new().with_compilation(old).dialogue;
+    dialogue.set_node("Start").unwrap();
+    let _ = next_lines(&mut dialogue);
+    let snapshot = dialogue.snapshot();
+
+    let mut new_dialogue = TestBase::new().with_compilation(new).dialogue;
+    new_dialogue.set_node("Start").unwrap();
+    let error = new_dialogue.restore(snapshot.clone()).unwrap_err();
+    let DialogueError::SnapshotProgramMismatch {
+        snapshot_checksum,
+        program_checksum,
+    } = &error
+    else {
+        panic!("Unexpected error: {error}");
+    };
+    assert_eq!(&snapshot.program_checksum(), snapshot_checksum);
+    assert_ne!(snapshot_checksum, program_checksum);
+    let message = error.to_string();
+    assert!(message.contains(&format!("{:#018x}", snapshot_checksum.unwrap())));
+    assert!(message.contains(&format!("{:#018x}", program_checksum.unwrap())));
+
+    // The dialogue is left as it was
+    assert_eq!(Some("Start".to_owned()), new_dialogue.current_node());
+    assert_eq!(vec!["One"], next_lines(&mut new_dialogue));
+}
+
+#[test]
+fn test_escaped_characters_are_delivered_literally() {
+    let source = r"<<declare $gold = 5>>
+You have {$gold} gold \{not $gold\} \[b\]not bold\[/b\] \<\< \# \\ \/\/ done
+-> Pick \{this\} \# one
+";
+    let compilation = Compiler::from_test_source(source).compile().unwrap();
+    let texts: Vec<_> = compilation
+        .string_table
+        .values()
+        .map(|string_info| string_info.text.as_str())
+        .collect();
+    // Escaped markup brackets are kept so that the markup parser does not treat them as markup
+    assert!(texts.contains(&r"You have {0} gold {not $gold} \[b\]not bold\[/b\] << # \ // done"));
+    assert!(texts.contains(&"Pick {this} # one"));
+
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_node("Start").unwrap();
+    assert_eq!(
+        vec![r"You have 5 gold {not $gold} [b]not bold[/b] << # \ // done"],
+        next_lines(&mut dialogue)
+    );
+    let options = next_options(&mut dialogue);
+    assert_eq!("Pick {this} # one", options[0].line.text);
+}
+
+#[test]
+fn test_smart_variable_is_computed_whenever_it_is_read() {
+    let source = "<<declare $gold = 50>>
+<<declare $is_rich = $gold > 100>>
+<<if $is_rich>>
+    Rich
+<<else>>
+    Poor
+<<endif>>
+<<set $gold to 150>>
+<<if $is_rich>>
+    Rich now
+<<endif>>
+";
+    let compilation = Compiler::from_test_source(source).compile().unwrap();
+    let declaration = compilation
+        .declarations
+        .iter()
+        .find(|declaration| declaration.name == "$is_rich")
+        .unwrap();
+    assert!(declaration.is_smart);
+    assert_eq!(Type::Boolean, declaration.r#type);
+    assert_eq!(None, declaration.default_value);
+
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    assert_eq!(
+        Some(YarnValue::from(false)),
+        dialogue.get_variable("$is_rich")
+    );
+    assert!(!dialogue
+        .node_names()
+        .unwrap()
+        .any(|name| name == "$is_rich"));
+    assert_eq!(vec!["Poor", "Rich now"], run_to_end(&mut dialogue));
+    assert_eq!(
+        Some(YarnValue::from(true)),
+        dialogue.get_variable("$is_rich")
+    );
+    // Reading it does not store anything
+    assert!(dialogue.variable_storage().get("$is_rich").is_err());
+
+    dialogue.set_variable("$gold", 10).unwrap();
+    assert_eq!(
+        Some(YarnValue::from(false)),
+        dialogue.get_variable("$is_rich")
+    );
+    assert!(matches!(
+        dialogue.set_variable("$is_rich", true),
+        Err(DialogueError::ReadOnlyVariable { .. })
+    ));
+}
+
+#[test]
+fn test_smart_variables_can_be_computed_from_each_other() {
+    let source = "<<declare $can_buy_castle = $is_rich and $has_land>>
+<<declare $is_rich = $gold > 100>>
+<<declare $gold = 500>>
+<<declare $has_land = true>>
+<<if $can_buy_castle>>
+    Buy the castle
+<<endif>>
+<<set $has_land to false>>
+<<if not $can_buy_castle>>
+    Buy some land first
+<<endif>>
+";
+    let compilation = Compiler::from_test_source(source).compile().unwrap();
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    assert_eq!(
+        vec!["Buy the castle", "Buy some land first"],
+        run_to_end(&mut dialogue)
+    );
+
+    dialogue.set_variable("$has_land", true).unwrap();
+    assert_eq!(
+        Some(YarnValue::from(true)),
+        dialogue.get_variable("$can_buy_castle")
+    );
+    dialogue.set_variable("$gold", 0).unwrap();
+    assert_eq!(
+        Some(YarnValue::from(false)),
+        dialogue.get_variable("$can_buy_castle")
+    );
+}
+
+/// Compiles 8 line groups with `candidate_count` equally salient candidates each.
+fn compile_line_groups(candidate_count: usize) -> Compilation {
+    let source: String = (0..8)
+        .map(|group| {
+            let candidates: String = (0..candidate_count)
+                .map(|candidate| format!("=> Group {group} candidate {candidate}\n"))
+                .collect();
+            format!("{candidates}After group {group}\n")
+        })
+        .collect();
+    Compiler::from_test_source(&source).compile().unwrap()
+}
+
+fn run_to_end(dialogue: &mut Dialogue) -> Vec<String> {
+    dialogue.set_node("Start").unwrap();
+    let mut lines = Vec::new();
+    loop {
+        lines.extend(next_lines(dialogue));
+        if !dialogue.is_active() {
+            return lines;
+        }
+    }
+}
@@ -3,6 +3,7 @@
 use crate::test_base::*;
 use test_base::prelude::*;
 use yarnspinner::compiler::*;
+use yarnspinner::core::{Position, Type};
 
 mod test_base;
 
@@ -54,6 +55,22 @@ fn test_empty_command() {
         .any(|d| d.message.contains("Command text expected")));
 }
 
+#[test]
+fn test_unterminated_enum_commands_at_end_of_file() {
+    for command in ["<<enum Col", "<<case Red", "<<endenum"] {
+        let result = Compiler::new()
+            .add_file(File {
+                file_name: "input".to_owned(),
+                source: format!("title: A\n---\n{command}"),
+            })
+            .compile()
+            .unwrap_err();
+        println!("{}", result);
+
+        assert!(!result.0.is_empty(), "{command}");
+    }
+}
+
 #[test]
 fn test_invalid_variable_name_in_set_or_declare() {
     let result = Compiler::from_test_source("\n<<set test = 1>>\n")
@@ -101,3 +118,405 @@ fn test_compiling_same_file_twice_fails() {
         .iter()
         .any(|d| d.message.contains("Duplicate line ID line:794945")));
 }
+
+#[test]
+fn test_duplicate_line_ids_in_one_file_name_both_lines() {
+    let source = "\
+title: Start
+---
+Hello. #line:greeting
+===
+title: Other
+---
+Goodbye. #line:greeting
+Hello. #line:greeting
+===
+";
+    let diagnostics = Compiler::new()
+        .add_file(File {
+            file_name: "story.yarn".to_owned(),
+            source: source.to_owned(),
+        })
+        .compile()
+        .unwrap_err()
+        .0;
+
+    let duplicates: Vec<_> = diagnostics
+        .iter()
+        .filter(|d| d.code == Some(DiagnosticCode::DuplicateLineId))
+        .collect();
+    assert_eq!(2, duplicates.len());
+    let line = |d: &Diagnostic| d.range.as_ref().unwrap().start.line;
+    assert_eq!((6, 7), (line(duplicates[0]), line(duplicates[1])));
+    assert_eq!(
+        "Duplicate line ID line:greeting: it is used for \"Hello.\" in \"story.yarn\" node Start line 3 \
+         and for \"Goodbye.\" in \"story.yarn\" node Other line 7",
+        duplicates[0].message
+    );
+    assert_eq!(
+        "Duplicate line ID line:greeting: the line \"Hello.\" in \"story.yarn\" node Other line 8 \
+         is a copy of the one in \"story.yarn\" node Start line 3. Give each copy its own line ID",
+        duplicates[1].message
+    );
+}
+
+#[test]
+fn test_duplicate_line_ids_across_files_name_both_files() {
+    let diagnostics = Compiler::new()
+        .add_file(File {
+            file_name: "first.yarn".to_owned(),
+            source: "title: Start\n---\nHello. #line:greeting\n===\n".to_owned(),
+        })
+        .add_file(File {
+            file_name: "second.yarn".to_owned(),
+            source: "title: Other\n---\nHi. #line:greeting\n===\n".to_owned(),
+        })
+        .compile()
+        .unwrap_err()
+        .0;
+
+    let duplicate = diagnostics
+        .iter()
+        .find(|d| d.code == Some(DiagnosticCode::DuplicateLineId))
+        .unwrap();
+    assert_eq!(Some("second.yarn"), duplicate.file_name.as_deref());
+    assert!(duplicate
+        .message
+        .contains("\"first.yarn\" node Start line 3"));
+    assert!(duplicate
+        .message
+        .contains("\"second.yarn\" node Other line 3"));
+}
+
+#[test]
+fn test_jump_to_missing_node_warns() {
+    let result = Compiler::from_test_source("<<jump Strat>>")
+        .compile()
+        .unwrap();
+
+    let warning = result
+        .warnings
+        .iter()
+        .find(|d| d.message == "Jump target 'Strat' does not exist")
+        .unwrap();
+    assert_eq!(DiagnosticSeverity::Warning, warning.severity);
+    assert_eq!(2, warning.range.as_ref().unwrap().start.line);
+}
+
+#[test]
+fn test_unknown_escape_sequence_points_at_backslash() {
+    let result = Compiler::from_test_source(r"Keep calm \and carry on.").compile();
+
+    let diagnostics = result.unwrap_err().0;
+    assert_eq!(1, diagnostics.len(), "{diagnostics:#?}");
+    let error = &diagnostics[0];
+    assert_eq!(Some(DiagnosticCode::InvalidEscapeSequence), error.code);
+    assert_eq!("a", error.arguments["character"]);
+    assert_eq!(
+        Position {
+            line: 2,
+            character: 10,
+        }..Position {
+            line: 2,
+            character: 12,
+        },
+        error.range.clone().unwrap()
+    );
+}
+
+#[test]
+fn test_jump_to_node_in_other_file_does_not_warn() {
+    let result = Compiler::new()
+        .add_file(File {
+            file_name: "first.yarn".to_string(),
+            source: "title: First\n---\n<<jump Second>>\n===\n".to_string(),
+        })
+        .add_file(File {
+            file_name: "second.yarn".to_string(),
+            source: "title: Second\n---\n<<jump {\"First\"}>>\n===\n".to_string(),
+        })
+        .compile()
+        .unwrap();
+
+    assert!(result.warnings.is_empty());
+}
+
+#[test]
+fn test_diagnostics_have_codes_and_ranges() {
+    let result = Compiler::from_test_source("<<set $x = 1>>\n<<set $x = \"one\">>")
+        .compile()
+        .unwrap_err();
+    let diagnostic = result
+        .0
+        .iter()
+        .find(|d| d.code == Some(DiagnosticCode::TypeMismatch))
+        .unwrap();
+    assert_eq!("YS0010", diagnostic.code.unwrap().as_str());
+    assert_eq!(DiagnosticSeverity::Error, diagnostic.severity);
+    assert_eq!(
+        Some(
+            Position {
+                line: 3,
+                character: 0,
+            }..Position {
+                line: 3,
+                character: 18,
+            }
+        ),
+        diagnostic.range
+    );
+
+    let result = Compiler::from_test_source("<<if true>>")
+        .compile()
+        .unwrap_err();
+    assert!(result
+        .0
+        .iter()
+        .all(|d| d.code == Some(DiagnosticCode::SyntaxError)));
+
+    let result = Compiler::new()
+        .add_file(File {
+            file_name: "a.yarn".to_string(),
+            source: create_test_node("A"),
+        })
+        .add_file(File {
+            file_name: "b.yarn".to_string(),
+            source: create_test_node("B"),
+        })
+        .compile()
+        .unwrap_err();
+    assert!(result
+        .0
+        .iter()
+        .any(|d| d.code == Some(DiagnosticCode::DuplicateNodeName)));
+}
+
+#[test]
+fn test_analysis_returns_errors_and_warnings() {
+    let compilation = Compiler::from_test_source(
+        "<<declare $gold = 0>>\n\
+        <<set $gold = \"lots\">> // error: wrong type\n\
+        Gold: {$gold}\n\
+        <<jump Nowhere>> // warning: missing jump target",
+    )
+    .with_compilation_type(CompilationType::Analyze)
+    .compile()
+    .unwrap();
+
+    println!("{:#?}", compilation.warnings);
+    assert!(compilation.program.is_none());
+    assert!(compilation.warnings.iter().any(|d| {
+        d.severity == DiagnosticSeverity::Error && d.code == Some(DiagnosticCode::TypeMismatch)
+    }));
+    assert!(compilation.warnings.iter().any(|d| {
+        d.severity == DiagnosticSeverity::Warning
+            && d.code == Some(DiagnosticCode::MissingJumpTarget)
+    }));
+    assert!(compilation
+        .declarations
+        .iter()
+        .any(|declaration| declaration.name == "$gold"));
+    assert_eq!(1, compilation.string_table.len());
+}
+
+#[test]
+fn test_syntax_errors_do_not_cascade_into_other_nodes() {
+    let result = Compiler::new()
+        .add_file(File {
+            file_name: "broken.yarn".to_string(),
+            source: "title: First\n---\n<<if true>>\nOne\n===\n\
+                title: Second\n---\n<<set $x = >>\n===\n\
+                title: Third\n---\nThree\n===\n"
+                .to_string(),
+        })
+        .compile()
+        .unwrap_err();
+
+    println!("{}", result);
+    let error_lines: Vec<_> = result
+        .0
+        .iter()
+        .map(|d| {
+            assert_eq!(Some("broken.yarn"), d.file_name.as_deref());
+            d.range.as_ref().unwrap().start.line
+        })
+        .collect();
+    assert!(result.0.iter().any(|d| d
+        .message
+        .contains("Expected an <<endif>> to match the <<if>> statement on line 3")));
+    assert!(result.0.iter().any(|d| d
+        .message
+        .contains("Unexpected \">>\" while reading an expression")));
+    // Only the lines of the broken statements are reported, nothing in the healthy third node
+    assert!(error_lines.iter().all(|&line| line == 4 || line == 7));
+}
+
+#[test]
+fn test_syntax_errors_do_not_prevent_checking_other_nodes() {
+    let result = Compiler::new()
+        .add_file(File {
+            file_name: "broken.yarn".to_string(),
+            source: "title: A\n---\n<<if $x>>\nOne\n===\n\
+                title: B\n---\n<<set $y = 1 + true>>\n===\n"
+                .to_string(),
+        })
+        .with_compilation_type(CompilationType::Analyze)
+        .compile()
+        .unwrap();
+
+    println!("{:#?}", result.warnings);
+    assert!(result.warnings.iter().any(|d| d
+        .message
+        .contains("Expected an <<endif>> to match the <<if>> statement on line 3")));
+    assert!(result.warnings.iter().any(|d| {
+        d.code == Some(DiagnosticCode::TypeMismatch) && d.range.as_ref().unwrap().start.line == 7
+    }));
+}
+
+#[test]
+fn test_syntax_errors_per_node_are_limited() {
+    let broken_headers: String = (0..30).map(|i| format!("key{i}\n")).collect();
+    let result = Compiler::new()
+        .add_file(File {
+            file_name: "headers.yarn".to_string(),
+            source: format!("title: Start\n{broken_headers}---\nHello\n===\n"),
+        })
+        .compile()
+        .unwrap_err();
+
+    assert_eq!(11, result.0.len(), "{result}");
+    assert_eq!(
+        "Too many syntax errors in this node, further errors are not reported",
+        result.0.last().unwrap().message
+    );
+}
+
+#[test]
+fn test_malformed_input_never_panics() {
+    const SOURCE: &str = "title: Start\n---\n<<if $a>>\nHello\n<<else>>\nWorld {$x\n\
+        -> Option <<if\n<<set $b = >>\n===\ntitle: Second\n---\nFine line\n<<jump Start>>\n===\n";
+    let truncations = SOURCE
+        .char_indices()
+        .map(|(index, _)| SOURCE[..index].to_owned());
+    let garbage = [
+        "\n",
+        "===",
+        "===\n===\n",
+        "\n===",
+        "---",
+        "title:\n---\n===",
+        "title: A\ntitle: B\n---\nx\n===\n---\n---\n===",
+        "garbage\n---\nbody\n===\nheader: x\nmore garbage <<if>>\n---\n===\n",
+        "title: A\n---\n<<endif>>\n===\n<<if>>\ntitle: B\n---\n",
+    ]
+    .map(str::to_owned);
+
+    for source in truncations.chain(garbage) {
+        let result = std::panic::catch_unwind(|| {
+            Compiler::new()
+                .add_file(File {
+                    file_name: "fuzz.yarn".to_string(),
+                    source: source.clone(),
+                })
+                .compile()
+        });
+        let Ok(result) = result else {
+            panic!("Compiling {source:?} panicked");
+        };
+        if let Err(error) = result {
+            assert!(!error.0.is_empty(), "No diagnostics for {source:?}");
+        }
+    }
+}
+
+#[test]
+fn test_assigning_smart_variable_fails() {
+    let result = Compiler::from_test_source(
+        "<<declare $gold = 0>>\n<<declare $is_rich = $gold > 100>>\n<<set $is_rich to true>>",
+    )
+    .compile()
+    .unwrap_err();
+
+    println!("{}", result);
+    let error = result
+        .0
+        .iter()
+        .find(|d| d.code == Some(DiagnosticCode::ReadOnlyVariable))
+        .unwrap();
+    assert_eq!("$is_rich", error.arguments["variable"]);
+    assert_eq!(4, error.range.as_ref().unwrap().start.line);
+}
+
+#[test]
+fn test_assigning_read_only_variable_fails() {
+    let result = Compiler::from_test_source("<<set $player_name to \"Nobody\">>")
+        .declare_variable(
+            Declaration::new("$player_name", Type::String)
+                .with_default_value("Ada")
+                .with_read_only(),
+        )
+        .compile()
+        .unwrap_err();
+
+    println!("{}", result);
+    let error = result
+        .0
+        .iter()
+        .find(|d| d.code == Some(DiagnosticCode::ReadOnlyVariable))
+        .unwrap();
+    assert_eq!("$player_name", error.arguments["variable"]);
+    assert_eq!(
+        "$player_name is read-only, so it can't be assigned a value",
+        error.message
+    );
+    assert_eq!(2, error.range.as_ref().unwrap().start.line);
+}
+
+#[test]
+fn test_assigning_regular_variable_next_to_read_only_one_succeeds() {
+    let result = Compiler::from_test_source(
+        "<<declare $gold = 0>>\n<<declare $is_rich = $gold > 100>>\n<<set $gold to 200>>\n<<set $title to $player_name>>",
+    )
+    .declare_variable(
+        Declaration::new("$player_name", Type::String)
+            .with_default_value("Ada")
+            .with_read_only(),
+    )
+    .declare_variable(Declaration::new("$title", Type::String).with_default_value(""))
+    .compile();
+
+    assert!(result.is_ok(), "{}", result.unwrap_err());
+}
+
+#[test]
+fn test_smart_variable_computed_from_itself_fails() {
+    let result = Compiler::from_test_source(
+        "<<declare $a = $b and true>>\n<<declare $b = $a or false>>\n<<declare $c = $c or true>>",
+    )
+    .compile()
+    .unwrap_err();
+
+    println!("{}", result);
+    let mut cyclic_variables: Vec<_> = result
+        .0
+        .iter()
+        .filter(|d| d.code == Some(DiagnosticCode::SmartVariableCycle))
+        .map(|d| d.arguments["variable"].as_str())
+        .collect();
+    cyclic_variables.sort();
+    assert_eq!(vec!["$a", "$b", "$c"], cyclic_variables);
+}
+
+#[test]
+fn test_analysis_reports_smart_variable_computed_from_itself() {
+    let compilation = Compiler::from_test_source("<<declare $c = $c or true>>")
+        .with_compilation_type(CompilationType::Analyze)
+        .compile()
+        .unwrap();
+
+    assert!(compilation
+        .warnings
+        .iter()
+        .any(|d| d.code == Some(DiagnosticCode::SmartVariableCycle)
+            && d.arguments["variable"] == "$c"));
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use test_base::prelude::*;
+use yarnspinner::compiler::*;
+use yarnspinner::core::*;
+
+mod test_base;
+
+const ORIGINAL_SOURCE: &str = "title: Start
+---
+Hello there.
+How are you?
+-> Fine
+-> Not so good
+Goodbye.
+===
+";
+
+#[test]
+fn test_editing_a_line_keeps_other_implicit_line_ids() {
+    let original = implicit_line_ids(ORIGINAL_SOURCE);
+    assert_eq!(5, original.len());
+
+    let edited = implicit_line_ids(
+        &ORIGINAL_SOURCE.replace("Hello there.", "A new line above.\nHello there, friend."),
+    );
+
+    assert_eq!(6, edited.len());
+    for text in ["How are you?", "Fine", "Not so good", "Goodbye."] {
+        assert_eq!(original[text], edited[text], "ID of \"{text}\" changed");
+    }
+    assert!(!edited.contains_key("Hello there."));
+    assert!(!original
+        .values()
+        .any(|line_id| line_id == &edited["Hello there, friend."]));
+}
+
+#[test]
+fn test_identical_lines_get_distinct_stable_implicit_line_ids() {
+    let compile = |source: &str| {
+        let mut string_table: Vec<_> = Compiler::from_test_nodes(source)
+            .compile()
+            .unwrap()
+            .string_table
+            .into_iter()
+            .collect();
+        string_table.sort_by_key(|(_, string_info)| string_info.line_number);
+        string_table
+            .into_iter()
+            .map(|(line_id, string_info)| (string_info.text, line_id))
+            .collect::<Vec<_>>()
+    };
+    let original = compile("title: Start\n---\nHmm.\nWell...\nHmm.\n===\n");
+    let edited = compile("title: Start\n---\nOkay.\nHmm.\nWell...\nHmm.\n===\n");
+
+    assert_ne!(original[0].1, original[2].1);
+    assert_eq!(&original[..], &edited[1..]);
+    assert_eq!(
+        original,
+        compile("title: Start\n---\nHmm.\nWell...\nHmm.\n===\n")
+    );
+}
+
+#[test]
+fn test_implicit_line_ids_are_scoped_to_file_and_node() {
+    let string_table = Compiler::from_test_nodes(
+        "title: Start\n---\nHello.\n===\ntitle: Other\n---\nHello.\n===\n",
+    )
+    .compile()
+    .unwrap()
+    .string_table;
+
+    let mut line_ids: Vec<_> = string_table
+        .keys()
+        .map(|line_id| line_id.0.as_str())
+        .collect();
+    line_ids.sort();
+    assert_eq!(2, line_ids.len());
+    assert!(line_ids[0].starts_with("line:story.yarn-Other-"));
+    assert!(line_ids[1].starts_with("line:story.yarn-Start-"));
+}
+
+#[test]
+fn test_untagged_lines_fail_when_explicit_line_ids_are_required() {
+    let source = "title: Start\n---\nHello. #line:hello\nHow are you?\n-> Fine #line:fine\n-> Not so good\n===\n";
+    let error = Compiler::new()
+        .add_file(File {
+            file_name: "story.yarn".to_owned(),
+            source: source.to_owned(),
+        })
+        .with_explicit_line_ids_required(true)
+        .compile()
+        .unwrap_err();
+
+    let untagged: Vec<_> = error
+        .0
+        .iter()
+        .map(|diagnostic| {
+            assert_eq!(Some(DiagnosticCode::MissingLineId), diagnostic.code);
+            assert_eq!(DiagnosticSeverity::Error, diagnostic.severity);
+            diagnostic.arguments["text"].as_str()
+        })
+        .collect();
+    assert_eq!(vec!["How are you?", "Not so good"], untagged);
+}
+
+#[test]
+fn test_tagged_lines_compile_when_explicit_line_ids_are_required() {
+    let compilation = Compiler::new()
+        .add_file(File {
+            file_name: "story.yarn".to_owned(),
+            source: "title: Start\n---\nHello. #line:hello\n-> Bye #line:bye\n===\n".to_owned(),
+        })
+        .with_explicit_line_ids_required(true)
+        .compile()
+        .unwrap();
+
+    assert!(!compilation.contains_implicit_string_tags);
+    assert_eq!(2, compilation.string_table.len());
+}
+
+fn implicit_line_ids(source: &str) -> HashMap<String, LineId> {
+    Compiler::from_test_nodes(source)
+        .compile()
+        .unwrap()
+        .string_table
+        .into_iter()
+        .filter(|(_, string_info)| string_info.is_implicit_tag)
+        .map(|(line_id, string_info)| (string_info.text, line_id))
+        .collect()
+}
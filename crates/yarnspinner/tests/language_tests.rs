@@ -47,7 +47,6 @@ fn can_compile_space_demo() {
 }
 
 #[test]
-#[should_panic]
 fn test_merging_nodes() {
     let test_base = TestBase::default();
     let sally_path = space_demo_scripts_path().join("Sally.yarn");
@@ -66,10 +65,14 @@ fn test_merging_nodes() {
         .unwrap();
 
     // Loading code with the same contents should throw
-    let _combined_not_working = Program::combine(vec![
+    let combined_not_working = Program::combine(vec![
         result_sally.program.unwrap(),
         result_sally_and_ship.program.unwrap(),
     ]);
+    assert!(matches!(
+        combined_not_working,
+        Err(ProgramCombineError::DuplicateNode(_))
+    ));
 }
 
 #[test]
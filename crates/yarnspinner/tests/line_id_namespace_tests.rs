@@ -0,0 +1,157 @@
+use test_base::prelude::*;
+use yarnspinner::compiler::*;
+
+mod test_base;
+
+#[test]
+fn test_merging_compilations_with_disjoint_namespaces_succeeds() {
+    let story = compile_in_namespace("story.yarn", STORY_SOURCE, "story").unwrap();
+    let liveops = compile_in_namespace("liveops.yarn", LIVEOPS_SOURCE, "liveops").unwrap();
+
+    let merged = Compilation::merge([story, liveops]).unwrap();
+
+    let program = merged.program.unwrap();
+    assert!(program.nodes.contains_key("Story"));
+    assert!(program.nodes.contains_key("Event"));
+    assert_eq!(4, merged.string_table.len());
+    let intro = &merged.string_table[&"line:story.intro_01".into()];
+    assert_eq!(Some("story"), intro.line_id_namespace.as_deref());
+    let (implicit_id, implicit) = merged
+        .string_table
+        .iter()
+        .find(|(_, string_info)| string_info.is_implicit_tag)
+        .unwrap();
+    assert!(implicit_id
+        .0
+        .starts_with("line:liveops.liveops.yarn-Event-"));
+    assert_eq!(Some("liveops"), implicit.line_id_namespace.as_deref());
+}
+
+#[test]
+fn test_explicit_line_id_outside_of_namespace_is_flagged() {
+    let source = "title: Event\n---\nWelcome to the event! #line:intro_01\n===\n";
+
+    let result = compile_in_namespace("liveops.yarn", source, "liveops");
+
+    let error = result.unwrap_err();
+    assert_eq!(1, error.0.len());
+    let diagnostic = &error.0[0];
+    assert_eq!(
+        Some(DiagnosticCode::LineIdNamespaceViolation),
+        diagnostic.code
+    );
+    assert_eq!(DiagnosticSeverity::Error, diagnostic.severity);
+    assert!(diagnostic.message.contains("line:intro_01"));
+
+    let compilation = Compiler::new()
+        .add_file(File {
+            file_name: "liveops.yarn".to_owned(),
+            source: source.to_owned(),
+        })
+        .with_line_id_namespace("liveops")
+        .with_line_id_namespace_violation_severity(DiagnosticSeverity::Warning)
+        .compile()
+        .unwrap();
+    assert_eq!(1, compilation.warnings.len());
+    assert_eq!(
+        Some(DiagnosticCode::LineIdNamespaceViolation),
+        compilation.warnings[0].code
+    );
+}
+
+#[test]
+fn test_cross_namespace_duplicates_are_impossible() {
+    // The same explicit tag cannot be valid in two namespaces...
+    let source = "title: Start\n---\nHello! #line:story.intro_01\n===\n";
+    assert!(compile_in_namespace("story.yarn", source, "story").is_ok());
+    assert!(compile_in_namespace("liveops.yarn", source, "liveops").is_err());
+
+    // ...and the same file produces different implicit IDs in different namespaces.
+    let untagged = "title: Start\n---\nHello!\n===\n";
+    let story = compile_in_namespace("start.yarn", untagged, "story").unwrap();
+    let liveops = compile_in_namespace("start.yarn", untagged, "liveops").unwrap();
+    assert!(story
+        .string_table
+        .keys()
+        .all(|line_id| !liveops.string_table.contains_key(line_id)));
+}
+
+#[test]
+fn test_merging_compilations_that_share_a_namespace_lists_offenders() {
+    let first = compile_in_namespace("first.yarn", STORY_SOURCE, "story").unwrap();
+    let second = compile_in_namespace(
+        "second.yarn",
+        "title: Epilogue\n---\nThe end. #line:story.intro_01\n===\n",
+        "story",
+    )
+    .unwrap();
+
+    let error = Compilation::merge([first, second]).unwrap_err();
+
+    assert_eq!(2, error.0.len());
+    assert_eq!(
+        Some(DiagnosticCode::LineIdNamespaceViolation),
+        error.0[0].code
+    );
+    assert!(error.0[0].message.contains("\"story\""));
+    assert!(error.0[0].message.contains("0, 1"));
+    assert_eq!(Some(DiagnosticCode::DuplicateLineId), error.0[1].code);
+    assert!(error.0[1].message.contains("line:story.intro_01"));
+    assert!(error.0[1]
+        .message
+        .contains("\"first.yarn\" line 3 (compilation 0)"));
+    assert!(error.0[1]
+        .message
+        .contains("\"second.yarn\" line 3 (compilation 1)"));
+}
+
+#[test]
+fn test_exporting_strings_can_be_filtered_by_namespace() {
+    let story = compile_in_namespace("story.yarn", STORY_SOURCE, "story").unwrap();
+    let liveops = compile_in_namespace("liveops.yarn", LIVEOPS_SOURCE, "liveops").unwrap();
+    let merged = Compilation::merge([story, liveops]).unwrap();
+
+    let csv = export_strings_csv(&merged, "en-US", Some("story"));
+
+    let rows: Vec<_> = csv.lines().collect();
+    assert_eq!(3, rows.len());
+    assert_eq!(
+        "language,id,text,file,node,line_number,lock,comment",
+        rows[0]
+    );
+    assert!(rows[1].starts_with("en-US,line:story.intro_01,Once upon a time,story.yarn,Story,3,"));
+    assert!(rows[2].starts_with("en-US,line:story.intro_02,"));
+    assert!(rows[2].ends_with(",Line metadata: sad"));
+    assert_eq!(
+        5,
+        export_strings_csv(&merged, "en-US", None).lines().count()
+    );
+}
+
+const STORY_SOURCE: &str = "title: Story
+---
+Once upon a time #line:story.intro_01
+there was a dialogue. #sad #line:story.intro_02
+===
+";
+
+const LIVEOPS_SOURCE: &str = "title: Event
+---
+Welcome to the event! #line:liveops.welcome
+Have fun!
+===
+";
+
+fn compile_in_namespace(
+    file_name: &str,
+    source: &str,
+    namespace: &str,
+) -> std::result::Result<Compilation, CompilerError> {
+    Compiler::new()
+        .add_file(File {
+            file_name: file_name.to_owned(),
+            source: source.to_owned(),
+        })
+        .with_line_id_namespace(namespace)
+        .compile()
+}
@@ -0,0 +1,187 @@
+use test_base::prelude::*;
+use yarnspinner::compiler::*;
+
+mod test_base;
+
+const GATE: &str = "\
+title: Gate
+ensures: $met_guard
+---
+<<if $knows_password>>
+    <<set $met_guard to true>>
+<<elseif $has_bribe>>
+    The guard takes the money and looks away.
+<<else>>
+    <<set $met_guard to true>>
+<<endif>>
+===
+";
+
+#[test]
+fn test_branch_missing_write_is_flagged_at_branch() {
+    let warnings = Compiler::from_test_nodes(GATE).compile().unwrap().warnings;
+    let warning = single(&warnings, DiagnosticCode::UnfulfilledContract);
+
+    assert_eq!(DiagnosticSeverity::Warning, warning.severity);
+    assert_eq!("Gate", warning.arguments["node"]);
+    assert_eq!("$met_guard", warning.arguments["variable"]);
+    assert_eq!(Some("story.yarn"), warning.file_name.as_deref());
+    assert_eq!(5, warning.range.as_ref().unwrap().start.line);
+    assert_eq!(Some("<<elseif $has_bribe>>"), warning.context.as_deref());
+}
+
+#[test]
+fn test_adding_missing_write_fulfills_contract() {
+    let source = GATE.replace(
+        "    The guard takes the money and looks away.\n",
+        "    The guard takes the money and looks away.\n    <<set $met_guard to true>>\n",
+    );
+    let warnings = Compiler::from_test_nodes(&source)
+        .compile()
+        .unwrap()
+        .warnings;
+    assert!(warnings.is_empty(), "{warnings:?}");
+}
+
+#[test]
+fn test_every_option_and_detours_are_checked() {
+    let source = "\
+title: Shop
+ensures: $paid
+---
+-> Pay in gold
+    <<set $paid to true>>
+-> Pay with a favour
+    <<detour Favour>>
+-> Leave
+===
+title: Favour
+ensures: $paid
+---
+<<set $paid to true>>
+===
+";
+    let warnings = Compiler::from_test_nodes(source)
+        .compile()
+        .unwrap()
+        .warnings;
+    let warning = single(&warnings, DiagnosticCode::UnfulfilledContract);
+    assert_eq!(Some("-> Leave"), warning.context.as_deref());
+}
+
+#[test]
+fn test_node_without_any_write_is_flagged_at_header() {
+    let source = "title: Start\nensures: $a $b\n---\n<<set $a to 1>>\n===\n";
+    let warnings = Compiler::from_test_nodes(source)
+        .compile()
+        .unwrap()
+        .warnings;
+    let warning = single(&warnings, DiagnosticCode::UnfulfilledContract);
+    assert_eq!("$b", warning.arguments["variable"]);
+    assert_eq!(Some("ensures: $a $b"), warning.context.as_deref());
+}
+
+#[test]
+fn test_stopping_does_not_need_to_fulfill_contract() {
+    let source = "\
+title: Start
+ensures: $done
+---
+<<if $give_up>>
+    <<stop>>
+<<endif>>
+<<set $done to true>>
+===
+";
+    assert!(Compiler::from_test_nodes(source)
+        .compile()
+        .unwrap()
+        .warnings
+        .is_empty());
+}
+
+#[test]
+fn test_loop_yields_unverifiable_note() {
+    let source = "\
+title: Loop
+ensures: $escaped
+---
+<<if $tries > 3>>
+    <<set $escaped to true>>
+<<else>>
+    <<jump Loop>>
+<<endif>>
+===
+";
+    let warnings = Compiler::from_test_nodes(source)
+        .compile()
+        .unwrap()
+        .warnings;
+    let note = single(&warnings, DiagnosticCode::UnverifiableContract);
+    assert_eq!(DiagnosticSeverity::Info, note.severity);
+    assert_eq!(Some("    <<jump Loop>>"), note.context.as_deref());
+    assert!(!warnings
+        .iter()
+        .any(|d| d.code == Some(DiagnosticCode::UnfulfilledContract)));
+}
+
+#[test]
+fn test_unread_requirement_is_stale() {
+    let source = "\
+title: Reward
+requires: $met_guard, $gold
+---
+<<declare $gold = 0>>
+You have {$gold} gold.
+===
+";
+    let warnings = Compiler::from_test_nodes(source)
+        .compile()
+        .unwrap()
+        .warnings;
+    let warning = single(&warnings, DiagnosticCode::StaleContract);
+    assert_eq!("$met_guard", warning.arguments["variable"]);
+    assert_eq!(1, warning.range.as_ref().unwrap().start.line);
+}
+
+#[test]
+fn test_strict_contracts_turn_violations_into_errors() {
+    let source = format!("{GATE}title: Loop\nensures: $x\n---\n<<jump Loop>>\n===\n");
+    let error = Compiler::new()
+        .add_file(File {
+            file_name: "gate.yarn".to_owned(),
+            source,
+        })
+        .with_strict_contracts(true)
+        .compile()
+        .unwrap_err();
+    let violation = single(&error.0, DiagnosticCode::UnfulfilledContract);
+    assert_eq!(DiagnosticSeverity::Error, violation.severity);
+    let note = single(&error.0, DiagnosticCode::UnverifiableContract);
+    assert_eq!(DiagnosticSeverity::Info, note.severity);
+}
+
+#[test]
+fn test_analysis_reports_contract_violations() {
+    let compilation = Compiler::new()
+        .add_file(File {
+            file_name: "gate.yarn".to_owned(),
+            source: GATE.to_owned(),
+        })
+        .with_compilation_type(CompilationType::Analyze)
+        .compile()
+        .unwrap();
+
+    assert!(compilation.program.is_none());
+    let warning = single(&compilation.warnings, DiagnosticCode::UnfulfilledContract);
+    assert_eq!("$met_guard", warning.arguments["variable"]);
+}
+
+fn single(diagnostics: &[Diagnostic], code: DiagnosticCode) -> &Diagnostic {
+    let matching: Vec<_> = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.code == Some(code))
+        .collect();
+    assert_eq!(1, matching.len(), "{diagnostics:#?}");
+    matching[0]
+}
@@ -0,0 +1,182 @@
+use test_base::prelude::*;
+use yarnspinner::compiler::*;
+use yarnspinner::core::*;
+
+mod test_base;
+
+#[test]
+fn test_node_graph_lists_nodes_and_edges() {
+    let compilation = Compiler::from_test_nodes(
+        "title: Start
+---
+<<detour Shop>>
+<<if $visited_castle>>
+    <<jump Castle>>
+<<endif>>
+-> Go to the castle
+    <<jump Castle>>
+-> Go somewhere else <<if false>>
+    <<jump {$destination}>>
+<<jump End>>
+===
+title: Shop
+---
+Buy something!
+<<return>>
+===
+title: Castle
+---
+<<jump End>>
+===
+title: End
+---
+Bye!
+===
+",
+    )
+    .declare_variables(story_declarations())
+    .compile()
+    .unwrap();
+
+    let graph = compilation.node_graph;
+
+    let node_names: Vec<_> = graph.nodes.iter().map(|node| node.name.as_str()).collect();
+    assert_eq!(vec!["Start", "Shop", "Castle", "End"], node_names);
+    assert_eq!("story.yarn", graph.nodes[1].file_name);
+    assert_eq!(12, graph.nodes[1].range.start.line);
+
+    let edges: Vec<_> = graph
+        .edges
+        .iter()
+        .map(|edge| {
+            (
+                edge.source.as_str(),
+                edge.target.clone(),
+                edge.kind,
+                edge.is_conditional,
+                edge.range.start.line,
+            )
+        })
+        .collect();
+    let node = |name: &str| NodeGraphTarget::Node(name.to_owned());
+    assert_eq!(
+        vec![
+            ("Start", node("Shop"), NodeGraphEdgeKind::Detour, false, 2),
+            ("Start", node("Castle"), NodeGraphEdgeKind::Jump, true, 4),
+            ("Start", node("Castle"), NodeGraphEdgeKind::Option, false, 7),
+            (
+                "Start",
+                NodeGraphTarget::Dynamic,
+                NodeGraphEdgeKind::Option,
+                true,
+                9
+            ),
+            ("Start", node("End"), NodeGraphEdgeKind::Jump, false, 10),
+            ("Castle", node("End"), NodeGraphEdgeKind::Jump, false, 19),
+        ],
+        edges
+    );
+}
+
+#[test]
+fn test_node_graph_can_be_exported_to_dot() {
+    let compilation = Compiler::from_test_nodes(
+        "title: Start
+---
+<<if true>>
+    <<jump End>>
+<<endif>>
+<<jump {\"End\"}>>
+===
+title: End
+---
+Bye!
+===
+",
+    )
+    .declare_variables(story_declarations())
+    .compile()
+    .unwrap();
+
+    let dot = compilation.node_graph.to_dot();
+
+    assert_eq!(
+        "digraph {
+    \"Start\";
+    \"End\";
+    \"<dynamic>\" [shape=diamond];
+    \"Start\" -> \"End\" [label=\"jump\", style=dashed];
+    \"Start\" -> \"<dynamic>\" [label=\"jump\"];
+}
+",
+        dot
+    );
+}
+
+#[test]
+fn test_self_loop_without_output_is_reported() {
+    let compilation = Compiler::from_test_nodes(
+        "title: Start\n---\n<<set $destination to \"Start\">>\n<<jump Start>>\n===\n",
+    )
+    .declare_variables(story_declarations())
+    .compile()
+    .unwrap();
+
+    let warnings = jump_loop_warnings(&compilation);
+    assert_eq!(1, warnings.len());
+    assert_eq!("Start -> Start", warnings[0].arguments["nodes"]);
+    assert_eq!(DiagnosticSeverity::Warning, warnings[0].severity);
+    assert_eq!(3, warnings[0].range.as_ref().unwrap().start.line);
+}
+
+#[test]
+fn test_loop_through_several_nodes_is_reported_once() {
+    let compilation = Compiler::from_test_nodes(
+        "title: Start\n---\nHello.\n<<jump A>>\n===\n\
+         title: A\n---\n<<jump B>>\n===\n\
+         title: B\n---\n<<jump A>>\n===\n",
+    )
+    .declare_variables(story_declarations())
+    .compile()
+    .unwrap();
+
+    let warnings = jump_loop_warnings(&compilation);
+    assert_eq!(1, warnings.len());
+    assert_eq!("A -> B -> A", warnings[0].arguments["nodes"]);
+    assert_eq!("A", warnings[0].arguments["node"]);
+}
+
+#[test]
+fn test_loops_that_may_stop_are_not_reported() {
+    for body in [
+        // Conditional jump
+        "<<if $visited_castle>>\n    <<jump Start>>\n<<endif>>",
+        // Dynamic jump
+        "<<jump {$destination}>>",
+        // Output before the jump
+        "Around we go.\n<<jump Start>>",
+        "<<wait 1>>\n<<jump Start>>",
+    ] {
+        let compilation = Compiler::from_test_nodes(&format!("title: Start\n---\n{body}\n===\n"))
+            .declare_variables(story_declarations())
+            .compile()
+            .unwrap();
+
+        assert!(jump_loop_warnings(&compilation).is_empty(), "{body}");
+    }
+}
+
+fn jump_loop_warnings(compilation: &Compilation) -> Vec<&Diagnostic> {
+    compilation
+        .warnings
+        .iter()
+        .filter(|diagnostic| diagnostic.code == Some(DiagnosticCode::InfiniteJumpLoop))
+        .collect()
+}
+
+fn story_declarations() -> [Declaration; 2] {
+    [
+        Declaration::new("$visited_castle", Type::Boolean).with_default_value(false),
+        Declaration::new("$destination", Type::String).with_default_value("End"),
+    ]
+}
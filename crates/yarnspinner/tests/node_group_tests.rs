@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use test_base::prelude::*;
+use yarnspinner::compiler::*;
+use yarnspinner::runtime::{
+    BestSaliencyStrategy, Dialogue, DialogueEvent, FirstMatchingSaliencyStrategy,
+    MemoryVariableStorage, RandomBestLeastRecentlyViewedSaliencyStrategy, SaliencyStrategy,
+    StringTableTextProvider,
+};
+
+mod test_base;
+
+const GREETING: &str = "\
+title: Greeting
+when: always
+---
+Hello.
+===
+title: Greeting
+when: $met_before
+---
+Hello again.
+===
+title: Greeting
+when: $met_before
+when: $gold > 10
+---
+Welcome back, big spender.
+===
+";
+
+#[test]
+fn test_first_matching_strategy_picks_first_passing_node() {
+    for (met_before, gold) in [(false, 0), (true, 0), (true, 20)] {
+        let lines = greet(met_before, gold, Box::new(FirstMatchingSaliencyStrategy));
+        assert_eq!(vec!["Hello."], lines);
+    }
+}
+
+#[test]
+fn test_best_strategy_picks_node_with_most_satisfied_conditions() {
+    for (met_before, gold, expected) in [
+        (false, 0, "Hello."),
+        (false, 20, "Hello."),
+        (true, 0, "Hello again."),
+        (true, 20, "Welcome back, big spender."),
+    ] {
+        let lines = greet(met_before, gold, Box::new(BestSaliencyStrategy));
+        assert_eq!(
+            vec![expected],
+            lines,
+            "met_before: {met_before}, gold: {gold}"
+        );
+    }
+}
+
+#[test]
+fn test_random_best_least_recently_viewed_strategy_cycles_through_best_nodes() {
+    let source = "\
+title: Start
+---
+<<declare $met_before = true>>
+<<jump Greeting>>
+===
+title: Greeting
+when: always
+---
+Hello.
+===
+title: Greeting
+when: $met_before
+---
+Hello again.
+===
+title: Greeting
+when: $met_before
+---
+Good to see you again.
+===
+";
+    let mut dialogue = dialogue(
+        source,
+        Box::new(RandomBestLeastRecentlyViewedSaliencyStrategy),
+    );
+
+    let mut lines: Vec<_> = (0..4).flat_map(|_| run(&mut dialogue)).collect();
+
+    // Neither of the equally salient nodes is repeated before the other one was seen
+    lines[..2].sort();
+    lines[2..].sort();
+    assert_eq!(
+        vec![
+            "Good to see you again.",
+            "Hello again.",
+            "Good to see you again.",
+            "Hello again.",
+        ],
+        lines
+    );
+}
+
+#[test]
+fn test_node_without_when_header_is_not_part_of_a_node_group() {
+    let source = format!("{GREETING}title: Greeting\n---\nHi.\n===\n");
+    let error = Compiler::from_test_nodes(&source).compile().unwrap_err();
+
+    assert!(error
+        .0
+        .iter()
+        .any(|diagnostic| diagnostic.code == Some(DiagnosticCode::DuplicateNodeName)));
+}
+
+#[test]
+fn test_errors_in_when_headers_point_to_the_header() {
+    let source = "title: Start\n---\nHi.\n===\ntitle: Greeting\nwhen: 1 + 1\n---\nHello.\n===\n";
+    let error = Compiler::from_test_nodes(source).compile().unwrap_err();
+
+    let diagnostic = error
+        .0
+        .iter()
+        .find(|diagnostic| diagnostic.code == Some(DiagnosticCode::TypeMismatch))
+        .unwrap();
+    assert!(diagnostic.context.as_ref().unwrap().contains("when: 1 + 1"));
+    let range = diagnostic.range.clone().unwrap();
+    assert_eq!((5, 6), (range.start.line, range.start.character));
+    assert_eq!((5, 11), (range.end.line, range.end.character));
+}
+
+#[test]
+fn test_adding_a_node_to_a_group_keeps_the_names_and_line_ids_of_the_others() {
+    let source = format!("title: Start\n---\n<<declare $met_before = true>>\n<<declare $gold = 0>>\n<<jump Greeting>>\n===\n{GREETING}");
+    let with_added_node = source.replacen(
+        "title: Greeting\n",
+        "title: Greeting\nwhen: $gold > 100\n---\nWhat a fortune!\n===\ntitle: Greeting\n",
+        1,
+    );
+    let compile = |source: &str| Compiler::from_test_nodes(source).compile().unwrap();
+    let compilation = compile(&source);
+    let compilation_with_added_node = compile(&with_added_node);
+
+    let program = compilation.program.unwrap();
+    let program_with_added_node = compilation_with_added_node.program.unwrap();
+    assert_eq!(5, program.nodes.len());
+    assert_eq!(6, program_with_added_node.nodes.len());
+    for name in program.nodes.keys() {
+        assert!(program_with_added_node.nodes.contains_key(name), "{name}");
+    }
+    let line_ids = |string_table: HashMap<_, StringInfo>| {
+        string_table
+            .into_iter()
+            .map(|(line_id, string_info)| (string_info.text, line_id))
+            .collect::<Vec<_>>()
+    };
+    let line_ids_with_added_node = line_ids(compilation_with_added_node.string_table);
+    for line_id in line_ids(compilation.string_table) {
+        assert!(line_ids_with_added_node.contains(&line_id), "{line_id:?}");
+    }
+}
+
+fn greet(met_before: bool, gold: usize, strategy: Box<dyn SaliencyStrategy>) -> Vec<String> {
+    let source = format!(
+        "title: Start\n---\n<<declare $met_before = {met_before}>>\n<<declare $gold = {gold}>>\n<<jump Greeting>>\n===\n{GREETING}"
+    );
+    run(&mut dialogue(&source, strategy))
+}
+
+fn dialogue(source: &str, strategy: Box<dyn SaliencyStrategy>) -> Dialogue {
+    let compilation = Compiler::from_test_nodes(source).compile().unwrap();
+    let string_table: HashMap<_, _> = compilation
+        .string_table
+        .into_iter()
+        .map(|(line_id, string_info)| (line_id, string_info.text))
+        .collect();
+    let mut text_provider = StringTableTextProvider::new();
+    text_provider.extend_base_language(string_table);
+    let mut dialogue = Dialogue::new(
+        Box::new(MemoryVariableStorage::new()),
+        Box::new(text_provider),
+    );
+    dialogue
+        .add_program(compilation.program.unwrap())
+        .unwrap()
+        .set_saliency_strategy(strategy);
+    dialogue
+}
+
+fn run(dialogue: &mut Dialogue) -> Vec<String> {
+    dialogue.set_node("Start").unwrap();
+    let mut lines = Vec::new();
+    loop {
+        for event in dialogue.continue_().unwrap() {
+            match event {
+                DialogueEvent::Line(line) => lines.push(line.text),
+                DialogueEvent::DialogueComplete => return lines,
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use test_base::prelude::*;
+use yarnspinner::compiler::*;
+use yarnspinner::runtime::{
+    Dialogue, DialogueEvent, MemoryVariableStorage, StringTableTextProvider,
+};
+
+mod test_base;
+
+const SOURCE: &str = "\
+title: Start
+---
+Welcome.
+<<jump Chapter1.Intro>>
+===
+title: Chapter1.Intro
+---
+The first chapter begins.
+<<jump Outro>>
+===
+title: Chapter1.Outro
+---
+The first chapter ends.
+<<jump {\"Epilogue\"}>>
+===
+title: Chapter1.Epilogue
+---
+Epilogue of the first chapter.
+===
+title: Outro
+---
+The story ends.
+===
+";
+
+#[test]
+fn test_fully_qualified_jump() {
+    let compilation = Compiler::from_test_nodes(SOURCE).compile().unwrap();
+    let start = &compilation.program.as_ref().unwrap().nodes["Start"];
+
+    assert!(start.instructions.iter().any(
+        |instruction| instruction.operands.first() == Some(&"Chapter1.Intro".to_owned().into())
+    ));
+    assert_eq!(
+        vec!["Welcome.", "The first chapter begins."],
+        run(compilation)[..2]
+    );
+}
+
+#[test]
+fn test_relative_jump_within_namespace() {
+    let lines = run(Compiler::from_test_nodes(SOURCE).compile().unwrap());
+
+    // `Outro` refers to the node in the same namespace, not the one outside of it,
+    // both when known at compile time and when computed at runtime
+    assert_eq!(
+        vec![
+            "Welcome.",
+            "The first chapter begins.",
+            "The first chapter ends.",
+            "Epilogue of the first chapter.",
+        ],
+        lines
+    );
+}
+
+#[test]
+fn test_relative_jump_from_outside_of_any_namespace_is_not_resolved() {
+    let compilation = Compiler::from_test_nodes(
+        "title: Start\n---\n<<jump Intro>>\n===\ntitle: Chapter1.Intro\n---\nHi.\n===\n",
+    )
+    .compile()
+    .unwrap();
+
+    let warning = compilation
+        .warnings
+        .iter()
+        .find(|diagnostic| diagnostic.code == Some(DiagnosticCode::MissingJumpTarget))
+        .unwrap();
+    assert_eq!("Intro", warning.arguments["node"]);
+}
+
+#[test]
+fn test_node_title_with_empty_namespace_is_invalid() {
+    let error = Compiler::from_test_nodes("title: Chapter1..Intro\n---\nHi.\n===\n")
+        .compile()
+        .unwrap_err();
+
+    assert!(error
+        .0
+        .iter()
+        .any(|diagnostic| diagnostic.code == Some(DiagnosticCode::InvalidNodeName)));
+}
+
+fn run(compilation: Compilation) -> Vec<String> {
+    let string_table: HashMap<_, _> = compilation
+        .string_table
+        .into_iter()
+        .map(|(line_id, string_info)| (line_id, string_info.text))
+        .collect();
+    let mut text_provider = StringTableTextProvider::new();
+    text_provider.extend_base_language(string_table);
+    let mut dialogue = Dialogue::new(
+        Box::new(MemoryVariableStorage::new()),
+        Box::new(text_provider),
+    );
+    dialogue
+        .add_program(compilation.program.unwrap())
+        .unwrap()
+        .set_node("Start")
+        .unwrap();
+
+    let mut lines = Vec::new();
+    loop {
+        for event in dialogue.continue_().unwrap() {
+            match event {
+                DialogueEvent::Line(line) => lines.push(line.text),
+                DialogueEvent::DialogueComplete => return lines,
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use yarnspinner::compiler::*;
+use yarnspinner::core::*;
+
+#[test]
+fn test_parsing_exposes_nodes_and_headers() {
+    let source = "title: Start\ntags: intro\n---\nHello!\n===\ntitle: End\n---\n<<stop>>\n===\n";
+    let parsed = parse_yarn_file("story.yarn", source).unwrap();
+
+    assert_eq!("story.yarn", parsed.name);
+    assert!(parsed.warnings.is_empty());
+    let titles: Vec<_> = parsed
+        .nodes
+        .iter()
+        .map(|node| node.title.as_deref())
+        .collect();
+    assert_eq!(vec![Some("Start"), Some("End")], titles);
+
+    let start = &parsed.nodes[0];
+    let headers: Vec<_> = start
+        .headers
+        .iter()
+        .map(|header| (header.key.as_str(), header.value.as_str()))
+        .collect();
+    assert_eq!(vec![("title", "Start"), ("tags", "intro")], headers);
+    assert_eq!(
+        Position {
+            line: 1,
+            character: 0
+        },
+        start.headers[1].range.start
+    );
+    assert_eq!(
+        Position {
+            line: 2,
+            character: 3
+        },
+        start.body_range.start
+    );
+    assert_eq!(
+        Position {
+            line: 4,
+            character: 0
+        },
+        start.body_range.end
+    );
+    assert_eq!(
+        Position {
+            line: 4,
+            character: 3
+        },
+        start.range.end
+    );
+    assert_eq!(5, parsed.nodes[1].range.start.line);
+}
+
+#[test]
+fn test_parsing_exposes_tokens_with_stable_kinds() {
+    let source = "title: Start\n---\n<<set $gold to 1 + 2>> // cheat\nAlice: Hi! #line:hi\n-> Leave\n    <<walk Alice>>\n===\n";
+    let parsed = parse_yarn_file("story.yarn", source).unwrap();
+
+    let significant: Vec<_> = parsed
+        .tokens
+        .iter()
+        .filter(|token| {
+            !matches!(
+                token.kind,
+                TokenKind::Whitespace | TokenKind::Newline | TokenKind::Indent | TokenKind::Dedent
+            )
+        })
+        .map(|token| (token.kind, token.text.as_str()))
+        .collect();
+    assert_eq!(
+        vec![
+            (TokenKind::HeaderKey, "title"),
+            (TokenKind::HeaderDelimiter, ": "),
+            (TokenKind::HeaderValue, "Start"),
+            (TokenKind::BodyStart, "---"),
+            (TokenKind::CommandStart, "<<"),
+            (TokenKind::Keyword, "set "),
+            (TokenKind::Variable, "$gold"),
+            (TokenKind::Operator, "to"),
+            (TokenKind::Number, "1"),
+            (TokenKind::Operator, "+"),
+            (TokenKind::Number, "2"),
+            (TokenKind::CommandEnd, ">>"),
+            (TokenKind::Comment, "// cheat"),
+            (TokenKind::Text, "Alice: Hi! "),
+            (TokenKind::Hashtag, "#"),
+            (TokenKind::HashtagText, "line:hi"),
+            (TokenKind::ShortcutArrow, "->"),
+            (TokenKind::Text, "Leave"),
+            (TokenKind::CommandStart, "<<"),
+            (TokenKind::CommandText, "walk Alice"),
+            (TokenKind::CommandEnd, ">>"),
+            (TokenKind::BodyEnd, "==="),
+        ],
+        significant
+    );
+
+    let variable = parsed
+        .tokens
+        .iter()
+        .find(|token| token.kind == TokenKind::Variable)
+        .unwrap();
+    assert_eq!(
+        Position {
+            line: 2,
+            character: 6
+        }..Position {
+            line: 2,
+            character: 11
+        },
+        variable.range
+    );
+}
+
+#[test]
+fn test_parsing_invalid_file_returns_diagnostics() {
+    let result = parse_yarn_file("broken.yarn", "title: Start\n---\n<<set $gold to >>\n===\n");
+    let diagnostics = result.unwrap_err();
+    assert!(!diagnostics.is_empty());
+    assert!(diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.file_name.as_deref() == Some("broken.yarn")));
+}
@@ -0,0 +1,294 @@
+use std::collections::HashMap;
+use yarnspinner::compiler::*;
+use yarnspinner::core::*;
+use yarnspinner::runtime::{
+    Dialogue, DialogueError, DialogueEvent, MemoryVariableStorage, StringTableTextProvider,
+};
+
+const SOURCE: &str = "\
+title: Start
+---
+<<declare $gold = 10>>
+<<if $gold > 5>>
+    You are rich.
+<<endif>>
+-> Buy a sword
+    <<set $gold to $gold - 5>>
+-> Leave
+<<jump End>>
+===
+title: End
+---
+Goodbye.
+===
+";
+
+#[test]
+fn test_loading_valid_program_round_trips() {
+    let compilation = compile();
+    let program = compilation.program.unwrap();
+
+    let loaded = Program::from_bytes(&program.to_bytes()).unwrap();
+    assert_eq!(program, loaded);
+    loaded
+        .validate_line_ids(compilation.string_table.keys())
+        .unwrap();
+}
+
+#[test]
+fn test_loading_truncated_bytes_fails_without_panicking() {
+    let bytes = compile().program.unwrap().to_bytes();
+
+    assert!(matches!(
+        Program::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(ProgramLoadError::Decode(_))
+    ));
+    for length in 0..bytes.len() {
+        // Cutting between two fields can still decode, but must never panic
+        let _ = Program::from_bytes(&bytes[..length]);
+    }
+}
+
+#[test]
+fn test_loading_program_with_dangling_jump_target_fails() {
+    let mut program = compile().program.unwrap();
+    let start = program.nodes.get_mut("Start").unwrap();
+    let (index, jump) = start
+        .instructions
+        .iter_mut()
+        .enumerate()
+        .find(|(_, instruction)| instruction.opcode == OpCode::JumpIfFalse as i32)
+        .unwrap();
+    jump.operands[0] = "nowhere".to_owned().into();
+
+    let error = Program::from_bytes(&program.to_bytes()).unwrap_err();
+    assert_eq!(
+        ProgramLoadError::UnknownLabel {
+            node: "Start".to_owned(),
+            instruction: index,
+            label: "nowhere".to_owned(),
+        },
+        error
+    );
+    assert_eq!(
+        format!("Instruction {index} of node Start jumps to the unknown label nowhere"),
+        error.to_string()
+    );
+}
+
+#[test]
+fn test_loading_program_with_label_out_of_bounds_fails() {
+    let mut program = compile().program.unwrap();
+    let start = program.nodes.get_mut("Start").unwrap();
+    let instruction_count = start.instructions.len() as i32;
+    let label = start.labels.keys().next().unwrap().clone();
+    start.labels.insert(label.clone(), instruction_count);
+
+    assert_eq!(
+        Err(ProgramLoadError::LabelOutOfBounds {
+            node: "Start".to_owned(),
+            label,
+            target: instruction_count,
+        }),
+        Program::from_bytes(&program.to_bytes())
+    );
+}
+
+#[test]
+fn test_loading_program_with_missing_node_fails() {
+    let mut program = compile().program.unwrap();
+    program.nodes.remove("End");
+
+    let error = Program::from_bytes(&program.to_bytes()).unwrap_err();
+    assert!(
+        matches!(&error, ProgramLoadError::MissingNode { node, target, .. } if node == "Start" && target == "End"),
+        "{error:?}"
+    );
+}
+
+#[test]
+fn test_loading_program_with_malformed_instruction_fails() {
+    let mut program = compile().program.unwrap();
+    program.nodes.get_mut("Start").unwrap().instructions[0] = Instruction {
+        opcode: OpCode::RunLine as i32,
+        operands: vec![],
+    };
+    assert_eq!(
+        Err(ProgramLoadError::InvalidOperands {
+            node: "Start".to_owned(),
+            instruction: 0,
+            opcode: OpCode::RunLine,
+        }),
+        Program::from_bytes(&program.to_bytes())
+    );
+
+    program.nodes.get_mut("Start").unwrap().instructions[0].opcode = 1000;
+    assert_eq!(
+        Err(ProgramLoadError::InvalidOpCode {
+            node: "Start".to_owned(),
+            instruction: 0,
+            opcode: 1000,
+        }),
+        Program::from_bytes(&program.to_bytes())
+    );
+}
+
+#[test]
+fn test_running_program_with_stack_underflow_fails_without_panicking() {
+    let node = Node {
+        name: "Start".to_owned(),
+        instructions: vec![Instruction {
+            opcode: OpCode::JumpIfFalse as i32,
+            operands: vec!["L".to_owned().into()],
+        }],
+        labels: HashMap::from([("L".to_owned(), 0)]),
+        ..Default::default()
+    };
+    let program = Program {
+        nodes: HashMap::from([("Start".to_owned(), node)]),
+        ..Default::default()
+    };
+    let program = Program::from_bytes(&program.to_bytes()).unwrap();
+    let mut dialogue = Dialogue::new(
+        Box::new(MemoryVariableStorage::new()),
+        Box::new(StringTableTextProvider::new()),
+    );
+    dialogue
+        .add_program(program)
+        .unwrap()
+        .set_node("Start")
+        .unwrap();
+
+    let error = dialogue.continue_().unwrap_err();
+    assert!(
+        matches!(&error, DialogueError::MalformedInstruction { node, instruction: 0, .. } if node == "Start"),
+        "{error:?}"
+    );
+}
+
+#[test]
+fn test_validating_line_ids_reports_missing_lines() {
+    let compilation = compile();
+    let program = compilation.program.unwrap();
+    let mut line_ids: Vec<_> = compilation.string_table.into_keys().collect();
+    let goodbye = program.nodes["End"]
+        .instructions
+        .iter()
+        .find(|instruction| instruction.opcode == OpCode::RunLine as i32)
+        .map(|instruction| LineId(instruction.read_operand(0)))
+        .unwrap();
+    line_ids.retain(|line_id| *line_id != goodbye);
+
+    assert!(matches!(
+        program.validate_line_ids(&line_ids),
+        Err(ProgramLoadError::MissingLineId { node, line_id, .. }) if node == "End" && line_id == goodbye
+    ));
+}
+
+#[test]
+fn test_running_program_loaded_from_memory() {
+    let compilation = compile();
+    let bytes = compilation.program.unwrap().to_bytes();
+    let string_table: HashMap<_, _> = compilation
+        .string_table
+        .into_iter()
+        .map(|(line_id, string_info)| (line_id, string_info.text))
+        .collect();
+
+    let program = Program::from_bytes(&bytes).unwrap();
+    program.validate_line_ids(string_table.keys()).unwrap();
+    let mut text_provider = StringTableTextProvider::new();
+    text_provider.extend_base_language(string_table);
+    let mut dialogue = Dialogue::new(
+        Box::new(MemoryVariableStorage::new()),
+        Box::new(text_provider),
+    );
+    dialogue
+        .add_program(program)
+        .unwrap()
+        .set_node("Start")
+        .unwrap();
+
+    let mut lines = Vec::new();
+    loop {
+        match dialogue.continue_().unwrap().pop().unwrap() {
+            DialogueEvent::Line(line) => lines.push(line.text),
+            DialogueEvent::Options(options) => {
+                dialogue.set_selected_option(options[0].id).unwrap();
+            }
+            DialogueEvent::DialogueComplete => break,
+            _ => {}
+        }
+    }
+    assert_eq!(vec!["You are rich.", "Goodbye."], lines);
+    assert_eq!(Some(YarnValue::from(5.0)), dialogue.get_variable("$gold"));
+}
+
+#[test]
+fn test_compiling_source_without_file() {
+    let compilation = Compiler::new()
+        .add_source(
+            "<repl>",
+            "title: Start\n---\n<<set $greeted to true>>\nHello from memory!\n===\n",
+        )
+        .compile()
+        .unwrap();
+    assert!(compilation
+        .string_table
+        .values()
+        .all(|string_info| string_info.file_name == "<repl>"));
+
+    let mut text_provider = StringTableTextProvider::new();
+    text_provider.extend_base_language(
+        compilation
+            .string_table
+            .into_iter()
+            .map(|(line_id, string_info)| (line_id, string_info.text))
+            .collect::<HashMap<_, _>>(),
+    );
+    let mut dialogue = Dialogue::new(
+        Box::new(MemoryVariableStorage::new()),
+        Box::new(text_provider),
+    );
+    dialogue
+        .add_program(compilation.program.unwrap())
+        .unwrap()
+        .set_node("Start")
+        .unwrap();
+
+    let mut lines = Vec::new();
+    loop {
+        match dialogue.continue_().unwrap().pop().unwrap() {
+            DialogueEvent::Line(line) => lines.push(line.text),
+            DialogueEvent::DialogueComplete => break,
+            _ => {}
+        }
+    }
+    assert_eq!(vec!["Hello from memory!"], lines);
+    assert_eq!(
+        Some(YarnValue::from(true)),
+        dialogue.get_variable("$greeted")
+    );
+}
+
+#[test]
+fn test_diagnostics_of_source_without_file_use_its_name() {
+    let error = Compiler::new()
+        .add_source("<repl>", "title: Start\n---\n<<set $x to>>\n===\n")
+        .compile()
+        .unwrap_err();
+    assert!(!error.0.is_empty());
+    for diagnostic in error.0 {
+        assert_eq!(Some("<repl>"), diagnostic.file_name.as_deref());
+    }
+}
+
+fn compile() -> Compilation {
+    Compiler::new()
+        .add_file(File {
+            file_name: "shop.yarn".to_owned(),
+            source: SOURCE.to_owned(),
+        })
+        .compile()
+        .unwrap()
+}
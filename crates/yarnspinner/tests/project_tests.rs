@@ -292,3 +292,188 @@ fn test_debug_output_is_produced() {
     assert_eq!(2, first_line_info.position.unwrap().line);
     assert_eq!(0, first_line_info.position.unwrap().character);
 }
+
+#[test]
+fn test_debug_output_maps_statements_to_instructions() {
+    let file = File {
+        file_name: "input".to_owned(),
+        source: "title: Start
+---
+<<declare $gold = 10>>
+<<if $gold > 5>>
+    You are rich.
+<<endif>>
+-> Buy a sword <<if $gold >= 10>> #line:sword
+    <<set $gold -= 10>>
+-> Leave #line:leave
+===
+"
+        .to_owned(),
+    };
+    let result = Compiler::new().add_file(file).compile().unwrap();
+    let instructions = &result.program.unwrap().nodes["Start"].instructions;
+    let debug_info = &result.debug_info["Start"];
+    let find = |kind: StatementKind| {
+        debug_info
+            .instructions
+            .iter()
+            .filter(move |statement| statement.kind == kind)
+    };
+
+    let condition = find(StatementKind::IfCondition).next().unwrap();
+    assert_eq!("$gold > 5", condition.source_text);
+    assert_eq!(
+        OpCode::JumpIfFalse as i32,
+        instructions[condition.instructions.end - 1].opcode
+    );
+
+    let line = find(StatementKind::Line).next().unwrap();
+    assert_eq!("You are rich.", line.source_text);
+    assert!(line.instructions.start >= condition.instructions.end);
+
+    let options: Vec<_> = find(StatementKind::Option).collect();
+    assert_eq!(2, options.len());
+    assert_eq!(
+        "Buy a sword <<if $gold >= 10>> #line:sword",
+        options[0].source_text
+    );
+    assert_eq!(
+        OpCode::AddOption as i32,
+        instructions[options[0].instructions.end - 1].opcode
+    );
+
+    let group = find(StatementKind::OptionGroup).next().unwrap();
+    assert_eq!(2, group.option_instructions.len());
+    let set = find(StatementKind::Set).next().unwrap();
+    assert_eq!("<<set $gold -= 10>>", set.source_text);
+    assert!(group.option_instructions[0].start <= set.instructions.start);
+    assert!(set.instructions.end <= group.option_instructions[0].end);
+    assert!(group.instructions.start <= options[0].instructions.start);
+    assert_eq!(group.instructions.end, instructions.len() - 1);
+}
+
+#[test]
+fn test_adding_line_tags_preserves_source_and_is_stable() {
+    let original_text = "title: Start\r\n\
+        ---\r\n\
+        // A comment about Sally.\r\n\
+        Sally: Hi there! #line:sally_hi\r\n\
+        Sally: Do you want \\{braces\\}?\r\n\
+        -> Yes\r\n\
+        -> No #line:sally_no // a comment\r\n\
+        ===\r\n";
+
+    let output = Compiler::add_tags_to_lines(original_text, Vec::new())
+        .unwrap()
+        .unwrap();
+
+    // Only the tags were added, everything else is untouched
+    let line_tag_regex = Regex::new(r" #line:\w+").unwrap();
+    let new_tags: Vec<_> = line_tag_regex
+        .find_iter(&output)
+        .map(|tag| tag.as_str())
+        .filter(|tag| !original_text.contains(tag))
+        .collect();
+    assert_eq!(2, new_tags.len(), "{output}");
+    assert_ne!(new_tags[0], new_tags[1]);
+    assert_eq!(
+        original_text,
+        new_tags
+            .iter()
+            .fold(output.clone(), |text, tag| text.replace(tag, ""))
+    );
+    assert_eq!(
+        output,
+        parse_yarn_file("start.yarn", &output).unwrap().source()
+    );
+
+    // Every line now has exactly one tag
+    for line in output
+        .lines()
+        .filter(|line| line.starts_with("Sally") || line.starts_with("->"))
+    {
+        assert_eq!(1, line_tag_regex.find_iter(line).count(), "{line}");
+    }
+    let compilation = Compiler::new()
+        .add_file(File {
+            file_name: "start.yarn".to_string(),
+            source: output.clone(),
+        })
+        .with_compilation_type(CompilationType::StringsOnly)
+        .compile()
+        .unwrap();
+    assert_eq!(4, compilation.string_table.len());
+
+    // Tagging again doesn't change anything
+    assert_eq!(
+        None,
+        Compiler::add_tags_to_lines(output, Vec::new()).unwrap()
+    );
+}
+
+#[test]
+fn test_add_line_tags_generates_unique_deterministic_tags() {
+    let source = "title: Start
+---
+Sally: Hello!
+Sally: Hello!
+Ship: Welcome aboard. #line:ship_welcome
+-> Thanks!
+-> Go away.
+===
+title: Other
+---
+Sally: Hello!
+===
+";
+    let line_tag_regex = Regex::new(r"#line:\w+").unwrap();
+    let tags = |text: &str| -> Vec<String> {
+        line_tag_regex
+            .find_iter(text)
+            .map(|tag| tag.as_str().to_owned())
+            .collect()
+    };
+
+    let tagged = add_line_tags(source, &HashSet::new()).unwrap();
+    assert_eq!(tagged, add_line_tags(source, &HashSet::new()).unwrap());
+    let new_tags: Vec<_> = tags(&tagged)
+        .into_iter()
+        .filter(|tag| tag != "#line:ship_welcome")
+        .collect();
+    assert_eq!(5, new_tags.len(), "{tagged}");
+    assert_eq!(5, new_tags.iter().collect::<HashSet<_>>().len());
+    assert!(tagged.contains("Ship: Welcome aboard. #line:ship_welcome\n"));
+    for line in tagged
+        .lines()
+        .filter(|line| line.contains(':') || line.starts_with("->"))
+    {
+        if line.starts_with("title:") {
+            continue;
+        }
+        assert_eq!(1, tags(line).len(), "{line}");
+    }
+
+    // Tags used elsewhere in the project are never generated again
+    let existing_ids: HashSet<_> = new_tags
+        .iter()
+        .map(|tag| LineId(tag.trim_start_matches('#').to_owned()))
+        .collect();
+    let retagged = add_line_tags(source, &existing_ids).unwrap();
+    assert!(tags(&retagged)
+        .iter()
+        .filter(|tag| *tag != "#line:ship_welcome")
+        .all(|tag| !new_tags.contains(tag)));
+
+    let compilation = Compiler::new()
+        .add_file(File {
+            file_name: "start.yarn".to_string(),
+            source: tagged,
+        })
+        .with_compilation_type(CompilationType::StringsOnly)
+        .compile()
+        .unwrap();
+    assert!(compilation
+        .string_table
+        .values()
+        .all(|string_info| !string_info.is_implicit_tag));
+}
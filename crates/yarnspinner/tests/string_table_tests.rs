@@ -0,0 +1,86 @@
+use yarnspinner::compiler::*;
+
+const SOURCE: &str = "title: Start
+---
+Welcome.
+<<if true>>
+    Inside an if.
+    <<if true>>
+        Inside a nested if.
+    <<endif>>
+<<else>>
+    Inside an else.
+<<endif>>
+<<wait 1>>
+-> Go left
+    <<play_sound steps>>
+    You went left.
+-> Go right <<if true>>
+    -> Really?
+        Yes.
+<<set $done to true>>
+Goodbye.
+===
+title: Epilogue
+---
+The end.
+===
+";
+
+fn compile(files: &[(&str, &str)]) -> Compilation {
+    let mut compiler = Compiler::new();
+    for (file_name, source) in files {
+        compiler.add_file(File {
+            file_name: file_name.to_string(),
+            source: source.to_string(),
+        });
+    }
+    compiler.compile().unwrap()
+}
+
+#[test]
+fn test_lines_for_node_are_in_script_order() {
+    let compilation = compile(&[("story.yarn", SOURCE)]);
+
+    let lines: Vec<_> = compilation
+        .lines_for_node("Start")
+        .into_iter()
+        .map(|(_, string_info)| (string_info.line_number, string_info.text.as_str()))
+        .collect();
+    assert_eq!(
+        vec![
+            (3, "Welcome."),
+            (5, "Inside an if."),
+            (7, "Inside a nested if."),
+            (10, "Inside an else."),
+            (13, "Go left"),
+            (15, "You went left."),
+            (16, "Go right"),
+            (17, "Really?"),
+            (18, "Yes."),
+            (20, "Goodbye."),
+        ],
+        lines
+    );
+    for (line_id, string_info) in compilation.lines_for_node("Start") {
+        assert_eq!(string_info, &compilation.string_table[line_id]);
+    }
+    assert!(compilation.lines_for_node("Nowhere").is_empty());
+}
+
+#[test]
+fn test_nodes_with_lines_are_ordered_by_file_and_position() {
+    let compilation = compile(&[
+        ("b.yarn", "title: Credits\n---\nThanks for playing.\n===\n"),
+        ("a.yarn", SOURCE),
+        (
+            "c.yarn",
+            "title: Silent\n---\n<<wait 1>>\n===\ntitle: Loud\n---\nHey!\n===\n",
+        ),
+    ]);
+
+    assert_eq!(
+        vec!["Start", "Epilogue", "Credits", "Loud"],
+        compilation.nodes_with_lines()
+    );
+}
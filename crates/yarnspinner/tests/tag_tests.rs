@@ -2,6 +2,7 @@
 
 use test_base::prelude::*;
 use yarnspinner::compiler::*;
+use yarnspinner::core::{Program, ProgramCombineError};
 
 mod test_base;
 
@@ -171,6 +172,122 @@ fn test_line_is_last_before_another_node_not_tagged() {
     assert!(!contains_last_line_tag(info));
 }
 
+const TAGGED_NODES: &str = "title: Start\n---\nWelcome! #line:welcome\n<<jump Cheats>>\n===\n\
+                            title: Shop\ntags: merchant  town\n---\nWhat do you need? #line:shop\n===\n\
+                            title: Cheats\ntags: debug\n---\nAll items unlocked. #line:cheats\n===\n";
+
+#[test]
+fn test_node_tags_are_exposed() {
+    let compilation = Compiler::new()
+        .add_source("tags.yarn", TAGGED_NODES)
+        .compile()
+        .unwrap();
+
+    assert_eq!(vec!["merchant", "town"], compilation.node_tags["Shop"]);
+    assert_eq!(vec!["debug"], compilation.node_tags["Cheats"]);
+    assert!(compilation.node_tags["Start"].is_empty());
+
+    let dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    assert_eq!(
+        Some(vec!["merchant".to_owned(), "town".to_owned()]),
+        dialogue.get_tags_for_node("Shop")
+    );
+}
+
+#[test]
+fn test_nodes_with_excluded_tags_are_left_out() {
+    let compilation = Compiler::new()
+        .add_source("tags.yarn", TAGGED_NODES)
+        .with_excluded_node_tags(["debug"])
+        .compile()
+        .unwrap();
+
+    let program = compilation.program.unwrap();
+    assert!(program.nodes.contains_key("Shop"));
+    assert!(!program.nodes.contains_key("Cheats"));
+    assert!(!compilation.debug_info.contains_key("Cheats"));
+    assert!(!compilation.node_tags.contains_key("Cheats"));
+    assert!(compilation.string_table.contains_key(&"line:shop".into()));
+    assert!(!compilation.string_table.contains_key(&"line:cheats".into()));
+
+    let warning = compilation
+        .warnings
+        .iter()
+        .find(|warning| warning.code == Some(DiagnosticCode::JumpToExcludedNode))
+        .unwrap();
+    assert_eq!("Cheats", warning.arguments["node"]);
+    assert_eq!(3, warning.range.as_ref().unwrap().start.line);
+}
+
+#[test]
+fn test_nodes_with_excluded_tags_are_still_validated() {
+    let result = Compiler::new()
+        .add_source(
+            "tags.yarn",
+            "title: Start\n---\nHello\n===\ntitle: Cheats\ntags: debug\n---\n<<set $gold to 1 + \"a\">>\n===\n",
+        )
+        .with_excluded_node_tags(["debug"])
+        .compile();
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_program_lists_node_names_and_filters_by_tag() {
+    let source = format!("{TAGGED_NODES}title: Tavern\ntags: town\n---\n<<declare $gold = 0>>\n<<declare $is_rich = $gold > 100>>\nCheers!\n===\n");
+    let program = Compiler::new()
+        .add_source("tags.yarn", source)
+        .compile()
+        .unwrap()
+        .program
+        .unwrap();
+
+    assert_eq!(
+        vec!["Cheats", "Shop", "Start", "Tavern"],
+        program.node_names()
+    );
+    assert_eq!(vec!["Shop", "Tavern"], program.nodes_with_tag("town"));
+    assert_eq!(vec!["Cheats"], program.nodes_with_tag("debug"));
+    assert!(program.nodes_with_tag("merchant town").is_empty());
+}
+
+#[test]
+fn test_combined_program_lists_nodes_of_all_programs() {
+    let compile = |source: &str| {
+        Compiler::from_test_source(source)
+            .compile()
+            .unwrap()
+            .program
+            .unwrap()
+    };
+    let base = Compiler::new()
+        .add_source("tags.yarn", TAGGED_NODES)
+        .compile()
+        .unwrap()
+        .program
+        .unwrap();
+    let dlc = Compiler::new()
+        .add_source("dlc.yarn", "title: Arena\ntags: town\n---\nFight!\n===\n")
+        .compile()
+        .unwrap()
+        .program
+        .unwrap();
+
+    let combined = Program::combine(vec![base.clone(), dlc]).unwrap();
+    assert_eq!(
+        vec!["Arena", "Cheats", "Shop", "Start"],
+        combined.node_names()
+    );
+    assert_eq!(vec!["Arena", "Shop"], combined.nodes_with_tag("town"));
+
+    // Node names stay unique because programs with the same node can't be combined
+    let error = Program::combine(vec![base, compile("Hello")]).unwrap_err();
+    assert_eq!(
+        ProgramCombineError::DuplicateNode("Start".to_owned()),
+        error
+    );
+}
+
 fn contains_last_line_tag(info: &StringInfo) -> bool {
     info.metadata.contains(&"lastline".to_owned())
 }
@@ -5,6 +5,7 @@ use yarnspinner_compiler::prelude::*;
 
 pub trait TestCompiler {
     fn from_test_source(source: &str) -> Self;
+    fn from_test_nodes(source: &str) -> Self;
 }
 
 impl TestCompiler for Compiler {
@@ -18,4 +19,11 @@ impl TestCompiler for Compiler {
         compiler.add_file(file);
         compiler
     }
+
+    /// Unlike [`TestCompiler::from_test_source`], the source contains its own nodes, headers included.
+    fn from_test_nodes(source: &str) -> Self {
+        let mut compiler = Self::new();
+        compiler.add_source("story.yarn", source);
+        compiler
+    }
 }
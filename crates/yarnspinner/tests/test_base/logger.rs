@@ -1,7 +1,14 @@
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use yarnspinner::log::{self, Level, Metadata, Record};
 
+/// All warnings logged during the tests. Since the logger is global, tests should only look for their own warnings.
+static WARNINGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+pub fn logged_warnings() -> Vec<String> {
+    WARNINGS.lock().unwrap().clone()
+}
+
 pub(crate) struct TestLogger {
     runtime_errors_cause_failure: Arc<AtomicBool>,
 }
@@ -26,6 +33,10 @@ impl log::Log for TestLogger {
                 Level::Debug => {
                     println!("{msg}")
                 }
+                Level::Warn => {
+                    println!("{msg}");
+                    WARNINGS.lock().unwrap().push(msg);
+                }
                 Level::Error => {
                     eprintln!("{msg}");
                     if self.runtime_errors_cause_failure.load(Ordering::Relaxed) {
@@ -35,7 +35,9 @@ use yarnspinner::log::{self, LevelFilter, SetLoggerError};
 
 pub mod prelude {
     #[allow(unused_imports)] // False positive
-    pub use crate::test_base::{extensions::*, paths::*, step::*, test_plan::*, *};
+    pub use crate::test_base::{
+        extensions::*, logger::logged_warnings, paths::*, step::*, test_plan::*, *,
+    };
 }
 
 pub fn init_logger(runtime_errors_cause_failure: Arc<AtomicBool>) -> Result<(), SetLoggerError> {
@@ -109,8 +111,9 @@ impl TestBase {
     }
 
     #[must_use]
-    pub fn with_compilation(self, compilation: Compilation) -> Self {
+    pub fn with_compilation(mut self, compilation: Compilation) -> Self {
         let string_table = compilation.string_table;
+        self.dialogue.add_debug_info(compilation.debug_info);
         self.with_program(compilation.program.unwrap())
             .with_string_table(string_table)
     }
@@ -124,7 +127,7 @@ impl TestBase {
 
     #[must_use]
     pub fn with_program(mut self, program: Program) -> Self {
-        self.dialogue.add_program(program);
+        self.dialogue.add_program(program).unwrap();
         self
     }
 
@@ -232,9 +235,16 @@ impl TestBase {
                             Some(StepValue::String(command.raw))
                         );
                     }
+                    DialogueEvent::DeferredFunctionCall(call) => {
+                        panic!(
+                            "Test plans cannot complete deferred functions, but \"{}\" was called",
+                            call.function_name
+                        );
+                    }
                     DialogueEvent::NodeComplete(_) => {}
                     DialogueEvent::NodeStart(_) => {}
                     DialogueEvent::LineHints(_) => {}
+                    DialogueEvent::Wait(_) => {}
                     DialogueEvent::DialogueComplete => {
                         let Some(test_plan) = self.test_plan.as_mut() else {
                             continue;
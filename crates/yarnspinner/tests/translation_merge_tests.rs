@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use test_base::prelude::*;
+use yarnspinner::compiler::*;
+use yarnspinner::core::*;
+
+mod test_base;
+
+#[test]
+fn test_merge_translation_sorts_lines_into_buckets() {
+    let base = base_compilation();
+    for policy in [
+        ConflictPolicy::KeepExisting,
+        ConflictPolicy::KeepIncoming,
+        ConflictPolicy::MarkForReview,
+    ] {
+        let result = merge_translation(&base, existing(), &incoming_csv(), policy).unwrap();
+
+        assert_eq!(vec![LineId::from("line:clean")], result.accepted);
+        assert_eq!(1, result.conflicts.len(), "policy: {policy:?}");
+        let conflict = &result.conflicts[0];
+        assert_eq!(LineId::from("line:conflict"), conflict.id);
+        assert_eq!("How are you doing today?", conflict.base_text);
+        assert_eq!(
+            Some(translated("Wie geht's?", "How are you?")),
+            conflict.existing
+        );
+        assert_eq!(
+            translated("Wie geht es dir?", "How are you?"),
+            conflict.incoming
+        );
+        assert_eq!(vec![LineId::from("line:orphan")], result.unknown);
+        assert_eq!(vec![LineId::from("line:missing")], result.missing);
+
+        assert_eq!(
+            Some(&translated("Hallo zusammen.", "Hello there.")),
+            result.merged.get(&"line:clean".into())
+        );
+        assert_eq!(
+            Some(&translated("Tschüss.", "Goodbye.")),
+            result.merged.get(&"line:missing".into())
+        );
+        assert!(!result.merged.contains_key(&"line:orphan".into()));
+
+        let expected_conflict_resolution = match policy {
+            ConflictPolicy::KeepExisting => translated("Wie geht's?", "How are you?"),
+            ConflictPolicy::KeepIncoming => translated("Wie geht es dir?", "How are you?"),
+            ConflictPolicy::MarkForReview => {
+                translated("(NEEDS UPDATE) Wie geht es dir?", "How are you?")
+            }
+        };
+        assert_eq!(
+            Some(&expected_conflict_resolution),
+            result.merged.get(&"line:conflict".into()),
+            "policy: {policy:?}"
+        );
+    }
+}
+
+#[test]
+fn test_merge_translation_report_lists_lines_needing_attention() {
+    let result = merge_translation(
+        &base_compilation(),
+        existing(),
+        &incoming_csv(),
+        ConflictPolicy::default(),
+    )
+    .unwrap();
+
+    let report = result.report();
+    assert!(report
+        .starts_with("Accepted 1 line(s), 1 conflict(s), 1 unknown line(s), 1 missing line(s)."));
+    assert!(report.contains("Conflict in line:conflict"));
+    assert!(report.contains("Unknown line line:orphan"));
+    assert!(report.contains("Missing line line:missing"));
+}
+
+#[test]
+fn test_merge_translation_rejects_csv_without_lock_column() {
+    let result = merge_translation(
+        &base_compilation(),
+        existing(),
+        "id,text\nline:clean,Hallo\n",
+        ConflictPolicy::default(),
+    );
+    assert_eq!(
+        Err(TranslationParseError("Missing column \"lock\"".to_owned())),
+        result
+    );
+}
+
+#[test]
+fn test_merged_translation_round_trips_through_strings_csv() {
+    let base = base_compilation();
+    let mut translation = existing();
+    translation.remove(&"line:missing".into());
+    translation.insert("line:orphan".into(), translated("Verwaist", "Orphan"));
+
+    let csv = export_translation_csv(&base, "de-CH", &translation);
+    assert!(csv.starts_with("language,id,text,file,node,line_number,lock,comment\n"));
+    assert!(csv.contains("de-CH,line:clean,Hallo.,"));
+
+    let read = read_translation_csv(&csv).unwrap();
+    assert_eq!(
+        HashMap::from([
+            ("line:clean".into(), translated("Hallo.", "Hello.")),
+            (
+                "line:conflict".into(),
+                translated("Wie geht's?", "How are you?")
+            ),
+            ("line:missing".into(), translated("Goodbye.", "Goodbye.")),
+        ]),
+        read
+    );
+}
+
+#[test]
+fn test_translation_with_missing_interpolation_is_reported() {
+    let base = Compiler::from_test_source(
+        "<<declare $name = \"Ada\">>\n\
+         <<declare $gold = 3>>\n\
+         {$name} has {$gold} gold. #line:gold\n\
+         Braces look like \\{this\\}, {$name}. #line:braces\n\
+         Hello there. #line:clean",
+    )
+    .compile()
+    .unwrap();
+    let rows = [
+        ("line:gold", "{0} hat Gold."),
+        ("line:braces", "Klammern sehen so aus: \\{so\\}, {0}."),
+        ("line:clean", "Hallo zusammen."),
+    ]
+    .map(|(id, text)| format!("de-CH,{id},\"{text}\",test.yarn,Start,1,,,\r\n"));
+    let translation = format!(
+        "language,id,text,file,node,line_number,lock,comment,translator\r\n{}",
+        rows.concat()
+    );
+
+    let diagnostics = check_translation_interpolations(&base, &translation).unwrap();
+    assert_eq!(1, diagnostics.len(), "{diagnostics:?}");
+    let diagnostic = &diagnostics[0];
+    assert_eq!(
+        Some(DiagnosticCode::InterpolationCountMismatch),
+        diagnostic.code
+    );
+    assert_eq!(DiagnosticSeverity::Warning, diagnostic.severity);
+    assert_eq!("line:gold", diagnostic.arguments["line_id"]);
+    assert_eq!("2", diagnostic.arguments["expected"]);
+    assert_eq!("1", diagnostic.arguments["actual"]);
+    assert_eq!(Some("<input>"), diagnostic.file_name.as_deref());
+    assert_eq!(
+        Some(4),
+        diagnostic.range.as_ref().map(|range| range.start.line)
+    );
+}
+
+fn base_compilation() -> Compilation {
+    Compiler::from_test_source(
+        "Hello there. #line:clean\n\
+         How are you doing today? #line:conflict\n\
+         Goodbye. #line:missing",
+    )
+    .compile()
+    .unwrap()
+}
+
+fn existing() -> HashMap<LineId, TranslatedLine> {
+    HashMap::from([
+        ("line:clean".into(), translated("Hallo.", "Hello.")),
+        (
+            "line:conflict".into(),
+            translated("Wie geht's?", "How are you?"),
+        ),
+        ("line:missing".into(), translated("Tschüss.", "Goodbye.")),
+    ])
+}
+
+/// As written by `bevy_yarnspinner`
+fn incoming_csv() -> String {
+    let rows = [
+        ("line:clean", "Hallo zusammen.", "Hello there."),
+        ("line:conflict", "Wie geht es dir?", "How are you?"),
+        ("line:orphan", "Verwaist", "Orphan"),
+    ]
+    .map(|(id, text, base_text)| {
+        let lock = compute_lock(base_text);
+        format!("de-CH,{id},{text},test.yarn,Start,1,{lock},,\r\n")
+    });
+    format!(
+        "\u{feff}language,id,text,file,node,line_number,lock,comment,translator\r\n{}",
+        rows.concat()
+    )
+}
+
+fn translated(text: &str, base_text: &str) -> TranslatedLine {
+    TranslatedLine {
+        text: text.to_owned(),
+        lock: compute_lock(base_text),
+    }
+}
@@ -175,6 +175,44 @@ fn test_expressions_disallow_mismatched_types() {
         .any(|d| d.message == "$int (Number) cannot be assigned a String"));
 }
 
+#[test]
+fn test_type_mismatch_range_covers_offending_term() {
+    let cases = [
+        // The string doesn't fit the number it's added to
+        (
+            "<<declare $gold = 5>>\n<<if $gold + \"coins\" > 3>>\n<<endif>>",
+            "All terms of + must be the same",
+            13..20,
+        ),
+        // Only booleans can be negated
+        (
+            "<<declare $gold = 5>>\n<<if !$gold>>\n<<endif>>",
+            "Number has no implementation defined for !",
+            6..11,
+        ),
+    ];
+    for (source, expected_message, characters) in cases {
+        let errors = Compiler::from_test_source(source).compile().unwrap_err().0;
+        let error = errors
+            .iter()
+            .find(|e| e.message.contains(expected_message))
+            .unwrap_or_else(|| panic!("{source}: {errors:?}"));
+        assert_eq!(
+            Some(
+                Position {
+                    line: 3,
+                    character: characters.start,
+                }..Position {
+                    line: 3,
+                    character: characters.end,
+                }
+            ),
+            error.range,
+            "{source}"
+        );
+    }
+}
+
 #[test]
 fn test_expressions_allows_using_undeclared_variable() {
     for source in [
@@ -595,29 +633,129 @@ fn test_type_conversion() {
 }
 
 #[test]
-#[should_panic = "Failed to convert a Yarn value to a number: ParseFloatError(ParseFloatError { kind: Invalid })"]
 fn test_type_conversion_failure_to_number() {
-    let source = "{number(\"hello\")}";
-    let test_base =
-        TestBase::new().with_test_plan(TestPlan::new().expect_line("test failure if seen"));
-    let result = Compiler::from_test_source(source)
-        .extend_library(test_base.dialogue.library().clone())
-        .compile()
-        .unwrap();
-    test_base.with_compilation(result).run_standard_testcase();
+    let error = run_until_error("Before\n{number(\"hello\")}");
+    assert!(matches!(
+        &error,
+        DialogueError::InvalidCast {
+            function,
+            value,
+            target,
+            source_file: Some(_),
+            line: Some(4),
+            ..
+        } if function == "number" && **target == Type::Number && *value == YarnValue::from("hello")
+    ));
 }
 
 #[test]
-#[should_panic = "Failed to convert a Yarn value to a bool: ParseBoolError(ParseBoolError"]
 fn test_type_conversion_failure_to_bool() {
-    let source = "{bool(\"hello\")}";
-    let test_base =
-        TestBase::new().with_test_plan(TestPlan::new().expect_line("test failure if seen"));
-    let result = Compiler::from_test_source(source)
-        .extend_library(test_base.dialogue.library().clone())
+    let error = run_until_error("Before\n{bool(\"yes\")}");
+    assert!(matches!(
+        &error,
+        DialogueError::InvalidCast {
+            function,
+            value,
+            target,
+            line: Some(4),
+            ..
+        } if function == "bool" && **target == Type::Boolean && *value == YarnValue::from("yes")
+    ));
+}
+
+#[test]
+fn test_type_conversion_of_variables() {
+    let source = "
+            <<set $text to string(3.5)>>
+            <<set $number to number(\"3.5\")>>
+            <<set $bool to bool(\"false\")>>
+            {$text + \"!\"} {$number * 2} {not $bool}
+            ";
+    let test_base = TestBase::new().with_test_plan(TestPlan::new().expect_line("3.5! 7 true"));
+    let result = Compiler::from_test_source(source).compile().unwrap();
+
+    let types: Vec<_> = result
+        .declarations
+        .iter()
+        .filter(|declaration| declaration.name.starts_with('$'))
+        .map(|declaration| (declaration.name.as_str(), declaration.r#type.clone()))
+        .collect();
+    assert!(types.contains(&("$text", Type::String)));
+    assert!(types.contains(&("$number", Type::Number)));
+    assert!(types.contains(&("$bool", Type::Boolean)));
+    assert!(result.warnings.is_empty());
+    test_base.with_compilation(result).run_standard_testcase();
+}
+
+#[test]
+fn test_type_conversion_results_are_type_checked() {
+    for (source, code) in [
+        (
+            "<<set $number to number(\"3.5\")>>\n<<set $number to \"text\">>",
+            DiagnosticCode::TypeMismatch,
+        ),
+        ("{bool(\"true\") + 1}", DiagnosticCode::TypeMismatch),
+        ("{string(1, 2)}", DiagnosticCode::WrongParameterCount),
+    ] {
+        let diagnostics = Compiler::from_test_source(source).compile().unwrap_err().0;
+        assert!(
+            diagnostics.iter().any(|d| d.code == Some(code)),
+            "Expected {code} for {source}, got {diagnostics:?}"
+        );
+    }
+}
+
+fn run_until_error(source: &str) -> DialogueError {
+    let result = Compiler::from_test_source(source).compile().unwrap();
+    let mut dialogue = TestBase::new().with_compilation(result).dialogue;
+    dialogue.set_node("Start").unwrap();
+    loop {
+        if let Err(error) = dialogue.continue_() {
+            return error;
+        }
+    }
+}
+
+#[test]
+fn test_number_functions() {
+    let source = "
+            round: {round(1.5)} {round(-1.5)} {round_places(3.14159, 2)}
+            floor and ceil: {floor(-1.5)} {ceil(-1.5)}
+            inc: {inc(1.5)} {inc(2)}
+            dec: {dec(1.5)} {dec(2)}
+            decimal: {decimal(1.25)} {decimal(-1.25)}
+            int: {int(1.7)} {int(-1.7)}
+            ";
+    let test_base = TestBase::new().with_test_plan(
+        TestPlan::new()
+            .expect_line("round: 2 -2 3.14")
+            .expect_line("floor and ceil: -2 -1")
+            .expect_line("inc: 2 3")
+            .expect_line("dec: 1 1")
+            .expect_line("decimal: 0.25 -0.25")
+            .expect_line("int: 1 -1"),
+    );
+    let result = Compiler::from_test_source(source).compile().unwrap();
+
+    test_base.with_compilation(result).run_standard_testcase();
+}
+
+#[test]
+fn test_dialogue_functions_are_type_checked_without_their_library() {
+    let result = Compiler::from_test_source("<<set $number = random_range(\"a\", 3)>>")
+        .compile()
+        .unwrap_err();
+    assert!(result.0.iter().any(|d| d
+        .message
+        .contains("random_range parameter 1 expects a Number, not a String")));
+
+    let result = Compiler::from_test_source("<<set $roll = dice(6)>>")
         .compile()
         .unwrap();
-    test_base.with_compilation(result).run_standard_testcase();
+    assert!(result
+        .declarations
+        .iter()
+        .any(|d| d.name == "$roll" && d.r#type == Type::Number));
 }
 
 #[test]
@@ -683,6 +821,106 @@ fn test_implicit_variable_declarations() {
     }
 }
 
+#[test]
+fn test_generated_declare_statements_replace_implicit_declarations() {
+    let source = r#"<<set $gold to 5>>
+<<set $price to 2.5>>
+<<set $name to "Ada \"the \\ Great\"">>
+<<set $met to true>>
+{round(1)}"#;
+    let result = Compiler::from_test_source(source).compile().unwrap();
+    let implicit_declarations = result.implicit_variable_declarations();
+    assert_eq!(4, implicit_declarations.len());
+    assert!(!implicit_declarations.iter().any(|d| d.name == "round"));
+
+    let mut declare_statements: Vec<_> = implicit_declarations
+        .iter()
+        .map(|d| d.to_declare_statement().unwrap())
+        .collect();
+    declare_statements.sort();
+    assert_eq!(
+        vec![
+            "<<declare $gold = 0 as Number>>",
+            "<<declare $met = false as Bool>>",
+            "<<declare $name = \"\" as String>>",
+            "<<declare $price = 0 as Number>>",
+        ],
+        declare_statements
+    );
+
+    let declared_source = format!("{}\n{source}", declare_statements.join("\n"));
+    let declared_result = Compiler::from_test_source(&declared_source)
+        .compile()
+        .unwrap();
+    assert!(declared_result.implicit_variable_declarations().is_empty());
+    assert_eq!(result.warnings, declared_result.warnings);
+    for declaration in implicit_declarations {
+        let explicit_declaration = declared_result
+            .declarations
+            .iter()
+            .find(|d| d.name == declaration.name)
+            .unwrap();
+        assert_eq!(declaration.r#type, explicit_declaration.r#type);
+        assert_eq!(
+            declaration.default_value,
+            explicit_declaration.default_value
+        );
+    }
+}
+
+#[test]
+fn test_declare_statements_round_trip_literals() {
+    let enum_declaration = "<<enum Food>>\n<<case Apple>>\n<<case Banana>>\n<<endenum>>";
+    let declarations = [
+        Declaration::new("$count", Type::Integer).with_default_value(3),
+        Declaration::new("$ratio", Type::Number).with_default_value(0.25),
+        Declaration::new("$quote", Type::String).with_default_value("say hi o/"),
+        Declaration::new("$ready", Type::Boolean).with_default_value(true),
+    ];
+    let declare_statements: Vec<_> = declarations
+        .iter()
+        .map(|d| d.to_declare_statement().unwrap())
+        .collect();
+    assert_eq!("<<declare $count = 3 as Integer>>", declare_statements[0]);
+    assert_eq!("<<declare $ratio = 0.25 as Number>>", declare_statements[1]);
+    assert_eq!(
+        r#"<<declare $quote = "say hi o/" as String>>"#,
+        declare_statements[2]
+    );
+    assert_eq!("<<declare $ready = true as Bool>>", declare_statements[3]);
+
+    let source = format!(
+        "{enum_declaration}\n<<declare $snack = Food.Banana>>\n{}",
+        declare_statements.join("\n")
+    );
+    let result = Compiler::from_test_source(&source).compile().unwrap();
+    for declaration in &declarations {
+        let compiled = result
+            .declarations
+            .iter()
+            .find(|d| d.name == declaration.name)
+            .unwrap();
+        assert_eq!(declaration.r#type, compiled.r#type);
+        assert_eq!(declaration.default_value, compiled.default_value);
+    }
+    let snack = result
+        .declarations
+        .iter()
+        .find(|d| d.name == "$snack")
+        .unwrap();
+    assert_eq!(
+        Some("<<declare $snack = Food.Banana as Food>>".to_owned()),
+        snack.to_declare_statement()
+    );
+    let case = Declaration::new("Food.Apple", snack.r#type.clone()).with_default_value(0);
+    assert_eq!(None, case.to_declare_statement());
+    // Yarn has no negative number literals and keeps escape sequences verbatim
+    let debt = Declaration::new("$debt", Type::Number).with_default_value(-1);
+    assert_eq!(None, debt.to_declare_statement());
+    let quote = Declaration::new("$quote", Type::String).with_default_value("say \"hi\"");
+    assert_eq!(None, quote.to_declare_statement());
+}
+
 #[test]
 fn test_nested_implicit_function_declarations() {
     let source = "
@@ -774,3 +1012,382 @@ fn test_if_statement_expressions_must_be_boolean() {
         .message
         .contains("Terms of 'if statement' must be Bool, not String")));
 }
+
+#[test]
+fn test_interpolated_expression_types_are_recorded() {
+    let result = Compiler::from_test_source(
+        "<<declare $gold = 3>>\nYou have {$gold} gold and {\"shiny\"} gems #line:gold",
+    )
+    .compile()
+    .unwrap();
+
+    let string_info = &result.string_table[&"line:gold".into()];
+    assert_eq!(
+        vec![Type::Number, Type::String],
+        string_info.expression_types
+    );
+    assert!(result.warnings.is_empty());
+}
+
+#[test]
+fn test_interpolating_undeclared_function_warns() {
+    let result = Compiler::from_test_source("You have {gold_count()} gold #line:gold")
+        .compile()
+        .unwrap();
+
+    let warning = result
+        .warnings
+        .iter()
+        .find(|d| d.code == Some(DiagnosticCode::UndeclaredFunction))
+        .unwrap();
+    assert!(warning.message.contains("gold_count"));
+    assert_eq!(DiagnosticSeverity::Warning, warning.severity);
+}
+
+#[test]
+fn test_custom_operators() {
+    let source = "
+    <<declare $name = \"Rumpelstiltskin\">>
+    {$name - \"stiltskin\"}
+    ";
+    let test_base = TestBase::new()
+        .with_test_plan(TestPlan::new().expect_line("Rumpel"))
+        .extend_library(|library| {
+            library.add_operator(Type::String, Operator::Subtract, |a: String, b: String| {
+                a.replace(&b, "")
+            });
+        });
+
+    let result = Compiler::from_test_source(source).compile();
+    let diagnostics = result.unwrap_err().0;
+    assert!(diagnostics
+        .iter()
+        .any(|d| d.message.contains("has no implementation defined for -")));
+
+    let result = Compiler::from_test_source(source)
+        .extend_library(test_base.dialogue.library().clone())
+        .compile()
+        .unwrap();
+    assert!(!result.declarations.iter().any(|d| d.name.contains('.')));
+
+    test_base.with_compilation(result).run_standard_testcase();
+}
+
+#[test]
+fn test_integer_declarations() {
+    let source = "
+    <<declare $gold = 5 as integer>>
+    <<declare $price = 2.5>>
+    ";
+    let result = Compiler::from_test_source(source).compile().unwrap();
+    let gold = result
+        .declarations
+        .iter()
+        .find(|d| d.name == "$gold")
+        .unwrap();
+    assert_eq!(Type::Integer, gold.r#type);
+    assert_eq!(Some(YarnValue::Number(5.0)), gold.default_value);
+
+    let errors = Compiler::from_test_source("<<declare $gold = 5.5 as integer>>")
+        .compile()
+        .unwrap_err()
+        .0;
+    assert!(errors.iter().any(|e| e
+        .message
+        .contains("Type integer does not match value 5.5 (Number)")));
+}
+
+#[test]
+fn test_constant_expression_declarations() {
+    let source = "
+    <<declare $price = 5 * 2>>
+    <<declare $debt = -5>>
+    <<declare $gold = (3 + 1) * 2 as integer>>
+    <<declare $greeting = \"hello \" + \"there\">>
+    {$price} {$debt} {$gold} {$greeting}
+    ";
+    let result = Compiler::from_test_source(source).compile().unwrap();
+    let declaration = |name| result.declarations.iter().find(|d| d.name == name).unwrap();
+    assert_eq!(Type::Number, declaration("$price").r#type);
+    assert_eq!(
+        Some(YarnValue::Number(10.0)),
+        declaration("$price").default_value
+    );
+    assert_eq!(
+        Some(YarnValue::Number(-5.0)),
+        declaration("$debt").default_value
+    );
+    assert_eq!(Type::Integer, declaration("$gold").r#type);
+    assert!(!declaration("$gold").is_smart);
+
+    TestBase::new()
+        .with_test_plan(TestPlan::new().expect_line("10 -5 8 hello there"))
+        .with_compilation(result)
+        .run_standard_testcase();
+}
+
+#[test]
+fn test_constant_expression_declarations_are_checked() {
+    for (source, message) in [
+        (
+            "<<declare $gold = 7 / 2 as integer>>",
+            "Type integer does not match value 7/2 (Number)",
+        ),
+        (
+            "<<declare $gold = 2.5 * 2 as integer>>",
+            "Type integer does not match value 2.5*2 (Number)",
+        ),
+        (
+            "<<declare $price = 5 * round(1.5)>>",
+            "Default values must be constant expressions, but `round(1.5)` is a function",
+        ),
+        (
+            "<<declare $price = 5 * 2 as string>>",
+            "Type string does not match value 5*2 (Number)",
+        ),
+    ] {
+        let errors = Compiler::from_test_source(source).compile().unwrap_err().0;
+        assert!(
+            errors.iter().any(|e| e.message.contains(message)),
+            "{source}: {errors:?}"
+        );
+    }
+}
+
+#[test]
+fn test_failed_constant_expression_still_declares_the_variable() {
+    for (source, message) in [
+        (
+            "<<declare $x = 1 / 0>>\n{$x}",
+            "Division by zero in constant expression",
+        ),
+        (
+            "<<declare $x = 1 / 0 as number>>\n{$x}",
+            "Division by zero in constant expression",
+        ),
+        (
+            "<<declare $x = 2 * round(1.5) as number>>\n{$x}",
+            "Default values must be constant expressions, but `round(1.5)` is a function",
+        ),
+    ] {
+        let errors = Compiler::from_test_source(source).compile().unwrap_err().0;
+        assert_eq!(1, errors.len(), "{source}: {errors:?}");
+        assert_eq!(message, errors[0].message);
+    }
+}
+
+#[test]
+fn test_integer_division() {
+    let source = "
+    <<declare $gold = 7 as integer>>
+    <<set $gold = $gold / 2>>
+    {$gold}
+    <<set $gold -= 5>>
+    {$gold % 2}
+    {$gold / 2.0}
+    ";
+    let result = Compiler::from_test_source(source).compile().unwrap();
+    TestBase::new()
+        .with_test_plan(
+            TestPlan::new()
+                .expect_line("3")
+                .expect_line("0")
+                .expect_line("-1"),
+        )
+        .with_compilation(result)
+        .run_standard_testcase();
+}
+
+#[test]
+fn test_integer_division_by_zero_fails() {
+    for operator in ["/", "%"] {
+        let error = run_until_error(&format!(
+            "<<declare $a = 5 as integer>>\n<<declare $b = 0 as integer>>\n{{$a {operator} $b}}"
+        ));
+        assert!(
+            matches!(
+                &error,
+                DialogueError::DivisionByZero {
+                    function,
+                    line: Some(5),
+                    ..
+                } if function.starts_with("Integer.")
+            ),
+            "{error:?}"
+        );
+    }
+}
+
+#[test]
+fn test_mixed_integer_and_number_expressions() {
+    let source = "
+    <<declare $gold = 10 as integer>>
+    <<declare $ratio = 0.5>>
+    {$gold * $ratio}
+    {$gold * 1.5}
+    ";
+    let result = Compiler::from_test_source(source).compile().unwrap();
+    TestBase::new()
+        .with_test_plan(TestPlan::new().expect_line("5").expect_line("15"))
+        .with_compilation(result)
+        .run_standard_testcase();
+
+    for source in [
+        "<<declare $gold = 10 as integer>>\n<<set $gold = $gold * 1.5>>",
+        "<<declare $gold = 10 as integer>>\n<<set $gold *= 1.5>>",
+    ] {
+        let errors = Compiler::from_test_source(source).compile().unwrap_err().0;
+        assert!(
+            errors.iter().any(|e| e
+                .message
+                .contains("$gold (Integer) cannot be assigned a Number")),
+            "{errors:?}"
+        );
+    }
+}
+
+#[test]
+fn test_enum_cases_in_if_chain() {
+    let source = "
+    <<enum Food>>
+        <<case Apple>>
+        <<case Banana>>
+        <<case Cherry = 10>>
+    <<endenum>>
+    <<declare $snack = Food.Banana>>
+    <<if $snack == Food.Apple>>
+        Apple
+    <<elseif $snack == Food.Banana>>
+        Banana
+    <<else>>
+        Something else
+    <<endif>>
+    <<set $snack to Food.Cherry>>
+    <<if $snack != Food.Banana>>
+        Not a banana but {$snack}
+    <<endif>>
+    ";
+    let result = Compiler::from_test_source(source).compile().unwrap();
+    let snack = result
+        .declarations
+        .iter()
+        .find(|d| d.name == "$snack")
+        .unwrap();
+    let Type::Enum(food) = &snack.r#type else {
+        panic!("Expected $snack to be an enum, but it is {}", snack.r#type);
+    };
+    assert_eq!("Food", food.name);
+    assert_eq!(3, food.cases.len());
+    assert_eq!(Some(YarnValue::Number(1.0)), snack.default_value);
+
+    TestBase::new()
+        .with_test_plan(
+            TestPlan::new()
+                .expect_line("Banana")
+                .expect_line("Not a banana but 10"),
+        )
+        .with_compilation(result)
+        .run_standard_testcase();
+}
+
+#[test]
+fn test_raw_values_are_cast_to_enum_cases() {
+    let source = "
+    <<enum Color>>
+        <<case Red = \"red\">>
+        <<case Green = \"green\">>
+    <<endenum>>
+    <<declare $color = \"red\" as Color>>
+    <<if $color == \"red\">>
+        Red
+    <<endif>>
+    <<set $color to \"green\">>
+    <<if $color == Color.Green>>
+        Green
+    <<endif>>
+    ";
+    let result = Compiler::from_test_source(source).compile().unwrap();
+    TestBase::new()
+        .with_test_plan(TestPlan::new().expect_line("Red").expect_line("Green"))
+        .with_compilation(result)
+        .run_standard_testcase();
+
+    let errors = Compiler::from_test_source(&format!("{source}\n<<set $color to \"blue\">>"))
+        .compile()
+        .unwrap_err()
+        .0;
+    assert!(
+        errors.iter().any(|e| e
+            .message
+            .contains("$color (Color) cannot be assigned a String")),
+        "{errors:?}"
+    );
+}
+
+#[test]
+fn test_comparing_cases_of_different_enums_fails() {
+    let source = "
+    <<enum Food>>
+        <<case Apple>>
+    <<endenum>>
+    <<enum Drink>>
+        <<case Tea>>
+    <<endenum>>
+    <<declare $snack = Food.Apple>>
+    <<if $snack == Drink.Tea>>
+        Huh?
+    <<endif>>
+    ";
+    let errors = Compiler::from_test_source(source).compile().unwrap_err().0;
+    assert!(
+        errors.iter().any(|e| e
+            .message
+            .contains("All terms of == must be the same, not Food, Drink")),
+        "{errors:?}"
+    );
+}
+
+#[test]
+fn test_invalid_enums_are_reported() {
+    let enum_declaration = "<<enum Food>>\n<<case Apple>>\n<<endenum>>\n";
+    let cases = [
+        ("<<case Apple>>", "Found <<case>> outside of an <<enum>>"),
+        (
+            "<<enum Food>>\n<<case Apple>>\n<<case Apple>>\n<<endenum>>",
+            "Enum Food already has a case named Apple",
+        ),
+        (
+            "<<enum Food>>\n<<case Apple = 1>>\n<<case Banana = \"1\">>\n<<endenum>>",
+            "has a different type than those of the other cases",
+        ),
+        (
+            "<<enum Food>>\n<<case Apple>>",
+            "Found <<enum>> without a matching <<endenum>>",
+        ),
+        (
+            "<<endenum>>",
+            "Found <<endenum>> without a matching <<enum>>",
+        ),
+        (
+            &format!(
+                "{enum_declaration}<<declare $snack = Food.Apple>>\n<<set $snack to Food.Durian>>"
+            ),
+            "Food.Durian is not a case of any enum",
+        ),
+        (
+            &format!("{enum_declaration}<<set Food.Apple to 1>>"),
+            "Variable names need to start with a $",
+        ),
+        (
+            &format!("{enum_declaration}{enum_declaration}"),
+            "Enum Food has already been declared",
+        ),
+    ];
+    for (source, expected_message) in cases {
+        let errors = Compiler::from_test_source(source).compile().unwrap_err().0;
+        assert!(
+            errors.iter().any(|e| e.message.contains(expected_message)),
+            "{source}: {errors:?}"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use test_base::prelude::*;
+use yarnspinner::compiler::*;
+
+mod test_base;
+
+#[test]
+fn test_content_after_jump_is_reported() {
+    let compilation = Compiler::from_test_nodes(
+        "title: Start\n---\nHello.\n<<jump End>>\nNobody hears this.\nOr this.\n===\n\
+         title: End\n---\nBye.\n===\n",
+    )
+    .compile()
+    .unwrap();
+
+    let warnings = unreachable_content_warnings(&compilation);
+    assert_eq!(1, warnings.len());
+    let warning = warnings[0];
+    assert_eq!(DiagnosticSeverity::Warning, warning.severity);
+    assert_eq!("<<jump End>>", warning.arguments["statement"]);
+    assert_eq!("4", warning.arguments["line"]);
+    // Points at the first unreachable statement
+    assert_eq!(4, warning.range.as_ref().unwrap().start.line);
+}
+
+#[test]
+fn test_content_after_stop_in_nested_block_is_reported() {
+    let compilation = Compiler::from_test_nodes(
+        "title: Start\n---\n-> Leave\n    <<stop>>\n    Nobody hears this.\n\
+         -> Stay\n    Welcome.\n===\n",
+    )
+    .compile()
+    .unwrap();
+
+    let warnings = unreachable_content_warnings(&compilation);
+    assert_eq!(1, warnings.len());
+    assert_eq!("<<stop>>", warnings[0].arguments["statement"]);
+    assert_eq!(4, warnings[0].range.as_ref().unwrap().start.line);
+}
+
+#[test]
+fn test_reachable_content_is_not_reported() {
+    for body in [
+        // Jump in one branch of an if
+        "<<if $gold > 10>>\n    <<jump End>>\n<<endif>>\nStill here.",
+        "<<if $gold > 10>>\n    <<jump End>>\n<<else>>\n    Poor.\n<<endif>>\nStill here.",
+        // Jump guarded by options
+        "-> Leave\n    <<jump End>>\n-> Stay\n    Welcome.\nStill here.",
+        // Jump in one branch of a once block
+        "<<once>>\n    <<jump End>>\n<<else>>\n    Welcome back.\n<<endonce>>\nStill here.",
+        "<<once>>\n<<stop>>\n<<endonce>>\nStill here.",
+        // Detours come back
+        "<<detour End>>\nStill here.",
+        // Nothing after the jump
+        "Hello.\n<<jump End>>",
+    ] {
+        let compilation = Compiler::from_test_nodes(&format!(
+            "title: Start\n---\n<<declare $gold = 0>>\n{body}\n===\ntitle: End\n---\nBye.\n===\n"
+        ))
+        .compile()
+        .unwrap();
+
+        assert!(
+            unreachable_content_warnings(&compilation).is_empty(),
+            "{body}"
+        );
+    }
+}
+
+#[test]
+fn test_node_groups_are_not_reported() {
+    let compilation = Compiler::from_test_nodes(
+        "title: Greeting\nwhen: always\n---\nHello.\n===\n\
+         title: Greeting\nwhen: always\n---\nHi.\n===\n",
+    )
+    .compile()
+    .unwrap();
+
+    assert!(unreachable_content_warnings(&compilation).is_empty());
+}
+
+fn unreachable_content_warnings(compilation: &Compilation) -> Vec<&Diagnostic> {
+    compilation
+        .warnings
+        .iter()
+        .filter(|diagnostic| diagnostic.code == Some(DiagnosticCode::UnreachableContent))
+        .collect()
+}
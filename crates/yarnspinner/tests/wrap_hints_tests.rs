@@ -0,0 +1,107 @@
+use test_base::prelude::*;
+use yarnspinner::compiler::*;
+use yarnspinner::core::*;
+
+mod test_base;
+
+#[test]
+fn test_double_width_characters_take_up_two_cells() {
+    let config = WrapConfig::with_width(10);
+
+    // Five ideographs fill a line exactly, the sixth starts a new one
+    let hints = wrap_hints("こんにちは世界", &config);
+    assert_eq!(2, hints.line_count);
+    assert_eq!(vec!["こんにちは".len()], hints.line_starts);
+    assert!(!hints.has_overlong_token);
+
+    // Without the double-width ranges, every character is a single cell
+    let hints = wrap_hints(
+        "こんにちは世界",
+        &config.clone().with_double_width_ranges([]),
+    );
+    assert_eq!(1, hints.line_count);
+
+    // Grapheme clusters count as a single cell
+    let hints = wrap_hints("e\u{301}e\u{301}e\u{301}e\u{301}e\u{301}", &config);
+    assert_eq!(1, hints.line_count);
+}
+
+#[test]
+fn test_placeholders_and_language_widths() {
+    let config = WrapConfig::with_width(12)
+        .with_placeholder_width(5)
+        .with_language_width("de-CH", 20);
+
+    let hints = wrap_hints("You have {0} gold.", &config);
+    assert_eq!(2, hints.line_count);
+    assert_eq!(vec!["You have ".len()], hints.line_starts);
+
+    let hints = wrap_hints("Du hast {0} Gold.", &config.for_language("de-CH"));
+    assert_eq!(1, hints.line_count);
+}
+
+#[test]
+fn test_overlong_token_is_broken_and_warned_about() {
+    let source = "title: Start\n---\n\
+                  Visit www.yarnspinner.dev/docs/ for more. #line:url\n\
+                  Short line #line:short\n\
+                  ===\n";
+    let compilation = Compiler::new()
+        .add_file(File {
+            file_name: "help.yarn".to_owned(),
+            source: source.to_owned(),
+        })
+        .with_wrap_config(WrapConfig::with_width(16))
+        .compile()
+        .unwrap();
+
+    let url = compilation.string_table[&"line:url".into()]
+        .wrap_hints
+        .clone()
+        .unwrap();
+    assert!(url.has_overlong_token);
+    assert_eq!(4, url.line_count);
+    let short = compilation.string_table[&"line:short".into()]
+        .wrap_hints
+        .clone()
+        .unwrap();
+    assert_eq!(
+        WrapHints {
+            line_count: 1,
+            line_starts: vec![],
+            has_overlong_token: false,
+        },
+        short
+    );
+
+    assert_eq!(1, compilation.warnings.len());
+    let warning = &compilation.warnings[0];
+    assert_eq!(Some(DiagnosticCode::LineOverflow), warning.code);
+    assert_eq!(DiagnosticSeverity::Warning, warning.severity);
+    assert_eq!(2, warning.range.as_ref().unwrap().start.line);
+}
+
+#[test]
+fn test_lines_exceeding_max_lines_are_warned_about() {
+    let source = "The quick brown fox jumps over the lazy dog.\nHi!\n";
+    let compilation = Compiler::from_test_source(source)
+        .with_wrap_config(WrapConfig::with_width(10).with_max_lines(3))
+        .compile()
+        .unwrap();
+
+    assert_eq!(1, compilation.warnings.len());
+    assert!(compilation.warnings[0]
+        .message
+        .contains("wraps into 5 lines, but the dialogue box only fits 3"));
+    assert!(compilation
+        .string_table
+        .values()
+        .all(|info| info.wrap_hints.is_some()));
+
+    let compilation = Compiler::from_test_source(source).compile().unwrap();
+    assert!(compilation.warnings.is_empty());
+    assert!(compilation
+        .string_table
+        .values()
+        .all(|info| info.wrap_hints.is_none()));
+}
@@ -0,0 +1,106 @@
+use test_base::prelude::*;
+use yarnspinner::compiler::*;
+use yarnspinner::core::*;
+use yarnspinner::runtime::*;
+
+mod test_base;
+
+#[test]
+fn test_operators_match_script_evaluation() {
+    let a = YarnValue::from(7);
+    let b = YarnValue::from(2);
+    let text = YarnValue::from("Gold: ");
+    let yes = YarnValue::from(true);
+    let no = YarnValue::from(false);
+    let cases: Vec<(&str, YarnValue)> = vec![
+        ("$a + $b", (a.clone() + b.clone()).unwrap()),
+        ("$a - $b", (a.clone() - b.clone()).unwrap()),
+        ("$a * $b", (a.clone() * b.clone()).unwrap()),
+        ("$a / $b", (a.clone() / b.clone()).unwrap()),
+        ("$a % $b", (a.clone() % b.clone()).unwrap()),
+        ("-$a", (-a.clone()).unwrap()),
+        ("$text + $text", (text.clone() + text.clone()).unwrap()),
+        ("$a > $b", (a > b).into()),
+        ("$a >= $a", (a >= a).into()),
+        ("$a < $b", (a < b).into()),
+        ("$b <= $a", (b <= a).into()),
+        ("$a == $b", (a == b).into()),
+        ("$a != $b", (a != b).into()),
+        ("$text == $text", (text == text).into()),
+        ("$yes and $no", (yes.clone() & no.clone()).unwrap()),
+        ("$yes or $no", (yes.clone() | no.clone()).unwrap()),
+        ("$yes xor $yes", (yes.clone() ^ yes.clone()).unwrap()),
+        ("not $yes", (!yes.clone()).unwrap()),
+    ];
+
+    let declarations = "<<declare $a = 7>>\n<<declare $b = 2>>\n<<declare $text = \"Gold: \">>\n\
+                        <<declare $yes = true>>\n<<declare $no = false>>\n";
+    for (expression, expected) in cases {
+        let source = format!("{declarations}<<set $result to {expression}>>\n");
+        let compilation = Compiler::from_test_source(&source).compile().unwrap();
+        let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+        dialogue.set_node("Start").unwrap();
+        while !dialogue
+            .continue_()
+            .unwrap()
+            .contains(&DialogueEvent::DialogueComplete)
+        {}
+
+        let actual = dialogue.variable_storage().get("$result").unwrap();
+        assert_eq!(expected, actual, "{expression}");
+    }
+}
+
+#[test]
+fn test_operators_convert_operands_like_scripts() {
+    assert_eq!(
+        YarnValue::from("Gold: 10"),
+        (YarnValue::from("Gold: ") + 10).unwrap()
+    );
+    assert_eq!(
+        YarnValue::from("1true"),
+        (YarnValue::from(1) + "true").unwrap()
+    );
+    assert_eq!(YarnValue::from("102"), (YarnValue::from("10") + 2).unwrap());
+    assert_eq!(YarnValue::from(8), (YarnValue::from("10") - 2).unwrap());
+    assert_eq!(YarnValue::from(2), (YarnValue::from(true) + true).unwrap());
+    assert_eq!(
+        YarnValue::from(true),
+        (YarnValue::from(1) & "true").unwrap()
+    );
+    assert!(YarnValue::from(f32::INFINITY) == (YarnValue::from(1) / 0).unwrap());
+}
+
+#[test]
+fn test_invalid_operands_fail_instead_of_panicking() {
+    assert!(matches!(
+        YarnValue::from("ten") - 1,
+        Err(YarnValueCastError::ParseFloatError(_))
+    ));
+    assert!(matches!(
+        -YarnValue::from("ten"),
+        Err(YarnValueCastError::ParseFloatError(_))
+    ));
+    assert!(matches!(
+        YarnValue::from("maybe") | true,
+        Err(YarnValueCastError::ParseBoolError(_))
+    ));
+}
+
+#[test]
+fn test_values_of_different_variants_are_not_ordered() {
+    assert!(YarnValue::from(2) < YarnValue::from(3));
+    assert!(YarnValue::from("apple") < YarnValue::from("banana"));
+    assert_eq!(None, YarnValue::from("2").partial_cmp(&YarnValue::from(3)));
+    assert!(!YarnValue::from("2").lt(&YarnValue::from(3)));
+    assert!(!YarnValue::from("2").ge(&YarnValue::from(3)));
+}
+
+#[test]
+fn test_values_convert_to_rust_types() {
+    let gold = YarnValue::from(10);
+    assert_eq!(10.0, f32::try_from(gold.clone()).unwrap());
+    assert_eq!(10, usize::try_from(&gold).unwrap());
+    assert_eq!("10", String::from(gold));
+    assert!(bool::try_from(YarnValue::from("true")).unwrap());
+}
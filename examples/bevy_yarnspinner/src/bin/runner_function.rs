@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+use bevy_yarnspinner::{events::*, prelude::*};
+
+// Runs headless and prints the dialogue to the terminal, so there is no dialogue view
+fn main() {
+    let mut library = YarnLibrary::new();
+    // Declaring the signature lets the compiler type check calls to the function
+    library.add_deferred_function(
+        "distance_to_player",
+        YarnSystemFunctionSignature::of(&distance_to_player),
+    );
+    App::new()
+        .add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            YarnSpinnerPlugin::with_yarn_source(YarnFileSource::file(
+                "dialogue/runner_function.yarn",
+            ))
+            .add_library(library),
+        ))
+        .add_systems(Startup, spawn_characters)
+        .add_systems(
+            Update,
+            (
+                spawn_dialogue_runners.run_if(resource_added::<YarnProject>),
+                (print_lines, exit_when_done).after(YarnSpinnerSystemSet),
+            ),
+        )
+        .run();
+}
+
+#[derive(Component)]
+struct Player;
+
+#[derive(Component)]
+struct Guard;
+
+fn spawn_characters(mut commands: Commands) {
+    commands.spawn((Player, Transform::from_xyz(-40.0, 0.0, 0.0)));
+    commands.spawn((Guard, Transform::from_xyz(0.0, 0.0, 0.0)));
+}
+
+fn spawn_dialogue_runners(mut commands: Commands, project: Res<YarnProject>) {
+    let mut dialogue_runner = project
+        .build_dialogue_runner()
+        // Only this runner can call the function
+        .with_function("distance_to_player", distance_to_player)
+        // The project also contains dialogue for other characters, which this runner does not need
+        .with_nodes(["Guard"])
+        .build();
+    dialogue_runner.start_node("Guard");
+    commands.spawn(dialogue_runner);
+}
+
+// Called with read-only access to the world whenever the dialogue needs its value
+fn distance_to_player(
+    _: In<()>,
+    players: Query<&Transform, With<Player>>,
+    guards: Query<&Transform, With<Guard>>,
+) -> f32 {
+    let player = players.single().translation;
+    let guard = guards.single().translation;
+    player.distance(guard).round()
+}
+
+fn print_lines(
+    mut present_line_events: EventReader<PresentLineEvent>,
+    mut dialogue_runners: Query<&mut DialogueRunner>,
+    mut players: Query<&mut Transform, With<Player>>,
+) {
+    for event in present_line_events.read() {
+        println!("{}", event.line.text);
+        // The player walks towards the guard while listening
+        players.single_mut().translation.x += 10.0;
+        if let Ok(mut dialogue_runner) = dialogue_runners.get_mut(event.source) {
+            dialogue_runner.continue_in_next_update();
+        }
+    }
+}
+
+fn exit_when_done(
+    mut dialogue_complete_events: EventReader<DialogueCompleteEvent>,
+    mut app_exit_events: EventWriter<AppExit>,
+) {
+    if dialogue_complete_events.read().next().is_some() {
+        app_exit_events.send(AppExit::Success);
+    }
+}
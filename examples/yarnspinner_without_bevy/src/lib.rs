@@ -88,7 +88,7 @@ impl TuiDialogueRunner {
         // compiled program, and tell it which node to start running from.
         //
         // To see how we actually drive this at runtime, scroll down to `fn update`!
-        dialogue.add_program(compilation.program.context("no program compiled")?);
+        dialogue.add_program(compilation.program.context("no program compiled")?)?;
         dialogue.set_node(start_node)?;
 
         Ok(TuiDialogueRunner {